@@ -0,0 +1,98 @@
+use crate::error::Result;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const ALIASES_FILE: &str = "aliases.toml";
+
+/// Former pea IDs and the IDs they were renamed to, kept in `.peas/aliases.toml`
+///
+/// The file is committed with the peas, so old IDs in commit messages, bodies
+/// and external links keep resolving for everyone after `peas mv`.
+#[derive(Debug, Default)]
+pub struct Aliases {
+    path: PathBuf,
+    map: BTreeMap<String, String>,
+}
+
+impl Aliases {
+    /// Load the aliases of a data directory; a missing file means none
+    pub fn load(data_path: &Path) -> Result<Self> {
+        let path = data_path.join(ALIASES_FILE);
+        let map = match std::fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, map })
+    }
+
+    /// The current ID of a former one
+    pub fn resolve(&self, id: &str) -> Option<&str> {
+        self.map.get(id).map(String::as_str)
+    }
+
+    /// Former IDs of a pea
+    pub fn of<'a>(&'a self, id: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.map
+            .iter()
+            .filter(move |(_, current)| *current == id)
+            .map(|(old, _)| old.as_str())
+    }
+
+    /// Record a rename; aliases of `old` move along, and `new` stops being one
+    pub fn add(&mut self, old: &str, new: &str) {
+        for current in self.map.values_mut() {
+            if current == old {
+                *current = new.to_string();
+            }
+        }
+        self.map.remove(new);
+        self.map.insert(old.to_string(), new.to_string());
+    }
+
+    /// File the aliases are kept in
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Content of the aliases file
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(&self.map)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, self.to_toml()?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_chained_renames() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut aliases = Aliases::load(temp_dir.path()).unwrap();
+        assert_eq!(aliases.resolve("peas-aaaaa"), None);
+
+        aliases.add("peas-aaaaa", "peas-bbbbb");
+        aliases.add("peas-bbbbb", "peas-ccccc");
+        aliases.save().unwrap();
+
+        let mut aliases = Aliases::load(temp_dir.path()).unwrap();
+        assert_eq!(aliases.resolve("peas-aaaaa"), Some("peas-ccccc"));
+        assert_eq!(aliases.resolve("peas-bbbbb"), Some("peas-ccccc"));
+        assert_eq!(
+            aliases.of("peas-ccccc").collect::<Vec<_>>(),
+            ["peas-aaaaa", "peas-bbbbb"]
+        );
+
+        // Renaming back makes the old ID a pea of its own again
+        aliases.add("peas-ccccc", "peas-aaaaa");
+        assert_eq!(aliases.resolve("peas-aaaaa"), None);
+        assert_eq!(aliases.resolve("peas-bbbbb"), Some("peas-aaaaa"));
+        assert_eq!(aliases.resolve("peas-ccccc"), Some("peas-aaaaa"));
+    }
+}
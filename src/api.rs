@@ -0,0 +1,299 @@
+//! High-level library API for embedding peas in other Rust tools.
+//!
+//! [`Client`] wraps project discovery, the ticket repository and search so
+//! callers do not need to wire up [`PeasConfig`] and [`PeaRepository`] by hand.
+//!
+//! ```
+//! use peas::api::Client;
+//! use peas::model::PeaType;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let client = Client::init(dir.path(), Default::default()).unwrap();
+//!
+//! let pea = client.new_pea("Fix login bug", PeaType::Bug).unwrap();
+//! client.create(&pea).unwrap();
+//!
+//! assert_eq!(client.search("title:login").unwrap().len(), 1);
+//! assert_eq!(client.stats().unwrap().total, 1);
+//! ```
+
+use crate::{
+    config::{DATA_DIR, PeasConfig},
+    error::{PeasError, Result},
+    model::{Pea, PeaStatus, PeaType},
+    search::RankedSearch,
+    storage::PeaRepository,
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const ALL_STATUSES: [PeaStatus; 5] = [
+    PeaStatus::Draft,
+    PeaStatus::Todo,
+    PeaStatus::InProgress,
+    PeaStatus::Completed,
+    PeaStatus::Scrapped,
+];
+
+const ALL_TYPES: [PeaType; 8] = [
+    PeaType::Milestone,
+    PeaType::Epic,
+    PeaType::Story,
+    PeaType::Feature,
+    PeaType::Bug,
+    PeaType::Chore,
+    PeaType::Research,
+    PeaType::Task,
+];
+
+/// Ticket counts for a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Stats {
+    /// Active (non-archived) tickets
+    pub total: usize,
+    pub archived: usize,
+    /// Active tickets per status, in workflow order
+    pub by_status: Vec<(PeaStatus, usize)>,
+    /// Active tickets per type, from milestone down to task
+    pub by_type: Vec<(PeaType, usize)>,
+    /// Active tickets per component, configured or in use, by name
+    pub by_component: Vec<(String, usize)>,
+    /// IDs of open milestones past their target date
+    pub overdue_milestones: Vec<String>,
+    /// Tickets reopened at least once per type, archived ones included
+    pub reopened_by_type: Vec<(PeaType, usize)>,
+}
+
+impl Stats {
+    /// Number of active tickets with the given status
+    pub fn status(&self, status: PeaStatus) -> usize {
+        self.by_status
+            .iter()
+            .find(|(s, _)| *s == status)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Number of active tickets of the given type
+    pub fn pea_type(&self, pea_type: PeaType) -> usize {
+        self.by_type
+            .iter()
+            .find(|(t, _)| *t == pea_type)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+/// Handle to a peas project
+pub struct Client {
+    config: PeasConfig,
+    root: PathBuf,
+    repo: PeaRepository,
+}
+
+impl Client {
+    /// Open the project containing `path`, searching parent directories for its config
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let (config, root) = PeasConfig::load(path.as_ref())?;
+        Ok(Self::with_config(config, root))
+    }
+
+    /// Initialize a new project in `root` with `config` and open it
+    pub fn init(root: impl AsRef<Path>, config: PeasConfig) -> Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let config_path = root.join(DATA_DIR).join("config.toml");
+        if config_path.exists() {
+            return Err(PeasError::AlreadyInitialized(
+                config_path.display().to_string(),
+            ));
+        }
+        config.validate()?;
+        std::fs::create_dir_all(config.data_path(&root))?;
+        config.save(&config_path)?;
+        Ok(Self::with_config(config, root))
+    }
+
+    /// Use an already loaded config for the project at `root`
+    pub fn with_config(config: PeasConfig, root: PathBuf) -> Self {
+        let repo = PeaRepository::new(&config, &root);
+        Self { config, root, repo }
+    }
+
+    pub fn config(&self) -> &PeasConfig {
+        &self.config
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The underlying repository, for operations not covered here
+    pub fn repository(&self) -> &PeaRepository {
+        &self.repo
+    }
+
+    /// Build a pea with a fresh ID and the project's default status
+    ///
+    /// Nothing is written until the pea is passed to [`Client::create`].
+    pub fn new_pea(&self, title: impl Into<String>, pea_type: PeaType) -> Result<Pea> {
+        let status = self.config.peas.default_status.parse()?;
+        Ok(Pea::new(self.repo.generate_id()?, title.into(), pea_type).with_status(status))
+    }
+
+    /// Write a new pea, returning the path of its file
+    ///
+    /// A `pre-create` hook may change the pea before it is written; use
+    /// [`Client::get`] to read back what was stored.
+    pub fn create(&self, pea: &Pea) -> Result<PathBuf> {
+        self.repo.create(pea)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Pea> {
+        self.repo.get(id)
+    }
+
+    /// Save changes to an existing pea and bump its `updated` timestamp
+    pub fn update(&self, pea: &mut Pea) -> Result<PathBuf> {
+        self.repo.update(pea)
+    }
+
+    pub fn delete(&self, id: &str) -> Result<()> {
+        self.repo.delete(id)
+    }
+
+    /// Move a pea into the archive, returning its new path
+    pub fn archive(&self, id: &str) -> Result<PathBuf> {
+        self.repo.archive(id)
+    }
+
+    /// All active (non-archived) peas
+    pub fn list(&self) -> Result<Vec<Pea>> {
+        self.repo.list()
+    }
+
+    pub fn list_archived(&self) -> Result<Vec<Pea>> {
+        self.repo.list_archived()
+    }
+
+    /// Active peas matching a query in `peas search` syntax (e.g. `tag:urgent`), or by a former ID,
+    /// best matches first
+    pub fn search(&self, query: &str) -> Result<Vec<Pea>> {
+        let query = RankedSearch::parse(query)
+            .map_err(|e| PeasError::validation("query", format!("Invalid search query: {}", e)))?;
+        Ok(query
+            .rank(self.repo.list()?, &self.repo.aliases()?)
+            .into_iter()
+            .map(|hit| hit.pea)
+            .collect())
+    }
+
+    pub fn stats(&self) -> Result<Stats> {
+        let peas = self.repo.list_summaries()?;
+        let today = chrono::Utc::now().date_naive();
+        let mut by_component: BTreeMap<String, usize> = self
+            .config
+            .components
+            .keys()
+            .map(|name| (name.clone(), 0))
+            .collect();
+        for component in peas.iter().filter_map(|p| p.component.as_ref()) {
+            *by_component.entry(component.clone()).or_default() += 1;
+        }
+        let archived = self.repo.list_archived()?;
+        let reopened: Vec<&Pea> = peas
+            .iter()
+            .chain(&archived)
+            .filter(|p| p.reopened_count > 0)
+            .collect();
+        Ok(Stats {
+            total: peas.len(),
+            archived: archived.len(),
+            by_status: ALL_STATUSES
+                .iter()
+                .map(|s| (*s, peas.iter().filter(|p| p.status == *s).count()))
+                .collect(),
+            by_type: ALL_TYPES
+                .iter()
+                .map(|t| (*t, peas.iter().filter(|p| p.pea_type == *t).count()))
+                .collect(),
+            by_component: by_component.into_iter().collect(),
+            overdue_milestones: peas
+                .iter()
+                .filter(|p| p.pea_type == PeaType::Milestone && p.is_overdue(today))
+                .map(|p| p.id.clone())
+                .collect(),
+            reopened_by_type: ALL_TYPES
+                .iter()
+                .map(|t| (*t, reopened.iter().filter(|p| p.pea_type == *t).count()))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_init_and_open_from_subdirectory() {
+        let temp_dir = TempDir::new().unwrap();
+        Client::init(temp_dir.path(), PeasConfig::default()).unwrap();
+        assert!(matches!(
+            Client::init(temp_dir.path(), PeasConfig::default()),
+            Err(PeasError::AlreadyInitialized(_))
+        ));
+
+        let nested = temp_dir.path().join("src/deep");
+        std::fs::create_dir_all(&nested).unwrap();
+        let client = Client::open(&nested).unwrap();
+        assert_eq!(
+            client.root().canonicalize().unwrap(),
+            temp_dir.path().canonicalize().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_crud_search_and_stats() {
+        let temp_dir = TempDir::new().unwrap();
+        let client = Client::init(temp_dir.path(), PeasConfig::default()).unwrap();
+
+        let bug = client.new_pea("Login fails", PeaType::Bug).unwrap();
+        client.create(&bug).unwrap();
+        let mut task = client
+            .new_pea("Write docs", PeaType::Task)
+            .unwrap()
+            .with_tags(vec!["docs".to_string()]);
+        client.create(&task).unwrap();
+
+        task.status = PeaStatus::InProgress;
+        client.update(&mut task).unwrap();
+        assert_eq!(client.get(&task.id).unwrap().status, PeaStatus::InProgress);
+
+        assert_eq!(client.search("tag:docs").unwrap()[0].id, task.id);
+        assert!(matches!(
+            client.search("regex:[bad"),
+            Err(PeasError::ValidationFailed { field: "query", .. })
+        ));
+
+        let overdue = client
+            .new_pea("Launch", PeaType::Milestone)
+            .unwrap()
+            .with_target_date(chrono::NaiveDate::from_ymd_opt(2020, 1, 1));
+        client.create(&overdue).unwrap();
+        assert_eq!(
+            client.stats().unwrap().overdue_milestones,
+            vec![overdue.id.clone()]
+        );
+        client.delete(&overdue.id).unwrap();
+
+        client.archive(&bug.id).unwrap();
+        let stats = client.stats().unwrap();
+        assert_eq!(stats.total, 1);
+        assert_eq!(stats.archived, 1);
+        assert_eq!(stats.status(PeaStatus::InProgress), 1);
+        assert_eq!(stats.pea_type(PeaType::Bug), 0);
+
+        client.delete(&task.id).unwrap();
+        assert!(client.list().unwrap().is_empty());
+    }
+}
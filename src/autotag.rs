@@ -0,0 +1,203 @@
+use crate::config::AutoTagSettings;
+use crate::error::{PeasError, Result};
+use crate::model::Pea;
+use regex::{Regex, RegexBuilder};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+/// A compiled `[autotag.<name>]` section
+pub struct AutoTag {
+    pub name: String,
+    title: Option<Regex>,
+    body: Option<Regex>,
+    tags: Vec<String>,
+    component: Option<String>,
+}
+
+impl AutoTag {
+    /// Whether the title or body pattern matches `pea`
+    pub fn matches(&self, pea: &Pea) -> bool {
+        self.title.as_ref().is_some_and(|r| r.is_match(&pea.title))
+            || self.body.as_ref().is_some_and(|r| r.is_match(&pea.body))
+    }
+}
+
+/// Auto-tagging rules from `[autotag.<name>]`
+///
+/// A rule whose title or body pattern matches a new pea adds its tags and, if
+/// the pea has no component yet, its component. Patterns are regular
+/// expressions matched anywhere and ignoring case. `peas retag --apply-rules`
+/// runs the same rules over existing peas.
+///
+/// ```
+/// use peas::autotag::AutoTags;
+/// use peas::config::AutoTagSettings;
+/// use peas::model::{Pea, PeaType};
+///
+/// let settings = [(
+///     "auth".to_string(),
+///     AutoTagSettings {
+///         title: Some(r"\blog ?in\b|password".to_string()),
+///         tags: vec!["auth".to_string()],
+///         ..Default::default()
+///     },
+/// )]
+/// .into();
+/// let autotags = AutoTags::new(&settings);
+///
+/// let pea = Pea::new("peas-abc12".into(), "Fix Login redirect".into(), PeaType::Bug);
+/// let tagged = autotags.apply(&pea).unwrap().unwrap();
+/// assert_eq!(tagged.tags, ["auth"]);
+/// ```
+pub struct AutoTags {
+    settings: BTreeMap<String, AutoTagSettings>,
+    /// Compiled on first use
+    compiled: OnceCell<Vec<AutoTag>>,
+}
+
+impl AutoTags {
+    pub fn new(settings: &BTreeMap<String, AutoTagSettings>) -> Self {
+        Self {
+            settings: settings.clone(),
+            compiled: OnceCell::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+
+    /// Compile every pattern, reporting the first invalid one
+    pub fn check(&self) -> Result<()> {
+        self.compiled().map(|_| ())
+    }
+
+    /// The compiled rules, in name order
+    pub fn compiled(&self) -> Result<&[AutoTag]> {
+        if let Some(rules) = self.compiled.get() {
+            return Ok(rules);
+        }
+        let compile = |name: &str, field: &str, pattern: &str| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| {
+                    PeasError::Config(format!(
+                        "Invalid {} pattern in autotag.{}: {}",
+                        field, name, e
+                    ))
+                })
+        };
+        let rules = self
+            .settings
+            .iter()
+            .map(|(name, settings)| {
+                Ok(AutoTag {
+                    name: name.clone(),
+                    title: settings
+                        .title
+                        .as_deref()
+                        .map(|title| compile(name, "title", title))
+                        .transpose()?,
+                    body: settings
+                        .body
+                        .as_deref()
+                        .map(|body| compile(name, "body", body))
+                        .transpose()?,
+                    tags: settings.tags.clone(),
+                    component: settings.component.clone(),
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(self.compiled.get_or_init(|| rules))
+    }
+
+    /// Add the tags and component of every matching rule, returning the pea if
+    /// that changed it
+    pub fn apply(&self, pea: &Pea) -> Result<Option<Pea>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let mut tagged = pea.clone();
+        for rule in self.compiled()?.iter().filter(|r| r.matches(pea)) {
+            tracing::debug!(rule = %rule.name, id = %pea.id, "Autotag rule matched");
+            for tag in &rule.tags {
+                if !tagged.tags.contains(tag) {
+                    tagged.tags.push(tag.clone());
+                }
+            }
+            if tagged.component.is_none() {
+                tagged.component = rule.component.clone();
+            }
+        }
+        Ok(Some(tagged).filter(|tagged| tagged != pea))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn autotags(entries: &[(&str, AutoTagSettings)]) -> AutoTags {
+        let settings = entries
+            .iter()
+            .map(|(name, settings)| (name.to_string(), settings.clone()))
+            .collect();
+        AutoTags::new(&settings)
+    }
+
+    #[test]
+    fn test_apply_adds_tags_and_first_component() {
+        let autotags = autotags(&[
+            (
+                "auth",
+                AutoTagSettings {
+                    title: Some("login".into()),
+                    tags: vec!["auth".into()],
+                    component: Some("auth".into()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "ui",
+                AutoTagSettings {
+                    body: Some(r"\bbutton\b".into()),
+                    tags: vec!["ui".into(), "auth".into()],
+                    component: Some("web".into()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        let pea = Pea::new("peas-abc12".into(), "LOGIN page".into(), PeaType::Task)
+            .with_body("Add a Button".into())
+            .with_tags(vec!["auth".into()]);
+        let tagged = autotags.apply(&pea).unwrap().unwrap();
+        assert_eq!(tagged.tags, ["auth", "ui"]);
+        assert_eq!(tagged.component.as_deref(), Some("auth"));
+
+        // A pea's own component wins, and nothing to add is no change
+        let pea = pea.with_component(Some("billing".into()));
+        let tagged = autotags.apply(&pea).unwrap().unwrap();
+        assert_eq!(tagged.component.as_deref(), Some("billing"));
+        assert_eq!(autotags.apply(&tagged).unwrap(), None);
+
+        let other = Pea::new("peas-abc13".into(), "Buttons".into(), PeaType::Task);
+        assert_eq!(autotags.apply(&other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_a_config_error() {
+        let autotags = autotags(&[(
+            "broken",
+            AutoTagSettings {
+                title: Some("(unclosed".into()),
+                tags: vec!["x".into()],
+                ..Default::default()
+            },
+        )]);
+        let err = autotags.check().unwrap_err();
+        assert!(matches!(err, PeasError::Config(ref m) if m.contains("autotag.broken")));
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::{PeasError, Result};
+use crate::model::{Claim, Pea};
+use chrono::{DateTime, Duration, Utc};
+
+/// How long a claim lasts when no duration is given
+pub const DEFAULT_TTL: &str = "2h";
+
+/// Parse a claim duration like `30m`, `2h` or `1d`
+pub fn parse_ttl(ttl: &str) -> Result<Duration> {
+    let ttl = ttl.trim();
+    let invalid = || {
+        PeasError::validation(
+            "ttl",
+            format!(
+                "Invalid duration '{}': expected minutes, hours or days like 30m, 2h, 1d",
+                ttl
+            ),
+        )
+    };
+    let split = ttl.len().saturating_sub(1);
+    let (count, unit) = (ttl.get(..split).ok_or_else(invalid)?, &ttl[split..]);
+    let count: i64 = count.parse().map_err(|_| invalid())?;
+    if count <= 0 {
+        return Err(invalid());
+    }
+    match unit {
+        "m" => Ok(Duration::minutes(count)),
+        "h" => Ok(Duration::hours(count)),
+        "d" => Ok(Duration::days(count)),
+        _ => Err(invalid()),
+    }
+}
+
+/// Claim `pea` for `actor` until `ttl` from `now`
+///
+/// Renews a claim the actor already holds. Fails while someone else holds an
+/// active claim, unless `force` takes it over.
+pub fn claim(
+    pea: &mut Pea,
+    actor: &str,
+    ttl: Duration,
+    force: bool,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    if !force && let Some(held) = pea.active_claim(now).filter(|c| c.by != actor) {
+        return Err(PeasError::Claimed {
+            id: pea.id.clone(),
+            by: held.by.clone(),
+            until: held.until,
+        });
+    }
+    pea.claim = Some(Claim {
+        by: actor.to_string(),
+        until: now + ttl,
+    });
+    Ok(())
+}
+
+/// Remove the claim on `pea`, returning the claim that was removed
+///
+/// Expired claims and the actor's own can always be released; an active
+/// claim held by someone else only with `force`.
+pub fn release(
+    pea: &mut Pea,
+    actor: Option<&str>,
+    force: bool,
+    now: DateTime<Utc>,
+) -> Result<Option<Claim>> {
+    if !force && pea.is_claimed_by_other(actor, now) {
+        let held = pea.claim.as_ref().expect("an active claim exists");
+        return Err(PeasError::Claimed {
+            id: pea.id.clone(),
+            by: held.by.clone(),
+            until: held.until,
+        });
+    }
+    Ok(pea.claim.take())
+}
+
+/// The actor making a claim, which a claim cannot do without
+pub fn require_actor(actor: Option<&str>) -> Result<&str> {
+    actor.ok_or_else(|| {
+        PeasError::validation(
+            "actor",
+            "Claims need to know who holds them: pass --actor or set PEAS_ACTOR",
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    #[test]
+    fn test_parse_ttl() {
+        assert_eq!(parse_ttl("30m").unwrap(), Duration::minutes(30));
+        assert_eq!(parse_ttl(" 2h ").unwrap(), Duration::hours(2));
+        assert_eq!(parse_ttl("1d").unwrap(), Duration::days(1));
+        for invalid in ["", "h", "0h", "-1h", "2w", "1.5h", "é"] {
+            assert!(parse_ttl(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_claim_and_release() {
+        let now = Utc::now();
+        let mut pea = Pea::new("peas-abc12".into(), "Login".into(), PeaType::Task);
+
+        claim(&mut pea, "agent-x", Duration::hours(1), false, now).unwrap();
+        assert!(pea.is_claimed_by_other(Some("agent-y"), now));
+        assert!(!pea.is_claimed_by_other(Some("agent-x"), now));
+
+        // Others cannot take or drop it, the holder can renew it
+        let err = claim(&mut pea, "agent-y", Duration::hours(1), false, now).unwrap_err();
+        assert!(matches!(err, PeasError::Claimed { ref by, .. } if by == "agent-x"));
+        assert!(release(&mut pea, Some("agent-y"), false, now).is_err());
+        assert!(release(&mut pea, None, false, now).is_err());
+        claim(&mut pea, "agent-x", Duration::hours(3), false, now).unwrap();
+        assert_eq!(pea.claim.as_ref().unwrap().until, now + Duration::hours(3));
+
+        // Once expired, anyone can claim it
+        let later = now + Duration::hours(4);
+        assert!(pea.active_claim(later).is_none());
+        claim(&mut pea, "agent-y", Duration::hours(1), false, later).unwrap();
+        claim(&mut pea, "agent-x", Duration::hours(1), true, later).unwrap();
+        assert_eq!(pea.claim.as_ref().unwrap().by, "agent-x");
+
+        let released = release(&mut pea, Some("agent-x"), false, later).unwrap();
+        assert_eq!(released.unwrap().by, "agent-x");
+        assert_eq!(pea.claim, None);
+        assert_eq!(release(&mut pea, None, false, later).unwrap(), None);
+    }
+}
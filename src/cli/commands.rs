@@ -1,4 +1,4 @@
-use crate::config::PeasSettings;
+use crate::{config::PeasSettings, logging::LogFormat, model::SortKey, terminal::ColorChoice};
 use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser)]
@@ -20,14 +20,30 @@ pub struct Cli {
     #[arg(long, global = true, hide = true)]
     pub peas_path: Option<String>,
 
-    /// Enable verbose logging (DEBUG level)
-    #[arg(short, long, global = true)]
-    pub verbose: bool,
+    /// Increase log verbosity (-v: DEBUG, -vv: TRACE with span timings)
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Format of log output on stderr
+    #[arg(long, value_enum, default_value_t = LogFormat::Text, global = true)]
+    pub log_format: LogFormat,
 
     /// Log to file (optional)
     #[arg(long, global = true)]
     pub log_file: Option<String>,
 
+    /// When to color output
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    pub color: ColorChoice,
+
+    /// Print long output directly instead of through $PAGER
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// Person or agent making changes, recorded on the peas they create and update
+    #[arg(long, global = true, env = "PEAS_ACTOR", value_name = "NAME")]
+    pub actor: Option<String>,
+
     /// Print version information
     #[arg(short = 'V', long = "version", global = true)]
     pub version: bool,
@@ -78,6 +94,10 @@ pub enum Commands {
         #[arg(long)]
         body_file: Option<String>,
 
+        /// Use the text on the clipboard as the body
+        #[arg(long, conflicts_with_all = ["body", "body_file"])]
+        body_from_clipboard: bool,
+
         /// Parent pea ID
         #[arg(long)]
         parent: Option<String>,
@@ -98,6 +118,22 @@ pub enum Commands {
         #[arg(long)]
         tag: Vec<String>,
 
+        /// Component from the config's [components] sections
+        #[arg(long)]
+        component: Option<String>,
+
+        /// Person responsible for the pea (default: the component's owner with auto_assign)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Target date for a milestone (YYYY-MM-DD)
+        #[arg(long)]
+        target_date: Option<chrono::NaiveDate>,
+
+        /// Keep the pea out of default lists and suggestions until this date (YYYY-MM-DD)
+        #[arg(long)]
+        defer_until: Option<chrono::NaiveDate>,
+
         /// Use a template (bug, feature, epic, milestone, chore, research)
         #[arg(long, value_enum)]
         template: Option<TemplateArg>,
@@ -116,11 +152,33 @@ pub enum Commands {
         /// Pea ID
         id: String,
 
+        /// Render the body's markdown with terminal styling
+        #[arg(long, conflicts_with = "json")]
+        render: bool,
+
+        /// Show times relative to now, e.g. "3 days ago"
+        #[arg(long)]
+        relative: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
 
+    /// Open a pea in the editor, the TUI or a web frontend (see [open] in the config)
+    Open {
+        /// Pea ID
+        id: String,
+
+        /// Where to open it (default: open.target from the config)
+        #[arg(long, value_enum)]
+        target: Option<OpenTargetArg>,
+
+        /// Print the file path or URL instead of opening it
+        #[arg(long)]
+        print: bool,
+    },
+
     /// List all peas
     #[command(visible_alias = "ls")]
     List {
@@ -144,10 +202,106 @@ pub enum Commands {
         #[arg(long)]
         tag: Option<String>,
 
+        /// Filter by component
+        #[arg(long)]
+        component: Option<String>,
+
+        /// Filter by assignee
+        #[arg(long, value_name = "NAME")]
+        assignee: Option<String>,
+
+        /// Filter by the person or agent that created the pea
+        #[arg(long, value_name = "NAME")]
+        created_by: Option<String>,
+
+        /// Filter by the person or agent behind the last change
+        #[arg(long, value_name = "NAME")]
+        updated_by: Option<String>,
+
+        /// Hide peas claimed by someone else
+        #[arg(long)]
+        unclaimed: bool,
+
+        /// Include peas deferred to a later date
+        #[arg(long)]
+        include_deferred: bool,
+
         /// Include archived peas
         #[arg(long)]
         archived: bool,
 
+        /// List peas as they were at a snapshot label, date (YYYY-MM-DD) or RFC 3339 time
+        #[arg(long, conflicts_with = "archived")]
+        as_of: Option<String>,
+
+        /// Columns of the table, comma-separated
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            default_value = "id,status,type,priority,title"
+        )]
+        columns: Vec<ListColumnArg>,
+
+        /// Sort keys, comma-separated; prefix a key with '-' to sort descending
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        sort: Vec<SortKey>,
+
+        /// Reverse the order
+        #[arg(long)]
+        reverse: bool,
+
+        /// Print peas in sections by this field, with counts
+        #[arg(long, value_enum)]
+        group_by: Option<ListGroupArg>,
+
+        /// Print only the number of matching peas
+        #[arg(long, conflicts_with_all = ["summary", "group_by"])]
+        count: bool,
+
+        /// Print only counts per status and type
+        #[arg(long, conflicts_with = "group_by")]
+        summary: bool,
+
+        /// Show the created and updated columns relative to now, e.g. "3 days ago"
+        #[arg(long)]
+        relative: bool,
+
+        /// Read the summary index instead of every ticket file
+        #[arg(long, conflicts_with_all = ["archived", "as_of"])]
+        fast: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Edit the status, priority and title of many peas at once in $EDITOR
+    EditList {
+        /// Filter by type
+        #[arg(short = 't', long, value_enum)]
+        r#type: Option<PeaTypeArg>,
+
+        /// Filter by status
+        #[arg(short, long, value_enum)]
+        status: Option<PeaStatusArg>,
+
+        /// Filter by priority
+        #[arg(short, long, value_enum)]
+        priority: Option<PeaPriorityArg>,
+
+        /// Filter by parent ID
+        #[arg(long)]
+        parent: Option<String>,
+
+        /// Filter by tag
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Show the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -182,6 +336,22 @@ pub enum Commands {
         #[arg(long)]
         parent: Option<String>,
 
+        /// New component (use empty string to clear)
+        #[arg(long)]
+        component: Option<String>,
+
+        /// New assignee (use empty string to clear)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// New target date for a milestone, YYYY-MM-DD (use empty string to clear)
+        #[arg(long)]
+        target_date: Option<String>,
+
+        /// Defer the pea until a date, YYYY-MM-DD (use empty string to clear)
+        #[arg(long)]
+        defer_until: Option<String>,
+
         /// Add a tag
         #[arg(long)]
         add_tag: Vec<String>,
@@ -214,6 +384,43 @@ pub enum Commands {
         #[arg(long = "remove-ref")]
         remove_ref: Vec<String>,
 
+        /// Reopen a completed or scrapped pea (to todo unless --status is given)
+        #[arg(long)]
+        reopen: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Set or clear frontmatter fields by name, for scripted edits
+    Patch {
+        /// Pea ID
+        id: String,
+
+        /// Set a field, e.g. priority=high, due=2026-10-01, tags=ui,backend
+        #[arg(long = "set", value_name = "FIELD=VALUE")]
+        set: Vec<String>,
+
+        /// Clear an optional or list field, e.g. parent
+        #[arg(long, value_name = "FIELD")]
+        unset: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Create and update peas from JSON records on stdin, all or nothing
+    Apply {
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -224,6 +431,10 @@ pub enum Commands {
     },
 
     /// Delete a pea permanently
+    ///
+    /// A pea with children is only deleted with --cascade, --reparent-to or
+    /// --orphan. Without one the command fails and names the children; earlier
+    /// versions left them pointing at the deleted pea.
     Delete {
         /// Pea ID
         id: String,
@@ -232,6 +443,18 @@ pub enum Commands {
         #[arg(short, long)]
         force: bool,
 
+        /// Delete all descendants along with the pea
+        #[arg(long, conflicts_with_all = ["reparent_to", "orphan"])]
+        cascade: bool,
+
+        /// Move the children of the pea under another pea
+        #[arg(long, value_name = "ID", conflicts_with = "orphan")]
+        reparent_to: Option<String>,
+
+        /// Clear the parent of the pea's children
+        #[arg(long)]
+        orphan: bool,
+
         /// Keep associated asset files instead of prompting to delete them
         #[arg(long)]
         keep_assets: bool,
@@ -239,6 +462,10 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Preview what would be deleted without making changes
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Search peas by text
@@ -246,6 +473,14 @@ pub enum Commands {
         /// Search query
         query: String,
 
+        /// Match words fuzzily, tolerating typos
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Rank peas by similarity in meaning to the query (needs the `semantic` feature)
+        #[arg(long, conflicts_with = "fuzzy")]
+        semantic: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
@@ -262,6 +497,14 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Preview the status change without making changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Carry the change up to a parent epic or feature, as `cascade` in the config does
+        #[arg(long)]
+        cascade: bool,
     },
 
     /// Mark a pea as completed
@@ -272,6 +515,143 @@ pub enum Commands {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Preview the status change without making changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Carry the change up to a parent epic or feature, as `cascade` in the config does
+        #[arg(long)]
+        cascade: bool,
+    },
+
+    /// Move a completed or scrapped pea back to todo
+    Reopen {
+        /// Pea ID
+        id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Preview the status change without making changes
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Add a timestamped note to a pea's work log, or list its notes
+    Note {
+        /// Pea ID
+        id: String,
+
+        /// Note text (use '-' to read from stdin; omit to list the notes)
+        text: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Claim a pea so other agents leave it alone (needs --actor or PEAS_ACTOR)
+    Claim {
+        /// Pea ID
+        id: String,
+
+        /// How long the claim lasts, e.g. 30m, 2h or 1d
+        #[arg(long, default_value = crate::claim::DEFAULT_TTL)]
+        ttl: String,
+
+        /// Take over an active claim held by someone else
+        #[arg(long)]
+        force: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Release a claim on a pea
+    Release {
+        /// Pea ID
+        id: String,
+
+        /// Release an active claim held by someone else
+        #[arg(long)]
+        force: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Set or show a pea's size estimate (story points or t-shirt size)
+    Size {
+        /// Pea ID
+        id: String,
+
+        /// Size on the configured scale, e.g. 5 or M (omit to show the current size)
+        value: Option<String>,
+
+        /// Remove the estimate
+        #[arg(long, conflicts_with = "value")]
+        clear: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Attach a labelled link (pull request, issue, doc) to a pea
+    Link {
+        /// Pea ID
+        id: String,
+
+        /// URL to link
+        url: String,
+
+        /// Short label shown instead of the URL
+        #[arg(short, long)]
+        label: Option<String>,
+
+        /// What the URL points at (inferred from the URL by default)
+        #[arg(short, long, value_enum)]
+        kind: Option<LinkKindArg>,
+
+        /// Remove the link with this URL instead
+        #[arg(long, conflicts_with_all = ["label", "kind"])]
+        remove: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check the merge state of a pea's pull request links on GitHub or GitLab
+    PrStatus {
+        /// Pea ID (default: every open pea with a pr link)
+        id: Option<String>,
+
+        /// Mark peas completed once a pull request merged and none is still open
+        #[arg(long)]
+        complete: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Set, show or clear the pea you are currently focused on
+    Focus {
+        /// Pea ID to focus on (omit to show the current focus)
+        id: Option<String>,
+
+        /// Clear the current focus
+        #[arg(long, conflicts_with = "id")]
+        clear: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     /// Archive peas (move to archive folder)
@@ -279,6 +659,11 @@ pub enum Commands {
     /// Archive a single pea by ID, or batch archive with filters:
     ///   peas archive <ID>
     ///   peas archive --status completed --older-than 30d
+    ///
+    /// A pea with children is only archived with --recursive, --reparent-to or
+    /// --orphan. Without one the command fails and names the children; earlier
+    /// versions left them pointing at the archived pea. A batch that would
+    /// leave children of matched peas behind fails the same way.
     Archive {
         /// Pea ID (for single archive; omit to use filters)
         id: Option<String>,
@@ -304,9 +689,17 @@ pub enum Commands {
         older_than: Option<String>,
 
         /// Recursively archive children (when archiving by ID)
-        #[arg(short = 'r', long)]
+        #[arg(short = 'r', long, visible_alias = "cascade")]
         recursive: bool,
 
+        /// Move the children of the pea under another pea (when archiving by ID)
+        #[arg(long, value_name = "ID", conflicts_with_all = ["recursive", "orphan"], requires = "id")]
+        reparent_to: Option<String>,
+
+        /// Clear the parent of the pea's children (when archiving by ID)
+        #[arg(long, conflicts_with = "recursive", requires = "id")]
+        orphan: bool,
+
         /// Keep associated asset files instead of prompting to delete them
         #[arg(long)]
         keep_assets: bool,
@@ -364,51 +757,272 @@ pub enum Commands {
         action: AssetAction,
     },
 
-    // =========================================================================
-    // Views & Reports
-    // =========================================================================
-    /// Open the interactive TUI
-    Tui,
+    /// List, rename and delete tags across all peas
+    Tag {
+        #[command(subcommand)]
+        action: TagAction,
+    },
+
+    /// Add tags and components to existing peas
+    Retag {
+        /// Apply the `[autotag.<name>]` rules to every active pea
+        #[arg(long, required = true)]
+        apply_rules: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
 
-    /// Suggest the next ticket to work on
-    Suggest {
         /// Output as JSON
         #[arg(long)]
         json: bool,
-        /// Number of suggestions to show (default: 1)
-        #[arg(long, short, default_value = "1")]
-        limit: usize,
     },
 
-    /// Generate a Markdown roadmap from milestones and epics
-    Roadmap,
-
     // =========================================================================
-    // Agent Integration
+    // Views & Reports
     // =========================================================================
-    /// Output instructions for AI coding agents
-    Prime,
+    /// Open the interactive TUI
+    Tui {
+        /// Start in the detail view of this pea
+        #[arg(long)]
+        select: Option<String>,
 
-    /// Output project context for LLMs
-    Context,
+        /// Start with this search filter, e.g. "status:in-progress tag:ui"
+        #[arg(long)]
+        filter: Option<String>,
 
-    // =========================================================================
-    // GraphQL API
-    // =========================================================================
-    /// Execute a GraphQL query
-    Query {
-        /// GraphQL query string
-        query: String,
+        /// Start in this view
+        #[arg(long, value_enum)]
+        view: Option<TuiViewArg>,
 
-        /// Variables as JSON
+        /// Show times relative to now, e.g. "3 days ago"
         #[arg(long)]
-        variables: Option<String>,
+        relative: bool,
     },
 
-    /// Execute a GraphQL mutation (automatically wraps in 'mutation { }')
-    Mutate {
-        /// Mutation body (without 'mutation' keyword)
-        mutation: String,
+    /// List peas that are new or changed since you last looked
+    Inbox {
+        /// Mark everything as read afterwards
+        #[arg(long)]
+        mark_read: bool,
+
+        /// List drafts waiting for triage instead (see `peas capture`)
+        #[arg(long, conflicts_with = "mark_read")]
+        drafts: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Quickly capture thoughts as drafts, one per line when read from stdin
+    Capture {
+        /// The thought, used as the title (reads stdin if omitted or '-')
+        #[arg(conflicts_with_all = ["from_file", "from_email"])]
+        text: Option<String>,
+
+        /// Capture a markdown document: its first heading becomes the title ('-' for stdin)
+        #[arg(long, value_name = "FILE", conflicts_with = "from_email")]
+        from_file: Option<String>,
+
+        /// Capture an email (.eml): its subject becomes the title ('-' for stdin)
+        #[arg(long, value_name = "FILE")]
+        from_email: Option<String>,
+
+        /// Type of the drafts
+        #[arg(short = 't', long, value_enum, default_value = "task")]
+        r#type: PeaTypeArg,
+
+        /// Tags to add (can be repeated)
+        #[arg(long)]
+        tag: Vec<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Weekly review: stale work, unprioritized todos and due dates, with quick actions
+    Review {
+        /// Review the past week (default)
+        #[arg(long)]
+        week: bool,
+
+        /// Review the past month instead
+        #[arg(long, conflicts_with = "week")]
+        month: bool,
+
+        /// Only list what would come up
+        #[arg(long)]
+        list: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Go through drafts and unfiled tickets one at a time, setting type, priority, parent and tags
+    Triage {
+        /// Visit the queue in random order
+        #[arg(long)]
+        random: bool,
+
+        /// Only list the peas waiting for triage
+        #[arg(long)]
+        list: bool,
+
+        /// List the peas waiting for triage as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Suggest the next ticket to work on
+    Suggest {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+        /// Number of suggestions to show (default: 1)
+        #[arg(
+            long,
+            short = 'n',
+            visible_alias = "limit",
+            short_alias = 'l',
+            default_value = "1"
+        )]
+        count: usize,
+    },
+
+    /// Generate a Markdown roadmap from milestones and epics
+    Roadmap,
+
+    /// Counts per status and type, with lead and cycle time percentiles
+    Stats {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export the relationship graph (parent, blocking, body references)
+    Graph {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "mermaid")]
+        format: GraphFormatArg,
+
+        /// Only include this milestone and everything below it
+        #[arg(long)]
+        milestone: Option<String>,
+
+        /// Only include peas with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+
+    /// Trend reports derived from git history
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
+    /// List milestones with progress and close finished ones
+    Milestone {
+        #[command(subcommand)]
+        action: MilestoneAction,
+    },
+
+    /// Draft CHANGELOG entries from peas completed since a git tag or date
+    ReleaseNotes {
+        /// Git tag, commit, or date (YYYY-MM-DD or RFC 3339) to start from
+        #[arg(long)]
+        since: String,
+
+        /// Heading for the generated section
+        #[arg(long, default_value = "Unreleased")]
+        heading: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Link peas to the releases in CHANGELOG.md
+    Changelog {
+        #[command(subcommand)]
+        action: ChangelogAction,
+    },
+
+    /// Show the git history of a pea as a timeline of field changes
+    Blame {
+        /// Pea ID
+        id: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Record the current peas for later `list --as-of` queries
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Summarize pea changes between git refs as markdown, for pull requests
+    CiSummary {
+        /// Branch or commit the changes are compared against (e.g. origin/main)
+        #[arg(long)]
+        base: String,
+
+        /// Branch or commit with the changes
+        #[arg(long, default_value = "HEAD")]
+        head: String,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    // =========================================================================
+    // Agent Integration
+    // =========================================================================
+    /// Output instructions for AI coding agents
+    Prime {
+        /// Fit the open peas and memories into about this many tokens
+        #[arg(long, value_name = "TOKENS")]
+        budget: Option<usize>,
+
+        /// Rank this pea and its subtree before everything else
+        #[arg(long, value_name = "ID")]
+        focus: Option<String>,
+    },
+
+    /// Output project context for LLMs
+    Context {
+        /// Fit the open peas and memories into about this many tokens
+        #[arg(long, value_name = "TOKENS")]
+        budget: Option<usize>,
+
+        /// Rank this pea and its subtree before everything else
+        #[arg(long, value_name = "ID")]
+        focus: Option<String>,
+    },
+
+    // =========================================================================
+    // GraphQL API
+    // =========================================================================
+    /// Execute a GraphQL query
+    Query {
+        /// GraphQL query string
+        query: String,
+
+        /// Variables as JSON
+        #[arg(long)]
+        variables: Option<String>,
+    },
+
+    /// Execute a GraphQL mutation (automatically wraps in 'mutation { }')
+    Mutate {
+        /// Mutation body (without 'mutation' keyword)
+        mutation: String,
 
         /// Variables as JSON
         #[arg(long)]
@@ -420,6 +1034,25 @@ pub enum Commands {
         /// Port to listen on
         #[arg(short, long, default_value = "4000")]
         port: u16,
+
+        /// Also serve a read-only HTML viewer at /view
+        #[arg(long)]
+        viewer: bool,
+    },
+
+    /// Push and pull changes against another peas instance's GraphQL server
+    Sync {
+        /// GraphQL endpoint of the remote `peas serve` (e.g. http://host:4000/)
+        #[arg(long)]
+        remote: String,
+
+        /// Show what would be pulled and pushed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
     },
 
     // =========================================================================
@@ -438,18 +1071,95 @@ pub enum Commands {
     ///
     /// Alias for `peas doctor --fix` focused on config migration.
     Migrate {
+        #[command(subcommand)]
+        action: Option<MigrateAction>,
+
         /// Dry run - show what would be migrated without making changes
         #[arg(long)]
         dry_run: bool,
     },
 
+    /// Find likely duplicate peas by title similarity and tag overlap
+    FindDuplicates {
+        /// Minimum similarity (0.0-1.0) for two peas to count as duplicates
+        #[arg(long, default_value = "0.7")]
+        threshold: f64,
+
+        /// Also scan archived peas
+        #[arg(long)]
+        archived: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Show peas related to one by tags, title, parent and references
+    Related {
+        /// Pea ID
+        id: String,
+
+        /// Number of related peas to show
+        #[arg(long, short = 'n', visible_alias = "limit", default_value = "10")]
+        count: usize,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Check peas against the policies in `[lint]`, failing for CI
+    Lint {
+        /// Lowest severity that makes the command fail
+        #[arg(long, value_enum, default_value = "error")]
+        fail_on: LintLevelArg,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List and dry-run the automation rules from `[rules.<name>]`
+    Rules {
+        #[command(subcommand)]
+        action: RulesAction,
+    },
+
     /// Undo the last operation
     Undo {
+        /// Show what the last undo step would revert, without reverting it
+        #[arg(long)]
+        show: bool,
+
         /// Output as JSON
         #[arg(long)]
         json: bool,
     },
 
+    /// Git integration for projects that keep .peas/ under version control
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+
+    /// Git merge driver for pea files (registered by `peas git setup`)
+    ///
+    /// Merges frontmatter field by field and writes the result to OURS.
+    /// Exits non-zero if conflicts remain.
+    MergeDriver {
+        /// Common ancestor version (%O)
+        base: String,
+
+        /// Current branch version, overwritten with the result (%A)
+        ours: String,
+
+        /// Other branch version (%B)
+        theirs: String,
+
+        /// Path of the file in the repository (%P), used in messages
+        path: Option<String>,
+    },
+
     // =========================================================================
     // Import & Export
     // =========================================================================
@@ -474,6 +1184,30 @@ pub enum Commands {
     },
 }
 
+impl Commands {
+    /// Whether the command runs inside a loaded project (everything but `init`, `migrate`, `doctor`)
+    pub fn needs_project(&self) -> bool {
+        !matches!(
+            self,
+            Commands::Init { .. }
+                | Commands::Migrate { action: None, .. }
+                | Commands::Doctor { .. }
+                | Commands::MergeDriver { .. }
+        )
+    }
+
+    /// Whether output may be long enough to go through the pager
+    pub fn pages_output(&self) -> bool {
+        matches!(
+            self,
+            Commands::List { .. }
+                | Commands::Show { .. }
+                | Commands::Roadmap
+                | Commands::Report { .. }
+        )
+    }
+}
+
 #[derive(Subcommand)]
 pub enum BulkAction {
     /// Set status of multiple peas
@@ -489,6 +1223,22 @@ pub enum BulkAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Apply all changes or none: a failure rolls back the peas already written
+        #[arg(long)]
+        atomic: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Scrap without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Scrap more peas than the `[bulk] max_destructive` limit
+        #[arg(long)]
+        force: bool,
     },
 
     /// Start multiple peas (set to in-progress)
@@ -500,6 +1250,14 @@ pub enum BulkAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Apply all changes or none: a failure rolls back the peas already written
+        #[arg(long)]
+        atomic: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Complete multiple peas (set to completed)
@@ -511,6 +1269,14 @@ pub enum BulkAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Apply all changes or none: a failure rolls back the peas already written
+        #[arg(long)]
+        atomic: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Add a tag to multiple peas
@@ -525,6 +1291,14 @@ pub enum BulkAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Apply all changes or none: a failure rolls back the peas already written
+        #[arg(long)]
+        atomic: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Set parent of multiple peas
@@ -539,6 +1313,14 @@ pub enum BulkAction {
         /// Output as JSON
         #[arg(long)]
         json: bool,
+
+        /// Apply all changes or none: a failure rolls back the peas already written
+        #[arg(long)]
+        atomic: bool,
+
+        /// Preview what would be changed without making changes
+        #[arg(long)]
+        dry_run: bool,
     },
 
     /// Create multiple peas at once (reads titles from stdin, one per line)
@@ -567,6 +1349,10 @@ pub enum BulkAction {
         #[arg(long)]
         json: bool,
 
+        /// Apply all changes or none: a failure rolls back the peas already written
+        #[arg(long)]
+        atomic: bool,
+
         /// Preview what would be created without making changes
         #[arg(long)]
         dry_run: bool,
@@ -637,6 +1423,165 @@ pub enum MemoryAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum GitAction {
+    /// Register the peas merge driver in .git/config and .gitattributes
+    Setup,
+}
+
+#[derive(Subcommand)]
+pub enum ChangelogAction {
+    /// Set released_in on peas mentioned in the changelog and report completed peas it misses
+    Sync {
+        /// Changelog to scan, relative to the project root
+        #[arg(long, default_value = "CHANGELOG.md")]
+        file: String,
+
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum MilestoneAction {
+    /// List milestones with progress and days until their target date
+    List {
+        /// Include completed and scrapped milestones
+        #[arg(long)]
+        all: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Mark a milestone completed once everything under it is closed
+    Close {
+        /// Milestone ID
+        id: String,
+
+        /// Close even if some peas under it are still open
+        #[arg(long)]
+        force: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ReportAction {
+    /// Cumulative flow: number of peas per status for each day
+    Cfd {
+        /// Number of weeks back from today
+        #[arg(long, default_value = "12")]
+        weeks: u32,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "chart")]
+        format: ReportFormatArg,
+    },
+
+    /// Size estimates against actual cycle times, by size, type and epic
+    Accuracy {
+        /// Output format
+        #[arg(short, long, value_enum, default_value = "chart")]
+        format: ReportFormatArg,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Record the active peas in .peas/snapshots/<label>.json
+    Create {
+        /// Snapshot label (default: the current date and time)
+        label: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List snapshots, oldest first
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum TagAction {
+    /// List tags with usage counts and configured descriptions
+    List {
+        /// Print only tag names, one per line (for shell completion scripts)
+        #[arg(long, conflicts_with = "json")]
+        names: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Rename a tag on every pea that has it
+    Rename {
+        /// Current tag name
+        old: String,
+
+        /// New tag name
+        new: String,
+
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Remove a tag from every pea that has it
+    Delete {
+        /// Tag to remove
+        tag: String,
+
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RulesAction {
+    /// List configured rules
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Run rules against every active pea without writing, showing what would change
+    Test {
+        /// Only test this rule
+        #[arg(long)]
+        rule: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum AssetAction {
     /// Add an asset to a ticket
@@ -689,6 +1634,30 @@ pub enum AssetAction {
     },
 }
 
+#[derive(Subcommand)]
+pub enum MigrateAction {
+    /// Change the ID prefix of every pea, e.g. after importing from beans
+    ///
+    /// Example: `peas migrate prefix --from beans- --to peas-`
+    Prefix {
+        /// Prefix to replace
+        #[arg(long)]
+        from: String,
+
+        /// New prefix
+        #[arg(long)]
+        to: String,
+
+        /// Show what would change without writing
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 pub enum PeaTypeArg {
     Milestone,
@@ -716,6 +1685,123 @@ impl From<PeaTypeArg> for crate::model::PeaType {
     }
 }
 
+#[derive(Clone, Copy, ValueEnum)]
+pub enum LinkKindArg {
+    Pr,
+    Issue,
+    Doc,
+}
+
+impl From<LinkKindArg> for crate::model::LinkKind {
+    fn from(arg: LinkKindArg) -> Self {
+        match arg {
+            LinkKindArg::Pr => crate::model::LinkKind::Pr,
+            LinkKindArg::Issue => crate::model::LinkKind::Issue,
+            LinkKindArg::Doc => crate::model::LinkKind::Doc,
+        }
+    }
+}
+
+/// Column of the `peas list` table
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ListColumnArg {
+    Id,
+    Status,
+    Type,
+    Priority,
+    Title,
+    Tags,
+    Component,
+    Assignee,
+    Parent,
+    Size,
+    Created,
+    Updated,
+}
+
+/// Field to group `peas list` output by
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ListGroupArg {
+    Status,
+    Type,
+    Parent,
+    Tag,
+    Assignee,
+}
+
+/// Order of `peas list` output
+#[derive(Clone, Copy, ValueEnum)]
+pub enum GraphFormatArg {
+    /// Graphviz DOT
+    Dot,
+    /// Mermaid flowchart
+    Mermaid,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ReportFormatArg {
+    /// ASCII chart or table for the terminal
+    Chart,
+    /// Comma-separated values
+    Csv,
+    /// JSON
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum OpenTargetArg {
+    Editor,
+    Tui,
+    Url,
+}
+
+impl From<OpenTargetArg> for crate::config::OpenTarget {
+    fn from(arg: OpenTargetArg) -> Self {
+        match arg {
+            OpenTargetArg::Editor => crate::config::OpenTarget::Editor,
+            OpenTargetArg::Tui => crate::config::OpenTarget::Tui,
+            OpenTargetArg::Url => crate::config::OpenTarget::Url,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum TuiViewArg {
+    /// Ticket tree
+    Tickets,
+    /// Ticket tree with a preview of the selected ticket
+    Split,
+    /// Memory list
+    Memory,
+}
+
+impl From<TuiViewArg> for crate::tui::app::StartView {
+    fn from(arg: TuiViewArg) -> Self {
+        match arg {
+            TuiViewArg::Tickets => crate::tui::app::StartView::Tickets,
+            TuiViewArg::Split => crate::tui::app::StartView::Split,
+            TuiViewArg::Memory => crate::tui::app::StartView::Memory,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum LintLevelArg {
+    Info,
+    Warning,
+    Error,
+}
+
+impl From<LintLevelArg> for crate::lint::Severity {
+    fn from(arg: LintLevelArg) -> Self {
+        match arg {
+            LintLevelArg::Info => crate::lint::Severity::Info,
+            LintLevelArg::Warning => crate::lint::Severity::Warning,
+            LintLevelArg::Error => crate::lint::Severity::Error,
+        }
+    }
+}
+
 #[derive(Clone, Copy, ValueEnum)]
 pub enum PeaStatusArg {
     Draft,
@@ -0,0 +1,358 @@
+use crate::error::PeasError;
+use crate::model::{Pea, PeaType, SizeScale};
+use crate::transaction::Transaction;
+use crate::validation;
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::io::Read;
+
+use super::CommandContext;
+use super::blame::field_changes;
+use super::patch::patch_field;
+
+/// Keys of `peas show --json` output that are managed by peas or have their own commands
+const IGNORED_KEYS: [&str; 8] = [
+    "slug",
+    "created",
+    "updated",
+    "created_by",
+    "updated_by",
+    "claim",
+    "links",
+    "assets",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Action {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// What happened to one input record
+#[derive(Debug, Serialize)]
+struct Outcome {
+    /// Position in the input, starting at 1
+    record: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    action: Option<Action>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pea: Option<Pea>,
+}
+
+pub fn handle_apply(ctx: &CommandContext, json: bool, dry_run: bool) -> Result<()> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let records = parse_records(&input)?;
+
+    // Plan every record before writing anything
+    let mut planned = HashSet::new();
+    let outcomes: Vec<Outcome> = records
+        .iter()
+        .enumerate()
+        .map(|(index, record)| {
+            let planned_before = planned.clone();
+            let exists = |id: &str| planned_before.contains(id) || ctx.repo.exists(id);
+            let result = plan(
+                record,
+                |id| ctx.repo.get(id).ok(),
+                || ctx.repo.generate_id().map_err(|e| e.to_string()),
+                exists,
+                ctx.config.peas.size_scale,
+            );
+            let mut outcome = Outcome {
+                record: index + 1,
+                action: None,
+                changes: Vec::new(),
+                error: None,
+                pea: None,
+            };
+            match result {
+                Ok((_, pea, _)) if !planned.insert(pea.id.clone()) => {
+                    outcome.error = Some(format!("{} appears more than once", pea.id));
+                }
+                Ok((action, pea, changes)) => {
+                    outcome.action = Some(action);
+                    outcome.changes = changes;
+                    outcome.pea = Some(pea);
+                }
+                Err(e) => outcome.error = Some(e),
+            }
+            outcome
+        })
+        .collect();
+
+    let invalid = outcomes.iter().filter(|o| o.error.is_some()).count();
+    if invalid > 0 {
+        report(&outcomes, json, dry_run, false)?;
+        return Err(PeasError::validation(
+            "input",
+            format!(
+                "{} of {} records are invalid, nothing was applied",
+                invalid,
+                outcomes.len()
+            ),
+        )
+        .into());
+    }
+    if dry_run {
+        return report(&outcomes, json, dry_run, false);
+    }
+
+    let mut outcomes = outcomes;
+    let mut tx = Transaction::begin(&ctx.repo, &ctx.config.data_path(&ctx.root))?;
+    for outcome in &mut outcomes {
+        let (Some(action), Some(pea)) = (outcome.action, outcome.pea.as_mut()) else {
+            continue;
+        };
+        let written = match action {
+            Action::Create => tx.create(pea),
+            Action::Update => tx.update(pea),
+            Action::Unchanged => continue,
+        };
+        if let Err(e) = written {
+            outcome.error = Some(e.to_string());
+            // Roll back the records written so far
+            tx.rollback()?;
+            report(&outcomes, json, dry_run, false)?;
+            return Err(e.into());
+        }
+    }
+
+    let description = format!("Apply {} records", tx.len());
+    tx.commit(&description)?;
+    // Pick up changes made by rules and hooks
+    for pea in outcomes.iter_mut().filter_map(|o| o.pea.as_mut()) {
+        *pea = ctx.repo.get(&pea.id)?;
+    }
+    report(&outcomes, json, dry_run, true)
+}
+
+/// A JSON array, or a stream of objects such as NDJSON
+fn parse_records(input: &str) -> crate::error::Result<Vec<Value>> {
+    let input = input.trim_start();
+    if input.starts_with('[') {
+        return Ok(serde_json::from_str(input)?);
+    }
+    Ok(serde_json::Deserializer::from_str(input)
+        .into_iter::<Value>()
+        .collect::<std::result::Result<_, _>>()?)
+}
+
+/// The pea a record turns into, with the action and field changes
+///
+/// A record with the ID of an existing pea patches it; any other record
+/// creates a pea, with its given ID or a new one. Keys are the field names
+/// of `peas patch`, plus `body`; `null` clears a field.
+fn plan(
+    record: &Value,
+    existing: impl Fn(&str) -> Option<Pea>,
+    new_id: impl FnOnce() -> std::result::Result<String, String>,
+    exists: impl Fn(&str) -> bool,
+    scale: SizeScale,
+) -> std::result::Result<(Action, Pea, Vec<String>), String> {
+    let Some(fields) = record.as_object() else {
+        return Err("expected a JSON object".to_string());
+    };
+    let id = match fields.get("id") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(id)) => Some(id.as_str()),
+        Some(_) => return Err("id must be a string".to_string()),
+    };
+
+    let original = id.and_then(existing);
+    let mut pea = match &original {
+        Some(pea) => pea.clone(),
+        None => {
+            let Some(title) = fields.get("title").and_then(Value::as_str) else {
+                return Err(match id {
+                    Some(id) => format!("{} does not exist and a new pea needs a title", id),
+                    None => "a new pea needs a title".to_string(),
+                });
+            };
+            let id = match id {
+                Some(id) => id.to_string(),
+                None => new_id()?,
+            };
+            validation::validate_id(&id).map_err(|e| e.to_string())?;
+            Pea::new(id, title.to_string(), PeaType::default())
+        }
+    };
+
+    for (key, value) in fields {
+        if key == "id" || IGNORED_KEYS.contains(&key.as_str()) {
+            continue;
+        }
+        let value = match value {
+            Value::Null => None,
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Array(items) => Some(
+                items
+                    .iter()
+                    .map(|item| item.as_str().map(String::from))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| format!("{} must be a list of strings", key))?
+                    .join(","),
+            ),
+            Value::Bool(_) | Value::Object(_) => {
+                return Err(format!("{} must be a string, list or null", key));
+            }
+        };
+        if key == "body" {
+            pea.body = value.unwrap_or_default().into();
+        } else {
+            patch_field(&mut pea, key, value.as_deref(), scale).map_err(|e| e.to_string())?;
+        }
+    }
+    validation::validate_target_date(pea.pea_type, pea.target_date).map_err(|e| e.to_string())?;
+    for reference in pea.parent.iter().chain(&pea.blocking) {
+        if !exists(reference) {
+            return Err(format!("{} does not exist", reference));
+        }
+    }
+
+    let mut changes = field_changes(original.as_ref(), &pea);
+    let action = match &original {
+        None => Action::Create,
+        Some(original) => {
+            if original.body != pea.body {
+                changes.push("body changed".to_string());
+            }
+            if changes.is_empty() {
+                Action::Unchanged
+            } else {
+                Action::Update
+            }
+        }
+    };
+    Ok((action, pea, changes))
+}
+
+fn report(outcomes: &[Outcome], json: bool, dry_run: bool, applied: bool) -> Result<()> {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "results": outcomes,
+                "applied": applied,
+                "dry_run": dry_run,
+            }))?
+        );
+        return Ok(());
+    }
+    if outcomes.is_empty() {
+        println!("{}", "No records".dimmed());
+    }
+    for outcome in outcomes {
+        let id = outcome.pea.as_ref().map_or("", |p| p.id.as_str());
+        if let Some(error) = &outcome.error {
+            println!(
+                "{} {}: {}",
+                "Record".red(),
+                outcome.record.to_string().red(),
+                error
+            );
+            continue;
+        }
+        let verb = match (outcome.action, dry_run) {
+            (Some(Action::Create), false) => "Created".green(),
+            (Some(Action::Create), true) => "Would create".green(),
+            (Some(Action::Update), false) => "Updated".green(),
+            (Some(Action::Update), true) => "Would update".green(),
+            _ => "Unchanged".dimmed(),
+        };
+        if outcome.action == Some(Action::Create) {
+            let title = outcome.pea.as_ref().map_or("", |p| p.title.as_str());
+            println!("{} {} {}", verb, id.cyan(), title);
+            continue;
+        }
+        println!("{} {}", verb, id.cyan());
+        for change in &outcome.changes {
+            println!("  {}", change);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaStatus};
+
+    #[test]
+    fn test_parse_records() {
+        let ndjson = "{\"title\": \"One\"}\n{\"title\": \"Two\"}\n";
+        assert_eq!(parse_records(ndjson).unwrap().len(), 2);
+        let array = " [{\"title\": \"One\"}, {\"id\": \"peas-abc12\"}]";
+        assert_eq!(parse_records(array).unwrap().len(), 2);
+        assert!(parse_records("").unwrap().is_empty());
+        assert!(parse_records("{\"title\": ").is_err());
+    }
+
+    #[test]
+    fn test_plan_creates_and_patches() {
+        let mut existing = Pea::new("peas-abc12".into(), "Login".into(), PeaType::Task);
+        existing.created_by = Some("agent-x".into());
+        existing.claim = Some(crate::model::Claim {
+            by: "agent-x".into(),
+            until: chrono::Utc::now(),
+        });
+        let lookup = |id: &str| (id == existing.id).then(|| existing.clone());
+        let exists = |id: &str| id == "peas-abc12";
+        let plan = |record: Value| {
+            plan(
+                &record,
+                lookup,
+                || Ok("peas-new01".to_string()),
+                exists,
+                SizeScale::Fibonacci,
+            )
+        };
+
+        // Full pea objects from --json output are accepted, timestamps and attribution ignored
+        let record = serde_json::to_value(&existing).unwrap();
+        let (action, _, _) = plan(record).unwrap();
+        assert_eq!(action, Action::Unchanged);
+
+        let (action, pea, changes) =
+            plan(serde_json::json!({"id": "peas-abc12", "status": "in-progress", "tags": ["ui"]}))
+                .unwrap();
+        assert_eq!(action, Action::Update);
+        assert_eq!(pea.status, PeaStatus::InProgress);
+        assert_eq!(pea.tags, vec!["ui"]);
+        assert_eq!(changes.len(), 2);
+
+        let (action, pea, _) = plan(serde_json::json!({
+            "title": "Logout",
+            "priority": "high",
+            "parent": "peas-abc12",
+            "size": 3,
+            "body": "Details",
+        }))
+        .unwrap();
+        assert_eq!(action, Action::Create);
+        assert_eq!(pea.id, "peas-new01");
+        assert_eq!(pea.priority, PeaPriority::High);
+        assert_eq!(pea.size.as_deref(), Some("3"));
+        assert_eq!(pea.body, "Details");
+
+        // A given ID that does not exist yet is used for the new pea
+        let (_, pea, _) = plan(serde_json::json!({"id": "peas-xyz99", "title": "X"})).unwrap();
+        assert_eq!(pea.id, "peas-xyz99");
+
+        assert!(plan(serde_json::json!(["not", "an", "object"])).is_err());
+        assert!(plan(serde_json::json!({"id": "peas-xyz99"})).is_err());
+        assert!(plan(serde_json::json!({"title": "X", "parent": "peas-gone1"})).is_err());
+        assert!(plan(serde_json::json!({"title": "X", "owner": "me"})).is_err());
+        assert!(plan(serde_json::json!({"title": "X", "tags": [1]})).is_err());
+    }
+}
@@ -1,12 +1,16 @@
 use crate::cli::commands::{PeaPriorityArg, PeaStatusArg, PeaTypeArg};
+use crate::error::PeasError;
 use crate::model::Pea;
+use crate::removal::{RemovalKind, RemovalPlan};
+use crate::undo::UndoOperation;
 use anyhow::{Result, bail};
 use chrono::{Duration, Utc};
 use colored::Colorize;
+use std::collections::HashSet;
 use std::io::{self, Write};
 
 use super::CommandContext;
-use super::utils::record_undo_archive;
+use super::utils::{child_policy, print_removal_effects, record_undo_batch, record_undo_step};
 
 pub struct ArchiveParams {
     pub id: Option<String>,
@@ -16,6 +20,10 @@ pub struct ArchiveParams {
     pub tag: Option<String>,
     pub older_than: Option<String>,
     pub recursive: bool,
+    /// Move the children under this pea
+    pub reparent_to: Option<String>,
+    /// Clear the parent of the children
+    pub orphan: bool,
     pub keep_assets: bool,
     pub confirm: bool,
     pub dry_run: bool,
@@ -24,16 +32,7 @@ pub struct ArchiveParams {
 
 pub fn handle_archive(ctx: &CommandContext, params: ArchiveParams) -> Result<()> {
     if let Some(ref id) = params.id {
-        if params.recursive {
-            // Collect the target + all descendants, then batch archive
-            let mut peas = collect_descendants(ctx, id)?;
-            // Add the root pea itself
-            if let Ok(root) = ctx.repo.get(id) {
-                peas.insert(0, root);
-            }
-            return handle_batch_archive_peas(ctx, peas, &params);
-        }
-        return handle_single_archive(ctx, id, params.keep_assets, params.json);
+        return handle_single_archive(ctx, id, &params);
     }
 
     // Batch mode: at least one filter must be provided
@@ -51,27 +50,86 @@ pub fn handle_archive(ctx: &CommandContext, params: ArchiveParams) -> Result<()>
     handle_batch_archive(ctx, &params)
 }
 
-fn handle_single_archive(
-    ctx: &CommandContext,
-    id: &str,
-    keep_assets: bool,
-    json: bool,
-) -> Result<()> {
+fn handle_single_archive(ctx: &CommandContext, id: &str, params: &ArchiveParams) -> Result<()> {
+    let (keep_assets, json) = (params.keep_assets, params.json);
     let pea = ctx.repo.get(id)?;
+    let id = pea.id.as_str();
+    let policy = child_policy(
+        ctx,
+        params.recursive,
+        params.reparent_to.clone(),
+        params.orphan,
+    );
+    let plan = RemovalPlan::new(RemovalKind::Archive, id, &policy, &ctx.repo.list()?)?;
+    let also_archived = &plan.removed[1..];
+    let updated: Vec<&str> = plan.updated.iter().map(|p| p.id.as_str()).collect();
+
+    let mut asset_count = 0;
+    for id in &plan.removed {
+        if ctx.asset_manager.has_assets(id) {
+            asset_count += ctx.asset_manager.list_assets(id)?.len();
+        }
+    }
 
-    let asset_count = if ctx.asset_manager.has_assets(id) {
-        ctx.asset_manager.list_assets(id)?.len()
-    } else {
-        0
-    };
+    if params.dry_run {
+        let assets = if keep_assets { 0 } else { asset_count };
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "dry_run": true,
+                    "would_archive": id,
+                    "also_archived": also_archived,
+                    "updated": updated,
+                    "assets": assets
+                }))?
+            );
+        } else {
+            println!("{} {} {}", "Would archive:".yellow(), id.cyan(), pea.title);
+            print_removal_effects(&plan);
+            if assets > 0 {
+                println!("  and delete {} asset(s)", assets.to_string().yellow());
+            }
+        }
+        return Ok(());
+    }
 
-    let original_path = ctx.repo.find_file_by_id(id)?;
-    let archive_path = ctx.repo.archive(id)?;
-    record_undo_archive(ctx, id, &original_path, &archive_path);
+    // Archiving a whole subtree is confirmed unless --confirm/-y was passed
+    if !also_archived.is_empty() && !params.confirm && !json {
+        print_removal_effects(&plan);
+        print!(
+            "\nArchive {} and {} descendant(s)? [y/N] ",
+            id.cyan(),
+            also_archived.len().to_string().yellow()
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    // One undo step, including what was changed before a failure
+    let mut operations = Vec::new();
+    let result = plan.apply(&ctx.repo, &mut operations);
+    let archive_path = operations.iter().find_map(|op| match op {
+        UndoOperation::Archive {
+            id: archived,
+            archive_path,
+            ..
+        } if archived == id => Some(archive_path.clone()),
+        _ => None,
+    });
+    if let Some(step) = plan.undo_step(operations) {
+        record_undo_step(ctx, step);
+    }
+    result?;
 
     let mut assets_deleted = 0;
     if asset_count > 0 && !keep_assets {
-        if !json {
+        let cleanup = if !json {
             print!(
                 "Also delete {} asset(s)? [Y/n] ",
                 asset_count.to_string().yellow()
@@ -80,16 +138,22 @@ fn handle_single_archive(
             let mut input = String::new();
             io::stdin().read_line(&mut input)?;
             let input = input.trim();
-            if input.is_empty() || input.eq_ignore_ascii_case("y") {
-                assets_deleted = ctx.asset_manager.cleanup_ticket_assets(id)?;
-            }
+            input.is_empty() || input.eq_ignore_ascii_case("y")
         } else {
-            assets_deleted = ctx.asset_manager.cleanup_ticket_assets(id)?;
+            true
+        };
+        if cleanup {
+            for id in &plan.removed {
+                if ctx.asset_manager.has_assets(id) {
+                    assets_deleted += ctx.asset_manager.cleanup_ticket_assets(id)?;
+                }
+            }
         }
     }
 
     let filename = archive_path
-        .file_name()
+        .as_deref()
+        .and_then(|path| path.file_name())
         .map(|f| f.to_string_lossy())
         .unwrap_or_default();
     if json {
@@ -99,11 +163,23 @@ fn handle_single_archive(
                 "action": "archived",
                 "id": id,
                 "pea": pea,
+                "also_archived": also_archived,
+                "updated": updated,
                 "assets_deleted": assets_deleted
             }))?
         );
     } else {
         println!("{} {} -> {}", "Archived".yellow(), id.cyan(), filename);
+        for other in also_archived {
+            println!("{} {}", "Archived".yellow(), other.cyan());
+        }
+        if !plan.updated.is_empty() {
+            println!(
+                "  {} {} other pea(s)",
+                "Updated".green(),
+                plan.updated.len()
+            );
+        }
         if assets_deleted > 0 {
             println!(
                 "  {} {} asset(s)",
@@ -122,7 +198,8 @@ fn handle_single_archive(
 }
 
 fn handle_batch_archive(ctx: &CommandContext, params: &ArchiveParams) -> Result<()> {
-    let mut peas = ctx.repo.list()?;
+    let all = ctx.repo.list()?;
+    let mut peas = all.clone();
 
     // Apply filters
     if let Some(s) = params.status {
@@ -146,9 +223,34 @@ fn handle_batch_archive(ctx: &CommandContext, params: &ArchiveParams) -> Result<
         peas.retain(|p| p.updated < cutoff);
     }
 
+    refuse_left_children(&peas, &all)?;
     handle_batch_archive_peas(ctx, peas, params)
 }
 
+/// Fail if archiving `batch` would leave active children pointing at an archived parent
+fn refuse_left_children(batch: &[Pea], all: &[Pea]) -> Result<()> {
+    let ids: HashSet<&str> = batch.iter().map(|p| p.id.as_str()).collect();
+    let left: Vec<String> = all
+        .iter()
+        .filter(|p| !ids.contains(p.id.as_str()))
+        .filter_map(|p| {
+            let parent = p.parent.as_deref().filter(|parent| ids.contains(parent))?;
+            Some(format!("{} (child of {})", p.id, parent))
+        })
+        .collect();
+    if left.is_empty() {
+        return Ok(());
+    }
+    Err(PeasError::validation(
+        "children",
+        format!(
+            "Archiving these peas would leave children behind: {}; archive their parents by ID with --cascade, --reparent-to or --orphan",
+            left.join(", ")
+        ),
+    )
+    .into())
+}
+
 fn handle_batch_archive_peas(
     ctx: &CommandContext,
     peas: Vec<Pea>,
@@ -215,13 +317,22 @@ fn handle_batch_archive_peas(
     // Execute archival
     let mut archived_ids: Vec<String> = Vec::new();
     let mut failed: Vec<(String, String)> = Vec::new();
+    let mut operations = Vec::new();
 
     for pea in &peas {
         match archive_one(ctx, &pea.id, params.keep_assets) {
-            Ok(()) => archived_ids.push(pea.id.clone()),
+            Ok(operation) => {
+                archived_ids.push(pea.id.clone());
+                operations.push(operation);
+            }
             Err(e) => failed.push((pea.id.clone(), e.to_string())),
         }
     }
+    record_undo_batch(
+        ctx,
+        &format!("Archive {} peas", operations.len()),
+        operations,
+    );
 
     if params.json {
         println!(
@@ -250,34 +361,20 @@ fn handle_batch_archive_peas(
     Ok(())
 }
 
-/// Recursively collect all descendants of a pea by walking the parent tree.
-fn collect_descendants(ctx: &CommandContext, parent_id: &str) -> Result<Vec<Pea>> {
-    let all_peas = ctx.repo.list()?;
-    let mut result = Vec::new();
-    let mut queue = vec![parent_id.to_string()];
-
-    while let Some(current_id) = queue.pop() {
-        for pea in &all_peas {
-            if pea.parent.as_deref() == Some(&current_id) {
-                queue.push(pea.id.clone());
-                result.push(pea.clone());
-            }
-        }
-    }
-
-    Ok(result)
-}
-
-fn archive_one(ctx: &CommandContext, id: &str, keep_assets: bool) -> Result<()> {
+/// Archive a pea, returning how to undo it
+fn archive_one(ctx: &CommandContext, id: &str, keep_assets: bool) -> Result<UndoOperation> {
     let original_path = ctx.repo.find_file_by_id(id)?;
     let archive_path = ctx.repo.archive(id)?;
-    record_undo_archive(ctx, id, &original_path, &archive_path);
 
     if !keep_assets && ctx.asset_manager.has_assets(id) {
         let _ = ctx.asset_manager.cleanup_ticket_assets(id);
     }
 
-    Ok(())
+    Ok(UndoOperation::Archive {
+        id: id.to_string(),
+        original_path,
+        archive_path,
+    })
 }
 
 fn print_preview(peas: &[Pea]) {
@@ -0,0 +1,214 @@
+use crate::git;
+use crate::model::Pea;
+use crate::storage::parse_markdown;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use serde::Serialize;
+
+use super::CommandContext;
+
+/// One step in a pea's history
+#[derive(Debug, Serialize)]
+struct Entry {
+    /// None for changes that are not committed yet
+    commit: Option<String>,
+    author: Option<String>,
+    date: DateTime<Utc>,
+    subject: String,
+    changes: Vec<String>,
+}
+
+pub fn handle_blame(ctx: &CommandContext, id: &str, json: bool) -> Result<()> {
+    let path = ctx.repo.find_file_by_id(id)?;
+    let current = ctx.repo.get(id)?;
+
+    let mut history = git::file_history(&ctx.root, &path)?;
+    if history.is_empty() {
+        anyhow::bail!(
+            "No git history for {} (is {} committed?)",
+            id,
+            path.display()
+        );
+    }
+    history.reverse();
+
+    let mut entries = Vec::new();
+    let mut previous: Option<Pea> = None;
+    for commit in history {
+        let changes = match git::show_file(&ctx.root, &commit.hash, &commit.path)
+            .ok()
+            .and_then(|content| parse_markdown(&content).ok())
+        {
+            Some(pea) => {
+                let changes = field_changes(previous.as_ref(), &pea);
+                previous = Some(pea);
+                changes
+            }
+            None => vec!["file removed or not parseable".to_string()],
+        };
+        entries.push(Entry {
+            commit: Some(commit.hash),
+            author: Some(commit.author),
+            date: commit.date,
+            subject: commit.subject,
+            changes,
+        });
+    }
+
+    let uncommitted = field_changes(previous.as_ref(), &current);
+    if !uncommitted.is_empty() {
+        entries.push(Entry {
+            commit: None,
+            author: None,
+            date: current.updated,
+            subject: "Uncommitted changes".to_string(),
+            changes: uncommitted,
+        });
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    println!("{} {}", current.id.cyan(), current.title.bold());
+    for entry in &entries {
+        let commit = entry
+            .commit
+            .as_deref()
+            .map(|hash| hash[..hash.len().min(7)].to_string())
+            .unwrap_or_else(|| "-------".to_string());
+        println!(
+            "\n{}  {}  {}  {}",
+            entry.date.format("%Y-%m-%d %H:%M").to_string().dimmed(),
+            commit.yellow(),
+            entry.author.as_deref().unwrap_or("you").bold(),
+            entry.subject
+        );
+        if entry.changes.is_empty() {
+            println!("  {}", "no field changes".dimmed());
+        }
+        for change in &entry.changes {
+            println!("  - {}", change);
+        }
+    }
+    Ok(())
+}
+
+/// Human-readable differences between two versions of a pea's frontmatter and body
+pub(super) fn field_changes(old: Option<&Pea>, new: &Pea) -> Vec<String> {
+    let Some(old) = old else {
+        return vec![format!(
+            "created as {} ({}, {} priority)",
+            new.pea_type, new.status, new.priority
+        )];
+    };
+
+    let mut changes = Vec::new();
+    let mut changed = |field: &str, before: String, after: String| {
+        if before != after {
+            changes.push(format!("{}: {} -> {}", field, before, after));
+        }
+    };
+    changed("title", old.title.clone(), new.title.clone());
+    changed("type", old.pea_type.to_string(), new.pea_type.to_string());
+    changed("status", old.status.to_string(), new.status.to_string());
+    changed(
+        "priority",
+        old.priority.to_string(),
+        new.priority.to_string(),
+    );
+    changed("size", or_none(&old.size), or_none(&new.size));
+    changed(
+        "component",
+        or_none(&old.component),
+        or_none(&new.component),
+    );
+    changed("assignee", or_none(&old.assignee), or_none(&new.assignee));
+    changed("parent", or_none(&old.parent), or_none(&new.parent));
+    changed(
+        "target date",
+        or_none(&old.target_date.map(|d| d.to_string())),
+        or_none(&new.target_date.map(|d| d.to_string())),
+    );
+    changed(
+        "released in",
+        or_none(&old.released_in),
+        or_none(&new.released_in),
+    );
+    changed(
+        "claimed by",
+        or_none(&old.claim.as_ref().map(|c| c.by.clone())),
+        or_none(&new.claim.as_ref().map(|c| c.by.clone())),
+    );
+
+    let urls = |pea: &Pea| -> Vec<String> { pea.links.iter().map(|l| l.url.clone()).collect() };
+    for (field, before, after) in [
+        ("tags", &old.tags, &new.tags),
+        ("blocking", &old.blocking, &new.blocking),
+        ("refs", &old.external_refs, &new.external_refs),
+        ("links", &urls(old), &urls(new)),
+        ("assets", &old.assets, &new.assets),
+    ] {
+        let diff: Vec<String> = after
+            .iter()
+            .filter(|v| !before.contains(v))
+            .map(|v| format!("+{}", v))
+            .chain(
+                before
+                    .iter()
+                    .filter(|v| !after.contains(v))
+                    .map(|v| format!("-{}", v)),
+            )
+            .collect();
+        if !diff.is_empty() {
+            changes.push(format!("{}: {}", field, diff.join(" ")));
+        }
+    }
+
+    if old.body.trim() != new.body.trim() {
+        changes.push("body edited".to_string());
+    }
+    changes
+}
+
+fn or_none(value: &Option<String>) -> String {
+    value.clone().unwrap_or_else(|| "none".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaStatus, PeaType};
+
+    #[test]
+    fn test_field_changes() {
+        let old = Pea::new("peas-abc12".into(), "Login".into(), PeaType::Bug)
+            .with_tags(vec!["auth".into(), "ui".into()]);
+        assert_eq!(
+            field_changes(None, &old),
+            vec!["created as bug (todo, normal priority)"]
+        );
+
+        let new = old
+            .clone()
+            .with_status(PeaStatus::InProgress)
+            .with_priority(PeaPriority::High)
+            .with_tags(vec!["auth".into(), "urgent".into()])
+            .with_body("Steps to reproduce".into());
+        assert_eq!(
+            field_changes(Some(&old), &new),
+            vec![
+                "status: todo -> in-progress",
+                "priority: normal -> high",
+                "tags: +urgent -ui",
+                "body edited",
+            ]
+        );
+
+        let mut touched = old.clone();
+        touched.touch();
+        assert!(field_changes(Some(&old), &touched).is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use crate::error::{PeasError, Result};
+use crate::model::{Memory, Pea, PeaStatus, descendants};
+use std::collections::HashSet;
+
+use super::CommandContext;
+use super::suggest::priority_rank;
+
+/// Rough number of characters per token in English prose and Markdown
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Longest pea body or memory excerpt in budgeted output, in characters
+pub(super) const EXCERPT_CHARS: usize = 400;
+
+/// Part of the budget kept for memories while there are any, as a divisor
+const MEMORY_SHARE: usize = 4;
+
+/// Check the `--budget` and `--focus` arguments of `peas prime` and `peas context`
+pub(super) fn check_args(
+    ctx: &CommandContext,
+    budget: Option<usize>,
+    focus: Option<&str>,
+) -> Result<()> {
+    if budget == Some(0) {
+        return Err(PeasError::validation(
+            "budget",
+            "The budget must be at least 1 token",
+        ));
+    }
+    if let Some(id) = focus {
+        ctx.repo.get(id)?;
+    }
+    Ok(())
+}
+
+/// Estimated number of tokens an LLM needs for `text`
+pub(super) fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// `text` on one line, cut at a word boundary to at most `max_chars` characters
+pub(super) fn excerpt(text: &str, max_chars: usize) -> String {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+    let cut: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    let cut = match cut.rfind(' ') {
+        Some(space) if space > cut.len() / 2 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end())
+}
+
+/// Open peas, most relevant first
+///
+/// With a focus, the focused pea leads, followed by everything below it and
+/// then the peas above it. Within each group in-progress peas come first,
+/// then higher priorities and recently updated ones.
+pub(super) fn rank_peas<'a>(peas: &'a [Pea], focus: Option<&str>) -> Vec<&'a Pea> {
+    let below: HashSet<&str> = focus
+        .map(|id| {
+            descendants(peas, id)
+                .iter()
+                .map(|p| p.id.as_str())
+                .collect()
+        })
+        .unwrap_or_default();
+    let above = focus.map(|id| ancestors(peas, id)).unwrap_or_default();
+    let group = |pea: &Pea| {
+        if Some(pea.id.as_str()) == focus {
+            0
+        } else if below.contains(pea.id.as_str()) {
+            1
+        } else if above.contains(&pea.id.as_str()) {
+            2
+        } else {
+            3
+        }
+    };
+
+    let mut open: Vec<&Pea> = peas.iter().filter(|p| p.is_open()).collect();
+    open.sort_by(|a, b| {
+        group(a)
+            .cmp(&group(b))
+            .then_with(|| {
+                (b.status == PeaStatus::InProgress).cmp(&(a.status == PeaStatus::InProgress))
+            })
+            .then_with(|| priority_rank(a.priority).cmp(&priority_rank(b.priority)))
+            .then_with(|| b.updated.cmp(&a.updated))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    open
+}
+
+/// Memories, most relevant first
+///
+/// Memories that mention one of the `related` peas by ID or share a tag with
+/// one come first; otherwise recently updated memories win.
+pub(super) fn rank_memories<'a>(memories: &'a [Memory], related: &[&Pea]) -> Vec<&'a Memory> {
+    let is_related = |memory: &Memory| {
+        related.iter().any(|pea| {
+            memory.content.contains(&pea.id) || pea.tags.iter().any(|t| memory.tags.contains(t))
+        })
+    };
+    let mut ranked: Vec<&Memory> = memories.iter().collect();
+    ranked.sort_by(|a, b| {
+        is_related(b)
+            .cmp(&is_related(a))
+            .then_with(|| b.updated.cmp(&a.updated))
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    ranked
+}
+
+/// The focused pea and everything below it
+pub(super) fn subtree<'a>(peas: &'a [Pea], focus: &str) -> Vec<&'a Pea> {
+    peas.iter()
+        .filter(|p| p.id == focus)
+        .chain(descendants(peas, focus))
+        .collect()
+}
+
+/// IDs of the peas above `id`, nearest first
+fn ancestors<'a>(peas: &'a [Pea], id: &str) -> Vec<&'a str> {
+    let mut result = Vec::new();
+    let mut current = peas.iter().find(|p| p.id == id);
+    while let Some(parent_id) = current.and_then(|p| p.parent.as_deref()) {
+        // Guard against parent cycles in hand-edited files
+        if parent_id == id || result.contains(&parent_id) {
+            break;
+        }
+        result.push(parent_id);
+        current = peas.iter().find(|p| p.id == parent_id);
+    }
+    result
+}
+
+/// Items that fit into a budget, in their original order
+pub(super) struct Fitted<T> {
+    pub items: Vec<T>,
+    /// Items left out for lack of room
+    pub omitted: usize,
+}
+
+/// Fit peas and then memories into `budget` tokens
+///
+/// Items are taken in order and skipped when they do not fit. Up to a quarter
+/// of the budget is kept for memories, and whatever the peas leave over goes
+/// to memories too.
+pub(super) fn fit<P, M>(
+    peas: Vec<P>,
+    memories: Vec<M>,
+    budget: usize,
+    pea_cost: impl Fn(&P) -> usize,
+    memory_cost: impl Fn(&M) -> usize,
+) -> (Fitted<P>, Fitted<M>) {
+    let memory_total: usize = memories.iter().map(&memory_cost).sum();
+    let reserve = memory_total.min(budget / MEMORY_SHARE);
+    let mut remaining = budget - reserve;
+    let peas = take_fitting(peas, &mut remaining, pea_cost);
+    remaining += reserve;
+    let memories = take_fitting(memories, &mut remaining, memory_cost);
+    (peas, memories)
+}
+
+fn take_fitting<T>(items: Vec<T>, remaining: &mut usize, cost: impl Fn(&T) -> usize) -> Fitted<T> {
+    let mut fitted = Fitted {
+        items: Vec::new(),
+        omitted: 0,
+    };
+    for item in items {
+        let cost = cost(&item);
+        if cost <= *remaining {
+            *remaining -= cost;
+            fitted.items.push(item);
+        } else {
+            fitted.omitted += 1;
+        }
+    }
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaType};
+
+    #[test]
+    fn test_excerpt() {
+        assert_eq!(excerpt("  Short\n\nbody ", 20), "Short body");
+        assert_eq!(
+            excerpt("The quick brown fox jumps over the lazy dog", 20),
+            "The quick brown…"
+        );
+        assert_eq!(excerpt("Supercalifragilistic", 8), "Superca…");
+        assert_eq!(estimate_tokens("12345"), 2);
+    }
+
+    #[test]
+    fn test_rank_peas_with_focus() {
+        let peas = vec![
+            Pea::new("peas-epic1".into(), "Epic".into(), PeaType::Epic),
+            Pea::new("peas-feat1".into(), "Feature".into(), PeaType::Feature)
+                .with_parent(Some("peas-epic1".into())),
+            Pea::new("peas-task1".into(), "Task".into(), PeaType::Task)
+                .with_parent(Some("peas-feat1".into())),
+            Pea::new("peas-hot01".into(), "Urgent".into(), PeaType::Bug)
+                .with_priority(PeaPriority::Critical),
+            Pea::new("peas-done1".into(), "Done".into(), PeaType::Task)
+                .with_parent(Some("peas-feat1".into()))
+                .with_status(PeaStatus::Completed),
+        ];
+        let ids = |ranked: Vec<&Pea>| ranked.iter().map(|p| p.id.clone()).collect::<Vec<_>>();
+
+        assert_eq!(ids(rank_peas(&peas, None))[0], "peas-hot01");
+        assert_eq!(
+            ids(rank_peas(&peas, Some("peas-feat1"))),
+            ["peas-feat1", "peas-task1", "peas-epic1", "peas-hot01"]
+        );
+        assert_eq!(subtree(&peas, "peas-feat1").len(), 3);
+    }
+
+    #[test]
+    fn test_fit_keeps_room_for_memories() {
+        let (peas, memories) = fit(vec![30, 30, 20], vec![20, 50], 100, |c| *c, |c| *c);
+        assert_eq!(peas.items, [30, 30]);
+        assert_eq!(peas.omitted, 1);
+        assert_eq!(memories.items, [20]);
+        assert_eq!(memories.omitted, 1);
+
+        // Without memories the peas may use everything
+        let (peas, _) = fit(vec![30, 30, 20], Vec::<usize>::new(), 100, |c| *c, |c| *c);
+        assert_eq!(peas.items, [30, 30, 20]);
+    }
+}
@@ -1,11 +1,15 @@
 use crate::cli::commands::{BulkAction, PeaPriorityArg, PeaStatusArg, PeaTypeArg};
+use crate::error::PeasError;
 use crate::model::{Pea, PeaStatus};
+use crate::transaction::Transaction;
+use crate::undo::UndoOperation;
 use anyhow::Result;
 use colored::Colorize;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
 
 use super::CommandContext;
-use super::utils::record_undo_update;
+use super::blame::field_changes;
+use super::utils::{format_status, record_undo_batch, warn_new_tags};
 
 /// Parameters for bulk create operation
 struct BulkCreateParams {
@@ -15,17 +19,34 @@ struct BulkCreateParams {
     priority: Option<PeaPriorityArg>,
     status: Option<PeaStatusArg>,
     json: bool,
+    atomic: bool,
     dry_run: bool,
 }
 
 pub fn handle_bulk(ctx: &CommandContext, action: BulkAction) -> Result<()> {
     match action {
-        BulkAction::Status { status, ids, json } => {
+        BulkAction::Status {
+            status,
+            ids,
+            json,
+            atomic,
+            dry_run,
+            yes,
+            force,
+        } => {
             let new_status: PeaStatus = status.into();
+            if new_status == PeaStatus::Scrapped
+                && !dry_run
+                && !confirm_destructive(ctx, &ids, "Scrap", yes, force, json)?
+            {
+                return Ok(());
+            }
             bulk_update(
                 ctx,
                 &ids,
                 json,
+                atomic,
+                dry_run,
                 |pea| {
                     pea.status = new_status;
                     true
@@ -33,44 +54,77 @@ pub fn handle_bulk(ctx: &CommandContext, action: BulkAction) -> Result<()> {
                 |id| format!("{} {} -> {}", "Updated".green(), id.cyan(), new_status),
             )
         }
-        BulkAction::Start { ids, json } => bulk_update(
+        BulkAction::Start {
+            ids,
+            json,
+            atomic,
+            dry_run,
+        } => bulk_update(
             ctx,
             &ids,
             json,
+            atomic,
+            dry_run,
             |pea| {
                 pea.status = PeaStatus::InProgress;
                 true
             },
             |id| format!("{} {}", "Started".green(), id.cyan()),
         ),
-        BulkAction::Done { ids, json } => bulk_update(
+        BulkAction::Done {
+            ids,
+            json,
+            atomic,
+            dry_run,
+        } => bulk_update(
             ctx,
             &ids,
             json,
+            atomic,
+            dry_run,
             |pea| {
                 pea.status = PeaStatus::Completed;
                 true
             },
             |id| format!("{} {}", "Completed".green(), id.cyan()),
         ),
-        BulkAction::Tag { tag, ids, json } => bulk_update_with_skip(
-            ctx,
-            &ids,
+        BulkAction::Tag {
+            tag,
+            ids,
             json,
-            |pea| {
-                if !pea.tags.contains(&tag) {
-                    pea.tags.push(tag.clone());
-                    (true, None)
-                } else {
-                    (false, Some("already has tag".to_string()))
-                }
-            },
-            |id| format!("{} {} +{}", "Tagged".green(), id.cyan(), tag.magenta()),
-        ),
-        BulkAction::Parent { parent, ids, json } => bulk_update(
+            atomic,
+            dry_run,
+        } => {
+            warn_new_tags(ctx, std::slice::from_ref(&tag));
+            bulk_update_with_skip(
+                ctx,
+                &ids,
+                json,
+                atomic,
+                dry_run,
+                |pea| {
+                    if !pea.tags.contains(&tag) {
+                        pea.tags.push(tag.clone());
+                        (true, None)
+                    } else {
+                        (false, Some("already has tag".to_string()))
+                    }
+                },
+                |id| format!("{} {} +{}", "Tagged".green(), id.cyan(), tag.magenta()),
+            )
+        }
+        BulkAction::Parent {
+            parent,
+            ids,
+            json,
+            atomic,
+            dry_run,
+        } => bulk_update(
             ctx,
             &ids,
             json,
+            atomic,
+            dry_run,
             |pea| {
                 pea.parent = Some(parent.clone());
                 true
@@ -91,6 +145,7 @@ pub fn handle_bulk(ctx: &CommandContext, action: BulkAction) -> Result<()> {
             priority,
             status,
             json,
+            atomic,
             dry_run,
         } => handle_bulk_create(
             ctx,
@@ -101,18 +156,83 @@ pub fn handle_bulk(ctx: &CommandContext, action: BulkAction) -> Result<()> {
                 priority,
                 status,
                 json,
+                atomic,
                 dry_run,
             },
         ),
     }
 }
 
+/// Guard a destructive bulk change
+///
+/// More peas than `[bulk] max_destructive` are refused without `--force`.
+/// Unless `--yes` or `--json` is given, the affected peas are listed and the
+/// user is asked to confirm. Returns whether to go ahead.
+fn confirm_destructive(
+    ctx: &CommandContext,
+    ids: &[String],
+    verb: &str,
+    yes: bool,
+    force: bool,
+    json: bool,
+) -> Result<bool> {
+    let mut unique: Vec<&String> = Vec::new();
+    for id in ids {
+        if !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    let limit = ctx.config.bulk.max_destructive;
+    if limit > 0 && unique.len() > limit && !force {
+        return Err(PeasError::validation(
+            "ids",
+            format!(
+                "{} would affect {} peas, more than the limit of {} ([bulk] max_destructive); pass --force to go ahead",
+                verb,
+                unique.len(),
+                limit
+            ),
+        )
+        .into());
+    }
+    if yes || json {
+        return Ok(true);
+    }
+
+    for id in &unique {
+        match ctx.repo.get(id) {
+            Ok(pea) => println!(
+                "  {} {} {}",
+                pea.id.cyan(),
+                format_status(pea.status),
+                pea.title
+            ),
+            Err(_) => println!("  {} {}", id.cyan(), "(not found)".red()),
+        }
+    }
+    print!(
+        "\n{} {} pea(s)? [y/N] ",
+        verb,
+        unique.len().to_string().yellow()
+    );
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    if !input.trim().eq_ignore_ascii_case("y") {
+        println!("Aborted.");
+        return Ok(false);
+    }
+    Ok(true)
+}
+
 /// Generic bulk update handler for simple mutations
 /// Uses validate-then-apply strategy: loads all peas and validates before writing any
 fn bulk_update<F, M>(
     ctx: &CommandContext,
     ids: &[String],
     json: bool,
+    atomic: bool,
+    dry_run: bool,
     mut mutate: F,
     message_fn: M,
 ) -> Result<()>
@@ -153,27 +273,20 @@ where
         return Ok(());
     }
 
-    // Phase 2: Apply all updates (now that we know all peas are valid)
-    let mut updated_peas = Vec::new();
-
-    for mut pea in peas_to_update {
-        // Record undo before update
-        if let Ok(old_path) = ctx.repo.find_file_by_id(&pea.id) {
-            record_undo_update(ctx, &pea.id, &old_path);
-        }
-        if let Err(e) = ctx.repo.update(&mut pea) {
-            if !json {
-                eprintln!("{} {}: {}", "Error updating".red(), pea.id, e);
-            }
-            errors_list.push(serde_json::json!({"id": pea.id, "error": e.to_string()}));
-        } else {
-            if !json {
-                println!("{}", message_fn(&pea.id));
-            }
-            updated_peas.push(pea);
-        }
+    if dry_run {
+        return bulk_dry_run(ctx, peas_to_update, json);
     }
 
+    // Phase 2: Apply all updates (now that we know all peas are valid)
+    let updated_peas = write_updates(
+        ctx,
+        peas_to_update,
+        atomic,
+        json,
+        &message_fn,
+        &mut errors_list,
+    )?;
+
     if json {
         println!(
             "{}",
@@ -205,6 +318,8 @@ fn bulk_update_with_skip<F, M>(
     ctx: &CommandContext,
     ids: &[String],
     json: bool,
+    atomic: bool,
+    dry_run: bool,
     mut mutate: F,
     message_fn: M,
 ) -> Result<()>
@@ -253,26 +368,20 @@ where
         return Ok(());
     }
 
-    // Phase 2: Apply all updates (now that we know all peas are valid)
-    let mut updated_peas = Vec::new();
-
-    for mut pea in peas_to_update {
-        if let Ok(old_path) = ctx.repo.find_file_by_id(&pea.id) {
-            record_undo_update(ctx, &pea.id, &old_path);
-        }
-        if let Err(e) = ctx.repo.update(&mut pea) {
-            if !json {
-                eprintln!("{} {}: {}", "Error updating".red(), pea.id, e);
-            }
-            errors_list.push(serde_json::json!({"id": pea.id, "error": e.to_string()}));
-        } else {
-            if !json {
-                println!("{}", message_fn(&pea.id));
-            }
-            updated_peas.push(pea);
-        }
+    if dry_run {
+        return bulk_dry_run(ctx, peas_to_update, json);
     }
 
+    // Phase 2: Apply all updates (now that we know all peas are valid)
+    let updated_peas = write_updates(
+        ctx,
+        peas_to_update,
+        atomic,
+        json,
+        &message_fn,
+        &mut errors_list,
+    )?;
+
     if json {
         println!(
             "{}",
@@ -293,6 +402,109 @@ where
     Ok(())
 }
 
+/// Report what a bulk update would change, checking each pea as the update would
+fn bulk_dry_run(ctx: &CommandContext, peas: Vec<Pea>, json: bool) -> Result<()> {
+    let mut would_update = Vec::new();
+    let mut errors_list: Vec<serde_json::Value> = Vec::new();
+    for pea in peas {
+        let before = ctx.repo.get(&pea.id)?;
+        match ctx.repo.preview_update(&pea) {
+            Ok(after) => {
+                if !json {
+                    println!("{} {}", "Would update:".yellow(), after.id.cyan());
+                    for change in field_changes(Some(&before), &after) {
+                        println!("  {}", change);
+                    }
+                }
+                would_update.push(after);
+            }
+            Err(e) => {
+                if !json {
+                    eprintln!("{} {}: {}", "Would fail".red(), pea.id, e);
+                }
+                errors_list.push(serde_json::json!({"id": pea.id, "error": e.to_string()}));
+            }
+        }
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "dry_run": true,
+                "would_update": would_update,
+                "errors": errors_list
+            }))?
+        );
+    } else {
+        println!("\n{} {} peas", "Would update:".yellow(), would_update.len());
+    }
+    Ok(())
+}
+
+/// Phase 2 of a bulk update: write the loaded peas
+///
+/// A pea that fails is reported in `errors` and the others are still written.
+/// With `atomic`, a failure instead rolls back every write. Either way the
+/// peas written are one undo step.
+fn write_updates<M>(
+    ctx: &CommandContext,
+    peas: Vec<Pea>,
+    atomic: bool,
+    json: bool,
+    message_fn: &M,
+    errors: &mut Vec<serde_json::Value>,
+) -> Result<Vec<Pea>>
+where
+    M: Fn(&str) -> String,
+{
+    let mut updated_peas = Vec::new();
+    if atomic {
+        let mut tx = Transaction::begin(&ctx.repo, &ctx.config.data_path(&ctx.root))?;
+        for mut pea in peas {
+            if let Err(e) = tx.update(&mut pea) {
+                tx.rollback()?;
+                return Err(anyhow::Error::from(e).context(format!(
+                    "Failed to update {}, bulk operation rolled back (no changes made)",
+                    pea.id
+                )));
+            }
+            updated_peas.push(pea);
+        }
+        tx.commit(&format!("Bulk update of {} peas", updated_peas.len()))?;
+        if !json {
+            for pea in &updated_peas {
+                println!("{}", message_fn(&pea.id));
+            }
+        }
+        return Ok(updated_peas);
+    }
+
+    let mut operations = Vec::new();
+    for mut pea in peas {
+        // Capture the previous content before the update
+        let operation = ctx
+            .repo
+            .find_file_by_id(&pea.id)
+            .and_then(|old_path| UndoOperation::update_from_file(&pea.id, &old_path));
+        if let Err(e) = ctx.repo.update(&mut pea) {
+            if !json {
+                eprintln!("{} {}: {}", "Error updating".red(), pea.id, e);
+            }
+            errors.push(serde_json::json!({"id": pea.id, "error": e.to_string()}));
+        } else {
+            if !json {
+                println!("{}", message_fn(&pea.id));
+            }
+            operations.extend(operation.ok());
+            updated_peas.push(pea);
+        }
+    }
+    let description = format!("Bulk update of {} peas", operations.len());
+    record_undo_batch(ctx, &description, operations);
+    Ok(updated_peas)
+}
+
 /// Handle bulk create from stdin
 fn handle_bulk_create(ctx: &CommandContext, params: BulkCreateParams) -> Result<()> {
     // Read titles from stdin, one per line
@@ -321,6 +533,8 @@ fn handle_bulk_create(ctx: &CommandContext, params: BulkCreateParams) -> Result<
         return Ok(());
     }
 
+    warn_new_tags(ctx, &params.tag);
+
     let pea_type = params.r#type.into();
     let pea_status: Option<PeaStatus> = params.status.map(|s: PeaStatusArg| s.into());
     let pea_priority = params.priority.map(|p: PeaPriorityArg| p.into());
@@ -373,6 +587,16 @@ fn handle_bulk_create(ctx: &CommandContext, params: BulkCreateParams) -> Result<
 
     let mut created_peas = Vec::new();
     let mut errors_list: Vec<serde_json::Value> = Vec::new();
+    let mut tx = if params.atomic {
+        Some(Transaction::begin(
+            &ctx.repo,
+            &ctx.config.data_path(&ctx.root),
+        )?)
+    } else {
+        None
+    };
+    let mut pending_lines = Vec::new();
+    let mut operations = Vec::new();
 
     for title in titles {
         let id = ctx.repo.generate_id()?;
@@ -391,18 +615,39 @@ fn handle_bulk_create(ctx: &CommandContext, params: BulkCreateParams) -> Result<
             pea = pea.with_priority(p);
         }
 
-        match ctx.repo.create(&pea) {
+        let created = match tx.as_mut() {
+            Some(tx) => tx.create(&pea),
+            None => ctx.repo.create(&pea),
+        };
+        match created {
             Ok(path) => {
+                if tx.is_none() {
+                    operations.push(UndoOperation::Create {
+                        id: pea.id.clone(),
+                        file_path: path.clone(),
+                    });
+                }
                 let filename = path
                     .file_name()
                     .map(|f| f.to_string_lossy())
                     .unwrap_or_default();
-                if !params.json {
-                    println!("{} {} {}", "Created".green(), pea.id.cyan(), filename);
+                let line = format!("{} {} {}", "Created".green(), pea.id.cyan(), filename);
+                // Atomic creates are only reported once they are committed
+                if tx.is_some() {
+                    pending_lines.push(line);
+                } else if !params.json {
+                    println!("{}", line);
                 }
                 created_peas.push(pea);
             }
             Err(e) => {
+                if let Some(tx) = tx {
+                    tx.rollback()?;
+                    return Err(anyhow::Error::from(e).context(format!(
+                        "Failed to create '{}', bulk create rolled back (no peas created)",
+                        title
+                    )));
+                }
                 if !params.json {
                     eprintln!("{} '{}': {}", "Error".red(), title, e);
                 }
@@ -414,6 +659,18 @@ fn handle_bulk_create(ctx: &CommandContext, params: BulkCreateParams) -> Result<
         }
     }
 
+    let description = format!("Bulk create of {} peas", created_peas.len());
+    if let Some(tx) = tx {
+        tx.commit(&description)?;
+        if !params.json {
+            for line in pending_lines {
+                println!("{}", line);
+            }
+        }
+    } else {
+        record_undo_batch(ctx, &description, operations);
+    }
+
     if params.json {
         println!(
             "{}",
@@ -0,0 +1,376 @@
+use crate::cli::commands::PeaTypeArg;
+use crate::error::PeasError;
+use crate::model::{Pea, PeaStatus};
+use anyhow::{Context, Result};
+use base64::Engine;
+use colored::Colorize;
+use regex::{Captures, Regex};
+use std::io::Read;
+use std::sync::LazyLock;
+
+use super::CommandContext;
+use super::utils::{UndoBatch, warn_new_tags};
+
+/// RFC 2047 encoded word in a header, e.g. `=?UTF-8?B?SGVsbG8=?=`
+static ENCODED_WORD: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"=\?[^?\s]+\?([bBqQ])\?([^?\s]*)\?=").expect("encoded word pattern should be valid")
+});
+
+/// A title and body taken from a captured document
+#[derive(Debug, PartialEq)]
+struct Draft {
+    title: String,
+    body: String,
+}
+
+pub fn handle_capture(
+    ctx: &CommandContext,
+    text: Option<String>,
+    from_file: Option<String>,
+    from_email: Option<String>,
+    r#type: PeaTypeArg,
+    tag: Vec<String>,
+    json: bool,
+) -> Result<()> {
+    let drafts = if let Some(path) = from_file {
+        vec![parse_markdown(&read_source(&path)?)?]
+    } else if let Some(path) = from_email {
+        vec![parse_email(&read_source(&path)?)?]
+    } else {
+        let text = match text.filter(|t| t != "-") {
+            Some(text) => text,
+            None => read_source("-")?,
+        };
+        text.lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(|title| Draft {
+                title: title.to_string(),
+                body: String::new(),
+            })
+            .collect()
+    };
+    if drafts.is_empty() {
+        return Err(PeasError::validation("text", "Nothing to capture").into());
+    }
+    warn_new_tags(ctx, &tag);
+
+    let mut batch = UndoBatch::new(ctx, format!("Capture {} draft(s)", drafts.len()));
+    let mut captured = Vec::new();
+    for draft in drafts {
+        let pea = Pea::new(ctx.repo.generate_id()?, draft.title, r#type.into())
+            .with_status(PeaStatus::Draft)
+            .with_tags(tag.clone())
+            .with_body(draft.body);
+        batch.create(&pea)?;
+        // Pick up changes made by rules or a pre-create hook
+        captured.push(ctx.repo.get(&pea.id)?);
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&captured)?);
+        return Ok(());
+    }
+    for pea in &captured {
+        println!("{} {} {}", "Captured".green(), pea.id.cyan(), pea.title);
+    }
+    Ok(())
+}
+
+/// Read a file, or stdin for `-`; invalid UTF-8 is replaced rather than rejected
+fn read_source(path: &str) -> Result<String> {
+    let mut bytes = Vec::new();
+    if path == "-" {
+        std::io::stdin().read_to_end(&mut bytes)?;
+    } else {
+        bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+    }
+    Ok(String::from_utf8_lossy(&bytes).replace("\r\n", "\n"))
+}
+
+/// Use the first heading, or else the first line, of a markdown document as
+/// the title and the rest as the body; YAML frontmatter is dropped
+fn parse_markdown(text: &str) -> Result<Draft> {
+    let mut text = text.trim_start();
+    if let Some(rest) = text.strip_prefix("---\n")
+        && let Some(end) = rest.find("\n---")
+    {
+        text = rest[end + 4..].trim_start_matches(|c| c != '\n');
+    }
+    let lines: Vec<&str> = text.lines().collect();
+    let heading = lines
+        .iter()
+        .position(|l| l.starts_with('#') && l.trim_start_matches('#').starts_with(' '));
+    let Some(index) = heading.or_else(|| lines.iter().position(|l| !l.trim().is_empty())) else {
+        return Err(PeasError::validation("from-file", "The document is empty").into());
+    };
+    let title = match heading {
+        Some(_) => lines[index].trim_start_matches('#').trim().to_string(),
+        None => lines[index].trim().to_string(),
+    };
+    let mut body = lines[..index].to_vec();
+    body.extend(&lines[index + 1..]);
+    Ok(Draft {
+        title,
+        body: body.join("\n").trim().to_string(),
+    })
+}
+
+/// Use the subject of an email as the title and its plain text as the body,
+/// noting the sender and date at the top
+fn parse_email(raw: &str) -> Result<Draft> {
+    let (headers, body) = split_message(raw);
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| decode_header(v))
+    };
+    let text = message_text(&headers, body).trim().to_string();
+    let title = match header("Subject").filter(|s| !s.trim().is_empty()) {
+        Some(subject) => subject.split_whitespace().collect::<Vec<_>>().join(" "),
+        None => match text.lines().find(|l| !l.trim().is_empty()) {
+            Some(line) => line.trim().to_string(),
+            None => {
+                return Err(PeasError::validation(
+                    "from-email",
+                    "The email has no subject or text",
+                )
+                .into());
+            }
+        },
+    };
+
+    let mut meta = Vec::new();
+    for name in ["From", "Date"] {
+        if let Some(value) = header(name) {
+            meta.push(format!("**{}:** {}", name, value.trim()));
+        }
+    }
+    let body = if meta.is_empty() {
+        text
+    } else {
+        format!("{}\n\n{}", meta.join("  \n"), text)
+    };
+    Ok(Draft {
+        title,
+        body: body.trim().to_string(),
+    })
+}
+
+/// Split a message or MIME part into unfolded headers and its body
+fn split_message(raw: &str) -> (Vec<(String, String)>, &str) {
+    let (head, body) = match raw.find("\n\n") {
+        Some(end) => (&raw[..end], &raw[end + 2..]),
+        None => (raw, ""),
+    };
+    let mut headers: Vec<(String, String)> = Vec::new();
+    for line in head.lines() {
+        if line.starts_with([' ', '\t']) {
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    (headers, body)
+}
+
+/// A `key=value` parameter of a header such as `Content-Type`
+fn header_param(value: &str, key: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|param| {
+        let (k, v) = param.split_once('=')?;
+        k.trim()
+            .eq_ignore_ascii_case(key)
+            .then(|| v.trim().trim_matches('"').to_string())
+    })
+}
+
+/// The readable text of a message: its plain text part, or the first text part
+fn message_text(headers: &[(String, String)], body: &str) -> String {
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    };
+    let content_type = header("Content-Type").unwrap_or("text/plain");
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    if mime.starts_with("multipart/") {
+        let Some(boundary) = header_param(content_type, "boundary") else {
+            return body.to_string();
+        };
+        let delimiter = format!("--{}", boundary);
+        let parts: Vec<(Vec<(String, String)>, &str)> = body
+            .split(delimiter.as_str())
+            .skip(1)
+            .take_while(|part| !part.starts_with("--"))
+            .map(|part| split_message(part.strip_prefix('\n').unwrap_or(part)))
+            .collect();
+        // Parts without a content type are plain text
+        let is_type = |headers: &[(String, String)], prefix: &str| {
+            let content_type = headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case("Content-Type"))
+                .map_or("text/plain".to_string(), |(_, v)| v.to_ascii_lowercase());
+            content_type.starts_with(prefix)
+        };
+        // Prefer plain text, then nested multiparts, then any other text
+        let part = parts
+            .iter()
+            .find(|(h, _)| is_type(h, "text/plain"))
+            .or_else(|| parts.iter().find(|(h, _)| is_type(h, "multipart/")))
+            .or_else(|| parts.iter().find(|(h, _)| is_type(h, "text/")));
+        return match part {
+            Some((headers, body)) => message_text(headers, body),
+            None => String::new(),
+        };
+    }
+
+    let encoding = header("Content-Transfer-Encoding")
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let text = match encoding.as_str() {
+        "base64" => {
+            let compact: String = body.split_whitespace().collect();
+            match base64::engine::general_purpose::STANDARD.decode(compact) {
+                Ok(bytes) => String::from_utf8_lossy(&bytes).replace("\r\n", "\n"),
+                Err(_) => body.to_string(),
+            }
+        }
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.to_string(),
+    };
+    if mime == "text/html" {
+        strip_html(&text)
+    } else {
+        text
+    }
+}
+
+/// Decode quoted-printable text; in headers (`q`) underscores are spaces
+fn decode_quoted_printable(text: &str, q: bool) -> String {
+    let text = text.replace("=\n", "");
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'=' if i + 2 < bytes.len() => {
+                match std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'='),
+                }
+            }
+            b'_' if q => out.push(b' '),
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Decode RFC 2047 encoded words in a header value
+fn decode_header(value: &str) -> String {
+    // Whitespace between adjacent encoded words is not part of the text
+    static BETWEEN_WORDS: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"\?=\s+=\?").expect("encoded word gap pattern should be valid")
+    });
+    let value = BETWEEN_WORDS.replace_all(value, "?==?");
+    ENCODED_WORD
+        .replace_all(&value, |caps: &Captures| {
+            if caps[1].eq_ignore_ascii_case("b") {
+                base64::engine::general_purpose::STANDARD
+                    .decode(&caps[2])
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                    .unwrap_or_else(|_| caps[0].to_string())
+            } else {
+                decode_quoted_printable(&caps[2], true)
+            }
+        })
+        .into_owned()
+}
+
+/// Crude text from an HTML-only email: tags dropped, common entities decoded
+fn strip_html(html: &str) -> String {
+    static TAG: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"(?is)<script.*?</script>|<style.*?</style>|<[^>]*>")
+            .expect("tag pattern should be valid")
+    });
+    let text = TAG.replace_all(html, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_markdown() {
+        let draft =
+            parse_markdown("---\ntags: [x]\n---\nIntro line\n\n## Fix the login\n\nSteps\n")
+                .unwrap();
+        assert_eq!(draft.title, "Fix the login");
+        assert_eq!(draft.body, "Intro line\n\n\nSteps");
+
+        let draft = parse_markdown("\n#hashtag idea\nmore").unwrap();
+        assert_eq!(draft.title, "#hashtag idea");
+        assert_eq!(draft.body, "more");
+        assert!(parse_markdown("  \n").is_err());
+    }
+
+    #[test]
+    fn test_parse_email() {
+        let raw = "From: Ann <ann@example.com>\n\
+                   Subject: =?UTF-8?Q?Caf=C3=A9_menu?=\n =?UTF-8?B?YnJva2Vu?=\n\
+                   Content-Type: multipart/alternative; boundary=\"b1\"\n\
+                   \n\
+                   preamble\n\
+                   --b1\n\
+                   Content-Type: text/html\n\
+                   \n\
+                   <p>Ignored</p>\n\
+                   --b1\n\
+                   Content-Type: text/plain; charset=utf-8\n\
+                   Content-Transfer-Encoding: quoted-printable\n\
+                   \n\
+                   The menu page =\n\
+                   is empty =E2=80=94 again.\n\
+                   --b1--\n";
+        let draft = parse_email(raw).unwrap();
+        assert_eq!(draft.title, "Café menubroken");
+        assert_eq!(
+            draft.body,
+            "**From:** Ann <ann@example.com>\n\nThe menu page is empty — again."
+        );
+
+        let raw = "Content-Type: text/html\nContent-Transfer-Encoding: base64\n\nPGI+SGk8L2I+ICZhbXA7IGJ5ZQ==\n";
+        let draft = parse_email(raw).unwrap();
+        assert_eq!(draft.title, "Hi & bye");
+    }
+}
@@ -0,0 +1,158 @@
+use crate::cli::commands::ChangelogAction;
+use crate::model::PeaStatus;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::collections::{BTreeMap, HashSet};
+
+use super::CommandContext;
+use super::utils::UndoBatch;
+
+pub fn handle_changelog(ctx: &CommandContext, action: ChangelogAction) -> Result<()> {
+    match action {
+        ChangelogAction::Sync {
+            file,
+            dry_run,
+            json,
+        } => handle_changelog_sync(ctx, &file, dry_run, json),
+    }
+}
+
+fn handle_changelog_sync(
+    ctx: &CommandContext,
+    file: &str,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let path = ctx.root.join(file);
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+
+    let peas = ctx.repo.list()?;
+    let known: HashSet<&str> = peas.iter().map(|p| p.id.as_str()).collect();
+    let releases = released_ids(&text, &known);
+
+    let mut batch = UndoBatch::new(ctx, "Changelog sync");
+    let mut annotated = Vec::new();
+    for mut pea in peas.iter().cloned() {
+        let Some(release) = releases.get(pea.id.as_str()) else {
+            continue;
+        };
+        if pea.released_in.as_deref() == Some(release.as_str()) {
+            continue;
+        }
+        pea.released_in = Some(release.clone());
+        if !dry_run {
+            batch.update(&mut pea)?;
+        }
+        annotated.push((pea.id, release.clone()));
+    }
+
+    let unreleased: Vec<_> = peas
+        .iter()
+        .filter(|p| {
+            p.status == PeaStatus::Completed
+                && p.released_in.is_none()
+                && !releases.contains_key(p.id.as_str())
+        })
+        .collect();
+
+    if json {
+        let annotated: Vec<_> = annotated
+            .iter()
+            .map(|(id, release)| serde_json::json!({ "id": id, "released_in": release }))
+            .collect();
+        let unreleased: Vec<&str> = unreleased.iter().map(|p| p.id.as_str()).collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "annotated": annotated,
+                "unreleased": unreleased,
+                "dry_run": dry_run,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if annotated.is_empty() {
+        println!("All peas mentioned in {} are up to date.", file);
+    } else {
+        let verb = if dry_run {
+            "Would annotate"
+        } else {
+            "Annotated"
+        };
+        println!("{} {} pea(s):", verb.green(), annotated.len());
+        for (id, release) in &annotated {
+            println!("  {} -> {}", id.cyan(), release);
+        }
+    }
+    if !unreleased.is_empty() {
+        println!(
+            "{}: {} completed pea(s) not mentioned in any release:",
+            "warning".yellow().bold(),
+            unreleased.len()
+        );
+        for pea in unreleased {
+            println!("  {} {}", pea.id.cyan(), pea.title);
+        }
+    }
+    Ok(())
+}
+
+/// Map each known pea ID to the release (`## ` heading) that first shipped it.
+///
+/// Changelogs list the newest release first, so a pea mentioned under several
+/// releases belongs to the lowest one. Mentions under an "Unreleased" heading
+/// or before the first heading do not count.
+fn released_ids<'a>(text: &str, known: &HashSet<&'a str>) -> BTreeMap<&'a str, String> {
+    let mut releases = BTreeMap::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        if let Some(heading) = line.strip_prefix("## ") {
+            current = release_name(heading);
+            continue;
+        }
+        let Some(release) = &current else {
+            continue;
+        };
+        for word in line.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_')) {
+            if let Some(id) = known.get(word) {
+                releases.insert(*id, release.clone());
+            }
+        }
+    }
+    releases
+}
+
+/// Version from a heading like `[1.2.0] - 2026-01-31`; None for Unreleased
+fn release_name(heading: &str) -> Option<String> {
+    let name = heading
+        .split_whitespace()
+        .next()?
+        .trim_start_matches('[')
+        .trim_end_matches(']');
+    (!name.eq_ignore_ascii_case("unreleased")).then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_released_ids_takes_oldest_release() {
+        let text = "# Changelog\n\n\
+                    Intro mentioning peas-aaaaa.\n\n\
+                    ## [Unreleased]\n\n- Pending (peas-ddddd)\n\n\
+                    ## [0.2.0] - 2026-02-01\n\n- Follow-up for peas-aaaaa, see peas-bbbbb\n\n\
+                    ## v0.1.0\n\n- First cut (peas-aaaaa)\n- Unknown peas-zzzzz\n";
+        let known = HashSet::from(["peas-aaaaa", "peas-bbbbb", "peas-ccccc", "peas-ddddd"]);
+        let releases = released_ids(text, &known);
+        assert_eq!(
+            releases,
+            BTreeMap::from([
+                ("peas-aaaaa", "v0.1.0".to_string()),
+                ("peas-bbbbb", "0.2.0".to_string()),
+            ])
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use crate::git;
+use crate::history::pea_location;
+use crate::model::{Pea, PeaStatus};
+use crate::storage::parse_markdown;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+use super::CommandContext;
+use super::blame::field_changes;
+
+/// Kinds of change with their section headings, in output order
+const SECTIONS: [(Kind, &str); 6] = [
+    (Kind::Created, "Created"),
+    (Kind::Completed, "Completed"),
+    (Kind::Status, "Status changes"),
+    (Kind::Updated, "Updated"),
+    (Kind::Archived, "Archived"),
+    (Kind::Deleted, "Deleted"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Created,
+    Completed,
+    Status,
+    Updated,
+    Archived,
+    Deleted,
+}
+
+/// What happened to one pea between the two refs
+#[derive(Debug, Serialize)]
+struct Change {
+    kind: Kind,
+    id: String,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    from: Option<PeaStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<PeaStatus>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<String>,
+}
+
+/// A pea as committed at one ref, and whether it was archived there
+type Version = (Pea, bool);
+
+pub fn handle_ci_summary(ctx: &CommandContext, base: &str, head: &str, json: bool) -> Result<()> {
+    // Compare against where the branch forked, like a pull request diff does
+    let fork = git::merge_base(&ctx.root, base, head)?;
+    let data_path = ctx.config.data_path(&ctx.root);
+
+    let mut versions: BTreeMap<String, (Option<Version>, Option<Version>)> = BTreeMap::new();
+    for path in git::changed_files(&ctx.root, &fork, head, &data_path)? {
+        let Some(archived) = pea_location(&path) else {
+            continue;
+        };
+        let read = |rev: &str| {
+            git::show_file(&ctx.root, rev, &path)
+                .ok()
+                .and_then(|content| parse_markdown(&content).ok())
+        };
+        if let Some(pea) = read(&fork) {
+            let id = pea.id.clone();
+            versions.entry(id).or_default().0 = Some((pea, archived));
+        }
+        if let Some(pea) = read(head) {
+            let id = pea.id.clone();
+            versions.entry(id).or_default().1 = Some((pea, archived));
+        }
+    }
+
+    let mut changes: Vec<Change> = versions
+        .into_values()
+        .filter_map(|(before, after)| classify(before, after))
+        .collect();
+    changes.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.id.cmp(&b.id)));
+
+    if json {
+        let mut counts = BTreeMap::new();
+        for change in &changes {
+            *counts.entry(change.kind).or_insert(0) += 1;
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "base": base,
+                "head": head,
+                "merge_base": fork,
+                "counts": counts,
+                "changes": changes,
+            }))?
+        );
+        return Ok(());
+    }
+
+    print!("{}", render(&changes));
+    Ok(())
+}
+
+/// Sort the two versions of a pea into one kind of change; None if nothing visible changed
+fn classify(before: Option<Version>, after: Option<Version>) -> Option<Change> {
+    let change = |kind, pea: &Pea| Change {
+        kind,
+        id: pea.id.clone(),
+        title: pea.title.clone(),
+        from: None,
+        to: None,
+        changes: Vec::new(),
+    };
+    match (before, after) {
+        (None, None) => None,
+        (None, Some((pea, _))) => Some(Change {
+            to: Some(pea.status),
+            ..change(Kind::Created, &pea)
+        }),
+        (Some((pea, _)), None) => Some(change(Kind::Deleted, &pea)),
+        (Some((old, was_archived)), Some((new, archived))) => {
+            let details: Vec<String> = field_changes(Some(&old), &new)
+                .into_iter()
+                .filter(|c| !c.starts_with("status:"))
+                .collect();
+            let kind = if old.status != new.status {
+                if new.status == PeaStatus::Completed {
+                    Kind::Completed
+                } else {
+                    Kind::Status
+                }
+            } else if archived && !was_archived {
+                Kind::Archived
+            } else if !details.is_empty() {
+                Kind::Updated
+            } else {
+                return None;
+            };
+            Some(Change {
+                from: (old.status != new.status).then_some(old.status),
+                to: (old.status != new.status).then_some(new.status),
+                changes: details,
+                ..change(kind, &new)
+            })
+        }
+    }
+}
+
+/// Markdown for a pull request description or comment
+fn render(changes: &[Change]) -> String {
+    let mut out = String::from("## Peas\n\n");
+    if changes.is_empty() {
+        out.push_str("No pea changes.\n");
+        return out;
+    }
+
+    let counts: Vec<String> = SECTIONS
+        .iter()
+        .filter_map(|(kind, heading)| {
+            let count = changes.iter().filter(|c| c.kind == *kind).count();
+            (count > 0).then(|| format!("{} {}", count, heading.to_lowercase()))
+        })
+        .collect();
+    out.push_str(&counts.join(", "));
+    out.push('\n');
+
+    for (kind, heading) in SECTIONS {
+        let items: Vec<&Change> = changes.iter().filter(|c| c.kind == kind).collect();
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {}\n\n", heading));
+        for change in items {
+            let mut line = format!("- `{}` {}", change.id, change.title);
+            match (change.from, change.to) {
+                (Some(from), Some(to)) => line.push_str(&format!(" ({} → {})", from, to)),
+                (None, Some(status)) => line.push_str(&format!(" ({})", status)),
+                _ => {}
+            }
+            if !change.changes.is_empty() {
+                line.push_str(&format!(": {}", change.changes.join("; ")));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaType};
+
+    #[test]
+    fn test_classify_and_render() {
+        let old = Pea::new("peas-abc12".into(), "Login crash".into(), PeaType::Bug);
+        let done = old.clone().with_status(PeaStatus::Completed);
+        let bumped = old.clone().with_priority(PeaPriority::High);
+        let new = Pea::new("peas-new01".into(), "Dark mode".into(), PeaType::Feature);
+
+        let changes: Vec<Change> = [
+            (None, Some((new, false))),
+            (Some((old.clone(), false)), Some((done, false))),
+            (Some((old.clone(), false)), Some((bumped, false))),
+            (Some((old.clone(), false)), Some((old.clone(), true))),
+            (Some((old.clone(), false)), Some((old, false))),
+        ]
+        .into_iter()
+        .filter_map(|(before, after)| classify(before, after))
+        .collect();
+        let kinds: Vec<Kind> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Kind::Created,
+                Kind::Completed,
+                Kind::Updated,
+                Kind::Archived
+            ]
+        );
+
+        assert_eq!(
+            render(&changes),
+            "## Peas\n\n\
+             1 created, 1 completed, 1 updated, 1 archived\n\n\
+             ### Created\n\n- `peas-new01` Dark mode (todo)\n\n\
+             ### Completed\n\n- `peas-abc12` Login crash (todo → completed)\n\n\
+             ### Updated\n\n- `peas-abc12` Login crash: priority: normal -> high\n\n\
+             ### Archived\n\n- `peas-abc12` Login crash\n"
+        );
+        assert_eq!(render(&[]), "## Peas\n\nNo pea changes.\n");
+    }
+}
@@ -0,0 +1,81 @@
+use crate::claim::{claim, parse_ttl, release, require_actor};
+use anyhow::Result;
+use chrono::Utc;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::record_undo_update;
+
+pub fn handle_claim(
+    ctx: &CommandContext,
+    id: &str,
+    ttl: &str,
+    force: bool,
+    json: bool,
+) -> Result<()> {
+    let actor = require_actor(ctx.config.actor.as_deref())?;
+    let ttl = parse_ttl(ttl)?;
+    let mut pea = ctx.repo.get(id)?;
+    let previous = pea.claim.clone();
+    claim(&mut pea, actor, ttl, force, Utc::now())?;
+
+    let old_path = ctx.repo.find_file_by_id(&pea.id)?;
+    record_undo_update(ctx, &pea.id, &old_path);
+    ctx.repo.update(&mut pea)?;
+
+    let claim = pea.claim.as_ref().expect("just claimed");
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pea)?);
+    } else {
+        let verb = match &previous {
+            Some(held) if held.by == claim.by => "Renewed claim on",
+            _ => "Claimed",
+        };
+        println!(
+            "{} {} until {}",
+            verb.green(),
+            pea.id.cyan(),
+            claim.until.format("%Y-%m-%d %H:%M")
+        );
+        if let Some(held) = previous.filter(|c| c.by != claim.by && c.is_active(Utc::now())) {
+            println!(
+                "{}: took over the claim by {}",
+                "warning".yellow().bold(),
+                held.by
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_release(ctx: &CommandContext, id: &str, force: bool, json: bool) -> Result<()> {
+    let mut pea = ctx.repo.get(id)?;
+    let released = release(&mut pea, ctx.config.actor.as_deref(), force, Utc::now())?;
+
+    if released.is_some() {
+        let old_path = ctx.repo.find_file_by_id(&pea.id)?;
+        record_undo_update(ctx, &pea.id, &old_path);
+        ctx.repo.update(&mut pea)?;
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "id": pea.id,
+                "released": released,
+            }))?
+        );
+    } else {
+        match released {
+            Some(claim) => println!(
+                "{} {} (was claimed by {})",
+                "Released".green(),
+                pea.id.cyan(),
+                claim.by
+            ),
+            None => println!("{} is not claimed", pea.id.cyan()),
+        }
+    }
+    Ok(())
+}
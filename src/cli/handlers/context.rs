@@ -1,12 +1,24 @@
-use crate::model::{PeaStatus, PeaType};
+use crate::model::{Pea, PeaStatus, PeaType};
+use crate::storage::MemoryRepository;
 use anyhow::Result;
+use serde_json::Value;
 
 use super::CommandContext;
+use super::budget::{
+    EXCERPT_CHARS, check_args, estimate_tokens, excerpt, fit, rank_memories, rank_peas, subtree,
+};
 
-pub fn handle_context(ctx: &CommandContext) -> Result<()> {
+pub fn handle_context(
+    ctx: &CommandContext,
+    budget: Option<usize>,
+    focus: Option<String>,
+) -> Result<()> {
+    check_args(ctx, budget, focus.as_deref())?;
     let peas = ctx.repo.list()?;
+    let today = chrono::Utc::now().date_naive();
+    let open_peas = rank_peas(&peas, focus.as_deref());
 
-    let context = serde_json::json!({
+    let mut context = serde_json::json!({
         "total": peas.len(),
         "by_status": {
             "draft": peas.iter().filter(|p| p.status == PeaStatus::Draft).count(),
@@ -22,16 +34,93 @@ pub fn handle_context(ctx: &CommandContext) -> Result<()> {
             "bug": peas.iter().filter(|p| p.pea_type == PeaType::Bug).count(),
             "task": peas.iter().filter(|p| p.pea_type == PeaType::Task).count(),
         },
-        "open_peas": peas.iter().filter(|p| p.is_open()).map(|p| {
-            serde_json::json!({
+        "overdue_milestones": peas
+            .iter()
+            .filter(|p| p.pea_type == PeaType::Milestone && p.is_overdue(today))
+            .map(|p| serde_json::json!({
                 "id": p.id,
                 "title": p.title,
-                "type": format!("{}", p.pea_type),
-                "status": format!("{}", p.status),
+                "target_date": p.target_date,
+            }))
+            .collect::<Vec<_>>(),
+        "open_peas": [],
+    });
+    if let Some(focus) = &focus {
+        context["focus"] = focus.as_str().into();
+    }
+
+    let Some(budget) = budget else {
+        context["open_peas"] = open_peas.iter().map(|p| summary(p)).collect();
+        println!("{}", serde_json::to_string_pretty(&context)?);
+        return Ok(());
+    };
+
+    // Budgeted entries carry excerpts, and memories join the peas
+    let memories = MemoryRepository::new(&ctx.config, &ctx.root).list(None)?;
+    let related = focus
+        .as_deref()
+        .map(|id| subtree(&peas, id))
+        .unwrap_or_default();
+    let pea_entries: Vec<Value> = open_peas
+        .iter()
+        .map(|p| {
+            let mut entry = summary(p);
+            entry["priority"] = p.priority.to_string().into();
+            if let Some(parent) = &p.parent {
+                entry["parent"] = parent.as_str().into();
+            }
+            let body = excerpt(&p.body, EXCERPT_CHARS);
+            if !body.is_empty() {
+                entry["body"] = body.into();
+            }
+            entry
+        })
+        .collect();
+    let memory_entries: Vec<Value> = rank_memories(&memories, &related)
+        .iter()
+        .map(|m| {
+            serde_json::json!({
+                "key": m.key,
+                "tags": m.tags,
+                "content": excerpt(&m.content, EXCERPT_CHARS),
             })
-        }).collect::<Vec<_>>(),
+        })
+        .collect();
+
+    context["memories"] = Value::Array(Vec::new());
+    context["budget"] = serde_json::json!({
+        "tokens": budget,
+        "omitted_peas": open_peas.len(),
+        "omitted_memories": memories.len(),
     });
+    let fixed = estimate_tokens(&serde_json::to_string_pretty(&context)?);
+    let (fitted_peas, fitted_memories) = fit(
+        pea_entries,
+        memory_entries,
+        budget.saturating_sub(fixed),
+        entry_tokens,
+        entry_tokens,
+    );
+    context["open_peas"] = fitted_peas.items.into();
+    context["memories"] = fitted_memories.items.into();
+    context["budget"]["omitted_peas"] = fitted_peas.omitted.into();
+    context["budget"]["omitted_memories"] = fitted_memories.omitted.into();
 
     println!("{}", serde_json::to_string_pretty(&context)?);
     Ok(())
 }
+
+fn summary(pea: &Pea) -> Value {
+    serde_json::json!({
+        "id": pea.id,
+        "title": pea.title,
+        "type": format!("{}", pea.pea_type),
+        "status": format!("{}", pea.status),
+    })
+}
+
+/// Tokens of an array entry, indented two levels deep in the output
+fn entry_tokens(entry: &Value) -> usize {
+    let text = serde_json::to_string_pretty(entry).unwrap_or_default();
+    estimate_tokens(&text) + text.lines().count() + 1
+}
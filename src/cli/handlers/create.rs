@@ -1,10 +1,12 @@
 use crate::cli::commands::{PeaPriorityArg, PeaStatusArg, PeaTypeArg, TemplateArg};
 use crate::model::Pea;
+use crate::validation;
 use anyhow::Result;
+use chrono::NaiveDate;
 use colored::Colorize;
 
 use super::CommandContext;
-use super::utils::{record_undo_create, resolve_body};
+use super::utils::{clipboard_body, record_undo_create, resolve_body, resolve_id, warn_new_tags};
 
 #[allow(clippy::too_many_arguments)]
 pub fn handle_create(
@@ -15,16 +17,25 @@ pub fn handle_create(
     priority: Option<PeaPriorityArg>,
     body: Option<String>,
     body_file: Option<String>,
+    body_from_clipboard: bool,
     parent: Option<String>,
     blocks: Vec<String>,
     blocked_by: Vec<String>,
     external_ref: Vec<String>,
     tag: Vec<String>,
+    component: Option<String>,
+    assignee: Option<String>,
+    target_date: Option<NaiveDate>,
+    defer_until: Option<NaiveDate>,
     template: Option<TemplateArg>,
     json: bool,
     dry_run: bool,
 ) -> Result<()> {
-    let body_content = resolve_body(body, body_file)?;
+    let body_content = if body_from_clipboard {
+        Some(clipboard_body()?)
+    } else {
+        resolve_body(body, body_file)?
+    };
     let id = ctx.repo.generate_id()?;
 
     // Apply template settings if specified, then allow CLI args to override
@@ -68,11 +79,17 @@ pub fn handle_create(
         pea = pea.with_tags(all_tags);
     }
 
-    if parent.is_some() {
-        pea = pea.with_parent(parent);
+    if let Some(parent) = parent {
+        pea = pea.with_parent(Some(resolve_id(ctx, parent)));
+    }
+    pea = pea.with_component(component).with_assignee(assignee);
+    if target_date.is_some() {
+        pea = pea.with_target_date(target_date);
+        validation::validate_target_date(pea.pea_type, pea.target_date)?;
     }
+    pea.defer_until = defer_until;
     if !blocks.is_empty() {
-        pea = pea.with_blocking(blocks);
+        pea = pea.with_blocking(blocks.into_iter().map(|b| resolve_id(ctx, b)).collect());
     }
     if !external_ref.is_empty() {
         pea = pea.with_external_refs(external_ref);
@@ -85,7 +102,11 @@ pub fn handle_create(
         pea = pea.with_body(bt.to_string());
     }
 
+    warn_new_tags(ctx, &pea.tags);
+
     if dry_run {
+        // Checked and shaped as a real create (or a GraphQL dry run) would
+        let pea = ctx.repo.preview_create(&pea)?;
         if json {
             println!(
                 "{}",
@@ -107,6 +128,8 @@ pub fn handle_create(
     }
 
     let path = ctx.repo.create(&pea)?;
+    // Pick up changes made by a pre-create hook
+    let pea = ctx.repo.get(&pea.id)?;
 
     // Apply blocked-by relationships (add this pea's ID to each blocker's blocking list)
     for blocker_id in &blocked_by {
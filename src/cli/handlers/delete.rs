@@ -1,26 +1,89 @@
+use crate::removal::{RemovalKind, RemovalPlan};
 use anyhow::Result;
 use colored::Colorize;
 use std::io::{self, Write};
 
 use super::CommandContext;
-use super::utils::record_undo_delete;
+use super::utils::{child_policy, print_removal_effects, record_undo_step, resolve_id};
+
+/// Parameters for delete operation
+pub struct DeleteParams {
+    pub id: String,
+    pub force: bool,
+    /// Delete the descendants too
+    pub cascade: bool,
+    /// Move the children under this pea
+    pub reparent_to: Option<String>,
+    /// Clear the parent of the children
+    pub orphan: bool,
+    pub keep_assets: bool,
+    pub json: bool,
+    pub dry_run: bool,
+}
+
+pub fn handle_delete(ctx: &CommandContext, params: DeleteParams) -> Result<()> {
+    let DeleteParams {
+        id,
+        force,
+        keep_assets,
+        json,
+        dry_run,
+        ..
+    } = params;
+
+    // Fail like a real delete would for an unknown ID; a file that no longer
+    // parses can still be deleted by its ID
+    ctx.repo.find_file_by_id(&id)?;
+    let id = resolve_id(ctx, id);
+    let policy = child_policy(ctx, params.cascade, params.reparent_to, params.orphan);
+    let plan = RemovalPlan::new(RemovalKind::Delete, &id, &policy, &ctx.repo.list()?)?;
 
-pub fn handle_delete(
-    ctx: &CommandContext,
-    id: String,
-    force: bool,
-    keep_assets: bool,
-    json: bool,
-) -> Result<()> {
     // Check for assets before confirmation
-    let asset_count = if ctx.asset_manager.has_assets(&id) {
-        ctx.asset_manager.list_assets(&id)?.len()
-    } else {
-        0
-    };
+    let asset_count: usize = plan
+        .removed
+        .iter()
+        .filter(|id| ctx.asset_manager.has_assets(id))
+        .map(|id| ctx.asset_manager.list_assets(id).map(|a| a.len()))
+        .sum::<std::result::Result<usize, _>>()?;
+    let updated: Vec<&str> = plan.updated.iter().map(|p| p.id.as_str()).collect();
+
+    if dry_run {
+        let assets = if keep_assets { 0 } else { asset_count };
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "dry_run": true,
+                    "would_delete": id,
+                    "also_deleted": &plan.removed[1..],
+                    "updated": updated,
+                    "mentioned_by": plan.mentioned_by,
+                    "assets": assets
+                }))?
+            );
+        } else {
+            println!("{} {}", "Would delete:".yellow(), id.cyan());
+            print_removal_effects(&plan);
+            if assets > 0 {
+                println!("  and {} asset(s)", assets.to_string().yellow());
+            }
+        }
+        return Ok(());
+    }
 
     if !force && !json {
-        print!("Delete {} permanently? [y/N] ", id.cyan());
+        if plan.removed.len() > 1 || !plan.updated.is_empty() || !plan.mentioned_by.is_empty() {
+            print_removal_effects(&plan);
+        }
+        let question = match plan.removed.len() {
+            1 => format!("Delete {} permanently?", id.cyan()),
+            n => format!(
+                "Delete {} and {} descendant(s) permanently?",
+                id.cyan(),
+                n - 1
+            ),
+        };
+        print!("{} [y/N] ", question);
         io::stdout().flush()?;
         let mut input = String::new();
         io::stdin().read_line(&mut input)?;
@@ -30,17 +93,18 @@ pub fn handle_delete(
         }
     }
 
-    // Record undo operation before delete
-    let file_path = ctx.repo.find_file_by_id(&id)?;
-    record_undo_delete(ctx, &id, &file_path);
-
-    // Delete the pea
-    ctx.repo.delete(&id)?;
+    // One undo step, including what was changed before a failure
+    let mut operations = Vec::new();
+    let result = plan.apply(&ctx.repo, &mut operations);
+    if let Some(step) = plan.undo_step(operations) {
+        record_undo_step(ctx, step);
+    }
+    result?;
 
     // Handle asset cleanup
     let mut assets_deleted = 0;
     if asset_count > 0 && !keep_assets {
-        if !force && !json {
+        let cleanup = if !force && !json {
             print!(
                 "Also delete {} asset(s)? [Y/n] ",
                 asset_count.to_string().yellow()
@@ -51,12 +115,17 @@ pub fn handle_delete(
             let input = input.trim();
 
             // Default to yes if user just presses enter
-            if input.is_empty() || input.eq_ignore_ascii_case("y") {
-                assets_deleted = ctx.asset_manager.cleanup_ticket_assets(&id)?;
-            }
-        } else if force {
+            input.is_empty() || input.eq_ignore_ascii_case("y")
+        } else {
             // In force mode, automatically delete assets
-            assets_deleted = ctx.asset_manager.cleanup_ticket_assets(&id)?;
+            force
+        };
+        if cleanup {
+            for id in &plan.removed {
+                if ctx.asset_manager.has_assets(id) {
+                    assets_deleted += ctx.asset_manager.cleanup_ticket_assets(id)?;
+                }
+            }
         }
     }
 
@@ -66,11 +135,31 @@ pub fn handle_delete(
             serde_json::to_string_pretty(&serde_json::json!({
                 "action": "deleted",
                 "id": id,
+                "also_deleted": &plan.removed[1..],
+                "updated": updated,
+                "mentioned_by": plan.mentioned_by,
                 "assets_deleted": assets_deleted
             }))?
         );
     } else {
         println!("{} {}", "Deleted".red(), id.cyan());
+        for other in &plan.removed[1..] {
+            println!("{} {}", "Deleted".red(), other.cyan());
+        }
+        if !plan.updated.is_empty() {
+            println!(
+                "  {} {} other pea(s)",
+                "Updated".green(),
+                plan.updated.len()
+            );
+        }
+        if force && !plan.mentioned_by.is_empty() {
+            println!(
+                "  {} still mentioned in the body of {}",
+                "Note:".yellow(),
+                plan.mentioned_by.join(", ").cyan()
+            );
+        }
         if assets_deleted > 0 {
             println!(
                 "  {} {} asset(s)",
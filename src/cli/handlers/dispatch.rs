@@ -0,0 +1,446 @@
+use anyhow::Result;
+
+use super::*;
+use crate::cli::Commands;
+use crate::cli::commands::MigrateAction;
+
+/// Run a command that does not need a loaded project (`init`, `migrate`, `doctor`, `merge-driver`)
+pub fn run_standalone(command: Commands) -> Result<()> {
+    match command {
+        Commands::Init { prefix, id_length } => handle_init(prefix, id_length),
+        Commands::Migrate {
+            action: None,
+            dry_run,
+        } => handle_migrate(dry_run),
+        Commands::Doctor { fix } => handle_doctor(fix),
+        Commands::MergeDriver {
+            base,
+            ours,
+            theirs,
+            path,
+        } => handle_merge_driver(&base, &ours, &theirs, path.as_deref()),
+        _ => anyhow::bail!("This command needs a peas project"),
+    }
+}
+
+/// Route a parsed subcommand to its handler
+pub fn dispatch(ctx: CommandContext, command: Commands) -> Result<()> {
+    match command {
+        Commands::Init { .. }
+        | Commands::Migrate { action: None, .. }
+        | Commands::Doctor { .. }
+        | Commands::MergeDriver { .. } => run_standalone(command),
+        Commands::Create {
+            title,
+            r#type,
+            status,
+            priority,
+            body,
+            body_file,
+            body_from_clipboard,
+            parent,
+            blocks,
+            blocked_by,
+            external_ref,
+            tag,
+            component,
+            assignee,
+            target_date,
+            defer_until,
+            template,
+            json,
+            dry_run,
+        } => handle_create(
+            &ctx,
+            title,
+            r#type,
+            status,
+            priority,
+            body,
+            body_file,
+            body_from_clipboard,
+            parent,
+            blocks,
+            blocked_by,
+            external_ref,
+            tag,
+            component,
+            assignee,
+            target_date,
+            defer_until,
+            template,
+            json,
+            dry_run,
+        ),
+        Commands::Show {
+            id,
+            render,
+            relative,
+            json,
+        } => handle_show(&ctx, id, render, relative, json),
+        Commands::Open { id, target, print } => {
+            handle_open(&ctx, id, target.map(Into::into), print)
+        }
+        Commands::List {
+            r#type,
+            status,
+            priority,
+            parent,
+            tag,
+            component,
+            assignee,
+            created_by,
+            updated_by,
+            unclaimed,
+            include_deferred,
+            archived,
+            as_of,
+            columns,
+            sort,
+            reverse,
+            group_by,
+            count,
+            summary,
+            relative,
+            fast,
+            json,
+        } => handle_list(
+            &ctx,
+            ListParams {
+                r#type,
+                status,
+                priority,
+                parent,
+                tag,
+                component,
+                assignee,
+                created_by,
+                updated_by,
+                unclaimed,
+                include_deferred,
+                archived,
+                as_of,
+                columns,
+                sort,
+                reverse,
+                group_by,
+                count,
+                summary,
+                relative,
+                fast,
+                json,
+            },
+        ),
+        Commands::EditList {
+            r#type,
+            status,
+            priority,
+            parent,
+            tag,
+            dry_run,
+            json,
+        } => handle_edit_list(
+            &ctx,
+            EditListParams {
+                r#type,
+                status,
+                priority,
+                parent,
+                tag,
+                dry_run,
+                json,
+            },
+        ),
+        Commands::Update {
+            id,
+            title,
+            r#type,
+            status,
+            priority,
+            body,
+            parent,
+            component,
+            assignee,
+            target_date,
+            defer_until,
+            add_tag,
+            remove_tag,
+            add_blocks,
+            remove_blocks,
+            add_blocked_by,
+            remove_blocked_by,
+            add_ref,
+            remove_ref,
+            reopen,
+            json,
+            dry_run,
+        } => handle_update(
+            &ctx,
+            id,
+            title,
+            r#type,
+            status,
+            priority,
+            body,
+            parent,
+            component,
+            assignee,
+            target_date,
+            defer_until,
+            add_tag,
+            remove_tag,
+            add_blocks,
+            remove_blocks,
+            add_blocked_by,
+            remove_blocked_by,
+            add_ref,
+            remove_ref,
+            reopen,
+            json,
+            dry_run,
+        ),
+        Commands::Patch {
+            id,
+            set,
+            unset,
+            json,
+            dry_run,
+        } => handle_patch(&ctx, id, set, unset, json, dry_run),
+        Commands::Apply { json, dry_run } => handle_apply(&ctx, json, dry_run),
+        Commands::Archive {
+            id,
+            status,
+            r#type,
+            priority,
+            tag,
+            older_than,
+            recursive,
+            reparent_to,
+            orphan,
+            keep_assets,
+            confirm,
+            dry_run,
+            json,
+        } => handle_archive(
+            &ctx,
+            ArchiveParams {
+                id,
+                status,
+                r#type,
+                priority,
+                tag,
+                older_than,
+                recursive,
+                reparent_to,
+                orphan,
+                keep_assets,
+                confirm,
+                dry_run,
+                json,
+            },
+        ),
+        Commands::Delete {
+            id,
+            force,
+            cascade,
+            reparent_to,
+            orphan,
+            keep_assets,
+            json,
+            dry_run,
+        } => handle_delete(
+            &ctx,
+            DeleteParams {
+                id,
+                force,
+                cascade,
+                reparent_to,
+                orphan,
+                keep_assets,
+                json,
+                dry_run,
+            },
+        ),
+        Commands::Search {
+            query,
+            fuzzy,
+            semantic,
+            json,
+        } => handle_search(&ctx, query, fuzzy, semantic, json),
+        Commands::Start {
+            id,
+            json,
+            dry_run,
+            cascade,
+        } => handle_start(&ctx, id, json, dry_run, cascade),
+        Commands::Done {
+            id,
+            json,
+            dry_run,
+            cascade,
+        } => handle_done(&ctx, id, json, dry_run, cascade),
+        Commands::Reopen { id, json, dry_run } => handle_reopen(&ctx, id, json, dry_run),
+        Commands::Note { id, text, json } => handle_note(&ctx, &id, text, json),
+        Commands::Claim {
+            id,
+            ttl,
+            force,
+            json,
+        } => handle_claim(&ctx, &id, &ttl, force, json),
+        Commands::Release { id, force, json } => handle_release(&ctx, &id, force, json),
+        Commands::Size {
+            id,
+            value,
+            clear,
+            json,
+        } => handle_size(&ctx, &id, value.as_deref(), clear, json),
+        Commands::Link {
+            id,
+            url,
+            label,
+            kind,
+            remove,
+            json,
+        } => handle_link(&ctx, &id, url, label, kind, remove, json),
+        Commands::PrStatus { id, complete, json } => {
+            handle_pr_status(&ctx, id.as_deref(), complete, json)
+        }
+        Commands::Focus { id, clear, json } => handle_focus(&ctx, id.as_deref(), clear, json),
+        Commands::Prime { budget, focus } => handle_prime(&ctx, budget, focus),
+        Commands::Context { budget, focus } => handle_context(&ctx, budget, focus),
+        Commands::Inbox {
+            mark_read,
+            drafts,
+            json,
+        } => handle_inbox(&ctx, mark_read, drafts, json),
+        Commands::Capture {
+            text,
+            from_file,
+            from_email,
+            r#type,
+            tag,
+            json,
+        } => handle_capture(&ctx, text, from_file, from_email, r#type, tag, json),
+        Commands::Review {
+            week: _,
+            month,
+            list,
+            json,
+        } => handle_review(&ctx, if month { 30 } else { 7 }, list, json),
+        Commands::Triage { random, list, json } => handle_triage(&ctx, random, list, json),
+        Commands::Suggest { json, count } => handle_suggest(&ctx, json, count),
+        Commands::Roadmap => handle_roadmap(&ctx),
+        Commands::Stats { json } => handle_stats(&ctx, json),
+        Commands::Graph {
+            format,
+            milestone,
+            tag,
+        } => handle_graph(&ctx, format, milestone.as_deref(), tag.as_deref()),
+        Commands::Report { action } => handle_report(&ctx, action),
+        Commands::Milestone { action } => handle_milestone(&ctx, action),
+        Commands::ReleaseNotes {
+            since,
+            heading,
+            json,
+        } => handle_release_notes(&ctx, &since, &heading, json),
+        Commands::Changelog { action } => handle_changelog(&ctx, action),
+        Commands::Blame { id, json } => handle_blame(&ctx, &id, json),
+        Commands::Snapshot { action } => handle_snapshot(&ctx, action),
+        Commands::CiSummary { base, head, json } => handle_ci_summary(&ctx, &base, &head, json),
+        Commands::Query { query, variables } => handle_query(ctx, query, variables),
+        Commands::Mutate {
+            mutation,
+            variables,
+        } => handle_mutate(ctx, mutation, variables),
+        Commands::Serve { port, viewer } => handle_serve(ctx, port, viewer),
+        Commands::Sync {
+            remote,
+            dry_run,
+            json,
+        } => handle_sync(&ctx, &remote, dry_run, json),
+        Commands::Tui {
+            select,
+            filter,
+            view,
+            relative,
+        } => handle_tui(ctx, select, filter, view.map(Into::into), relative),
+        Commands::ImportBeans { path, dry_run } => handle_import_beans(&ctx, path, dry_run),
+        Commands::ExportBeans { output } => handle_export_beans(&ctx, output),
+        Commands::Bulk { action } => handle_bulk(&ctx, action),
+        Commands::Memory { action } => handle_memory(&ctx, action),
+        Commands::Asset { action } => handle_asset(&ctx, action),
+        Commands::Tag { action } => handle_tag(&ctx, action),
+        Commands::Migrate {
+            action:
+                Some(MigrateAction::Prefix {
+                    from,
+                    to,
+                    dry_run,
+                    json,
+                }),
+            ..
+        } => handle_migrate_prefix(&ctx, &from, &to, dry_run, json),
+        Commands::Retag {
+            apply_rules: _,
+            dry_run,
+            json,
+        } => handle_retag(&ctx, dry_run, json),
+        Commands::FindDuplicates {
+            threshold,
+            archived,
+            json,
+        } => handle_find_duplicates(&ctx, threshold, archived, json),
+        Commands::Related { id, count, json } => handle_related(&ctx, id, count, json),
+        Commands::Lint { fail_on, json } => handle_lint(&ctx, fail_on.into(), json),
+        Commands::Rules { action } => handle_rules(&ctx, action),
+        Commands::Undo { show, json } => handle_undo(&ctx, show, json),
+        Commands::Git { action } => handle_git(&ctx, action),
+        Commands::Mv {
+            old_id,
+            new_id,
+            force,
+        } => handle_mv(&ctx, old_id, new_id, force),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cli::Cli, config::PeasConfig, model::PeaStatus};
+    use clap::Parser;
+    use tempfile::TempDir;
+
+    fn run(root: &std::path::Path, args: &[&str]) -> Result<()> {
+        let cli = Cli::try_parse_from(std::iter::once("peas").chain(args.iter().copied()))?;
+        let command = cli.command.expect("subcommand");
+        assert!(command.needs_project());
+        dispatch(
+            CommandContext::new(PeasConfig::default(), root.to_path_buf()),
+            command,
+        )
+    }
+
+    #[test]
+    fn test_dispatch_routes_to_handlers() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+
+        run(root, &["create", "Routed", "-t", "bug", "--json"]).unwrap();
+        // Fresh repository each time so nothing is served from a stale cache
+        let repo = || crate::storage::PeaRepository::new(&PeasConfig::default(), root);
+        let id = repo().list().unwrap()[0].id.clone();
+
+        run(root, &["start", &id]).unwrap();
+        assert_eq!(repo().get(&id).unwrap().status, PeaStatus::InProgress);
+
+        run(root, &["delete", &id, "--force"]).unwrap();
+        assert!(repo().list().unwrap().is_empty());
+        assert!(run(root, &["show", &id]).is_err());
+    }
+
+    #[test]
+    fn test_standalone_commands_need_no_project() {
+        let cli = Cli::try_parse_from(["peas", "init"]).unwrap();
+        assert!(!cli.command.unwrap().needs_project());
+    }
+}
@@ -1,10 +1,16 @@
+use crate::aliases::Aliases;
 use crate::config::{DATA_DIR, PeasConfig, SCHEMA_URL};
 use crate::global_config::GlobalPeasConfig;
+use crate::integrity::{IdShape, broken_references, fix_references};
+use crate::local::LocalState;
+use crate::model::Pea;
+use crate::storage::{FrontmatterFormat, detect_format, render_markdown_with_format};
+use crate::transaction::{self, JOURNAL_FILE};
 use crate::updater::{UpdateCheckOutcome, spawn_update_check};
 use anyhow::Result;
 use colored::Colorize;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Legacy config file names
 const LEGACY_CONFIG_FILES: &[&str] = &[".peas.toml", ".peas.yml", ".peas.yaml", ".peas.json"];
@@ -62,7 +68,7 @@ pub fn handle_doctor(fix: bool) -> Result<()> {
     check_ticket_format(&cwd, &mut results, fix)?;
 
     // Check 5: Ticket integrity
-    check_ticket_integrity(&cwd, &mut results)?;
+    check_ticket_integrity(&cwd, &mut results, fix)?;
 
     // Check 6: Mixed ID styles
     check_mixed_id_styles(&cwd, &mut results)?;
@@ -70,7 +76,10 @@ pub fn handle_doctor(fix: bool) -> Result<()> {
     // Check 7: Sequential ID counter (if applicable)
     check_sequential_counter(&cwd, &mut results, fix)?;
 
-    // Check 8: Update check
+    // Check 8: Interrupted transaction
+    check_interrupted_transaction(&cwd, &mut results, fix)?;
+
+    // Check 9: Update check
     check_update(&global_config, update_handle, &mut results);
 
     // Summary
@@ -503,7 +512,7 @@ fn fix_malformed_array(frontmatter: &str, field: &str) -> Option<String> {
     Some(frontmatter.replace(&old_value, &new_value))
 }
 
-fn check_ticket_integrity(cwd: &Path, results: &mut DiagnosticResults) -> Result<()> {
+fn check_ticket_integrity(cwd: &Path, results: &mut DiagnosticResults, fix: bool) -> Result<()> {
     println!("{}", "Ticket Integrity".bold());
 
     let data_dir = cwd.join(DATA_DIR);
@@ -513,10 +522,9 @@ fn check_ticket_integrity(cwd: &Path, results: &mut DiagnosticResults) -> Result
         return Ok(());
     }
 
-    // Collect all ticket IDs
+    // Collect all tickets with their files
     let mut ticket_ids: HashSet<String> = HashSet::new();
-    let mut tickets_with_parents: Vec<(String, String)> = Vec::new();
-    let mut tickets_with_blocking: Vec<(String, Vec<String>)> = Vec::new();
+    let mut tickets: Vec<(PathBuf, String, Pea)> = Vec::new();
     let mut parse_errors = 0;
     let mut total_tickets = 0;
 
@@ -534,16 +542,7 @@ fn check_ticket_integrity(cwd: &Path, results: &mut DiagnosticResults) -> Result
                     if !ticket_ids.insert(pea.id.clone()) {
                         results.error(&format!("Duplicate ID: {}", pea.id));
                     }
-
-                    // Collect parent references
-                    if let Some(ref parent) = pea.parent {
-                        tickets_with_parents.push((pea.id.clone(), parent.clone()));
-                    }
-
-                    // Collect blocking references
-                    if !pea.blocking.is_empty() {
-                        tickets_with_blocking.push((pea.id.clone(), pea.blocking.clone()));
-                    }
+                    tickets.push((path, content, pea));
                 }
                 Err(_) => {
                     parse_errors += 1;
@@ -564,42 +563,83 @@ fn check_ticket_integrity(cwd: &Path, results: &mut DiagnosticResults) -> Result
         results.error(&format!("{} tickets failed to parse", parse_errors));
     }
 
-    // Check parent references
-    let mut orphaned_parents = 0;
-    for (id, parent) in &tickets_with_parents {
-        if !ticket_ids.contains(parent) {
-            if orphaned_parents == 0 {
-                results.warn("Orphaned parent references found:");
-            }
-            orphaned_parents += 1;
-            println!("      - {} references missing parent {}", id, parent);
-        }
-    }
-    if orphaned_parents == 0 && !tickets_with_parents.is_empty() {
-        results.pass("All parent references valid");
+    // Mentions of archived tickets are fine, and the config tells what an ID looks like
+    let archived = archived_ids(&data_dir.join("archive"))?;
+    let aliases = Aliases::load(&data_dir).unwrap_or_default();
+    let shape = std::fs::read_to_string(data_dir.join("config.toml"))
+        .ok()
+        .and_then(|content| toml::from_str::<PeasConfig>(&content).ok())
+        .map(|config| IdShape {
+            prefix: config.peas.prefix,
+            length: config.peas.id_length,
+        });
+
+    let peas: Vec<Pea> = tickets.iter().map(|(_, _, pea)| pea.clone()).collect();
+    let broken = broken_references(&peas, &archived, &aliases, shape.as_ref());
+    if broken.is_empty() {
+        results.pass("All parent, blocking and body references valid");
+        println!();
+        return Ok(());
     }
 
-    // Check blocking references
-    let mut orphaned_blocking = 0;
-    for (id, blocking) in &tickets_with_blocking {
-        for blocked_id in blocking {
-            if !ticket_ids.contains(blocked_id) {
-                if orphaned_blocking == 0 {
-                    results.warn("Orphaned blocking references found:");
-                }
-                orphaned_blocking += 1;
-                println!("      - {} blocks missing ticket {}", id, blocked_id);
+    let fixable = broken.iter().filter(|b| b.is_fixable()).count();
+    if fix && fixable > 0 {
+        for (path, content, pea) in &tickets {
+            if let Some(fixed) = fix_references(pea, &broken) {
+                let format = detect_format(content).unwrap_or(FrontmatterFormat::Toml);
+                std::fs::write(path, render_markdown_with_format(&fixed, format)?)?;
+                println!("      {} Fixed references in {}", "✓".green(), pea.id);
             }
         }
     }
-    if orphaned_blocking == 0 && !tickets_with_blocking.is_empty() {
-        results.pass("All blocking references valid");
+
+    let remaining: Vec<_> = broken.iter().filter(|b| !fix || !b.is_fixable()).collect();
+    if remaining.is_empty() {
+        results.pass(&format!("Fixed {} broken references", fixable));
+    } else {
+        results.warn("Broken references found:");
+        for reference in &remaining {
+            let suffix = if reference.is_fixable() {
+                " [fixable]"
+            } else {
+                ""
+            };
+            println!("      - {}{}", reference, suffix);
+        }
+        if !fix && fixable > 0 {
+            results.suggestion(
+                "Run `peas doctor --fix` to follow renames and drop blocking references to missing tickets",
+            );
+        }
+        if remaining.iter().any(|b| !b.is_fixable()) {
+            results.suggestion(
+                "Set another parent with `peas update <id> --parent` and edit mentions by hand",
+            );
+        }
     }
 
     println!();
     Ok(())
 }
 
+/// IDs of the archived tickets
+fn archived_ids(archive_dir: &Path) -> Result<HashSet<String>> {
+    let mut ids = HashSet::new();
+    if !archive_dir.exists() {
+        return Ok(ids);
+    }
+    for entry in std::fs::read_dir(archive_dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|e| e == "md") {
+            let content = std::fs::read_to_string(&path)?;
+            if let Ok(pea) = crate::storage::parse_markdown(&content) {
+                ids.insert(pea.id);
+            }
+        }
+    }
+    Ok(ids)
+}
+
 fn check_mixed_id_styles(cwd: &Path, results: &mut DiagnosticResults) -> Result<()> {
     let data_dir = cwd.join(DATA_DIR);
     if !data_dir.exists() {
@@ -654,6 +694,30 @@ fn check_mixed_id_styles(cwd: &Path, results: &mut DiagnosticResults) -> Result<
     Ok(())
 }
 
+fn check_interrupted_transaction(
+    cwd: &Path,
+    results: &mut DiagnosticResults,
+    fix: bool,
+) -> Result<()> {
+    let data_dir = cwd.join(DATA_DIR);
+    if !LocalState::new(&data_dir).path(JOURNAL_FILE).exists() {
+        return Ok(());
+    }
+
+    println!("{}", "Transactions".bold());
+    results.error("A transaction was interrupted and may have left peas half-written");
+    if fix {
+        let reverted = transaction::recover(&data_dir)?.unwrap_or(0);
+        println!("      {} Rolled back {} write(s)", "✓".green(), reverted);
+    } else {
+        results.suggestion(
+            "Run `peas doctor --fix` to roll it back, unless another peas command is still running",
+        );
+    }
+    println!();
+    Ok(())
+}
+
 fn check_sequential_counter(cwd: &Path, results: &mut DiagnosticResults, fix: bool) -> Result<()> {
     let data_dir = cwd.join(DATA_DIR);
     let counter_path = data_dir.join(".id");
@@ -0,0 +1,265 @@
+use crate::cli::commands::{PeaPriorityArg, PeaStatusArg, PeaTypeArg};
+use crate::error::PeasError;
+use crate::local::LocalState;
+use crate::model::{Pea, PeaPriority, PeaStatus};
+use crate::undo::UndoOperation;
+use anyhow::Result;
+use colored::Colorize;
+use std::str::FromStr;
+
+use super::CommandContext;
+use super::utils::run_editor;
+
+/// Buffer file in `.peas/local/` while the editor is open
+const BUFFER_FILE: &str = "edit-list.txt";
+
+const HEADER: &str = "\
+# Edit the status, priority or title of any line, then save and quit.
+# Removing a line leaves that pea unchanged; an empty buffer changes nothing.
+# Lines starting with '#' are ignored.
+#
+# <id> <status> <priority> <title>
+";
+
+/// Filters selecting the peas to edit, as for `peas list`
+pub struct EditListParams {
+    pub r#type: Option<PeaTypeArg>,
+    pub status: Option<PeaStatusArg>,
+    pub priority: Option<PeaPriorityArg>,
+    pub parent: Option<String>,
+    pub tag: Option<String>,
+    pub dry_run: bool,
+    pub json: bool,
+}
+
+/// One line of the buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    id: String,
+    status: PeaStatus,
+    priority: PeaPriority,
+    title: String,
+}
+
+impl From<&Pea> for Row {
+    fn from(pea: &Pea) -> Self {
+        Self {
+            id: pea.id.clone(),
+            status: pea.status,
+            priority: pea.priority,
+            title: pea.title.clone(),
+        }
+    }
+}
+
+pub fn handle_edit_list(ctx: &CommandContext, params: EditListParams) -> Result<()> {
+    let mut peas = ctx.repo.list()?;
+    if let Some(t) = params.r#type {
+        let filter_type = t.into();
+        peas.retain(|p| p.pea_type == filter_type);
+    }
+    if let Some(s) = params.status {
+        let filter_status: PeaStatus = s.into();
+        peas.retain(|p| p.status == filter_status);
+    }
+    if let Some(p) = params.priority {
+        let filter_priority = p.into();
+        peas.retain(|p| p.priority == filter_priority);
+    }
+    if let Some(ref parent_id) = params.parent {
+        peas.retain(|p| p.parent.as_deref() == Some(parent_id.as_str()));
+    }
+    if let Some(ref t) = params.tag {
+        peas.retain(|p| p.tags.contains(t));
+    }
+    if peas.is_empty() {
+        if params.json {
+            println!(
+                "{}",
+                serde_json::json!({ "updated": [], "dry_run": params.dry_run })
+            );
+        } else {
+            println!("{}", "No peas match".dimmed());
+        }
+        return Ok(());
+    }
+
+    let local = LocalState::new(&ctx.config.data_path(&ctx.root));
+    local.write(BUFFER_FILE, &render(&peas))?;
+    let edited = run_editor(&local.path(BUFFER_FILE)).and_then(|()| {
+        local
+            .read(BUFFER_FILE)
+            .ok_or_else(|| anyhow::anyhow!("The edit buffer disappeared"))
+    });
+    local.remove(BUFFER_FILE)?;
+    let rows = parse(&edited?)?;
+
+    // Validate everything before writing anything
+    let mut updates = Vec::new();
+    for row in rows {
+        let Some(pea) = peas.iter().find(|p| p.id == row.id) else {
+            return Err(PeasError::validation(
+                "id",
+                format!("{} was not in the list being edited", row.id),
+            )
+            .into());
+        };
+        if Row::from(pea) != row {
+            updates.push((pea, row));
+        }
+    }
+
+    let mut updated = Vec::new();
+    let mut operations = Vec::new();
+    let apply = || -> Result<()> {
+        for (pea, row) in updates {
+            let changes = describe(pea, &row);
+            let mut pea = pea.clone();
+            pea.status = row.status;
+            pea.priority = row.priority;
+            pea.title = row.title;
+            if !params.dry_run {
+                let path = ctx.repo.find_file_by_id(&pea.id)?;
+                operations.push(UndoOperation::update_from_file(&pea.id, &path)?);
+                ctx.repo.update(&mut pea)?;
+            }
+            if !params.json {
+                let verb = if params.dry_run {
+                    "Would update"
+                } else {
+                    "Updated"
+                };
+                println!("{} {} {}", verb.green(), pea.id.cyan(), changes.join(", "));
+            }
+            updated.push(pea);
+        }
+        Ok(())
+    };
+    let result = apply();
+    // Record whatever was applied, even if a later pea failed
+    let description = format!("Edit list on {} peas", operations.len());
+    crate::undo::record_batch(&ctx.repo.undo_manager(), &description, operations)?;
+    result?;
+
+    if params.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "updated": updated,
+                "dry_run": params.dry_run,
+            }))?
+        );
+    } else if updated.is_empty() {
+        println!("{}", "No changes".dimmed());
+    }
+    Ok(())
+}
+
+/// The buffer for `peas`, with aligned columns
+fn render(peas: &[Pea]) -> String {
+    let id_width = peas.iter().map(|p| p.id.len()).max().unwrap_or(0);
+    let mut buffer = HEADER.to_string();
+    buffer.push('\n');
+    for pea in peas {
+        buffer.push_str(&format!(
+            "{:<id_width$}  {:<11}  {:<8}  {}\n",
+            pea.id,
+            pea.status.to_string(),
+            pea.priority.to_string(),
+            pea.title
+        ));
+    }
+    buffer
+}
+
+/// Rows of an edited buffer, failing on the first line that does not parse
+fn parse(buffer: &str) -> crate::error::Result<Vec<Row>> {
+    let mut rows: Vec<Row> = Vec::new();
+    for (index, line) in buffer.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = |message: String| {
+            PeasError::validation("buffer", format!("Line {}: {}", index + 1, message))
+        };
+        let mut rest = line;
+        let mut next = || {
+            let field = rest.trim_start();
+            let (field, after) =
+                field.split_at(field.find(char::is_whitespace).unwrap_or(field.len()));
+            rest = after;
+            field
+        };
+        let (id, status, priority) = (next(), next(), next());
+        let title = rest.trim();
+        if title.is_empty() {
+            return Err(invalid(
+                "expected <id> <status> <priority> <title>".to_string(),
+            ));
+        }
+        let row = Row {
+            id: id.to_string(),
+            status: PeaStatus::from_str(status).map_err(|e| invalid(e.to_string()))?,
+            priority: PeaPriority::from_str(priority).map_err(|e| invalid(e.to_string()))?,
+            title: title.to_string(),
+        };
+        if rows.iter().any(|r| r.id == row.id) {
+            return Err(invalid(format!("{} appears more than once", row.id)));
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Field changes from `pea` to `row`, like `status todo -> in-progress`
+fn describe(pea: &Pea, row: &Row) -> Vec<String> {
+    let mut changes = Vec::new();
+    if pea.status != row.status {
+        changes.push(format!("status {} -> {}", pea.status, row.status));
+    }
+    if pea.priority != row.priority {
+        changes.push(format!("priority {} -> {}", pea.priority, row.priority));
+    }
+    if pea.title != row.title {
+        changes.push(format!("title \"{}\" -> \"{}\"", pea.title, row.title));
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    #[test]
+    fn test_render_and_parse_buffer() {
+        let peas = vec![
+            Pea::new("peas-abc12".into(), "Login form".into(), PeaType::Task),
+            Pea::new("peas-x1".into(), "Fix  spacing".into(), PeaType::Bug)
+                .with_status(PeaStatus::InProgress),
+        ];
+        let buffer = render(&peas);
+        assert!(buffer.contains("peas-x1     in-progress  normal    Fix  spacing\n"));
+        let rows = parse(&buffer).unwrap();
+        assert_eq!(rows, peas.iter().map(Row::from).collect::<Vec<_>>());
+
+        // Columns may be realigned freely, titles keep their inner spacing
+        let rows = parse("peas-x1 done   p1 Fix  spacing now\n").unwrap();
+        assert_eq!(rows[0].status, PeaStatus::Completed);
+        assert_eq!(rows[0].priority, PeaPriority::High);
+        assert_eq!(rows[0].title, "Fix  spacing now");
+        assert_eq!(
+            describe(&peas[1], &rows[0]),
+            vec![
+                "status in-progress -> completed",
+                "priority normal -> high",
+                "title \"Fix  spacing\" -> \"Fix  spacing now\"",
+            ]
+        );
+
+        assert!(parse("peas-x1 todo normal").is_err());
+        assert!(parse("# comment\npeas-x1 started normal Title").is_err());
+        assert!(parse("peas-x1 todo normal A\npeas-x1 todo high B").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use crate::model::Pea;
+use crate::model::text::{dice, title_words};
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::{BTreeMap, HashSet};
+
+use super::CommandContext;
+use super::utils::print_pea_list;
+
+/// Weight of title similarity when both peas have tags; tag overlap gets the rest
+const TITLE_WEIGHT: f64 = 0.75;
+
+pub fn handle_find_duplicates(
+    ctx: &CommandContext,
+    threshold: f64,
+    archived: bool,
+    json: bool,
+) -> Result<()> {
+    if !(0.0..=1.0).contains(&threshold) {
+        anyhow::bail!("Threshold must be between 0.0 and 1.0");
+    }
+
+    let mut peas = ctx.repo.list()?;
+    if archived {
+        peas.extend(ctx.repo.list_archived()?);
+    }
+
+    let clusters = find_clusters(&peas, threshold);
+
+    if json {
+        let output: Vec<_> = clusters
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "similarity": round2(c.similarity),
+                    "peas": c.members.iter().map(|&i| &peas[i]).collect::<Vec<_>>(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if clusters.is_empty() {
+        println!("No likely duplicates found.");
+        return Ok(());
+    }
+
+    println!(
+        "Found {} cluster(s) of likely duplicates:",
+        clusters.len().to_string().yellow()
+    );
+    for (n, cluster) in clusters.iter().enumerate() {
+        println!();
+        println!(
+            "{} {}",
+            format!("Cluster {}", n + 1).bold(),
+            format!("(similarity {:.2})", cluster.similarity).dimmed()
+        );
+        let members: Vec<Pea> = cluster.members.iter().map(|&i| peas[i].clone()).collect();
+        print_pea_list(&members);
+    }
+    Ok(())
+}
+
+/// A group of peas that are transitively similar to each other
+#[derive(Debug)]
+struct Cluster {
+    /// Indices into the scanned peas, oldest first
+    members: Vec<usize>,
+    /// Highest pairwise similarity within the cluster
+    similarity: f64,
+}
+
+/// Group peas whose pairwise similarity reaches `threshold`
+fn find_clusters(peas: &[Pea], threshold: f64) -> Vec<Cluster> {
+    let titles: Vec<HashSet<String>> = peas.iter().map(|p| bigrams(&p.title)).collect();
+
+    // Union-find over all similar pairs
+    let mut parent: Vec<usize> = (0..peas.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    let mut best: BTreeMap<usize, f64> = BTreeMap::new();
+    for i in 0..peas.len() {
+        for j in (i + 1)..peas.len() {
+            let score = similarity(&titles[i], &titles[j], &peas[i].tags, &peas[j].tags);
+            if score >= threshold {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                let merged = best
+                    .remove(&a)
+                    .unwrap_or(0.0)
+                    .max(best.remove(&b).unwrap_or(0.0))
+                    .max(score);
+                parent[b] = a;
+                best.insert(a, merged);
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..peas.len() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(i);
+    }
+
+    let mut clusters: Vec<Cluster> = groups
+        .into_iter()
+        .filter(|(_, members)| members.len() > 1)
+        .map(|(r, mut members)| {
+            members.sort_by_key(|&i| peas[i].created);
+            Cluster {
+                members,
+                similarity: best.get(&r).copied().unwrap_or(0.0),
+            }
+        })
+        .collect();
+    clusters.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    clusters
+}
+
+/// Combined title and tag similarity in `0.0..=1.0`
+fn similarity(
+    title_a: &HashSet<String>,
+    title_b: &HashSet<String>,
+    tags_a: &[String],
+    tags_b: &[String],
+) -> f64 {
+    let title = dice(title_a, title_b);
+    if tags_a.is_empty() || tags_b.is_empty() {
+        return title;
+    }
+    let tags_a: HashSet<String> = tags_a.iter().map(|t| t.to_lowercase()).collect();
+    let tags_b: HashSet<String> = tags_b.iter().map(|t| t.to_lowercase()).collect();
+    let union = tags_a.union(&tags_b).count();
+    let tags = tags_a.intersection(&tags_b).count() as f64 / union as f64;
+    TITLE_WEIGHT * title + (1.0 - TITLE_WEIGHT) * tags
+}
+
+/// Character bigrams of the normalized title, per word
+///
+/// Bigrams tolerate typos and word-form differences ("login" vs "logins")
+/// better than whole-word comparison.
+fn bigrams(title: &str) -> HashSet<String> {
+    title_words(title)
+        .into_iter()
+        .flat_map(|word| {
+            let chars: Vec<char> = word.chars().collect();
+            if chars.len() < 2 {
+                return vec![word];
+            }
+            chars.windows(2).map(|w| w.iter().collect()).collect()
+        })
+        .collect()
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn pea(id: &str, title: &str, tags: &[&str]) -> Pea {
+        Pea::new(id.to_string(), title.to_string(), PeaType::Task)
+            .with_tags(tags.iter().map(|t| t.to_string()).collect())
+    }
+
+    #[test]
+    fn test_title_similarity_ignores_case_punctuation_and_stopwords() {
+        let a = bigrams("Fix the login bug");
+        let b = bigrams("fix login-bug");
+        assert_eq!(dice(&a, &b), 1.0);
+        assert!(dice(&a, &bigrams("Write release notes")) < 0.3);
+    }
+
+    #[test]
+    fn test_clusters_are_transitive_and_sorted() {
+        let peas = vec![
+            pea("peas-1", "Login page crashes", &[]),
+            pea("peas-2", "Write docs", &[]),
+            pea("peas-3", "Login page crashes on submit", &[]),
+            pea("peas-4", "login page crash", &[]),
+            pea("peas-5", "Write the docs", &[]),
+        ];
+        let clusters = find_clusters(&peas, 0.7);
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].similarity, 1.0);
+        assert_eq!(clusters[0].members, vec![1, 4]);
+        assert_eq!(clusters[1].members, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_tag_overlap_shifts_score() {
+        let a = bigrams("Cache invalidation");
+        let b = bigrams("Cache invalidation bug");
+        let shared = similarity(&a, &b, &["perf".into()], &["perf".into()]);
+        let disjoint = similarity(&a, &b, &["perf".into()], &["ui".into()]);
+        assert!(shared > dice(&a, &b));
+        assert!(disjoint < dice(&a, &b));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::focus::Focus;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::print_pea;
+
+pub fn handle_focus(ctx: &CommandContext, id: Option<&str>, clear: bool, json: bool) -> Result<()> {
+    let focus = Focus::new(&ctx.config.data_path(&ctx.root));
+
+    if clear {
+        let previous = focus.clear()?;
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "focus": null,
+                    "cleared": previous,
+                }))?
+            );
+        } else {
+            match previous {
+                Some(id) => println!("{} focus on {}", "Cleared".green(), id.cyan()),
+                None => println!("No focus set."),
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(id) = id {
+        let pea = ctx.repo.get(id)?;
+        focus.set(&pea.id)?;
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({ "focus": pea }))?
+            );
+        } else {
+            println!("{} {} {}", "Focused on".green(), pea.id.cyan(), pea.title);
+            if !pea.is_open() {
+                println!(
+                    "{}: {} is already {}",
+                    "warning".yellow().bold(),
+                    pea.id,
+                    pea.status
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let Some(id) = focus.get() else {
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({ "focus": null }))?
+            );
+        } else {
+            println!("No focus set. Use 'peas focus <id>' to set one.");
+        }
+        return Ok(());
+    };
+
+    match ctx.repo.get(&id) {
+        Ok(pea) => {
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({ "focus": pea }))?
+                );
+            } else {
+                print_pea(&pea);
+                if !pea.is_open() {
+                    println!();
+                    println!(
+                        "{}: focused pea is {}, run 'peas focus --clear' or pick another",
+                        "Note".yellow().bold(),
+                        pea.status
+                    );
+                }
+            }
+        }
+        Err(_) => {
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "focus": null,
+                        "missing": id,
+                    }))?
+                );
+            } else {
+                println!(
+                    "{}: focused pea {} no longer exists, run 'peas focus --clear'",
+                    "warning".yellow().bold(),
+                    id
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::cli::commands::GitAction;
+use crate::config::DATA_DIR;
+use crate::git;
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use super::CommandContext;
+
+/// Line in .gitattributes that routes pea files through the merge driver
+const ATTRIBUTES_LINE: &str = "merge=peas";
+
+pub fn handle_git(ctx: &CommandContext, action: GitAction) -> Result<()> {
+    match action {
+        GitAction::Setup => handle_git_setup(ctx),
+    }
+}
+
+fn handle_git_setup(ctx: &CommandContext) -> Result<()> {
+    // Fails with a git error outside a repository
+    git::run(&ctx.root, &["rev-parse", "--show-toplevel"])?;
+
+    git::run(
+        &ctx.root,
+        &["config", "merge.peas.name", "peas field-aware merge driver"],
+    )?;
+    git::run(
+        &ctx.root,
+        &[
+            "config",
+            "merge.peas.driver",
+            "peas merge-driver %O %A %B %P",
+        ],
+    )?;
+    println!(
+        "{} merge driver 'peas' in .git/config",
+        "Registered".green()
+    );
+
+    let path = ctx.root.join(".gitattributes");
+    let existing = if path.exists() {
+        std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?
+    } else {
+        String::new()
+    };
+    let pattern = format!("{}/**/*.md", DATA_DIR);
+    let registered = existing.lines().any(|line| {
+        let mut parts = line.split_whitespace();
+        parts.next() == Some(pattern.as_str()) && parts.any(|attr| attr == ATTRIBUTES_LINE)
+    });
+    if registered {
+        println!(".gitattributes already routes {} through it", pattern);
+    } else {
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&format!("{} {}\n", pattern, ATTRIBUTES_LINE));
+        std::fs::write(&path, content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        println!("{} {} to .gitattributes", "Added".green(), pattern);
+    }
+    println!(
+        "{}: commit .gitattributes; everyone who clones needs to run 'peas git setup' once",
+        "note".yellow()
+    );
+    Ok(())
+}
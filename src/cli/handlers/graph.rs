@@ -0,0 +1,245 @@
+use crate::cli::commands::GraphFormatArg;
+use crate::model::{Pea, PeaType, References, descendants};
+use anyhow::Result;
+use std::collections::HashSet;
+
+use super::CommandContext;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeKind {
+    /// Parent to child
+    Parent,
+    Blocks,
+    /// Mentioned in the body
+    Refs,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Edge<'a> {
+    from: &'a str,
+    to: &'a str,
+    kind: EdgeKind,
+}
+
+pub fn handle_graph(
+    ctx: &CommandContext,
+    format: GraphFormatArg,
+    milestone: Option<&str>,
+    tag: Option<&str>,
+) -> Result<()> {
+    let peas = ctx.repo.list()?;
+
+    let mut nodes: Vec<&Pea> = match milestone {
+        Some(id) => {
+            let milestone = ctx.repo.get(id)?;
+            if milestone.pea_type != PeaType::Milestone {
+                anyhow::bail!(
+                    "{} is a {}, not a milestone",
+                    milestone.id,
+                    milestone.pea_type
+                );
+            }
+            let mut nodes = descendants(&peas, &milestone.id);
+            if let Some(root) = peas.iter().find(|p| p.id == milestone.id) {
+                nodes.insert(0, root);
+            }
+            nodes
+        }
+        None => peas.iter().collect(),
+    };
+    if let Some(tag) = tag {
+        nodes.retain(|p| p.tags.iter().any(|t| t == tag));
+    }
+
+    let edges = collect_edges(&nodes);
+    let output = match format {
+        GraphFormatArg::Dot => render_dot(&nodes, &edges),
+        GraphFormatArg::Mermaid => render_mermaid(&nodes, &edges),
+    };
+    print!("{}", output);
+    Ok(())
+}
+
+/// Edges between the given peas; a body reference is skipped when a parent
+/// or blocking edge already connects the pair
+fn collect_edges<'a>(nodes: &[&'a Pea]) -> Vec<Edge<'a>> {
+    let included: HashSet<&str> = nodes.iter().map(|p| p.id.as_str()).collect();
+
+    let mut edges = Vec::new();
+    for pea in nodes {
+        if let Some(parent) = pea.parent.as_deref()
+            && let Some(&parent) = included.get(parent)
+        {
+            edges.push(Edge {
+                from: parent,
+                to: &pea.id,
+                kind: EdgeKind::Parent,
+            });
+        }
+        for blocked in &pea.blocking {
+            if let Some(&blocked) = included.get(blocked.as_str()) {
+                edges.push(Edge {
+                    from: &pea.id,
+                    to: blocked,
+                    kind: EdgeKind::Blocks,
+                });
+            }
+        }
+    }
+
+    let linked: HashSet<(&str, &str)> = edges
+        .iter()
+        .flat_map(|e| [(e.from, e.to), (e.to, e.from)])
+        .collect();
+    let references = References::build(nodes.iter().copied());
+    let mut refs = Vec::new();
+    for pea in nodes {
+        for target in references.mentions(&pea.id) {
+            let Some(&target) = included.get(target.as_str()) else {
+                continue;
+            };
+            if !linked.contains(&(pea.id.as_str(), target)) {
+                refs.push(Edge {
+                    from: &pea.id,
+                    to: target,
+                    kind: EdgeKind::Refs,
+                });
+            }
+        }
+    }
+    edges.extend(refs);
+    edges
+}
+
+fn render_dot(nodes: &[&Pea], edges: &[Edge]) -> String {
+    let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut out =
+        String::from("digraph peas {\n    rankdir=LR;\n    node [shape=box, style=rounded];\n\n");
+    for pea in nodes {
+        let style = if pea.is_open() {
+            ""
+        } else {
+            ", color=gray, fontcolor=gray"
+        };
+        out.push_str(&format!(
+            "    \"{}\" [label=\"{}\\n[{}] {}\"{}];\n",
+            escape(&pea.id),
+            escape(&pea.id),
+            pea.pea_type,
+            escape(&pea.title),
+            style
+        ));
+    }
+    if !edges.is_empty() {
+        out.push('\n');
+    }
+    for edge in edges {
+        let attrs = match edge.kind {
+            EdgeKind::Parent => "",
+            EdgeKind::Blocks => " [label=\"blocks\", color=red]",
+            EdgeKind::Refs => " [label=\"refs\", style=dashed]",
+        };
+        out.push_str(&format!(
+            "    \"{}\" -> \"{}\"{};\n",
+            escape(edge.from),
+            escape(edge.to),
+            attrs
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_mermaid(nodes: &[&Pea], edges: &[Edge]) -> String {
+    // Mermaid node IDs are safest as plain identifiers
+    let node_id = |id: &str| id.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    // Quotes would end the label; mermaid understands HTML entities
+    let escape = |s: &str| s.replace('"', "#quot;");
+
+    let mut out = String::from("graph LR\n");
+    for pea in nodes {
+        out.push_str(&format!(
+            "    {}[\"{}<br/>[{}] {}\"]\n",
+            node_id(&pea.id),
+            pea.id,
+            pea.pea_type,
+            escape(&pea.title)
+        ));
+    }
+    for edge in edges {
+        let arrow = match edge.kind {
+            EdgeKind::Parent => "-->",
+            EdgeKind::Blocks => "-->|blocks|",
+            EdgeKind::Refs => "-.->|refs|",
+        };
+        out.push_str(&format!(
+            "    {} {} {}\n",
+            node_id(edge.from),
+            arrow,
+            node_id(edge.to)
+        ));
+    }
+    let closed: Vec<_> = nodes
+        .iter()
+        .filter(|p| !p.is_open())
+        .map(|p| node_id(&p.id))
+        .collect();
+    if !closed.is_empty() {
+        out.push_str("    classDef closed fill:#eee,stroke:#999,color:#999\n");
+        out.push_str(&format!("    class {} closed\n", closed.join(",")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pea(id: &str, title: &str) -> Pea {
+        Pea::new(id.to_string(), title.to_string(), PeaType::Task)
+    }
+
+    #[test]
+    fn test_edges_and_rendering() {
+        let epic = pea("peas-epic1", "Auth \"v2\"");
+        let login = pea("peas-login", "Login").with_parent(Some("peas-epic1".to_string()));
+        let mut logout = pea("peas-logut", "Logout").with_parent(Some("peas-epic1".to_string()));
+        logout.blocking = vec!["peas-login".to_string()];
+        // Already linked by blocking, mentioned twice, and a reference outside the graph
+        logout.body = "See peas-login and peas-epic1, peas-epic1 again, not peas-gone1".into();
+        let nodes = vec![&epic, &login, &logout];
+
+        let edges = collect_edges(&nodes);
+        let summary: Vec<_> = edges.iter().map(|e| (e.from, e.to, e.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("peas-epic1", "peas-login", EdgeKind::Parent),
+                ("peas-epic1", "peas-logut", EdgeKind::Parent),
+                ("peas-logut", "peas-login", EdgeKind::Blocks),
+            ]
+        );
+
+        let mut ref_only = pea("peas-notes", "Notes");
+        ref_only.body = "Follow-up to peas-login".into();
+        let nodes = vec![&login, &ref_only];
+        let edges = collect_edges(&nodes);
+        assert_eq!(
+            edges,
+            vec![Edge {
+                from: "peas-notes",
+                to: "peas-login",
+                kind: EdgeKind::Refs
+            }]
+        );
+
+        let mermaid = render_mermaid(&[&epic, &login], &[]);
+        assert!(mermaid.starts_with("graph LR\n"));
+        assert!(mermaid.contains("peas_epic1[\"peas-epic1<br/>[task] Auth #quot;v2#quot;\"]"));
+
+        let dot = render_dot(&nodes, &edges);
+        assert!(dot.contains("\"peas-notes\" -> \"peas-login\" [label=\"refs\", style=dashed];"));
+        let dot = render_dot(&[&epic], &[]);
+        assert!(dot.contains("Auth \\\"v2\\\""));
+    }
+}
@@ -0,0 +1,109 @@
+use crate::model::PeaStatus;
+use crate::read_markers::ReadMarkers;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::{format_read_state, format_status};
+
+pub fn handle_inbox(ctx: &CommandContext, mark_read: bool, drafts: bool, json: bool) -> Result<()> {
+    if drafts {
+        return print_drafts(ctx, json);
+    }
+    let mut markers = ReadMarkers::load(&ctx.config.data_path(&ctx.root));
+    let mut unread: Vec<_> = ctx
+        .repo
+        .list()?
+        .into_iter()
+        .map(|pea| (markers.state(&pea), pea))
+        .filter(|(state, _)| state.is_unread())
+        .collect();
+    unread.sort_by_key(|(_, pea)| std::cmp::Reverse(pea.updated));
+
+    if mark_read {
+        markers.mark_all_read();
+        markers.save()?;
+    }
+
+    if json {
+        let items: Vec<_> = unread
+            .iter()
+            .map(|(state, pea)| serde_json::json!({ "state": state, "pea": pea }))
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "items": items,
+                "count": unread.len(),
+                "marked_read": mark_read,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if unread.is_empty() {
+        println!("Nothing new since you last looked.");
+        return Ok(());
+    }
+
+    for (state, pea) in &unread {
+        println!(
+            "{} {:<7} {} {} [{}] {} {}",
+            format_read_state(*state),
+            state.to_string(),
+            pea.id.cyan(),
+            format_status(pea.status),
+            format!("{}", pea.pea_type).blue(),
+            pea.title,
+            pea.updated.format("%Y-%m-%d %H:%M").to_string().dimmed()
+        );
+    }
+    println!();
+    if mark_read {
+        println!("{} {} pea(s) as read", "Marked".green(), unread.len());
+    } else {
+        println!(
+            "{} unread. Use 'peas show <id>' to read one or 'peas inbox --mark-read' to clear all.",
+            unread.len()
+        );
+    }
+    Ok(())
+}
+
+/// Drafts waiting for triage, oldest first
+fn print_drafts(ctx: &CommandContext, json: bool) -> Result<()> {
+    let drafts: Vec<_> = ctx
+        .repo
+        .list()?
+        .into_iter()
+        .filter(|p| p.status == PeaStatus::Draft)
+        .collect();
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "items": drafts,
+                "count": drafts.len(),
+            }))?
+        );
+        return Ok(());
+    }
+
+    if drafts.is_empty() {
+        println!("No drafts waiting for triage.");
+        return Ok(());
+    }
+    for pea in &drafts {
+        println!(
+            "{} [{}] {} {}",
+            pea.id.cyan(),
+            format!("{}", pea.pea_type).blue(),
+            pea.title,
+            pea.created.format("%Y-%m-%d %H:%M").to_string().dimmed()
+        );
+    }
+    println!();
+    println!("{} draft(s). Use 'peas triage' to sort them.", drafts.len());
+    Ok(())
+}
@@ -31,8 +31,27 @@ pub fn handle_init(prefix: String, id_length: usize) -> Result<()> {
             default_status: "todo".to_string(),
             default_type: "task".to_string(),
             frontmatter: "toml".to_string(),
+            tag_policy: Default::default(),
+            size_scale: Default::default(),
+            naming_scheme: Default::default(),
+            lock_closed: false,
+            cascade: Default::default(),
         },
         tui: Default::default(),
+        log: Default::default(),
+        server: Default::default(),
+        lint: Default::default(),
+        open: Default::default(),
+        undo: Default::default(),
+        bulk: Default::default(),
+        display: Default::default(),
+        semantic: Default::default(),
+        validation: Default::default(),
+        tags: Default::default(),
+        components: Default::default(),
+        autotag: Default::default(),
+        rules: Default::default(),
+        actor: None,
     };
 
     // Create data directory
@@ -0,0 +1,58 @@
+use crate::cli::commands::LinkKindArg;
+use crate::model::PeaLink;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::record_undo_update;
+
+pub fn handle_link(
+    ctx: &CommandContext,
+    id: &str,
+    url: String,
+    label: Option<String>,
+    kind: Option<LinkKindArg>,
+    remove: bool,
+    json: bool,
+) -> Result<()> {
+    let mut pea = ctx.repo.get(id)?;
+
+    if remove && !pea.links.iter().any(|l| l.url == url) {
+        anyhow::bail!("{} has no link to {}", pea.id, url);
+    }
+
+    let old_path = ctx.repo.find_file_by_id(&pea.id)?;
+    record_undo_update(ctx, &pea.id, &old_path);
+
+    let mut link = PeaLink::new(url);
+    if remove {
+        pea.links.retain(|l| l.url != link.url);
+    } else {
+        link.label = label;
+        if let Some(kind) = kind {
+            link.kind = kind.into();
+        }
+        pea.set_link(link.clone());
+    }
+    ctx.repo.update(&mut pea)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pea)?);
+    } else if remove {
+        println!(
+            "{} link {} from {}",
+            "Removed".green(),
+            link.url,
+            pea.id.cyan()
+        );
+    } else {
+        println!(
+            "{} {} [{}] {}",
+            "Linked".green(),
+            pea.id.cyan(),
+            link.kind,
+            link.title().yellow()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use crate::error::PeasError;
+use crate::lint::{Severity, lint};
+use anyhow::Result;
+use chrono::Utc;
+use colored::Colorize;
+use std::collections::HashMap;
+
+use super::CommandContext;
+
+pub fn handle_lint(ctx: &CommandContext, fail_on: Severity, json: bool) -> Result<()> {
+    let peas = ctx.repo.list()?;
+    let findings = lint(&peas, &ctx.config, Utc::now());
+    let count = |severity| findings.iter().filter(|f| f.severity == severity).count();
+    let failing = findings.iter().filter(|f| f.severity >= fail_on).count();
+
+    if json {
+        let titles: HashMap<&str, &str> = peas
+            .iter()
+            .map(|p| (p.id.as_str(), p.title.as_str()))
+            .collect();
+        let items: Vec<_> = findings
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "check": f.check,
+                    "severity": f.severity,
+                    "id": f.id,
+                    "title": titles.get(f.id.as_str()),
+                    "message": f.message,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "findings": items,
+                "counts": {
+                    "error": count(Severity::Error),
+                    "warning": count(Severity::Warning),
+                    "info": count(Severity::Info),
+                },
+                "passed": failing == 0,
+            }))?
+        );
+    } else if findings.is_empty() {
+        println!("{} {} pea(s) checked, no findings", "✓".green(), peas.len());
+    } else {
+        for finding in &findings {
+            let severity = match finding.severity {
+                Severity::Error => "error".red().bold(),
+                Severity::Warning => "warning".yellow().bold(),
+                _ => "info".blue(),
+            };
+            println!(
+                "{:<7} {} {} {}",
+                severity,
+                finding.id.cyan(),
+                format!("[{}]", finding.check).dimmed(),
+                finding.message
+            );
+        }
+        println!();
+        println!(
+            "{} pea(s) checked: {} error(s), {} warning(s), {} info",
+            peas.len(),
+            count(Severity::Error),
+            count(Severity::Warning),
+            count(Severity::Info)
+        );
+    }
+
+    if failing > 0 {
+        return Err(PeasError::validation(
+            "lint",
+            format!("{} finding(s) at {} or above", failing, fail_on),
+        )
+        .into());
+    }
+    Ok(())
+}
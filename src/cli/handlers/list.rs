@@ -1,9 +1,18 @@
-use crate::cli::commands::{PeaPriorityArg, PeaStatusArg, PeaTypeArg};
-use crate::model::PeaStatus;
+use crate::cli::commands::{ListColumnArg, ListGroupArg, PeaPriorityArg, PeaStatusArg, PeaTypeArg};
+use crate::model::{Pea, SortKey};
+use crate::read_markers::ReadMarkers;
+use crate::snapshot::peas_as_of;
+use crate::storage::ListQuery;
+use crate::terminal::{TimeFormat, output_width};
 use anyhow::Result;
+use clap::ValueEnum;
+use colored::Colorize;
 
 use super::CommandContext;
-use super::utils::print_pea_list;
+use super::utils::{format_priority, format_read_state, format_status};
+
+/// Titles are not squeezed below this many characters to fit the terminal
+const MIN_TITLE_WIDTH: usize = 20;
 
 /// Parameters for list operation
 pub struct ListParams {
@@ -12,41 +21,384 @@ pub struct ListParams {
     pub priority: Option<PeaPriorityArg>,
     pub parent: Option<String>,
     pub tag: Option<String>,
+    pub component: Option<String>,
+    pub assignee: Option<String>,
+    pub created_by: Option<String>,
+    pub updated_by: Option<String>,
+    /// Hide peas with an active claim by anyone but the current actor
+    pub unclaimed: bool,
+    /// Keep peas whose `defer_until` date is still ahead
+    pub include_deferred: bool,
     pub archived: bool,
+    /// Snapshot label or time to list peas as they were at
+    pub as_of: Option<String>,
+    pub columns: Vec<ListColumnArg>,
+    pub sort: Vec<SortKey>,
+    pub reverse: bool,
+    pub group_by: Option<ListGroupArg>,
+    /// Print only the number of peas
+    pub count: bool,
+    /// Print only counts per status and type
+    pub summary: bool,
+    /// Show dates relative to now
+    pub relative: bool,
+    /// Read the summary index rather than every ticket file
+    pub fast: bool,
     pub json: bool,
 }
 
 pub fn handle_list(ctx: &CommandContext, params: ListParams) -> Result<()> {
-    let mut peas = if params.archived {
-        ctx.repo.list_archived()?
+    let query = ListQuery {
+        pea_type: params.r#type.map(Into::into),
+        status: params.status.map(Into::into),
+        priority: params.priority.map(Into::into),
+        parent: params.parent.clone(),
+        tag: params.tag.clone(),
+        component: params.component.clone(),
+        assignee: params.assignee.clone(),
+        created_by: params.created_by.clone(),
+        updated_by: params.updated_by.clone(),
+        unclaimed: params.unclaimed,
+        hide_deferred: !params.include_deferred,
+        sort: params.sort.clone(),
+        reverse: params.reverse,
+        ..Default::default()
+    };
+
+    let mut origin = None;
+    let peas = if let Some(as_of) = &params.as_of {
+        let (peas, from) = peas_as_of(&ctx.config, &ctx.root, as_of)?;
+        origin = Some(from);
+        query.apply(peas, ctx.repo.actor()).peas
+    } else if params.archived {
+        query
+            .apply(ctx.repo.list_archived()?, ctx.repo.actor())
+            .peas
+    } else if params.fast {
+        query
+            .apply(ctx.repo.list_summaries()?, ctx.repo.actor())
+            .peas
     } else {
-        ctx.repo.list()?
+        ctx.repo.query(&query)?.peas
     };
 
-    // Apply filters
-    if let Some(t) = params.r#type {
-        let filter_type = t.into();
-        peas.retain(|p| p.pea_type == filter_type);
+    if params.count {
+        if params.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({ "total": peas.len() }))?
+            );
+        } else {
+            println!("{}", peas.len());
+        }
+        return Ok(());
     }
-    if let Some(s) = params.status {
-        let filter_status: PeaStatus = s.into();
-        peas.retain(|p| p.status == filter_status);
+    if params.summary {
+        return print_summary(&peas, params.json);
     }
-    if let Some(p) = params.priority {
-        let filter_priority = p.into();
-        peas.retain(|p| p.priority == filter_priority);
+
+    let groups = match params.group_by {
+        Some(group_by) => group_peas(&peas, group_by),
+        None => vec![(None, peas.iter().collect())],
+    };
+
+    if params.json {
+        match params.group_by {
+            Some(group_by) => {
+                let groups: Vec<_> = groups
+                    .iter()
+                    .map(|(key, peas)| {
+                        serde_json::json!({
+                            "key": key,
+                            "count": peas.len(),
+                            "peas": peas,
+                        })
+                    })
+                    .collect();
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "group_by": group_by.to_possible_value().map(|v| v.get_name().to_string()),
+                        "groups": groups,
+                    }))?
+                );
+            }
+            None => println!("{}", serde_json::to_string_pretty(&peas)?),
+        }
+        return Ok(());
     }
-    if let Some(ref parent_id) = params.parent {
-        peas.retain(|p| p.parent.as_deref() == Some(parent_id.as_str()));
+
+    // Headings name the group and count its peas
+    let groups: Vec<(Option<String>, Vec<&Pea>)> = groups
+        .into_iter()
+        .map(|(key, peas)| {
+            let heading = params.group_by.map(|group_by| {
+                let label = match (group_by, key) {
+                    (ListGroupArg::Parent, Some(parent)) => match ctx.repo.get(&parent) {
+                        Ok(parent) => format!("{} {}", parent.id, parent.title),
+                        Err(_) => parent,
+                    },
+                    (_, Some(key)) => key,
+                    (ListGroupArg::Parent, None) => "no parent".to_string(),
+                    (ListGroupArg::Tag, None) => "untagged".to_string(),
+                    (_, None) => "unassigned".to_string(),
+                };
+                format!("{} ({})", label, peas.len())
+            });
+            (heading, peas)
+        })
+        .collect();
+    let time = TimeFormat::new(&ctx.config.display, params.relative);
+    if let Some(origin) = origin {
+        println!("{}", format!("As of {}", origin).dimmed());
+        print_table(&groups, &params.columns, time, None);
+    } else {
+        let markers = ReadMarkers::load(&ctx.config.data_path(&ctx.root));
+        print_table(&groups, &params.columns, time, Some(&markers));
     }
-    if let Some(ref t) = params.tag {
-        peas.retain(|p| p.tags.contains(t));
+    Ok(())
+}
+
+/// Counts per status and type, leaving out the ones without peas
+fn print_summary(peas: &[Pea], json: bool) -> Result<()> {
+    let counts = |group_by| -> Vec<(String, usize)> {
+        group_peas(peas, group_by)
+            .into_iter()
+            .map(|(key, members)| (key.unwrap_or_default(), members.len()))
+            .collect()
+    };
+    let by_status = counts(ListGroupArg::Status);
+    let by_type = counts(ListGroupArg::Type);
+
+    if json {
+        let map = |counts: &[(String, usize)]| {
+            counts
+                .iter()
+                .map(|(key, count)| (key.clone(), (*count).into()))
+                .collect::<serde_json::Map<_, _>>()
+        };
+        let output = serde_json::json!({
+            "total": peas.len(),
+            "by_status": map(&by_status),
+            "by_type": map(&by_type),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
     }
 
-    if params.json {
-        println!("{}", serde_json::to_string_pretty(&peas)?);
-    } else {
-        print_pea_list(&peas);
+    let join = |counts: &[(String, usize)]| {
+        counts
+            .iter()
+            .map(|(key, count)| format!("{} {}", key, count))
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+    println!("{} {}", peas.len().to_string().bold(), "peas".bold());
+    if !peas.is_empty() {
+        println!("{} {}", "Status:".dimmed(), join(&by_status));
+        println!("{}   {}", "Type:".dimmed(), join(&by_type));
     }
     Ok(())
 }
+
+/// Group peas by a field, keeping their order within each group
+///
+/// Statuses and types come in their declared order, other values
+/// alphabetically with peas lacking one (`None`) last. A pea with several tags
+/// is in the group of each.
+fn group_peas(peas: &[Pea], group_by: ListGroupArg) -> Vec<(Option<String>, Vec<&Pea>)> {
+    let mut groups: Vec<(Option<String>, Vec<&Pea>)> = Vec::new();
+    for pea in peas {
+        let keys = match group_by {
+            ListGroupArg::Status => vec![Some(pea.status.to_string())],
+            ListGroupArg::Type => vec![Some(pea.pea_type.to_string())],
+            ListGroupArg::Parent => vec![pea.parent.clone()],
+            ListGroupArg::Tag if pea.tags.is_empty() => vec![None],
+            ListGroupArg::Tag => pea.tags.iter().cloned().map(Some).collect(),
+            ListGroupArg::Assignee => vec![pea.assignee.clone()],
+        };
+        for key in keys {
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, members)) => members.push(pea),
+                None => groups.push((key, vec![pea])),
+            }
+        }
+    }
+    match group_by {
+        ListGroupArg::Status => groups.sort_by_key(|(_, peas)| peas[0].status as u8),
+        ListGroupArg::Type => groups.sort_by_key(|(_, peas)| peas[0].pea_type as u8),
+        _ => groups.sort_by(|(a, _), (b, _)| (a.is_none(), a).cmp(&(b.is_none(), b))),
+    }
+    groups
+}
+
+fn header(column: ListColumnArg) -> &'static str {
+    match column {
+        ListColumnArg::Id => "ID",
+        ListColumnArg::Status => "STATUS",
+        ListColumnArg::Type => "TYPE",
+        ListColumnArg::Priority => "PRIORITY",
+        ListColumnArg::Title => "TITLE",
+        ListColumnArg::Tags => "TAGS",
+        ListColumnArg::Component => "COMPONENT",
+        ListColumnArg::Assignee => "ASSIGNEE",
+        ListColumnArg::Parent => "PARENT",
+        ListColumnArg::Size => "SIZE",
+        ListColumnArg::Created => "CREATED",
+        ListColumnArg::Updated => "UPDATED",
+    }
+}
+
+/// Plain text of a cell; titles carry the claim, if any
+fn cell(pea: &Pea, column: ListColumnArg, time: TimeFormat) -> String {
+    match column {
+        ListColumnArg::Id => pea.id.clone(),
+        ListColumnArg::Status => pea.status.to_string(),
+        ListColumnArg::Type => pea.pea_type.to_string(),
+        ListColumnArg::Priority => pea.priority.to_string(),
+        ListColumnArg::Title => match pea.active_claim(chrono::Utc::now()) {
+            Some(claim) => format!("{} (claimed by {})", pea.title, claim.by),
+            None => pea.title.clone(),
+        },
+        ListColumnArg::Tags => pea.tags.join(", "),
+        ListColumnArg::Component => pea.component.clone().unwrap_or_default(),
+        ListColumnArg::Assignee => pea.assignee.clone().unwrap_or_default(),
+        ListColumnArg::Parent => pea.parent.clone().unwrap_or_default(),
+        ListColumnArg::Size => pea.size.clone().unwrap_or_default(),
+        ListColumnArg::Created => time.date(pea.created),
+        ListColumnArg::Updated => time.date(pea.updated),
+    }
+}
+
+/// Color a cell after it was truncated to fit
+fn style(pea: &Pea, column: ListColumnArg, text: String) -> String {
+    if text.is_empty() {
+        return text;
+    }
+    match column {
+        ListColumnArg::Id | ListColumnArg::Parent => text.cyan().to_string(),
+        ListColumnArg::Status if text == pea.status.to_string() => {
+            format_status(pea.status).to_string()
+        }
+        ListColumnArg::Type => text.blue().to_string(),
+        ListColumnArg::Priority if text == pea.priority.to_string() => {
+            format_priority(pea.priority).to_string()
+        }
+        ListColumnArg::Title => {
+            // Whatever follows the title is the claim
+            let split = text
+                .char_indices()
+                .nth(pea.title.chars().count())
+                .map_or(text.len(), |(i, _)| i);
+            match text.split_at(split) {
+                (_, "") => text,
+                (title, claim) => format!("{}{}", title, claim.magenta()),
+            }
+        }
+        ListColumnArg::Tags | ListColumnArg::Component => text.magenta().to_string(),
+        ListColumnArg::Created | ListColumnArg::Updated => text.dimmed().to_string(),
+        _ => text,
+    }
+}
+
+/// Shorten `text` to `width` characters, ending in an ellipsis when cut
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(width.saturating_sub(1)).collect();
+    short.push('…');
+    short
+}
+
+/// Print peas as an aligned table, cutting titles to fit the terminal
+///
+/// Each group is printed under its heading, if it has one, with columns
+/// aligned across groups. With read markers, a dot in front flags peas new or
+/// changed since last viewed, if there are any.
+fn print_table(
+    groups: &[(Option<String>, Vec<&Pea>)],
+    columns: &[ListColumnArg],
+    time: TimeFormat,
+    markers: Option<&ReadMarkers>,
+) {
+    if groups.iter().all(|(_, peas)| peas.is_empty()) {
+        println!("No peas found.");
+        return;
+    }
+    let markers = markers.filter(|m| {
+        groups
+            .iter()
+            .any(|(_, peas)| peas.iter().any(|p| m.is_unread(p)))
+    });
+
+    let rows: Vec<Vec<Vec<String>>> = groups
+        .iter()
+        .map(|(_, peas)| {
+            peas.iter()
+                .map(|pea| columns.iter().map(|&c| cell(pea, c, time)).collect())
+                .collect()
+        })
+        .collect();
+    let mut widths: Vec<usize> = columns.iter().map(|&c| header(c).len()).collect();
+    for row in rows.iter().flatten() {
+        for (width, text) in widths.iter_mut().zip(row) {
+            *width = (*width).max(text.chars().count());
+        }
+    }
+    if let Some(total) = output_width()
+        && let Some(title) = columns.iter().position(|&c| c == ListColumnArg::Title)
+    {
+        let marker = if markers.is_some() { 2 } else { 0 };
+        let others: usize = widths
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != title)
+            .map(|(_, width)| width + 2)
+            .sum();
+        let room = total.saturating_sub(marker + others).max(MIN_TITLE_WIDTH);
+        widths[title] = widths[title].min(room);
+    }
+
+    // Cells are styled text with the length of their plain text
+    let line = |cells: Vec<(String, usize)>, marker: String| {
+        let last = cells.len().saturating_sub(1);
+        let mut out = marker;
+        for (i, ((styled, len), width)) in cells.into_iter().zip(&widths).enumerate() {
+            out.push_str(&styled);
+            if i < last {
+                out.push_str(&" ".repeat(width.saturating_sub(len) + 2));
+            }
+        }
+        out
+    };
+
+    let headers = columns
+        .iter()
+        .map(|&c| (header(c).bold().to_string(), header(c).len()))
+        .collect();
+    let blank = if markers.is_some() { "  " } else { "" };
+    println!("{}", line(headers, blank.to_string()));
+    for ((heading, peas), rows) in groups.iter().zip(rows) {
+        if let Some(heading) = heading {
+            println!("\n{}", heading.bold());
+        }
+        for (pea, row) in peas.iter().zip(rows) {
+            let cells = columns
+                .iter()
+                .zip(row)
+                .zip(&widths)
+                .map(|((&column, text), &width)| {
+                    let text = truncate(&text, width);
+                    let len = text.chars().count();
+                    (style(pea, column, text), len)
+                })
+                .collect();
+            let marker = match markers {
+                Some(markers) => format!("{} ", format_read_state(markers.state(pea))),
+                None => String::new(),
+            };
+            println!("{}", line(cells, marker));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::aliases::Aliases;
+use crate::git;
+use crate::integrity::follow_renames;
+use crate::storage::{
+    FrontmatterFormat, PeaMerge, detect_format, merge_peas, parse_markdown,
+    render_markdown_with_format,
+};
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Merge a pea file the way git's `merge.<driver>.driver` contract expects:
+/// the result replaces `ours`, and a non-zero exit marks the file conflicted.
+pub fn handle_merge_driver(base: &str, ours: &str, theirs: &str, path: Option<&str>) -> Result<()> {
+    let read = |file: &str| {
+        std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file))
+    };
+    let (base_text, ours_text, theirs_text) = (read(base)?, read(ours)?, read(theirs)?);
+    let name = path.unwrap_or(ours);
+
+    let parsed = (
+        parse_markdown(&base_text),
+        parse_markdown(&ours_text),
+        parse_markdown(&theirs_text),
+    );
+    let (merged, clean) = match parsed {
+        (Ok(base_pea), Ok(ours_pea), Ok(theirs_pea)) => {
+            let format = detect_format(&ours_text).unwrap_or(FrontmatterFormat::Toml);
+            let PeaMerge { mut pea, conflicts } = merge_peas(&base_pea, &ours_pea, &theirs_pea);
+
+            let mut clean = true;
+            let mut field_markers = Vec::new();
+            for conflict in &conflicts {
+                if conflict.field == "body" {
+                    let (body, body_clean) = git::merge_text(
+                        &format!("{}\n", ours_pea.body),
+                        &format!("{}\n", base_pea.body),
+                        &format!("{}\n", theirs_pea.body),
+                    )?;
+                    pea.body = body.trim().into();
+                    clean &= body_clean;
+                } else {
+                    field_markers.push(format!(
+                        "<<<<<<< ours\n{field}: {}\n=======\n{field}: {}\n>>>>>>> theirs",
+                        conflict.ours,
+                        conflict.theirs,
+                        field = conflict.field
+                    ));
+                    clean = false;
+                }
+            }
+            // Frontmatter has to stay parseable, so field conflicts keep our value
+            // and are spelled out in the body for the user to resolve
+            if !field_markers.is_empty() {
+                pea.body = format!("{}\n\n{}", pea.body, field_markers.join("\n"))
+                    .trim()
+                    .into();
+            }
+            // A rename merged in from either side leaves references to the old ID
+            if let Some(data_dir) = path.and_then(|p| Path::new(p).parent()) {
+                pea = follow_renames(&pea, &Aliases::load(data_dir)?);
+            }
+            (render_markdown_with_format(&pea, format)?, clean)
+        }
+        // Not a pea (memories, hand-broken files): plain line-based merge
+        _ => {
+            let (text, clean) = git::merge_text(&ours_text, &base_text, &theirs_text)?;
+            (text, clean)
+        }
+    };
+
+    std::fs::write(ours, merged).with_context(|| format!("Failed to write {}", ours))?;
+    if !clean {
+        anyhow::bail!("Merge conflict in {}", name);
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use super::CommandContext;
+use crate::error::PeasError;
+use crate::integrity::rename_references;
+use crate::model::Pea;
+use crate::storage::parse_markdown;
+use crate::transaction::Transaction;
+use crate::validation;
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Change the prefix of every pea ID starting with `from`, in the archive too
+///
+/// Files, parent and blocking references, mentions in bodies, the aliases and
+/// the configured prefix are rewritten in one transaction, undone as one step.
+/// The old IDs stay resolvable as aliases.
+pub fn handle_migrate_prefix(
+    ctx: &CommandContext,
+    from: &str,
+    to: &str,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    if from.is_empty() || from == to {
+        return Err(PeasError::validation("from", "Give two different prefixes").into());
+    }
+
+    let data_path = ctx.config.data_path(&ctx.root);
+    let mut files = pea_files(&data_path)?;
+    files.extend(pea_files(&ctx.config.archive_path(&ctx.root))?);
+
+    let renames: BTreeMap<String, String> = files
+        .iter()
+        .filter_map(|(_, pea)| {
+            let rest = pea.id.strip_prefix(from)?;
+            Some((pea.id.clone(), format!("{}{}", to, rest)))
+        })
+        .collect();
+    for new_id in renames.values() {
+        validation::validate_id(new_id)?;
+    }
+    let kept: HashSet<&str> = files
+        .iter()
+        .map(|(_, pea)| pea.id.as_str())
+        .filter(|id| !renames.contains_key(*id))
+        .collect();
+    let taken: Vec<&str> = renames
+        .values()
+        .map(String::as_str)
+        .filter(|id| kept.contains(id))
+        .collect();
+    if !taken.is_empty() {
+        return Err(PeasError::validation(
+            "to",
+            format!("IDs already in use: {}", taken.join(", ")),
+        )
+        .into());
+    }
+
+    // Peas to rewrite, with the IDs they had
+    let changed: Vec<(PathBuf, String, Pea)> = files
+        .into_iter()
+        .filter_map(|(path, pea)| {
+            let rewritten = rename_references(&pea, &renames);
+            (rewritten != pea).then_some((path, pea.id, rewritten))
+        })
+        .collect();
+    let updated: Vec<&str> = changed
+        .iter()
+        .filter(|(_, old_id, _)| !renames.contains_key(old_id))
+        .map(|(_, old_id, _)| old_id.as_str())
+        .collect();
+
+    let config_path = data_path.join("config.toml");
+    let config = std::fs::read_to_string(&config_path).ok();
+    let new_config = config
+        .as_deref()
+        .and_then(|content| replace_prefix_setting(content, from, to));
+
+    if !dry_run && (!changed.is_empty() || new_config.is_some()) {
+        let mut tx = Transaction::begin(&ctx.repo, &data_path)?;
+        for (path, old_id, pea) in &changed {
+            tx.rewrite(old_id, path, pea)?;
+        }
+
+        // Keep older undo steps pointing at the files under their new names;
+        // longer IDs first so none is mistaken for the start of another
+        let mut replacements: Vec<(&str, &str)> = renames
+            .iter()
+            .map(|(old, new)| (old.as_str(), new.as_str()))
+            .collect();
+        replacements.sort_by_key(|(old, _)| std::cmp::Reverse(old.len()));
+        for (path, content) in ctx.repo.undo_manager().replaced_entries(&replacements)? {
+            tx.write_file(&path, &content)?;
+        }
+
+        if !renames.is_empty() {
+            let mut aliases = ctx.repo.aliases()?;
+            let assets = data_path.join("assets");
+            for (old, new) in &renames {
+                aliases.add(old, new);
+                if assets.join(old).is_dir() {
+                    tx.move_path(&assets.join(old), &assets.join(new))?;
+                }
+            }
+            tx.write_file(aliases.path(), &aliases.to_toml()?)?;
+        }
+        if let Some(content) = &new_config {
+            tx.write_file(&config_path, content)?;
+        }
+        tx.commit(&format!("Migrate prefix {} to {}", from, to))?;
+
+        let focus = crate::focus::Focus::new(&data_path);
+        if let Some(new) = focus.get().and_then(|id| renames.get(&id)) {
+            focus.set(new)?;
+        }
+    }
+
+    if json {
+        let renamed: Vec<_> = renames
+            .iter()
+            .map(|(old, new)| serde_json::json!({ "old": old, "new": new }))
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "from": from,
+                "to": to,
+                "renamed": renamed,
+                "updated": updated,
+                "config_updated": new_config.is_some(),
+                "dry_run": dry_run,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if renames.is_empty() {
+        println!("No peas have IDs starting with '{}'.", from);
+        return Ok(());
+    }
+    let (rename, update, set) = if dry_run {
+        ("Would rename", "Would update", "Would set")
+    } else {
+        ("Renamed", "Updated", "Set")
+    };
+    println!(
+        "{} {} pea(s) from {} to {}:",
+        rename.green(),
+        renames.len(),
+        from.cyan(),
+        to.cyan()
+    );
+    for (old, new) in &renames {
+        println!("  {} → {}", old, new.cyan());
+    }
+    if !updated.is_empty() {
+        println!(
+            "{} references in {} other pea(s): {}",
+            update.green(),
+            updated.len(),
+            updated.join(", ").cyan()
+        );
+    }
+    if new_config.is_some() {
+        println!(
+            "{} prefix = \"{}\" in {}",
+            set.green(),
+            to,
+            config_path.display()
+        );
+    } else if ctx.config.peas.prefix != to {
+        println!(
+            "{}: the configured prefix is '{}'; set it to '{}' so new peas match",
+            "note".yellow(),
+            ctx.config.peas.prefix,
+            to
+        );
+    }
+    if !dry_run {
+        println!("  Old IDs still resolve (.peas/aliases.toml)");
+    }
+    Ok(())
+}
+
+/// Ticket files in a directory with their peas, whatever their prefix
+fn pea_files(dir: &Path) -> Result<Vec<(PathBuf, Pea)>> {
+    let mut files = Vec::new();
+    if !dir.exists() {
+        return Ok(files);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().is_none_or(|e| e != "md") {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        // Other markdown files, such as a README, are left alone
+        if let Ok(pea) = parse_markdown(&content) {
+            files.push((path, pea));
+        }
+    }
+    Ok(files)
+}
+
+/// The config with `prefix = "<from>"` changed to `to`, if it has that line
+fn replace_prefix_setting(content: &str, from: &str, to: &str) -> Option<String> {
+    let setting = format!("prefix = \"{}\"", from);
+    let mut found = false;
+    let lines: Vec<String> = content
+        .lines()
+        .map(|line| {
+            if !found && line.trim() == setting {
+                found = true;
+                line.replace(&setting, &format!("prefix = \"{}\"", to))
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    found.then(|| lines.join("\n") + "\n")
+}
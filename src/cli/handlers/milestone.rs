@@ -0,0 +1,217 @@
+use crate::cli::commands::MilestoneAction;
+use crate::error::PeasError;
+use crate::model::{Pea, PeaStatus, PeaType, descendants};
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::{format_days_remaining, format_status, record_undo_update};
+
+pub fn handle_milestone(ctx: &CommandContext, action: MilestoneAction) -> Result<()> {
+    match action {
+        MilestoneAction::List { all, json } => handle_milestone_list(ctx, all, json),
+        MilestoneAction::Close { id, force, json } => handle_milestone_close(ctx, id, force, json),
+    }
+}
+
+/// Completion of the peas below a milestone; scrapped peas do not count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Progress {
+    done: usize,
+    total: usize,
+}
+
+impl Progress {
+    fn of(descendants: &[&Pea]) -> Self {
+        Self {
+            done: descendants
+                .iter()
+                .filter(|p| p.status == PeaStatus::Completed)
+                .count(),
+            total: descendants
+                .iter()
+                .filter(|p| p.status != PeaStatus::Scrapped)
+                .count(),
+        }
+    }
+
+    fn percent(self) -> usize {
+        (self.done * 100).checked_div(self.total).unwrap_or(0)
+    }
+
+    fn bar(self, width: usize) -> String {
+        let filled = (self.done * width).checked_div(self.total).unwrap_or(0);
+        format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+    }
+}
+
+fn handle_milestone_list(ctx: &CommandContext, all: bool, json: bool) -> Result<()> {
+    let peas = ctx.repo.list()?;
+    let today = Utc::now().date_naive();
+
+    let mut milestones: Vec<&Pea> = peas
+        .iter()
+        .filter(|p| p.pea_type == PeaType::Milestone && (all || p.is_open()))
+        .collect();
+    // Soonest target first, undated milestones last
+    milestones.sort_by_key(|m| (m.target_date.is_none(), m.target_date, m.created));
+
+    if json {
+        let output: Vec<_> = milestones
+            .iter()
+            .map(|m| {
+                let progress = Progress::of(&descendants(&peas, &m.id));
+                serde_json::json!({
+                    "id": m.id,
+                    "title": m.title,
+                    "status": m.status,
+                    "target_date": m.target_date,
+                    "days_remaining": m.days_remaining(today),
+                    "overdue": m.is_overdue(today),
+                    "done": progress.done,
+                    "total": progress.total,
+                    "percent": progress.percent(),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if milestones.is_empty() {
+        println!("No milestones found.");
+        return Ok(());
+    }
+
+    for milestone in milestones {
+        let progress = Progress::of(&descendants(&peas, &milestone.id));
+        println!(
+            "{} {} {}",
+            milestone.id.cyan(),
+            milestone.title.bold(),
+            format_status(milestone.status)
+        );
+        let mut line = format!(
+            "  {} {}/{} done ({}%)",
+            progress.bar(20),
+            progress.done,
+            progress.total,
+            progress.percent()
+        );
+        if let Some(target) = milestone.target_date {
+            line.push_str(&format!("  {}", format_due(milestone, target, today)));
+        }
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Target date with days remaining (open milestones) in a matching color
+fn format_due(milestone: &Pea, target: NaiveDate, today: NaiveDate) -> String {
+    let date = format!("due {}", target);
+    let Some(days) = milestone
+        .days_remaining(today)
+        .filter(|_| milestone.is_open())
+    else {
+        return date.dimmed().to_string();
+    };
+    let text = format!("{} ({})", date, format_days_remaining(days));
+    match days {
+        d if d < 0 => text.red().bold().to_string(),
+        d if d <= 7 => text.yellow().to_string(),
+        _ => text,
+    }
+}
+
+fn handle_milestone_close(ctx: &CommandContext, id: String, force: bool, json: bool) -> Result<()> {
+    let mut milestone = ctx.repo.get(&id)?;
+    if milestone.pea_type != PeaType::Milestone {
+        return Err(PeasError::validation(
+            "type",
+            format!("{} is a {}, not a milestone", id, milestone.pea_type),
+        )
+        .into());
+    }
+
+    let peas = ctx.repo.list()?;
+    let open: Vec<&Pea> = descendants(&peas, &id)
+        .into_iter()
+        .filter(|p| p.is_open())
+        .collect();
+    if !open.is_empty() && !force {
+        let ids: Vec<&str> = open.iter().map(|p| p.id.as_str()).collect();
+        return Err(PeasError::validation(
+            "status",
+            format!(
+                "Milestone {} still has {} open pea(s): {} (use --force to close anyway)",
+                id,
+                open.len(),
+                ids.join(", ")
+            ),
+        )
+        .into());
+    }
+
+    if milestone.status != PeaStatus::Completed {
+        let old_path = ctx.repo.find_file_by_id(&id)?;
+        record_undo_update(ctx, &id, &old_path);
+        milestone.status = PeaStatus::Completed;
+        ctx.repo.update(&mut milestone)?;
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&milestone)?);
+    } else {
+        println!(
+            "{} milestone {} {}",
+            "Closed".green(),
+            milestone.id.cyan(),
+            milestone.title
+        );
+        if !open.is_empty() {
+            println!(
+                "{}: {} pea(s) under it are still open",
+                "warning".yellow().bold(),
+                open.len()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pea(id: &str, parent: Option<&str>, status: PeaStatus) -> Pea {
+        Pea::new(id.to_string(), id.to_string(), PeaType::Task)
+            .with_parent(parent.map(str::to_string))
+            .with_status(status)
+    }
+
+    #[test]
+    fn test_progress_counts_nested_peas_and_skips_scrapped() {
+        let peas = vec![
+            pea("m", None, PeaStatus::Todo),
+            pea("epic", Some("m"), PeaStatus::InProgress),
+            pea("t1", Some("epic"), PeaStatus::Completed),
+            pea("t2", Some("epic"), PeaStatus::Scrapped),
+            pea("t3", Some("m"), PeaStatus::Completed),
+            pea("other", None, PeaStatus::Todo),
+        ];
+        let progress = Progress::of(&descendants(&peas, "m"));
+        assert_eq!(progress, Progress { done: 2, total: 3 });
+        assert_eq!(progress.percent(), 66);
+        assert_eq!(progress.bar(6), "[####--]");
+    }
+
+    #[test]
+    fn test_descendants_survive_parent_cycles() {
+        let peas = vec![
+            pea("a", Some("b"), PeaStatus::Todo),
+            pea("b", Some("a"), PeaStatus::Todo),
+        ];
+        assert_eq!(descendants(&peas, "a").len(), 2);
+    }
+}
@@ -1,54 +1,123 @@
+mod apply;
 mod archive;
 mod asset;
+mod blame;
+mod budget;
 mod bulk;
+mod capture;
+mod changelog;
+mod ci_summary;
+mod claim;
 mod context;
 mod create;
 mod delete;
+mod dispatch;
 mod doctor;
+mod edit_list;
 mod export_beans;
+mod find_duplicates;
+mod focus;
+mod git;
+mod graph;
 mod import_beans;
+mod inbox;
 mod init;
+mod link;
+mod lint;
 mod list;
 mod memory;
+mod merge_driver;
 mod migrate;
+mod migrate_prefix;
+mod milestone;
 mod mutate;
 mod mv;
+mod note;
+mod open;
+mod patch;
+mod pr_status;
 mod prime;
 mod query;
+mod related;
+mod release_notes;
+mod report;
+mod retag;
+mod review;
 mod roadmap;
+mod rules;
 mod search;
 mod serve;
 mod show;
+mod size;
+mod snapshot;
+mod stats;
 mod status;
 mod suggest;
+mod sync;
+mod tag;
+mod triage;
 mod tui;
 mod undo;
 mod update;
 mod utils;
 
+pub use apply::handle_apply;
 pub use archive::{ArchiveParams, handle_archive};
 pub use asset::handle_asset;
+pub use blame::handle_blame;
 pub use bulk::handle_bulk;
+pub use capture::handle_capture;
+pub use changelog::handle_changelog;
+pub use ci_summary::handle_ci_summary;
+pub use claim::{handle_claim, handle_release};
 pub use context::handle_context;
 pub use create::handle_create;
-pub use delete::handle_delete;
+pub use delete::{DeleteParams, handle_delete};
+pub use dispatch::{dispatch, run_standalone};
 pub use doctor::handle_doctor;
+pub use edit_list::{EditListParams, handle_edit_list};
 pub use export_beans::handle_export_beans;
+pub use find_duplicates::handle_find_duplicates;
+pub use focus::handle_focus;
+pub use git::handle_git;
+pub use graph::handle_graph;
 pub use import_beans::handle_import_beans;
+pub use inbox::handle_inbox;
 pub use init::handle_init;
+pub use link::handle_link;
+pub use lint::handle_lint;
 pub use list::{ListParams, handle_list};
 pub use memory::handle_memory;
+pub use merge_driver::handle_merge_driver;
 pub use migrate::handle_migrate;
+pub use migrate_prefix::handle_migrate_prefix;
+pub use milestone::handle_milestone;
 pub use mutate::handle_mutate;
 pub use mv::handle_mv;
+pub use note::handle_note;
+pub use open::handle_open;
+pub use patch::handle_patch;
+pub use pr_status::handle_pr_status;
 pub use prime::handle_prime;
 pub use query::handle_query;
+pub use related::handle_related;
+pub use release_notes::handle_release_notes;
+pub use report::handle_report;
+pub use retag::handle_retag;
+pub use review::handle_review;
 pub use roadmap::handle_roadmap;
+pub use rules::handle_rules;
 pub use search::handle_search;
 pub use serve::handle_serve;
 pub use show::handle_show;
-pub use status::{handle_done, handle_start};
+pub use size::handle_size;
+pub use snapshot::handle_snapshot;
+pub use stats::handle_stats;
+pub use status::{handle_done, handle_reopen, handle_start};
 pub use suggest::handle_suggest;
+pub use sync::handle_sync;
+pub use tag::handle_tag;
+pub use triage::handle_triage;
 pub use tui::handle_tui;
 pub use undo::handle_undo;
 pub use update::handle_update;
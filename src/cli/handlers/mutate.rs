@@ -1,4 +1,4 @@
-use crate::graphql::build_schema;
+use crate::graphql::{build_schema, execute};
 use anyhow::Result;
 
 use super::CommandContext;
@@ -19,7 +19,7 @@ pub fn handle_mutate(
     // Auto-wrap in mutation { }
     let query = format!("mutation {{ {} }}", mutation);
     let request = async_graphql::Request::new(&query).variables(vars);
-    let response = tokio::runtime::Runtime::new()?.block_on(schema.execute(request));
+    let response = tokio::runtime::Runtime::new()?.block_on(execute(&schema, request));
 
     println!("{}", serde_json::to_string_pretty(&response)?);
     Ok(())
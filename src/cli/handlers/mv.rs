@@ -1,7 +1,10 @@
 use super::CommandContext;
 use crate::config::{DATA_DIR, IdMode};
+use crate::integrity::rename_references;
+use crate::transaction::Transaction;
 use anyhow::{Context, Result, bail};
 use colored::Colorize;
+use std::collections::BTreeMap;
 
 pub fn handle_mv(
     ctx: &CommandContext,
@@ -17,14 +20,14 @@ pub fn handle_mv(
     let old_suffix = old_suffix.strip_prefix(prefix).unwrap_or(&old_suffix);
     let new_suffix = new_suffix.strip_prefix(prefix).unwrap_or(&new_suffix);
 
-    let old_id = format!("{}{}", prefix, old_suffix);
     let new_id = format!("{}{}", prefix, new_suffix);
 
-    // Validate source ticket exists
+    // Validate source ticket exists; it may be given by its slug
     let pea = ctx
         .repo
-        .get(&old_id)
-        .with_context(|| format!("Ticket not found: {}", old_id))?;
+        .get(old_suffix)
+        .with_context(|| format!("Ticket not found: {}{}", prefix, old_suffix))?;
+    let old_id = pea.id.clone();
 
     // Check if new ID already exists
     if ctx.repo.get(&new_id).is_ok() {
@@ -83,97 +86,74 @@ pub fn handle_mv(
 
     // Find all tickets that reference this ID
     let all_peas = ctx.repo.list()?;
+    let renames = BTreeMap::from([(old_id.clone(), new_id.clone())]);
     let mut updated_parents = 0;
     let mut updated_blocking = 0;
+    let mut updated_mentions = 0;
 
     let data_dir = ctx.root.join(DATA_DIR);
 
-    // Update references in other tickets
+    // All or nothing: a failed write reverts the ones before it
+    let mut tx = Transaction::begin(&ctx.repo, &ctx.config.data_path(&ctx.root))?;
+
+    // Rename the ticket itself first, so references to the new ID are valid
+    let old_path = ctx.repo.find_file_by_id(&old_id)?;
+    let new_path = tx.rewrite(&old_id, &old_path, &rename_references(&pea, &renames))?;
+
+    // Update parent, blocking and body references in other tickets
     for other_pea in &all_peas {
         if other_pea.id == old_id {
             continue; // Skip the ticket we're renaming
         }
 
-        let mut needs_update = false;
-        let mut updated_pea = other_pea.clone();
-
-        // Check parent reference
-        if updated_pea.parent.as_ref() == Some(&old_id) {
-            updated_pea.parent = Some(new_id.clone());
-            needs_update = true;
+        let mut updated_pea = rename_references(other_pea, &renames);
+        if updated_pea == *other_pea {
+            continue;
+        }
+        if updated_pea.parent != other_pea.parent {
             updated_parents += 1;
         }
-
-        // Check blocking references
-        if updated_pea.blocking.contains(&old_id) {
-            updated_pea.blocking = updated_pea
-                .blocking
-                .iter()
-                .map(|b| {
-                    if b == &old_id {
-                        new_id.clone()
-                    } else {
-                        b.clone()
-                    }
-                })
-                .collect();
-            needs_update = true;
+        if updated_pea.blocking != other_pea.blocking {
             updated_blocking += 1;
         }
-
-        if needs_update {
-            ctx.repo.update(&mut updated_pea)?;
+        if updated_pea.body != other_pea.body {
+            updated_mentions += 1;
         }
+        tx.update(&mut updated_pea)?;
     }
 
-    // Now rename the ticket itself
-    let mut renamed_pea = pea.clone();
-    renamed_pea.id = new_id.clone();
-
-    // Get old and new file paths
-    let old_filename = format!(
-        "{}--{}.md",
-        old_id,
-        slug::slugify(&pea.title)
-            .chars()
-            .take(50)
-            .collect::<String>()
-    );
-    let new_filename = format!(
-        "{}--{}.md",
-        new_id,
-        slug::slugify(&pea.title)
-            .chars()
-            .take(50)
-            .collect::<String>()
-    );
-
-    let old_path = data_dir.join(&old_filename);
-    let new_path = data_dir.join(&new_filename);
-
-    // Write the updated ticket content to the new file
-    let content = crate::storage::render_markdown_with_format(
-        &renamed_pea,
-        ctx.config.peas.frontmatter_format(),
-    )?;
-    std::fs::write(&new_path, content)?;
-
-    // Remove the old file
-    if old_path.exists() {
-        std::fs::remove_file(&old_path)?;
+    // Update undo steps that reference the old ID, including their file paths
+    let file_name = |path: &std::path::Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let (old_filename, new_filename) = (file_name(&old_path), file_name(&new_path));
+    let replacements = [
+        (old_filename.as_str(), new_filename.as_str()),
+        (&old_id, &new_id),
+    ];
+    let undo_entries = ctx.repo.undo_manager().replaced_entries(&replacements)?;
+    for (path, content) in &undo_entries {
+        tx.write_file(path, content)?;
     }
 
-    // Update the .undo file if it references the old ID
-    let undo_path = data_dir.join(".undo");
-    if undo_path.exists() {
-        let undo_content = std::fs::read_to_string(&undo_path)?;
-        if undo_content.contains(&old_id) {
-            let updated_undo = undo_content.replace(&old_id, &new_id);
-            // Also update file paths in undo
-            let updated_undo = updated_undo.replace(&old_filename, &new_filename);
-            std::fs::write(&undo_path, updated_undo)?;
-            println!("  Updated .undo file");
-        }
+    // Keep the old ID working in commit messages, bodies and links
+    let mut aliases = ctx.repo.aliases()?;
+    aliases.add(&old_id, &new_id);
+    tx.write_file(aliases.path(), &aliases.to_toml()?)?;
+    tx.commit(&format!("Rename {} to {}", old_id, new_id))?;
+
+    if !undo_entries.is_empty() {
+        println!("  Updated undo history");
+    }
+    println!("  Recorded {} as an alias", old_id);
+
+    // Keep the focus pointing at the renamed ticket
+    let focus = crate::focus::Focus::new(&data_dir);
+    if focus.get().as_deref() == Some(old_id.as_str()) {
+        focus.set(&new_id)?;
+        println!("  Updated focus");
     }
 
     println!("{} Renamed {} → {}", "✓".green(), old_id, new_id);
@@ -183,6 +163,9 @@ pub fn handle_mv(
     if updated_blocking > 0 {
         println!("  Updated {} blocking reference(s)", updated_blocking);
     }
+    if updated_mentions > 0 {
+        println!("  Updated mentions in {} other ticket(s)", updated_mentions);
+    }
 
     Ok(())
 }
@@ -0,0 +1,46 @@
+use crate::error::PeasError;
+use anyhow::Result;
+use chrono::Utc;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::{record_undo_update, resolve_body};
+
+pub fn handle_note(ctx: &CommandContext, id: &str, text: Option<String>, json: bool) -> Result<()> {
+    let mut pea = ctx.repo.get(id)?;
+
+    let Some(text) = resolve_body(text, None)? else {
+        let notes = pea.work_log();
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "id": pea.id,
+                    "notes": notes,
+                }))?
+            );
+        } else if notes.is_empty() {
+            println!("{} has no notes", pea.id.cyan());
+        } else {
+            for note in notes {
+                println!("- {}", note);
+            }
+        }
+        return Ok(());
+    };
+    if text.trim().is_empty() {
+        return Err(PeasError::validation("text", "A note cannot be empty").into());
+    }
+
+    let old_path = ctx.repo.find_file_by_id(&pea.id)?;
+    record_undo_update(ctx, &pea.id, &old_path);
+    pea.add_note(&text, ctx.config.actor.as_deref(), Utc::now())?;
+    ctx.repo.update(&mut pea)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pea)?);
+    } else {
+        println!("{} note to {}", "Added".green(), pea.id.cyan());
+    }
+    Ok(())
+}
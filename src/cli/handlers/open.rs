@@ -0,0 +1,61 @@
+use crate::config::OpenTarget;
+use crate::error::PeasError;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::run_editor;
+
+pub fn handle_open(
+    ctx: &CommandContext,
+    id: String,
+    target: Option<OpenTarget>,
+    print: bool,
+) -> Result<()> {
+    let pea = ctx.repo.get(&id)?;
+    let target = target.unwrap_or(ctx.config.open.target);
+
+    match target {
+        OpenTarget::Editor => {
+            let path = ctx.repo.find_file_by_id(&pea.id)?;
+            if print {
+                println!("{}", path.display());
+                return Ok(());
+            }
+            run_editor(&path)?;
+        }
+        OpenTarget::Tui => {
+            if print {
+                return Err(PeasError::validation(
+                    "print",
+                    "--print works with the editor and url targets",
+                )
+                .into());
+            }
+            // A separate process, so logging is kept off the terminal like for `peas tui`
+            let status = std::process::Command::new(std::env::current_exe()?)
+                .args(["tui", "--select", &pea.id])
+                .current_dir(&ctx.root)
+                .status()?;
+            if !status.success() {
+                anyhow::bail!("TUI exited with non-zero status");
+            }
+        }
+        OpenTarget::Url => {
+            let Some(url) = ctx.config.open.url_for(&pea.id) else {
+                return Err(PeasError::validation(
+                    "target",
+                    "The url target needs an open.url template in the config",
+                )
+                .into());
+            };
+            if print {
+                println!("{}", url);
+                return Ok(());
+            }
+            open::that(&url)?;
+            println!("{} {}", "Opened".green(), url);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::error::PeasError;
+use crate::model::{Pea, PeaPriority, PeaStatus, PeaType, SizeScale};
+use crate::validation;
+use anyhow::Result;
+use colored::Colorize;
+use std::str::FromStr;
+
+use super::CommandContext;
+use super::blame::field_changes;
+use super::utils::{record_undo_update, warn_new_tags};
+
+/// Frontmatter fields `peas patch` can change, with their aliases
+///
+/// The ID and timestamps are managed by peas; links, assets and the body
+/// have their own commands.
+const FIELDS: [(&str, &[&str]); 14] = [
+    ("title", &[]),
+    ("type", &[]),
+    ("status", &[]),
+    ("priority", &[]),
+    ("size", &[]),
+    ("tags", &["tag"]),
+    ("component", &[]),
+    ("assignee", &[]),
+    ("parent", &[]),
+    ("blocking", &[]),
+    ("external_refs", &["refs"]),
+    ("target_date", &["due"]),
+    ("defer_until", &["defer"]),
+    ("released_in", &[]),
+];
+
+pub fn handle_patch(
+    ctx: &CommandContext,
+    id: String,
+    set: Vec<String>,
+    unset: Vec<String>,
+    json: bool,
+    dry_run: bool,
+) -> Result<()> {
+    if set.is_empty() && unset.is_empty() {
+        return Err(PeasError::validation("set", "Nothing to patch, use --set or --unset").into());
+    }
+    let original = ctx.repo.get(&id)?;
+    let mut pea = original.clone();
+    let scale = ctx.config.peas.size_scale;
+
+    for assignment in &set {
+        let Some((field, value)) = assignment.split_once('=') else {
+            return Err(PeasError::validation(
+                "set",
+                format!("Expected <field>=<value>, got '{}'", assignment),
+            )
+            .into());
+        };
+        patch_field(&mut pea, field.trim(), Some(value.trim()), scale)?;
+    }
+    for field in &unset {
+        patch_field(&mut pea, field.trim(), None, scale)?;
+    }
+    validation::validate_target_date(pea.pea_type, pea.target_date)?;
+    // The configured limits, checked before a dry run too
+    ctx.repo.validator().pea(&pea)?;
+    let new_tags: Vec<String> = pea
+        .tags
+        .iter()
+        .filter(|t| !original.tags.contains(t))
+        .cloned()
+        .collect();
+    warn_new_tags(ctx, &new_tags);
+
+    let changes = field_changes(Some(&original), &pea);
+    if !dry_run && !changes.is_empty() {
+        let old_path = ctx.repo.find_file_by_id(&pea.id)?;
+        record_undo_update(ctx, &pea.id, &old_path);
+        ctx.repo.update(&mut pea)?;
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "pea": pea,
+                "changes": changes,
+                "dry_run": dry_run,
+            }))?
+        );
+    } else if changes.is_empty() {
+        println!("{} {}", "No changes to".dimmed(), pea.id.cyan());
+    } else {
+        let verb = if dry_run { "Would patch" } else { "Patched" };
+        println!("{} {}", verb.green(), pea.id.cyan());
+        for change in changes {
+            println!("  {}", change);
+        }
+    }
+    Ok(())
+}
+
+/// Set a field from its command-line form, or clear it for `None`
+///
+/// List fields take comma-separated values; an empty value clears optional
+/// fields like `--unset` does.
+pub(super) fn patch_field(
+    pea: &mut Pea,
+    field: &str,
+    value: Option<&str>,
+    scale: SizeScale,
+) -> crate::error::Result<()> {
+    let Some((name, _)) = FIELDS
+        .iter()
+        .find(|(name, aliases)| *name == field || aliases.contains(&field))
+    else {
+        let names: Vec<&str> = FIELDS.iter().map(|(name, _)| *name).collect();
+        return Err(PeasError::validation(
+            "field",
+            format!(
+                "Unknown field '{}' (expected one of: {})",
+                field,
+                names.join(", ")
+            ),
+        ));
+    };
+    let value = value.filter(|v| !v.is_empty());
+    let list = |value: Option<&str>| -> Vec<String> {
+        value
+            .map(|v| {
+                v.split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let required = || {
+        value.ok_or_else(|| {
+            PeasError::validation(name, format!("{} cannot be unset or empty", name))
+        })
+    };
+
+    match *name {
+        "title" => {
+            let title = required()?;
+            validation::validate_title(title)?;
+            pea.title = title.to_string();
+        }
+        "type" => pea.pea_type = PeaType::from_str(required()?)?,
+        "status" => pea.status = PeaStatus::from_str(required()?)?,
+        "priority" => pea.priority = PeaPriority::from_str(required()?)?,
+        "size" => pea.size = value.map(|v| scale.normalize(v)).transpose()?,
+        "tags" => {
+            let tags = list(value);
+            for tag in &tags {
+                validation::validate_tag(tag)?;
+            }
+            pea.tags = tags;
+        }
+        "component" => pea.component = value.map(String::from),
+        "assignee" => pea.assignee = value.map(String::from),
+        "parent" => pea.parent = value.map(String::from),
+        "blocking" => pea.blocking = list(value),
+        "external_refs" => pea.external_refs = list(value),
+        "target_date" => {
+            pea.target_date = value.map(validation::parse_target_date).transpose()?;
+        }
+        "defer_until" => {
+            pea.defer_until = value.map(validation::parse_defer_until).transpose()?;
+        }
+        "released_in" => pea.released_in = value.map(String::from),
+        _ => unreachable!("every field in FIELDS is handled"),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_fields() {
+        let mut pea = Pea::new("peas-abc12".into(), "Plan".into(), PeaType::Milestone)
+            .with_parent(Some("peas-root1".into()));
+        let scale = SizeScale::Fibonacci;
+
+        patch_field(&mut pea, "priority", Some("p1"), scale).unwrap();
+        patch_field(&mut pea, "due", Some("2026-10-01"), scale).unwrap();
+        patch_field(&mut pea, "tags", Some("ui, backend,"), scale).unwrap();
+        patch_field(&mut pea, "size", Some("8"), scale).unwrap();
+        patch_field(&mut pea, "parent", None, scale).unwrap();
+        patch_field(&mut pea, "assignee", Some("ann"), scale).unwrap();
+        assert_eq!(pea.priority, PeaPriority::High);
+        assert_eq!(pea.assignee.as_deref(), Some("ann"));
+        assert_eq!(pea.target_date.unwrap().to_string(), "2026-10-01");
+        assert_eq!(pea.tags, vec!["ui", "backend"]);
+        assert_eq!(pea.size.as_deref(), Some("8"));
+        assert_eq!(pea.parent, None);
+
+        // Checked against the field's values
+        assert!(patch_field(&mut pea, "status", Some("started"), scale).is_err());
+        assert!(patch_field(&mut pea, "size", Some("4"), scale).is_err());
+        assert!(patch_field(&mut pea, "due", Some("tomorrow"), scale).is_err());
+        assert!(patch_field(&mut pea, "title", None, scale).is_err());
+        assert!(patch_field(&mut pea, "id", Some("peas-other"), scale).is_err());
+        assert!(patch_field(&mut pea, "owner", Some("me"), scale).is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::forge::{ForgeClient, PrState, PullRequest};
+use crate::model::{LinkKind, Pea, PeaStatus};
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+
+use super::CommandContext;
+use super::utils::UndoBatch;
+
+/// State of one pr link; `error` is set when the lookup failed
+#[derive(Debug, Serialize)]
+struct PrCheck {
+    id: String,
+    url: String,
+    label: Option<String>,
+    state: Option<PrState>,
+    error: Option<String>,
+}
+
+pub fn handle_pr_status(
+    ctx: &CommandContext,
+    id: Option<&str>,
+    complete: bool,
+    json: bool,
+) -> Result<()> {
+    let has_pr = |p: &Pea| p.links.iter().any(|l| l.kind == LinkKind::Pr);
+    let peas: Vec<Pea> = match id {
+        Some(id) => {
+            let pea = ctx.repo.get(id)?;
+            if !has_pr(&pea) {
+                anyhow::bail!("{} has no pr links (add one with `peas link`)", pea.id);
+            }
+            vec![pea]
+        }
+        None => ctx
+            .repo
+            .list()?
+            .into_iter()
+            .filter(|p| p.is_open() && has_pr(p))
+            .collect(),
+    };
+
+    let client = ForgeClient::new()?;
+    let mut checks = Vec::new();
+    let mut completed = Vec::new();
+    let mut batch = UndoBatch::new(ctx, "Complete peas with merged pull requests");
+    for mut pea in peas {
+        let mut states = Vec::new();
+        for link in pea.links.iter().filter(|l| l.kind == LinkKind::Pr) {
+            let result = match PullRequest::parse(&link.url) {
+                Some(pr) => client.state(&pr).map_err(|e| e.to_string()),
+                None => Err("not a GitHub or GitLab pull request URL".to_string()),
+            };
+            if let Ok(state) = &result {
+                states.push(*state);
+            }
+            checks.push(PrCheck {
+                id: pea.id.clone(),
+                url: link.url.clone(),
+                label: link.label.clone(),
+                state: result.as_ref().ok().copied(),
+                error: result.err(),
+            });
+        }
+
+        if complete && pea.is_open() && should_complete(&states) {
+            pea.status = PeaStatus::Completed;
+            batch.update(&mut pea)?;
+            completed.push(pea.id);
+        }
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "pull_requests": checks,
+                "completed": completed,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if checks.is_empty() {
+        println!("No open peas with pr links.");
+        return Ok(());
+    }
+    for check in &checks {
+        let state = match (check.state, &check.error) {
+            (Some(PrState::Merged), _) => "merged".green(),
+            (Some(PrState::Open), _) => "open".yellow(),
+            (Some(PrState::Draft), _) => "draft".dimmed(),
+            (Some(PrState::Closed), _) => "closed".red(),
+            (None, _) => "error".red().bold(),
+        };
+        println!(
+            "{} {:<8} {}",
+            check.id.cyan(),
+            state,
+            check.label.as_deref().unwrap_or(&check.url)
+        );
+        if let Some(error) = &check.error {
+            println!("  {}", error.dimmed());
+        }
+    }
+    for id in &completed {
+        println!(
+            "{} {} is now {}",
+            "Done".green(),
+            id.cyan(),
+            "completed".green()
+        );
+    }
+    Ok(())
+}
+
+/// At least one pull request merged and none is still open or in draft
+fn should_complete(states: &[PrState]) -> bool {
+    states.contains(&PrState::Merged)
+        && !states
+            .iter()
+            .any(|s| matches!(s, PrState::Open | PrState::Draft))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_complete() {
+        assert!(should_complete(&[PrState::Merged]));
+        assert!(should_complete(&[PrState::Merged, PrState::Closed]));
+        assert!(!should_complete(&[PrState::Merged, PrState::Open]));
+        assert!(!should_complete(&[PrState::Closed]));
+        assert!(!should_complete(&[]));
+    }
+}
@@ -1,18 +1,30 @@
 use crate::config::DATA_DIR;
-use crate::model::PeaStatus;
+use crate::model::{Memory, Pea, PeaStatus};
+use crate::storage::MemoryRepository;
 use anyhow::Result;
 
 use super::CommandContext;
-
-pub fn handle_prime(ctx: &CommandContext) -> Result<()> {
+use super::budget::{
+    EXCERPT_CHARS, check_args, estimate_tokens, excerpt, fit, rank_memories, rank_peas, subtree,
+};
+
+/// Tokens kept for the section headings and "... and N more" lines of a budgeted prime
+const SECTION_TOKENS: usize = 40;
+
+pub fn handle_prime(
+    ctx: &CommandContext,
+    budget: Option<usize>,
+    focus: Option<String>,
+) -> Result<()> {
+    check_args(ctx, budget, focus.as_deref())?;
     let peas = ctx.repo.list()?;
-    let open_peas: Vec<_> = peas.iter().filter(|p| p.is_open()).collect();
+    let open_peas = rank_peas(&peas, focus.as_deref());
     let in_progress: Vec<_> = peas
         .iter()
         .filter(|p| p.status == PeaStatus::InProgress)
         .collect();
 
-    println!(
+    let instructions = format!(
         r#"# Peas - Issue Tracker
 
 This project uses **peas** for issue tracking. Issues are stored as markdown files in the `{}` directory.
@@ -28,6 +40,7 @@ peas create "<title>" -t <type>    # Create a new pea
 peas update <id> -s <status>       # Update pea status
 peas start <id>                    # Mark as in-progress
 peas done <id>                     # Mark as completed
+peas link <id> <url> -l "PR"       # Attach a PR, issue or doc link
 peas search "<query>"              # Search peas
 peas roadmap                       # Show project roadmap
 peas suggest                       # Get next suggested ticket to work on
@@ -92,6 +105,12 @@ draft, todo, in-progress, completed, scrapped
 "#,
         DATA_DIR
     );
+    println!("{}", instructions);
+
+    if let Some(budget) = budget {
+        let remaining = budget.saturating_sub(estimate_tokens(&instructions) + SECTION_TOKENS);
+        return print_within_budget(ctx, &peas, &open_peas, focus.as_deref(), remaining);
+    }
 
     if !in_progress.is_empty() {
         println!("## Currently In Progress ({})", in_progress.len());
@@ -115,3 +134,81 @@ draft, todo, in-progress, completed, scrapped
 
     Ok(())
 }
+
+/// The most relevant open peas and memories, with excerpts, in `budget` tokens
+fn print_within_budget(
+    ctx: &CommandContext,
+    peas: &[Pea],
+    ranked_peas: &[&Pea],
+    focus: Option<&str>,
+    budget: usize,
+) -> Result<()> {
+    let memories = MemoryRepository::new(&ctx.config, &ctx.root).list(None)?;
+    let related = focus.map(|id| subtree(peas, id)).unwrap_or_default();
+    let ranked_memories = rank_memories(&memories, &related);
+
+    let pea_entries: Vec<String> = ranked_peas.iter().map(|p| pea_entry(p)).collect();
+    let memory_entries: Vec<String> = ranked_memories.iter().map(|m| memory_entry(m)).collect();
+    let cost = |entry: &String| estimate_tokens(entry) + 1;
+    let (fitted_peas, fitted_memories) = fit(pea_entries, memory_entries, budget, cost, cost);
+
+    println!(
+        "## Open Peas ({} of {}, most relevant first)",
+        fitted_peas.items.len(),
+        ranked_peas.len()
+    );
+    for entry in &fitted_peas.items {
+        println!("{}", entry);
+    }
+    if fitted_peas.omitted > 0 {
+        println!(
+            "... and {} more (use `peas list` for full list)",
+            fitted_peas.omitted
+        );
+    }
+
+    if !memories.is_empty() {
+        println!();
+        println!(
+            "## Memories ({} of {})",
+            fitted_memories.items.len(),
+            memories.len()
+        );
+        for entry in &fitted_memories.items {
+            println!("{}", entry);
+        }
+        if fitted_memories.omitted > 0 {
+            println!(
+                "... and {} more (use `peas memory list` for full list)",
+                fitted_memories.omitted
+            );
+        }
+    }
+    Ok(())
+}
+
+fn pea_entry(pea: &Pea) -> String {
+    let mut entry = format!(
+        "- [{}] {}, {}, {} - {}",
+        pea.id, pea.pea_type, pea.status, pea.priority, pea.title
+    );
+    let body = excerpt(&pea.body, EXCERPT_CHARS);
+    if !body.is_empty() {
+        entry.push_str("\n  ");
+        entry.push_str(&body);
+    }
+    entry
+}
+
+fn memory_entry(memory: &Memory) -> String {
+    let mut entry = format!("- **{}**", memory.key);
+    if !memory.tags.is_empty() {
+        entry.push_str(&format!(" ({})", memory.tags.join(", ")));
+    }
+    let content = excerpt(&memory.content, EXCERPT_CHARS);
+    if !content.is_empty() {
+        entry.push_str(": ");
+        entry.push_str(&content);
+    }
+    entry
+}
@@ -1,4 +1,4 @@
-use crate::graphql::build_schema;
+use crate::graphql::{build_schema, execute};
 use anyhow::Result;
 
 use super::CommandContext;
@@ -13,7 +13,7 @@ pub fn handle_query(ctx: CommandContext, query: String, variables: Option<String
     };
 
     let request = async_graphql::Request::new(&query).variables(vars);
-    let response = tokio::runtime::Runtime::new()?.block_on(schema.execute(request));
+    let response = tokio::runtime::Runtime::new()?.block_on(execute(&schema, request));
 
     println!("{}", serde_json::to_string_pretty(&response)?);
     Ok(())
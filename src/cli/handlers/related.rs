@@ -0,0 +1,45 @@
+use crate::model::related;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::format_pea_line;
+
+pub fn handle_related(ctx: &CommandContext, id: String, count: usize, json: bool) -> Result<()> {
+    let pea = ctx.repo.get(&id)?;
+    let peas = ctx.repo.list()?;
+    let mut found = related(&pea, &peas);
+    found.truncate(count);
+
+    if json {
+        let output: Vec<_> = found
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "pea": r.pea,
+                    "score": r.score(),
+                    "factors": r.factors,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if found.is_empty() {
+        println!("No peas related to {}.", pea.id.cyan());
+        return Ok(());
+    }
+
+    println!("{} {} {}\n", "Related to".bold(), pea.id.cyan(), pea.title);
+    for r in &found {
+        println!(
+            "{} {}",
+            format!("{:>3}", r.score()).dimmed(),
+            format_pea_line(r.pea)
+        );
+        let reasons: Vec<&str> = r.factors.iter().map(|f| f.reason.as_str()).collect();
+        println!("    {}", reasons.join(", ").dimmed());
+    }
+    Ok(())
+}
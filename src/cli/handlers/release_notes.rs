@@ -0,0 +1,171 @@
+use crate::model::{Pea, PeaStatus, PeaType};
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::CommandContext;
+
+/// Changelog sections in output order
+const SECTIONS: [&str; 4] = ["Features", "Fixes", "Chores", "Other"];
+
+pub fn handle_release_notes(
+    ctx: &CommandContext,
+    since: &str,
+    heading: &str,
+    json: bool,
+) -> Result<()> {
+    let since_time = resolve_since(ctx, since)?;
+
+    let mut peas = ctx.repo.list()?;
+    peas.extend(ctx.repo.list_archived()?);
+    let mut completed: Vec<Pea> = peas
+        .into_iter()
+        .filter(|p| {
+            // Peas already linked to a release by `peas changelog sync` are not repeated
+            p.status == PeaStatus::Completed
+                && p.released_in.is_none()
+                && p.updated >= since_time
+                && section(p.pea_type).is_some()
+        })
+        .collect();
+    completed.sort_by_key(|p| p.updated);
+
+    if json {
+        let output: Vec<_> = completed
+            .iter()
+            .map(|p| {
+                serde_json::json!({
+                    "id": p.id,
+                    "title": p.title,
+                    "type": p.pea_type,
+                    "section": section(p.pea_type),
+                    "completed": p.updated,
+                    "references": p.external_refs,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    print!("{}", render(heading, &completed));
+    Ok(())
+}
+
+/// Start of the window: a date, an RFC 3339 timestamp, or a git tag/commit
+fn resolve_since(ctx: &CommandContext, since: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(since, "%Y-%m-%d") {
+        return Ok(date.and_time(chrono::NaiveTime::MIN).and_utc());
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(since) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    Ok(crate::git::ref_date(&ctx.root, since)?)
+}
+
+/// Changelog section for a pea type; milestones are releases themselves and are left out
+fn section(pea_type: PeaType) -> Option<&'static str> {
+    match pea_type {
+        PeaType::Feature | PeaType::Story | PeaType::Epic => Some("Features"),
+        PeaType::Bug => Some("Fixes"),
+        PeaType::Chore => Some("Chores"),
+        PeaType::Task | PeaType::Research => Some("Other"),
+        PeaType::Milestone => None,
+    }
+}
+
+/// Markdown for a changelog entry, one `###` section per non-empty group
+fn render(heading: &str, peas: &[Pea]) -> String {
+    let mut out = format!("## {}\n", heading);
+    if peas.is_empty() {
+        out.push_str("\nNo completed peas in this range.\n");
+        return out;
+    }
+
+    for name in SECTIONS {
+        let items: Vec<&Pea> = peas
+            .iter()
+            .filter(|p| section(p.pea_type) == Some(name))
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("\n### {}\n\n", name));
+        for pea in items {
+            let mut line = format!("- {} ({})", pea.title, pea.id);
+            if !pea.external_refs.is_empty() {
+                let refs: Vec<String> = pea.external_refs.iter().map(|r| format_ref(r)).collect();
+                line.push_str(&format!(" - {}", refs.join(", ")));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Issue and pull request URLs become short `[#N](url)` links; other URLs are autolinked
+fn format_ref(reference: &str) -> String {
+    if !reference.starts_with("http://") && !reference.starts_with("https://") {
+        return reference.to_string();
+    }
+    let mut segments = reference.trim_end_matches('/').rsplit('/');
+    let number = segments.next().unwrap_or_default();
+    let kind = segments.next().unwrap_or_default();
+    let is_number = !number.is_empty() && number.chars().all(|c| c.is_ascii_digit());
+    if is_number && matches!(kind, "issues" | "pull" | "merge_requests") {
+        return format!("[#{}]({})", number, reference);
+    }
+    // Jira-style keys such as PROJ-123 at the end of a browse URL
+    if kind == "browse"
+        && number.split_once('-').is_some_and(|(key, n)| {
+            !key.is_empty() && !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())
+        })
+    {
+        return format!("[{}]({})", number, reference);
+    }
+    format!("<{}>", reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_ref() {
+        assert_eq!(
+            format_ref("https://github.com/o/r/issues/12"),
+            "[#12](https://github.com/o/r/issues/12)"
+        );
+        assert_eq!(
+            format_ref("https://gitlab.com/o/r/-/merge_requests/7/"),
+            "[#7](https://gitlab.com/o/r/-/merge_requests/7/)"
+        );
+        assert_eq!(
+            format_ref("https://acme.atlassian.net/browse/PROJ-9"),
+            "[PROJ-9](https://acme.atlassian.net/browse/PROJ-9)"
+        );
+        assert_eq!(
+            format_ref("https://example.com/x"),
+            "<https://example.com/x>"
+        );
+        assert_eq!(format_ref("PROJ-9"), "PROJ-9");
+    }
+
+    #[test]
+    fn test_render_groups_by_section() {
+        let peas = vec![
+            Pea::new("p-1".into(), "Fix crash".into(), PeaType::Bug)
+                .with_external_refs(vec!["https://github.com/o/r/pull/3".into()]),
+            Pea::new("p-2".into(), "Add export".into(), PeaType::Feature),
+            Pea::new("p-3".into(), "Bump deps".into(), PeaType::Chore),
+        ];
+        assert_eq!(
+            render("1.2.0", &peas),
+            "## 1.2.0\n\n\
+             ### Features\n\n- Add export (p-2)\n\n\
+             ### Fixes\n\n- Fix crash (p-1) - [#3](https://github.com/o/r/pull/3)\n\n\
+             ### Chores\n\n- Bump deps (p-3)\n"
+        );
+        assert!(render("x", &[]).contains("No completed peas"));
+    }
+}
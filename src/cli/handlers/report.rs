@@ -0,0 +1,439 @@
+use crate::cli::commands::{ReportAction, ReportFormatArg};
+use crate::error::PeasError;
+use crate::flow::{Distribution, days, flow_times};
+use crate::history::{StatusState, status_history};
+use crate::model::{Pea, PeaStatus, PeaType, SizeScale, descendants};
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, NaiveTime, Utc};
+use colored::{ColoredString, Colorize};
+use std::collections::BTreeMap;
+
+use super::CommandContext;
+use super::stats::{distribution_json, format_duration, full_history};
+
+/// Statuses from the bottom of a cumulative flow chart to the top, with their chart characters
+const BANDS: [(PeaStatus, char); 5] = [
+    (PeaStatus::Completed, '█'),
+    (PeaStatus::Scrapped, 'x'),
+    (PeaStatus::InProgress, '▓'),
+    (PeaStatus::Todo, '▒'),
+    (PeaStatus::Draft, '░'),
+];
+
+/// Width of the longest bar in the chart
+const CHART_WIDTH: usize = 50;
+
+/// Number of peas per status at the end of one day, in [`BANDS`] order
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Day {
+    date: NaiveDate,
+    counts: [usize; BANDS.len()],
+}
+
+impl Day {
+    fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+pub fn handle_report(ctx: &CommandContext, action: ReportAction) -> Result<()> {
+    match action {
+        ReportAction::Cfd { weeks, format } => handle_report_cfd(ctx, weeks, format),
+        ReportAction::Accuracy { format } => handle_report_accuracy(ctx, format),
+    }
+}
+
+fn handle_report_cfd(ctx: &CommandContext, weeks: u32, format: ReportFormatArg) -> Result<()> {
+    if weeks == 0 {
+        return Err(PeasError::validation("weeks", "--weeks must be at least 1").into());
+    }
+    let today = Utc::now().date_naive();
+    let first = today - Duration::days(i64::from(weeks) * 7 - 1);
+    let since = first.and_time(NaiveTime::MIN).and_utc();
+
+    let history = status_history(&ctx.config, &ctx.root, since)?;
+    let days = cfd(&history, first, today);
+
+    match format {
+        ReportFormatArg::Csv => {
+            let header: Vec<String> = BANDS.iter().map(|(s, _)| s.to_string()).collect();
+            println!("date,{}", header.join(","));
+            for day in &days {
+                let counts: Vec<String> = day.counts.iter().map(ToString::to_string).collect();
+                println!("{},{}", day.date, counts.join(","));
+            }
+        }
+        ReportFormatArg::Json => {
+            let output: Vec<_> = days
+                .iter()
+                .map(|day| {
+                    let mut row = serde_json::Map::new();
+                    row.insert("date".to_string(), day.date.to_string().into());
+                    for ((status, _), count) in BANDS.iter().zip(day.counts) {
+                        row.insert(status.to_string(), count.into());
+                    }
+                    serde_json::Value::Object(row)
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        ReportFormatArg::Chart => print!("{}", chart(&days)),
+    }
+    Ok(())
+}
+
+/// Counts for every day from `first` to `last`, from the last state recorded by the end of each day
+fn cfd(history: &[StatusState], first: NaiveDate, last: NaiveDate) -> Vec<Day> {
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN);
+    first
+        .iter_days()
+        .take_while(|date| *date <= last)
+        .map(|date| {
+            let end = date.and_time(end_of_day).and_utc();
+            let mut counts = [0; BANDS.len()];
+            if let Some(state) = history.iter().rfind(|s| s.time <= end) {
+                for pea in state.peas.values() {
+                    if let Some(band) = BANDS.iter().position(|(s, _)| *s == pea.status) {
+                        counts[band] += 1;
+                    }
+                }
+            }
+            Day { date, counts }
+        })
+        .collect()
+}
+
+/// Stacked bars for the last day of each week, completed work at the left
+fn chart(days: &[Day]) -> String {
+    let legend: Vec<String> = BANDS
+        .iter()
+        .map(|(status, symbol)| format!("{} {}", paint(*status, &symbol.to_string()), status))
+        .collect();
+    let mut out = format!("{}\n\n", legend.join("  "));
+
+    let max = days.iter().map(Day::total).max().unwrap_or(0).max(1);
+    let mut weekly: Vec<&Day> = days.iter().rev().step_by(7).collect();
+    weekly.reverse();
+    for day in weekly {
+        let mut bar = String::new();
+        let mut cumulative = 0;
+        let mut drawn = 0;
+        for ((status, symbol), count) in BANDS.iter().zip(day.counts) {
+            cumulative += count;
+            // Round the band edges, not the bands, so bars add up to the total
+            let edge = (cumulative * CHART_WIDTH + max / 2) / max;
+            let segment = symbol.to_string().repeat(edge - drawn);
+            bar.push_str(&paint(*status, &segment).to_string());
+            drawn = edge;
+        }
+        out.push_str(&format!(
+            "{} │{}{} {}\n",
+            day.date,
+            bar,
+            " ".repeat(CHART_WIDTH - drawn),
+            day.total()
+        ));
+    }
+    out
+}
+
+fn paint(status: PeaStatus, text: &str) -> ColoredString {
+    match status {
+        PeaStatus::Completed => text.green(),
+        PeaStatus::Scrapped => text.dimmed(),
+        PeaStatus::InProgress => text.yellow(),
+        PeaStatus::Todo => text.blue(),
+        PeaStatus::Draft => text.normal(),
+    }
+}
+
+/// A completed pea with a size, and how long it took
+#[derive(Debug, Clone)]
+struct Sample<'a> {
+    pea: &'a Pea,
+    points: u32,
+    /// Cycle time, or lead time for peas never seen in progress
+    actual: Duration,
+}
+
+/// Estimated points against actual time for one group of samples
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Calibration {
+    group: String,
+    peas: usize,
+    points: u32,
+    actual: Distribution,
+    /// Actual time divided by points, per pea
+    per_point: Distribution,
+}
+
+impl Calibration {
+    fn of(group: String, samples: &[&Sample]) -> Option<Self> {
+        Some(Self {
+            group,
+            peas: samples.len(),
+            points: samples.iter().map(|s| s.points).sum(),
+            actual: Distribution::of(samples.iter().map(|s| s.actual))?,
+            per_point: Distribution::of(samples.iter().map(|s| s.actual / s.points as i32))?,
+        })
+    }
+}
+
+fn handle_report_accuracy(ctx: &CommandContext, format: ReportFormatArg) -> Result<()> {
+    let scale = ctx.config.peas.size_scale;
+    let mut peas = ctx.repo.list()?;
+    peas.extend(ctx.repo.list_archived()?);
+    let times = flow_times(&peas, &full_history(ctx)?);
+    let by_id = |id: &str| peas.iter().find(|p| p.id == id);
+    let (samples, unestimated) = samples(
+        times
+            .iter()
+            .filter_map(|t| Some((by_id(&t.id)?, t.cycle().unwrap_or_else(|| t.lead())))),
+        scale,
+    );
+
+    let by_size = group(&samples, |s| {
+        (s.points, s.pea.size.clone().unwrap_or_default())
+    });
+    let by_type = group(&samples, |s| {
+        (s.pea.pea_type.to_string(), s.pea.pea_type.to_string())
+    });
+    let by_epic: Vec<Calibration> = peas
+        .iter()
+        .filter(|p| p.pea_type == PeaType::Epic)
+        .filter_map(|epic| {
+            let below = descendants(&peas, &epic.id);
+            let samples: Vec<&Sample> = samples
+                .iter()
+                .filter(|s| below.iter().any(|p| p.id == s.pea.id))
+                .collect();
+            Calibration::of(epic.id.clone(), &samples)
+        })
+        .collect();
+    let sections = [("size", by_size), ("type", by_type), ("epic", by_epic)];
+
+    match format {
+        ReportFormatArg::Csv => {
+            println!("by,group,peas,points,actual_p50_days,per_point_p50_days,per_point_p85_days");
+            for (by, rows) in &sections {
+                for row in rows {
+                    println!(
+                        "{},{},{},{},{},{},{}",
+                        by,
+                        row.group,
+                        row.peas,
+                        row.points,
+                        days(row.actual.p50),
+                        days(row.per_point.p50),
+                        days(row.per_point.p85)
+                    );
+                }
+            }
+        }
+        ReportFormatArg::Json => {
+            let mut output = serde_json::json!({
+                "scale": scale,
+                "estimated": samples.len(),
+                "unestimated": unestimated,
+            });
+            for (by, rows) in &sections {
+                output[format!("by_{}", by)] = rows
+                    .iter()
+                    .map(|row| {
+                        serde_json::json!({
+                            "group": row.group,
+                            "peas": row.peas,
+                            "points": row.points,
+                            "actual_days": distribution_json(row.actual),
+                            "per_point_days": distribution_json(row.per_point),
+                        })
+                    })
+                    .collect();
+            }
+            println!("{}", serde_json::to_string_pretty(&output)?);
+        }
+        ReportFormatArg::Chart => {
+            println!(
+                "{} ({} completed with a size on the {} scale, {} without)",
+                "Estimate accuracy".bold(),
+                samples.len(),
+                scale,
+                unestimated
+            );
+            if samples.is_empty() {
+                println!("  {}", "Nothing to compare yet".dimmed());
+                return Ok(());
+            }
+            for (by, rows) in &sections {
+                if rows.is_empty() {
+                    continue;
+                }
+                println!(
+                    "\n  {} {:>5} {:>7} {:>10} {:>16}",
+                    format!("{:<16}", format!("By {}", by)).bold(),
+                    "peas",
+                    "points",
+                    "actual p50",
+                    "per point p50/85"
+                );
+                for row in rows {
+                    // Epics are named by ID, with the title after the numbers
+                    let title = match *by {
+                        "epic" => by_id(&row.group).map_or("", |epic| epic.title.as_str()),
+                        _ => "",
+                    };
+                    println!(
+                        "  {:<16} {:>5} {:>7} {:>10} {:>16} {}",
+                        row.group,
+                        row.peas,
+                        row.points,
+                        format_duration(row.actual.p50),
+                        format!(
+                            "{} / {}",
+                            format_duration(row.per_point.p50),
+                            format_duration(row.per_point.p85)
+                        ),
+                        title.dimmed()
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Completed peas with a non-zero size on `scale`, and the number without one
+fn samples<'a>(
+    completed: impl IntoIterator<Item = (&'a Pea, Duration)>,
+    scale: SizeScale,
+) -> (Vec<Sample<'a>>, usize) {
+    let mut samples = Vec::new();
+    let mut unestimated = 0;
+    for (pea, actual) in completed {
+        match pea.size.as_deref().and_then(|s| scale.points(s)) {
+            Some(points) if points > 0 => samples.push(Sample {
+                pea,
+                points,
+                actual,
+            }),
+            _ => unestimated += 1,
+        }
+    }
+    (samples, unestimated)
+}
+
+/// One calibration per group, ordered by the first element of `key`, named by the second
+fn group<K: Ord>(samples: &[Sample], key: impl Fn(&Sample) -> (K, String)) -> Vec<Calibration> {
+    let mut groups: BTreeMap<K, (String, Vec<&Sample>)> = BTreeMap::new();
+    for sample in samples {
+        let (key, name) = key(sample);
+        groups
+            .entry(key)
+            .or_insert_with(|| (name, Vec::new()))
+            .1
+            .push(sample);
+    }
+    groups
+        .into_values()
+        .filter_map(|(name, samples)| Calibration::of(name, &samples))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::PeaState;
+    use chrono::{DateTime, TimeZone};
+
+    fn state(time: DateTime<Utc>, statuses: &[(&str, PeaStatus)]) -> StatusState {
+        StatusState {
+            time,
+            peas: statuses
+                .iter()
+                .map(|(id, status)| {
+                    let state = PeaState {
+                        status: *status,
+                        updated: time,
+                    };
+                    (id.to_string(), state)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_accuracy_samples_and_groups() {
+        let pea = |id: &str, pea_type, size: Option<&str>| {
+            let mut pea = Pea::new(id.into(), id.into(), pea_type);
+            pea.size = size.map(String::from);
+            pea
+        };
+        let peas = [
+            pea("a", PeaType::Task, Some("2")),
+            pea("b", PeaType::Task, Some("2")),
+            pea("c", PeaType::Bug, Some("13")),
+            pea("d", PeaType::Bug, None),
+            pea("e", PeaType::Bug, Some("0")),
+        ];
+        let actual = [4, 8, 26, 1, 1].map(Duration::days);
+        let (samples, unestimated) = samples(peas.iter().zip(actual), SizeScale::Fibonacci);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(unestimated, 2);
+
+        // Numerically, so 13 comes after 2
+        let by_size = group(&samples, |s| (s.points, s.pea.size.clone().unwrap()));
+        let groups: Vec<&str> = by_size.iter().map(|c| c.group.as_str()).collect();
+        assert_eq!(groups, ["2", "13"]);
+        assert_eq!(by_size[0].peas, 2);
+        assert_eq!(by_size[0].points, 4);
+        assert_eq!(by_size[0].actual.p50, Duration::days(4));
+        assert_eq!(by_size[0].per_point.p50, Duration::days(2));
+        assert_eq!(by_size[0].per_point.max, Duration::days(4));
+        assert_eq!(by_size[1].per_point.p50, Duration::days(2));
+
+        let by_type = group(&samples, |s| {
+            (s.pea.pea_type.to_string(), s.pea.pea_type.to_string())
+        });
+        assert_eq!(by_type[0].group, "bug");
+        assert_eq!(by_type[1].points, 4);
+    }
+
+    #[test]
+    fn test_cfd_days_and_chart() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+        let history = vec![
+            state(at(2, 9), &[("a", PeaStatus::Todo)]),
+            state(
+                at(4, 9),
+                &[("a", PeaStatus::InProgress), ("b", PeaStatus::Todo)],
+            ),
+            state(
+                at(4, 18),
+                &[("a", PeaStatus::Completed), ("b", PeaStatus::Todo)],
+            ),
+        ];
+        let date = |day| NaiveDate::from_ymd_opt(2026, 3, day).unwrap();
+        let days = cfd(&history, date(1), date(8));
+
+        assert_eq!(days.len(), 8);
+        assert_eq!(days[0].counts, [0, 0, 0, 0, 0]);
+        assert_eq!(days[1].counts, [0, 0, 0, 1, 0]);
+        assert_eq!(days[2].counts, [0, 0, 0, 1, 0]);
+        // The last state of the day wins
+        assert_eq!(days[3].counts, [1, 0, 0, 1, 0]);
+        assert_eq!(days[7].counts, [1, 0, 0, 1, 0]);
+
+        // Without the color codes
+        let chart = regex::Regex::new("\x1b\\[[0-9;]*m")
+            .unwrap()
+            .replace_all(&chart(&days), "")
+            .to_string();
+        let rows: Vec<&str> = chart.lines().skip(2).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0], format!("2026-03-01 │{} 0", " ".repeat(50)));
+        assert_eq!(
+            rows[1],
+            format!("2026-03-08 │{}{} 2", "█".repeat(25), "▒".repeat(25))
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use crate::autotag::AutoTags;
+use crate::error::PeasError;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::UndoBatch;
+
+pub fn handle_retag(ctx: &CommandContext, dry_run: bool, json: bool) -> Result<()> {
+    let autotags = AutoTags::new(&ctx.config.autotag);
+    if autotags.is_empty() {
+        return Err(PeasError::validation(
+            "autotag",
+            "No auto-tagging rules: add [autotag.<name>] sections to .peas/config.toml",
+        )
+        .into());
+    }
+
+    let mut batch = UndoBatch::new(ctx, "Apply autotag rules");
+    let mut updated = Vec::new();
+    for pea in ctx.repo.list()? {
+        let Some(mut tagged) = autotags.apply(&pea)? else {
+            continue;
+        };
+        let added: Vec<String> = tagged
+            .tags
+            .iter()
+            .filter(|t| !pea.tags.contains(t))
+            .cloned()
+            .collect();
+        let component = tagged.component.clone().filter(|_| pea.component.is_none());
+        if !dry_run {
+            batch.update(&mut tagged)?;
+        }
+        updated.push((tagged.id, added, component));
+    }
+
+    if json {
+        let updated: Vec<_> = updated
+            .iter()
+            .map(|(id, tags, component)| {
+                serde_json::json!({
+                    "id": id,
+                    "tags": tags,
+                    "component": component,
+                })
+            })
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "updated": updated,
+                "dry_run": dry_run,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if updated.is_empty() {
+        println!("No peas need new tags or components.");
+        return Ok(());
+    }
+    for (id, tags, component) in &updated {
+        let mut changes: Vec<String> = tags
+            .iter()
+            .map(|t| format!("+{}", t).magenta().to_string())
+            .collect();
+        if let Some(component) = component {
+            changes.push(format!("component {}", component.magenta()));
+        }
+        println!("  {}  {}", id.cyan(), changes.join(" "));
+    }
+    let verb = if dry_run { "Would retag" } else { "Retagged" };
+    println!("{} {} pea(s)", verb.green(), updated.len());
+    Ok(())
+}
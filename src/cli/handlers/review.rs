@@ -0,0 +1,192 @@
+use crate::model::{Pea, PeaPriority, PeaStatus};
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use colored::Colorize;
+use std::io::{self, BufRead};
+
+use super::CommandContext;
+use super::utils::{UndoBatch, format_priority, format_status, prompt};
+
+/// Why a pea comes up in the review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Stale,
+    Unprioritized,
+    Due,
+}
+
+impl Section {
+    const ALL: [Section; 3] = [Section::Stale, Section::Unprioritized, Section::Due];
+
+    fn key(self) -> &'static str {
+        match self {
+            Section::Stale => "stale",
+            Section::Unprioritized => "unprioritized",
+            Section::Due => "due",
+        }
+    }
+
+    fn heading(self, days: i64) -> String {
+        match self {
+            Section::Stale => format!("In progress without an update for {} days", days),
+            Section::Unprioritized => format!("Todo at the default priority for {} days", days),
+            Section::Due => format!("Due within {} days", days),
+        }
+    }
+}
+
+/// The section a pea is reviewed in, if any; each pea comes up only once
+fn section(pea: &Pea, days: i64) -> Option<Section> {
+    let now = Utc::now();
+    let window = Duration::days(days);
+    if pea.status == PeaStatus::InProgress && now - pea.updated >= window {
+        Some(Section::Stale)
+    } else if pea.status == PeaStatus::Todo
+        && pea.priority == PeaPriority::Normal
+        && now - pea.created >= window
+    {
+        Some(Section::Unprioritized)
+    } else if pea.is_open()
+        && pea
+            .days_remaining(now.date_naive())
+            .is_some_and(|left| left <= days)
+    {
+        Some(Section::Due)
+    } else {
+        None
+    }
+}
+
+/// One priority level up, or `None` at critical
+fn bumped(priority: PeaPriority) -> Option<PeaPriority> {
+    match priority {
+        PeaPriority::Critical => None,
+        PeaPriority::High => Some(PeaPriority::Critical),
+        PeaPriority::Normal => Some(PeaPriority::High),
+        PeaPriority::Low => Some(PeaPriority::Normal),
+        PeaPriority::Deferred => Some(PeaPriority::Low),
+    }
+}
+
+pub fn handle_review(ctx: &CommandContext, days: i64, list: bool, json: bool) -> Result<()> {
+    let peas = ctx.repo.list()?;
+    let mut sections: Vec<(Section, Vec<Pea>)> =
+        Section::ALL.iter().map(|s| (*s, Vec::new())).collect();
+    for pea in peas {
+        if let Some(section) = section(&pea, days) {
+            sections[section as usize].1.push(pea);
+        }
+    }
+    // Closest due date first
+    sections[Section::Due as usize]
+        .1
+        .sort_by_key(|p| p.target_date);
+
+    if json {
+        let mut result = serde_json::Map::new();
+        for (section, peas) in &sections {
+            result.insert(section.key().to_string(), serde_json::to_value(peas)?);
+        }
+        result.insert("days".to_string(), days.into());
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+    let total: usize = sections.iter().map(|(_, peas)| peas.len()).sum();
+    if total == 0 {
+        println!("Nothing to review.");
+        return Ok(());
+    }
+    if list {
+        for (section, peas) in sections.iter().filter(|(_, peas)| !peas.is_empty()) {
+            println!("{}", section.heading(days).bold());
+            for pea in peas {
+                print_item(pea);
+            }
+            println!();
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut batch = UndoBatch::new(ctx, "Review");
+    let (mut changed, mut kept) = (0, 0);
+    let mut seen = 0;
+    'review: for (section, peas) in sections {
+        if peas.is_empty() {
+            continue;
+        }
+        println!("\n{}", section.heading(days).bold());
+        for mut pea in peas {
+            seen += 1;
+            print!("{} ", format!("[{}/{}]", seen, total).dimmed());
+            print_item(&pea);
+            let action = loop {
+                let question = "b: bump priority, d: defer, s: scrap, enter: keep, q: quit";
+                let Some(answer) = prompt(&mut lines, question)? else {
+                    break 'review;
+                };
+                match answer.as_str() {
+                    "" | "k" => break None,
+                    "q" => break 'review,
+                    "b" => match bumped(pea.priority) {
+                        Some(priority) => {
+                            pea.priority = priority;
+                            break Some(format!("Bumped to {}", priority));
+                        }
+                        None => println!("  {}", "Already critical".yellow()),
+                    },
+                    "d" => {
+                        // Deferred work is no longer in progress
+                        pea.priority = PeaPriority::Deferred;
+                        if pea.status == PeaStatus::InProgress {
+                            pea.status = PeaStatus::Todo;
+                        }
+                        break Some("Deferred".to_string());
+                    }
+                    "s" => {
+                        pea.status = PeaStatus::Scrapped;
+                        break Some("Scrapped".to_string());
+                    }
+                    answer => println!("  {}", format!("Unknown action '{}'", answer).red()),
+                }
+            };
+            let Some(done) = action else {
+                kept += 1;
+                continue;
+            };
+            if let Err(e) = batch.update(&mut pea) {
+                println!("  {}", e.to_string().red());
+                kept += 1;
+                continue;
+            }
+            changed += 1;
+            println!("  {} {}", done.green(), pea.id.cyan());
+        }
+    }
+
+    println!(
+        "\n{} {} pea(s): {} changed, {} kept",
+        "Reviewed".green(),
+        changed + kept,
+        changed,
+        kept
+    );
+    Ok(())
+}
+
+fn print_item(pea: &Pea) {
+    let due = pea
+        .target_date
+        .map(|d| format!(" due {}", d).yellow().to_string())
+        .unwrap_or_default();
+    println!(
+        "{} {} {} {}{} {}",
+        pea.id.cyan(),
+        format_status(pea.status),
+        format_priority(pea.priority),
+        pea.title,
+        due,
+        format!("(updated {})", pea.updated.format("%Y-%m-%d")).dimmed()
+    );
+}
@@ -1,10 +1,16 @@
-use crate::model::{PeaStatus, PeaType};
+use crate::model::{PeaStatus, PeaType, SizeTotals, descendants};
 use anyhow::Result;
+use colored::Colorize;
 
 use super::CommandContext;
+use super::utils::{format_days_remaining, format_size_totals};
 
 pub fn handle_roadmap(ctx: &CommandContext) -> Result<()> {
     let peas = ctx.repo.list()?;
+    let today = chrono::Utc::now().date_naive();
+    let scale = ctx.config.peas.size_scale;
+    // Point roll-ups are only shown once something is estimated
+    let sized = peas.iter().any(|p| p.size.is_some());
     let milestones: Vec<_> = peas
         .iter()
         .filter(|p| p.pea_type == PeaType::Milestone)
@@ -14,6 +20,35 @@ pub fn handle_roadmap(ctx: &CommandContext) -> Result<()> {
 
     for milestone in &milestones {
         println!("## Milestone: {} ({})\n", milestone.title, milestone.id);
+        if let Some(target) = milestone.target_date {
+            match milestone
+                .days_remaining(today)
+                .filter(|_| milestone.is_open())
+            {
+                Some(days) if days < 0 => {
+                    println!(
+                        "**Target:** {} (**{}**)\n",
+                        target,
+                        format_days_remaining(days)
+                    );
+                    // Also on stderr, so it shows when the roadmap is redirected to a file
+                    eprintln!(
+                        "{}: milestone {} is {}",
+                        "warning".yellow().bold(),
+                        milestone.id,
+                        format_days_remaining(days)
+                    );
+                }
+                Some(days) => {
+                    println!("**Target:** {} ({})\n", target, format_days_remaining(days))
+                }
+                None => println!("**Target:** {}\n", target),
+            }
+        }
+        if sized {
+            let totals = SizeTotals::of(descendants(&peas, &milestone.id), scale);
+            println!("**Size:** {}\n", format_size_totals(totals));
+        }
         if !milestone.body.is_empty() {
             println!("> {}\n", milestone.body.lines().next().unwrap_or(""));
         }
@@ -25,6 +60,10 @@ pub fn handle_roadmap(ctx: &CommandContext) -> Result<()> {
 
         for epic in &epics {
             println!("### Epic: {} ({})\n", epic.title, epic.id);
+            if sized {
+                let totals = SizeTotals::of(descendants(&peas, &epic.id), scale);
+                println!("**Size:** {}\n", format_size_totals(totals));
+            }
             if !epic.body.is_empty() {
                 println!("> {}\n", epic.body.lines().next().unwrap_or(""));
             }
@@ -40,7 +79,13 @@ pub fn handle_roadmap(ctx: &CommandContext) -> Result<()> {
                     PeaStatus::InProgress => "[-]",
                     _ => "[ ]",
                 };
-                println!("- {} {} ({})", status_icon, task.title, task.id);
+                match &task.size {
+                    Some(size) => println!(
+                        "- {} {} ({}, size {})",
+                        status_icon, task.title, task.id, size
+                    ),
+                    None => println!("- {} {} ({})", status_icon, task.title, task.id),
+                }
             }
             println!();
         }
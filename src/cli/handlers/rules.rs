@@ -0,0 +1,192 @@
+use crate::cli::commands::RulesAction;
+use crate::error::PeasError;
+use crate::model::Pea;
+use crate::rules::Rules;
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use super::CommandContext;
+
+/// What a rule would do to one pea
+enum Outcome {
+    /// Field, old and new value
+    Changes(Vec<(String, Value, Value)>),
+    /// The rule threw or failed
+    Fails(String),
+}
+
+pub fn handle_rules(ctx: &CommandContext, action: RulesAction) -> Result<()> {
+    match action {
+        RulesAction::List { json } => handle_rules_list(ctx, json),
+        RulesAction::Test { rule, json } => handle_rules_test(ctx, rule.as_deref(), json),
+    }
+}
+
+fn handle_rules_list(ctx: &CommandContext, json: bool) -> Result<()> {
+    if json {
+        let rules: Vec<_> = ctx
+            .config
+            .rules
+            .iter()
+            .map(|(name, rule)| {
+                serde_json::json!({
+                    "name": name,
+                    "on": rule.on,
+                    "when": rule.when,
+                    "then": rule.then,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&rules)?);
+        return Ok(());
+    }
+
+    if ctx.config.rules.is_empty() {
+        println!("No rules configured. Add [rules.<name>] sections to .peas/config.toml.");
+        return Ok(());
+    }
+    for (name, rule) in &ctx.config.rules {
+        let on: Vec<String> = rule.on.iter().map(ToString::to_string).collect();
+        println!("{} (on {})", name.cyan().bold(), on.join(", "));
+        if let Some(when) = &rule.when {
+            println!("  {} {}", "when".dimmed(), when);
+        }
+        println!("  {} {}", "then".dimmed(), rule.then);
+    }
+    Ok(())
+}
+
+fn handle_rules_test(ctx: &CommandContext, only: Option<&str>, json: bool) -> Result<()> {
+    let rules = Rules::new(&ctx.config.rules);
+    let compiled = rules.compiled()?;
+    if let Some(name) = only
+        && !compiled.iter().any(|r| r.name == name)
+    {
+        anyhow::bail!("No rule named '{}'", name);
+    }
+    let peas = ctx.repo.list()?;
+
+    let mut report = Vec::new();
+    for rule in compiled
+        .iter()
+        .filter(|r| only.is_none_or(|name| name == r.name))
+    {
+        let mut results = Vec::new();
+        for pea in &peas {
+            match rules.run(rule, pea) {
+                Ok(None) => {}
+                Ok(Some(changed)) => results.push((pea, Outcome::Changes(changes(pea, &changed)))),
+                Err(PeasError::Rule { message, .. }) => {
+                    results.push((pea, Outcome::Fails(message)))
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        report.push((rule, results));
+    }
+
+    if json {
+        let output: Vec<_> = report
+            .iter()
+            .map(|(rule, results)| {
+                let results: Vec<_> = results
+                    .iter()
+                    .map(|(pea, outcome)| match outcome {
+                        Outcome::Changes(changes) => serde_json::json!({
+                            "id": pea.id,
+                            "title": pea.title,
+                            "changes": changes
+                                .iter()
+                                .map(|(field, from, to)| {
+                                    serde_json::json!({ "field": field, "from": from, "to": to })
+                                })
+                                .collect::<Vec<_>>(),
+                        }),
+                        Outcome::Fails(message) => serde_json::json!({
+                            "id": pea.id,
+                            "title": pea.title,
+                            "error": message,
+                        }),
+                    })
+                    .collect();
+                serde_json::json!({
+                    "rule": rule.name,
+                    "on": rule.on,
+                    "matched": results.len(),
+                    "results": results,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if report.is_empty() {
+        println!("No rules configured. Add [rules.<name>] sections to .peas/config.toml.");
+        return Ok(());
+    }
+    for (rule, results) in &report {
+        let on: Vec<String> = rule.on.iter().map(ToString::to_string).collect();
+        println!("{} (on {})", rule.name.cyan().bold(), on.join(", "));
+        for (pea, outcome) in results {
+            println!("  {} {}", pea.id.cyan(), pea.title);
+            match outcome {
+                Outcome::Changes(changes) if changes.is_empty() => {
+                    println!("    {}", "no change".dimmed())
+                }
+                Outcome::Changes(changes) => {
+                    for (field, from, to) in changes {
+                        if field == "body" {
+                            println!("    body: {}", "changed".yellow());
+                        } else {
+                            println!(
+                                "    {}: {} → {}",
+                                field,
+                                format_value(from).dimmed(),
+                                format_value(to).green()
+                            );
+                        }
+                    }
+                }
+                Outcome::Fails(message) => println!("    {} {}", "fails:".red(), message),
+            }
+        }
+        println!(
+            "  {} of {} pea(s) match",
+            results.len().to_string().yellow(),
+            peas.len()
+        );
+    }
+    Ok(())
+}
+
+/// Fields that differ between two versions of a pea
+fn changes(before: &Pea, after: &Pea) -> Vec<(String, Value, Value)> {
+    let fields = |pea: &Pea| {
+        let mut value = serde_json::to_value(pea).unwrap_or_default();
+        if let Some(map) = value.as_object_mut() {
+            map.insert("body".to_string(), pea.body.to_string().into());
+        }
+        value.as_object().cloned().unwrap_or_default()
+    };
+    let (before, after) = (fields(before), fields(after));
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let from = before.get(name).cloned().unwrap_or(Value::Null);
+            let to = after.get(name).cloned().unwrap_or(Value::Null);
+            (from != to).then(|| (name.clone(), from, to))
+        })
+        .collect()
+}
+
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => "(none)".to_string(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -1,15 +1,23 @@
 use anyhow::Result;
+use colored::Colorize;
 
 use super::CommandContext;
-use super::utils::print_pea_list;
-use crate::search::SearchQuery;
+use super::utils::format_pea_line;
+use crate::search::RankedSearch;
 
-pub fn handle_search(ctx: &CommandContext, query: String, json: bool) -> Result<()> {
-    let peas = ctx.repo.list()?;
-
-    // Parse search query (supports field-specific and regex)
-    let search_query = match SearchQuery::parse(&query) {
-        Ok(q) => q,
+pub fn handle_search(
+    ctx: &CommandContext,
+    query: String,
+    fuzzy: bool,
+    semantic: bool,
+    json: bool,
+) -> Result<()> {
+    if semantic {
+        return handle_semantic_search(ctx, &query, json);
+    }
+    // Parse search query (supports field-specific and regex terms, phrases and exclusions)
+    let search_query = match RankedSearch::parse(&query) {
+        Ok(q) => q.with_fuzzy(fuzzy),
         Err(e) => {
             tracing::error!(query = %query, error = %e, "Invalid search query");
             eprintln!("Invalid search query: {}", e);
@@ -19,20 +27,93 @@ pub fn handle_search(ctx: &CommandContext, query: String, json: bool) -> Result<
             eprintln!("  peas search tag:urgent       # Search in tags");
             eprintln!("  peas search regex:bug.*fix   # Regex search");
             eprintln!("  peas search title:regex:.*   # Regex in specific field");
+            eprintln!("  peas search '\"login page\"'   # Exact phrase");
+            eprintln!("  peas search login -tag:wontfix   # Exclude matches");
+            eprintln!("  peas search --fuzzy authentcation   # Tolerate typos");
             return Err(anyhow::anyhow!(e));
         }
     };
 
-    let results: Vec<_> = peas
-        .into_iter()
-        .filter(|p| search_query.matches_pea(p))
-        .collect();
+    // Bodies are read only when a term can match them, otherwise just for the hits
+    let peas = if search_query.needs_body() {
+        ctx.repo.list()?
+    } else {
+        ctx.repo.list_summaries()?
+    };
+    let mut hits = search_query.rank(peas, &ctx.repo.aliases()?);
+    for hit in hits.iter_mut().filter(|hit| !hit.pea.body.is_loaded()) {
+        ctx.repo.load_body(&mut hit.pea)?;
+        hit.snippet = search_query.snippet(&hit.pea.body);
+    }
+
+    if json {
+        let results = hits
+            .iter()
+            .map(|hit| {
+                let mut value = serde_json::to_value(&hit.pea)?;
+                value["score"] = hit.score.into();
+                value["snippet"] = hit.snippet.as_ref().map(|s| s.marked("**", "**")).into();
+                Ok(value)
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        println!("Found {} results for '{}':\n", hits.len(), query);
+        if hits.is_empty() {
+            println!("No peas found.");
+        }
+        for hit in &hits {
+            println!("{}", format_pea_line(&hit.pea));
+            if let Some(snippet) = &hit.snippet {
+                let mut line = String::new();
+                let mut last = 0;
+                for range in &snippet.matches {
+                    line.push_str(&snippet.text[last..range.start].dimmed().to_string());
+                    line.push_str(&snippet.text[range.clone()].yellow().bold().to_string());
+                    last = range.end;
+                }
+                line.push_str(&snippet.text[last..].dimmed().to_string());
+                println!("    {}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "semantic")]
+fn handle_semantic_search(ctx: &CommandContext, query: &str, json: bool) -> Result<()> {
+    use crate::semantic::{DEFAULT_LIMIT, SemanticIndex};
+
+    let index = SemanticIndex::new(&ctx.config.data_path(&ctx.root), &ctx.config.semantic)?;
+    let hits = index.search(query, ctx.repo.list()?, DEFAULT_LIMIT)?;
 
     if json {
+        let results = hits
+            .iter()
+            .map(|hit| {
+                let mut value = serde_json::to_value(&hit.pea)?;
+                value["similarity"] = hit.similarity.into();
+                Ok(value)
+            })
+            .collect::<Result<Vec<_>, serde_json::Error>>()?;
         println!("{}", serde_json::to_string_pretty(&results)?);
     } else {
-        println!("Found {} results for '{}':\n", results.len(), query);
-        print_pea_list(&results);
+        println!("Found {} similar peas for '{}':\n", hits.len(), query);
+        if hits.is_empty() {
+            println!("No peas found.");
+        }
+        for hit in &hits {
+            println!(
+                "{} {}",
+                format!("{:.2}", hit.similarity).dimmed(),
+                format_pea_line(&hit.pea)
+            );
+        }
     }
     Ok(())
 }
+
+#[cfg(not(feature = "semantic"))]
+fn handle_semantic_search(_ctx: &CommandContext, _query: &str, _json: bool) -> Result<()> {
+    Err(crate::error::PeasError::missing_feature("semantic").into())
+}
@@ -1,45 +1,271 @@
-use crate::graphql::build_schema;
+use crate::{
+    config::PeasConfig,
+    graphql::{
+        build_schema, defer, metrics::Metrics, persisted, rate_limit::RateLimiter, viewer, web_ui,
+    },
+    model::PeaStatus,
+    storage::{MemoryRepository, PeaRepository},
+};
 use anyhow::Result;
+use std::{path::PathBuf, sync::Arc};
 
 use super::CommandContext;
 
-pub fn handle_serve(ctx: CommandContext, port: u16) -> Result<()> {
+pub fn handle_serve(ctx: CommandContext, port: u16, viewer: bool) -> Result<()> {
+    persisted::validate(&ctx.config.server).map_err(anyhow::Error::msg)?;
+
+    let state = Arc::new(ServerState {
+        metrics: Metrics::new(),
+        rate_limiter: RateLimiter::new(ctx.config.server.rate_limit),
+        max_body_bytes: ctx.config.server.max_body_bytes,
+        config: ctx.config.clone(),
+        root: ctx.root.clone(),
+        viewer,
+    });
     let schema = build_schema(ctx.config, ctx.root);
 
     println!("Starting GraphQL server on http://localhost:{}", port);
     println!("GraphQL Playground: http://localhost:{}", port);
+    println!("Metrics: http://localhost:{}/metrics", port);
+    println!("Web UI: http://localhost:{}/ui", port);
+    if viewer {
+        println!("Viewer (read-only): http://localhost:{}/view", port);
+    }
+    if state.config.server.persisted_only {
+        println!(
+            "Persisted queries only: {}",
+            state
+                .config
+                .server
+                .persisted_queries
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
 
-    tokio::runtime::Runtime::new()?.block_on(async { run_server(schema, port).await })?;
+    tokio::runtime::Runtime::new()?.block_on(async { run_server(schema, state, port).await })?;
     Ok(())
 }
 
-async fn run_server(schema: crate::graphql::PeasSchema, port: u16) -> Result<()> {
+/// Request header naming the person or agent behind a GraphQL request
+const ACTOR_HEADER: &str = "x-peas-actor";
+
+/// Shared state for the non-GraphQL routes
+struct ServerState {
+    metrics: Metrics,
+    /// None when rate limiting is disabled
+    rate_limiter: Option<RateLimiter>,
+    max_body_bytes: usize,
+    config: PeasConfig,
+    root: PathBuf,
+    /// Serve the read-only HTML viewer
+    viewer: bool,
+}
+
+async fn run_server(
+    schema: crate::graphql::PeasSchema,
+    state: Arc<ServerState>,
+    port: u16,
+) -> Result<()> {
+    use async_graphql::futures_util::{StreamExt, stream};
     use async_graphql::http::GraphiQLSource;
     use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
     use axum::{
         Router,
-        extract::Extension,
-        response::{Html, IntoResponse},
+        body::Body,
+        extract::{ConnectInfo, Extension, Path, Query, Request, State},
+        http::{StatusCode, header},
+        middleware::{self, Next},
+        response::{Html, IntoResponse, Response},
         routing::get,
     };
+    use std::{convert::Infallible, net::SocketAddr, time::Instant};
+
+    /// Reject clients over their rate limit and bodies over the size limit
+    /// before any GraphQL work happens
+    async fn limits(
+        State(state): State<Arc<ServerState>>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
+        request: Request,
+        next: Next,
+    ) -> Response {
+        if let Some(limiter) = &state.rate_limiter
+            && let Err(retry_after) = limiter.check(addr.ip(), Instant::now())
+        {
+            tracing::warn!(client = %addr.ip(), "Rate limit exceeded");
+            state.metrics.record_rejection("rate_limit");
+            let seconds = retry_after.as_secs().max(1).to_string();
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, seconds)],
+                "Too many requests\n",
+            )
+                .into_response();
+        }
+
+        // Buffer the body up to the limit; GraphQL requests are small
+        let (parts, body) = request.into_parts();
+        let Ok(bytes) = axum::body::to_bytes(body, state.max_body_bytes).await else {
+            tracing::warn!(client = %addr.ip(), limit = state.max_body_bytes, "Request body too large");
+            state.metrics.record_rejection("body_too_large");
+            return (StatusCode::PAYLOAD_TOO_LARGE, "Request body too large\n").into_response();
+        };
+        next.run(Request::from_parts(parts, Body::from(bytes)))
+            .await
+    }
 
     async fn graphql_handler(
         Extension(schema): Extension<crate::graphql::PeasSchema>,
+        Extension(state): Extension<Arc<ServerState>>,
+        headers: axum::http::HeaderMap,
         req: GraphQLRequest,
-    ) -> GraphQLResponse {
-        schema.execute(req.into_inner()).await.into()
+    ) -> Response {
+        let mut request = match persisted::resolve(req.into_inner(), &state.config.server) {
+            Ok(request) => request,
+            Err(message) => {
+                state.metrics.record_rejection("not_persisted");
+                return GraphQLResponse::from(async_graphql::Response::from_errors(vec![
+                    async_graphql::ServerError::new(message, None),
+                ]))
+                .into_response();
+            }
+        };
+        let actor = headers
+            .get(ACTOR_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .filter(|actor| !actor.is_empty())
+            .map(str::to_string);
+        if let Some(actor) = &actor {
+            request = request.data(crate::graphql::Actor(actor.clone()));
+        }
+        let mutations = crate::graphql::metrics::mutation_fields(&request);
+
+        let incremental = headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|accept| accept.contains("multipart/mixed"));
+        if incremental && let Some(initial) = defer::initial(&mut request) {
+            // The complete run parses the query again, without the directives
+            let mut complete = async_graphql::Request::new(request.query.clone())
+                .variables(request.variables.clone());
+            complete.operation_name = request.operation_name.clone();
+            if let Some(actor) = actor {
+                complete = complete.data(crate::graphql::Actor(actor));
+            }
+            request.set_parsed_query(initial);
+
+            let response = crate::graphql::execute(&schema, request).await;
+            state.metrics.record(&mutations, &response);
+            let first = defer::initial_payload(&response);
+            let head = defer::part(&first);
+            let rest = stream::once(async move {
+                let response = crate::graphql::execute(&schema, complete).await;
+                let last = defer::subsequent_payload(&first, &response);
+                Ok::<_, Infallible>(defer::part(&last) + defer::CLOSE)
+            });
+            return (
+                [(header::CONTENT_TYPE, defer::CONTENT_TYPE)],
+                Body::from_stream(stream::iter([Ok(head)]).chain(rest)),
+            )
+                .into_response();
+        }
+
+        let response = crate::graphql::execute(&schema, request).await;
+        state.metrics.record(&mutations, &response);
+        GraphQLResponse::from(response).into_response()
     }
 
     async fn graphiql() -> impl IntoResponse {
         Html(GraphiQLSource::build().endpoint("/").finish())
     }
 
-    let app = Router::new()
+    async fn metrics(Extension(state): Extension<Arc<ServerState>>) -> impl IntoResponse {
+        (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            state.metrics.render(&state.config, &state.root),
+        )
+    }
+
+    #[derive(serde::Deserialize)]
+    struct ViewFilter {
+        status: Option<String>,
+    }
+
+    async fn view_index(
+        Extension(state): Extension<Arc<ServerState>>,
+        Query(filter): Query<ViewFilter>,
+    ) -> Response {
+        let status = match filter.status.as_deref().map(str::parse::<PeaStatus>) {
+            None => None,
+            Some(Ok(status)) => Some(status),
+            Some(Err(e)) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        };
+        let repo = PeaRepository::new(&state.config, &state.root);
+        let project = state
+            .root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "peas".to_string());
+        match repo.list() {
+            Ok(peas) => Html(viewer::render_index(&project, &peas, status)).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        }
+    }
+
+    async fn view_pea(
+        Extension(state): Extension<Arc<ServerState>>,
+        Path(id): Path<String>,
+    ) -> Response {
+        let repo = PeaRepository::new(&state.config, &state.root);
+        let peas = match repo.list() {
+            Ok(peas) => peas,
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        };
+        let memories = MemoryRepository::new(&state.config, &state.root)
+            .list(None)
+            .unwrap_or_default();
+        match peas.iter().find(|p| p.id == id) {
+            Some(pea) => Html(viewer::render_pea(pea, &peas, &memories)).into_response(),
+            None => (StatusCode::NOT_FOUND, format!("Pea not found: {}\n", id)).into_response(),
+        }
+    }
+
+    async fn ui_asset(path: Option<Path<String>>) -> Response {
+        let path = path.map(|Path(p)| p).unwrap_or_default();
+        match web_ui::asset(&path) {
+            Some((content_type, body)) => {
+                ([(header::CONTENT_TYPE, content_type)], body).into_response()
+            }
+            None => (StatusCode::NOT_FOUND, "Not found\n").into_response(),
+        }
+    }
+
+    let mut app = Router::new()
         .route("/", get(graphiql).post(graphql_handler))
-        .layer(Extension(schema));
+        .route("/metrics", get(metrics))
+        .route("/ui", get(ui_asset))
+        .route("/ui/", get(ui_asset))
+        .route("/ui/{file}", get(ui_asset));
+    if state.viewer {
+        app = app
+            .route("/view", get(view_index))
+            .route("/view/{id}", get(view_pea));
+    }
+    let app = app
+        .layer(middleware::from_fn_with_state(state.clone(), limits))
+        .layer(Extension(schema))
+        .layer(Extension(state));
 
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
-    axum::serve(listener, app).await?;
+    tracing::info!(port, "GraphQL server listening");
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
@@ -2,25 +2,86 @@ use anyhow::Result;
 use colored::Colorize;
 
 use super::CommandContext;
-use super::utils::{format_priority, format_status};
+use super::utils::{format_days_remaining, format_priority, format_size_totals, format_status};
 use crate::assets::AssetManager;
-
-pub fn handle_show(ctx: &CommandContext, id: String, json: bool) -> Result<()> {
+use crate::model::{IncludeTarget, PeaType, References, SizeTotals, descendants, expand_includes};
+use crate::read_markers::ReadMarkers;
+use crate::storage::MemoryRepository;
+use crate::terminal::TimeFormat;
+
+pub fn handle_show(
+    ctx: &CommandContext,
+    id: String,
+    render: bool,
+    relative: bool,
+    json: bool,
+) -> Result<()> {
     let pea = ctx.repo.get(&id)?;
 
     if json {
         println!("{}", serde_json::to_string_pretty(&pea)?);
     } else {
-        print_pea_with_refs(&pea, ctx);
+        let time = TimeFormat::new(&ctx.config.display, relative);
+        print_pea_with_refs(&pea, ctx, render, time);
     }
+
+    let mut markers = ReadMarkers::load(&ctx.config.data_path(&ctx.root));
+    markers.mark_read(&pea);
+    let _ = markers.save();
     Ok(())
 }
 
-fn print_pea_with_refs(pea: &crate::model::Pea, ctx: &CommandContext) {
+fn print_pea_with_refs(
+    pea: &crate::model::Pea,
+    ctx: &CommandContext,
+    render: bool,
+    time: TimeFormat,
+) {
     println!("{} {}", pea.id.cyan().bold(), pea.title.bold());
+    if let Some(slug) = &pea.slug {
+        println!("Slug:     {}", slug.dimmed());
+    }
+    if let Ok(aliases) = ctx.repo.aliases() {
+        let former: Vec<&str> = aliases.of(&pea.id).collect();
+        if !former.is_empty() {
+            println!("Aliases:  {}", former.join(", ").dimmed());
+        }
+    }
     println!("Type:     {}", format!("{}", pea.pea_type).blue());
     println!("Status:   {}", format_status(pea.status));
     println!("Priority: {}", format_priority(pea.priority));
+    if let Some(size) = &pea.size {
+        println!("Size:     {}", size);
+    }
+    if matches!(pea.pea_type, PeaType::Milestone | PeaType::Epic)
+        && let Ok(peas) = ctx.repo.list_summaries()
+    {
+        let below = descendants(&peas, &pea.id);
+        if !below.is_empty() {
+            let totals = SizeTotals::of(below, ctx.config.peas.size_scale);
+            println!("Points:   {}", format_size_totals(totals));
+        }
+    }
+    if let Some(target) = pea.target_date {
+        let today = chrono::Utc::now().date_naive();
+        match pea.days_remaining(today).filter(|_| pea.is_open()) {
+            Some(days) if days < 0 => println!(
+                "Target:   {} {}",
+                target,
+                format!("({})", format_days_remaining(days)).red().bold()
+            ),
+            Some(days) => println!("Target:   {} ({})", target, format_days_remaining(days)),
+            None => println!("Target:   {}", target),
+        }
+    }
+    if let Some(date) = pea.defer_until
+        && pea.is_deferred(chrono::Utc::now().date_naive())
+    {
+        println!("Deferred: until {}", date.to_string().yellow());
+    }
+    if let Some(release) = &pea.released_in {
+        println!("Released: {}", release);
+    }
 
     // Show parent with title if available
     if let Some(parent_id) = &pea.parent {
@@ -48,13 +109,42 @@ fn print_pea_with_refs(pea: &crate::model::Pea, ctx: &CommandContext) {
         println!("Blocking: {}", blocking_info.join(", "));
     }
 
+    // Backlinks: other peas mentioning this one in their body
+    if let Ok(peas) = ctx.repo.list() {
+        let references = References::build(&peas);
+        let referenced_by: Vec<String> = references
+            .referenced_by(&pea.id)
+            .iter()
+            .filter_map(|id| peas.iter().find(|p| &p.id == id))
+            .map(|p| format!("{} ({})", p.id.cyan(), p.title.dimmed()))
+            .collect();
+        if !referenced_by.is_empty() {
+            println!("Referenced by: {}", referenced_by.join(", "));
+        }
+    }
+
     if !pea.external_refs.is_empty() {
         println!("Refs:     {}", pea.external_refs.join(", ").yellow());
     }
 
+    for (i, link) in pea.links.iter().enumerate() {
+        let heading = if i == 0 { "Links:" } else { "" };
+        let title = match &link.label {
+            Some(label) => format!("{} {}", label, link.url.dimmed()),
+            None => link.url.clone(),
+        };
+        println!("{:<9} [{}] {}", heading, link.kind, title.yellow());
+    }
+
     if !pea.tags.is_empty() {
         println!("Tags:     {}", pea.tags.join(", ").magenta());
     }
+    if let Some(component) = &pea.component {
+        println!("Component: {}", component.magenta());
+    }
+    if let Some(assignee) = &pea.assignee {
+        println!("Assignee: {}", assignee);
+    }
 
     // Show assets if any
     if !pea.assets.is_empty() {
@@ -74,13 +164,39 @@ fn print_pea_with_refs(pea: &crate::model::Pea, ctx: &CommandContext) {
         }
     }
 
-    println!("Created:  {}", pea.created.format("%Y-%m-%d %H:%M"));
-    println!("Updated:  {}", pea.updated.format("%Y-%m-%d %H:%M"));
+    let by = |actor: &Option<String>| {
+        actor
+            .as_ref()
+            .map(|a| format!(" by {}", a))
+            .unwrap_or_default()
+    };
+    println!(
+        "Created:  {}{}",
+        time.datetime(pea.created),
+        by(&pea.created_by)
+    );
+    println!(
+        "Updated:  {}{}",
+        time.datetime(pea.updated),
+        by(&pea.updated_by)
+    );
+    if let Some(claim) = pea.active_claim(chrono::Utc::now()) {
+        println!("Claimed:  {}", claim.to_string().magenta());
+    }
 
-    // Print body with resolved ticket references
+    // Print body with includes expanded and resolved ticket references
     if !pea.body.is_empty() {
-        let resolved_body = resolve_ticket_refs(&pea.body, &ctx.config.peas.prefix, ctx);
-        println!("\n{}", resolved_body);
+        let memories = MemoryRepository::new(&ctx.config, &ctx.root);
+        let body = expand_includes(&pea.body, |target| match target {
+            IncludeTarget::Pea(id) => ctx.repo.get(id).ok().map(|p| p.body.into_string()),
+            IncludeTarget::Memory(key) => memories.get(key).ok().map(|m| m.content),
+        });
+        if render {
+            println!("\n{}", crate::tui::markdown_ansi(&body));
+        } else {
+            let resolved_body = resolve_ticket_refs(&body, &ctx.config.peas.prefix, ctx);
+            println!("\n{}", resolved_body);
+        }
     }
 }
 
@@ -0,0 +1,64 @@
+use crate::model::{SizeTotals, descendants};
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::{format_size_totals, record_undo_update};
+
+pub fn handle_size(
+    ctx: &CommandContext,
+    id: &str,
+    value: Option<&str>,
+    clear: bool,
+    json: bool,
+) -> Result<()> {
+    let scale = ctx.config.peas.size_scale;
+    let mut pea = ctx.repo.get(id)?;
+
+    if value.is_none() && !clear {
+        let peas = ctx.repo.list()?;
+        let below = descendants(&peas, &pea.id);
+        let totals = (!below.is_empty()).then(|| SizeTotals::of(below, scale));
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "id": pea.id,
+                    "size": pea.size,
+                    "points": pea.size.as_deref().and_then(|s| scale.points(s)),
+                    "children": totals,
+                }))?
+            );
+            return Ok(());
+        }
+        match &pea.size {
+            Some(size) => println!("{} size {}", pea.id.cyan(), size.bold()),
+            None => println!(
+                "{} has no size ({} scale: {})",
+                pea.id.cyan(),
+                scale,
+                scale.values().join(", ")
+            ),
+        }
+        if let Some(totals) = totals {
+            println!("Below it: {}", format_size_totals(totals));
+        }
+        return Ok(());
+    }
+
+    let size = value.map(|v| scale.normalize(v)).transpose()?;
+    let old_path = ctx.repo.find_file_by_id(&pea.id)?;
+    record_undo_update(ctx, &pea.id, &old_path);
+    pea.size = size;
+    ctx.repo.update(&mut pea)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pea)?);
+    } else {
+        match &pea.size {
+            Some(size) => println!("{} {} size {}", "Sized".green(), pea.id.cyan(), size.bold()),
+            None => println!("{} size of {}", "Cleared".green(), pea.id.cyan()),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::cli::commands::SnapshotAction;
+use crate::git;
+use crate::snapshot::{Snapshot, Snapshots, default_label};
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+
+pub fn handle_snapshot(ctx: &CommandContext, action: SnapshotAction) -> Result<()> {
+    let snapshots = Snapshots::new(&ctx.config.data_path(&ctx.root));
+    match action {
+        SnapshotAction::Create { label, json } => {
+            let label = label.unwrap_or_else(default_label);
+            let peas = ctx.repo.list()?;
+            // Uncommitted changes are recorded too, the commit is only a reference point
+            let commit = git::run(&ctx.root, &["rev-parse", "HEAD"]).ok();
+            let snapshot = Snapshot::new(&label, &peas, commit)?;
+            let path = snapshots.save(&snapshot)?;
+
+            if json {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&serde_json::json!({
+                        "label": snapshot.label,
+                        "created": snapshot.created,
+                        "commit": snapshot.commit,
+                        "hash": snapshot.hash,
+                        "peas": snapshot.peas.len(),
+                        "path": path,
+                    }))?
+                );
+            } else {
+                println!(
+                    "{} snapshot {} of {} pea(s) ({})",
+                    "Created".green(),
+                    snapshot.label.cyan(),
+                    snapshot.peas.len(),
+                    &snapshot.hash[..12]
+                );
+            }
+        }
+        SnapshotAction::List { json } => {
+            let snapshots = snapshots.list()?;
+            if json {
+                let output: Vec<_> = snapshots
+                    .iter()
+                    .map(|s| {
+                        serde_json::json!({
+                            "label": s.label,
+                            "created": s.created,
+                            "commit": s.commit,
+                            "hash": s.hash,
+                            "peas": s.peas.len(),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&output)?);
+                return Ok(());
+            }
+
+            if snapshots.is_empty() {
+                println!("No snapshots yet. Record one with `peas snapshot create [label]`.");
+                return Ok(());
+            }
+            for s in &snapshots {
+                let commit = s
+                    .commit
+                    .as_deref()
+                    .map(|hash| hash[..hash.len().min(7)].to_string())
+                    .unwrap_or_else(|| "-------".to_string());
+                println!(
+                    "{}  {}  {}  {:>4} pea(s)  {}",
+                    s.created.format("%Y-%m-%d %H:%M").to_string().dimmed(),
+                    commit.yellow(),
+                    &s.hash[..12],
+                    s.peas.len(),
+                    s.label.cyan().bold()
+                );
+            }
+        }
+    }
+    Ok(())
+}
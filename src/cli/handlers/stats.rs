@@ -0,0 +1,199 @@
+use crate::api::Client;
+use crate::error::PeasError;
+use crate::flow::{Distribution, FlowTimes, days, flow_times};
+use crate::history::{StatusState, current_state, status_history};
+use crate::model::{PeaType, descendants};
+use anyhow::Result;
+use chrono::{DateTime, Duration};
+use colored::Colorize;
+
+use super::CommandContext;
+
+pub fn handle_stats(ctx: &CommandContext, json: bool) -> Result<()> {
+    let stats = Client::with_config(ctx.config.clone(), ctx.root.clone()).stats()?;
+
+    let mut peas = ctx.repo.list_summaries()?;
+    peas.extend(ctx.repo.list_archived()?);
+    let times = flow_times(&peas, &full_history(ctx)?);
+    let lead = Distribution::of(times.iter().map(FlowTimes::lead));
+    let cycle = Distribution::of(times.iter().filter_map(FlowTimes::cycle));
+
+    let epics: Vec<_> = peas
+        .iter()
+        .filter(|p| p.pea_type == PeaType::Epic)
+        .filter_map(|epic| {
+            let below = descendants(&peas, &epic.id);
+            let times: Vec<&FlowTimes> = times
+                .iter()
+                .filter(|t| below.iter().any(|p| p.id == t.id))
+                .collect();
+            let lead = Distribution::of(times.iter().map(|t| t.lead()))?;
+            let cycle = Distribution::of(times.iter().filter_map(|t| t.cycle()));
+            Some((epic, lead, cycle))
+        })
+        .collect();
+
+    if json {
+        let by_id = |id: &str| peas.iter().find(|p| p.id == id);
+        let output = serde_json::json!({
+            "total": stats.total,
+            "archived": stats.archived,
+            "by_status": stats
+                .by_status
+                .iter()
+                .map(|(status, count)| (status.to_string(), (*count).into()))
+                .collect::<serde_json::Map<_, _>>(),
+            "by_type": stats
+                .by_type
+                .iter()
+                .map(|(pea_type, count)| (pea_type.to_string(), (*count).into()))
+                .collect::<serde_json::Map<_, _>>(),
+            "by_component": stats
+                .by_component
+                .iter()
+                .map(|(component, count)| (component.clone(), (*count).into()))
+                .collect::<serde_json::Map<_, _>>(),
+            "reopened_by_type": stats
+                .reopened_by_type
+                .iter()
+                .map(|(pea_type, count)| (pea_type.to_string(), (*count).into()))
+                .collect::<serde_json::Map<_, _>>(),
+            "lead_time_days": lead.map(distribution_json),
+            "cycle_time_days": cycle.map(distribution_json),
+            "epics": epics
+                .iter()
+                .map(|(epic, lead, cycle)| {
+                    serde_json::json!({
+                        "id": epic.id,
+                        "title": epic.title,
+                        "lead_time_days": distribution_json(*lead),
+                        "cycle_time_days": cycle.map(distribution_json),
+                    })
+                })
+                .collect::<Vec<_>>(),
+            "peas": times
+                .iter()
+                .map(|t| {
+                    serde_json::json!({
+                        "id": t.id,
+                        "title": by_id(&t.id).map(|p| p.title.as_str()),
+                        "started": t.started,
+                        "completed": t.completed,
+                        "lead_time_days": days(t.lead()),
+                        "cycle_time_days": t.cycle().map(days),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    println!(
+        "{} {} active, {} archived",
+        "Peas:".bold(),
+        stats.total,
+        stats.archived
+    );
+    let counts: Vec<String> = stats
+        .by_status
+        .iter()
+        .map(|(status, count)| format!("{} {}", status, count))
+        .collect();
+    println!("  {}", counts.join("  "));
+    if !stats.by_component.is_empty() {
+        let counts: Vec<String> = stats
+            .by_component
+            .iter()
+            .map(|(component, count)| format!("{} {}", component, count))
+            .collect();
+        println!("  {} {}", "Components:".dimmed(), counts.join("  "));
+    }
+    let reopened: Vec<String> = stats
+        .reopened_by_type
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(pea_type, count)| format!("{} {}", pea_type, count))
+        .collect();
+    if !reopened.is_empty() {
+        println!("  {} {}", "Reopened:".dimmed(), reopened.join("  "));
+    }
+
+    println!(
+        "\n{} ({} completed)",
+        "Flow".bold(),
+        lead.map_or(0, |d| d.count)
+    );
+    if lead.is_none() {
+        println!("  {}", "No completed peas yet".dimmed());
+        return Ok(());
+    }
+    println!(
+        "  {:<12} {:>8} {:>8} {:>8} {:>8}",
+        "", "p50", "p85", "p95", "max"
+    );
+    for (name, dist) in [("Lead time", lead), ("Cycle time", cycle)] {
+        match dist {
+            Some(d) => println!(
+                "  {:<12} {:>8} {:>8} {:>8} {:>8}",
+                name,
+                format_duration(d.p50),
+                format_duration(d.p85),
+                format_duration(d.p95),
+                format_duration(d.max)
+            ),
+            None => println!("  {:<12} {}", name, "never started".dimmed()),
+        }
+    }
+
+    if !epics.is_empty() {
+        println!("\n{}", "By epic".bold());
+        for (epic, lead, cycle) in &epics {
+            let cycle = cycle.map_or_else(
+                || "-".to_string(),
+                |c| format!("{} / {}", format_duration(c.p50), format_duration(c.p85)),
+            );
+            println!(
+                "  {} {} ({} completed)\n    lead p50/p85 {} / {}, cycle p50/p85 {}",
+                epic.id.cyan(),
+                epic.title,
+                lead.count,
+                format_duration(lead.p50),
+                format_duration(lead.p85),
+                cycle
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The whole status history, or only the current state outside a git repository
+pub(super) fn full_history(ctx: &CommandContext) -> Result<Vec<StatusState>> {
+    match status_history(&ctx.config, &ctx.root, DateTime::UNIX_EPOCH) {
+        Ok(history) => Ok(history),
+        Err(PeasError::Git(e)) => {
+            tracing::debug!(error = %e, "No git history, using update times");
+            Ok(vec![current_state(&ctx.repo)?])
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub(super) fn distribution_json(d: Distribution) -> serde_json::Value {
+    serde_json::json!({
+        "count": d.count,
+        "p50": days(d.p50),
+        "p85": days(d.p85),
+        "p95": days(d.p95),
+        "max": days(d.max),
+    })
+}
+
+/// Hours below two days, days above
+pub(super) fn format_duration(duration: Duration) -> String {
+    if duration < Duration::days(2) {
+        format!("{:.1}h", duration.num_minutes() as f64 / 60.0)
+    } else {
+        format!("{:.1}d", duration.num_minutes() as f64 / (24.0 * 60.0))
+    }
+}
@@ -1,47 +1,199 @@
-use crate::model::PeaStatus;
+use crate::config::Cascade;
+use crate::model::{Pea, PeaStatus, PeaType};
+use crate::undo::UndoOperation;
 use anyhow::Result;
 use colored::Colorize;
+use std::io::{self, BufRead};
 
 use super::CommandContext;
-use super::utils::record_undo_update;
+use super::utils::{UndoBatch, print_dry_run, prompt};
 
 /// Generic status update handler
-fn update_status(ctx: &CommandContext, id: &str, new_status: PeaStatus, json: bool) -> Result<()> {
+fn update_status(
+    ctx: &CommandContext,
+    id: &str,
+    new_status: PeaStatus,
+    json: bool,
+    dry_run: bool,
+    cascade: bool,
+) -> Result<()> {
     let mut pea = ctx.repo.get(id)?;
 
-    // Record undo operation before update
+    if dry_run {
+        let original = pea.clone();
+        pea.status = new_status;
+        let pea = ctx.repo.preview_update(&pea)?;
+        return print_dry_run(&original, &pea, json);
+    }
+
+    // Capture the previous content for undo before updating
     let old_path = ctx.repo.find_file_by_id(&pea.id)?;
-    record_undo_update(ctx, &pea.id, &old_path);
+    let mut operations = vec![UndoOperation::update_from_file(&pea.id, &old_path)?];
 
     pea.status = new_status;
     // NOTE: No touch() call - update() handles it internally now
     ctx.repo.update(&mut pea)?;
 
+    let status_str = match new_status {
+        PeaStatus::InProgress => "in-progress".yellow(),
+        PeaStatus::Completed => "completed".green(),
+        _ => format!("{}", new_status).white(),
+    };
+    let action = match new_status {
+        PeaStatus::InProgress => "Started".green(),
+        PeaStatus::Completed => "Done".green(),
+        _ => "Updated".green(),
+    };
     if json {
         println!("{}", serde_json::to_string_pretty(&pea)?);
     } else {
-        let status_str = match new_status {
-            PeaStatus::InProgress => "in-progress".yellow(),
-            PeaStatus::Completed => "completed".green(),
-            _ => format!("{}", new_status).white(),
-        };
-        let action = match new_status {
-            PeaStatus::InProgress => "Started".green(),
-            PeaStatus::Completed => "Done".green(),
-            _ => "Updated".green(),
-        };
         println!("{} {} is now {}", action, pea.id.cyan(), status_str);
     }
 
+    let mode = match ctx.config.peas.cascade {
+        Cascade::Off if cascade => Cascade::Prompt,
+        mode => mode,
+    };
+    let result = cascade_to_parents(ctx, &pea, mode, json, &mut operations);
+
+    // The pea and the parents it carried along are undone together
+    let undo_manager = ctx.repo.undo_manager();
+    let _ = if operations.len() == 1 {
+        undo_manager.record(operations.remove(0))
+    } else {
+        crate::undo::record_batch(
+            &undo_manager,
+            &format!("{} {} and its parents", action, pea.id),
+            operations,
+        )
+    };
+
+    if !json {
+        for parent in result? {
+            println!(
+                "{} parent {} {} is now {}",
+                action,
+                parent.pea_type,
+                parent.id.cyan(),
+                status_str
+            );
+        }
+    }
     Ok(())
 }
 
+/// Carry a started or completed pea's status up to its epic or feature parents
+///
+/// Starting a child starts a parent that is still todo or draft. Completing
+/// the last open child completes the parent, after asking unless `mode` is
+/// auto; with `json` there is no asking, so only auto completes parents.
+/// Returns the parents that were changed, closest first.
+fn cascade_to_parents(
+    ctx: &CommandContext,
+    pea: &Pea,
+    mode: Cascade,
+    json: bool,
+    operations: &mut Vec<UndoOperation>,
+) -> Result<Vec<Pea>> {
+    let mut changed = Vec::new();
+    if mode == Cascade::Off {
+        return Ok(changed);
+    }
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut child = pea.clone();
+    while let Some(parent_id) = &child.parent {
+        let Ok(mut parent) = ctx.repo.get(parent_id) else {
+            break;
+        };
+        if !matches!(parent.pea_type, PeaType::Epic | PeaType::Feature) {
+            break;
+        }
+        match child.status {
+            PeaStatus::InProgress
+                if matches!(parent.status, PeaStatus::Draft | PeaStatus::Todo) => {}
+            PeaStatus::Completed if parent.is_open() => {
+                let peas = ctx.repo.list()?;
+                let mut children = peas
+                    .iter()
+                    .filter(|p| p.parent.as_ref() == Some(&parent.id));
+                if !children.all(Pea::is_closed) {
+                    break;
+                }
+                let complete = match mode {
+                    Cascade::Auto => true,
+                    _ if json => false,
+                    _ => {
+                        let question = format!(
+                            "All children of {} {} are closed; complete it too? [y/N]",
+                            parent.id, parent.title
+                        );
+                        prompt(&mut lines, &question)?.is_some_and(|a| a.eq_ignore_ascii_case("y"))
+                    }
+                };
+                if !complete {
+                    break;
+                }
+            }
+            _ => break,
+        }
+        let old_path = ctx.repo.find_file_by_id(&parent.id)?;
+        operations.push(UndoOperation::update_from_file(&parent.id, &old_path)?);
+        parent.status = child.status;
+        ctx.repo.update(&mut parent)?;
+        changed.push(parent.clone());
+        child = parent;
+    }
+    Ok(changed)
+}
+
 /// Handle start command (set status to InProgress)
-pub fn handle_start(ctx: &CommandContext, id: String, json: bool) -> Result<()> {
-    update_status(ctx, &id, PeaStatus::InProgress, json)
+pub fn handle_start(
+    ctx: &CommandContext,
+    id: String,
+    json: bool,
+    dry_run: bool,
+    cascade: bool,
+) -> Result<()> {
+    update_status(ctx, &id, PeaStatus::InProgress, json, dry_run, cascade)
 }
 
 /// Handle done command (set status to Completed)
-pub fn handle_done(ctx: &CommandContext, id: String, json: bool) -> Result<()> {
-    update_status(ctx, &id, PeaStatus::Completed, json)
+pub fn handle_done(
+    ctx: &CommandContext,
+    id: String,
+    json: bool,
+    dry_run: bool,
+    cascade: bool,
+) -> Result<()> {
+    update_status(ctx, &id, PeaStatus::Completed, json, dry_run, cascade)
+}
+
+/// Handle reopen command (move a closed pea back to Todo)
+pub fn handle_reopen(ctx: &CommandContext, id: String, json: bool, dry_run: bool) -> Result<()> {
+    let mut pea = ctx.repo.get(&id)?;
+    let original = pea.clone();
+    pea.status = PeaStatus::Todo;
+
+    if dry_run {
+        let pea = ctx.repo.preview_reopen(&pea)?;
+        return print_dry_run(&original, &pea, json);
+    }
+
+    UndoBatch::new(ctx, format!("Reopen {}", pea.id)).reopen(&mut pea)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&pea)?);
+    } else {
+        println!(
+            "{} {} is now {} (was {}, reopened {} time(s))",
+            "Reopened".green(),
+            pea.id.cyan(),
+            "todo".white(),
+            original.status,
+            pea.reopened_count
+        );
+    }
+
+    Ok(())
 }
@@ -1,46 +1,105 @@
-use crate::model::{PeaPriority, PeaStatus, PeaType};
+use crate::focus::Focus;
+use crate::model::{Pea, PeaPriority, PeaStatus, PeaType};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 
 use super::CommandContext;
 use super::utils::print_pea;
 
-pub fn handle_suggest(ctx: &CommandContext, json: bool, limit: usize) -> Result<()> {
-    let peas = ctx.repo.list()?;
+/// Most points a ticket gets for the tickets it unblocks
+const MAX_UNBLOCK_POINTS: i64 = 30;
 
-    // Build a map of ticket ID to completion status
-    let status_map: HashMap<String, PeaStatus> =
-        peas.iter().map(|p| (p.id.clone(), p.status)).collect();
+/// Days before a milestone's target date its tickets start gaining points
+const DUE_WINDOW_DAYS: i64 = 30;
 
-    // Calculate how many tickets each ticket is blocking
-    let mut blocking_count: HashMap<String, usize> = HashMap::new();
-    for pea in &peas {
-        for blocked_id in &pea.blocking {
-            *blocking_count.entry(blocked_id.clone()).or_insert(0) += 1;
+/// Most points a ticket gets for sitting untouched
+const MAX_STALE_POINTS: i64 = 10;
+
+/// One part of a suggestion's score
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Factor {
+    reason: String,
+    points: i64,
+}
+
+impl Factor {
+    fn new(reason: impl Into<String>, points: i64) -> Self {
+        Self {
+            reason: reason.into(),
+            points,
         }
     }
+}
+
+/// An actionable ticket with the factors of its score
+struct Candidate<'a> {
+    pea: &'a Pea,
+    factors: Vec<Factor>,
+    /// Open tickets this one blocks
+    unblocks: usize,
+}
+
+impl Candidate<'_> {
+    fn score(&self) -> i64 {
+        self.factors.iter().map(|f| f.points).sum()
+    }
+
+    /// The factor worth the most points, as a one-line reason
+    fn reason(&self) -> String {
+        self.factors
+            .iter()
+            .filter(|f| f.points > 0)
+            .max_by_key(|f| f.points)
+            .map_or_else(|| "Next in queue".to_string(), |f| capitalize(&f.reason))
+    }
+}
+
+pub fn handle_suggest(ctx: &CommandContext, json: bool, count: usize) -> Result<()> {
+    let peas = ctx.repo.list_summaries()?;
+    let by_id: HashMap<&str, &Pea> = peas.iter().map(|p| (p.id.as_str(), p)).collect();
 
-    // Filter to open, actionable items (not milestones/epics which are containers)
-    // Also filter out tickets with unmet dependencies
-    let mut candidates: Vec<_> = peas
+    // A ticket is blocked while an open ticket lists it in `blocking`
+    let blocked_ids: HashSet<&str> = peas
         .iter()
-        .filter(|p| {
-            if !p.is_open() || matches!(p.pea_type, PeaType::Milestone | PeaType::Epic) {
-                return false;
-            }
+        .filter(|p| p.is_open())
+        .flat_map(|p| p.blocking.iter().map(String::as_str))
+        .collect();
 
-            // Check if all blocking dependencies are completed
-            for blocker_id in &p.blocking {
-                if let Some(status) = status_map.get(blocker_id)
-                    && *status != PeaStatus::Completed
-                    && *status != PeaStatus::Scrapped
-                {
-                    return false; // Has unmet dependency
-                }
+    // Open, actionable items (not milestones/epics which are containers),
+    // leaving out those deferred to a later date
+    let now = Utc::now();
+    let actionable: Vec<&Pea> = peas
+        .iter()
+        .filter(|p| p.is_open() && !matches!(p.pea_type, PeaType::Milestone | PeaType::Epic))
+        .filter(|p| !p.is_deferred(now.date_naive()))
+        .collect();
+    let blocked = actionable
+        .iter()
+        .filter(|p| blocked_ids.contains(p.id.as_str()))
+        .count();
+    // Tickets another agent has claimed are theirs to work on
+    let actor = ctx.config.actor.as_deref();
+    let claimed = actionable
+        .iter()
+        .filter(|p| !blocked_ids.contains(p.id.as_str()) && p.is_claimed_by_other(actor, now))
+        .count();
+    let mut candidates: Vec<Candidate> = actionable
+        .into_iter()
+        .filter(|p| !blocked_ids.contains(p.id.as_str()) && !p.is_claimed_by_other(actor, now))
+        .map(|pea| {
+            let unblocks = pea
+                .blocking
+                .iter()
+                .filter(|id| by_id.get(id.as_str()).is_some_and(|p| p.is_open()))
+                .count();
+            Candidate {
+                pea,
+                factors: score(pea, unblocks, &by_id, now),
+                unblocks,
             }
-
-            true
         })
         .collect();
 
@@ -107,85 +166,61 @@ pub fn handle_suggest(ctx: &CommandContext, json: bool, limit: usize) -> Result<
                 "{}",
                 serde_json::to_string_pretty(&serde_json::json!({
                     "suggestion": null,
-                    "reason": "No open actionable tickets found (some may be blocked)"
+                    "reason": "No open actionable tickets found (some may be blocked or claimed)",
+                    "blocked": blocked,
+                    "claimed": claimed
                 }))?
             );
+        } else if blocked > 0 || claimed > 0 {
+            println!(
+                "No open actionable tickets found ({} blocked by open tickets, {} claimed by others).",
+                blocked, claimed
+            );
         } else {
-            println!("No open actionable tickets found (some may be blocked by dependencies).");
+            println!("No open actionable tickets found.");
         }
         return Ok(());
     }
 
-    // Sort by: in-progress first, then blocking count, then priority, then by type
+    // Highest score first; ties go to the more urgent priority, then bugs
     candidates.sort_by(|a, b| {
-        // In-progress items first
-        let a_in_progress = a.status == PeaStatus::InProgress;
-        let b_in_progress = b.status == PeaStatus::InProgress;
-        if a_in_progress != b_in_progress {
-            return b_in_progress.cmp(&a_in_progress);
-        }
-
-        // Then by blocking count (tickets blocking more items come first)
-        let a_blocks = blocking_count.get(&a.id).unwrap_or(&0);
-        let b_blocks = blocking_count.get(&b.id).unwrap_or(&0);
-        if a_blocks != b_blocks {
-            return b_blocks.cmp(a_blocks);
-        }
+        b.score()
+            .cmp(&a.score())
+            .then_with(|| priority_rank(a.pea.priority).cmp(&priority_rank(b.pea.priority)))
+            .then_with(|| type_rank(a.pea.pea_type).cmp(&type_rank(b.pea.pea_type)))
+            .then_with(|| a.pea.id.cmp(&b.pea.id))
+    });
 
-        // Then by priority
-        let priority_order = |p: &PeaPriority| match p {
-            PeaPriority::Critical => 0,
-            PeaPriority::High => 1,
-            PeaPriority::Normal => 2,
-            PeaPriority::Low => 3,
-            PeaPriority::Deferred => 4,
-        };
-        let a_pri = priority_order(&a.priority);
-        let b_pri = priority_order(&b.priority);
-        if a_pri != b_pri {
-            return a_pri.cmp(&b_pri);
+    // The ticket the user is focused on beats every other ordering
+    let focus = Focus::new(&ctx.config.data_path(&ctx.root)).get();
+    if let Some(pos) = candidates
+        .iter()
+        .position(|c| Some(&c.pea.id) == focus.as_ref())
+    {
+        let focused = candidates.remove(pos);
+        candidates.insert(0, focused);
+    }
+    let reason_for = |c: &Candidate| {
+        if Some(&c.pea.id) == focus.as_ref() {
+            "Current focus".to_string()
+        } else {
+            c.reason()
         }
-
-        // Then by type (bugs before features before tasks)
-        let type_order = |t: &PeaType| match t {
-            PeaType::Bug => 0,
-            PeaType::Feature => 1,
-            PeaType::Story => 2,
-            PeaType::Chore => 3,
-            PeaType::Research => 4,
-            PeaType::Task => 5,
-            _ => 6,
-        };
-        type_order(&a.pea_type).cmp(&type_order(&b.pea_type))
-    });
+    };
 
     // Take top N suggestions
-    let num_suggestions = limit.min(candidates.len());
-    let suggestions: Vec<_> = candidates.iter().take(num_suggestions).collect();
+    candidates.truncate(count);
 
     if json {
-        let suggestions_with_reasons: Vec<_> = suggestions
+        let suggestions: Vec<_> = candidates
             .iter()
-            .map(|s| {
-                let blocks_count = blocking_count.get(&s.id).unwrap_or(&0);
-                let reason = if s.status == PeaStatus::InProgress {
-                    "Currently in progress".to_string()
-                } else if *blocks_count > 0 {
-                    format!("Blocking {} ticket(s)", blocks_count)
-                } else if s.priority == PeaPriority::Critical {
-                    "Critical priority".to_string()
-                } else if s.priority == PeaPriority::High {
-                    "High priority".to_string()
-                } else if s.pea_type == PeaType::Bug {
-                    "Bug fix".to_string()
-                } else {
-                    "Next in queue".to_string()
-                };
-
+            .map(|c| {
                 serde_json::json!({
-                    "pea": s,
-                    "reason": reason,
-                    "blocks_count": blocks_count
+                    "pea": c.pea,
+                    "reason": reason_for(c),
+                    "blocks_count": c.unblocks,
+                    "score": c.score(),
+                    "factors": c.factors,
                 })
             })
             .collect();
@@ -193,62 +228,258 @@ pub fn handle_suggest(ctx: &CommandContext, json: bool, limit: usize) -> Result<
         println!(
             "{}",
             serde_json::to_string_pretty(&serde_json::json!({
-                "suggestions": suggestions_with_reasons,
-                "count": num_suggestions
+                "suggestions": suggestions,
+                "count": candidates.len(),
+                "blocked": blocked,
+                "claimed": claimed
             }))?
         );
-    } else if num_suggestions == 1 {
-        let suggestion = suggestions[0];
-        let blocks_count = blocking_count.get(&suggestion.id).unwrap_or(&0);
-        let reason = if suggestion.status == PeaStatus::InProgress {
-            "Currently in progress".to_string()
-        } else if *blocks_count > 0 {
-            format!("Blocking {} ticket(s)", blocks_count)
-        } else if suggestion.priority == PeaPriority::Critical {
-            "Critical priority".to_string()
-        } else if suggestion.priority == PeaPriority::High {
-            "High priority".to_string()
-        } else if suggestion.pea_type == PeaType::Bug {
-            "Bug fix".to_string()
-        } else {
-            "Next in queue".to_string()
-        };
-
-        println!("{}: {}", "Suggested".green().bold(), reason);
+    } else if let [suggestion] = candidates.as_slice() {
+        println!("{}: {}", "Suggested".green().bold(), reason_for(suggestion));
+        println!();
+        print_pea(suggestion.pea);
         println!();
-        print_pea(suggestion);
+        println!("{}", explain(suggestion).dimmed());
     } else {
-        println!("{} {} suggestions:", "Top".green().bold(), num_suggestions);
+        println!("{} {} suggestions:", "Top".green().bold(), candidates.len());
         println!();
-        for (i, suggestion) in suggestions.iter().enumerate() {
-            let blocks_count = blocking_count.get(&suggestion.id).unwrap_or(&0);
-            let reason = if suggestion.status == PeaStatus::InProgress {
-                "Currently in progress".to_string()
-            } else if *blocks_count > 0 {
-                format!("Blocking {} ticket(s)", blocks_count)
-            } else if suggestion.priority == PeaPriority::Critical {
-                "Critical priority".to_string()
-            } else if suggestion.priority == PeaPriority::High {
-                "High priority".to_string()
-            } else if suggestion.pea_type == PeaType::Bug {
-                "Bug fix".to_string()
-            } else {
-                "Next in queue".to_string()
-            };
-
-            println!("{}. {} - {}", i + 1, reason.cyan(), suggestion.title);
+        for (i, suggestion) in candidates.iter().enumerate() {
+            println!(
+                "{}. {} - {}",
+                i + 1,
+                reason_for(suggestion).cyan(),
+                suggestion.pea.title
+            );
             println!(
                 "   {} [{}] {}",
-                suggestion.id.dimmed(),
-                suggestion.pea_type,
-                suggestion.priority
+                suggestion.pea.id.dimmed(),
+                suggestion.pea.pea_type,
+                suggestion.pea.priority
             );
-            if *blocks_count > 0 {
-                println!("   {} Unblocks {} ticket(s)", "⚠".yellow(), blocks_count);
+            if suggestion.unblocks > 0 {
+                println!(
+                    "   {} Unblocks {} ticket(s)",
+                    "⚠".yellow(),
+                    suggestion.unblocks
+                );
             }
+            println!("   {}", explain(suggestion).dimmed());
             println!();
         }
     }
 
     Ok(())
 }
+
+/// The factors of a ticket's score
+///
+/// Due dates and milestone priority come from the nearest open milestone
+/// above the ticket, since only milestones have target dates.
+fn score(
+    pea: &Pea,
+    unblocks: usize,
+    by_id: &HashMap<&str, &Pea>,
+    now: DateTime<Utc>,
+) -> Vec<Factor> {
+    let mut factors = Vec::new();
+    if pea.status == PeaStatus::InProgress {
+        factors.push(Factor::new("in progress", 30));
+    }
+    let priority_points = match pea.priority {
+        PeaPriority::Critical => 40,
+        PeaPriority::High => 25,
+        PeaPriority::Normal => 10,
+        PeaPriority::Low => 0,
+        PeaPriority::Deferred => -20,
+    };
+    if priority_points != 0 {
+        factors.push(Factor::new(
+            format!("{} priority", pea.priority),
+            priority_points,
+        ));
+    }
+    if unblocks > 0 {
+        factors.push(Factor::new(
+            format!("unblocks {} ticket(s)", unblocks),
+            (10 * unblocks as i64).min(MAX_UNBLOCK_POINTS),
+        ));
+    }
+
+    if let Some(milestone) = milestone_of(pea, by_id) {
+        if let Some(days) = milestone.days_remaining(now.date_naive()) {
+            if days < 0 {
+                factors.push(Factor::new(
+                    format!("milestone {} overdue by {} day(s)", milestone.id, -days),
+                    DUE_WINDOW_DAYS,
+                ));
+            } else if days < DUE_WINDOW_DAYS {
+                factors.push(Factor::new(
+                    format!("milestone {} due in {} day(s)", milestone.id, days),
+                    DUE_WINDOW_DAYS - days,
+                ));
+            }
+        }
+        let milestone_points = match milestone.priority {
+            PeaPriority::Critical => 20,
+            PeaPriority::High => 10,
+            _ => 0,
+        };
+        if milestone_points > 0 {
+            factors.push(Factor::new(
+                format!(
+                    "in {} priority milestone {}",
+                    milestone.priority, milestone.id
+                ),
+                milestone_points,
+            ));
+        }
+    }
+
+    let idle_days = (now - pea.updated).num_days();
+    let stale_points = (idle_days / 7 * 2).min(MAX_STALE_POINTS);
+    if stale_points > 0 {
+        factors.push(Factor::new(
+            format!("untouched for {} days", idle_days),
+            stale_points,
+        ));
+    }
+    if pea.pea_type == PeaType::Bug {
+        factors.push(Factor::new("bug fix", 5));
+    }
+    factors
+}
+
+/// The nearest open milestone above a pea
+fn milestone_of<'a>(pea: &Pea, by_id: &HashMap<&str, &'a Pea>) -> Option<&'a Pea> {
+    let mut seen = HashSet::new();
+    let mut current = pea.parent.as_deref();
+    // Guard against parent cycles in hand-edited files
+    while let Some(id) = current
+        && seen.insert(id)
+    {
+        let parent = by_id.get(id)?;
+        if parent.pea_type == PeaType::Milestone && parent.is_open() {
+            return Some(parent);
+        }
+        current = parent.parent.as_deref();
+    }
+    None
+}
+
+/// `score 65: in progress +30, high priority +25, ...`
+fn explain(candidate: &Candidate) -> String {
+    let factors: Vec<String> = candidate
+        .factors
+        .iter()
+        .map(|f| format!("{} {:+}", f.reason, f.points))
+        .collect();
+    if factors.is_empty() {
+        return format!("score {}", candidate.score());
+    }
+    format!("score {}: {}", candidate.score(), factors.join(", "))
+}
+
+pub(super) fn priority_rank(priority: PeaPriority) -> u8 {
+    match priority {
+        PeaPriority::Critical => 0,
+        PeaPriority::High => 1,
+        PeaPriority::Normal => 2,
+        PeaPriority::Low => 3,
+        PeaPriority::Deferred => 4,
+    }
+}
+
+/// Bugs before features before tasks
+fn type_rank(pea_type: PeaType) -> u8 {
+    match pea_type {
+        PeaType::Bug => 0,
+        PeaType::Feature => 1,
+        PeaType::Story => 2,
+        PeaType::Chore => 3,
+        PeaType::Research => 4,
+        PeaType::Task => 5,
+        _ => 6,
+    }
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate};
+
+    #[test]
+    fn test_score_factors() {
+        let now = Utc::now();
+        let milestone = Pea::new("peas-m1".into(), "Release".into(), PeaType::Milestone)
+            .with_priority(PeaPriority::High)
+            .with_target_date(Some(now.date_naive() + Duration::days(10)));
+        let epic = Pea::new("peas-e1".into(), "Epic".into(), PeaType::Epic)
+            .with_parent(Some("peas-m1".into()));
+        let mut task = Pea::new("peas-t1".into(), "Task".into(), PeaType::Bug)
+            .with_status(PeaStatus::InProgress)
+            .with_priority(PeaPriority::Low)
+            .with_parent(Some("peas-e1".into()));
+        task.updated = now - Duration::days(15);
+        let peas = [milestone, epic, task];
+        let by_id: HashMap<&str, &Pea> = peas.iter().map(|p| (p.id.as_str(), p)).collect();
+
+        let factors = score(&peas[2], 2, &by_id, now);
+        assert_eq!(
+            factors,
+            vec![
+                Factor::new("in progress", 30),
+                Factor::new("unblocks 2 ticket(s)", 20),
+                Factor::new("milestone peas-m1 due in 10 day(s)", 20),
+                Factor::new("in high priority milestone peas-m1", 10),
+                Factor::new("untouched for 15 days", 4),
+                Factor::new("bug fix", 5),
+            ]
+        );
+        let candidate = Candidate {
+            pea: &peas[2],
+            factors,
+            unblocks: 2,
+        };
+        assert_eq!(candidate.score(), 89);
+        assert_eq!(candidate.reason(), "In progress");
+        assert!(explain(&candidate).starts_with("score 89: in progress +30, unblocks"));
+
+        // An overdue milestone counts fully, a far one not at all
+        let mut milestone = peas[0].clone();
+        milestone.target_date = NaiveDate::from_ymd_opt(2020, 1, 1);
+        let far = peas[0]
+            .clone()
+            .with_target_date(Some(now.date_naive() + Duration::days(90)));
+        for (milestone, expected) in [(milestone, Some(DUE_WINDOW_DAYS)), (far, None)] {
+            let peas = [
+                milestone,
+                peas[2].clone().with_parent(Some("peas-m1".into())),
+            ];
+            let by_id: HashMap<&str, &Pea> = peas.iter().map(|p| (p.id.as_str(), p)).collect();
+            let due = score(&peas[1], 0, &by_id, now)
+                .into_iter()
+                .find(|f| f.reason.contains("due") || f.reason.contains("overdue"))
+                .map(|f| f.points);
+            assert_eq!(due, expected);
+        }
+    }
+
+    #[test]
+    fn test_milestone_of_survives_parent_cycles() {
+        let a =
+            Pea::new("peas-a".into(), "A".into(), PeaType::Task).with_parent(Some("peas-b".into()));
+        let b =
+            Pea::new("peas-b".into(), "B".into(), PeaType::Epic).with_parent(Some("peas-a".into()));
+        let peas = [a, b];
+        let by_id: HashMap<&str, &Pea> = peas.iter().map(|p| (p.id.as_str(), p)).collect();
+        assert!(milestone_of(&peas[0], &by_id).is_none());
+    }
+}
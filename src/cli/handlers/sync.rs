@@ -0,0 +1,50 @@
+use crate::sync::{HttpRemote, sync};
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+
+pub fn handle_sync(ctx: &CommandContext, remote: &str, dry_run: bool, json: bool) -> Result<()> {
+    let client = HttpRemote::new(remote)?;
+    let data_path = ctx.config.data_path(&ctx.root);
+    let report = sync(&ctx.repo, &data_path, remote, &client, dry_run)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    let (pulled, pushed) = if dry_run {
+        ("Would pull", "Would push")
+    } else {
+        ("Pulled", "Pushed")
+    };
+    if report.pulled.is_empty() && report.pushed.is_empty() {
+        println!("Already in sync with {}", remote);
+    }
+    if !report.pulled.is_empty() {
+        println!(
+            "{} {} pea(s): {}",
+            pulled.green(),
+            report.pulled.len(),
+            report.pulled.join(", ").cyan()
+        );
+    }
+    if !report.pushed.is_empty() {
+        println!(
+            "{} {} pea(s): {}",
+            pushed.green(),
+            report.pushed.len(),
+            report.pushed.join(", ").cyan()
+        );
+    }
+    if !report.conflicts.is_empty() {
+        println!(
+            "{}: {} pea(s) changed on both sides, kept the later version: {}",
+            "warning".yellow().bold(),
+            report.conflicts.len(),
+            report.conflicts.join(", ")
+        );
+    }
+    Ok(())
+}
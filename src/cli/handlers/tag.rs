@@ -0,0 +1,178 @@
+use crate::cli::commands::TagAction;
+use crate::config::TagPolicy;
+use anyhow::Result;
+use colored::Colorize;
+
+use super::CommandContext;
+use super::utils::{UndoBatch, warn_new_tags};
+
+pub fn handle_tag(ctx: &CommandContext, action: TagAction) -> Result<()> {
+    match action {
+        TagAction::List { names, json } => handle_tag_list(ctx, names, json),
+        TagAction::Rename {
+            old,
+            new,
+            dry_run,
+            json,
+        } => {
+            ctx.repo.validator().tag(&new)?;
+            if old == new {
+                anyhow::bail!("Old and new tag are the same");
+            }
+            warn_new_tags(ctx, std::slice::from_ref(&new));
+            rewrite_tag(ctx, &old, Some(&new), dry_run, json)
+        }
+        TagAction::Delete { tag, dry_run, json } => rewrite_tag(ctx, &tag, None, dry_run, json),
+    }
+}
+
+fn handle_tag_list(ctx: &CommandContext, names: bool, json: bool) -> Result<()> {
+    let mut counts = ctx.repo.tag_counts()?;
+    // Under the strict policy only configured tags can be completed
+    if names && ctx.config.peas.tag_policy == TagPolicy::Strict {
+        counts.clear();
+    }
+    // Configured tags are listed even when no pea uses them yet
+    for name in ctx.config.tags.keys() {
+        counts.entry(name.clone()).or_default();
+    }
+
+    if names {
+        for name in counts.keys() {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
+    let mut tags: Vec<(String, usize)> = counts.into_iter().collect();
+    tags.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    if json {
+        let output: Vec<_> = tags
+            .iter()
+            .map(|(name, count)| {
+                let settings = ctx.config.tags.get(name);
+                serde_json::json!({
+                    "tag": name,
+                    "count": count,
+                    "description": settings.and_then(|s| s.description.as_deref()),
+                    "color": settings.and_then(|s| s.color.as_deref()),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&output)?);
+        return Ok(());
+    }
+
+    if tags.is_empty() {
+        println!("No tags found.");
+        return Ok(());
+    }
+
+    let width = tags.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    for (name, count) in &tags {
+        let description = ctx
+            .config
+            .tags
+            .get(name)
+            .and_then(|s| s.description.as_deref())
+            .unwrap_or_default();
+        println!(
+            "{}  {:>4}  {}",
+            format!("{:<width$}", name, width = width).magenta(),
+            count,
+            description.dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Rename `tag` to `replacement` on every active pea, or remove it when `replacement` is None
+fn rewrite_tag(
+    ctx: &CommandContext,
+    tag: &str,
+    replacement: Option<&str>,
+    dry_run: bool,
+    json: bool,
+) -> Result<()> {
+    let affected: Vec<_> = ctx
+        .repo
+        .list()?
+        .into_iter()
+        .filter(|p| p.tags.iter().any(|t| t == tag))
+        .collect();
+
+    let description = match replacement {
+        Some(new) => format!("Rename tag {} to {}", tag, new),
+        None => format!("Delete tag {}", tag),
+    };
+    let mut batch = UndoBatch::new(ctx, description);
+    let mut updated = Vec::new();
+    for mut pea in affected {
+        let mut tags = Vec::with_capacity(pea.tags.len());
+        for t in pea.tags.drain(..) {
+            let t = if t == tag {
+                match replacement {
+                    Some(new) => new.to_string(),
+                    None => continue,
+                }
+            } else {
+                t
+            };
+            // A pea that already had the new tag keeps a single copy
+            if !tags.contains(&t) {
+                tags.push(t);
+            }
+        }
+        pea.tags = tags;
+
+        if !dry_run {
+            batch.update(&mut pea)?;
+        }
+        updated.push(pea.id);
+    }
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "tag": tag,
+                "renamed_to": replacement,
+                "updated": updated,
+                "dry_run": dry_run,
+            }))?
+        );
+        return Ok(());
+    }
+
+    if updated.is_empty() {
+        println!("No peas have tag '{}'.", tag);
+    } else {
+        let verb = match (replacement, dry_run) {
+            (Some(_), true) => "Would rename",
+            (Some(_), false) => "Renamed",
+            (None, true) => "Would remove",
+            (None, false) => "Removed",
+        };
+        let target = match replacement {
+            Some(new) => format!("{} -> {}", tag.magenta(), new.magenta()),
+            None => tag.magenta().to_string(),
+        };
+        println!(
+            "{} tag {} on {} pea(s): {}",
+            verb.green(),
+            target,
+            updated.len(),
+            updated.join(", ").cyan()
+        );
+    }
+    if ctx.config.tags.contains_key(tag) {
+        println!(
+            "{}: tag '{}' has settings in .peas/config.toml; update its [tags.{}] section by hand",
+            "note".yellow(),
+            tag,
+            tag
+        );
+    }
+    Ok(())
+}
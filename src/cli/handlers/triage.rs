@@ -0,0 +1,188 @@
+use crate::model::{Pea, PeaPriority, PeaStatus, PeaType};
+use anyhow::Result;
+use colored::Colorize;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, BufRead};
+
+use super::CommandContext;
+use super::utils::{UndoBatch, format_status, prompt, resolve_id, warn_new_tags};
+
+/// Whether a pea still needs triage: a draft, or, when there are open
+/// milestones or epics to file it under, a todo ticket without a parent
+fn needs_triage(pea: &Pea, has_containers: bool) -> bool {
+    match pea.status {
+        PeaStatus::Draft => true,
+        PeaStatus::Todo => {
+            has_containers
+                && pea.parent.is_none()
+                && !matches!(pea.pea_type, PeaType::Milestone | PeaType::Epic)
+        }
+        _ => false,
+    }
+}
+
+pub fn handle_triage(ctx: &CommandContext, random: bool, list: bool, json: bool) -> Result<()> {
+    let peas = ctx.repo.list()?;
+    let has_containers = peas
+        .iter()
+        .any(|p| p.is_open() && matches!(p.pea_type, PeaType::Milestone | PeaType::Epic));
+    let mut queue: Vec<Pea> = peas
+        .into_iter()
+        .filter(|p| needs_triage(p, has_containers))
+        .collect();
+    if random {
+        let state = RandomState::new();
+        queue.sort_by_cached_key(|p| state.hash_one(&p.id));
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&queue)?);
+        return Ok(());
+    }
+    if queue.is_empty() {
+        println!("Nothing to triage.");
+        return Ok(());
+    }
+    if list {
+        for pea in &queue {
+            println!(
+                "{} {} [{}] {}",
+                pea.id.cyan(),
+                format_status(pea.status),
+                format!("{}", pea.pea_type).blue(),
+                pea.title
+            );
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut batch = UndoBatch::new(ctx, "Triage");
+    let (mut triaged, mut skipped) = (0, 0);
+    let total = queue.len();
+    'queue: for (i, mut pea) in queue.into_iter().enumerate() {
+        println!(
+            "\n{} {} {} [{}] {}",
+            format!("[{}/{}]", i + 1, total).dimmed(),
+            pea.id.cyan(),
+            format_status(pea.status),
+            format!("{}", pea.pea_type).blue(),
+            pea.title.bold()
+        );
+        for line in pea.body.lines().filter(|l| !l.trim().is_empty()).take(3) {
+            println!("  {}", line.dimmed());
+        }
+        let original = pea.clone();
+
+        // The type prompt also takes the skip and quit answers
+        loop {
+            let question = format!("Type [{}] (s: skip, q: quit)", pea.pea_type);
+            let Some(answer) = prompt(&mut lines, &question)? else {
+                break 'queue;
+            };
+            match answer.as_str() {
+                "" => break,
+                "q" => break 'queue,
+                "s" => {
+                    skipped += 1;
+                    continue 'queue;
+                }
+                answer => match answer.parse::<PeaType>() {
+                    Ok(pea_type) => {
+                        pea.pea_type = pea_type;
+                        break;
+                    }
+                    Err(e) => println!("  {}", e.to_string().red()),
+                },
+            }
+        }
+        loop {
+            let question = format!("Priority [{}]", pea.priority);
+            let Some(answer) = prompt(&mut lines, &question)? else {
+                break 'queue;
+            };
+            if answer.is_empty() {
+                break;
+            }
+            match answer.parse::<PeaPriority>() {
+                Ok(priority) => {
+                    pea.priority = priority;
+                    break;
+                }
+                Err(e) => println!("  {}", e.to_string().red()),
+            }
+        }
+        loop {
+            let current = pea.parent.as_deref().unwrap_or("none");
+            let question = format!("Parent [{}] (-: none)", current);
+            let Some(answer) = prompt(&mut lines, &question)? else {
+                break 'queue;
+            };
+            match answer.as_str() {
+                "" => break,
+                "-" => {
+                    pea.parent = None;
+                    break;
+                }
+                answer => {
+                    let parent = resolve_id(ctx, answer.to_string());
+                    if parent != pea.id && ctx.repo.get(&parent).is_ok() {
+                        pea.parent = Some(parent);
+                        break;
+                    }
+                    println!("  {}", format!("No other pea '{}'", answer).red());
+                }
+            }
+        }
+        let question = format!("Tags [{}] (comma-separated, -: none)", pea.tags.join(", "));
+        let Some(answer) = prompt(&mut lines, &question)? else {
+            break 'queue;
+        };
+        match answer.as_str() {
+            "" => {}
+            "-" => pea.tags.clear(),
+            answer => {
+                let mut tags: Vec<String> = Vec::new();
+                for tag in answer.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+                    if !tags.iter().any(|t| t == tag) {
+                        tags.push(tag.to_string());
+                    }
+                }
+                let added: Vec<String> = tags
+                    .iter()
+                    .filter(|t| !original.tags.contains(t))
+                    .cloned()
+                    .collect();
+                warn_new_tags(ctx, &added);
+                pea.tags = tags;
+            }
+        }
+
+        // Triaged drafts are ready to be worked on
+        if pea.status == PeaStatus::Draft {
+            pea.status = PeaStatus::Todo;
+        }
+        if pea == original {
+            println!("  {}", "No changes".dimmed());
+            skipped += 1;
+            continue;
+        }
+        // A rejected change, e.g. an unknown tag, skips the pea rather than ending triage
+        if let Err(e) = batch.update(&mut pea) {
+            println!("  {}", e.to_string().red());
+            skipped += 1;
+            continue;
+        }
+        triaged += 1;
+        println!("  {} {}", "Triaged".green(), pea.id.cyan());
+    }
+
+    println!(
+        "\n{} {} pea(s), skipped {}",
+        "Triaged".green(),
+        triaged,
+        skipped
+    );
+    Ok(())
+}
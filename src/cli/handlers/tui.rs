@@ -1,8 +1,29 @@
 use anyhow::Result;
 
 use super::CommandContext;
+use crate::error::PeasError;
+use crate::search::SearchQuery;
+use crate::tui::TuiOptions;
+use crate::tui::app::StartView;
 
-pub fn handle_tui(ctx: CommandContext) -> Result<()> {
-    crate::tui::run_tui(ctx.config, ctx.root)?;
+pub fn handle_tui(
+    mut ctx: CommandContext,
+    select: Option<String>,
+    filter: Option<String>,
+    view: Option<StartView>,
+    relative: bool,
+) -> Result<()> {
+    ctx.config.display.relative |= relative;
+    if let Some(filter) = &filter {
+        SearchQuery::parse_terms(filter, false).map_err(|e| PeasError::validation("filter", e))?;
+    }
+    // Accept short IDs like the other commands; archived peas are looked up by the TUI
+    let select = select.map(|id| ctx.repo.get(&id).map_or(id, |pea| pea.id));
+    let options = TuiOptions {
+        select,
+        filter,
+        view,
+    };
+    crate::tui::run_tui(ctx.config, ctx.root, options)?;
     Ok(())
 }
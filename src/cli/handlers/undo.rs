@@ -1,11 +1,26 @@
-use crate::undo::UndoManager;
+use crate::storage::parse_markdown;
+use crate::undo::{UndoEntry, UndoOperation};
 use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
 
 use super::CommandContext;
+use super::blame::field_changes;
 
-pub fn handle_undo(ctx: &CommandContext, json: bool) -> Result<()> {
-    let data_path = ctx.config.data_path(&ctx.root);
-    let undo_manager = UndoManager::new(&data_path);
+/// One file change an undo step would revert
+#[derive(Debug, Serialize)]
+struct RevertStep {
+    action: &'static str,
+    id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    changes: Vec<String>,
+}
+
+pub fn handle_undo(ctx: &CommandContext, show: bool, json: bool) -> Result<()> {
+    let undo_manager = ctx.repo.undo_manager();
+    if show {
+        return show_last(ctx, &undo_manager.entries()?, json);
+    }
 
     match undo_manager.undo() {
         Ok(msg) => {
@@ -37,3 +52,92 @@ pub fn handle_undo(ctx: &CommandContext, json: bool) -> Result<()> {
     }
     Ok(())
 }
+
+/// Preview the last undo step
+fn show_last(ctx: &CommandContext, entries: &[UndoEntry], json: bool) -> Result<()> {
+    let Some(entry) = entries.last() else {
+        if json {
+            println!("{}", serde_json::json!({ "entry": null, "remaining": 0 }));
+        } else {
+            println!("{}", "Nothing to undo".dimmed());
+        }
+        return Ok(());
+    };
+    // Reverted latest first, like the undo itself
+    let steps: Vec<RevertStep> = entry
+        .operation
+        .flatten()
+        .into_iter()
+        .rev()
+        .map(|op| revert_step(ctx, op))
+        .collect();
+    let remaining = entries.len() - 1;
+
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "entry": {
+                    "recorded": entry.recorded,
+                    "description": entry.description,
+                    "ids": entry.ids,
+                    "steps": steps,
+                },
+                "remaining": remaining,
+            }))?
+        );
+        return Ok(());
+    }
+
+    println!(
+        "{} {} {}",
+        "Would undo:".yellow(),
+        entry.description,
+        format!("({})", entry.recorded.format("%Y-%m-%d %H:%M UTC")).dimmed()
+    );
+    for step in &steps {
+        println!("  {} {}", step.action, step.id.cyan());
+        for change in &step.changes {
+            println!("    {}", change);
+        }
+    }
+    if remaining > 0 {
+        println!("{}", format!("{} earlier undo step(s)", remaining).dimmed());
+    }
+    Ok(())
+}
+
+fn revert_step(ctx: &CommandContext, op: &UndoOperation) -> RevertStep {
+    let (action, changes) = match op {
+        UndoOperation::Create { .. } => ("delete", Vec::new()),
+        UndoOperation::Update {
+            id,
+            previous_content,
+            ..
+        } => {
+            // Fields going back to their previous values, when both versions parse
+            let changes = match (ctx.repo.get(id), parse_markdown(previous_content)) {
+                (Ok(current), Ok(previous)) => {
+                    let mut changes = field_changes(Some(&current), &previous);
+                    if current.body != previous.body {
+                        changes.push("body changed".to_string());
+                    }
+                    changes
+                }
+                _ => Vec::new(),
+            };
+            ("restore", changes)
+        }
+        UndoOperation::Delete { .. } => ("recreate", Vec::new()),
+        UndoOperation::Archive { .. } => ("unarchive", Vec::new()),
+        UndoOperation::Unarchive { .. } => ("archive", Vec::new()),
+        UndoOperation::File { .. } => ("restore", Vec::new()),
+        UndoOperation::Move { .. } => ("move back", Vec::new()),
+        UndoOperation::Batch { .. } => unreachable!("batches are flattened"),
+    };
+    RevertStep {
+        action,
+        id: op.id().to_string(),
+        changes,
+    }
+}
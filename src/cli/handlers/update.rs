@@ -1,9 +1,11 @@
 use crate::cli::commands::{PeaPriorityArg, PeaStatusArg, PeaTypeArg};
+use crate::model::PeaStatus;
+use crate::validation;
 use anyhow::Result;
 use colored::Colorize;
 
 use super::CommandContext;
-use super::utils::record_undo_update;
+use super::utils::{UndoBatch, resolve_id, warn_new_tags};
 
 #[allow(clippy::too_many_arguments)]
 pub fn handle_update(
@@ -15,6 +17,10 @@ pub fn handle_update(
     priority: Option<PeaPriorityArg>,
     body: Option<String>,
     parent: Option<String>,
+    component: Option<String>,
+    assignee: Option<String>,
+    target_date: Option<String>,
+    defer_until: Option<String>,
     add_tag: Vec<String>,
     remove_tag: Vec<String>,
     add_blocks: Vec<String>,
@@ -23,6 +29,7 @@ pub fn handle_update(
     remove_blocked_by: Vec<String>,
     add_ref: Vec<String>,
     remove_ref: Vec<String>,
+    reopen: bool,
     json: bool,
     dry_run: bool,
 ) -> Result<()> {
@@ -37,16 +44,44 @@ pub fn handle_update(
     }
     if let Some(s) = status {
         pea.status = s.into();
+    } else if reopen && pea.is_closed() {
+        pea.status = PeaStatus::Todo;
     }
     if let Some(p) = priority {
         pea.priority = p.into();
     }
     if let Some(b) = body {
-        pea.body = b;
+        pea.body = b.into();
     }
     if let Some(p) = parent {
-        pea.parent = if p.is_empty() { None } else { Some(p) };
+        pea.parent = if p.is_empty() {
+            None
+        } else {
+            Some(resolve_id(ctx, p))
+        };
+    }
+    if let Some(c) = component {
+        pea.component = if c.is_empty() { None } else { Some(c) };
+    }
+    if let Some(a) = assignee {
+        pea.assignee = if a.is_empty() { None } else { Some(a) };
+    }
+    if let Some(d) = target_date {
+        pea.target_date = if d.is_empty() {
+            None
+        } else {
+            Some(validation::parse_target_date(&d)?)
+        };
+    }
+    validation::validate_target_date(pea.pea_type, pea.target_date)?;
+    if let Some(d) = defer_until {
+        pea.defer_until = if d.is_empty() {
+            None
+        } else {
+            Some(validation::parse_defer_until(&d)?)
+        };
     }
+    warn_new_tags(ctx, &add_tag);
     for t in add_tag {
         if !pea.tags.contains(&t) {
             pea.tags.push(t);
@@ -56,13 +91,15 @@ pub fn handle_update(
         pea.tags.retain(|x| x != &t);
     }
     // --add-blocks: this pea blocks the given IDs
-    for b in &add_blocks {
-        if !pea.blocking.contains(b) {
-            pea.blocking.push(b.clone());
+    for b in add_blocks {
+        let b = resolve_id(ctx, b);
+        if !pea.blocking.contains(&b) {
+            pea.blocking.push(b);
         }
     }
-    for b in &remove_blocks {
-        pea.blocking.retain(|x| x != b);
+    for b in remove_blocks {
+        let b = resolve_id(ctx, b);
+        pea.blocking.retain(|x| x != &b);
     }
     // --add-blocked-by: the given IDs block this pea (inverse: add this pea's ID to the other pea's blocking list)
     // We collect these to apply after dry-run check, since they modify other peas
@@ -79,6 +116,12 @@ pub fn handle_update(
     }
 
     if dry_run {
+        // Rejected as a real update would be
+        if reopen {
+            ctx.repo.preview_reopen(&pea)?;
+        } else {
+            ctx.repo.preview_update(&pea)?;
+        }
         // Build a list of changes
         let mut changes = Vec::new();
         if pea.title != original.title {
@@ -99,6 +142,18 @@ pub fn handle_update(
         if pea.parent != original.parent {
             changes.push(format!("parent: {:?} -> {:?}", original.parent, pea.parent));
         }
+        if pea.target_date != original.target_date {
+            changes.push(format!(
+                "target_date: {:?} -> {:?}",
+                original.target_date, pea.target_date
+            ));
+        }
+        if pea.defer_until != original.defer_until {
+            changes.push(format!(
+                "defer_until: {:?} -> {:?}",
+                original.defer_until, pea.defer_until
+            ));
+        }
         if pea.tags != original.tags {
             changes.push(format!("tags: {:?} -> {:?}", original.tags, pea.tags));
         }
@@ -148,12 +203,15 @@ pub fn handle_update(
         return Ok(());
     }
 
-    // Record undo operation before update
-    let old_path = ctx.repo.find_file_by_id(&pea.id)?;
-    record_undo_update(ctx, &pea.id, &old_path);
+    // The pea and the blockers it changes are undone together
+    let mut batch = UndoBatch::new(ctx, format!("Update {}", id));
 
     // NOTE: No touch() call - update() handles it internally now
-    let path = ctx.repo.update(&mut pea)?;
+    let path = if reopen {
+        batch.reopen(&mut pea)?
+    } else {
+        batch.update(&mut pea)?
+    };
     let filename = path
         .file_name()
         .map(|f| f.to_string_lossy())
@@ -165,13 +223,13 @@ pub fn handle_update(
             let mut blocker = ctx.repo.get(blocker_id)?;
             if !blocker.blocking.contains(&id) {
                 blocker.blocking.push(id.clone());
-                ctx.repo.update(&mut blocker)?;
+                batch.update(&mut blocker)?;
             }
         }
         for blocker_id in &remove_blocked_by {
             let mut blocker = ctx.repo.get(blocker_id)?;
             blocker.blocking.retain(|x| x != &id);
-            ctx.repo.update(&mut blocker)?;
+            batch.update(&mut blocker)?;
         }
     }
 
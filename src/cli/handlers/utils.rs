@@ -1,11 +1,16 @@
-use crate::model::{Pea, PeaPriority, PeaStatus};
-use crate::undo::UndoManager;
+use crate::config::TagPolicy;
+use crate::error::PeasError;
+use crate::model::{Pea, PeaPriority, PeaStatus, SizeTotals};
+use crate::read_markers::ReadState;
+use crate::removal::{ChildPolicy, RemovalKind, RemovalPlan};
+use crate::undo::UndoOperation;
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::io::{self, Read};
-use std::path::Path;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 
 use super::CommandContext;
+use super::blame::field_changes;
 
 /// Resolve body content from CLI arg, file, or stdin
 pub fn resolve_body(body: Option<String>, body_file: Option<String>) -> Result<Option<String>> {
@@ -28,6 +33,34 @@ pub fn resolve_body(body: Option<String>, body_file: Option<String>) -> Result<O
     Ok(None)
 }
 
+/// Read a body from the system clipboard, dropping control characters
+pub fn clipboard_body() -> Result<String> {
+    let text = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| anyhow::anyhow!("Could not read the clipboard: {}", e))?;
+    let body = crate::validation::sanitize_pasted(&text).trim().to_string();
+    if body.is_empty() {
+        return Err(PeasError::validation("body", "The clipboard holds no text").into());
+    }
+    Ok(body)
+}
+
+/// Ask a question, returning the trimmed answer or `None` at end of input
+pub fn prompt(
+    lines: &mut impl Iterator<Item = io::Result<String>>,
+    question: &str,
+) -> Result<Option<String>> {
+    print!("  {}: ", question);
+    io::stdout().flush()?;
+    match lines.next() {
+        Some(line) => Ok(Some(line?.trim().to_string())),
+        None => {
+            println!();
+            Ok(None)
+        }
+    }
+}
+
 /// Validate body file path to prevent path traversal and reading sensitive files
 fn validate_body_file_path(path_str: &str) -> Result<()> {
     use std::path::Path;
@@ -168,38 +201,250 @@ pub fn print_pea_list(peas: &[Pea]) {
     }
 
     for pea in peas {
-        let status_str = format_status(pea.status);
-        let type_str = format!("{}", pea.pea_type).blue();
+        println!("{}", format_pea_line(pea));
+    }
+}
+
+/// One line of `print_pea_list`: ID, status, type, title and claim
+pub fn format_pea_line(pea: &Pea) -> String {
+    format!(
+        "{} {} [{}] {}{}",
+        pea.id.cyan(),
+        format_status(pea.status),
+        format!("{}", pea.pea_type).blue(),
+        pea.title,
+        format_claim(pea)
+    )
+}
+
+/// Suffix for a pea with an active claim, e.g. " (claimed by agent-x)", or nothing
+pub fn format_claim(pea: &Pea) -> String {
+    match pea.active_claim(chrono::Utc::now()) {
+        Some(claim) => format!(" {}", format!("(claimed by {})", claim.by).magenta()),
+        None => String::new(),
+    }
+}
+
+/// One-character read marker: green for new, yellow for changed, blank when read
+pub fn format_read_state(state: ReadState) -> colored::ColoredString {
+    match state {
+        ReadState::New => "●".green().bold(),
+        ReadState::Changed => "●".yellow().bold(),
+        ReadState::Read => " ".normal(),
+    }
+}
+
+/// Time left until a target date, e.g. "5 days left" or "overdue by 2 days"
+pub fn format_days_remaining(days: i64) -> String {
+    match days {
+        0 => "due today".to_string(),
+        1 => "1 day left".to_string(),
+        -1 => "overdue by 1 day".to_string(),
+        d if d > 1 => format!("{} days left", d),
+        d => format!("overdue by {} days", -d),
+    }
+}
+
+/// Point roll-up, e.g. "8/21 points done, 2 unestimated"
+pub fn format_size_totals(totals: SizeTotals) -> String {
+    let mut text = format!("{}/{} points done", totals.done, totals.points);
+    if totals.unestimated > 0 {
+        text.push_str(&format!(", {} unestimated", totals.unestimated));
+    }
+    text
+}
+
+/// Warn about tags that are new to the project when the tag policy is `warn`
+///
+/// Call before writing, while the new tags are not yet in use.
+pub fn warn_new_tags(ctx: &CommandContext, tags: &[String]) {
+    if ctx.config.peas.tag_policy != TagPolicy::Warn || tags.is_empty() {
+        return;
+    }
+    let Ok(in_use) = ctx.repo.tag_counts() else {
+        return;
+    };
+    for tag in ctx.config.new_tags(tags, &in_use) {
+        eprintln!(
+            "{}: '{}' is a new tag; check for typos or add a [tags.{}] section to .peas/config.toml",
+            "warning".yellow().bold(),
+            tag,
+            tag
+        );
+    }
+}
+
+/// ID of the pea a reference names by ID, ID suffix or slug
+///
+/// Unknown references are returned as given, for validation to report.
+pub fn resolve_id(ctx: &CommandContext, reference: String) -> String {
+    ctx.repo.get(&reference).map(|p| p.id).unwrap_or(reference)
+}
+
+/// How a delete or archive treats the pea's children, from its flags
+pub fn child_policy(
+    ctx: &CommandContext,
+    cascade: bool,
+    reparent_to: Option<String>,
+    orphan: bool,
+) -> ChildPolicy {
+    match reparent_to {
+        _ if cascade => ChildPolicy::Cascade,
+        Some(target) => ChildPolicy::Reparent(resolve_id(ctx, target)),
+        None if orphan => ChildPolicy::Orphan,
+        None => ChildPolicy::Refuse,
+    }
+}
+
+/// Print what a removal does besides removing the pea itself
+pub fn print_removal_effects(plan: &RemovalPlan) {
+    let verb = match plan.kind {
+        RemovalKind::Delete => "deleted",
+        RemovalKind::Archive => "archived",
+    };
+    for id in plan.removed.iter().skip(1) {
+        println!("  {} {} too", id.cyan(), verb);
+    }
+    for pea in &plan.updated {
+        let change = match &pea.parent {
+            _ if !plan.moved_children.contains(&pea.id) => "blocking list updated".to_string(),
+            Some(parent) => format!("moved under {}", parent),
+            None => "parent cleared".to_string(),
+        };
+        println!("  {} {}", pea.id.cyan(), change.dimmed());
+    }
+    if !plan.mentioned_by.is_empty() {
         println!(
-            "{} {} [{}] {}",
-            pea.id.cyan(),
-            status_str,
-            type_str,
-            pea.title
+            "  {} still mentioned in the body of {}",
+            "Note:".yellow(),
+            plan.mentioned_by.join(", ").cyan()
         );
     }
 }
 
+/// Run `$EDITOR` (or `$VISUAL`) on a file and wait for it to close
+pub fn run_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("EDITOR")
+        .or_else(|_| std::env::var("VISUAL"))
+        .unwrap_or_else(|_| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        });
+    let status = std::process::Command::new(&editor)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Failed to run editor '{}'", editor))?;
+    if !status.success() {
+        anyhow::bail!("Editor exited with non-zero status");
+    }
+    Ok(())
+}
+
 /// Record create operation with undo manager
 pub fn record_undo_create(ctx: &CommandContext, id: &str, path: &Path) {
-    let undo_manager = UndoManager::new(&ctx.config.data_path(&ctx.root));
+    let undo_manager = ctx.repo.undo_manager();
     let _ = crate::undo::record_create(&undo_manager, id, path);
 }
 
+/// Report the update a dry run would make, in the format of `peas update --dry-run`
+pub fn print_dry_run(before: &Pea, after: &Pea, json: bool) -> Result<()> {
+    let changes = field_changes(Some(before), after);
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "dry_run": true,
+                "id": after.id,
+                "changes": changes,
+                "before": before,
+                "after": after
+            }))?
+        );
+    } else if changes.is_empty() {
+        println!(
+            "{} {} (no changes)",
+            "Would update:".yellow(),
+            after.id.cyan()
+        );
+    } else {
+        println!("{} {}", "Would update:".yellow(), after.id.cyan());
+        for change in changes {
+            println!("  {}", change);
+        }
+    }
+    Ok(())
+}
+
 /// Record update operation with undo manager
 pub fn record_undo_update(ctx: &CommandContext, id: &str, old_path: &Path) {
-    let undo_manager = UndoManager::new(&ctx.config.data_path(&ctx.root));
+    let undo_manager = ctx.repo.undo_manager();
     let _ = crate::undo::record_update(&undo_manager, id, old_path);
 }
 
-/// Record delete operation with undo manager
-pub fn record_undo_delete(ctx: &CommandContext, id: &str, file_path: &Path) {
-    let undo_manager = UndoManager::new(&ctx.config.data_path(&ctx.root));
-    let _ = crate::undo::record_delete(&undo_manager, id, file_path);
+/// Record an undo step made up elsewhere, such as a removal plan's
+pub fn record_undo_step(ctx: &CommandContext, operation: UndoOperation) {
+    let _ = ctx.repo.undo_manager().record(operation);
+}
+
+/// Record the operations of a command that changed several peas as one undo step
+pub fn record_undo_batch(ctx: &CommandContext, description: &str, operations: Vec<UndoOperation>) {
+    let undo_manager = ctx.repo.undo_manager();
+    let _ = crate::undo::record_batch(&undo_manager, description, operations);
+}
+
+/// Updates made by one command, recorded as a single undo step when dropped
+///
+/// Recording on drop keeps the peas written before an error undoable.
+pub struct UndoBatch<'a> {
+    ctx: &'a CommandContext,
+    description: String,
+    operations: Vec<UndoOperation>,
+}
+
+impl<'a> UndoBatch<'a> {
+    pub fn new(ctx: &'a CommandContext, description: impl Into<String>) -> Self {
+        Self {
+            ctx,
+            description: description.into(),
+            operations: Vec::new(),
+        }
+    }
+
+    /// Create a pea, to be deleted again on undo
+    pub fn create(&mut self, pea: &Pea) -> Result<PathBuf> {
+        let path = self.ctx.repo.create(pea)?;
+        self.operations.push(UndoOperation::Create {
+            id: pea.id.clone(),
+            file_path: path.clone(),
+        });
+        Ok(path)
+    }
+
+    /// Update a pea, capturing its previous content first
+    pub fn update(&mut self, pea: &mut Pea) -> Result<PathBuf> {
+        let old_path = self.ctx.repo.find_file_by_id(&pea.id)?;
+        let operation = UndoOperation::update_from_file(&pea.id, &old_path)?;
+        let path = self.ctx.repo.update(pea)?;
+        self.operations.push(operation);
+        Ok(path)
+    }
+
+    /// Reopen a closed pea, capturing its previous content first
+    pub fn reopen(&mut self, pea: &mut Pea) -> Result<PathBuf> {
+        let old_path = self.ctx.repo.find_file_by_id(&pea.id)?;
+        let operation = UndoOperation::update_from_file(&pea.id, &old_path)?;
+        let path = self.ctx.repo.reopen(pea)?;
+        self.operations.push(operation);
+        Ok(path)
+    }
 }
 
-/// Record archive operation with undo manager
-pub fn record_undo_archive(ctx: &CommandContext, id: &str, original: &Path, archive: &Path) {
-    let undo_manager = UndoManager::new(&ctx.config.data_path(&ctx.root));
-    let _ = crate::undo::record_archive(&undo_manager, id, original, archive);
+impl Drop for UndoBatch<'_> {
+    fn drop(&mut self) {
+        let operations = std::mem::take(&mut self.operations);
+        record_undo_batch(self.ctx, &self.description, operations);
+    }
 }
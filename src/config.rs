@@ -1,4 +1,5 @@
 use crate::error::{PeasError, Result};
+use crate::model::SizeScale;
 use crate::storage::FrontmatterFormat;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
@@ -22,6 +23,45 @@ pub enum IdMode {
     Sequential,
 }
 
+/// How pea files are named
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NamingScheme {
+    /// ID and title slug, e.g. `peas-abc12--fix-login-bug.md` (default)
+    #[default]
+    IdSlug,
+    /// ID only, e.g. `peas-abc12.md`
+    Id,
+}
+
+/// How tags outside the `[tags]` sections are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TagPolicy {
+    /// Any tag is accepted (default)
+    #[default]
+    Any,
+    /// Tags that are neither configured nor used by any pea yet produce a warning
+    Warn,
+    /// Only configured tags are accepted
+    Strict,
+}
+
+/// How `peas start` and `peas done` carry a status change up to a parent
+/// epic or feature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Cascade {
+    /// Parents are left alone unless `--cascade` is given (default)
+    #[default]
+    Off,
+    /// Starting a child starts its parent; completing the last open child
+    /// asks whether to complete the parent
+    Prompt,
+    /// Like `prompt`, but completes the parent without asking
+    Auto,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PeasConfig {
     #[serde(default)]
@@ -29,6 +69,53 @@ pub struct PeasConfig {
 
     #[serde(default)]
     pub tui: TuiSettings,
+
+    #[serde(default, skip_serializing_if = "LogSettings::is_default")]
+    pub log: LogSettings,
+
+    #[serde(default, skip_serializing_if = "ServerSettings::is_default")]
+    pub server: ServerSettings,
+
+    #[serde(default, skip_serializing_if = "LintSettings::is_default")]
+    pub lint: LintSettings,
+
+    #[serde(default, skip_serializing_if = "OpenSettings::is_default")]
+    pub open: OpenSettings,
+
+    #[serde(default, skip_serializing_if = "UndoSettings::is_default")]
+    pub undo: UndoSettings,
+
+    #[serde(default, skip_serializing_if = "BulkSettings::is_default")]
+    pub bulk: BulkSettings,
+
+    #[serde(default, skip_serializing_if = "DisplaySettings::is_default")]
+    pub display: DisplaySettings,
+
+    #[serde(default, skip_serializing_if = "SemanticSettings::is_default")]
+    pub semantic: SemanticSettings,
+
+    #[serde(default, skip_serializing_if = "ValidationSettings::is_default")]
+    pub validation: ValidationSettings,
+
+    /// Optional description and color per tag (`[tags.<name>]` sections)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub tags: std::collections::BTreeMap<String, TagSettings>,
+
+    /// Project areas a pea can belong to, with their owners (`[components.<name>]` sections)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub components: std::collections::BTreeMap<String, ComponentSettings>,
+
+    /// Tags and components added to new peas by title and body patterns (`[autotag.<name>]` sections)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub autotag: std::collections::BTreeMap<String, AutoTagSettings>,
+
+    /// Automation rules run on writes, in name order (`[rules.<name>]` sections)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub rules: std::collections::BTreeMap<String, RuleSettings>,
+
+    /// Who is making changes, from `--actor` or `PEAS_ACTOR`; never read from the file
+    #[serde(skip)]
+    pub actor: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -55,6 +142,24 @@ pub struct PeasSettings {
 
     #[serde(default = "default_frontmatter")]
     pub frontmatter: String,
+
+    #[serde(default)]
+    pub tag_policy: TagPolicy,
+
+    /// Scale that `size` estimates are given on
+    #[serde(default)]
+    pub size_scale: SizeScale,
+
+    /// Whether file names include the title slug; files are renamed on their next write
+    #[serde(default)]
+    pub naming_scheme: NamingScheme,
+
+    /// Whether completed and scrapped peas are read-only until reopened
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lock_closed: bool,
+
+    #[serde(default)]
+    pub cascade: Cascade,
 }
 
 fn default_prefix() -> String {
@@ -81,6 +186,697 @@ fn default_frontmatter() -> String {
 pub struct TuiSettings {
     #[serde(default = "default_use_type_emojis")]
     pub use_type_emojis: bool,
+
+    #[serde(default, skip_serializing_if = "KeySettings::is_default")]
+    pub keys: KeySettings,
+}
+
+/// Base keymap preset for the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyPreset {
+    /// Arrow keys plus vim-style letters (default)
+    #[default]
+    Default,
+    /// Vim motions (Ctrl+F/Ctrl+B paging)
+    Vim,
+    /// Emacs motions (Ctrl+N/Ctrl+P, Ctrl+V/Alt+V, Ctrl+S search)
+    Emacs,
+}
+
+/// One key or a list of keys bound to a TUI action
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// `[tui.keys]` section: a preset plus per-action overrides
+///
+/// ```toml
+/// [tui.keys]
+/// preset = "vim"
+/// quit = ["q", "ctrl+c"]
+/// archive = "x"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeySettings {
+    #[serde(default)]
+    pub preset: KeyPreset,
+
+    /// Action name -> key(s), replacing the preset's binding for that action
+    #[serde(flatten)]
+    pub bindings: std::collections::BTreeMap<String, KeyList>,
+}
+
+impl KeySettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Project-level logging settings (`[log]` section)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogSettings {
+    /// Keep a daily-rotated JSON log under `.peas/logs/`
+    #[serde(default)]
+    pub file: bool,
+
+    /// Number of rotated log files to keep
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+impl LogSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+fn default_log_max_files() -> usize {
+    crate::logging::DEFAULT_MAX_LOG_FILES
+}
+
+impl Default for LogSettings {
+    fn default() -> Self {
+        Self {
+            file: false,
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+/// Thresholds and severities for `peas lint` (`[lint]` section)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LintSettings {
+    /// Shortest acceptable title, in characters
+    #[serde(default = "default_lint_title_min")]
+    pub title_min: usize,
+
+    /// Longest acceptable title, in characters
+    #[serde(default = "default_lint_title_max")]
+    pub title_max: usize,
+
+    /// Days an in-progress pea may go without an update
+    #[serde(default = "default_lint_stale_days")]
+    pub stale_days: u32,
+
+    /// Severity per check name, overriding its default (`off` disables it)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub checks: std::collections::BTreeMap<String, crate::lint::Severity>,
+}
+
+impl LintSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Configured severity of a check, or its default
+    pub fn severity(&self, check: &str) -> crate::lint::Severity {
+        self.checks.get(check).copied().unwrap_or_else(|| {
+            crate::lint::CHECKS
+                .iter()
+                .find(|c| c.name == check)
+                .map_or(crate::lint::Severity::Warning, |c| c.severity)
+        })
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.title_min > self.title_max {
+            return Err(PeasError::Config(
+                "lint.title_min cannot be greater than lint.title_max".to_string(),
+            ));
+        }
+        if let Some(name) = self
+            .checks
+            .keys()
+            .find(|name| !crate::lint::CHECKS.iter().any(|c| c.name == *name))
+        {
+            let known: Vec<&str> = crate::lint::CHECKS.iter().map(|c| c.name).collect();
+            return Err(PeasError::Config(format!(
+                "Unknown lint check '{}' (expected one of: {})",
+                name,
+                known.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn default_lint_title_min() -> usize {
+    5
+}
+
+fn default_lint_title_max() -> usize {
+    80
+}
+
+fn default_lint_stale_days() -> u32 {
+    14
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        Self {
+            title_min: default_lint_title_min(),
+            title_max: default_lint_title_max(),
+            stale_days: default_lint_stale_days(),
+            checks: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+/// Where `peas open` shows a pea
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OpenTarget {
+    /// The pea's file in `$EDITOR` (default)
+    #[default]
+    Editor,
+    /// The pea's detail view in `peas tui`
+    Tui,
+    /// The `open.url` template in the browser
+    Url,
+}
+
+impl std::fmt::Display for OpenTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenTarget::Editor => write!(f, "editor"),
+            OpenTarget::Tui => write!(f, "tui"),
+            OpenTarget::Url => write!(f, "url"),
+        }
+    }
+}
+
+/// Settings for `peas open` (`[open]` section)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenSettings {
+    /// Where peas are opened unless `--target` says otherwise
+    #[serde(default)]
+    pub target: OpenTarget,
+
+    /// URL template for the `url` target; `{id}` is replaced with the pea ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+impl OpenSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// The URL of a pea, if a template is configured
+    pub fn url_for(&self, id: &str) -> Option<String> {
+        self.url.as_ref().map(|url| url.replace("{id}", id))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match &self.url {
+            Some(url) if !url.contains("{id}") => Err(PeasError::Config(
+                "open.url must contain the {id} placeholder".to_string(),
+            )),
+            None if self.target == OpenTarget::Url => Err(PeasError::Config(
+                "open.target 'url' needs an open.url template".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Limits on titles, bodies and tags (`[validation]` section)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationSettings {
+    /// Longest title, in characters
+    #[serde(default = "default_max_title_length")]
+    pub max_title_length: usize,
+
+    /// Longest body, in characters
+    #[serde(default = "default_max_body_length")]
+    pub max_body_length: usize,
+
+    /// Longest tag, in characters
+    #[serde(default = "default_max_tag_length")]
+    pub max_tag_length: usize,
+
+    /// Regex every tag has to match in full, e.g. `[a-z0-9-]+`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag_pattern: Option<String>,
+}
+
+impl ValidationSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("max_title_length", self.max_title_length),
+            ("max_body_length", self.max_body_length),
+            ("max_tag_length", self.max_tag_length),
+        ] {
+            if value == 0 {
+                return Err(PeasError::Config(format!(
+                    "validation.{} must be at least 1",
+                    name
+                )));
+            }
+        }
+        if let Some(pattern) = &self.tag_pattern {
+            regex::Regex::new(pattern).map_err(|e| {
+                PeasError::Config(format!(
+                    "validation.tag_pattern is not a valid regex: {}",
+                    e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+fn default_max_title_length() -> usize {
+    crate::validation::MAX_TITLE_LENGTH
+}
+
+fn default_max_body_length() -> usize {
+    crate::validation::MAX_BODY_LENGTH
+}
+
+fn default_max_tag_length() -> usize {
+    crate::validation::MAX_TAG_LENGTH
+}
+
+impl Default for ValidationSettings {
+    fn default() -> Self {
+        Self {
+            max_title_length: default_max_title_length(),
+            max_body_length: default_max_body_length(),
+            max_tag_length: default_max_tag_length(),
+            tag_pattern: None,
+        }
+    }
+}
+
+/// How much undo history is kept (`[undo]` section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UndoSettings {
+    /// Most undo steps kept; older ones are pruned first
+    #[serde(default = "default_undo_max_entries")]
+    pub max_entries: usize,
+
+    /// Days an undo step is kept (0 keeps them regardless of age)
+    #[serde(default = "default_undo_max_age_days")]
+    pub max_age_days: u32,
+}
+
+impl UndoSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max_entries == 0 {
+            return Err(PeasError::Config(
+                "undo.max_entries must be at least 1".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn default_undo_max_entries() -> usize {
+    50
+}
+
+fn default_undo_max_age_days() -> u32 {
+    30
+}
+
+impl Default for UndoSettings {
+    fn default() -> Self {
+        Self {
+            max_entries: default_undo_max_entries(),
+            max_age_days: default_undo_max_age_days(),
+        }
+    }
+}
+
+/// Guard rails for bulk changes (`[bulk]` section)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BulkSettings {
+    /// Most peas a destructive bulk change may touch without `--force` (0 disables the limit)
+    #[serde(default = "default_bulk_max_destructive")]
+    pub max_destructive: usize,
+}
+
+impl BulkSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+fn default_bulk_max_destructive() -> usize {
+    20
+}
+
+impl Default for BulkSettings {
+    fn default() -> Self {
+        Self {
+            max_destructive: default_bulk_max_destructive(),
+        }
+    }
+}
+
+/// How timestamps are shown (`[display]` section)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplaySettings {
+    /// Time zone that dates and times are shown in
+    #[serde(default)]
+    pub timezone: DisplayTimezone,
+
+    /// Show how long ago things happened ("3 days ago"), as `--relative` does
+    #[serde(default)]
+    pub relative: bool,
+}
+
+impl DisplaySettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Where `peas search --semantic` gets embeddings from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmbeddingProvider {
+    /// Hashed word and trigram features computed in-process (default)
+    #[default]
+    Local,
+    /// An OpenAI-compatible `/embeddings` endpoint, e.g. OpenAI or Ollama
+    Api,
+}
+
+/// Embeddings for semantic search (`[semantic]` section, `semantic` feature)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SemanticSettings {
+    #[serde(default)]
+    pub provider: EmbeddingProvider,
+
+    /// Endpoint for the `api` provider, e.g. `http://localhost:11434/v1/embeddings`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Model name sent to the `api` provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Environment variable holding the bearer token for the `api` provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key_env: Option<String>,
+
+    /// Vector length of the `local` provider
+    #[serde(default = "default_semantic_dimensions")]
+    pub dimensions: usize,
+}
+
+fn default_semantic_dimensions() -> usize {
+    512
+}
+
+impl Default for SemanticSettings {
+    fn default() -> Self {
+        Self {
+            provider: EmbeddingProvider::default(),
+            url: None,
+            model: None,
+            api_key_env: None,
+            dimensions: default_semantic_dimensions(),
+        }
+    }
+}
+
+impl SemanticSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.dimensions == 0 {
+            return Err(PeasError::Config(
+                "semantic.dimensions must be greater than 0".to_string(),
+            ));
+        }
+        if self.provider == EmbeddingProvider::Api && (self.url.is_none() || self.model.is_none()) {
+            return Err(PeasError::Config(
+                "semantic.provider 'api' needs semantic.url and semantic.model".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Time zone for displayed timestamps; files always store UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayTimezone {
+    /// Coordinated Universal Time (default)
+    #[default]
+    Utc,
+    /// The system's local time zone
+    Local,
+}
+
+/// Limits for the GraphQL server and inline queries (`[server]` section)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerSettings {
+    /// Requests per minute allowed per client IP in `peas serve` (0 disables)
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit: u32,
+
+    /// Maximum HTTP request body size in bytes
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+
+    /// Maximum GraphQL query depth
+    #[serde(default = "default_max_query_depth")]
+    pub max_query_depth: usize,
+
+    /// Maximum GraphQL query complexity score
+    #[serde(default = "default_max_query_complexity")]
+    pub max_query_complexity: usize,
+
+    /// Only accept persisted queries in `peas serve`
+    #[serde(default)]
+    pub persisted_only: bool,
+
+    /// Named GraphQL documents clients can run by `operationName`
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub persisted_queries: std::collections::BTreeMap<String, String>,
+}
+
+impl ServerSettings {
+    fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Validate limits, returning errors for values that would reject every request.
+    pub fn validate(&self) -> Result<()> {
+        if self.max_body_bytes == 0 {
+            return Err(PeasError::Config(
+                "server.max_body_bytes must be greater than 0".to_string(),
+            ));
+        }
+        if self.max_query_depth == 0 {
+            return Err(PeasError::Config(
+                "server.max_query_depth must be greater than 0".to_string(),
+            ));
+        }
+        if self.max_query_complexity == 0 {
+            return Err(PeasError::Config(
+                "server.max_query_complexity must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn default_rate_limit() -> u32 {
+    600
+}
+
+fn default_max_body_bytes() -> usize {
+    1024 * 1024
+}
+
+fn default_max_query_depth() -> usize {
+    10
+}
+
+fn default_max_query_complexity() -> usize {
+    500
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            rate_limit: default_rate_limit(),
+            max_body_bytes: default_max_body_bytes(),
+            max_query_depth: default_max_query_depth(),
+            max_query_complexity: default_max_query_complexity(),
+            persisted_only: false,
+            persisted_queries: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+/// Display settings for one tag
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagSettings {
+    /// What the tag means, shown by `peas tag list`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Color name (`red`, `lightblue`), hex (`#ff8800`) or 256-color index
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl TagSettings {
+    /// Parsed color, if one is set and valid
+    pub fn parsed_color(&self) -> Option<ratatui::style::Color> {
+        self.color.as_deref().and_then(|c| c.parse().ok())
+    }
+
+    pub fn validate(&self, name: &str) -> Result<()> {
+        if let Some(color) = &self.color
+            && color.parse::<ratatui::style::Color>().is_err()
+        {
+            return Err(PeasError::Config(format!(
+                "Invalid color '{}' for tag '{}'",
+                color, name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Writes an automation rule runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleEvent {
+    Create,
+    Update,
+}
+
+impl std::fmt::Display for RuleEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleEvent::Create => write!(f, "create"),
+            RuleEvent::Update => write!(f, "update"),
+        }
+    }
+}
+
+/// One area of the project, such as `auth` or `billing`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentSettings {
+    /// What the component covers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// People responsible for the component, the first one leading
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub owners: Vec<String>,
+
+    /// Assign new peas in the component to its first owner
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_assign: bool,
+}
+
+impl ComponentSettings {
+    pub fn validate(&self, name: &str) -> Result<()> {
+        crate::validation::validate_tag(name)
+            .map_err(|_| PeasError::Config(format!("Invalid component name '{}'", name)))?;
+        if self.auto_assign && self.owners.is_empty() {
+            return Err(PeasError::Config(format!(
+                "components.{}.auto_assign needs at least one owner",
+                name
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Tags and a component for new peas matching a pattern, see [`AutoTags`](crate::autotag::AutoTags)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AutoTagSettings {
+    /// Regex matched against the title, ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    /// Regex matched against the body, ignoring case
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+
+    /// Tags added to a matching pea
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Component given to a matching pea that has none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+}
+
+impl AutoTagSettings {
+    pub fn validate(&self, name: &str) -> Result<()> {
+        if self.title.is_none() && self.body.is_none() {
+            return Err(PeasError::Config(format!(
+                "autotag.{} needs a title or body pattern",
+                name
+            )));
+        }
+        if self.tags.is_empty() && self.component.is_none() {
+            return Err(PeasError::Config(format!(
+                "autotag.{} needs tags or a component to add",
+                name
+            )));
+        }
+        for tag in &self.tags {
+            crate::validation::validate_tag(tag).map_err(|_| {
+                PeasError::Config(format!("Invalid tag '{}' in autotag.{}", tag, name))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// An automation rule written in Rhai, see [`Rules`](crate::rules::Rules)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleSettings {
+    /// Writes the rule runs on (default: create)
+    #[serde(default = "default_rule_events")]
+    pub on: Vec<RuleEvent>,
+
+    /// Condition on `pea`, e.g. `pea.type == "bug"`; the rule always applies without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+
+    /// Script changing `pea`, e.g. `pea.priority = "critical"`
+    pub then: String,
+}
+
+fn default_rule_events() -> Vec<RuleEvent> {
+    vec![RuleEvent::Create]
 }
 
 fn default_use_type_emojis() -> bool {
@@ -91,6 +887,7 @@ impl Default for TuiSettings {
     fn default() -> Self {
         Self {
             use_type_emojis: default_use_type_emojis(),
+            keys: KeySettings::default(),
         }
     }
 }
@@ -105,6 +902,11 @@ impl Default for PeasSettings {
             default_status: default_status(),
             default_type: default_type(),
             frontmatter: default_frontmatter(),
+            tag_policy: TagPolicy::default(),
+            size_scale: SizeScale::default(),
+            naming_scheme: NamingScheme::default(),
+            lock_closed: false,
+            cascade: Cascade::default(),
         }
     }
 }
@@ -170,6 +972,68 @@ impl PeasSettings {
 }
 
 impl PeasConfig {
+    /// Validate all sections
+    pub fn validate(&self) -> Result<()> {
+        self.peas.validate()?;
+        self.server.validate()?;
+        self.lint.validate()?;
+        self.open.validate()?;
+        self.undo.validate()?;
+        self.semantic.validate()?;
+        self.validation.validate()?;
+        for (name, tag) in &self.tags {
+            tag.validate(name)?;
+        }
+        for (name, component) in &self.components {
+            component.validate(name)?;
+        }
+        for (name, autotag) in &self.autotag {
+            autotag.validate(name)?;
+            if let Some(component) = &autotag.component
+                && !self.components.contains_key(component)
+            {
+                return Err(PeasError::Config(format!(
+                    "autotag.{} adds component '{}', which has no [components.{}] section",
+                    name, component, component
+                )));
+            }
+            if self.peas.tag_policy == TagPolicy::Strict
+                && let Some(tag) = autotag.tags.iter().find(|t| !self.tags.contains_key(*t))
+            {
+                return Err(PeasError::Config(format!(
+                    "autotag.{} adds tag '{}', which the strict tag policy rejects",
+                    name, tag
+                )));
+            }
+        }
+        crate::autotag::AutoTags::new(&self.autotag).check()?;
+        crate::rules::Rules::new(&self.rules).check()?;
+        if self.peas.tag_policy == TagPolicy::Strict && self.tags.is_empty() {
+            return Err(PeasError::Config(
+                "peas.tag_policy 'strict' needs at least one [tags.<name>] section".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Tags from `tags` that are new to the project under the `warn` policy
+    ///
+    /// A tag is new when it has no `[tags]` section and is not in `in_use`.
+    /// Always empty for the other policies.
+    pub fn new_tags<'a>(
+        &self,
+        tags: &'a [String],
+        in_use: &std::collections::BTreeMap<String, usize>,
+    ) -> Vec<&'a str> {
+        if self.peas.tag_policy != TagPolicy::Warn {
+            return Vec::new();
+        }
+        tags.iter()
+            .map(String::as_str)
+            .filter(|t| !self.tags.contains_key(*t) && !in_use.contains_key(*t))
+            .collect()
+    }
+
     pub fn load(start_path: &Path) -> Result<(Self, PathBuf)> {
         let (config_path, is_legacy) = Self::find_config_file(start_path)?;
         let content = std::fs::read_to_string(&config_path)?;
@@ -186,7 +1050,7 @@ impl PeasConfig {
         };
 
         // Validate config values
-        config.peas.validate()?;
+        config.validate()?;
 
         // Print deprecation warnings
         if is_legacy {
@@ -414,4 +1278,145 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_tui_keys_section_parses() {
+        let config: PeasConfig = toml::from_str(
+            r#"
+[tui.keys]
+preset = "emacs"
+quit = ["q", "ctrl+c"]
+archive = "x"
+"#,
+        )
+        .unwrap();
+        let keys = &config.tui.keys;
+        assert_eq!(keys.preset, KeyPreset::Emacs);
+        assert_eq!(keys.bindings["archive"], KeyList::One("x".to_string()));
+        assert_eq!(keys.bindings["quit"].keys(), vec!["q", "ctrl+c"]);
+        assert!(!keys.bindings.contains_key("preset"));
+    }
+
+    #[test]
+    fn test_server_section_defaults_and_validation() {
+        let config: PeasConfig = toml::from_str("[server]\nrate_limit = 0\n").unwrap();
+        assert_eq!(config.server.rate_limit, 0);
+        assert_eq!(config.server.max_query_depth, 10);
+        assert!(config.server.validate().is_ok());
+
+        let config: PeasConfig = toml::from_str("[server]\nmax_body_bytes = 0\n").unwrap();
+        assert!(config.server.validate().is_err());
+
+        // Default limits are not written back into config files
+        let rendered = toml::to_string(&PeasConfig::default()).unwrap();
+        assert!(!rendered.contains("[server]"));
+    }
+
+    #[test]
+    fn test_open_section() {
+        let config: PeasConfig =
+            toml::from_str("[open]\ntarget = \"url\"\nurl = \"http://localhost:4000/#/{id}\"\n")
+                .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.open.target, OpenTarget::Url);
+        assert_eq!(
+            config.open.url_for("peas-abc12").as_deref(),
+            Some("http://localhost:4000/#/peas-abc12")
+        );
+
+        let config: PeasConfig = toml::from_str("[open]\ntarget = \"url\"\n").unwrap();
+        assert!(config.validate().is_err());
+        let config: PeasConfig = toml::from_str("[open]\nurl = \"http://x/\"\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_display_section() {
+        let config: PeasConfig =
+            toml::from_str("[display]\ntimezone = \"local\"\nrelative = true\n").unwrap();
+        assert_eq!(config.display.timezone, DisplayTimezone::Local);
+        assert!(config.display.relative);
+
+        let rendered = toml::to_string(&PeasConfig::default()).unwrap();
+        assert!(!rendered.contains("[display]"));
+        assert!(toml::from_str::<PeasConfig>("[display]\ntimezone = \"mars\"\n").is_err());
+    }
+
+    #[test]
+    fn test_semantic_section() {
+        let config: PeasConfig = toml::from_str(
+            "[semantic]\nprovider = \"api\"\nurl = \"http://localhost:11434/v1/embeddings\"\nmodel = \"nomic-embed-text\"\n",
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.semantic.provider, EmbeddingProvider::Api);
+        assert_eq!(config.semantic.dimensions, 512);
+
+        let config: PeasConfig = toml::from_str("[semantic]\nprovider = \"api\"\n").unwrap();
+        assert!(config.validate().is_err());
+        let config: PeasConfig = toml::from_str("[semantic]\ndimensions = 0\n").unwrap();
+        assert!(config.validate().is_err());
+        assert!(
+            !toml::to_string(&PeasConfig::default())
+                .unwrap()
+                .contains("[semantic]")
+        );
+    }
+
+    #[test]
+    fn test_tags_section() {
+        let config: PeasConfig = toml::from_str(
+            "[tags.urgent]\ndescription = \"Drop everything\"\ncolor = \"#ff0000\"\n\n[tags.ui]\ncolor = \"lightblue\"\n",
+        )
+        .unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            config.tags["urgent"].description.as_deref(),
+            Some("Drop everything")
+        );
+        assert_eq!(
+            config.tags["ui"].parsed_color(),
+            Some(ratatui::style::Color::LightBlue)
+        );
+
+        let config: PeasConfig = toml::from_str("[tags.bad]\ncolor = \"nope\"\n").unwrap();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_tag_policy() {
+        let config: PeasConfig = toml::from_str("[peas]\ntag_policy = \"strict\"\n").unwrap();
+        assert!(config.validate().is_err());
+
+        let config: PeasConfig =
+            toml::from_str("[peas]\ntag_policy = \"warn\"\n\n[tags.ui]\n").unwrap();
+        let in_use = std::collections::BTreeMap::from([("backend".to_string(), 2)]);
+        let tags = vec![
+            "ui".to_string(),
+            "backend".to_string(),
+            "frontnd".to_string(),
+        ];
+        assert_eq!(config.new_tags(&tags, &in_use), vec!["frontnd"]);
+        assert!(PeasConfig::default().new_tags(&tags, &in_use).is_empty());
+    }
+
+    #[test]
+    fn test_autotag_section() {
+        let valid = "[components.auth]\n\n[autotag.login]\ntitle = \"log ?in\"\ntags = [\"auth\"]\ncomponent = \"auth\"\n";
+        let config: PeasConfig = toml::from_str(valid).unwrap();
+        assert!(config.validate().is_ok());
+        assert_eq!(config.autotag["login"].tags, ["auth"]);
+
+        for invalid in [
+            // Undeclared component, nothing to match or add, broken pattern
+            "[autotag.login]\ntitle = \"login\"\ncomponent = \"auth\"\n",
+            "[autotag.login]\ntags = [\"auth\"]\n",
+            "[autotag.login]\ntitle = \"login\"\n",
+            "[autotag.login]\nbody = \"(\"\ntags = [\"auth\"]\n",
+            "[peas]\ntag_policy = \"strict\"\n[tags.ui]\n[autotag.login]\ntitle = \"x\"\ntags = [\"auth\"]\n",
+        ] {
+            let config: PeasConfig = toml::from_str(invalid).unwrap();
+            assert!(config.validate().is_err(), "{}", invalid);
+        }
+    }
 }
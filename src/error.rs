@@ -1,5 +1,27 @@
+use crate::model::Dependents;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+/// Process exit codes for `peas` commands, by error category
+///
+/// Scripts can branch on these instead of parsing messages. Clap reports
+/// usage errors with 2.
+pub mod exit_code {
+    /// Any error without a more specific code
+    pub const FAILURE: i32 = 1;
+    /// A pea or memory does not exist
+    pub const NOT_FOUND: i32 = 3;
+    /// Input was rejected by validation, a rule or a hook
+    pub const VALIDATION: i32 = 4;
+    /// The target already exists or changed concurrently
+    pub const CONFLICT: i32 = 5;
+    /// A file or value could not be parsed
+    pub const PARSE: i32 = 6;
+    /// The project is missing or misconfigured
+    pub const CONFIG: i32 = 7;
+}
+
 #[derive(Error, Debug)]
 pub enum PeasError {
     #[error("Configuration error: {0}")]
@@ -8,17 +30,60 @@ pub enum PeasError {
     #[error("Pea not found: {0}")]
     NotFound(String),
 
+    #[error("Pea already exists: {0}")]
+    DuplicateId(String),
+
     #[error("Invalid pea ID: {0}")]
     InvalidId(String),
 
     #[error("Storage error: {0}")]
     Storage(String),
 
-    #[error("Parse error: {0}")]
-    Parse(String),
+    #[error(
+        "Concurrent modification detected for pea '{id}'. The file was modified by another process.\nYour version was updated at: {ours}\nCurrent version was updated at: {theirs}\nPlease reload and try again."
+    )]
+    Conflict {
+        id: String,
+        ours: DateTime<Utc>,
+        theirs: DateTime<Utc>,
+    },
+
+    #[error(
+        "Deleting '{id}' would leave {} dangling; repeat with the confirmation token {token}",
+        dependents.summary()
+    )]
+    ConfirmationRequired {
+        id: String,
+        token: String,
+        dependents: Dependents,
+    },
+
+    #[error("Pea '{id}' is claimed by {by} until {until}")]
+    Claimed {
+        id: String,
+        by: String,
+        until: DateTime<Utc>,
+    },
 
-    #[error("Validation error: {0}")]
-    Validation(String),
+    #[error("Parse error: {}{message}", location(path.as_deref(), *line))]
+    ParseError {
+        message: String,
+        /// File being parsed, when known
+        path: Option<PathBuf>,
+        /// 1-based line in `path` (or in the parsed text)
+        line: Option<usize>,
+    },
+
+    #[error("Validation error: {message}")]
+    ValidationFailed {
+        /// Input field that failed, e.g. `title` or `tags`
+        field: &'static str,
+        message: String,
+        /// The rule the input broke, when there is a single one to name
+        constraint: Option<Constraint>,
+        /// The rejected input
+        value: Option<String>,
+    },
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -38,6 +103,31 @@ pub enum PeasError {
     #[error("File watcher error: {0}")]
     Notify(#[from] notify::Error),
 
+    #[error("Git error: {0}")]
+    Git(String),
+
+    #[error("Sync error: {0}")]
+    Sync(String),
+
+    #[error("Forge error: {0}")]
+    Forge(String),
+
+    #[error("Plugin error: {0}")]
+    Plugin(String),
+
+    #[error("Embedding error: {0}")]
+    Embedding(String),
+
+    #[error("Rule {rule}: {message}")]
+    Rule { rule: String, message: String },
+
+    #[error("Hook {hook} rejected the operation: {message}")]
+    Hook {
+        /// Hook name, e.g. `pre-create`
+        hook: &'static str,
+        message: String,
+    },
+
     #[error("Project not initialized. Run 'peas init' first.")]
     NotInitialized,
 
@@ -45,4 +135,230 @@ pub enum PeasError {
     AlreadyInitialized(String),
 }
 
+/// A rule an input has to follow, reported with [`PeasError::ValidationFailed`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Constraint {
+    /// Must not be empty
+    Required,
+    /// At most this many characters
+    MaxLength(usize),
+    /// Must match this regex as a whole
+    Pattern(String),
+    /// Must not contain this character
+    ForbiddenCharacter(char),
+    /// Must be written like this, e.g. `YYYY-MM-DD`
+    Format(&'static str),
+    /// Must be the ID of an existing pea
+    Exists,
+    /// Must be one of these values
+    OneOf(Vec<String>),
+}
+
+impl Constraint {
+    /// Stable camelCase name, used for GraphQL error extensions
+    pub fn name(&self) -> &'static str {
+        match self {
+            Constraint::Required => "required",
+            Constraint::MaxLength(_) => "maxLength",
+            Constraint::Pattern(_) => "pattern",
+            Constraint::ForbiddenCharacter(_) => "forbiddenCharacter",
+            Constraint::Format(_) => "format",
+            Constraint::Exists => "exists",
+            Constraint::OneOf(_) => "oneOf",
+        }
+    }
+}
+
+fn location(path: Option<&Path>, line: Option<usize>) -> String {
+    match (path, line) {
+        (Some(path), Some(line)) => format!("{}:{}: ", path.display(), line),
+        (Some(path), None) => format!("{}: ", path.display()),
+        (None, Some(line)) => format!("line {}: ", line),
+        (None, None) => String::new(),
+    }
+}
+
+impl PeasError {
+    /// A parse error without file context
+    pub fn parse(message: impl Into<String>) -> Self {
+        PeasError::ParseError {
+            message: message.into(),
+            path: None,
+            line: None,
+        }
+    }
+
+    /// A validation failure for `field`
+    pub fn validation(field: &'static str, message: impl Into<String>) -> Self {
+        PeasError::ValidationFailed {
+            field,
+            message: message.into(),
+            constraint: None,
+            value: None,
+        }
+    }
+
+    /// Attach the broken rule and the rejected input to a validation failure
+    ///
+    /// ```
+    /// use peas::error::{Constraint, PeasError};
+    ///
+    /// let err = PeasError::validation("title", "Title cannot be empty")
+    ///     .with_constraint(Constraint::Required, "");
+    /// assert!(matches!(
+    ///     err,
+    ///     PeasError::ValidationFailed { constraint: Some(Constraint::Required), .. }
+    /// ));
+    /// ```
+    pub fn with_constraint(self, constraint: Constraint, value: impl Into<String>) -> Self {
+        match self {
+            PeasError::ValidationFailed { field, message, .. } => PeasError::ValidationFailed {
+                field,
+                message,
+                constraint: Some(constraint),
+                value: Some(value.into()),
+            },
+            other => other,
+        }
+    }
+
+    /// Functionality behind a cargo feature this build was made without
+    pub fn missing_feature(feature: &str) -> Self {
+        PeasError::Config(format!("peas was built without the '{}' feature", feature))
+    }
+
+    /// Attach the file a parse error came from
+    pub fn with_path(self, file: &Path) -> Self {
+        match self {
+            PeasError::ParseError {
+                message,
+                path: None,
+                line,
+            } => PeasError::ParseError {
+                message,
+                path: Some(file.to_path_buf()),
+                line,
+            },
+            other => other,
+        }
+    }
+
+    /// Stable machine-readable code, used for GraphQL error extensions
+    pub fn code(&self) -> &'static str {
+        match self {
+            PeasError::Config(_) => "CONFIG_ERROR",
+            PeasError::NotFound(_) => "NOT_FOUND",
+            PeasError::DuplicateId(_) => "DUPLICATE_ID",
+            PeasError::InvalidId(_) => "INVALID_ID",
+            PeasError::Storage(_) => "STORAGE_ERROR",
+            PeasError::Conflict { .. } => "CONFLICT",
+            PeasError::Claimed { .. } => "CLAIMED",
+            PeasError::ConfirmationRequired { .. } => "CONFIRMATION_REQUIRED",
+            PeasError::ParseError { .. } => "PARSE_ERROR",
+            PeasError::ValidationFailed { .. } => "VALIDATION_FAILED",
+            PeasError::Io(_) => "IO_ERROR",
+            PeasError::Yaml(_)
+            | PeasError::Json(_)
+            | PeasError::TomlDe(_)
+            | PeasError::TomlSer(_) => "SERIALIZATION_ERROR",
+            PeasError::Notify(_) => "WATCHER_ERROR",
+            PeasError::Git(_) => "GIT_ERROR",
+            PeasError::Sync(_) => "SYNC_ERROR",
+            PeasError::Forge(_) => "FORGE_ERROR",
+            PeasError::Plugin(_) => "PLUGIN_ERROR",
+            PeasError::Embedding(_) => "EMBEDDING_ERROR",
+            PeasError::Rule { .. } => "RULE_FAILED",
+            PeasError::Hook { .. } => "HOOK_REJECTED",
+            PeasError::NotInitialized => "NOT_INITIALIZED",
+            PeasError::AlreadyInitialized(_) => "ALREADY_INITIALIZED",
+        }
+    }
+
+    /// Process exit code for the CLI, see [`exit_code`]
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            PeasError::NotFound(_) => exit_code::NOT_FOUND,
+            PeasError::ValidationFailed { .. }
+            | PeasError::InvalidId(_)
+            | PeasError::Rule { .. }
+            | PeasError::Hook { .. } => exit_code::VALIDATION,
+            PeasError::DuplicateId(_)
+            | PeasError::Conflict { .. }
+            | PeasError::Claimed { .. }
+            | PeasError::ConfirmationRequired { .. }
+            | PeasError::AlreadyInitialized(_) => exit_code::CONFLICT,
+            PeasError::ParseError { .. }
+            | PeasError::Yaml(_)
+            | PeasError::Json(_)
+            | PeasError::TomlDe(_) => exit_code::PARSE,
+            PeasError::Config(_) | PeasError::NotInitialized => exit_code::CONFIG,
+            PeasError::Storage(_)
+            | PeasError::Io(_)
+            | PeasError::TomlSer(_)
+            | PeasError::Notify(_)
+            | PeasError::Git(_)
+            | PeasError::Sync(_)
+            | PeasError::Forge(_)
+            | PeasError::Plugin(_)
+            | PeasError::Embedding(_) => exit_code::FAILURE,
+        }
+    }
+
+    /// What the user can do about the error, shown next to it in the TUI
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            PeasError::NotFound(_) => {
+                Some("it may have been deleted or archived; press r to refresh")
+            }
+            PeasError::Conflict { .. } => Some("the file changed on disk; press r to reload"),
+            PeasError::DuplicateId(_) => Some("pick another ID"),
+            PeasError::Claimed { .. } => Some("wait until the claim expires or is released"),
+            PeasError::ParseError { path: Some(_), .. } => {
+                Some("fix the file by hand or run 'peas doctor'")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Exit code for an error chain, using the first [`PeasError`] in it
+pub fn exit_code_for(error: &anyhow::Error) -> i32 {
+    error
+        .chain()
+        .find_map(|e| e.downcast_ref::<PeasError>())
+        .map_or(exit_code::FAILURE, PeasError::exit_code)
+}
+
 pub type Result<T> = std::result::Result<T, PeasError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_location() {
+        let err = PeasError::ParseError {
+            message: "bad key".to_string(),
+            path: None,
+            line: Some(3),
+        }
+        .with_path(Path::new(".peas/peas-abc.md"));
+        assert_eq!(err.to_string(), "Parse error: .peas/peas-abc.md:3: bad key");
+        assert_eq!(PeasError::parse("oops").to_string(), "Parse error: oops");
+    }
+
+    #[test]
+    fn test_exit_code_found_through_context() {
+        use anyhow::Context;
+
+        let err = Err::<(), _>(PeasError::NotFound("peas-x".to_string()))
+            .context("Failed to show pea")
+            .unwrap_err();
+        assert_eq!(exit_code_for(&err), exit_code::NOT_FOUND);
+        assert_eq!(exit_code_for(&anyhow::anyhow!("plain")), exit_code::FAILURE);
+        assert_eq!(
+            PeasError::validation("title", "empty").exit_code(),
+            exit_code::VALIDATION
+        );
+    }
+}
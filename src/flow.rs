@@ -0,0 +1,147 @@
+use crate::history::StatusState;
+use crate::model::{Pea, PeaStatus};
+use chrono::{DateTime, Duration, Utc};
+
+/// When a completed pea was created, started and completed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowTimes {
+    pub id: String,
+    pub created: DateTime<Utc>,
+    /// First time the pea was seen in progress; None if it went straight to completed
+    pub started: Option<DateTime<Utc>>,
+    pub completed: DateTime<Utc>,
+}
+
+impl FlowTimes {
+    /// Created to completed
+    pub fn lead(&self) -> Duration {
+        self.completed - self.created
+    }
+
+    /// Started to completed
+    pub fn cycle(&self) -> Option<Duration> {
+        self.started.map(|started| self.completed - started)
+    }
+}
+
+/// Flow times of the completed peas in `peas`, read from their recorded states
+///
+/// A status change is dated by the pea's `updated` time in the first state
+/// showing it, which is when the change was saved rather than committed. A
+/// pea completed again after being reopened counts from its last completion.
+/// Without history, `updated` of a completed pea is its completion time.
+pub fn flow_times(peas: &[Pea], history: &[StatusState]) -> Vec<FlowTimes> {
+    peas.iter()
+        .filter(|pea| pea.status == PeaStatus::Completed)
+        .map(|pea| {
+            let mut started = None;
+            let mut completed = None;
+            let mut previous = None;
+            for state in history.iter().filter_map(|s| s.peas.get(&pea.id)) {
+                if previous != Some(state.status) {
+                    match state.status {
+                        PeaStatus::InProgress => {
+                            started.get_or_insert(state.updated);
+                        }
+                        PeaStatus::Completed => completed = Some(state.updated),
+                        _ => completed = None,
+                    }
+                }
+                previous = Some(state.status);
+            }
+            let completed = completed.unwrap_or(pea.updated);
+            FlowTimes {
+                id: pea.id.clone(),
+                created: pea.created,
+                started: started.filter(|started| *started <= completed),
+                completed,
+            }
+        })
+        .collect()
+}
+
+/// Percentiles of a set of durations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Distribution {
+    pub count: usize,
+    pub p50: Duration,
+    pub p85: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+impl Distribution {
+    /// None for an empty set
+    pub fn of(durations: impl IntoIterator<Item = Duration>) -> Option<Self> {
+        let mut sorted: Vec<Duration> = durations.into_iter().collect();
+        sorted.sort();
+        let max = *sorted.last()?;
+        // Nearest rank: the smallest value with at least p% of values at or below it
+        let percentile = |p: usize| sorted[(p * sorted.len()).div_ceil(100).max(1) - 1];
+        Some(Self {
+            count: sorted.len(),
+            p50: percentile(50),
+            p85: percentile(85),
+            p95: percentile(95),
+            max,
+        })
+    }
+}
+
+/// A duration in days, rounded to two decimals, for JSON output
+pub fn days(duration: Duration) -> f64 {
+    (duration.num_minutes() as f64 / (24.0 * 60.0) * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::PeaState;
+    use crate::model::PeaType;
+
+    #[test]
+    fn test_flow_times_from_history() {
+        let t0 = Utc::now() - Duration::days(30);
+        let at = |days: i64| t0 + Duration::days(days);
+        let state = |time, status, updated| StatusState {
+            time,
+            peas: [("peas-flow1".to_string(), PeaState { status, updated })].into(),
+        };
+
+        let mut pea = Pea::new("peas-flow1".into(), "Flow".into(), PeaType::Task)
+            .with_status(PeaStatus::Completed);
+        pea.created = t0;
+        pea.updated = at(12);
+        let history = vec![
+            state(at(1), PeaStatus::Todo, at(0)),
+            state(at(3), PeaStatus::InProgress, at(2)),
+            state(at(6), PeaStatus::Completed, at(5)),
+            // Reopened, restarted and completed again
+            state(at(8), PeaStatus::InProgress, at(7)),
+            state(at(11), PeaStatus::Completed, at(10)),
+        ];
+
+        let times = flow_times(std::slice::from_ref(&pea), &history);
+        assert_eq!(times[0].started, Some(at(2)));
+        assert_eq!(times[0].completed, at(10));
+        assert_eq!(times[0].lead(), Duration::days(10));
+        assert_eq!(times[0].cycle(), Some(Duration::days(8)));
+
+        // No history: completed at its last update, never seen started
+        let times = flow_times(&[pea], &[]);
+        assert_eq!(times[0].lead(), Duration::days(12));
+        assert_eq!(times[0].cycle(), None);
+    }
+
+    #[test]
+    fn test_distribution_percentiles() {
+        assert_eq!(Distribution::of([]), None);
+        let dist = Distribution::of((1..=20).rev().map(Duration::days)).unwrap();
+        assert_eq!(dist.count, 20);
+        assert_eq!(dist.p50, Duration::days(10));
+        assert_eq!(dist.p85, Duration::days(17));
+        assert_eq!(dist.p95, Duration::days(19));
+        assert_eq!(dist.max, Duration::days(20));
+        assert_eq!(days(Duration::hours(36)), 1.5);
+    }
+}
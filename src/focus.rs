@@ -0,0 +1,60 @@
+use crate::error::Result;
+use crate::local::LocalState;
+use std::path::Path;
+
+const FOCUS_FILE: &str = "focus";
+
+/// The ticket the current user is working on, kept in `.peas/local/focus`
+pub struct Focus {
+    local: LocalState,
+}
+
+impl Focus {
+    pub fn new(data_path: &Path) -> Self {
+        Self {
+            local: LocalState::new(data_path),
+        }
+    }
+
+    /// Get the focused pea ID, if any
+    pub fn get(&self) -> Option<String> {
+        let content = self.local.read(FOCUS_FILE)?;
+        let id = content.trim();
+        (!id.is_empty()).then(|| id.to_string())
+    }
+
+    /// Focus on a pea, replacing any previous focus
+    pub fn set(&self, id: &str) -> Result<()> {
+        self.local.write(FOCUS_FILE, &format!("{}\n", id))
+    }
+
+    /// Clear the focus, returning the previously focused ID
+    pub fn clear(&self) -> Result<Option<String>> {
+        let previous = self.get();
+        self.local.remove(FOCUS_FILE)?;
+        Ok(previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_set_get_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let focus = Focus::new(temp_dir.path());
+        assert_eq!(focus.get(), None);
+
+        focus.set("peas-abc12").unwrap();
+        assert_eq!(focus.get().as_deref(), Some("peas-abc12"));
+
+        focus.set("peas-def34").unwrap();
+        assert_eq!(focus.get().as_deref(), Some("peas-def34"));
+
+        assert_eq!(focus.clear().unwrap().as_deref(), Some("peas-def34"));
+        assert_eq!(focus.get(), None);
+        assert_eq!(focus.clear().unwrap(), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Pull request state from GitHub and GitLab.
+//!
+//! Links of kind `pr` are recognised by their URL shape and looked up
+//! through the forge's REST API. Tokens are read from `GITHUB_TOKEN` (or
+//! `GH_TOKEN`) and `GITLAB_TOKEN`; public projects work without one.
+
+use crate::error::{PeasError, Result};
+use crate::http::http_client;
+use serde::Serialize;
+use std::fmt;
+
+/// Where a pull request lives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+}
+
+/// A pull (or merge) request recognised from its web URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PullRequest {
+    pub forge: Forge,
+    pub number: u64,
+    /// REST endpoint describing the pull request
+    pub api_url: String,
+}
+
+impl PullRequest {
+    /// Recognise GitHub (`/<owner>/<repo>/pull/<n>`) and GitLab
+    /// (`/<group>/<project>/-/merge_requests/<n>`) URLs, self-hosted
+    /// instances included; None for anything else
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?;
+        let origin = match parsed.port() {
+            Some(port) => format!("{}://{}:{}", parsed.scheme(), host, port),
+            None => format!("{}://{}", parsed.scheme(), host),
+        };
+        let path = parsed.path().trim_matches('/');
+
+        if let Some((project, number)) = path.split_once("/merge_requests/") {
+            let project = project.trim_end_matches("/-");
+            let number = number.split('/').next()?.parse().ok()?;
+            return Some(Self {
+                forge: Forge::GitLab,
+                number,
+                api_url: format!(
+                    "{}/api/v4/projects/{}/merge_requests/{}",
+                    origin,
+                    project.replace('/', "%2F"),
+                    number
+                ),
+            });
+        }
+
+        let segments: Vec<&str> = path.split('/').collect();
+        if let [owner, repo, "pull", number, ..] = segments.as_slice() {
+            let number = number.parse().ok()?;
+            let api = if host == "github.com" {
+                "https://api.github.com".to_string()
+            } else {
+                // GitHub Enterprise serves its API under the web host
+                format!("{}/api/v3", origin)
+            };
+            return Some(Self {
+                forge: Forge::GitHub,
+                number,
+                api_url: format!("{}/repos/{}/{}/pulls/{}", api, owner, repo, number),
+            });
+        }
+        None
+    }
+}
+
+/// Merge state of a pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PrState {
+    Draft,
+    Open,
+    Merged,
+    Closed,
+}
+
+impl fmt::Display for PrState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrState::Draft => write!(f, "draft"),
+            PrState::Open => write!(f, "open"),
+            PrState::Merged => write!(f, "merged"),
+            PrState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// State from a GitHub `pulls/<n>` response
+pub fn github_state(json: &serde_json::Value) -> Result<PrState> {
+    if json["merged"].as_bool() == Some(true) || !json["merged_at"].is_null() {
+        return Ok(PrState::Merged);
+    }
+    match json["state"].as_str() {
+        Some("open") if json["draft"].as_bool() == Some(true) => Ok(PrState::Draft),
+        Some("open") => Ok(PrState::Open),
+        Some("closed") => Ok(PrState::Closed),
+        other => Err(PeasError::Forge(format!(
+            "Unexpected pull request state: {}",
+            other.unwrap_or("none")
+        ))),
+    }
+}
+
+/// State from a GitLab `merge_requests/<n>` response
+pub fn gitlab_state(json: &serde_json::Value) -> Result<PrState> {
+    match json["state"].as_str() {
+        Some("merged") => Ok(PrState::Merged),
+        Some("opened") if json["draft"].as_bool() == Some(true) => Ok(PrState::Draft),
+        Some("opened") => Ok(PrState::Open),
+        Some("closed") | Some("locked") => Ok(PrState::Closed),
+        other => Err(PeasError::Forge(format!(
+            "Unexpected merge request state: {}",
+            other.unwrap_or("none")
+        ))),
+    }
+}
+
+/// Looks up pull requests over HTTP
+pub struct ForgeClient {
+    client: reqwest::blocking::Client,
+}
+
+impl ForgeClient {
+    pub fn new() -> Result<Self> {
+        let client = http_client(std::time::Duration::from_secs(30))
+            .map_err(|e| PeasError::Forge(e.to_string()))?;
+        Ok(Self { client })
+    }
+
+    pub fn state(&self, pr: &PullRequest) -> Result<PrState> {
+        let mut request = self.client.get(&pr.api_url);
+        request = match pr.forge {
+            Forge::GitHub => {
+                let request = request.header("Accept", "application/vnd.github+json");
+                match std::env::var("GITHUB_TOKEN").or_else(|_| std::env::var("GH_TOKEN")) {
+                    Ok(token) => request.bearer_auth(token),
+                    Err(_) => request,
+                }
+            }
+            Forge::GitLab => match std::env::var("GITLAB_TOKEN") {
+                Ok(token) => request.header("PRIVATE-TOKEN", token),
+                Err(_) => request,
+            },
+        };
+
+        let response = request
+            .send()
+            .map_err(|e| PeasError::Forge(format!("{}: {}", pr.api_url, e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PeasError::Forge(format!(
+                "{} returned {}",
+                pr.api_url, status
+            )));
+        }
+        let json: serde_json::Value = response
+            .json()
+            .map_err(|e| PeasError::Forge(format!("{}: {}", pr.api_url, e)))?;
+        match pr.forge {
+            Forge::GitHub => github_state(&json),
+            Forge::GitLab => gitlab_state(&json),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pull_request_urls() {
+        let pr = PullRequest::parse("https://github.com/asaaki/peas/pull/42/files").unwrap();
+        assert_eq!(pr.forge, Forge::GitHub);
+        assert_eq!(pr.number, 42);
+        assert_eq!(
+            pr.api_url,
+            "https://api.github.com/repos/asaaki/peas/pulls/42"
+        );
+        assert_eq!(
+            PullRequest::parse("https://ghe.example.com/o/r/pull/7")
+                .unwrap()
+                .api_url,
+            "https://ghe.example.com/api/v3/repos/o/r/pulls/7"
+        );
+
+        let mr =
+            PullRequest::parse("http://localhost:8080/group/sub/app/-/merge_requests/3").unwrap();
+        assert_eq!(mr.forge, Forge::GitLab);
+        assert_eq!(
+            mr.api_url,
+            "http://localhost:8080/api/v4/projects/group%2Fsub%2Fapp/merge_requests/3"
+        );
+
+        assert!(PullRequest::parse("https://github.com/o/r/issues/1").is_none());
+        assert!(PullRequest::parse("https://example.com/docs").is_none());
+    }
+
+    #[test]
+    fn test_states_from_api_responses() {
+        let github = |v| github_state(&v).unwrap();
+        assert_eq!(
+            github(json!({"state": "open", "draft": true})),
+            PrState::Draft
+        );
+        assert_eq!(
+            github(json!({"state": "open", "draft": false})),
+            PrState::Open
+        );
+        assert_eq!(
+            github(json!({"state": "closed", "merged": true})),
+            PrState::Merged
+        );
+        assert_eq!(
+            github(json!({"state": "closed", "merged_at": null})),
+            PrState::Closed
+        );
+
+        let gitlab = |v| gitlab_state(&v).unwrap();
+        assert_eq!(gitlab(json!({"state": "merged"})), PrState::Merged);
+        assert_eq!(
+            gitlab(json!({"state": "opened", "draft": true})),
+            PrState::Draft
+        );
+        assert_eq!(gitlab(json!({"state": "locked"})), PrState::Closed);
+        assert!(gitlab_state(&json!({})).is_err());
+    }
+}
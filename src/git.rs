@@ -0,0 +1,226 @@
+//! Thin wrapper around the `git` command line.
+//!
+//! peas never links against libgit2; everything goes through the `git`
+//! binary on `PATH`, run against the project root.
+
+use crate::error::{PeasError, Result};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+use std::process::Command;
+
+/// Run `git -C <root> <args>` and return its trimmed stdout
+pub fn run(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .map_err(|e| PeasError::Git(format!("failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(PeasError::Git(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Commit time of a tag, branch or commit
+pub fn ref_date(root: &Path, reference: &str) -> Result<DateTime<Utc>> {
+    let commit = format!("{}^{{commit}}", reference);
+    let date = run(root, &["log", "-1", "--format=%cI", &commit, "--"])?;
+    DateTime::parse_from_rfc3339(&date)
+        .map(|d| d.with_timezone(&Utc))
+        .map_err(|e| {
+            PeasError::Git(format!(
+                "unexpected date '{}' for {}: {}",
+                date, reference, e
+            ))
+        })
+}
+
+/// A commit that touched a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileCommit {
+    pub hash: String,
+    pub author: String,
+    pub date: DateTime<Utc>,
+    pub subject: String,
+    /// Path of the file in this commit, relative to the repository root
+    pub path: String,
+}
+
+/// Commits that touched `path`, newest first, following renames
+pub fn file_history(root: &Path, path: &Path) -> Result<Vec<FileCommit>> {
+    let path = path.to_string_lossy();
+    let log = run(
+        root,
+        &[
+            "log",
+            "--follow",
+            "--name-only",
+            "--format=%x1e%H%x1f%an%x1f%aI%x1f%s",
+            "--",
+            &path,
+        ],
+    )?;
+    log.split('\x1e')
+        .filter(|record| !record.trim().is_empty())
+        .map(|record| {
+            let mut lines = record.lines();
+            let header = lines.next().unwrap_or_default();
+            let fields: Vec<&str> = header.split('\x1f').collect();
+            let [hash, author, date, subject] = fields[..] else {
+                return Err(PeasError::Git(format!("unexpected log line '{}'", header)));
+            };
+            let date = DateTime::parse_from_rfc3339(date)
+                .map_err(|e| PeasError::Git(format!("unexpected date '{}': {}", date, e)))?
+                .with_timezone(&Utc);
+            let path = lines
+                .map(str::trim)
+                .find(|l| !l.is_empty())
+                .unwrap_or_default();
+            Ok(FileCommit {
+                hash: hash.to_string(),
+                author: author.to_string(),
+                date,
+                subject: subject.to_string(),
+                path: path.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Contents of `path` (relative to the repository root) at `rev`
+pub fn show_file(root: &Path, rev: &str, path: &str) -> Result<String> {
+    run(root, &["show", &format!("{}:{}", rev, path)])
+}
+
+/// The best common ancestor of two commits, as a full hash
+pub fn merge_base(root: &Path, a: &str, b: &str) -> Result<String> {
+    run(root, &["merge-base", a, b])
+}
+
+/// Files under `path` that differ between two commits, relative to the repository root
+///
+/// Renames are reported as a removal and an addition.
+pub fn changed_files(root: &Path, from: &str, to: &str, path: &Path) -> Result<Vec<String>> {
+    let path = path.to_string_lossy();
+    let diff = run(
+        root,
+        &["diff", "--name-only", "--no-renames", from, to, "--", &path],
+    )?;
+    Ok(diff
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Files under `path` at `rev`, relative to the repository root
+pub fn list_files(root: &Path, rev: &str, path: &Path) -> Result<Vec<String>> {
+    let path = path.to_string_lossy();
+    let files = run(
+        root,
+        &[
+            "ls-tree",
+            "-r",
+            "--name-only",
+            "--full-name",
+            rev,
+            "--",
+            &path,
+        ],
+    )?;
+    Ok(files
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// The last commit on `HEAD` at or before `time` that touched `path`, with its commit time
+pub fn last_commit_before(
+    root: &Path,
+    time: DateTime<Utc>,
+    path: &Path,
+) -> Result<Option<(String, DateTime<Utc>)>> {
+    let before = format!("--before={}", time.to_rfc3339());
+    Ok(commits(root, &["-1", &before], path)?.pop())
+}
+
+/// Commits on `HEAD` after `time` that touched `path`, oldest first, with their commit times
+pub fn commits_since(
+    root: &Path,
+    time: DateTime<Utc>,
+    path: &Path,
+) -> Result<Vec<(String, DateTime<Utc>)>> {
+    let since = format!("--since={}", time.to_rfc3339());
+    commits(root, &["--reverse", &since], path)
+}
+
+fn commits(root: &Path, options: &[&str], path: &Path) -> Result<Vec<(String, DateTime<Utc>)>> {
+    let path = path.to_string_lossy();
+    let mut args = vec!["log", "--format=%H %cI"];
+    args.extend_from_slice(options);
+    args.extend(["HEAD", "--", &path]);
+    run(root, &args)?
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(hash, date)| {
+            let date = DateTime::parse_from_rfc3339(date)
+                .map_err(|e| PeasError::Git(format!("unexpected date '{}': {}", date, e)))?
+                .with_timezone(&Utc);
+            Ok((hash.to_string(), date))
+        })
+        .collect()
+}
+
+/// Line-based three-way merge of `ours`, `base` and `theirs` via `git merge-file`.
+///
+/// Returns the merged text, with conflict markers labelled `ours`/`theirs`
+/// where both sides changed the same lines, and whether it merged cleanly.
+pub fn merge_text(ours: &str, base: &str, theirs: &str) -> Result<(String, bool)> {
+    let dir = std::env::temp_dir();
+    let stem = format!("peas-merge-{}", std::process::id());
+    let files = [("ours", ours), ("base", base), ("theirs", theirs)].map(|(name, text)| {
+        let path = dir.join(format!("{}-{}", stem, name));
+        (path, text)
+    });
+    for (path, text) in &files {
+        std::fs::write(path, text)?;
+    }
+
+    let output = Command::new("git")
+        .args([
+            "merge-file",
+            "-p",
+            "-L",
+            "ours",
+            "-L",
+            "base",
+            "-L",
+            "theirs",
+        ])
+        .args(files.iter().map(|(path, _)| path))
+        .output();
+    for (path, _) in &files {
+        let _ = std::fs::remove_file(path);
+    }
+    let output = output.map_err(|e| PeasError::Git(format!("failed to run git: {}", e)))?;
+
+    // The exit code is the number of conflicts; negative on error
+    match output.status.code() {
+        Some(code) if code >= 0 => Ok((
+            String::from_utf8_lossy(&output.stdout).into_owned(),
+            code == 0,
+        )),
+        _ => Err(PeasError::Git(format!(
+            "git merge-file failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))),
+    }
+}
@@ -0,0 +1,407 @@
+//! Incremental delivery of queries with `@defer` and `@stream` for `peas serve`.
+//!
+//! async-graphql does not execute these directives, so peas runs a deferred
+//! query twice: first without its deferred fragments and streamed fields, so
+//! metadata such as IDs and titles arrives quickly, then in full. The second
+//! payload carries only what the first lacked, as `incremental` entries with
+//! the path of each object. Both are sent as one `multipart/mixed` response in
+//! the `deferSpec=20220824` format that clients such as Apollo understand.
+//!
+//! Clients that do not accept `multipart/mixed`, and `peas query`, get one
+//! complete response: the [`Deferral`] extension drops the directives, which
+//! the spec allows a server to do.
+
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory, NextParseQuery};
+use async_graphql::parser::types::{
+    Directive, DocumentOperations, ExecutableDocument, Field, OperationDefinition, OperationType,
+    Selection, SelectionSet,
+};
+use async_graphql::{Name, Pos, Positioned, Request, Response, ServerResult, Value, Variables};
+use serde_json::{Map, Value as Json, json};
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+/// Content type of an incremental response
+pub const CONTENT_TYPE: &str = "multipart/mixed; boundary=\"-\"; deferSpec=20220824";
+
+/// Delimiter closing an incremental response
+pub const CLOSE: &str = "\r\n-----\r\n";
+
+const DIRECTIVES: [&str; 2] = ["defer", "stream"];
+
+/// Schema extension that accepts `@defer` and `@stream` by ignoring them
+pub struct Deferral;
+
+impl ExtensionFactory for Deferral {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(Deferral)
+    }
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for Deferral {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let mut document = next.run(ctx, query, variables).await?;
+        let operations: Vec<&mut OperationDefinition> = match &mut document.operations {
+            DocumentOperations::Single(operation) => vec![&mut operation.node],
+            DocumentOperations::Multiple(operations) => {
+                operations.values_mut().map(|op| &mut op.node).collect()
+            }
+        };
+        for operation in operations {
+            strip(&mut operation.selection_set.node);
+        }
+        for fragment in document.fragments.values_mut() {
+            strip(&mut fragment.node.selection_set.node);
+        }
+        Ok(document)
+    }
+}
+
+/// The document to answer first, or None when the query defers nothing
+///
+/// Only queries are split, as running a mutation twice would apply it twice.
+pub fn initial(request: &mut Request) -> Option<ExecutableDocument> {
+    let variables = request.variables.clone();
+    let operation_name = request.operation_name.clone();
+    let mut document = request.parsed_query().ok()?.clone();
+    let operation = match &mut document.operations {
+        DocumentOperations::Single(operation) => &mut operation.node,
+        DocumentOperations::Multiple(operations) => {
+            &mut operations.get_mut(operation_name.as_deref()?)?.node
+        }
+    };
+    if operation.ty != OperationType::Query {
+        return None;
+    }
+
+    let mut deferred = remove_deferred(&mut operation.selection_set.node, &variables);
+    for fragment in document.fragments.values_mut() {
+        deferred |= remove_deferred(&mut fragment.node.selection_set.node, &variables);
+    }
+    if !deferred {
+        return None;
+    }
+
+    // Fragments and variables only the deferred parts used would fail validation
+    let fragments: HashMap<Name, SelectionSet> = document
+        .fragments
+        .iter()
+        .map(|(name, fragment)| (name.clone(), fragment.node.selection_set.node.clone()))
+        .collect();
+    let mut used_fragments = HashSet::new();
+    for operation in document.operations.iter().map(|(_, op)| &op.node) {
+        used_fragments.extend(reachable(&operation.selection_set.node, &fragments));
+    }
+    document
+        .fragments
+        .retain(|name, _| used_fragments.contains(name));
+
+    let operation = match &mut document.operations {
+        DocumentOperations::Single(operation) => &mut operation.node,
+        DocumentOperations::Multiple(operations) => {
+            &mut operations.get_mut(operation_name.as_deref()?)?.node
+        }
+    };
+    let mut used_variables = HashSet::new();
+    variables_in(&operation.selection_set.node, &mut used_variables);
+    for name in reachable(&operation.selection_set.node, &fragments) {
+        variables_in(&fragments[&name], &mut used_variables);
+    }
+    operation
+        .variable_definitions
+        .retain(|definition| used_variables.contains(&definition.node.name.node));
+
+    Some(document)
+}
+
+/// The first part of an incremental response
+pub fn initial_payload(response: &Response) -> Json {
+    let mut payload = serde_json::to_value(response).unwrap_or_else(|_| json!({}));
+    payload["hasNext"] = json!(true);
+    payload
+}
+
+/// The last part: whatever the complete response adds to the first one
+pub fn subsequent_payload(initial: &Json, complete: &Response) -> Json {
+    let complete = serde_json::to_value(complete).unwrap_or_else(|_| json!({}));
+    let mut incremental = Vec::new();
+    additions(
+        &complete["data"],
+        &initial["data"],
+        &mut Vec::new(),
+        &mut incremental,
+    );
+
+    let mut payload = json!({ "hasNext": false });
+    if !incremental.is_empty() {
+        payload["incremental"] = Json::Array(incremental);
+    }
+    let seen = initial["errors"].as_array();
+    let errors: Vec<Json> = complete["errors"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|error| !seen.is_some_and(|seen| seen.contains(error)))
+        .cloned()
+        .collect();
+    if !errors.is_empty() {
+        payload["errors"] = Json::Array(errors);
+    }
+    payload
+}
+
+/// A payload as one part of a `multipart/mixed` body
+pub fn part(payload: &Json) -> String {
+    format!(
+        "\r\n---\r\nContent-Type: application/json; charset=utf-8\r\n\r\n{}",
+        payload
+    )
+}
+
+fn is_incremental(directive: &Positioned<Directive>) -> bool {
+    DIRECTIVES.contains(&directive.node.name.node.as_str())
+}
+
+/// Whether a `@defer` or `@stream` applies; `if: false` turns it off
+fn defers(directives: &[Positioned<Directive>], variables: &Variables) -> bool {
+    directives.iter().filter(|d| is_incremental(d)).any(|d| {
+        let condition = d.node.get_argument("if").map(|value| {
+            value
+                .node
+                .clone()
+                .into_const_with(|name| variables.get(&name).cloned().ok_or(()))
+        });
+        !matches!(condition, Some(Ok(Value::Boolean(false))))
+    })
+}
+
+/// Drop `@defer` and `@stream` everywhere in a selection set
+fn strip(set: &mut SelectionSet) {
+    for item in &mut set.items {
+        item.node.directives_mut().retain(|d| !is_incremental(d));
+        match &mut item.node {
+            Selection::Field(field) => strip(&mut field.node.selection_set.node),
+            Selection::InlineFragment(fragment) => strip(&mut fragment.node.selection_set.node),
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+}
+
+/// Remove deferred fragments and streamed fields, returning whether any were found
+fn remove_deferred(set: &mut SelectionSet, variables: &Variables) -> bool {
+    let before = set.items.len();
+    set.items
+        .retain(|item| !defers(item.node.directives(), variables));
+    let mut removed = set.items.len() != before;
+    for item in &mut set.items {
+        match &mut item.node {
+            Selection::Field(field) => {
+                removed |= remove_deferred(&mut field.node.selection_set.node, variables);
+            }
+            Selection::InlineFragment(fragment) => {
+                removed |= remove_deferred(&mut fragment.node.selection_set.node, variables);
+            }
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+
+    // An object needs at least one field selected
+    if set.items.is_empty() && before > 0 {
+        let pos = Pos::default();
+        set.items.push(Positioned::new(
+            Selection::Field(Positioned::new(
+                Field {
+                    alias: None,
+                    name: Positioned::new(Name::new("__typename"), pos),
+                    arguments: Vec::new(),
+                    directives: Vec::new(),
+                    selection_set: Positioned::new(SelectionSet::default(), pos),
+                },
+                pos,
+            )),
+            pos,
+        ));
+    }
+    removed
+}
+
+/// Names of the fragments a selection set spreads, directly or through others
+fn reachable(set: &SelectionSet, fragments: &HashMap<Name, SelectionSet>) -> HashSet<Name> {
+    fn spreads(set: &SelectionSet, out: &mut Vec<Name>) {
+        for item in &set.items {
+            match &item.node {
+                Selection::Field(field) => spreads(&field.node.selection_set.node, out),
+                Selection::InlineFragment(fragment) => {
+                    spreads(&fragment.node.selection_set.node, out)
+                }
+                Selection::FragmentSpread(spread) => {
+                    out.push(spread.node.fragment_name.node.clone())
+                }
+            }
+        }
+    }
+
+    let mut found = HashSet::new();
+    let mut pending = Vec::new();
+    spreads(set, &mut pending);
+    while let Some(name) = pending.pop() {
+        if let Some(fragment) = fragments.get(&name)
+            && found.insert(name)
+        {
+            spreads(fragment, &mut pending);
+        }
+    }
+    found
+}
+
+/// Names of the variables used in a selection set, outside fragment spreads
+fn variables_in(set: &SelectionSet, out: &mut HashSet<Name>) {
+    for item in &set.items {
+        let mut arguments: Vec<_> = item
+            .node
+            .directives()
+            .iter()
+            .flat_map(|directive| &directive.node.arguments)
+            .collect();
+        if let Selection::Field(field) = &item.node {
+            arguments.extend(&field.node.arguments);
+        }
+        for (_, value) in arguments {
+            let _ = value.node.clone().into_const_with(|name| {
+                out.insert(name);
+                Ok::<_, Infallible>(Value::Null)
+            });
+        }
+
+        match &item.node {
+            Selection::Field(field) => variables_in(&field.node.selection_set.node, out),
+            Selection::InlineFragment(fragment) => {
+                variables_in(&fragment.node.selection_set.node, out)
+            }
+            Selection::FragmentSpread(_) => {}
+        }
+    }
+}
+
+/// Entries for the fields `complete` has and `initial` lacks, by object path
+fn additions(complete: &Json, initial: &Json, path: &mut Vec<Json>, out: &mut Vec<Json>) {
+    match (complete, initial) {
+        (Json::Object(complete), Json::Object(initial)) => {
+            let data: Map<String, Json> = complete
+                .iter()
+                .filter(|(key, _)| !initial.contains_key(*key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            if !data.is_empty() {
+                out.push(json!({ "data": data, "path": path }));
+            }
+            for (key, value) in complete {
+                if let Some(before) = initial.get(key) {
+                    path.push(json!(key));
+                    additions(value, before, path, out);
+                    path.pop();
+                }
+            }
+        }
+        (Json::Array(complete), Json::Array(initial)) => {
+            for (index, (value, before)) in complete.iter().zip(initial).enumerate() {
+                path.push(json!(index));
+                additions(value, before, path, out);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_leaves_out_deferred_parts() {
+        let mut request = Request::new(
+            "query($full: Boolean!, $id: String!, $parent: String!) {
+                peas { nodes { id ... @defer(if: $full) { body } } }
+                pea(id: $id) { title }
+                children(parentId: $parent) @stream { id }
+                stats { ...Totals @defer }
+            }
+            fragment Totals on Stats { total }",
+        )
+        .variables(Variables::from_json(
+            json!({ "full": true, "id": "peas-abcde", "parent": "peas-fghij" }),
+        ));
+        let document = initial(&mut request).unwrap();
+
+        let operation = match &document.operations {
+            DocumentOperations::Single(operation) => &operation.node,
+            DocumentOperations::Multiple(_) => unreachable!(),
+        };
+        let fields: Vec<&str> = operation
+            .selection_set
+            .node
+            .items
+            .iter()
+            .filter_map(|item| match &item.node {
+                Selection::Field(field) => Some(field.node.name.node.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(fields, ["peas", "pea", "stats"]);
+        // Unused once the deferred parts are gone
+        assert!(document.fragments.is_empty());
+        let variables: Vec<&str> = operation
+            .variable_definitions
+            .iter()
+            .map(|definition| definition.node.name.node.as_str())
+            .collect();
+        assert_eq!(variables, ["id"]);
+    }
+
+    #[test]
+    fn test_nothing_to_split() {
+        let mut off = Request::new("{ peas { nodes { id ... @defer(if: false) { body } } } }");
+        assert!(initial(&mut off).is_none());
+
+        let mut mutation = Request::new(
+            r#"mutation { createPea(input: { title: "x" }) { id ... @defer { body } } }"#,
+        );
+        assert!(initial(&mut mutation).is_none());
+    }
+
+    #[test]
+    fn test_subsequent_payload_holds_the_additions() {
+        let initial = json!({
+            "data": { "peas": { "nodes": [{ "id": "peas-aaaaa" }, { "id": "peas-bbbbb" }] } },
+            "hasNext": true,
+        });
+        let complete = Response::new(
+            Value::from_json(json!({
+                "peas": { "nodes": [
+                    { "id": "peas-aaaaa", "body": "First" },
+                    { "id": "peas-bbbbb", "body": "Second" },
+                ] },
+            }))
+            .unwrap(),
+        );
+        assert_eq!(
+            subsequent_payload(&initial, &complete),
+            json!({
+                "hasNext": false,
+                "incremental": [
+                    { "data": { "body": "First" }, "path": ["peas", "nodes", 0] },
+                    { "data": { "body": "Second" }, "path": ["peas", "nodes", 1] },
+                ],
+            })
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! Prometheus metrics for `peas serve`.
+//!
+//! Request counters live in memory for the lifetime of the server; repository
+//! gauges are read from disk on every scrape so they always match the files.
+
+use crate::{
+    config::PeasConfig,
+    model::PeaStatus,
+    storage::{MemoryRepository, PeaRepository},
+};
+use async_graphql::{
+    Request, Response,
+    parser::{
+        parse_query,
+        types::{OperationType, Selection},
+    },
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::Path,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+/// Counters collected while serving GraphQL requests
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests: AtomicU64,
+    request_errors: AtomicU64,
+    /// Executed mutations by root field name (e.g. `createPea`)
+    mutations: Mutex<BTreeMap<String, u64>>,
+    /// HTTP requests turned away before execution, by reason
+    rejected: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one executed request and its outcome
+    pub fn record(&self, mutation_fields: &[String], response: &Response) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if response.is_err() {
+            self.request_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        if !mutation_fields.is_empty() {
+            let mut mutations = self.mutations.lock().unwrap_or_else(|e| e.into_inner());
+            for field in mutation_fields {
+                *mutations.entry(field.clone()).or_default() += 1;
+            }
+        }
+    }
+
+    /// Record a request rejected by the server limits (e.g. `rate_limit`)
+    pub fn record_rejection(&self, reason: &'static str) {
+        let mut rejected = self.rejected.lock().unwrap_or_else(|e| e.into_inner());
+        *rejected.entry(reason).or_default() += 1;
+    }
+
+    /// Render counters plus current repository gauges in Prometheus text format
+    pub fn render(&self, config: &PeasConfig, project_root: &Path) -> String {
+        let mut out = String::new();
+
+        metric_header(
+            &mut out,
+            "peas_graphql_requests_total",
+            "counter",
+            "GraphQL requests handled",
+        );
+        let _ = writeln!(
+            out,
+            "peas_graphql_requests_total {}",
+            self.requests.load(Ordering::Relaxed)
+        );
+        metric_header(
+            &mut out,
+            "peas_graphql_request_errors_total",
+            "counter",
+            "GraphQL requests that returned errors",
+        );
+        let _ = writeln!(
+            out,
+            "peas_graphql_request_errors_total {}",
+            self.request_errors.load(Ordering::Relaxed)
+        );
+        metric_header(
+            &mut out,
+            "peas_graphql_mutations_total",
+            "counter",
+            "Mutations executed, by root field",
+        );
+        let mutations = self.mutations.lock().unwrap_or_else(|e| e.into_inner());
+        for (field, count) in mutations.iter() {
+            let _ = writeln!(
+                out,
+                "peas_graphql_mutations_total{{field=\"{}\"}} {}",
+                field, count
+            );
+        }
+        drop(mutations);
+        metric_header(
+            &mut out,
+            "peas_http_rejected_total",
+            "counter",
+            "HTTP requests rejected by server limits, by reason",
+        );
+        let rejected = self.rejected.lock().unwrap_or_else(|e| e.into_inner());
+        for (reason, count) in rejected.iter() {
+            let _ = writeln!(
+                out,
+                "peas_http_rejected_total{{reason=\"{}\"}} {}",
+                reason, count
+            );
+        }
+        drop(rejected);
+
+        let repo = PeaRepository::new(config, project_root);
+        if let Ok(peas) = repo.list() {
+            metric_header(
+                &mut out,
+                "peas_tickets",
+                "gauge",
+                "Active tickets, by status",
+            );
+            for status in [
+                PeaStatus::Draft,
+                PeaStatus::Todo,
+                PeaStatus::InProgress,
+                PeaStatus::Completed,
+                PeaStatus::Scrapped,
+            ] {
+                let count = peas.iter().filter(|p| p.status == status).count();
+                let _ = writeln!(out, "peas_tickets{{status=\"{}\"}} {}", status, count);
+            }
+        }
+        if let Ok(archived) = repo.list_archived() {
+            metric_header(
+                &mut out,
+                "peas_archived_tickets",
+                "gauge",
+                "Archived tickets",
+            );
+            let _ = writeln!(out, "peas_archived_tickets {}", archived.len());
+        }
+        if let Ok(stats) = repo.file_stats() {
+            metric_header(
+                &mut out,
+                "peas_ticket_files_bytes",
+                "gauge",
+                "Total size of active ticket files",
+            );
+            let _ = writeln!(out, "peas_ticket_files_bytes {}", stats.bytes);
+            metric_header(
+                &mut out,
+                "peas_parse_errors",
+                "gauge",
+                "Ticket files that cannot be parsed",
+            );
+            let _ = writeln!(out, "peas_parse_errors {}", stats.parse_errors);
+        }
+        if let Ok((count, bytes)) = MemoryRepository::new(config, project_root).stats() {
+            metric_header(&mut out, "peas_memories", "gauge", "Stored memories");
+            let _ = writeln!(out, "peas_memories {}", count);
+            metric_header(
+                &mut out,
+                "peas_memory_files_bytes",
+                "gauge",
+                "Total size of memory files",
+            );
+            let _ = writeln!(out, "peas_memory_files_bytes {}", bytes);
+        }
+
+        out
+    }
+}
+
+fn metric_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Root fields of the mutation operation a request will execute
+///
+/// Unparseable queries yield nothing; the schema reports the syntax error.
+pub fn mutation_fields(request: &Request) -> Vec<String> {
+    let Ok(document) = parse_query(&request.query) else {
+        return Vec::new();
+    };
+    document
+        .operations
+        .iter()
+        .filter(|(name, _)| match &request.operation_name {
+            Some(wanted) => name.is_some_and(|n| n.as_str() == wanted),
+            None => true,
+        })
+        .filter(|(_, operation)| operation.node.ty == OperationType::Mutation)
+        .flat_map(|(_, operation)| operation.node.selection_set.node.items.iter())
+        .filter_map(|selection| match &selection.node {
+            Selection::Field(field) => Some(field.node.name.node.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Pea, PeaType};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mutation_fields() {
+        let request = Request::new(
+            r#"mutation { createPea(input: { title: "x" }) { id } setStatus(id: "a", status: TODO) { id } }"#,
+        );
+        assert_eq!(mutation_fields(&request), vec!["createPea", "setStatus"]);
+        assert!(mutation_fields(&Request::new("{ stats { total } }")).is_empty());
+        assert!(mutation_fields(&Request::new("mutation {")).is_empty());
+
+        let named = Request::new("query A { stats { total } } mutation B { deletePea(id: \"x\") }")
+            .operation_name("A");
+        assert!(mutation_fields(&named).is_empty());
+    }
+
+    #[test]
+    fn test_render_counters_and_gauges() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        let pea = Pea::new(
+            repo.generate_id().unwrap(),
+            "Task".to_string(),
+            PeaType::Task,
+        );
+        repo.create(&pea).unwrap();
+
+        let metrics = Metrics::new();
+        metrics.record(&["createPea".to_string()], &Response::default());
+        metrics.record(
+            &[],
+            &Response::from_errors(vec![async_graphql::ServerError::new("boom", None)]),
+        );
+
+        metrics.record_rejection("rate_limit");
+
+        let text = metrics.render(&config, temp_dir.path());
+        assert!(text.contains("# TYPE peas_graphql_requests_total counter"));
+        assert!(text.contains("peas_graphql_requests_total 2\n"));
+        assert!(text.contains("peas_graphql_request_errors_total 1\n"));
+        assert!(text.contains("peas_graphql_mutations_total{field=\"createPea\"} 1\n"));
+        assert!(text.contains("peas_http_rejected_total{reason=\"rate_limit\"} 1\n"));
+        assert!(text.contains("peas_tickets{status=\"todo\"} 1\n"));
+        assert!(text.contains("peas_parse_errors 0\n"));
+        assert!(text.contains("peas_memories 0\n"));
+    }
+}
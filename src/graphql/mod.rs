@@ -18,11 +18,18 @@
 //!
 //! ## Schema
 //!
-//! - **Queries**: `pea`, `peas`, `search`, `children`, `stats`
+//! - **Queries**: `pea`, `peas`, `search`, `searchHits`, `similar`, `children`, `stats`
 //! - **Mutations**: `createPea`, `updatePea`, `setStatus`, `archivePea`, `deletePea`
+//! - **Directives**: `@defer` and `@stream`, delivered incrementally by `peas serve`
 
+pub mod defer;
+pub mod metrics;
+pub mod persisted;
+pub mod rate_limit;
 mod schema;
 mod types;
+pub mod viewer;
+pub mod web_ui;
 
-pub use schema::{PeasSchema, build_schema};
+pub use schema::{Actor, PeasSchema, build_schema, execute};
 pub use types::*;
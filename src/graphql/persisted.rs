@@ -0,0 +1,114 @@
+//! Persisted (named) GraphQL queries for `peas serve`.
+//!
+//! Operators register queries by name in `[server.persisted_queries]`.
+//! Clients run one by sending its name as `operationName` with an empty
+//! `query`. With `server.persisted_only = true` ad-hoc queries are refused,
+//! which limits untrusted agents to a fixed API surface.
+
+use crate::config::ServerSettings;
+use async_graphql::{Request, parser::parse_query};
+
+/// Check that every registered query parses and holds a single operation
+pub fn validate(settings: &ServerSettings) -> Result<(), String> {
+    if settings.persisted_only && settings.persisted_queries.is_empty() {
+        return Err(
+            "server.persisted_only is set but no server.persisted_queries are configured"
+                .to_string(),
+        );
+    }
+    for (name, query) in &settings.persisted_queries {
+        let document = parse_query(query)
+            .map_err(|e| format!("Persisted query '{}' does not parse: {}", name, e))?;
+        if document.operations.iter().count() != 1 {
+            return Err(format!(
+                "Persisted query '{}' must contain exactly one operation",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Substitute a persisted query into `request`, or refuse it
+///
+/// Requests with an empty `query` are looked up by `operationName`.
+/// Ad-hoc queries pass through unless `persisted_only` is set.
+pub fn resolve(mut request: Request, settings: &ServerSettings) -> Result<Request, String> {
+    if !request.query.trim().is_empty() {
+        if settings.persisted_only {
+            return Err("This server only accepts persisted queries; \
+                 send the query name as operationName with an empty query"
+                .to_string());
+        }
+        return Ok(request);
+    }
+
+    let Some(name) = request.operation_name.take() else {
+        return Err(
+            "Empty query: send a query or the operationName of a persisted query".to_string(),
+        );
+    };
+    let Some(query) = settings.persisted_queries.get(&name) else {
+        return Err(format!("Unknown persisted query '{}'", name));
+    };
+    tracing::debug!(name = %name, "Using persisted query");
+    // The stored document has a single operation, so no name is needed to pick it
+    request.query = query.clone();
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(persisted_only: bool) -> ServerSettings {
+        let mut settings = ServerSettings {
+            persisted_only,
+            ..ServerSettings::default()
+        };
+        settings.persisted_queries.insert(
+            "openTickets".to_string(),
+            "query Open { peas(filter: { status: TODO }) { nodes { id } } }".to_string(),
+        );
+        settings
+    }
+
+    #[test]
+    fn test_resolve_by_name() {
+        let request = Request::new("").operation_name("openTickets");
+        let resolved = resolve(request, &settings(true)).unwrap();
+        assert!(resolved.query.starts_with("query Open"));
+        assert!(resolved.operation_name.is_none());
+
+        let unknown = Request::new("").operation_name("nope");
+        assert!(resolve(unknown, &settings(false)).is_err());
+    }
+
+    #[test]
+    fn test_persisted_only_refuses_ad_hoc_queries() {
+        let ad_hoc = Request::new("{ stats { total } }");
+        assert!(resolve(ad_hoc, &settings(true)).is_err());
+
+        let ad_hoc = Request::new("{ stats { total } }");
+        assert!(resolve(ad_hoc, &settings(false)).is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&settings(true)).is_ok());
+        assert!(
+            validate(&ServerSettings {
+                persisted_only: true,
+                ..ServerSettings::default()
+            })
+            .is_err()
+        );
+
+        let mut broken = settings(false);
+        broken.persisted_queries.insert(
+            "two".to_string(),
+            "query A { stats { total } } query B { stats { total } }".to_string(),
+        );
+        assert!(validate(&broken).unwrap_err().contains("'two'"));
+    }
+}
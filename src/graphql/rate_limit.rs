@@ -0,0 +1,100 @@
+//! Per-client rate limiting for `peas serve`.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Number of tracked clients above which idle entries are pruned
+const PRUNE_THRESHOLD: usize = 1024;
+
+/// Token bucket per client IP: `per_minute` requests, refilled continuously
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    last: Instant,
+}
+
+impl RateLimiter {
+    /// Create a limiter, or None when `per_minute` is 0 (limiting disabled)
+    pub fn new(per_minute: u32) -> Option<Self> {
+        (per_minute > 0).then(|| Self {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+
+    /// Take one request token for `ip` at `now`
+    ///
+    /// Returns the time until the next token is available when the client
+    /// has used up its allowance.
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(self.per_minute);
+        let rate = self.refill_per_sec();
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            // Clients whose bucket would be full again are indistinguishable from new ones
+            buckets.retain(|_, b| {
+                b.tokens + now.saturating_duration_since(b.last).as_secs_f64() * rate < capacity
+            });
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: capacity,
+            last: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.last).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.last = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_when_zero() {
+        assert!(RateLimiter::new(0).is_none());
+    }
+
+    #[test]
+    fn test_limits_per_ip_and_refills() {
+        let limiter = RateLimiter::new(2).unwrap();
+        let a: IpAddr = "127.0.0.1".parse().unwrap();
+        let b: IpAddr = "10.0.0.1".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check(a, start).is_ok());
+        assert!(limiter.check(a, start).is_ok());
+        let retry = limiter.check(a, start).unwrap_err();
+        assert_eq!(retry.as_secs(), 30);
+
+        // Other clients have their own allowance
+        assert!(limiter.check(b, start).is_ok());
+
+        // Two requests per minute refill one token every 30 seconds
+        assert!(limiter.check(a, start + Duration::from_secs(30)).is_ok());
+        assert!(limiter.check(a, start + Duration::from_secs(31)).is_err());
+    }
+}
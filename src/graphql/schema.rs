@@ -1,11 +1,29 @@
+use super::defer::Deferral;
 use super::types::*;
 use crate::{
-    config::PeasConfig,
-    model::{Memory as ModelMemory, Pea as ModelPea},
+    config::{PeasConfig, TagPolicy},
+    error::{Constraint, PeasError},
+    model::{
+        Dependents, Memory as ModelMemory, Pea as ModelPea, SizeTotals as ModelSizeTotals,
+        descendants, parse_timestamp,
+    },
+    removal::{ChildPolicy, RemovalKind, RemovalPlan},
+    search::RankedSearch,
     storage::{MemoryRepository, PeaRepository},
+    undo::{UndoManager, UndoOperation},
+    validation,
 };
-use async_graphql::{Context, EmptySubscription, Object, Schema};
-use std::{path::PathBuf, sync::Arc};
+use async_graphql::{
+    Context, EmptySubscription, ErrorExtensionValues, ErrorExtensions, Object, Request, Response,
+    Schema, Value,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::Instant,
+};
+use tracing::Instrument;
 
 pub type PeasSchema = Schema<QueryRoot, MutationRoot, EmptySubscription>;
 
@@ -14,13 +32,14 @@ pub struct AppState {
     pub project_root: PathBuf,
 }
 
-/// Maximum allowed query depth to prevent deeply nested abuse.
-const MAX_QUERY_DEPTH: usize = 10;
-
-/// Maximum query complexity score.
-const MAX_QUERY_COMPLEXITY: usize = 500;
+/// Person or agent behind a request, overriding the configured actor
+///
+/// `peas serve` attaches it from the `X-Peas-Actor` header.
+pub struct Actor(pub String);
 
 pub fn build_schema(config: PeasConfig, project_root: PathBuf) -> PeasSchema {
+    // Depth and complexity limits keep expensive recursive queries in check
+    let limits = config.server.clone();
     let state = Arc::new(AppState {
         config,
         project_root,
@@ -28,25 +47,237 @@ pub fn build_schema(config: PeasConfig, project_root: PathBuf) -> PeasSchema {
 
     Schema::build(QueryRoot, MutationRoot, EmptySubscription)
         .data(state)
-        .limit_depth(MAX_QUERY_DEPTH)
-        .limit_complexity(MAX_QUERY_COMPLEXITY)
+        .limit_depth(limits.max_query_depth)
+        .limit_complexity(limits.max_query_complexity)
+        .extension(Deferral)
         .finish()
 }
 
-fn get_repo(ctx: &Context<'_>) -> async_graphql::Result<PeaRepository> {
-    let state = ctx
-        .data::<Arc<AppState>>()
-        .map_err(|_| async_graphql::Error::new("AppState not found in context"))?;
-    Ok(PeaRepository::new(&state.config, &state.project_root))
+/// Execute a request inside a `graphql` tracing span
+///
+/// Storage spans opened by resolvers nest under it, so a single request can
+/// be followed through the logs.
+pub async fn execute(schema: &PeasSchema, request: Request) -> Response {
+    let operation = request
+        .operation_name
+        .clone()
+        .unwrap_or_else(|| "anonymous".to_string());
+    let span = tracing::info_span!("graphql", operation = %operation);
+    async move {
+        let started = Instant::now();
+        tracing::trace!(query = %request.query, "Executing GraphQL request");
+        let mut response = schema.execute(request).await;
+        add_error_extensions(&mut response);
+        for error in &response.errors {
+            tracing::debug!(error = %error.message, "GraphQL error");
+        }
+        tracing::debug!(
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            errors = response.errors.len(),
+            "GraphQL request finished"
+        );
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// Tag errors raised by peas with a `code` extension plus details such as the
+/// failing `field`, so clients can react without parsing messages
+fn add_error_extensions(response: &mut Response) {
+    for error in &mut response.errors {
+        let Some(peas_error) = error.source::<PeasError>() else {
+            continue;
+        };
+        let mut details: Vec<(&str, Value)> = vec![("code", peas_error.code().into())];
+        match peas_error {
+            PeasError::NotFound(id) | PeasError::DuplicateId(id) => {
+                details.push(("id", id.as_str().into()));
+            }
+            PeasError::ValidationFailed {
+                field,
+                constraint,
+                value,
+                ..
+            } => {
+                details.push(("field", (*field).into()));
+                if let Some(constraint) = constraint {
+                    details.push(("constraint", constraint.name().into()));
+                    match constraint {
+                        Constraint::MaxLength(limit) => {
+                            details.push(("limit", (*limit as u64).into()))
+                        }
+                        Constraint::Pattern(pattern) => {
+                            details.push(("pattern", pattern.as_str().into()))
+                        }
+                        Constraint::ForbiddenCharacter(c) => {
+                            details.push(("character", format!("U+{:04X}", *c as u32).into()))
+                        }
+                        Constraint::Format(format) => details.push(("format", (*format).into())),
+                        Constraint::OneOf(allowed) => details.push((
+                            "allowed",
+                            Value::List(allowed.iter().map(|a| a.as_str().into()).collect()),
+                        )),
+                        Constraint::Required | Constraint::Exists => {}
+                    }
+                }
+                if let Some(value) = value {
+                    details.push(("value", shorten(value).into()));
+                }
+            }
+            PeasError::Conflict { id, theirs, .. } => {
+                details.push(("id", id.as_str().into()));
+                details.push(("currentUpdated", theirs.to_rfc3339().into()));
+            }
+            PeasError::Claimed { id, by, until } => {
+                details.push(("id", id.as_str().into()));
+                details.push(("claimedBy", by.as_str().into()));
+                details.push(("claimedUntil", until.to_rfc3339().into()));
+            }
+            PeasError::ConfirmationRequired {
+                id,
+                token,
+                dependents,
+            } => {
+                let ids =
+                    |ids: &[String]| Value::List(ids.iter().map(|id| id.as_str().into()).collect());
+                details.push(("id", id.as_str().into()));
+                details.push(("confirmationToken", token.as_str().into()));
+                details.push(("children", ids(&dependents.children)));
+                details.push(("blockedBy", ids(&dependents.blocked_by)));
+                details.push(("mentionedBy", ids(&dependents.mentioned_by)));
+            }
+            PeasError::ParseError { path, line, .. } => {
+                if let Some(path) = path {
+                    details.push(("path", path.display().to_string().into()));
+                }
+                if let Some(line) = line {
+                    details.push(("line", (*line as u64).into()));
+                }
+            }
+            _ => {}
+        }
+
+        let extensions = error
+            .extensions
+            .get_or_insert_with(ErrorExtensionValues::default);
+        for (name, value) in details {
+            extensions.set(name, value);
+        }
+    }
+}
+
+/// Rejected input as echoed back in an error, cut short so a huge body
+/// doesn't blow up the response
+fn shorten(value: &str) -> String {
+    const MAX_CHARS: usize = 100;
+    match value.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}…", &value[..end]),
+        None => value.to_string(),
+    }
+}
+
+fn get_state<'a>(ctx: &Context<'a>) -> async_graphql::Result<&'a Arc<AppState>> {
+    ctx.data::<Arc<AppState>>()
+        .map_err(|_| async_graphql::Error::new("AppState not found in context"))
+}
+
+pub(super) fn get_repo(ctx: &Context<'_>) -> async_graphql::Result<PeaRepository> {
+    let state = get_state(ctx)?;
+    let repo = PeaRepository::new(&state.config, &state.project_root);
+    Ok(match ctx.data_opt::<Actor>() {
+        Some(Actor(actor)) => repo.with_actor(Some(actor.clone())),
+        None => repo,
+    })
+}
+
+/// Ranked results of a search query, as in `peas search`
+fn search_hits(
+    ctx: &Context<'_>,
+    query: &str,
+    limit: Option<usize>,
+    fuzzy: bool,
+) -> async_graphql::Result<Vec<crate::search::SearchHit>> {
+    let query = RankedSearch::parse(query)
+        .map_err(|e| PeasError::validation("query", e))?
+        .with_fuzzy(fuzzy);
+    let repo = get_repo(ctx)?;
+    let mut hits = query.rank(repo.list()?, &repo.aliases()?);
+    hits.truncate(limit.unwrap_or(50));
+    Ok(hits)
 }
 
 fn get_memory_repo(ctx: &Context<'_>) -> async_graphql::Result<MemoryRepository> {
-    let state = ctx
-        .data::<Arc<AppState>>()
-        .map_err(|_| async_graphql::Error::new("AppState not found in context"))?;
+    let state = get_state(ctx)?;
     Ok(MemoryRepository::new(&state.config, &state.project_root))
 }
 
+/// Record a mutation so `peas undo` can revert it
+///
+/// The change is already written, so failing to record it is only logged.
+fn record_undo(ctx: &Context<'_>, operation: UndoOperation) -> async_graphql::Result<()> {
+    let state = get_state(ctx)?;
+    let undo_manager = UndoManager::new(&state.config.data_path(&state.project_root))
+        .with_settings(state.config.undo);
+    if let Err(e) = undo_manager.record(operation) {
+        tracing::warn!(error = %e, "Failed to record undo operation");
+    }
+    Ok(())
+}
+
+/// How `archivePea` and `deletePea` treat children, from their arguments
+fn child_policy(
+    repo: &PeaRepository,
+    cascade: bool,
+    reparent_to: Option<String>,
+    orphan: bool,
+) -> async_graphql::Result<ChildPolicy> {
+    let policy = match (cascade, reparent_to, orphan) {
+        (false, None, false) => ChildPolicy::Refuse,
+        (true, None, false) => ChildPolicy::Cascade,
+        (false, Some(target), false) => ChildPolicy::Reparent(repo.get(&target)?.id),
+        (false, None, true) => ChildPolicy::Orphan,
+        _ => {
+            return Err(PeasError::validation(
+                "children",
+                "Pass only one of cascade, reparentTo and orphan",
+            )
+            .into());
+        }
+    };
+    Ok(policy)
+}
+
+/// Carry out a removal, recorded as one undo step even if it fails halfway
+fn remove(
+    ctx: &Context<'_>,
+    repo: &PeaRepository,
+    plan: &RemovalPlan,
+) -> async_graphql::Result<()> {
+    let mut operations = Vec::new();
+    let result = plan.apply(repo, &mut operations);
+    if let Some(step) = plan.undo_step(operations) {
+        record_undo(ctx, step)?;
+    }
+    Ok(result?)
+}
+
+/// Write a changed pea, or with `dry_run` only check it and apply automation rules
+fn save(
+    ctx: &Context<'_>,
+    repo: &PeaRepository,
+    pea: &mut ModelPea,
+    dry_run: bool,
+) -> async_graphql::Result<()> {
+    if dry_run {
+        *pea = repo.preview_update(pea)?;
+        return Ok(());
+    }
+    let operation = UndoOperation::update_from_file(&pea.id, &repo.find_file_by_id(&pea.id)?)?;
+    repo.update(pea)?;
+    record_undo(ctx, operation)
+}
+
 pub struct QueryRoot;
 
 #[Object]
@@ -62,51 +293,88 @@ impl QueryRoot {
     }
 
     /// List peas with optional filtering
+    ///
+    /// With `asOf` (a snapshot label, `YYYY-MM-DD` date or RFC 3339 time) the
+    /// peas are listed as they were then, from a snapshot or git history.
+    /// Relationships of the returned peas still resolve against the current state.
+    ///
+    /// `sort` takes comma-separated keys like `peas list --sort`, e.g.
+    /// `"priority,-updated"`; without it peas come in creation order.
+    #[allow(clippy::too_many_arguments)]
     async fn peas(
         &self,
         ctx: &Context<'_>,
         filter: Option<PeaFilter>,
         limit: Option<usize>,
         offset: Option<usize>,
+        as_of: Option<String>,
+        sort: Option<String>,
+        #[graphql(desc = "List archived peas instead of active ones")] archived: Option<bool>,
+        #[graphql(desc = "List archived peas along with active ones")] include_archived: Option<
+            bool,
+        >,
     ) -> async_graphql::Result<PeaConnection> {
+        let archived = archived.unwrap_or(false);
+        let include_archived = include_archived.unwrap_or(false);
+        if as_of.is_some() && (archived || include_archived) {
+            return Err(PeasError::validation(
+                "asOf",
+                "Snapshots cannot be combined with archived peas",
+            )
+            .into());
+        }
+        let sort = crate::model::SortKey::parse_list(sort.as_deref().unwrap_or_default())?;
         let repo = get_repo(ctx)?;
-        let mut peas = repo.list()?;
-
-        // Apply filters
+        let mut query = crate::storage::ListQuery {
+            sort,
+            offset: offset.unwrap_or(0),
+            limit: Some(limit.unwrap_or(100)),
+            ..Default::default()
+        };
         if let Some(f) = filter {
-            if let Some(t) = f.pea_type {
-                let filter_type: crate::model::PeaType = t.into();
-                peas.retain(|p| p.pea_type == filter_type);
-            }
-            if let Some(s) = f.status {
-                let filter_status: crate::model::PeaStatus = s.into();
-                peas.retain(|p| p.status == filter_status);
-            }
-            if let Some(p) = f.priority {
-                let filter_priority: crate::model::PeaPriority = p.into();
-                peas.retain(|pea| pea.priority == filter_priority);
-            }
-            if let Some(ref parent_id) = f.parent {
-                peas.retain(|p| p.parent.as_deref() == Some(parent_id.as_str()));
-            }
-            if let Some(ref tag) = f.tag {
-                peas.retain(|p| p.tags.contains(tag));
-            }
-            if let Some(is_open) = f.is_open {
-                peas.retain(|p| p.is_open() == is_open);
-            }
+            query.pea_type = f.pea_type.map(Into::into);
+            query.status = f.status.map(Into::into);
+            query.priority = f.priority.map(Into::into);
+            query.parent = f.parent;
+            query.tag = f.tag;
+            query.component = f.component;
+            query.assignee = f.assignee;
+            query.is_open = f.is_open;
+            query.created_by = f.created_by;
+            query.updated_by = f.updated_by;
+            query.unclaimed = f.unclaimed == Some(true);
         }
 
-        let total_count = peas.len();
-
-        // Apply pagination
-        let offset = offset.unwrap_or(0);
-        let limit = limit.unwrap_or(100);
-        let peas: Vec<Pea> = peas
+        let stored = if archived || include_archived {
+            repo.list_archived()?
+        } else {
+            Vec::new()
+        };
+        let archived_ids: HashSet<String> = stored.iter().map(|p| p.id.clone()).collect();
+        let page = match as_of {
+            Some(as_of) => {
+                let state = get_state(ctx)?;
+                let peas =
+                    crate::snapshot::peas_as_of(&state.config, &state.project_root, &as_of)?.0;
+                query.apply(peas, repo.actor())
+            }
+            None if archived => query.apply(stored, repo.actor()),
+            None if include_archived => {
+                let mut peas = repo.list()?;
+                peas.extend(stored);
+                query.apply(peas, repo.actor())
+            }
+            None => repo.query(&query)?,
+        };
+        let total_count = page.total;
+        let peas: Vec<Pea> = page
+            .peas
             .into_iter()
-            .skip(offset)
-            .take(limit)
-            .map(|p| p.into())
+            .map(|p| {
+                let mut pea = Pea::from(p);
+                pea.archived = archived_ids.contains(&pea.id);
+                pea
+            })
             .collect();
 
         Ok(PeaConnection {
@@ -115,29 +383,63 @@ impl QueryRoot {
         })
     }
 
-    /// Search peas by text in title and body
+    /// Search peas in `peas search` syntax, best matches first
     async fn search(
         &self,
         ctx: &Context<'_>,
         query: String,
         limit: Option<usize>,
+        #[graphql(desc = "Match words fuzzily, tolerating typos")] fuzzy: Option<bool>,
     ) -> async_graphql::Result<Vec<Pea>> {
-        let repo = get_repo(ctx)?;
-        let peas = repo.list()?;
-        let query_lower = query.to_lowercase();
+        Ok(search_hits(ctx, &query, limit, fuzzy.unwrap_or(false))?
+            .into_iter()
+            .map(|hit| hit.pea.into())
+            .collect())
+    }
 
-        let results: Vec<Pea> = peas
+    /// Search like `search`, with the score and a snippet of each result
+    async fn search_hits(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        limit: Option<usize>,
+        #[graphql(desc = "Match words fuzzily, tolerating typos")] fuzzy: Option<bool>,
+    ) -> async_graphql::Result<Vec<SearchHit>> {
+        Ok(search_hits(ctx, &query, limit, fuzzy.unwrap_or(false))?
             .into_iter()
-            .filter(|p| {
-                p.title.to_lowercase().contains(&query_lower)
-                    || p.body.to_lowercase().contains(&query_lower)
-                    || p.id.to_lowercase().contains(&query_lower)
-            })
-            .take(limit.unwrap_or(50))
-            .map(|p| p.into())
-            .collect();
+            .map(Into::into)
+            .collect())
+    }
+
+    /// Peas closest in meaning to a pea, by their embeddings (`semantic` feature)
+    async fn similar(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<SimilarPea>> {
+        #[cfg(feature = "semantic")]
+        {
+            use crate::semantic::{DEFAULT_LIMIT, SemanticIndex};
 
-        Ok(results)
+            let state = get_state(ctx)?;
+            let repo = get_repo(ctx)?;
+            let pea = repo.get(&id)?;
+            let index = SemanticIndex::new(
+                &state.config.data_path(&state.project_root),
+                &state.config.semantic,
+            )?;
+            Ok(index
+                .similar(&pea.id, repo.list()?, limit.unwrap_or(DEFAULT_LIMIT))?
+                .into_iter()
+                .map(Into::into)
+                .collect())
+        }
+        #[cfg(not(feature = "semantic"))]
+        {
+            let _ = (ctx, id, limit);
+            Err(PeasError::missing_feature("semantic").into())
+        }
     }
 
     /// Get children of a pea
@@ -152,34 +454,194 @@ impl QueryRoot {
     }
 
     /// Get project statistics
-    async fn stats(&self, ctx: &Context<'_>) -> async_graphql::Result<ProjectStats> {
+    async fn stats(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Only count peas created at or after this date or RFC 3339 time")]
+        created_after: Option<String>,
+        #[graphql(desc = "Only count peas created before this date or RFC 3339 time")]
+        created_before: Option<String>,
+    ) -> async_graphql::Result<ProjectStats> {
+        let parse = |field: &'static str, value: Option<String>| {
+            value
+                .map(|v| {
+                    parse_timestamp(&v).ok_or_else(|| {
+                        PeasError::validation(
+                            field,
+                            format!(
+                                "'{}' is neither a date (YYYY-MM-DD) nor an RFC 3339 time",
+                                v
+                            ),
+                        )
+                    })
+                })
+                .transpose()
+        };
+        let after = parse("createdAfter", created_after)?;
+        let before = parse("createdBefore", created_before)?;
+        let in_range = |p: &ModelPea| {
+            after.is_none_or(|t| p.created >= t) && before.is_none_or(|t| p.created < t)
+        };
+
         let repo = get_repo(ctx)?;
-        let peas = repo.list()?;
+        let peas: Vec<ModelPea> = repo
+            .list_summaries()?
+            .into_iter()
+            .filter(|p| in_range(p))
+            .collect();
+        let archived: Vec<ModelPea> = repo
+            .list_archived()?
+            .into_iter()
+            .filter(|p| in_range(p))
+            .collect();
+        let now = chrono::Utc::now();
+        let today = now.date_naive();
+        let state = get_state(ctx)?;
+        let scale = state.config.peas.size_scale;
+        let mut by_component: BTreeMap<String, usize> = state
+            .config
+            .components
+            .keys()
+            .map(|name| (name.clone(), 0))
+            .collect();
+        for component in peas.iter().filter_map(|p| p.component.as_ref()) {
+            *by_component.entry(component.clone()).or_default() += 1;
+        }
+        let mut by_tag: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in peas.iter().flat_map(|p| &p.tags) {
+            *by_tag.entry(tag).or_default() += 1;
+        }
+        let mut by_tag: Vec<TagCount> = by_tag
+            .into_iter()
+            .map(|(tag, count)| TagCount {
+                tag: tag.to_string(),
+                count,
+            })
+            .collect();
+        by_tag.sort_by_key(|t| std::cmp::Reverse(t.count));
+        let mut by_assignee: BTreeMap<Option<&str>, usize> = BTreeMap::new();
+        for pea in &peas {
+            *by_assignee.entry(pea.assignee.as_deref()).or_default() += 1;
+        }
 
-        use crate::model::{PeaStatus as MS, PeaType as MT};
+        use crate::model::PeaType as MT;
 
         Ok(ProjectStats {
             total: peas.len(),
-            by_status: StatusCounts {
-                draft: peas.iter().filter(|p| p.status == MS::Draft).count(),
-                todo: peas.iter().filter(|p| p.status == MS::Todo).count(),
-                in_progress: peas.iter().filter(|p| p.status == MS::InProgress).count(),
-                completed: peas.iter().filter(|p| p.status == MS::Completed).count(),
-                scrapped: peas.iter().filter(|p| p.status == MS::Scrapped).count(),
-            },
-            by_type: TypeCounts {
-                milestone: peas.iter().filter(|p| p.pea_type == MT::Milestone).count(),
-                epic: peas.iter().filter(|p| p.pea_type == MT::Epic).count(),
-                story: peas.iter().filter(|p| p.pea_type == MT::Story).count(),
-                feature: peas.iter().filter(|p| p.pea_type == MT::Feature).count(),
-                bug: peas.iter().filter(|p| p.pea_type == MT::Bug).count(),
-                chore: peas.iter().filter(|p| p.pea_type == MT::Chore).count(),
-                research: peas.iter().filter(|p| p.pea_type == MT::Research).count(),
-                task: peas.iter().filter(|p| p.pea_type == MT::Task).count(),
-            },
+            by_status: StatusCounts::of(&peas),
+            by_type: TypeCounts::of(&peas),
+            by_priority: PriorityCounts::of(&peas),
+            by_component: by_component
+                .into_iter()
+                .map(|(component, count)| ComponentCount { component, count })
+                .collect(),
+            by_tag,
+            by_assignee: by_assignee
+                .into_iter()
+                .map(|(assignee, count)| AssigneeCount {
+                    assignee: assignee.map(String::from),
+                    count,
+                })
+                .collect(),
+            by_milestone: peas
+                .iter()
+                .filter(|p| p.pea_type == MT::Milestone)
+                .map(|p| {
+                    let below = descendants(&peas, &p.id);
+                    MilestoneCount {
+                        id: p.id.clone(),
+                        title: p.title.clone(),
+                        total: below.len(),
+                        by_status: StatusCounts::of(below),
+                    }
+                })
+                .collect(),
+            open_age: AgeBucket::histogram(
+                peas.iter()
+                    .filter(|p| p.is_open())
+                    .map(|p| (now - p.created).num_days().max(0)),
+            ),
+            overdue_milestones: peas
+                .iter()
+                .filter(|p| p.pea_type == MT::Milestone && p.is_overdue(today))
+                .map(|p| p.clone().into())
+                .collect(),
+            points: ModelSizeTotals::of(&peas, scale).into(),
+            size_rollups: peas
+                .iter()
+                .filter(|p| matches!(p.pea_type, MT::Milestone | MT::Epic))
+                .map(|p| SizeRollup {
+                    id: p.id.clone(),
+                    title: p.title.clone(),
+                    pea_type: p.pea_type.into(),
+                    totals: ModelSizeTotals::of(descendants(&peas, &p.id), scale).into(),
+                })
+                .collect(),
+            reopened: TypeCounts::of(
+                peas.iter()
+                    .chain(&archived)
+                    .filter(|p| p.reopened_count > 0),
+            ),
         })
     }
 
+    /// Tags in use or configured, optionally filtered by a case-insensitive prefix
+    async fn tags(
+        &self,
+        ctx: &Context<'_>,
+        prefix: Option<String>,
+    ) -> async_graphql::Result<Vec<TagInfo>> {
+        let state = get_state(ctx)?;
+        let mut counts = get_repo(ctx)?.tag_counts()?;
+        for name in state.config.tags.keys() {
+            counts.entry(name.clone()).or_default();
+        }
+        let prefix = prefix.unwrap_or_default().to_lowercase();
+        let strict = state.config.peas.tag_policy == TagPolicy::Strict;
+
+        Ok(counts
+            .into_iter()
+            .filter(|(name, _)| name.to_lowercase().starts_with(&prefix))
+            .map(|(name, count)| {
+                let settings = state.config.tags.get(&name);
+                TagInfo {
+                    allowed: !strict || settings.is_some(),
+                    description: settings.and_then(|s| s.description.clone()),
+                    color: settings.and_then(|s| s.color.clone()),
+                    name,
+                    count,
+                }
+            })
+            .collect())
+    }
+
+    /// Peas updated after `since` (RFC 3339), or all peas, for `peas sync`
+    async fn sync_changes(
+        &self,
+        ctx: &Context<'_>,
+        since: Option<String>,
+    ) -> async_graphql::Result<Vec<SyncPea>> {
+        let since = since
+            .map(|s| {
+                chrono::DateTime::parse_from_rfc3339(&s)
+                    .map(|t| t.with_timezone(&chrono::Utc))
+                    .map_err(|e| PeasError::validation("since", e.to_string()))
+            })
+            .transpose()?;
+        get_repo(ctx)?
+            .list()?
+            .into_iter()
+            .filter(|p| since.is_none_or(|t| p.updated > t))
+            .map(|p| {
+                Ok(SyncPea {
+                    content: crate::sync::encode(&p)?,
+                    id: p.id,
+                    updated: p.updated.to_rfc3339(),
+                })
+            })
+            .collect()
+    }
+
     /// Get a single memory by key
     async fn memory(
         &self,
@@ -215,6 +677,11 @@ impl MutationRoot {
         &self,
         ctx: &Context<'_>,
         input: CreatePeaInput,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
     ) -> async_graphql::Result<Pea> {
         let repo = get_repo(ctx)?;
         let id = repo.generate_id()?;
@@ -243,9 +710,30 @@ impl MutationRoot {
         if let Some(tags) = input.tags {
             pea = pea.with_tags(tags);
         }
+        pea = pea
+            .with_component(input.component)
+            .with_assignee(input.assignee);
+        if let Some(date) = input.target_date {
+            pea = pea.with_target_date(Some(validation::parse_target_date(&date)?));
+        }
+        validation::validate_target_date(pea.pea_type, pea.target_date)?;
+        if let Some(date) = input.defer_until {
+            pea.defer_until = Some(validation::parse_defer_until(&date)?);
+        }
 
-        repo.create(&pea)?;
-        Ok(pea.into())
+        if dry_run {
+            return Ok(repo.preview_create(&pea)?.into());
+        }
+        let file_path = repo.create(&pea)?;
+        record_undo(
+            ctx,
+            UndoOperation::Create {
+                id: pea.id.clone(),
+                file_path,
+            },
+        )?;
+        // Return what was written, including changes from a pre-create hook
+        Ok(repo.get(&pea.id)?.into())
     }
 
     /// Update an existing pea
@@ -253,6 +741,11 @@ impl MutationRoot {
         &self,
         ctx: &Context<'_>,
         input: UpdatePeaInput,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
     ) -> async_graphql::Result<Pea> {
         let repo = get_repo(ctx)?;
         let mut pea = repo.get(&input.id)?;
@@ -270,7 +763,7 @@ impl MutationRoot {
             pea.priority = p.into();
         }
         if let Some(body) = input.body {
-            pea.body = body;
+            pea.body = body.into();
         }
         if let Some(parent) = input.parent {
             pea.parent = if parent.is_empty() {
@@ -306,9 +799,38 @@ impl MutationRoot {
                 pea.tags.retain(|t| t != &tag);
             }
         }
+        if let Some(component) = input.component {
+            pea.component = if component.is_empty() {
+                None
+            } else {
+                Some(component)
+            };
+        }
+        if let Some(assignee) = input.assignee {
+            pea.assignee = if assignee.is_empty() {
+                None
+            } else {
+                Some(assignee)
+            };
+        }
+        if let Some(date) = input.target_date {
+            pea.target_date = if date.is_empty() {
+                None
+            } else {
+                Some(validation::parse_target_date(&date)?)
+            };
+        }
+        validation::validate_target_date(pea.pea_type, pea.target_date)?;
+        if let Some(date) = input.defer_until {
+            pea.defer_until = if date.is_empty() {
+                None
+            } else {
+                Some(validation::parse_defer_until(&date)?)
+            };
+        }
 
         // NOTE: No touch() call - update() handles it internally now
-        repo.update(&mut pea)?;
+        save(ctx, &repo, &mut pea, dry_run)?;
         Ok(pea.into())
     }
 
@@ -318,26 +840,185 @@ impl MutationRoot {
         ctx: &Context<'_>,
         id: String,
         status: PeaStatus,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
     ) -> async_graphql::Result<Pea> {
         let repo = get_repo(ctx)?;
         let mut pea = repo.get(&id)?;
         pea.status = status.into();
         // NOTE: No touch() call - update() handles it internally now
-        repo.update(&mut pea)?;
+        save(ctx, &repo, &mut pea, dry_run)?;
         Ok(pea.into())
     }
 
+    /// Reopen a completed or scrapped pea, which `lock_closed` otherwise keeps
+    /// read-only; a note in its work log records the reopening
+    async fn reopen_pea(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(default_with = "PeaStatus::Todo")] status: PeaStatus,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let repo = get_repo(ctx)?;
+        let mut pea = repo.get(&id)?;
+        pea.status = status.into();
+        if dry_run {
+            return Ok(repo.preview_reopen(&pea)?.into());
+        }
+        let operation = UndoOperation::update_from_file(&pea.id, &repo.find_file_by_id(&pea.id)?)?;
+        repo.reopen(&mut pea)?;
+        record_undo(ctx, operation)?;
+        Ok(pea.into())
+    }
+
+    /// Store peas sent by `peas sync` (full markdown files) unless the stored
+    /// version is as new or newer; returns the IDs that were written
+    ///
+    /// The peas are checked, and rules and hooks run, as for `createPea` and
+    /// `updatePea`. All writes are one undo step.
+    async fn sync_push(
+        &self,
+        ctx: &Context<'_>,
+        peas: Vec<String>,
+        #[graphql(
+            default,
+            desc = "Check the peas and return the IDs that would be written without writing them"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Vec<String>> {
+        let repo = get_repo(ctx)?;
+        let peas = peas
+            .iter()
+            .map(|content| crate::sync::decode(content))
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        let mut operations = Vec::new();
+        let result = crate::sync::accept(&repo, peas, dry_run, &mut operations);
+        let step = match operations.len() {
+            0 => None,
+            1 => operations.pop(),
+            n => Some(UndoOperation::Batch {
+                description: format!("Accept {} synced peas", n),
+                operations,
+            }),
+        };
+        if let Some(step) = step {
+            record_undo(ctx, step)?;
+        }
+        Ok(result?)
+    }
+
     /// Archive a pea
-    async fn archive_pea(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<bool> {
+    ///
+    /// `cascade` archives its descendants too, `reparentTo` moves its children
+    /// under another pea and `orphan` clears their parent. The pea is dropped
+    /// from the `blocking` lists of the peas that stay.
+    async fn archive_pea(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(default, desc = "Archive the descendants too")] cascade: bool,
+        #[graphql(desc = "Move the children under this pea")] reparent_to: Option<String>,
+        #[graphql(default, desc = "Clear the parent of the children")] orphan: bool,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<bool> {
         let repo = get_repo(ctx)?;
-        repo.archive(&id)?;
+        let id = repo.get(&id)?.id;
+        let policy = child_policy(&repo, cascade, reparent_to, orphan)?;
+        let plan = RemovalPlan::new(RemovalKind::Archive, &id, &policy, &repo.list()?)?;
+        if dry_run {
+            return Ok(true);
+        }
+        remove(ctx, &repo, &plan)?;
         Ok(true)
     }
 
+    /// Move an archived pea back among the active ones
+    async fn unarchive_pea(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let repo = get_repo(ctx)?;
+        if dry_run {
+            let mut pea = Pea::from(repo.get_archived(&id)?);
+            pea.archived = true;
+            return Ok(pea);
+        }
+        let (archive_path, restored_path) = repo.unarchive(&id)?;
+        let pea = repo.get(&id)?;
+        record_undo(
+            ctx,
+            UndoOperation::Unarchive {
+                id: pea.id.clone(),
+                archive_path,
+                restored_path,
+            },
+        )?;
+        Ok(pea.into())
+    }
+
     /// Delete a pea permanently
-    async fn delete_pea(&self, ctx: &Context<'_>, id: String) -> async_graphql::Result<bool> {
+    ///
+    /// A pea with children or peas referencing it is only deleted with the
+    /// token from the `CONFIRMATION_REQUIRED` error of a first attempt.
+    /// Children are handled as by `archivePea`.
+    #[allow(clippy::too_many_arguments)]
+    async fn delete_pea(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(desc = "Token from a previous attempt, confirming dependents may dangle")]
+        confirm: Option<String>,
+        #[graphql(default, desc = "Delete the descendants too")] cascade: bool,
+        #[graphql(desc = "Move the children under this pea")] reparent_to: Option<String>,
+        #[graphql(default, desc = "Clear the parent of the children")] orphan: bool,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<bool> {
         let repo = get_repo(ctx)?;
-        repo.delete(&id)?;
+        // Fail like a real delete would for an unknown ID; a file that no longer
+        // parses can still be deleted by its ID
+        repo.find_file_by_id(&id)?;
+        let peas = repo.list()?;
+        let policy = child_policy(&repo, cascade, reparent_to, orphan)?;
+        let plan = RemovalPlan::new(RemovalKind::Delete, &id, &policy, &peas)?;
+        let dependents = Dependents::of(&id, &peas);
+        if !dependents.is_empty() {
+            let pea = repo.get(&id)?;
+            let token = dependents.confirmation_token(&pea);
+            if confirm.as_deref() != Some(token.as_str()) {
+                return Err(PeasError::ConfirmationRequired {
+                    id: pea.id,
+                    token,
+                    dependents,
+                }
+                .into());
+            }
+        }
+        if dry_run {
+            return Ok(true);
+        }
+        remove(ctx, &repo, &plan)?;
         Ok(true)
     }
 
@@ -347,13 +1028,18 @@ impl MutationRoot {
         ctx: &Context<'_>,
         id: String,
         tag: String,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
     ) -> async_graphql::Result<Pea> {
         let repo = get_repo(ctx)?;
         let mut pea = repo.get(&id)?;
         if !pea.tags.contains(&tag) {
             pea.tags.push(tag);
             // NOTE: No touch() call - update() handles it internally now
-            repo.update(&mut pea)?;
+            save(ctx, &repo, &mut pea, dry_run)?;
         }
         Ok(pea.into())
     }
@@ -364,12 +1050,159 @@ impl MutationRoot {
         ctx: &Context<'_>,
         id: String,
         tag: String,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
     ) -> async_graphql::Result<Pea> {
         let repo = get_repo(ctx)?;
         let mut pea = repo.get(&id)?;
         pea.tags.retain(|t| t != &tag);
         // NOTE: No touch() call - update() handles it internally now
-        repo.update(&mut pea)?;
+        save(ctx, &repo, &mut pea, dry_run)?;
+        Ok(pea.into())
+    }
+
+    /// Set the size estimate of a pea (validated against the configured
+    /// scale), or clear it with null
+    async fn set_size(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        size: Option<String>,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let scale = get_state(ctx)?.config.peas.size_scale;
+        let repo = get_repo(ctx)?;
+        let mut pea = repo.get(&id)?;
+        pea.size = size.map(|s| scale.normalize(&s)).transpose()?;
+        save(ctx, &repo, &mut pea, dry_run)?;
+        Ok(pea.into())
+    }
+
+    /// Append a timestamped note to the work log in a pea's body
+    async fn add_note(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        text: String,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        if text.trim().is_empty() {
+            return Err(PeasError::validation("text", "A note cannot be empty").into());
+        }
+        let repo = get_repo(ctx)?;
+        let mut pea = repo.get(&id)?;
+        pea.add_note(&text, repo.actor(), chrono::Utc::now())?;
+        save(ctx, &repo, &mut pea, dry_run)?;
+        Ok(pea.into())
+    }
+
+    /// Claim a pea for the request's actor so other agents leave it alone;
+    /// renews the actor's own claim
+    async fn claim_pea(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(desc = "How long the claim lasts, e.g. 30m, 2h or 1d (default 2h)")] ttl: Option<
+            String,
+        >,
+        #[graphql(default, desc = "Take over an active claim held by someone else")] force: bool,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let repo = get_repo(ctx)?;
+        let actor = crate::claim::require_actor(repo.actor())?.to_string();
+        let ttl = crate::claim::parse_ttl(ttl.as_deref().unwrap_or(crate::claim::DEFAULT_TTL))?;
+        let mut pea = repo.get(&id)?;
+        crate::claim::claim(&mut pea, &actor, ttl, force, chrono::Utc::now())?;
+        save(ctx, &repo, &mut pea, dry_run)?;
+        Ok(pea.into())
+    }
+
+    /// Release the claim on a pea; an active claim held by someone else
+    /// needs `force`
+    async fn release_pea(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        #[graphql(default, desc = "Release an active claim held by someone else")] force: bool,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let repo = get_repo(ctx)?;
+        let mut pea = repo.get(&id)?;
+        if crate::claim::release(&mut pea, repo.actor(), force, chrono::Utc::now())?.is_some() {
+            save(ctx, &repo, &mut pea, dry_run)?;
+        }
+        Ok(pea.into())
+    }
+
+    /// Add a link to a pea, or relabel an existing one with the same URL;
+    /// the kind is inferred from the URL when omitted
+    async fn add_link(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        url: String,
+        label: Option<String>,
+        kind: Option<LinkKind>,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let repo = get_repo(ctx)?;
+        let mut pea = repo.get(&id)?;
+        let mut link = crate::model::PeaLink::new(url);
+        link.label = label;
+        if let Some(kind) = kind {
+            link.kind = kind.into();
+        }
+        pea.set_link(link);
+        save(ctx, &repo, &mut pea, dry_run)?;
+        Ok(pea.into())
+    }
+
+    /// Remove the link with the given URL from a pea
+    async fn remove_link(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+        url: String,
+        #[graphql(
+            default,
+            desc = "Check the change and return the result without writing it"
+        )]
+        dry_run: bool,
+    ) -> async_graphql::Result<Pea> {
+        let repo = get_repo(ctx)?;
+        let mut pea = repo.get(&id)?;
+        // Fails like `peas link --remove` does
+        if !pea.links.iter().any(|l| l.url == url) {
+            return Err(
+                async_graphql::Error::new(format!("{} has no link to {}", pea.id, url))
+                    .extend_with(|_, e| e.set("code", "NOT_FOUND")),
+            );
+        }
+        pea.links.retain(|l| l.url != url);
+        save(ctx, &repo, &mut pea, dry_run)?;
         Ok(pea.into())
     }
 
@@ -1,5 +1,5 @@
 use crate::model::{self, Memory as ModelMemory, Pea as ModelPea};
-use async_graphql::{Enum, InputObject, SimpleObject};
+use async_graphql::{ComplexObject, Context, Enum, InputObject, SimpleObject};
 
 #[derive(Enum, Copy, Clone, Eq, PartialEq)]
 pub enum PeaType {
@@ -109,37 +109,182 @@ impl From<PeaPriority> for model::PeaPriority {
     }
 }
 
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum LinkKind {
+    Pr,
+    Issue,
+    Doc,
+}
+
+impl From<model::LinkKind> for LinkKind {
+    fn from(k: model::LinkKind) -> Self {
+        match k {
+            model::LinkKind::Pr => LinkKind::Pr,
+            model::LinkKind::Issue => LinkKind::Issue,
+            model::LinkKind::Doc => LinkKind::Doc,
+        }
+    }
+}
+
+impl From<LinkKind> for model::LinkKind {
+    fn from(k: LinkKind) -> Self {
+        match k {
+            LinkKind::Pr => model::LinkKind::Pr,
+            LinkKind::Issue => model::LinkKind::Issue,
+            LinkKind::Doc => model::LinkKind::Doc,
+        }
+    }
+}
+
+/// Labelled link to a pull request, issue or document
 #[derive(SimpleObject)]
+pub struct PeaLink {
+    pub url: String,
+    pub label: Option<String>,
+    pub kind: LinkKind,
+}
+
+impl From<model::PeaLink> for PeaLink {
+    fn from(l: model::PeaLink) -> Self {
+        Self {
+            url: l.url,
+            label: l.label,
+            kind: l.kind.into(),
+        }
+    }
+}
+
+/// Soft lock telling other agents someone is working on a pea
+#[derive(SimpleObject)]
+pub struct Claim {
+    /// Person or agent holding the claim
+    pub by: String,
+    /// When the claim expires (RFC 3339)
+    pub until: String,
+}
+
+impl From<model::Claim> for Claim {
+    fn from(c: model::Claim) -> Self {
+        Self {
+            by: c.by,
+            until: c.until.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
 pub struct Pea {
     pub id: String,
     pub title: String,
+    /// Title slug, accepted wherever an ID is
+    pub slug: Option<String>,
     pub pea_type: PeaType,
     pub status: PeaStatus,
     pub priority: PeaPriority,
+    /// Estimate on the configured size scale
+    pub size: Option<String>,
     pub tags: Vec<String>,
+    /// Part of the project the pea belongs to
+    pub component: Option<String>,
+    /// Person or agent responsible for the pea
+    pub assignee: Option<String>,
     pub parent: Option<String>,
     pub blocking: Vec<String>,
     pub external_refs: Vec<String>,
+    pub links: Vec<PeaLink>,
+    /// Due date of a milestone (YYYY-MM-DD)
+    pub target_date: Option<String>,
+    /// Date before which the pea is deferred (YYYY-MM-DD)
+    pub defer_until: Option<String>,
+    /// Release whose CHANGELOG entry mentions this pea
+    pub released_in: Option<String>,
+    /// Times the pea was reopened after being completed or scrapped
+    pub reopened_count: u32,
     pub created: String,
     pub updated: String,
+    /// Person or agent that created the pea, when known
+    pub created_by: Option<String>,
+    /// Person or agent behind the last change, when known
+    pub updated_by: Option<String>,
+    /// Active claim on the pea; expired claims are left out
+    pub claim: Option<Claim>,
+    /// Notes in the body's work log, oldest first (see `addNote`)
+    pub work_log: Vec<String>,
     pub body: String,
+    /// Whether the pea was listed from the archive (see `peas(includeArchived)`)
+    pub archived: bool,
+}
+
+#[ComplexObject]
+impl Pea {
+    /// IDs of peas whose body mentions this pea (backlinks)
+    async fn referenced_by(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<String>> {
+        let peas = super::schema::get_repo(ctx)?.list()?;
+        Ok(model::References::build(&peas)
+            .referenced_by(&self.id)
+            .to_vec())
+    }
+
+    /// Other peas related by tags, title, parent and references, most related first
+    async fn related(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Most peas to return (default 10)")] limit: Option<usize>,
+    ) -> async_graphql::Result<Vec<RelatedPea>> {
+        let peas = super::schema::get_repo(ctx)?.list()?;
+        let Some(pea) = peas.iter().find(|p| p.id == self.id) else {
+            return Ok(Vec::new());
+        };
+        Ok(model::related(pea, &peas)
+            .into_iter()
+            .take(limit.unwrap_or(10))
+            .map(|r| RelatedPea {
+                score: r.score(),
+                reasons: r.factors.into_iter().map(|f| f.reason).collect(),
+                pea: r.pea.clone().into(),
+            })
+            .collect())
+    }
+
+    /// Former IDs of this pea, which still resolve (see `peas mv`)
+    async fn aliases(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<String>> {
+        let aliases = super::schema::get_repo(ctx)?.aliases()?;
+        Ok(aliases.of(&self.id).map(String::from).collect())
+    }
 }
 
 impl From<ModelPea> for Pea {
     fn from(p: ModelPea) -> Self {
+        let claim = p.active_claim(chrono::Utc::now()).cloned().map(Into::into);
+        let work_log = p.work_log();
         Self {
             id: p.id,
             title: p.title,
+            slug: p.slug,
             pea_type: p.pea_type.into(),
             status: p.status.into(),
             priority: p.priority.into(),
+            size: p.size,
             tags: p.tags,
+            component: p.component,
+            assignee: p.assignee,
             parent: p.parent,
             blocking: p.blocking,
             external_refs: p.external_refs,
+            links: p.links.into_iter().map(Into::into).collect(),
+            target_date: p.target_date.map(|d| d.to_string()),
+            defer_until: p.defer_until.map(|d| d.to_string()),
+            released_in: p.released_in,
+            reopened_count: p.reopened_count,
             created: p.created.to_rfc3339(),
             updated: p.updated.to_rfc3339(),
-            body: p.body,
+            created_by: p.created_by,
+            updated_by: p.updated_by,
+            claim,
+            work_log,
+            body: p.body.into_string(),
+            archived: false,
         }
     }
 }
@@ -151,7 +296,15 @@ pub struct PeaFilter {
     pub priority: Option<PeaPriority>,
     pub parent: Option<String>,
     pub tag: Option<String>,
+    pub component: Option<String>,
+    pub assignee: Option<String>,
     pub is_open: Option<bool>,
+    /// Person or agent that created the pea
+    pub created_by: Option<String>,
+    /// Person or agent behind the last change
+    pub updated_by: Option<String>,
+    /// Leave out peas claimed by someone other than the request's actor
+    pub unclaimed: Option<bool>,
 }
 
 #[derive(InputObject)]
@@ -165,6 +318,14 @@ pub struct CreatePeaInput {
     pub blocking: Option<Vec<String>>,
     pub external_refs: Option<Vec<String>>,
     pub tags: Option<Vec<String>>,
+    /// One of the components declared in the config
+    pub component: Option<String>,
+    /// Defaults to the component's first owner when it auto-assigns
+    pub assignee: Option<String>,
+    /// Due date for a milestone (YYYY-MM-DD)
+    pub target_date: Option<String>,
+    /// Keep the pea out of `peas list` and `peas suggest` until this date (YYYY-MM-DD)
+    pub defer_until: Option<String>,
 }
 
 #[derive(InputObject)]
@@ -181,6 +342,14 @@ pub struct UpdatePeaInput {
     pub remove_external_refs: Option<Vec<String>>,
     pub add_tags: Option<Vec<String>>,
     pub remove_tags: Option<Vec<String>>,
+    /// Component name, empty string to clear
+    pub component: Option<String>,
+    /// Assignee, empty string to clear
+    pub assignee: Option<String>,
+    /// Due date for a milestone (YYYY-MM-DD, empty string to clear)
+    pub target_date: Option<String>,
+    /// Defer-until date (YYYY-MM-DD, empty string to clear)
+    pub defer_until: Option<String>,
 }
 
 #[derive(SimpleObject)]
@@ -189,11 +358,128 @@ pub struct PeaConnection {
     pub total_count: usize,
 }
 
+/// A search result with its relevance
+#[derive(SimpleObject)]
+pub struct SearchHit {
+    pub pea: Pea,
+    /// Higher for matches in the title, then tags, ID and body
+    pub score: u32,
+    /// Context around the first match in the body, matches in `**`
+    pub snippet: Option<String>,
+}
+
+impl From<crate::search::SearchHit> for SearchHit {
+    fn from(hit: crate::search::SearchHit) -> Self {
+        Self {
+            snippet: hit.snippet.map(|s| s.marked("**", "**")),
+            score: hit.score,
+            pea: hit.pea.into(),
+        }
+    }
+}
+
+/// A pea related to another, see `Pea.related`
+#[derive(SimpleObject)]
+pub struct RelatedPea {
+    pub pea: Pea,
+    /// Sum of the points of all reasons
+    pub score: u32,
+    /// Why the peas are related, e.g. "shares tag auth" or "same parent peas-abc12"
+    pub reasons: Vec<String>,
+}
+
+/// A pea with its similarity in meaning to another pea
+#[derive(SimpleObject)]
+pub struct SimilarPea {
+    pub pea: Pea,
+    /// Cosine similarity of their embeddings, up to 1 for the same text
+    pub similarity: f32,
+}
+
+#[cfg(feature = "semantic")]
+impl From<crate::semantic::SemanticHit> for SimilarPea {
+    fn from(hit: crate::semantic::SemanticHit) -> Self {
+        Self {
+            similarity: hit.similarity,
+            pea: hit.pea.into(),
+        }
+    }
+}
+
+/// A pea as exchanged by `peas sync`
+#[derive(SimpleObject)]
+pub struct SyncPea {
+    pub id: String,
+    pub updated: String,
+    /// The full markdown file, frontmatter included
+    pub content: String,
+}
+
+/// A tag known to the project, for suggesting valid values
+#[derive(SimpleObject)]
+pub struct TagInfo {
+    pub name: String,
+    /// Number of active peas with the tag
+    pub count: usize,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    /// Whether the tag can be added under the project's tag policy
+    pub allowed: bool,
+}
+
 #[derive(SimpleObject)]
 pub struct ProjectStats {
     pub total: usize,
     pub by_status: StatusCounts,
     pub by_type: TypeCounts,
+    pub by_priority: PriorityCounts,
+    /// Active peas per component, configured or in use
+    pub by_component: Vec<ComponentCount>,
+    /// Peas per tag, most used first
+    pub by_tag: Vec<TagCount>,
+    /// Peas per assignee; `assignee` is null for unassigned peas
+    pub by_assignee: Vec<AssigneeCount>,
+    /// Status counts of the peas below each milestone
+    pub by_milestone: Vec<MilestoneCount>,
+    /// Open peas by days since they were created
+    pub open_age: Vec<AgeBucket>,
+    /// Open milestones whose target date has passed
+    pub overdue_milestones: Vec<Pea>,
+    /// Estimated points across the project
+    pub points: SizeTotals,
+    /// Point roll-ups of every milestone and epic
+    pub size_rollups: Vec<SizeRollup>,
+    /// Peas reopened at least once, archived ones included
+    pub reopened: TypeCounts,
+}
+
+/// Summed size estimates; scrapped peas do not count
+#[derive(SimpleObject)]
+pub struct SizeTotals {
+    pub points: u32,
+    /// Points of completed peas
+    pub done: u32,
+    /// Peas without a size on the configured scale
+    pub unestimated: usize,
+}
+
+impl From<model::SizeTotals> for SizeTotals {
+    fn from(t: model::SizeTotals) -> Self {
+        Self {
+            points: t.points,
+            done: t.done,
+            unestimated: t.unestimated,
+        }
+    }
+}
+
+/// Point totals of the peas below a milestone or epic
+#[derive(SimpleObject)]
+pub struct SizeRollup {
+    pub id: String,
+    pub title: String,
+    pub pea_type: PeaType,
+    pub totals: SizeTotals,
 }
 
 #[derive(SimpleObject)]
@@ -205,6 +491,133 @@ pub struct StatusCounts {
     pub scrapped: usize,
 }
 
+impl StatusCounts {
+    /// Count peas per status
+    pub fn of<'a>(peas: impl IntoIterator<Item = &'a ModelPea>) -> Self {
+        use model::PeaStatus as MS;
+        let mut counts = Self {
+            draft: 0,
+            todo: 0,
+            in_progress: 0,
+            completed: 0,
+            scrapped: 0,
+        };
+        for pea in peas {
+            *match pea.status {
+                MS::Draft => &mut counts.draft,
+                MS::Todo => &mut counts.todo,
+                MS::InProgress => &mut counts.in_progress,
+                MS::Completed => &mut counts.completed,
+                MS::Scrapped => &mut counts.scrapped,
+            } += 1;
+        }
+        counts
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct PriorityCounts {
+    pub critical: usize,
+    pub high: usize,
+    pub normal: usize,
+    pub low: usize,
+    pub deferred: usize,
+}
+
+impl PriorityCounts {
+    /// Count peas per priority
+    pub fn of<'a>(peas: impl IntoIterator<Item = &'a ModelPea>) -> Self {
+        use model::PeaPriority as MP;
+        let mut counts = Self {
+            critical: 0,
+            high: 0,
+            normal: 0,
+            low: 0,
+            deferred: 0,
+        };
+        for pea in peas {
+            *match pea.priority {
+                MP::Critical => &mut counts.critical,
+                MP::High => &mut counts.high,
+                MP::Normal => &mut counts.normal,
+                MP::Low => &mut counts.low,
+                MP::Deferred => &mut counts.deferred,
+            } += 1;
+        }
+        counts
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ComponentCount {
+    pub component: String,
+    pub count: usize,
+}
+
+#[derive(SimpleObject)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+#[derive(SimpleObject)]
+pub struct AssigneeCount {
+    pub assignee: Option<String>,
+    pub count: usize,
+}
+
+/// Progress of a milestone by the peas below it
+#[derive(SimpleObject)]
+pub struct MilestoneCount {
+    pub id: String,
+    pub title: String,
+    /// Peas below the milestone, at any depth
+    pub total: usize,
+    pub by_status: StatusCounts,
+}
+
+/// Bounds of the open-age histogram in days; the last bucket is open-ended
+const AGE_BUCKETS: [(i64, Option<i64>); 6] = [
+    (0, Some(1)),
+    (1, Some(7)),
+    (7, Some(30)),
+    (30, Some(90)),
+    (90, Some(365)),
+    (365, None),
+];
+
+/// Open peas created `min_days` up to (not including) `max_days` ago
+#[derive(SimpleObject)]
+pub struct AgeBucket {
+    pub min_days: i64,
+    /// Null for the last bucket
+    pub max_days: Option<i64>,
+    pub count: usize,
+}
+
+impl AgeBucket {
+    /// Sort ages in days into the fixed buckets, empty ones included
+    pub fn histogram(ages: impl IntoIterator<Item = i64>) -> Vec<Self> {
+        let mut buckets: Vec<Self> = AGE_BUCKETS
+            .iter()
+            .map(|&(min_days, max_days)| Self {
+                min_days,
+                max_days,
+                count: 0,
+            })
+            .collect();
+        for age in ages {
+            if let Some(bucket) = buckets
+                .iter_mut()
+                .find(|b| age >= b.min_days && b.max_days.is_none_or(|max| age < max))
+            {
+                bucket.count += 1;
+            }
+        }
+        buckets
+    }
+}
+
 #[derive(SimpleObject)]
 pub struct TypeCounts {
     pub milestone: usize,
@@ -217,6 +630,36 @@ pub struct TypeCounts {
     pub task: usize,
 }
 
+impl TypeCounts {
+    /// Count peas per type
+    pub fn of<'a>(peas: impl IntoIterator<Item = &'a ModelPea>) -> Self {
+        use model::PeaType as MT;
+        let mut counts = Self {
+            milestone: 0,
+            epic: 0,
+            story: 0,
+            feature: 0,
+            bug: 0,
+            chore: 0,
+            research: 0,
+            task: 0,
+        };
+        for pea in peas {
+            *match pea.pea_type {
+                MT::Milestone => &mut counts.milestone,
+                MT::Epic => &mut counts.epic,
+                MT::Story => &mut counts.story,
+                MT::Feature => &mut counts.feature,
+                MT::Bug => &mut counts.bug,
+                MT::Chore => &mut counts.chore,
+                MT::Research => &mut counts.research,
+                MT::Task => &mut counts.task,
+            } += 1;
+        }
+        counts
+    }
+}
+
 #[derive(SimpleObject, Clone)]
 pub struct Memory {
     pub key: String,
@@ -0,0 +1,259 @@
+//! Read-only HTML viewer for `peas serve --viewer`.
+//!
+//! Pages are rendered on the server straight from the repository, with no
+//! JavaScript, so stakeholders can browse peas with nothing but a browser.
+
+use crate::model::{Memory, Pea, PeaStatus, expand_includes, resolve_include};
+use std::fmt::Write;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;color:#222}\
+table{border-collapse:collapse;width:100%}th,td{text-align:left;padding:.35rem .5rem;border-bottom:1px solid #ddd}\
+a{color:#2a6f3f}code{color:#555}.tag{background:#eef;border-radius:.3rem;padding:0 .3rem;margin-right:.2rem}\
+pre{white-space:pre-wrap;background:#f6f6f6;padding:1rem;border-radius:.3rem}nav a{margin-right:.8rem}";
+
+/// Escape text for use in HTML content and attribute values
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn page(title: &str, content: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>{}</title><style>{}</style></head><body>{}</body></html>\n",
+        escape(title),
+        STYLE,
+        content
+    )
+}
+
+fn tags(pea: &Pea) -> String {
+    pea.tags
+        .iter()
+        .map(|t| format!("<span class=\"tag\">{}</span>", escape(t)))
+        .collect()
+}
+
+fn link(pea: &Pea) -> String {
+    format!(
+        "<a href=\"/view/{}\">{}</a>",
+        escape(&pea.id),
+        escape(&pea.title)
+    )
+}
+
+/// List page; `status` narrows it to one status, otherwise closed peas are hidden
+pub fn render_index(project: &str, peas: &[Pea], status: Option<PeaStatus>) -> String {
+    let mut shown: Vec<&Pea> = peas
+        .iter()
+        .filter(|p| match status {
+            Some(status) => p.status == status,
+            None => p.is_open(),
+        })
+        .collect();
+    // Most urgent first (priorities are declared critical to deferred), then most recent
+    shown.sort_by_key(|p| (p.priority as u8, std::cmp::Reverse(p.updated)));
+
+    let mut html = format!(
+        "<h1>{}</h1><nav><a href=\"/view\">Open</a>",
+        escape(project)
+    );
+    for s in [
+        PeaStatus::Draft,
+        PeaStatus::Todo,
+        PeaStatus::InProgress,
+        PeaStatus::Completed,
+        PeaStatus::Scrapped,
+    ] {
+        let _ = write!(html, "<a href=\"/view?status={}\">{}</a>", s, s);
+    }
+    html.push_str("</nav>");
+
+    if shown.is_empty() {
+        html.push_str("<p>No peas.</p>");
+        return page(project, &html);
+    }
+    html.push_str(
+        "<table><tr><th>ID</th><th>Title</th><th>Type</th><th>Status</th><th>Priority</th><th>Tags</th></tr>",
+    );
+    for pea in shown {
+        let _ = write!(
+            html,
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(&pea.id),
+            link(pea),
+            pea.pea_type,
+            pea.status,
+            pea.priority,
+            tags(pea)
+        );
+    }
+    html.push_str("</table>");
+    page(project, &html)
+}
+
+/// Detail page with metadata, relationships and the body as plain text, with
+/// includes expanded from `all` and `memories`
+pub fn render_pea(pea: &Pea, all: &[Pea], memories: &[Memory]) -> String {
+    let mut html = format!(
+        "<nav><a href=\"/view\">&larr; All peas</a></nav><h1>{}</h1><table>",
+        escape(&pea.title)
+    );
+    let mut row = |name: &str, value: String| {
+        let _ = write!(html, "<tr><th>{}</th><td>{}</td></tr>", name, value);
+    };
+    row("ID", format!("<code>{}</code>", escape(&pea.id)));
+    row("Type", pea.pea_type.to_string());
+    row("Status", pea.status.to_string());
+    row("Priority", pea.priority.to_string());
+    if !pea.tags.is_empty() {
+        row("Tags", tags(pea));
+    }
+    if let Some(parent) = &pea.parent {
+        let value = match all.iter().find(|p| &p.id == parent) {
+            Some(parent) => link(parent),
+            None => escape(parent),
+        };
+        row("Parent", value);
+    }
+    if let Some(target) = pea.target_date {
+        row("Target", target.to_string());
+    }
+    if !pea.blocking.is_empty() {
+        let links: Vec<String> = pea
+            .blocking
+            .iter()
+            .map(|id| match all.iter().find(|p| &p.id == id) {
+                Some(p) => link(p),
+                None => escape(id),
+            })
+            .collect();
+        row("Blocking", links.join(", "));
+    }
+    if !pea.external_refs.is_empty() {
+        let refs: Vec<String> = pea
+            .external_refs
+            .iter()
+            .map(|r| {
+                if r.starts_with("http://") || r.starts_with("https://") {
+                    format!("<a href=\"{0}\">{0}</a>", escape(r))
+                } else {
+                    escape(r)
+                }
+            })
+            .collect();
+        row("Refs", refs.join("<br>"));
+    }
+    if !pea.links.is_empty() {
+        let links: Vec<String> = pea
+            .links
+            .iter()
+            .map(|l| {
+                if l.url.starts_with("http://") || l.url.starts_with("https://") {
+                    format!(
+                        "[{}] <a href=\"{}\">{}</a>",
+                        l.kind,
+                        escape(&l.url),
+                        escape(l.title())
+                    )
+                } else {
+                    format!("[{}] {} ({})", l.kind, escape(l.title()), escape(&l.url))
+                }
+            })
+            .collect();
+        row("Links", links.join("<br>"));
+    }
+    let by = |actor: &Option<String>| {
+        actor
+            .as_ref()
+            .map(|a| format!(" by {}", escape(a)))
+            .unwrap_or_default()
+    };
+    row(
+        "Created",
+        format!(
+            "{}{}",
+            pea.created.format("%Y-%m-%d %H:%M UTC"),
+            by(&pea.created_by)
+        ),
+    );
+    row(
+        "Updated",
+        format!(
+            "{}{}",
+            pea.updated.format("%Y-%m-%d %H:%M UTC"),
+            by(&pea.updated_by)
+        ),
+    );
+    html.push_str("</table>");
+
+    if !pea.body.is_empty() {
+        let body = expand_includes(&pea.body, |target| resolve_include(all, memories, target));
+        let _ = write!(html, "<pre>{}</pre>", escape(&body));
+    }
+
+    let children: Vec<&Pea> = all
+        .iter()
+        .filter(|p| p.parent.as_deref() == Some(&pea.id))
+        .collect();
+    if !children.is_empty() {
+        html.push_str("<h2>Children</h2><ul>");
+        for child in children {
+            let _ = write!(html, "<li>{} ({})</li>", link(child), child.status);
+        }
+        html.push_str("</ul>");
+    }
+    page(&pea.title, &html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    #[test]
+    fn test_pages_escape_user_content() {
+        let parent = Pea::new("peas-p1".into(), "Epic <b>".into(), PeaType::Epic);
+        let child = Pea::new("peas-c1".into(), "Child & co".into(), PeaType::Task)
+            .with_parent(Some("peas-p1".into()))
+            .with_body("<script>alert(1)</script>".into());
+        let done = Pea::new("peas-d1".into(), "Done".into(), PeaType::Task)
+            .with_status(PeaStatus::Completed);
+        let all = vec![parent.clone(), child.clone(), done];
+
+        let index = render_index("demo", &all, None);
+        assert!(index.contains("Epic &lt;b&gt;"));
+        assert!(index.contains("<a href=\"/view/peas-c1\">Child &amp; co</a>"));
+        assert!(!index.contains("peas-d1"));
+        assert!(render_index("demo", &all, Some(PeaStatus::Completed)).contains("peas-d1"));
+
+        let detail = render_pea(&child, &all, &[]);
+        assert!(detail.contains("&lt;script&gt;"));
+        assert!(!detail.contains("<script>"));
+        assert!(detail.contains("<a href=\"/view/peas-p1\">Epic &lt;b&gt;</a>"));
+        assert!(render_pea(&parent, &all, &[]).contains("<h2>Children</h2>"));
+    }
+
+    #[test]
+    fn test_detail_expands_includes() {
+        let criteria = Pea::new("peas-crit1".into(), "Criteria".into(), PeaType::Task)
+            .with_body("- <fast>".into());
+        let pea = Pea::new("peas-a1".into(), "A".into(), PeaType::Task)
+            .with_body("{{include: peas-crit1}}\n{{include: memory:style}}".into());
+        let memories = vec![Memory::new("style".into()).with_content("Plain words".into())];
+
+        let detail = render_pea(&pea, &[criteria], &memories);
+        assert!(detail.contains("<pre>- &lt;fast&gt;\nPlain words</pre>"));
+    }
+}
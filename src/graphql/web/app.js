@@ -0,0 +1,224 @@
+// peas web UI: a small client for the GraphQL endpoint served at "/".
+"use strict";
+
+const STATUSES = ["DRAFT", "TODO", "IN_PROGRESS", "COMPLETED", "SCRAPPED"];
+const TYPES = ["MILESTONE", "EPIC", "STORY", "FEATURE", "BUG", "CHORE", "RESEARCH", "TASK"];
+const PEA_FIELDS =
+  "id title peaType status priority tags parent blocking externalRefs links { url label kind } targetDate created updated body";
+
+const state = { peas: [], view: "board", selected: null };
+
+const $ = (id) => document.getElementById(id);
+
+/** Build an element; strings become text nodes, so content is never parsed as HTML */
+function el(tag, attrs, ...children) {
+  const node = document.createElement(tag);
+  for (const [key, value] of Object.entries(attrs || {})) {
+    if (key.startsWith("on")) node.addEventListener(key.slice(2), value);
+    else node.setAttribute(key, value);
+  }
+  for (const child of children.flat()) {
+    if (child !== null && child !== undefined) node.append(child);
+  }
+  return node;
+}
+
+const label = (value) => value.toLowerCase().replace("_", " ");
+
+async function gql(query, variables) {
+  const response = await fetch("/", {
+    method: "POST",
+    headers: { "Content-Type": "application/json" },
+    body: JSON.stringify({ query, variables: variables || {} }),
+  });
+  const body = await response.json();
+  if (body.errors && body.errors.length) throw new Error(body.errors[0].message);
+  return body.data;
+}
+
+function showError(error) {
+  $("error").textContent = error ? String(error.message || error) : "";
+  $("error").hidden = !error;
+}
+
+/** Type, status and tag are filtered by the server, text in the browser */
+async function load() {
+  const filter = {};
+  if ($("filter-type").value) filter.peaType = $("filter-type").value;
+  if ($("filter-status").value) filter.status = $("filter-status").value;
+  if ($("filter-tag").value.trim()) filter.tag = $("filter-tag").value.trim();
+  try {
+    const data = await gql(
+      `query($filter: PeaFilter) { peas(filter: $filter, limit: 100000) { nodes { ${PEA_FIELDS} } } }`,
+      { filter },
+    );
+    state.peas = data.peas.nodes;
+    showError(null);
+  } catch (error) {
+    showError(error);
+  }
+  render();
+}
+
+async function setStatus(id, status) {
+  try {
+    await gql(
+      "mutation($id: String!, $status: PeaStatus!) { setStatus(id: $id, status: $status) { id } }",
+      { id, status },
+    );
+    await load();
+  } catch (error) {
+    showError(error);
+  }
+}
+
+function visiblePeas() {
+  const text = $("filter-text").value.trim().toLowerCase();
+  if (!text) return state.peas;
+  return state.peas.filter((p) =>
+    [p.id, p.title, p.body].some((field) => field.toLowerCase().includes(text)),
+  );
+}
+
+function statusSelect(pea) {
+  const select = el(
+    "select",
+    { onchange: (e) => setStatus(pea.id, e.target.value), onclick: (e) => e.stopPropagation() },
+    STATUSES.map((s) => el("option", { value: s }, label(s))),
+  );
+  select.value = pea.status;
+  return select;
+}
+
+/** Only http(s) URLs become anchors, so stored links cannot run script */
+const externalLink = (url, text) =>
+  /^https?:\/\//.test(url) ? el("a", { href: url, target: "_blank", rel: "noopener" }, text) : `${text} (${url})`;
+
+const tags = (pea) => pea.tags.map((t) => el("span", { class: "tag" }, t));
+
+function card(pea) {
+  return el(
+    "div",
+    { class: "card", onclick: () => select(pea.id) },
+    el("div", { class: "title" }, pea.title),
+    el(
+      "div",
+      { class: "meta" },
+      `${pea.id} · ${label(pea.peaType)} · `,
+      el("span", { class: `priority-${pea.priority.toLowerCase()}` }, label(pea.priority)),
+    ),
+    tags(pea),
+  );
+}
+
+function renderBoard(peas) {
+  const statuses = $("filter-status").value ? [$("filter-status").value] : STATUSES;
+  return el(
+    "div",
+    { class: "board" },
+    statuses.map((status) => {
+      const items = peas.filter((p) => p.status === status);
+      return el(
+        "div",
+        { class: "column" },
+        el("h2", {}, `${label(status)} (${items.length})`),
+        items.map(card),
+      );
+    }),
+  );
+}
+
+function renderList(peas) {
+  return el(
+    "table",
+    {},
+    el("tr", {}, ["ID", "Title", "Type", "Priority", "Tags", "Status"].map((h) => el("th", {}, h))),
+    peas.map((pea) =>
+      el(
+        "tr",
+        { class: "row", onclick: () => select(pea.id) },
+        el("td", {}, pea.id),
+        el("td", {}, pea.title),
+        el("td", {}, label(pea.peaType)),
+        el("td", { class: `priority-${pea.priority.toLowerCase()}` }, label(pea.priority)),
+        el("td", {}, tags(pea)),
+        el("td", {}, statusSelect(pea)),
+      ),
+    ),
+  );
+}
+
+function renderDetail() {
+  const detail = $("detail");
+  const pea = state.peas.find((p) => p.id === state.selected);
+  detail.hidden = !pea;
+  detail.replaceChildren();
+  if (!pea) return;
+
+  const link = (id) => {
+    const target = state.peas.find((p) => p.id === id);
+    return el("a", { href: `#${id}`, onclick: () => select(id) }, target ? target.title : id);
+  };
+  const children = state.peas.filter((p) => p.parent === pea.id);
+  const rows = [
+    ["ID", pea.id],
+    ["Type", label(pea.peaType)],
+    ["Status", statusSelect(pea)],
+    ["Priority", label(pea.priority)],
+    ["Tags", pea.tags.length ? tags(pea) : null],
+    ["Parent", pea.parent ? link(pea.parent) : null],
+    ["Blocking", pea.blocking.length ? pea.blocking.flatMap((id, i) => (i ? [", ", link(id)] : [link(id)])) : null],
+    ["Target", pea.targetDate],
+    ["Links", pea.links.length ? pea.links.map((l) => el("div", {}, `[${l.kind.toLowerCase()}] `, externalLink(l.url, l.label || l.url))) : null],
+    ["Refs", pea.externalRefs.length ? pea.externalRefs.map((r) => el("div", {}, externalLink(r, r))) : null],
+    ["Updated", new Date(pea.updated).toLocaleString()],
+  ].filter(([, value]) => value !== null && value !== undefined);
+
+  detail.append(
+    el("button", { type: "button", onclick: () => select(null) }, "Close"),
+    el("h2", {}, pea.title),
+    el("dl", {}, rows.map(([name, value]) => [el("dt", {}, name), el("dd", {}, value)])),
+    pea.body ? el("pre", {}, pea.body) : null,
+    children.length
+      ? [el("h3", {}, "Children"), el("ul", {}, children.map((c) => el("li", {}, link(c.id), ` (${label(c.status)})`)))]
+      : null,
+  );
+}
+
+function select(id) {
+  state.selected = id;
+  if (id) history.replaceState(null, "", `#${id}`);
+  else history.replaceState(null, "", location.pathname);
+  renderDetail();
+}
+
+function render() {
+  const peas = visiblePeas();
+  $("peas").replaceChildren(state.view === "board" ? renderBoard(peas) : renderList(peas));
+  $("show-board").classList.toggle("active", state.view === "board");
+  $("show-list").classList.toggle("active", state.view === "list");
+  renderDetail();
+}
+
+function init() {
+  for (const type of TYPES) $("filter-type").append(el("option", { value: type }, label(type)));
+  for (const status of STATUSES) $("filter-status").append(el("option", { value: status }, label(status)));
+
+  $("filter-text").addEventListener("input", render);
+  for (const id of ["filter-type", "filter-status"]) $(id).addEventListener("change", load);
+  $("filter-tag").addEventListener("change", load);
+  $("filters").addEventListener("submit", (e) => e.preventDefault());
+  $("show-board").addEventListener("click", () => {
+    state.view = "board";
+    render();
+  });
+  $("show-list").addEventListener("click", () => {
+    state.view = "list";
+    render();
+  });
+
+  state.selected = decodeURIComponent(location.hash.slice(1)) || null;
+  load();
+}
+
+init();
@@ -0,0 +1,37 @@
+//! Embedded single-page web UI for `peas serve`, served at `/ui`.
+//!
+//! The page, script and stylesheet are compiled into the binary and talk to
+//! the regular GraphQL endpoint, so the UI can list, filter and inspect peas
+//! and change their status without anything else installed.
+
+const INDEX_HTML: &str = include_str!("web/index.html");
+const APP_JS: &str = include_str!("web/app.js");
+const STYLE_CSS: &str = include_str!("web/style.css");
+
+/// Content type and body of the embedded file at `path` below `/ui`
+pub fn asset(path: &str) -> Option<(&'static str, &'static str)> {
+    match path.trim_start_matches('/') {
+        "" | "index.html" => Some(("text/html; charset=utf-8", INDEX_HTML)),
+        "app.js" => Some(("text/javascript; charset=utf-8", APP_JS)),
+        "style.css" => Some(("text/css; charset=utf-8", STYLE_CSS)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assets_are_embedded() {
+        let (content_type, html) = asset("").unwrap();
+        assert!(content_type.starts_with("text/html"));
+        assert!(html.contains("/ui/app.js") && html.contains("/ui/style.css"));
+        assert_eq!(
+            asset("/app.js").unwrap().0,
+            "text/javascript; charset=utf-8"
+        );
+        assert!(asset("style.css").unwrap().1.contains(".board"));
+        assert!(asset("../Cargo.toml").is_none());
+    }
+}
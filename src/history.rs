@@ -0,0 +1,154 @@
+use crate::config::{DATA_DIR, PeasConfig};
+use crate::error::Result;
+use crate::git;
+use crate::model::{Pea, PeaStatus};
+use crate::storage::{PeaRepository, parse_markdown};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Whether a file under `.peas/` is an archived pea, or None if it is not a pea at all
+///
+/// `path` is relative to the repository root, as git reports it.
+pub fn pea_location(path: &str) -> Option<bool> {
+    if !path.ends_with(".md") {
+        return None;
+    }
+    let segments: Vec<&str> = path.split('/').collect();
+    let start = segments.iter().rposition(|s| *s == DATA_DIR)?;
+    match segments.get(start + 1..segments.len() - 1)? {
+        [] => Some(false),
+        ["archive"] => Some(true),
+        // Memories and anything else peas keeps in its directory
+        _ => None,
+    }
+}
+
+/// Every pea committed at `rev`, with whether it was archived
+pub fn peas_at(root: &Path, data_path: &Path, rev: &str) -> Result<Vec<(Pea, bool)>> {
+    let mut peas = Vec::new();
+    for file in git::list_files(root, rev, data_path)? {
+        let Some(archived) = pea_location(&file) else {
+            continue;
+        };
+        if let Some(pea) = read(root, rev, &file) {
+            peas.push((pea, archived));
+        }
+    }
+    Ok(peas)
+}
+
+fn read(root: &Path, rev: &str, file: &str) -> Option<Pea> {
+    let content = git::show_file(root, rev, file).ok()?;
+    match parse_markdown(&content) {
+        Ok(pea) => Some(pea),
+        Err(e) => {
+            tracing::warn!(file = %file, rev = %rev, error = %e, "Skipping unparseable pea");
+            None
+        }
+    }
+}
+
+/// A pea's status in a recorded state, with when the pea was last changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeaState {
+    pub status: PeaStatus,
+    pub updated: DateTime<Utc>,
+}
+
+impl From<&Pea> for PeaState {
+    fn from(pea: &Pea) -> Self {
+        Self {
+            status: pea.status,
+            updated: pea.updated,
+        }
+    }
+}
+
+/// The status of every pea, archived ones included, at one point in time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusState {
+    pub time: DateTime<Utc>,
+    /// By pea ID
+    pub peas: BTreeMap<String, PeaState>,
+}
+
+/// Pea statuses after every commit that touched `.peas/` since `since`, oldest first
+///
+/// The first state is the last commit before `since` (if any), the last one
+/// is the working tree at the current time, uncommitted changes included.
+pub fn status_history(
+    config: &PeasConfig,
+    root: &Path,
+    since: DateTime<Utc>,
+) -> Result<Vec<StatusState>> {
+    let data_path = config.data_path(root);
+    let mut history = Vec::new();
+
+    // Pea ID and state by file, so archiving (a move) does not lose the pea
+    let mut files: BTreeMap<String, (String, PeaState)> = BTreeMap::new();
+    let to_state = |time, files: &BTreeMap<String, (String, PeaState)>| StatusState {
+        time,
+        peas: files.values().cloned().collect(),
+    };
+
+    let mut previous = git::last_commit_before(root, since, &data_path)?;
+    if let Some((hash, time)) = &previous {
+        for file in git::list_files(root, hash, &data_path)? {
+            if pea_location(&file).is_some()
+                && let Some(pea) = read(root, hash, &file)
+            {
+                let state = PeaState::from(&pea);
+                files.insert(file, (pea.id, state));
+            }
+        }
+        history.push(to_state(*time, &files));
+    }
+
+    for (hash, time) in git::commits_since(root, since, &data_path)? {
+        let changed = match &previous {
+            Some((from, _)) => git::changed_files(root, from, &hash, &data_path)?,
+            None => git::list_files(root, &hash, &data_path)?,
+        };
+        for file in changed.into_iter().filter(|f| pea_location(f).is_some()) {
+            match read(root, &hash, &file) {
+                Some(pea) => {
+                    let state = PeaState::from(&pea);
+                    files.insert(file, (pea.id, state))
+                }
+                None => files.remove(&file),
+            };
+        }
+        history.push(to_state(time, &files));
+        previous = Some((hash, time));
+    }
+
+    history.push(current_state(&PeaRepository::new(config, root))?);
+    Ok(history)
+}
+
+/// Statuses in the working tree right now, archived peas included
+pub fn current_state(repo: &PeaRepository) -> Result<StatusState> {
+    let mut peas = repo.list()?;
+    peas.extend(repo.list_archived()?);
+    Ok(StatusState {
+        time: Utc::now(),
+        peas: peas
+            .iter()
+            .map(|p| (p.id.clone(), PeaState::from(p)))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pea_location() {
+        assert_eq!(pea_location(".peas/peas-abc12--login.md"), Some(false));
+        assert_eq!(pea_location("app/.peas/archive/peas-abc12.md"), Some(true));
+        assert_eq!(pea_location(".peas/memory/auth.md"), None);
+        assert_eq!(pea_location(".peas/config.toml"), None);
+    }
+}
@@ -0,0 +1,385 @@
+use crate::error::{PeasError, Result};
+use crate::model::Pea;
+#[cfg(feature = "wasm")]
+use crate::plugins::Plugins;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "wasm")]
+use std::cell::OnceCell;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Directory under `.peas/` holding hook executables
+pub const HOOKS_DIR: &str = "hooks";
+
+/// Points in a pea's lifecycle where a hook can run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    PreCreate,
+    PostCreate,
+    PreUpdate,
+    PostUpdate,
+    PreDelete,
+    PostDelete,
+}
+
+impl HookEvent {
+    /// File name of the hook executable in `.peas/hooks/`
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::PreCreate => "pre-create",
+            HookEvent::PostCreate => "post-create",
+            HookEvent::PreUpdate => "pre-update",
+            HookEvent::PostUpdate => "post-update",
+            HookEvent::PreDelete => "pre-delete",
+            HookEvent::PostDelete => "post-delete",
+        }
+    }
+
+    /// Whether the hook may replace the pea being written
+    fn can_change_pea(self) -> bool {
+        matches!(self, HookEvent::PreCreate | HookEvent::PreUpdate)
+    }
+}
+
+/// What a hook receives on stdin, and may print back to change the pea
+#[derive(Serialize)]
+struct Payload<'a> {
+    hook: &'static str,
+    pea: &'a Pea,
+    body: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Reply {
+    /// Rejects the operation, for plugins that cannot exit non-zero
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    pea: Option<Pea>,
+    /// Missing means the body is kept
+    body: Option<String>,
+}
+
+/// Executables in `.peas/hooks/` run around creating, updating and deleting peas
+///
+/// Each hook gets a JSON object with `hook`, `pea` and `body` on stdin and
+/// runs from the project root. A `pre-*` hook rejects the operation by
+/// exiting non-zero (its stderr becomes the error message); `pre-create` and
+/// `pre-update` may also print the object back with changes to alter what
+/// gets written. `post-*` hooks cannot undo anything, a failure is only logged.
+///
+/// WASM plugins in `.peas/plugins/` (see [`Plugins`](crate::plugins::Plugins))
+/// see the same events after the executable hook, in name order.
+pub struct Hooks {
+    dir: PathBuf,
+    #[cfg(feature = "wasm")]
+    data_path: PathBuf,
+    /// Compiled on first use
+    #[cfg(feature = "wasm")]
+    plugins: OnceCell<Option<Plugins>>,
+}
+
+impl Hooks {
+    pub fn new(data_path: &Path) -> Self {
+        Self {
+            dir: data_path.join(HOOKS_DIR),
+            #[cfg(feature = "wasm")]
+            data_path: data_path.to_path_buf(),
+            #[cfg(feature = "wasm")]
+            plugins: OnceCell::new(),
+        }
+    }
+
+    /// Whether a hook or plugin would run for `event`
+    pub fn has(&self, event: HookEvent) -> bool {
+        if self.find(event).is_some() {
+            return true;
+        }
+        // A plugin that fails to load still counts, so running it reports the error
+        #[cfg(feature = "wasm")]
+        if self.plugins().map_or(true, |plugins| {
+            plugins.is_some_and(|plugins| {
+                plugins
+                    .iter()
+                    .any(|plugin| plugin.handler(event.name()).is_some())
+            })
+        }) {
+            return true;
+        }
+        false
+    }
+
+    /// Run a `pre-*` hook and plugins, returning the pea as changed by them, if they did
+    ///
+    /// The ID and timestamps always stay those of `pea`.
+    pub fn run_pre(&self, event: HookEvent, pea: &Pea) -> Result<Option<Pea>> {
+        let mut changed = None;
+        if let Some(output) = self.run(event, pea)? {
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+                let message = if stderr.is_empty() {
+                    format!("exited with {}", output.status)
+                } else {
+                    stderr
+                };
+                return Err(hook_error(event, message));
+            }
+            // Only hooks that may change the pea reply on stdout; others may print anything
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            if event.can_change_pea() && !stdout.trim().is_empty() {
+                let reply = serde_json::from_str(&stdout)
+                    .map_err(|e| hook_error(event, format!("printed invalid JSON: {}", e)))?;
+                changed = apply_reply(event, pea, reply).map_err(|m| hook_error(event, m))?;
+            }
+        }
+
+        #[cfg(feature = "wasm")]
+        if let Some(plugins) = self.plugins()? {
+            for plugin in plugins.iter() {
+                let Some(handler) = plugin.handler(event.name()) else {
+                    continue;
+                };
+                let current = changed.as_ref().unwrap_or(pea);
+                let Some(reply) = plugins.call(plugin, &handler, &payload(event, current)?)? else {
+                    continue;
+                };
+                let in_plugin =
+                    |message| hook_error(event, format!("{}: {}", plugin.name, message));
+                let reply = serde_json::from_str(&reply)
+                    .map_err(|e| in_plugin(format!("invalid reply: {}", e)))?;
+                if let Some(pea) = apply_reply(event, current, reply).map_err(in_plugin)? {
+                    changed = Some(pea);
+                }
+            }
+        }
+        #[cfg(not(feature = "wasm"))]
+        if self.dir.with_file_name("plugins").is_dir() {
+            tracing::warn!("Ignoring .peas/plugins/, peas was built without the `wasm` feature");
+        }
+
+        Ok(changed.filter(|changed| changed != pea))
+    }
+
+    /// Run a `post-*` hook and plugins; the operation already happened, so failures are only logged
+    pub fn run_post(&self, event: HookEvent, pea: &Pea) {
+        match self.run(event, pea) {
+            Ok(Some(output)) if !output.status.success() => {
+                tracing::warn!(
+                    hook = event.name(),
+                    status = %output.status,
+                    stderr = %String::from_utf8_lossy(&output.stderr).trim(),
+                    "Hook failed"
+                );
+            }
+            Err(e) => tracing::warn!(hook = event.name(), error = %e, "Hook failed to run"),
+            _ => {}
+        }
+
+        #[cfg(feature = "wasm")]
+        match (self.plugins(), payload(event, pea)) {
+            (Ok(Some(plugins)), Ok(input)) => {
+                for plugin in plugins.iter() {
+                    let Some(handler) = plugin.handler(event.name()) else {
+                        continue;
+                    };
+                    if let Err(e) = plugins.call(plugin, &handler, &input) {
+                        tracing::warn!(hook = event.name(), error = %e, "Plugin failed");
+                    }
+                }
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                tracing::warn!(hook = event.name(), error = %e, "Plugins failed to run")
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "wasm")]
+    fn plugins(&self) -> Result<Option<&Plugins>> {
+        if let Some(plugins) = self.plugins.get() {
+            return Ok(plugins.as_ref());
+        }
+        let plugins = Plugins::load(&self.data_path)?;
+        Ok(self.plugins.get_or_init(|| plugins).as_ref())
+    }
+
+    fn find(&self, event: HookEvent) -> Option<PathBuf> {
+        let path = self.dir.join(event.name());
+        let metadata = path.metadata().ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if metadata.permissions().mode() & 0o111 == 0 {
+                tracing::warn!(path = %path.display(), "Ignoring hook that is not executable");
+                return None;
+            }
+        }
+        Some(path)
+    }
+
+    fn run(&self, event: HookEvent, pea: &Pea) -> Result<Option<std::process::Output>> {
+        let Some(path) = self.find(event) else {
+            return Ok(None);
+        };
+        tracing::debug!(hook = event.name(), id = %pea.id, "Running hook");
+
+        let payload = payload(event, pea)?;
+        // Hooks live in `.peas/hooks/`, the project root is two levels up
+        let project_root = self.dir.parent().and_then(Path::parent);
+        let mut command = Command::new(&path);
+        command
+            .env("PEAS_HOOK", event.name())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(root) = project_root {
+            command.current_dir(root);
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| hook_error(event, format!("could not be started: {}", e)))?;
+
+        // Written from another thread while the output is drained, so a hook
+        // that prints a lot before reading its input cannot deadlock
+        let stdin = child.stdin.take();
+        let writer = std::thread::spawn(move || match stdin {
+            // A hook that does not read its input closes the pipe early
+            Some(mut stdin) => match stdin.write_all(payload.as_bytes()) {
+                Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => Err(e),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        });
+        let output = child.wait_with_output()?;
+        writer
+            .join()
+            .map_err(|_| hook_error(event, "input could not be written".to_string()))??;
+        Ok(Some(output))
+    }
+}
+
+fn payload(event: HookEvent, pea: &Pea) -> Result<String> {
+    Ok(serde_json::to_string(&Payload {
+        hook: event.name(),
+        pea,
+        body: &pea.body,
+    })?)
+}
+
+/// The pea as changed by a reply, or the rejection message
+fn apply_reply(
+    event: HookEvent,
+    pea: &Pea,
+    reply: Reply,
+) -> std::result::Result<Option<Pea>, String> {
+    if let Some(error) = reply.error {
+        return Err(error);
+    }
+    let Some(mut changed) = reply.pea.filter(|_| event.can_change_pea()) else {
+        return Ok(None);
+    };
+    if changed.id != pea.id {
+        return Err("must not change the pea ID".to_string());
+    }
+    changed.body = reply.body.map_or_else(|| pea.body.clone(), Into::into);
+    changed.created = pea.created;
+    changed.updated = pea.updated;
+    Ok(Some(changed))
+}
+
+fn hook_error(event: HookEvent, message: String) -> PeasError {
+    PeasError::Hook {
+        hook: event.name(),
+        message,
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn install(data_path: &Path, event: HookEvent, script: &str) {
+        let dir = data_path.join(HOOKS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(event.name());
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_pre_hooks_veto_and_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_path = temp_dir.path().join(".peas");
+        let hooks = Hooks::new(&data_path);
+        let pea = Pea::new(
+            "peas-hook1".to_string(),
+            "Hooked".to_string(),
+            PeaType::Task,
+        )
+        .with_body("Original".to_string());
+
+        // No hook installed
+        assert!(!hooks.has(HookEvent::PreCreate));
+        assert_eq!(hooks.run_pre(HookEvent::PreCreate, &pea).unwrap(), None);
+
+        install(
+            &data_path,
+            HookEvent::PreDelete,
+            "echo 'tickets are forever' >&2; exit 1",
+        );
+        let err = hooks.run_pre(HookEvent::PreDelete, &pea).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Hook pre-delete rejected the operation: tickets are forever"
+        );
+
+        install(
+            &data_path,
+            HookEvent::PreDelete,
+            "echo 'deleting peas-hook1'",
+        );
+        assert_eq!(hooks.run_pre(HookEvent::PreDelete, &pea).unwrap(), None);
+
+        // Echoing the input back with a change, body omitted
+        install(
+            &data_path,
+            HookEvent::PreCreate,
+            r#"sed -e 's/"title":"Hooked"/"title":"Renamed by hook"/' -e 's/,"body":"Original"//'"#,
+        );
+        let changed = hooks.run_pre(HookEvent::PreCreate, &pea).unwrap().unwrap();
+        assert_eq!(changed.title, "Renamed by hook");
+        assert_eq!(changed.body, "Original");
+        assert_eq!(changed.created, pea.created);
+
+        install(
+            &data_path,
+            HookEvent::PreUpdate,
+            r#"sed 's/peas-hook1/peas-other/'"#,
+        );
+        assert!(hooks.run_pre(HookEvent::PreUpdate, &pea).is_err());
+    }
+
+    #[test]
+    fn test_hook_output_before_reading_input_does_not_block() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_path = temp_dir.path().join(".peas");
+        let hooks = Hooks::new(&data_path);
+        // Both the input and the output are larger than a pipe buffer
+        let pea = Pea::new("peas-hook2".to_string(), "Big".to_string(), PeaType::Task)
+            .with_body("x".repeat(256 * 1024));
+        install(
+            &data_path,
+            HookEvent::PreUpdate,
+            "head -c 262144 /dev/zero >&2; cat >/dev/null; exit 1",
+        );
+        let err = hooks.run_pre(HookEvent::PreUpdate, &pea).unwrap_err();
+        assert!(err.to_string().starts_with("Hook pre-update rejected"));
+    }
+}
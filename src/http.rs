@@ -0,0 +1,13 @@
+//! HTTP client setup shared by the forge, sync and embedding clients.
+
+use std::time::Duration;
+
+/// A blocking client identifying itself as peas, giving up after `timeout`
+pub fn http_client(timeout: Duration) -> reqwest::Result<reqwest::blocking::Client> {
+    // reqwest is built without a default TLS crypto provider
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .user_agent(format!("peas/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+}
@@ -53,7 +53,7 @@ pub fn parse_beans_file(content: &str, filename: &str) -> Result<Pea> {
 
     // Beans uses YAML frontmatter with --- delimiters
     if !content.starts_with("---") {
-        return Err(PeasError::Parse(
+        return Err(PeasError::parse(
             "Beans file must start with YAML frontmatter (---)".to_string(),
         ));
     }
@@ -62,7 +62,7 @@ pub fn parse_beans_file(content: &str, filename: &str) -> Result<Pea> {
     let rest = &content[3..];
     let end_idx = rest
         .find("\n---")
-        .ok_or_else(|| PeasError::Parse("Missing closing frontmatter delimiter".to_string()))?;
+        .ok_or_else(|| PeasError::parse("Missing closing frontmatter delimiter".to_string()))?;
 
     let frontmatter_str = &rest[..end_idx].trim();
     let body = rest[end_idx + 4..].trim();
@@ -78,7 +78,7 @@ pub fn parse_beans_file(content: &str, filename: &str) -> Result<Pea> {
         .join("\n");
 
     let fm: BeansFrontmatter =
-        serde_yaml::from_str(&yaml_content).map_err(|e| PeasError::Parse(e.to_string()))?;
+        serde_yaml::from_str(&yaml_content).map_err(|e| PeasError::parse(e.to_string()))?;
 
     let pea_type = fm.pea_type.parse::<PeaType>().unwrap_or_default();
     let status = fm.status.parse::<PeaStatus>().unwrap_or_default();
@@ -126,7 +126,7 @@ fn extract_beans_id(frontmatter: &str, filename: &str) -> Result<String> {
         return Ok(stem[..idx].to_string());
     }
 
-    Err(PeasError::Parse(format!(
+    Err(PeasError::parse(format!(
         "Could not extract ID from file: {}",
         filename
     )))
@@ -186,7 +186,7 @@ pub fn export_to_beans(pea: &Pea) -> Result<String> {
         blocking: pea.blocking.clone(),
     };
 
-    let yaml = serde_yaml::to_string(&fm).map_err(|e| PeasError::Parse(e.to_string()))?;
+    let yaml = serde_yaml::to_string(&fm).map_err(|e| PeasError::parse(e.to_string()))?;
 
     let mut output = String::new();
     output.push_str("---\n");
@@ -205,12 +205,7 @@ pub fn export_to_beans(pea: &Pea) -> Result<String> {
 
 /// Generate beans-style filename
 pub fn beans_filename(pea: &Pea) -> String {
-    let slug = slug::slugify(&pea.title);
-    let slug = if slug.len() > 50 {
-        slug[..50].to_string()
-    } else {
-        slug
-    };
+    let slug = crate::model::title_slug(&pea.title).unwrap_or_default();
     format!("{}--{}.md", pea.id, slug)
 }
 
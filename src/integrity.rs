@@ -0,0 +1,295 @@
+//! Keeping references between peas intact when IDs change or disappear
+//!
+//! Peas point at each other through `parent`, `blocking` and mentions in
+//! their bodies. [`rename_references`] rewrites those after a rename, and
+//! [`broken_references`] finds the ones left pointing at a pea that is gone,
+//! with a fix where one is clear. `peas mv`, `peas migrate-prefix`, the git
+//! merge driver and `peas doctor` all go through here.
+
+use crate::aliases::Aliases;
+use crate::model::Pea;
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::sync::LazyLock;
+
+/// Word-like tokens that could be a pea ID, as in body cross-references
+static ID_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\w-]+").expect("ID token pattern should be valid"));
+
+/// The pea with renamed IDs in its ID, parent, blocking list and body
+///
+/// ```
+/// use peas::integrity::rename_references;
+/// use peas::model::{Pea, PeaType};
+/// use std::collections::BTreeMap;
+///
+/// let pea = Pea::new("peas-a1".into(), "Login".into(), PeaType::Task)
+///     .with_parent(Some("peas-old01".into()))
+///     .with_body("Split from peas-old01.".into());
+/// let renames = BTreeMap::from([("peas-old01".to_string(), "peas-new01".to_string())]);
+///
+/// let pea = rename_references(&pea, &renames);
+/// assert_eq!(pea.parent.as_deref(), Some("peas-new01"));
+/// assert_eq!(pea.body.to_string(), "Split from peas-new01.");
+/// ```
+pub fn rename_references(pea: &Pea, renames: &BTreeMap<String, String>) -> Pea {
+    let rename = |id: &String| renames.get(id).cloned().unwrap_or_else(|| id.clone());
+    let mut pea = pea.clone();
+    pea.id = rename(&pea.id);
+    pea.parent = pea.parent.as_ref().map(rename);
+    pea.blocking = pea.blocking.iter().map(rename).collect();
+    pea.body = ID_TOKEN
+        .replace_all(&pea.body, |token: &regex::Captures| {
+            rename(&token[0].to_string())
+        })
+        .into_owned()
+        .into();
+    pea
+}
+
+/// The pea with references to renamed IDs pointing at their current ones
+///
+/// Its own ID stays as it is.
+pub fn follow_renames(pea: &Pea, aliases: &Aliases) -> Pea {
+    let renames: BTreeMap<String, String> = pea
+        .parent
+        .iter()
+        .chain(&pea.blocking)
+        .map(String::as_str)
+        .chain(ID_TOKEN.find_iter(&pea.body).map(|t| t.as_str()))
+        .filter(|id| *id != pea.id)
+        .filter_map(|id| Some((id.to_string(), aliases.resolve(id)?.to_string())))
+        .collect();
+    rename_references(pea, &renames)
+}
+
+/// How one pea points at another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    Parent,
+    Blocking,
+    Mention,
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ReferenceKind::Parent => "parent",
+            ReferenceKind::Blocking => "blocking",
+            ReferenceKind::Mention => "mention",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A reference to a pea that no longer exists under that ID
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenReference {
+    /// The pea holding the reference
+    pub from: String,
+    pub kind: ReferenceKind,
+    /// The ID it points at
+    pub target: String,
+    /// The current ID, when the target was renamed
+    pub renamed_to: Option<String>,
+}
+
+impl BrokenReference {
+    /// Whether [`fix_references`] repairs it: renamed targets are followed and
+    /// blocking references to missing peas dropped. Parents and mentions of
+    /// missing peas need a person to decide.
+    pub fn is_fixable(&self) -> bool {
+        self.renamed_to.is_some() || self.kind == ReferenceKind::Blocking
+    }
+}
+
+impl fmt::Display for BrokenReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.renamed_to, self.kind) {
+            (Some(new), _) => write!(
+                f,
+                "{} {} {} was renamed to {}",
+                self.from, self.kind, self.target, new
+            ),
+            (None, ReferenceKind::Parent) => {
+                write!(f, "{} references missing parent {}", self.from, self.target)
+            }
+            (None, ReferenceKind::Blocking) => {
+                write!(f, "{} blocks missing ticket {}", self.from, self.target)
+            }
+            (None, ReferenceKind::Mention) => {
+                write!(f, "{} mentions missing ticket {}", self.from, self.target)
+            }
+        }
+    }
+}
+
+/// What a pea ID looks like in a project: its prefix and suffix length
+///
+/// Used to tell mentions of deleted peas from ordinary words in a body.
+#[derive(Debug, Clone)]
+pub struct IdShape {
+    pub prefix: String,
+    pub length: usize,
+}
+
+impl IdShape {
+    fn matches(&self, token: &str) -> bool {
+        token.strip_prefix(&self.prefix).is_some_and(|suffix| {
+            suffix.len() == self.length && suffix.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+    }
+}
+
+/// References in `peas` to peas that are neither among them nor archived
+///
+/// Parent and blocking references to archived peas count as broken, as they
+/// can't be worked on; body mentions of them are history and stay. Mentions
+/// are only recognised for renamed IDs and, with a `shape`, tokens that look
+/// like IDs of the project.
+///
+/// ```
+/// use peas::aliases::Aliases;
+/// use peas::integrity::{ReferenceKind, broken_references};
+/// use peas::model::{Pea, PeaType};
+/// use std::collections::HashSet;
+///
+/// let peas = vec![
+///     Pea::new("peas-a1".into(), "Login".into(), PeaType::Task)
+///         .with_blocking(vec!["peas-gone1".into()]),
+/// ];
+///
+/// let broken = broken_references(&peas, &HashSet::new(), &Aliases::default(), None);
+/// assert_eq!(broken[0].kind, ReferenceKind::Blocking);
+/// assert!(broken[0].is_fixable());
+/// ```
+pub fn broken_references(
+    peas: &[Pea],
+    archived: &HashSet<String>,
+    aliases: &Aliases,
+    shape: Option<&IdShape>,
+) -> Vec<BrokenReference> {
+    let active: HashSet<&str> = peas.iter().map(|p| p.id.as_str()).collect();
+    let renamed = |target: &str| {
+        aliases
+            .resolve(target)
+            .filter(|new| active.contains(new))
+            .map(String::from)
+    };
+
+    let mut broken = Vec::new();
+    for pea in peas {
+        let mut report = |kind, target: &str| {
+            broken.push(BrokenReference {
+                from: pea.id.clone(),
+                kind,
+                target: target.to_string(),
+                renamed_to: renamed(target),
+            })
+        };
+        if let Some(parent) = &pea.parent
+            && !active.contains(parent.as_str())
+        {
+            report(ReferenceKind::Parent, parent);
+        }
+        for blocked in &pea.blocking {
+            if !active.contains(blocked.as_str()) {
+                report(ReferenceKind::Blocking, blocked);
+            }
+        }
+        let mut seen = HashSet::new();
+        for token in ID_TOKEN.find_iter(&pea.body).map(|t| t.as_str()) {
+            if token == pea.id
+                || active.contains(token)
+                || archived.contains(token)
+                || !seen.insert(token)
+            {
+                continue;
+            }
+            if renamed(token).is_some() || shape.is_some_and(|s| s.matches(token)) {
+                report(ReferenceKind::Mention, token);
+            }
+        }
+    }
+    broken
+}
+
+/// The pea with the fixable references among `broken` repaired
+///
+/// Returns `None` when none of them belong to this pea.
+pub fn fix_references(pea: &Pea, broken: &[BrokenReference]) -> Option<Pea> {
+    let own: Vec<&BrokenReference> = broken
+        .iter()
+        .filter(|b| b.from == pea.id && b.is_fixable())
+        .collect();
+    if own.is_empty() {
+        return None;
+    }
+
+    let renames: BTreeMap<String, String> = own
+        .iter()
+        .filter_map(|b| Some((b.target.clone(), b.renamed_to.clone()?)))
+        .collect();
+    let mut fixed = rename_references(pea, &renames);
+    fixed.id = pea.id.clone();
+    fixed.blocking.retain(|blocked| {
+        !own.iter().any(|b| {
+            b.kind == ReferenceKind::Blocking && b.renamed_to.is_none() && &b.target == blocked
+        })
+    });
+    Some(fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+    use tempfile::TempDir;
+
+    fn pea(id: &str) -> Pea {
+        Pea::new(id.to_string(), id.to_string(), PeaType::Task)
+    }
+
+    #[test]
+    fn test_broken_references_and_fixes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut aliases = Aliases::load(temp_dir.path()).unwrap();
+        aliases.add("peas-old01", "peas-new01");
+        let archived = HashSet::from(["peas-arch1".to_string()]);
+        let shape = IdShape {
+            prefix: "peas-".into(),
+            length: 5,
+        };
+        let peas = vec![
+            pea("peas-new01"),
+            pea("peas-kid01")
+                .with_parent(Some("peas-old01".into()))
+                .with_blocking(vec!["peas-gone1".into(), "peas-new01".into()])
+                .with_body(
+                    "From peas-old01, after peas-arch1 and peas-gone2; see peas-docs.".into(),
+                ),
+            pea("peas-kid02").with_parent(Some("peas-gone3".into())),
+        ];
+
+        let broken = broken_references(&peas, &archived, &aliases, Some(&shape));
+        let found: Vec<String> = broken.iter().map(|b| b.to_string()).collect();
+        assert_eq!(
+            found,
+            [
+                "peas-kid01 parent peas-old01 was renamed to peas-new01",
+                "peas-kid01 blocks missing ticket peas-gone1",
+                "peas-kid01 mention peas-old01 was renamed to peas-new01",
+                "peas-kid01 mentions missing ticket peas-gone2",
+                "peas-kid02 references missing parent peas-gone3",
+            ]
+        );
+
+        let fixed = fix_references(&peas[1], &broken).unwrap();
+        assert_eq!(fixed.id, "peas-kid01");
+        assert_eq!(fixed.parent.as_deref(), Some("peas-new01"));
+        assert_eq!(fixed.blocking, ["peas-new01"]);
+        assert!(fixed.body.to_string().starts_with("From peas-new01,"));
+        assert!(fix_references(&peas[2], &broken).is_none());
+    }
+}
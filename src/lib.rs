@@ -31,15 +31,45 @@
 //!
 //! ## Modules
 //!
+//! - [`aliases`]: Former pea IDs that still resolve after a rename
+//! - [`api`]: High-level client for embedding peas in other tools
+//! - [`claim`]: Soft locks that keep parallel workers off the same pea
 //! - [`cli`]: Command-line interface definitions
 //! - [`config`]: Configuration loading and management
 //! - [`error`]: Error types and result aliases
+//! - [`focus`]: The ticket currently in focus for this checkout
+//! - [`flow`]: Lead and cycle time metrics
+//! - [`forge`]: Pull request state from GitHub and GitLab
+//! - [`git`]: Git integration via the `git` binary
 //! - [`graphql`]: GraphQL schema and resolvers
+//! - [`history`]: Past pea states read from git history
+//! - [`hooks`]: Executables in `.peas/hooks/` run around create, update and delete
+//! - [`integrity`]: References between peas kept intact as IDs change
+//! - [`lint`]: Policy checks for `peas lint`
+//! - [`local`]: Per-user state kept out of git
 //! - [`model`]: Data models (Pea, PeaType, PeaStatus, etc.)
+//! - [`plugins`]: WASM plugins for validation rules and automations (`wasm` feature)
+//! - [`read_markers`]: Which tickets changed since the user last looked
+//! - [`removal`]: Deleting and archiving with children and references taken care of
+//! - [`rules`]: Rhai automation rules from `[rules.<name>]`
+//! - [`semantic`]: Embedding-based similarity search (`semantic` feature)
+//! - [`snapshot`]: Recorded project states and time-travel reads
 //! - [`storage`]: File-based storage and markdown parsing
+//! - [`testing`]: Fixtures for tests (temp projects, golden files)
+//! - [`transaction`]: Multi-pea writes that succeed or fail together
 //! - [`tui`]: Terminal user interface
 //! - [`validation`]: Input validation utilities
 
+/// Pea ID aliases.
+///
+/// Maps IDs changed by `peas mv` to the current ones, so old references keep working.
+pub mod aliases;
+
+/// High-level library API.
+///
+/// `Client` opens a project and exposes ticket CRUD, search and stats.
+pub mod api;
+
 /// Command-line interface definitions using clap.
 pub mod cli;
 
@@ -68,6 +98,16 @@ pub mod model;
 /// Handles reading/writing peas as markdown files with TOML frontmatter.
 pub mod storage;
 
+/// Test fixtures.
+///
+/// Temp projects, reproducible peas and golden-file assertions for tests.
+pub mod testing;
+
+/// All-or-nothing writes.
+///
+/// Journals writes to several peas so they can be committed as one undo step or rolled back.
+pub mod transaction;
+
 /// Terminal user interface.
 ///
 /// Interactive TUI built with ratatui for managing peas.
@@ -88,21 +128,124 @@ pub mod import_export;
 /// Handles adding, listing, and removing asset files associated with peas.
 pub mod assets;
 
+/// Pattern-based auto-tagging.
+///
+/// Adds tags and components from `[autotag.<name>]` sections to peas whose title or body matches.
+pub mod autotag;
+
+/// Soft locks on peas.
+///
+/// Claims record who works on a pea until when, so agents sharing a backlog do not collide.
+pub mod claim;
+
+/// Personal focus tracking.
+///
+/// Remembers the single ticket the user is working on, in local state.
+pub mod focus;
+
+/// Flow metrics.
+///
+/// Lead and cycle times of completed peas with percentile distributions, for `peas stats`.
+pub mod flow;
+
+/// GitHub and GitLab pull request lookups.
+///
+/// Resolves the merge state of `pr` links for `peas pr-status`.
+pub mod forge;
+
+/// Git integration via the `git` command line.
+///
+/// Resolves tags and commits for history-based commands like `release-notes`.
+pub mod git;
+
 /// Global user-level configuration.
 ///
 /// Manages settings stored outside the project (e.g. update check preferences).
 pub mod global_config;
 
+/// Pea history from git.
+///
+/// Reads peas as committed at earlier revisions for time-travel queries and trend reports.
+pub mod history;
+
+/// Scriptable hooks.
+///
+/// Runs executables in `.peas/hooks/` that can reject or change writes.
+pub mod hooks;
+
+/// Referential integrity.
+///
+/// Rewrites references after ID changes and finds the ones left pointing at missing peas.
+pub mod integrity;
+
+/// Repository linting.
+///
+/// Configurable policy checks on titles, bodies, parents, staleness and tags.
+pub mod lint;
+
+/// HTTP client setup.
+///
+/// Builds the blocking reqwest client used to reach forges, sync remotes and embedding APIs.
+pub mod http;
+
 /// Logging initialization and configuration.
 ///
-/// Sets up tracing-subscriber with optional file output and TUI-safe modes.
+/// Sets up tracing-subscriber with `-v/-vv` levels, text or JSON output,
+/// optional rotating log files and TUI-safe modes.
 pub mod logging;
 
+/// Per-user local state.
+///
+/// Stores focus, saved filters, read markers and drafts in a gitignored `.peas/local/`.
+pub mod local;
+
+/// WASM plugins.
+///
+/// Compiles modules from `.peas/plugins/` with wasmtime and runs them sandboxed on hook events.
+#[cfg(feature = "wasm")]
+pub mod plugins;
+
+/// Read/unread tracking.
+///
+/// Flags peas that are new or changed since the user last viewed them.
+pub mod read_markers;
+
+/// Pea removal.
+///
+/// Deletes or archives peas, handling their children and the references to them.
+pub mod removal;
+
+/// Automation rules.
+///
+/// Runs Rhai scripts from `[rules.<name>]` sections when peas are created or updated.
+pub mod rules;
+
 /// Search query parsing and execution.
 ///
 /// Supports field-specific and regex search across pea fields.
 pub mod search;
 
+/// Semantic search.
+///
+/// Embeds peas locally or through an embeddings API and ranks them by similarity, with vectors in `.peas/.index/`.
+#[cfg(feature = "semantic")]
+pub mod semantic;
+
+/// Snapshots and time travel.
+///
+/// Records the active peas under a label and reads past states from snapshots or git history.
+pub mod snapshot;
+
+/// Sync with another peas instance.
+///
+/// Pushes and pulls peas over the GraphQL API, keeping the newer version on conflict.
+pub mod sync;
+
+/// Terminal output.
+///
+/// `--color` handling and paging of long output through `$PAGER`.
+pub mod terminal;
+
 /// Undo functionality for reverting operations.
 ///
 /// Tracks the last mutation and allows undoing it.
@@ -0,0 +1,217 @@
+use crate::config::PeasConfig;
+use crate::model::{Pea, PeaStatus, PeaType};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How serious a lint finding is, from least to most
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The check is disabled
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Off => write!(f, "off"),
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A lint check with its default severity
+pub struct Check {
+    pub name: &'static str,
+    pub severity: Severity,
+    pub description: &'static str,
+}
+
+/// Every check `peas lint` runs, severities can be changed in `[lint.checks]`
+pub const CHECKS: &[Check] = &[
+    Check {
+        name: "title-length",
+        severity: Severity::Warning,
+        description: "Title shorter than lint.title_min or longer than lint.title_max characters",
+    },
+    Check {
+        name: "missing-body",
+        severity: Severity::Warning,
+        description: "Open epic, feature or story without a description",
+    },
+    Check {
+        name: "orphan-task",
+        severity: Severity::Info,
+        description: "Open task without a parent",
+    },
+    Check {
+        name: "stale-in-progress",
+        severity: Severity::Warning,
+        description: "In progress without an update for lint.stale_days days",
+    },
+    Check {
+        name: "unknown-tag",
+        severity: Severity::Warning,
+        description: "Tag without a [tags.<name>] section (only when tags are configured)",
+    },
+];
+
+/// One problem found in one pea
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub check: &'static str,
+    pub severity: Severity,
+    pub id: String,
+    pub message: String,
+}
+
+/// Run every enabled check against `peas`, most severe findings first
+pub fn lint(peas: &[Pea], config: &PeasConfig, now: DateTime<Utc>) -> Vec<Finding> {
+    let settings = &config.lint;
+    let mut findings = Vec::new();
+    for pea in peas {
+        let mut report = |check: &'static str, message: String| {
+            let severity = settings.severity(check);
+            if severity != Severity::Off {
+                findings.push(Finding {
+                    check,
+                    severity,
+                    id: pea.id.clone(),
+                    message,
+                });
+            }
+        };
+
+        let length = pea.title.chars().count();
+        if length < settings.title_min {
+            report(
+                "title-length",
+                format!(
+                    "Title has {} characters, fewer than {}",
+                    length, settings.title_min
+                ),
+            );
+        } else if length > settings.title_max {
+            report(
+                "title-length",
+                format!(
+                    "Title has {} characters, more than {}",
+                    length, settings.title_max
+                ),
+            );
+        }
+
+        if pea.is_open() {
+            if matches!(
+                pea.pea_type,
+                PeaType::Epic | PeaType::Feature | PeaType::Story
+            ) && pea.body.trim().is_empty()
+            {
+                report(
+                    "missing-body",
+                    format!("{} has no description", pea.pea_type),
+                );
+            }
+            if pea.pea_type == PeaType::Task && pea.parent.is_none() {
+                report("orphan-task", "Task has no parent".to_string());
+            }
+        }
+
+        if pea.status == PeaStatus::InProgress {
+            let idle = (now - pea.updated).num_days();
+            if idle >= i64::from(settings.stale_days) {
+                report(
+                    "stale-in-progress",
+                    format!("In progress without an update for {} days", idle),
+                );
+            }
+        }
+
+        if !config.tags.is_empty() {
+            for tag in pea.tags.iter().filter(|t| !config.tags.contains_key(*t)) {
+                report(
+                    "unknown-tag",
+                    format!("Tag '{}' has no [tags.{}] section", tag, tag),
+                );
+            }
+        }
+    }
+
+    findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_lint_checks_and_severity_overrides() {
+        let now = Utc::now();
+        let mut config = PeasConfig::default();
+        config.tags.insert("ui".to_string(), Default::default());
+
+        let short = Pea::new("peas-aaaa1".to_string(), "Fix".to_string(), PeaType::Bug);
+        let feature = Pea::new(
+            "peas-bbbb1".to_string(),
+            "Dark mode for the editor".to_string(),
+            PeaType::Feature,
+        )
+        .with_tags(vec!["ui".to_string(), "uii".to_string()]);
+        let mut stale = Pea::new(
+            "peas-cccc1".to_string(),
+            "Migrate the database".to_string(),
+            PeaType::Task,
+        )
+        .with_parent(Some("peas-bbbb1".to_string()))
+        .with_status(PeaStatus::InProgress);
+        stale.updated = now - Duration::days(30);
+        let orphan = Pea::new(
+            "peas-dddd1".to_string(),
+            "Write the release notes".to_string(),
+            PeaType::Task,
+        );
+        let peas = vec![short, feature, stale, orphan];
+
+        let summary = |findings: &[Finding]| -> Vec<(&str, Severity, String)> {
+            findings
+                .iter()
+                .map(|f| (f.check, f.severity, f.id.clone()))
+                .collect()
+        };
+        assert_eq!(
+            summary(&lint(&peas, &config, now)),
+            vec![
+                ("title-length", Severity::Warning, "peas-aaaa1".to_string()),
+                ("missing-body", Severity::Warning, "peas-bbbb1".to_string()),
+                ("unknown-tag", Severity::Warning, "peas-bbbb1".to_string()),
+                (
+                    "stale-in-progress",
+                    Severity::Warning,
+                    "peas-cccc1".to_string()
+                ),
+                ("orphan-task", Severity::Info, "peas-dddd1".to_string()),
+            ]
+        );
+
+        config
+            .lint
+            .checks
+            .insert("unknown-tag".to_string(), Severity::Error);
+        config
+            .lint
+            .checks
+            .insert("orphan-task".to_string(), Severity::Off);
+        config.lint.stale_days = 60;
+        let findings = lint(&peas, &config, now);
+        assert_eq!(findings[0].check, "unknown-tag");
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings.len(), 3);
+    }
+}
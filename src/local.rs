@@ -0,0 +1,83 @@
+use crate::error::Result;
+use std::path::{Path, PathBuf};
+
+/// Directory under `.peas/` for per-user state
+pub const LOCAL_DIR: &str = "local";
+
+/// Create `dir` with a `.gitignore` keeping all of it out of version control
+pub fn ignore_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(&gitignore, "*\n")?;
+    }
+    Ok(())
+}
+
+/// Per-user state in `.peas/local/`: focus, saved TUI filter, read markers and draft bodies
+///
+/// The directory carries its own `.gitignore`, so nothing written here shows
+/// up in `git status` of a shared tracker.
+pub struct LocalState {
+    dir: PathBuf,
+}
+
+impl LocalState {
+    pub fn new(data_path: &Path) -> Self {
+        Self {
+            dir: data_path.join(LOCAL_DIR),
+        }
+    }
+
+    /// Path of an entry, relative names may contain subdirectories (e.g. `drafts/<id>.md`)
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Read an entry, `None` if it was never written
+    pub fn read(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(name)).ok()
+    }
+
+    /// Write an entry, creating the local directory and its `.gitignore` on first use
+    pub fn write(&self, name: &str, content: &str) -> Result<()> {
+        let path = self.path(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        ignore_dir(&self.dir)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Remove an entry if it exists
+    pub fn remove(&self, name: &str) -> Result<()> {
+        let path = self.path(name);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_write_creates_gitignored_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let local = LocalState::new(temp_dir.path());
+        assert_eq!(local.read("focus"), None);
+
+        local.write("drafts/peas-abc12.md", "draft").unwrap();
+        assert_eq!(local.read("drafts/peas-abc12.md").as_deref(), Some("draft"));
+        let gitignore = temp_dir.path().join(LOCAL_DIR).join(".gitignore");
+        assert_eq!(std::fs::read_to_string(gitignore).unwrap(), "*\n");
+
+        local.remove("drafts/peas-abc12.md").unwrap();
+        local.remove("drafts/peas-abc12.md").unwrap();
+        assert_eq!(local.read("drafts/peas-abc12.md"), None);
+    }
+}
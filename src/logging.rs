@@ -1,63 +1,166 @@
-use std::path::PathBuf;
-use tracing_subscriber::{EnvFilter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry,
+    fmt::{self, format::FmtSpan},
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+};
+
+/// Directory under `.peas/` holding the rotating log files
+pub const LOGS_DIR: &str = "logs";
+
+/// Default number of rotated log files kept in `.peas/logs/`
+pub const DEFAULT_MAX_LOG_FILES: usize = 7;
+
+/// Format of log lines written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LogFormat {
+    /// Compact human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, for machine consumption
+    Json,
+}
+
+/// Logging options collected from CLI flags and project config
+#[derive(Debug, Clone)]
+pub struct LogOptions {
+    /// Number of `-v` flags: 0 = info, 1 = debug, 2+ = trace
+    pub verbosity: u8,
+    /// Format of stderr output
+    pub format: LogFormat,
+    /// Explicit log file (`--log-file`), rotated daily
+    pub log_file: Option<PathBuf>,
+    /// Directory for the rotating project log (`.peas/logs/` when `[log] file = true`)
+    pub log_dir: Option<PathBuf>,
+    /// Number of rotated files kept in `log_dir`
+    pub max_files: usize,
+    /// Disable stderr logging (useful for TUI mode)
+    pub quiet: bool,
+    /// Color stderr output
+    pub ansi: bool,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            verbosity: 0,
+            format: LogFormat::default(),
+            log_file: None,
+            log_dir: None,
+            max_files: DEFAULT_MAX_LOG_FILES,
+            quiet: false,
+            ansi: true,
+        }
+    }
+}
+
+/// Log level for the given number of `-v` flags
+pub fn level_for_verbosity(verbosity: u8) -> &'static str {
+    match verbosity {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    }
+}
+
+/// Whether `path` lies inside a project log directory
+///
+/// The TUI watches `.peas/` recursively and must not refresh on its own log writes.
+pub fn is_log_path(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == LOGS_DIR)
+}
+
+/// Daily-rotated appender writing `<prefix>.<date>.log` files into `dir`
+fn rotating_appender(
+    dir: &Path,
+    prefix: &str,
+    max_files: Option<usize>,
+) -> Option<RollingFileAppender> {
+    std::fs::create_dir_all(dir).ok()?;
+    let mut builder = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(prefix);
+    if let Some(max_files) = max_files {
+        builder = builder
+            .filename_suffix("log")
+            .max_log_files(max_files.max(1));
+    }
+    builder.build(dir).ok()
+}
 
 /// Initialize the logging system
 ///
-/// # Arguments
-/// * `verbose` - Enable verbose (DEBUG) logging
-/// * `log_file` - Optional path to log file. If None, logs only to stderr
-/// * `quiet` - If true, disable stderr logging (useful for TUI mode)
-pub fn init(verbose: bool, log_file: Option<PathBuf>, quiet: bool) {
-    // Determine log level from verbose flag or RUST_LOG env var
-    let default_level = if verbose { "debug" } else { "info" };
+/// Stderr output follows `options.format`; file output is always JSON so
+/// agent-driven runs can be analysed after the fact.
+pub fn init(options: LogOptions) {
+    // RUST_LOG wins over -v flags
+    let level = level_for_verbosity(options.verbosity);
     let env_filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| EnvFilter::new(format!("peas={}", default_level)));
+        .unwrap_or_else(|_| EnvFilter::new(format!("peas={}", level)));
 
-    // Build stderr layer if not quiet
-    let stderr_layer = if !quiet {
-        Some(
-            fmt::layer()
-                .with_writer(std::io::stderr)
-                .with_target(false) // Don't show module path
-                .compact(), // Use compact format
-        )
+    // Show span timings on close at the most verbose level
+    let span_events = if options.verbosity >= 2 {
+        FmtSpan::CLOSE
     } else {
-        None
+        FmtSpan::NONE
     };
 
-    // Build file layer if path provided
-    let file_layer = log_file.map(|log_path| {
-        // Create log directory if it doesn't exist
-        if let Some(parent) = log_path.parent() {
-            let _ = std::fs::create_dir_all(parent);
-        }
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> = Vec::new();
+
+    // Build stderr layer if not quiet
+    if !options.quiet {
+        let layer = fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_ansi(options.ansi)
+            .with_target(false) // Don't show module path
+            .with_span_events(span_events.clone());
+        layers.push(match options.format {
+            LogFormat::Text => layer.compact().boxed(),
+            LogFormat::Json => layer.json().boxed(),
+        });
+    }
 
-        // Set up file appender with rotation
-        let file_appender = tracing_appender::rolling::daily(
-            log_path
-                .parent()
-                .unwrap_or_else(|| std::path::Path::new(".")),
-            log_path
+    // An explicit --log-file takes precedence over the project log directory
+    let appender = match (&options.log_file, &options.log_dir) {
+        (Some(log_path), _) => rotating_appender(
+            log_path.parent().unwrap_or_else(|| Path::new(".")),
+            &log_path
                 .file_name()
-                .unwrap_or_else(|| std::ffi::OsStr::new("peas.log")),
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "peas.log".to_string()),
+            None,
+        ),
+        (None, Some(dir)) => {
+            // Logs are local debugging aids; keep them out of version control
+            let _ = crate::local::ignore_dir(dir);
+            rotating_appender(dir, "peas", Some(options.max_files))
+        }
+        (None, None) => None,
+    };
+    if let Some(appender) = appender {
+        layers.push(
+            fmt::layer()
+                .with_writer(appender)
+                .with_ansi(false) // No colors in file
+                .with_span_events(span_events)
+                .json() // Use JSON format for structured logs
+                .boxed(),
         );
-
-        fmt::layer()
-            .with_writer(file_appender)
-            .with_ansi(false) // No colors in file
-            .json() // Use JSON format for structured logs
-    });
+    }
 
     // Initialize the subscriber with the layers
     tracing_subscriber::registry()
+        .with(layers)
         .with(env_filter)
-        .with(stderr_layer)
-        .with(file_layer)
         .init();
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use std::sync::Once;
     use tempfile::TempDir;
 
@@ -100,4 +203,30 @@ mod tests {
         init_test_logging();
         // Logging already initialized, this is a smoke test
     }
+
+    #[test]
+    fn test_level_for_verbosity() {
+        assert_eq!(level_for_verbosity(0), "info");
+        assert_eq!(level_for_verbosity(1), "debug");
+        assert_eq!(level_for_verbosity(2), "trace");
+        assert_eq!(level_for_verbosity(5), "trace");
+    }
+
+    #[test]
+    fn test_is_log_path() {
+        assert!(is_log_path(Path::new(
+            "/project/.peas/logs/peas.2026-01-01.log"
+        )));
+        assert!(!is_log_path(Path::new(
+            "/project/.peas/peas-abc12--logs.md"
+        )));
+    }
+
+    #[test]
+    fn test_rotating_appender_creates_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(".peas").join(LOGS_DIR);
+        assert!(rotating_appender(&dir, "peas", Some(3)).is_some());
+        assert!(dir.is_dir());
+    }
 }
@@ -3,18 +3,25 @@ use clap::Parser;
 use peas::{
     cli::{Cli, Commands, handlers::CommandContext},
     config::PeasConfig,
+    error::exit_code_for,
     global_config::GlobalPeasConfig,
+    logging::{LOGS_DIR, LogOptions},
+    terminal::Pager,
     updater::{UpdateCheckOutcome, spawn_update_check},
 };
 use std::path::PathBuf;
 
-fn main() -> Result<()> {
+fn main() {
     // Windows has a 1MB default stack which is too small for clap's generated
     // parser with many subcommands. Run on a thread with a larger stack.
     const STACK_SIZE: usize = 8 * 1024 * 1024; // 8 MB
     let builder = std::thread::Builder::new().stack_size(STACK_SIZE);
     let handler = builder.spawn(run).expect("failed to spawn main thread");
-    handler.join().expect("main thread panicked")
+    if let Err(err) = handler.join().expect("main thread panicked") {
+        // Same output as returning the error from main, but with a categorized exit code
+        eprintln!("Error: {:?}", err);
+        std::process::exit(exit_code_for(&err));
+    }
 }
 
 fn run() -> Result<()> {
@@ -75,12 +82,19 @@ fn run() -> Result<()> {
     };
 
     // Determine if we're in TUI mode (to disable stderr logging)
-    let is_tui_mode = matches!(command, Commands::Tui);
+    let is_tui_mode = matches!(command, Commands::Tui { .. });
+
+    cli.color.apply();
 
-    // Initialize logging system
     // In TUI mode, disable stderr logging to prevent interference with terminal rendering
-    let log_file = cli.log_file.as_ref().map(PathBuf::from);
-    peas::logging::init(cli.verbose, log_file, is_tui_mode);
+    let mut log_options = LogOptions {
+        verbosity: cli.verbose,
+        format: cli.log_format,
+        log_file: cli.log_file.as_ref().map(PathBuf::from),
+        quiet: is_tui_mode,
+        ansi: cli.color.for_stderr(),
+        ..LogOptions::default()
+    };
 
     let config_opt = cli.config;
 
@@ -92,182 +106,34 @@ fn run() -> Result<()> {
         );
     }
 
-    match command {
-        Commands::Init { prefix, id_length } => peas::cli::handlers::handle_init(prefix, id_length),
-        Commands::Migrate { dry_run } => peas::cli::handlers::handle_migrate(dry_run),
-        Commands::Doctor { fix } => peas::cli::handlers::handle_doctor(fix),
-        _ => {
-            // All other commands require loading config
-            let (config, root) = load_config(config_opt)?;
-            let ctx = CommandContext::new(config, root);
+    if !command.needs_project() {
+        peas::logging::init(log_options);
+        return peas::cli::handlers::run_standalone(command);
+    }
 
-            match command {
-                Commands::Init { .. } | Commands::Migrate { .. } | Commands::Doctor { .. } => {
-                    unreachable!()
-                }
-                Commands::Create {
-                    title,
-                    r#type,
-                    status,
-                    priority,
-                    body,
-                    body_file,
-                    parent,
-                    blocks,
-                    blocked_by,
-                    external_ref,
-                    tag,
-                    template,
-                    json,
-                    dry_run,
-                } => peas::cli::handlers::handle_create(
-                    &ctx,
-                    title,
-                    r#type,
-                    status,
-                    priority,
-                    body,
-                    body_file,
-                    parent,
-                    blocks,
-                    blocked_by,
-                    external_ref,
-                    tag,
-                    template,
-                    json,
-                    dry_run,
-                ),
-                Commands::Show { id, json } => peas::cli::handlers::handle_show(&ctx, id, json),
-                Commands::List {
-                    r#type,
-                    status,
-                    priority,
-                    parent,
-                    tag,
-                    archived,
-                    json,
-                } => peas::cli::handlers::handle_list(
-                    &ctx,
-                    peas::cli::handlers::ListParams {
-                        r#type,
-                        status,
-                        priority,
-                        parent,
-                        tag,
-                        archived,
-                        json,
-                    },
-                ),
-                Commands::Update {
-                    id,
-                    title,
-                    r#type,
-                    status,
-                    priority,
-                    body,
-                    parent,
-                    add_tag,
-                    remove_tag,
-                    add_blocks,
-                    remove_blocks,
-                    add_blocked_by,
-                    remove_blocked_by,
-                    add_ref,
-                    remove_ref,
-                    json,
-                    dry_run,
-                } => peas::cli::handlers::handle_update(
-                    &ctx,
-                    id,
-                    title,
-                    r#type,
-                    status,
-                    priority,
-                    body,
-                    parent,
-                    add_tag,
-                    remove_tag,
-                    add_blocks,
-                    remove_blocks,
-                    add_blocked_by,
-                    remove_blocked_by,
-                    add_ref,
-                    remove_ref,
-                    json,
-                    dry_run,
-                ),
-                Commands::Archive {
-                    id,
-                    status,
-                    r#type,
-                    priority,
-                    tag,
-                    older_than,
-                    recursive,
-                    keep_assets,
-                    confirm,
-                    dry_run,
-                    json,
-                } => peas::cli::handlers::handle_archive(
-                    &ctx,
-                    peas::cli::handlers::ArchiveParams {
-                        id,
-                        status,
-                        r#type,
-                        priority,
-                        tag,
-                        older_than,
-                        recursive,
-                        keep_assets,
-                        confirm,
-                        dry_run,
-                        json,
-                    },
-                ),
-                Commands::Delete {
-                    id,
-                    force,
-                    keep_assets,
-                    json,
-                } => peas::cli::handlers::handle_delete(&ctx, id, force, keep_assets, json),
-                Commands::Search { query, json } => {
-                    peas::cli::handlers::handle_search(&ctx, query, json)
-                }
-                Commands::Start { id, json } => peas::cli::handlers::handle_start(&ctx, id, json),
-                Commands::Done { id, json } => peas::cli::handlers::handle_done(&ctx, id, json),
-                Commands::Prime => peas::cli::handlers::handle_prime(&ctx),
-                Commands::Context => peas::cli::handlers::handle_context(&ctx),
-                Commands::Suggest { json, limit } => {
-                    peas::cli::handlers::handle_suggest(&ctx, json, limit)
-                }
-                Commands::Roadmap => peas::cli::handlers::handle_roadmap(&ctx),
-                Commands::Query { query, variables } => {
-                    peas::cli::handlers::handle_query(ctx, query, variables)
-                }
-                Commands::Mutate {
-                    mutation,
-                    variables,
-                } => peas::cli::handlers::handle_mutate(ctx, mutation, variables),
-                Commands::Serve { port } => peas::cli::handlers::handle_serve(ctx, port),
-                Commands::Tui => peas::cli::handlers::handle_tui(ctx),
-                Commands::ImportBeans { path, dry_run } => {
-                    peas::cli::handlers::handle_import_beans(&ctx, path, dry_run)
-                }
-                Commands::ExportBeans { output } => {
-                    peas::cli::handlers::handle_export_beans(&ctx, output)
-                }
-                Commands::Bulk { action } => peas::cli::handlers::handle_bulk(&ctx, action),
-                Commands::Memory { action } => peas::cli::handlers::handle_memory(&ctx, action),
-                Commands::Asset { action } => peas::cli::handlers::handle_asset(&ctx, action),
-                Commands::Undo { json } => peas::cli::handlers::handle_undo(&ctx, json),
-                Commands::Mv {
-                    old_id,
-                    new_id,
-                    force,
-                } => peas::cli::handlers::handle_mv(&ctx, old_id, new_id, force),
-            }
-        }
+    // All other commands require loading config
+    let (mut config, root) = load_config(config_opt)?;
+    config.actor = cli
+        .actor
+        .map(|actor| actor.trim().to_string())
+        .filter(|actor| !actor.is_empty());
+
+    // The project config may ask for a rotating log under .peas/logs/
+    if config.log.file {
+        log_options.log_dir = Some(config.data_path(&root).join(LOGS_DIR));
+        log_options.max_files = config.log.max_files;
     }
+    peas::logging::init(log_options);
+    tracing::debug!(root = %root.display(), actor = config.actor.as_deref(), "Loaded project config");
+
+    let ctx = CommandContext::new(config, root);
+
+    // Held until the command is done, then waits for the pager to quit
+    let _pager = (command.pages_output() && !cli.no_pager)
+        .then(Pager::start)
+        .flatten();
+
+    peas::cli::handlers::dispatch(ctx, command)
 }
 
 fn load_config(config_path: Option<String>) -> Result<(PeasConfig, PathBuf)> {
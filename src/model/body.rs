@@ -0,0 +1,113 @@
+use crate::error::{PeasError, Result};
+use std::fmt;
+use std::ops::Deref;
+
+/// Markdown body of a pea, which may not have been read yet
+///
+/// Peas from the summary index (see `PeaRepository::list_summaries`) come
+/// without their body, so listing never reads multi-kilobyte bodies;
+/// `PeaRepository::load_body` reads it when needed, and updates load it on
+/// their own. An unloaded body reads as empty and cannot be changed.
+///
+/// ```
+/// use peas::model::Body;
+///
+/// let mut body = Body::from("Steps");
+/// body.to_mut()?.push_str(" to reproduce");
+/// assert_eq!(body, "Steps to reproduce");
+///
+/// let mut unloaded = Body::NotLoaded;
+/// assert!(!unloaded.is_loaded());
+/// assert!(unloaded.is_empty());
+/// assert!(unloaded.to_mut().is_err());
+/// # Ok::<(), peas::error::PeasError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    Loaded(String),
+    NotLoaded,
+}
+
+impl Body {
+    pub fn is_loaded(&self) -> bool {
+        matches!(self, Body::Loaded(_))
+    }
+
+    /// The text to change in place
+    ///
+    /// Fails if the body was not loaded, as changing it would lose the text on disk.
+    pub fn to_mut(&mut self) -> Result<&mut String> {
+        match self {
+            Body::Loaded(text) => Ok(text),
+            Body::NotLoaded => Err(PeasError::Storage(
+                "The body must be loaded before changing it".to_string(),
+            )),
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        match self {
+            Body::Loaded(text) => text,
+            Body::NotLoaded => String::new(),
+        }
+    }
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body::Loaded(String::new())
+    }
+}
+
+impl Deref for Body {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Body::Loaded(text) => text,
+            Body::NotLoaded => "",
+        }
+    }
+}
+
+impl From<String> for Body {
+    fn from(text: String) -> Self {
+        Body::Loaded(text)
+    }
+}
+
+impl From<&str> for Body {
+    fn from(text: &str) -> Self {
+        Body::Loaded(text.to_string())
+    }
+}
+
+impl PartialEq<str> for Body {
+    fn eq(&self, other: &str) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<&str> for Body {
+    fn eq(&self, other: &&str) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<String> for Body {
+    fn eq(&self, other: &String) -> bool {
+        **self == *other
+    }
+}
+
+impl PartialEq<Body> for String {
+    fn eq(&self, other: &Body) -> bool {
+        **other == *self
+    }
+}
+
+impl fmt::Display for Body {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self)
+    }
+}
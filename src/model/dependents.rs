@@ -0,0 +1,146 @@
+use super::{Pea, References};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Peas that point at a pea and would dangle without it
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Dependents {
+    /// Peas whose parent it is
+    pub children: Vec<String>,
+    /// Peas listing it in `blocking`
+    pub blocked_by: Vec<String>,
+    /// Peas whose body mentions it
+    pub mentioned_by: Vec<String>,
+}
+
+impl Dependents {
+    /// The peas in `peas` that depend on the pea with this ID
+    ///
+    /// ```
+    /// use peas::model::{Dependents, Pea, PeaType};
+    ///
+    /// let peas = vec![
+    ///     Pea::new("peas-epic1".into(), "Auth".into(), PeaType::Epic),
+    ///     Pea::new("peas-a1".into(), "Login".into(), PeaType::Task)
+    ///         .with_parent(Some("peas-epic1".into())),
+    /// ];
+    ///
+    /// let dependents = Dependents::of("peas-epic1", &peas);
+    /// assert_eq!(dependents.children, ["peas-a1"]);
+    /// assert!(Dependents::of("peas-a1", &peas).is_empty());
+    /// ```
+    pub fn of(id: &str, peas: &[Pea]) -> Self {
+        let ids = |keep: &dyn Fn(&Pea) -> bool| -> Vec<String> {
+            peas.iter()
+                .filter(|p| p.id != id && keep(p))
+                .map(|p| p.id.clone())
+                .collect()
+        };
+        Self {
+            children: ids(&|p| p.parent.as_deref() == Some(id)),
+            blocked_by: ids(&|p| p.blocking.iter().any(|b| b == id)),
+            mentioned_by: References::build(peas)
+                .referenced_by(id)
+                .iter()
+                .filter(|other| *other != id)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.blocked_by.is_empty() && self.mentioned_by.is_empty()
+    }
+
+    /// A short summary such as "2 children, 1 mention"
+    pub fn summary(&self) -> String {
+        let count = |n: usize, one: &str, many: &str| match n {
+            0 => None,
+            1 => Some(format!("1 {}", one)),
+            n => Some(format!("{} {}", n, many)),
+        };
+        [
+            count(self.children.len(), "child", "children"),
+            count(
+                self.blocked_by.len(),
+                "blocking reference",
+                "blocking references",
+            ),
+            count(self.mentioned_by.len(), "mention", "mentions"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+
+    /// Token confirming a destructive change to `pea` with these dependents
+    ///
+    /// It changes whenever the pea or its dependents do, so a confirmation
+    /// only holds for the state it was given for.
+    pub fn confirmation_token(&self, pea: &Pea) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(pea.id.as_bytes());
+        hasher.update(pea.updated.to_rfc3339().as_bytes());
+        for id in self
+            .children
+            .iter()
+            .chain(&self.blocked_by)
+            .chain(&self.mentioned_by)
+        {
+            hasher.update(b"\0");
+            hasher.update(id.as_bytes());
+        }
+        format!("{:x}", hasher.finalize())[..16].to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn pea(id: &str) -> Pea {
+        Pea::new(id.to_string(), id.to_string(), PeaType::Task)
+    }
+
+    #[test]
+    fn test_dependents_of_a_pea() {
+        let peas = vec![
+            pea("peas-epic1"),
+            pea("peas-kid01").with_parent(Some("peas-epic1".into())),
+            pea("peas-blk01").with_blocking(vec!["peas-epic1".into()]),
+            pea("peas-doc01").with_body("See peas-epic1.".into()),
+        ];
+
+        let dependents = Dependents::of("peas-epic1", &peas);
+        assert_eq!(dependents.children, ["peas-kid01"]);
+        assert_eq!(dependents.blocked_by, ["peas-blk01"]);
+        assert_eq!(dependents.mentioned_by, ["peas-doc01"]);
+        assert_eq!(
+            dependents.summary(),
+            "1 child, 1 blocking reference, 1 mention"
+        );
+    }
+
+    #[test]
+    fn test_confirmation_token_follows_changes() {
+        let epic = pea("peas-epic1");
+        let mut peas = vec![
+            epic.clone(),
+            pea("peas-kid01").with_parent(Some("peas-epic1".into())),
+        ];
+        let token = Dependents::of(&epic.id, &peas).confirmation_token(&epic);
+        assert_eq!(token.len(), 16);
+        assert_eq!(
+            Dependents::of(&epic.id, &peas).confirmation_token(&epic),
+            token
+        );
+
+        peas.push(pea("peas-kid02").with_parent(Some("peas-epic1".into())));
+        assert_ne!(
+            Dependents::of(&epic.id, &peas).confirmation_token(&epic),
+            token
+        );
+    }
+}
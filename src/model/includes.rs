@@ -0,0 +1,120 @@
+use super::{Memory, Pea};
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+/// `{{include: <id>}}` or `{{include: memory:<key>}}`
+static INCLUDE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*include:\s*(memory:)?([\w./-]+)\s*\}\}")
+        .expect("include pattern should be valid")
+});
+
+/// How deep included bodies may include further bodies
+pub const MAX_INCLUDE_DEPTH: usize = 4;
+
+/// What an include directive points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncludeTarget<'a> {
+    /// A pea ID, ID prefix or slug
+    Pea(&'a str),
+    /// A memory key
+    Memory(&'a str),
+}
+
+/// Expand `{{include: <id>}}` directives in a body for display.
+///
+/// `{{include: peas-abc12}}` is replaced with that pea's body and
+/// `{{include: memory:<key>}}` with a memory's content, so shared blocks such
+/// as acceptance criteria live in one place. Included text is expanded in turn,
+/// up to [`MAX_INCLUDE_DEPTH`] levels. A directive that cannot be resolved, or
+/// that would include itself, is left as written.
+///
+/// ```
+/// use peas::model::{IncludeTarget, expand_includes};
+///
+/// let body = "Build the form.\n\n{{include: peas-crit1}}";
+/// let expanded = expand_includes(body, |target| match target {
+///     IncludeTarget::Pea("peas-crit1") => Some("- Works offline".to_string()),
+///     _ => None,
+/// });
+/// assert_eq!(expanded, "Build the form.\n\n- Works offline");
+/// ```
+pub fn expand_includes<F>(body: &str, resolve: F) -> String
+where
+    F: Fn(IncludeTarget) -> Option<String>,
+{
+    expand(body, &resolve, &mut Vec::new())
+}
+
+fn expand<F>(body: &str, resolve: &F, stack: &mut Vec<String>) -> String
+where
+    F: Fn(IncludeTarget) -> Option<String>,
+{
+    INCLUDE
+        .replace_all(body, |caps: &Captures| {
+            let directive = caps[0].to_string();
+            let reference = format!("{}{}", caps.get(1).map_or("", |m| m.as_str()), &caps[2]);
+            if stack.len() >= MAX_INCLUDE_DEPTH || stack.contains(&reference) {
+                return directive;
+            }
+            let target = match caps.get(1) {
+                Some(_) => IncludeTarget::Memory(&caps[2]),
+                None => IncludeTarget::Pea(&caps[2]),
+            };
+            let Some(included) = resolve(target) else {
+                return directive;
+            };
+            stack.push(reference);
+            let expanded = expand(included.trim_end(), resolve, stack);
+            stack.pop();
+            expanded
+        })
+        .into_owned()
+}
+
+/// Resolve include targets against peas and memories already loaded,
+/// matching peas by ID or slug
+pub fn resolve_include(peas: &[Pea], memories: &[Memory], target: IncludeTarget) -> Option<String> {
+    match target {
+        IncludeTarget::Pea(id) => peas
+            .iter()
+            .find(|p| p.id == id || p.slug.as_deref() == Some(id))
+            .map(|p| p.body.to_string()),
+        IncludeTarget::Memory(key) => memories
+            .iter()
+            .find(|m| m.key == key)
+            .map(|m| m.content.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn pea(id: &str, body: &str) -> Pea {
+        Pea::new(id.to_string(), format!("{} title", id), PeaType::Task).with_body(body.to_string())
+    }
+
+    #[test]
+    fn test_nested_memory_and_cyclic_includes() {
+        let peas = vec![
+            pea("peas-aaaa1", "A\n{{include: peas-bbbb1}}\n"),
+            pea("peas-bbbb1", "B {{ include: memory:criteria }}"),
+            pea(
+                "peas-cccc1",
+                "{{include: peas-cccc1-title}} {{include: peas-zzzz9}}",
+            ),
+        ];
+        let memories = vec![Memory::new("criteria".into()).with_content("C\n".into())];
+        let expand =
+            |body: &str| expand_includes(body, |target| resolve_include(&peas, &memories, target));
+
+        assert_eq!(expand("{{include: peas-aaaa1}}"), "A\nB C");
+        // Matched by slug, including itself only once; unknown targets stay
+        assert_eq!(
+            expand("{{include: peas-cccc1-title}}"),
+            "{{include: peas-cccc1-title}} {{include: peas-zzzz9}}"
+        );
+        assert_eq!(expand("no directives"), "no directives");
+    }
+}
@@ -3,15 +3,45 @@
 //! This module defines the core data structures:
 //!
 //! - [`Pea`]: The main issue/task entity
+//! - [`Body`]: A pea's markdown body, loaded on demand
 //! - [`PeaType`]: Issue types (milestone, epic, feature, bug, task)
 //! - [`PeaStatus`]: Workflow states (draft, todo, in-progress, completed, scrapped)
 //! - [`PeaPriority`]: Priority levels (critical, high, normal, low, deferred)
+//! - [`SizeScale`]: Estimation scales (fibonacci, t-shirt) and point roll-ups
+//! - [`PeaLink`]: Labelled links to pull requests, issues and docs
+//! - [`Claim`]: Soft lock on a pea while someone works on it
+//! - [`Pea::add_note`]: Timestamped notes in a "Work log" section of the body
+//! - [`expand_includes`]: `{{include: <id>}}` directives that transclude other bodies
+//! - [`References`]: Pea IDs mentioned in bodies, and the backlinks they create
+//! - [`Dependents`]: Children and references that would dangle without a pea
+//! - [`related`]: Other peas scored by tags, titles, parents and references
+//! - [`SortKey`]: Multi-key sort orders such as `priority,-updated`
+//! - [`parse_timestamp`]: Lenient reading of hand-edited timestamps
 //! - [`Memory`]: Project knowledge and context storage
 
+mod body;
+mod dependents;
+mod includes;
 mod memory;
 mod pea;
+mod references;
+mod related;
+mod size;
+mod sort;
+pub(crate) mod text;
+pub(crate) mod timestamp;
 mod types;
+mod worklog;
 
+pub use body::Body;
+pub use dependents::Dependents;
+pub use includes::{IncludeTarget, MAX_INCLUDE_DEPTH, expand_includes, resolve_include};
 pub use memory::Memory;
-pub use pea::Pea;
-pub use types::{PeaPriority, PeaStatus, PeaType};
+pub use pea::{Claim, Pea, PeaLink, descendants, title_slug};
+pub use references::References;
+pub use related::{RelatedFactor, RelatedPea, related};
+pub use size::{SizeScale, SizeTotals};
+pub use sort::{SortField, SortKey, sort_peas};
+pub use timestamp::parse_timestamp;
+pub use types::{LinkKind, PeaPriority, PeaStatus, PeaType};
+pub use worklog::WORK_LOG_HEADING;
@@ -1,5 +1,6 @@
-use super::types::{PeaPriority, PeaStatus, PeaType};
-use chrono::{DateTime, Utc};
+use super::body::Body;
+use super::types::{LinkKind, PeaPriority, PeaStatus, PeaType};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 /// A pea (issue/ticket) with metadata and optional body text.
@@ -24,6 +25,10 @@ pub struct Pea {
     pub id: String,
     pub title: String,
 
+    /// Title slug, kept in sync with the title and accepted wherever an ID is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slug: Option<String>,
+
     #[serde(rename = "type")]
     pub pea_type: PeaType,
 
@@ -33,9 +38,21 @@ pub struct Pea {
     #[serde(default)]
     pub priority: PeaPriority,
 
+    /// Estimate on the configured size scale (story points or t-shirt size)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<String>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
 
+    /// Area of the project, one of the `[components.<name>]` in the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component: Option<String>,
+
+    /// Person responsible for the pea
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent: Option<String>,
 
@@ -45,17 +62,108 @@ pub struct Pea {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub external_refs: Vec<String>,
 
+    /// Labelled links to pull requests, issues and documents
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<PeaLink>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub assets: Vec<String>,
 
-    #[serde(default)]
+    /// Date a milestone is due
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_date: Option<NaiveDate>,
+
+    /// Date before which the pea stays out of default lists and suggestions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defer_until: Option<NaiveDate>,
+
+    /// Release whose CHANGELOG entry mentions this pea
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub released_in: Option<String>,
+
+    /// Soft lock held by whoever is working on the pea
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claim: Option<Claim>,
+
+    /// Times the pea went from completed or scrapped back to an open status
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub reopened_count: u32,
+
+    #[serde(default, deserialize_with = "super::timestamp::deserialize")]
     pub created: DateTime<Utc>,
 
-    #[serde(default)]
+    #[serde(default, deserialize_with = "super::timestamp::deserialize")]
     pub updated: DateTime<Utc>,
 
+    /// Person or agent that created the pea, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+
+    /// Person or agent behind the last change, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_by: Option<String>,
+
     #[serde(skip)]
-    pub body: String,
+    pub body: Body,
+}
+
+/// A labelled URL attached to a pea
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeaLink {
+    pub url: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    #[serde(default)]
+    pub kind: LinkKind,
+}
+
+/// A soft lock on a pea, so parallel workers do not pick the same one
+///
+/// Nothing stops others from editing a claimed pea; `peas claim` refuses to
+/// take it over and `peas suggest` skips it until the claim expires.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claim {
+    /// Person or agent holding the claim
+    pub by: String,
+
+    /// When the claim lapses unless it is renewed
+    pub until: DateTime<Utc>,
+}
+
+impl Claim {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.until > now
+    }
+}
+
+impl std::fmt::Display for Claim {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} until {}",
+            self.by,
+            self.until.format("%Y-%m-%d %H:%M")
+        )
+    }
+}
+
+impl PeaLink {
+    /// Link with the kind inferred from the URL
+    pub fn new(url: String) -> Self {
+        let kind = LinkKind::infer(&url);
+        Self {
+            url,
+            label: None,
+            kind,
+        }
+    }
+
+    /// The label, or the URL for unlabelled links
+    pub fn title(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.url)
+    }
 }
 
 impl Pea {
@@ -63,18 +171,30 @@ impl Pea {
         let now = Utc::now();
         Self {
             id,
+            slug: title_slug(&title),
             title,
             pea_type,
             status: PeaStatus::default(),
             priority: PeaPriority::default(),
+            size: None,
             tags: Vec::new(),
+            component: None,
+            assignee: None,
             parent: None,
             blocking: Vec::new(),
             external_refs: Vec::new(),
+            links: Vec::new(),
             assets: Vec::new(),
+            target_date: None,
+            defer_until: None,
+            released_in: None,
+            claim: None,
+            reopened_count: 0,
             created: now,
             updated: now,
-            body: String::new(),
+            created_by: None,
+            updated_by: None,
+            body: Body::default(),
         }
     }
 
@@ -93,6 +213,16 @@ impl Pea {
         self
     }
 
+    pub fn with_component(mut self, component: Option<String>) -> Self {
+        self.component = component;
+        self
+    }
+
+    pub fn with_assignee(mut self, assignee: Option<String>) -> Self {
+        self.assignee = assignee;
+        self
+    }
+
     pub fn with_parent(mut self, parent: Option<String>) -> Self {
         self.parent = parent;
         self
@@ -108,11 +238,40 @@ impl Pea {
         self
     }
 
+    pub fn with_links(mut self, links: Vec<PeaLink>) -> Self {
+        self.links = links;
+        self
+    }
+
+    pub fn with_target_date(mut self, target_date: Option<NaiveDate>) -> Self {
+        self.target_date = target_date;
+        self
+    }
+
     pub fn with_body(mut self, body: String) -> Self {
-        self.body = body;
+        self.body = Body::Loaded(body);
         self
     }
 
+    /// Add a link, replacing the label and kind of an existing one with the same URL
+    pub fn set_link(&mut self, link: PeaLink) {
+        match self.links.iter_mut().find(|l| l.url == link.url) {
+            Some(existing) => *existing = link,
+            None => self.links.push(link),
+        }
+    }
+
+    /// The claim on the pea, unless it has expired
+    pub fn active_claim(&self, now: DateTime<Utc>) -> Option<&Claim> {
+        self.claim.as_ref().filter(|c| c.is_active(now))
+    }
+
+    /// Whether someone other than `actor` holds an active claim
+    pub fn is_claimed_by_other(&self, actor: Option<&str>, now: DateTime<Utc>) -> bool {
+        self.active_claim(now)
+            .is_some_and(|c| Some(c.by.as_str()) != actor)
+    }
+
     pub fn touch(&mut self) {
         self.updated = Utc::now();
     }
@@ -127,4 +286,58 @@ impl Pea {
     pub fn is_closed(&self) -> bool {
         matches!(self.status, PeaStatus::Completed | PeaStatus::Scrapped)
     }
+
+    /// Days from `today` until the target date; negative once it has passed
+    pub fn days_remaining(&self, today: NaiveDate) -> Option<i64> {
+        self.target_date.map(|d| (d - today).num_days())
+    }
+
+    /// Whether the pea is deferred past `today`; it shows up again on its
+    /// `defer_until` date
+    pub fn is_deferred(&self, today: NaiveDate) -> bool {
+        self.defer_until.is_some_and(|date| date > today)
+    }
+
+    /// Whether the pea is still open after its target date
+    pub fn is_overdue(&self, today: NaiveDate) -> bool {
+        self.is_open() && self.days_remaining(today).is_some_and(|days| days < 0)
+    }
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// All peas below `id` in the parent hierarchy
+pub fn descendants<'a>(peas: &'a [Pea], id: &str) -> Vec<&'a Pea> {
+    let mut result: Vec<&Pea> = Vec::new();
+    let mut queue = vec![id];
+    while let Some(current) = queue.pop() {
+        for pea in peas {
+            // Guard against parent cycles in hand-edited files
+            if pea.parent.as_deref() == Some(current) && !result.iter().any(|p| p.id == pea.id) {
+                queue.push(&pea.id);
+                result.push(pea);
+            }
+        }
+    }
+    result
+}
+
+/// Maximum length of a title slug
+const MAX_SLUG_LEN: usize = 50;
+
+/// `title` as a lowercase, dash-separated slug for file names and lookups,
+/// or `None` when nothing of it is left
+///
+/// ```
+/// use peas::model::title_slug;
+///
+/// assert_eq!(title_slug("Fix login bug!").as_deref(), Some("fix-login-bug"));
+/// assert_eq!(title_slug("???"), None);
+/// ```
+pub fn title_slug(title: &str) -> Option<String> {
+    let mut slug = slug::slugify(title);
+    slug.truncate(MAX_SLUG_LEN);
+    Some(slug).filter(|s| !s.is_empty())
 }
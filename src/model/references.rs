@@ -0,0 +1,99 @@
+use super::Pea;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+/// Word-like tokens that could be a pea ID (IDs are letters, digits, `-` and `_`)
+static ID_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\w-]+").expect("ID token pattern should be valid"));
+
+/// Cross-references between peas found in their bodies, in both directions.
+///
+/// Only IDs of the peas the index was built from count, so a mention of a
+/// deleted or out-of-scope pea is ignored.
+///
+/// ```
+/// use peas::model::{Pea, PeaType, References};
+///
+/// let api = Pea::new("peas-api01".into(), "API".into(), PeaType::Task);
+/// let docs = Pea::new("peas-doc01".into(), "Docs".into(), PeaType::Task)
+///     .with_body("Describe the endpoints from peas-api01.".into());
+///
+/// let refs = References::build([&api, &docs]);
+/// assert_eq!(refs.mentions("peas-doc01"), ["peas-api01"]);
+/// assert_eq!(refs.referenced_by("peas-api01"), ["peas-doc01"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct References {
+    outgoing: HashMap<String, Vec<String>>,
+    incoming: HashMap<String, Vec<String>>,
+}
+
+impl References {
+    pub fn build<'a>(peas: impl IntoIterator<Item = &'a Pea>) -> Self {
+        let peas: Vec<&Pea> = peas.into_iter().collect();
+        let known: HashSet<&str> = peas.iter().map(|p| p.id.as_str()).collect();
+
+        let mut refs = Self::default();
+        for pea in &peas {
+            let mut seen = HashSet::new();
+            for token in ID_TOKEN.find_iter(&pea.body) {
+                let target = token.as_str();
+                if target != pea.id && known.contains(target) && seen.insert(target) {
+                    refs.outgoing
+                        .entry(pea.id.clone())
+                        .or_default()
+                        .push(target.to_string());
+                    refs.incoming
+                        .entry(target.to_string())
+                        .or_default()
+                        .push(pea.id.clone());
+                }
+            }
+        }
+        refs
+    }
+
+    /// IDs mentioned in the body of `id`, in order of first mention
+    pub fn mentions(&self, id: &str) -> &[String] {
+        self.outgoing.get(id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// IDs of the peas whose body mentions `id` (backlinks)
+    pub fn referenced_by(&self, id: &str) -> &[String] {
+        self.incoming.get(id).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn pea(id: &str, body: &str) -> Pea {
+        Pea::new(id.to_string(), id.to_string(), PeaType::Task).with_body(body.to_string())
+    }
+
+    #[test]
+    fn test_only_known_ids_count_once() {
+        let a = pea(
+            "peas-aaaa1",
+            "Needs peas-bbbb1, see peas-bbbb1. Also peas-aaaa1 and peas-gone1",
+        );
+        let b = pea("peas-bbbb1", "Split from peas-aaaa1");
+        let c = pea(
+            "peas-cccc1",
+            "Like peas-bbbb1-ish but not quite; mentions peas-aaaa1",
+        );
+        let refs = References::build([&a, &b, &c]);
+
+        assert_eq!(refs.mentions("peas-aaaa1"), ["peas-bbbb1"]);
+        assert_eq!(refs.mentions("peas-cccc1"), ["peas-aaaa1"]);
+        assert_eq!(
+            refs.referenced_by("peas-aaaa1"),
+            ["peas-bbbb1", "peas-cccc1"]
+        );
+        assert_eq!(refs.referenced_by("peas-bbbb1"), ["peas-aaaa1"]);
+        assert!(refs.referenced_by("peas-cccc1").is_empty());
+    }
+}
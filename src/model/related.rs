@@ -0,0 +1,215 @@
+use super::text::{dice, title_words};
+use super::{Pea, References};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Points per tag two peas share
+const TAG_POINTS: u32 = 10;
+
+/// Most points for shared tags
+const MAX_TAG_POINTS: u32 = 30;
+
+/// Points for identical titles; similar ones get their share
+const TITLE_POINTS: u32 = 40;
+
+/// Title similarity below which titles count as unrelated
+const MIN_TITLE_SIMILARITY: f64 = 0.25;
+
+/// Points for siblings under the same parent
+const PARENT_POINTS: u32 = 15;
+
+/// Points when one pea mentions or blocks the other
+const REFERENCE_POINTS: u32 = 25;
+
+/// Points per pea that both peas mention or block
+const CO_REFERENCE_POINTS: u32 = 10;
+
+/// Most points for co-references
+const MAX_CO_REFERENCE_POINTS: u32 = 20;
+
+/// One reason two peas are related, with its points
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelatedFactor {
+    pub reason: String,
+    pub points: u32,
+}
+
+impl RelatedFactor {
+    fn new(reason: impl Into<String>, points: u32) -> Self {
+        Self {
+            reason: reason.into(),
+            points,
+        }
+    }
+}
+
+/// A pea related to another, with the factors of its score
+#[derive(Debug, Clone)]
+pub struct RelatedPea<'a> {
+    pub pea: &'a Pea,
+    pub factors: Vec<RelatedFactor>,
+}
+
+impl RelatedPea<'_> {
+    pub fn score(&self) -> u32 {
+        self.factors.iter().map(|f| f.points).sum()
+    }
+}
+
+/// Peas in `peas` related to `pea`, most related first
+///
+/// Scored by shared tags, title similarity, a shared parent, references
+/// between the two (body mentions and `blocking`) and peas both of them
+/// reference. Peas without any of these are left out.
+///
+/// ```
+/// use peas::model::{Pea, PeaType, related};
+///
+/// let login = Pea::new("peas-a1".into(), "Login form".into(), PeaType::Bug)
+///     .with_tags(vec!["auth".into()]);
+/// let peas = vec![
+///     login.clone(),
+///     Pea::new("peas-b2".into(), "Login error messages".into(), PeaType::Task)
+///         .with_tags(vec!["auth".into()]),
+///     Pea::new("peas-c3".into(), "Dark mode".into(), PeaType::Task),
+/// ];
+///
+/// let found = related(&login, &peas);
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].pea.id, "peas-b2");
+/// assert_eq!(found[0].factors[0].reason, "shares tag auth");
+/// ```
+pub fn related<'a>(pea: &Pea, peas: &'a [Pea]) -> Vec<RelatedPea<'a>> {
+    let references = References::build(peas);
+    let outgoing = |p: &Pea| -> HashSet<String> {
+        references
+            .mentions(&p.id)
+            .iter()
+            .chain(&p.blocking)
+            .cloned()
+            .collect()
+    };
+    let own_refs = outgoing(pea);
+    let own_tags: HashSet<String> = pea.tags.iter().map(|t| t.to_lowercase()).collect();
+    let own_words = title_words(&pea.title);
+
+    let mut found: Vec<RelatedPea> = peas
+        .iter()
+        .filter(|other| other.id != pea.id)
+        .map(|other| {
+            let mut factors = Vec::new();
+
+            let mut shared: Vec<&String> = other
+                .tags
+                .iter()
+                .filter(|t| own_tags.contains(&t.to_lowercase()))
+                .collect();
+            shared.dedup();
+            if !shared.is_empty() {
+                let names: Vec<&str> = shared.iter().map(|t| t.as_str()).collect();
+                let noun = if names.len() == 1 { "tag" } else { "tags" };
+                factors.push(RelatedFactor::new(
+                    format!("shares {} {}", noun, names.join(", ")),
+                    (shared.len() as u32 * TAG_POINTS).min(MAX_TAG_POINTS),
+                ));
+            }
+
+            let similarity = dice(&own_words, &title_words(&other.title));
+            if similarity >= MIN_TITLE_SIMILARITY {
+                factors.push(RelatedFactor::new(
+                    "similar title",
+                    (similarity * TITLE_POINTS as f64).round() as u32,
+                ));
+            }
+
+            if let Some(parent) = &pea.parent
+                && other.parent.as_ref() == Some(parent)
+            {
+                factors.push(RelatedFactor::new(
+                    format!("same parent {}", parent),
+                    PARENT_POINTS,
+                ));
+            }
+
+            let other_refs = outgoing(other);
+            if own_refs.contains(&other.id) {
+                factors.push(RelatedFactor::new(
+                    "mentioned by this pea",
+                    REFERENCE_POINTS,
+                ));
+            }
+            if other_refs.contains(&pea.id) {
+                factors.push(RelatedFactor::new("mentions this pea", REFERENCE_POINTS));
+            }
+            let mut common: Vec<&String> = own_refs.intersection(&other_refs).collect();
+            common.sort();
+            if !common.is_empty() {
+                let ids: Vec<&str> = common.iter().map(|id| id.as_str()).collect();
+                factors.push(RelatedFactor::new(
+                    format!("both reference {}", ids.join(", ")),
+                    (common.len() as u32 * CO_REFERENCE_POINTS).min(MAX_CO_REFERENCE_POINTS),
+                ));
+            }
+
+            RelatedPea {
+                pea: other,
+                factors,
+            }
+        })
+        .filter(|r| r.score() > 0)
+        .collect();
+
+    found.sort_by(|a, b| {
+        b.score()
+            .cmp(&a.score())
+            .then_with(|| b.pea.updated.cmp(&a.pea.updated))
+    });
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn pea(id: &str, title: &str) -> Pea {
+        Pea::new(id.to_string(), title.to_string(), PeaType::Task)
+    }
+
+    #[test]
+    fn test_related_scores_every_factor() {
+        let epic = pea("peas-epic1", "Auth epic");
+        let target = pea("peas-tgt01", "Rate limit login attempts")
+            .with_parent(Some("peas-epic1".to_string()))
+            .with_tags(vec!["auth".to_string(), "security".to_string()])
+            .with_body("Needs the lockout from peas-lock1.".to_string());
+        let peas = vec![
+            epic,
+            target.clone(),
+            pea("peas-lock1", "Account lockout"),
+            pea("peas-sib01", "Session handling").with_parent(Some("peas-epic1".to_string())),
+            pea("peas-tag01", "Audit log")
+                .with_tags(vec!["Auth".to_string(), "security".to_string()]),
+            pea("peas-ttl01", "Login attempts dashboard"),
+            pea("peas-co001", "Unlock emails")
+                .with_body("Sent when peas-lock1 expires.".to_string()),
+            pea("peas-back1", "Docs").with_body("Describe peas-tgt01.".to_string()),
+            pea("peas-none1", "Dark mode"),
+        ];
+
+        let found = related(&target, &peas);
+        let score = |id: &str| found.iter().find(|r| r.pea.id == id).map(|r| r.score());
+        assert_eq!(score("peas-tag01"), Some(20));
+        assert_eq!(score("peas-lock1"), Some(25));
+        assert_eq!(score("peas-back1"), Some(25));
+        assert_eq!(score("peas-sib01"), Some(15));
+        assert_eq!(score("peas-co001"), Some(10));
+        assert_eq!(score("peas-ttl01"), Some(23));
+        assert_eq!(score("peas-none1"), None);
+        assert_eq!(score("peas-epic1"), None);
+        assert!(found.windows(2).all(|w| w[0].score() >= w[1].score()));
+
+        let tagged = found.iter().find(|r| r.pea.id == "peas-tag01").unwrap();
+        assert_eq!(tagged.factors[0].reason, "shares tags Auth, security");
+    }
+}
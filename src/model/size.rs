@@ -0,0 +1,161 @@
+use super::{Pea, PeaStatus};
+use crate::error::{PeasError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Estimation scale for the `size` field, set with `peas.size_scale`.
+///
+/// ```
+/// use peas::model::SizeScale;
+///
+/// assert_eq!(SizeScale::Fibonacci.normalize("8").unwrap(), "8");
+/// assert!(SizeScale::Fibonacci.normalize("4").is_err());
+/// assert_eq!(SizeScale::Tshirt.normalize("xl").unwrap(), "XL");
+///
+/// // T-shirt sizes count as points in roll-ups
+/// assert_eq!(SizeScale::Tshirt.points("M"), Some(3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeScale {
+    /// Story points 0, 1, 2, 3, 5, 8, 13, 21, 34 (default)
+    #[default]
+    Fibonacci,
+    /// XS, S, M, L, XL, XXL
+    #[serde(alias = "t-shirt")]
+    Tshirt,
+}
+
+const FIBONACCI: [(&str, u32); 9] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("5", 5),
+    ("8", 8),
+    ("13", 13),
+    ("21", 21),
+    ("34", 34),
+];
+
+const TSHIRT: [(&str, u32); 6] = [
+    ("XS", 1),
+    ("S", 2),
+    ("M", 3),
+    ("L", 5),
+    ("XL", 8),
+    ("XXL", 13),
+];
+
+impl SizeScale {
+    fn table(self) -> &'static [(&'static str, u32)] {
+        match self {
+            SizeScale::Fibonacci => &FIBONACCI,
+            SizeScale::Tshirt => &TSHIRT,
+        }
+    }
+
+    /// Allowed values, smallest first
+    pub fn values(self) -> Vec<&'static str> {
+        self.table().iter().map(|(value, _)| *value).collect()
+    }
+
+    /// The canonical spelling of `input`, or a validation error listing the allowed values
+    pub fn normalize(self, input: &str) -> Result<String> {
+        let input = input.trim();
+        self.table()
+            .iter()
+            .find(|(value, _)| value.eq_ignore_ascii_case(input))
+            .map(|(value, _)| value.to_string())
+            .ok_or_else(|| {
+                PeasError::validation(
+                    "size",
+                    format!(
+                        "'{}' is not on the {} scale ({})",
+                        input,
+                        self,
+                        self.values().join(", ")
+                    ),
+                )
+            })
+    }
+
+    /// Story points for a size; None if it is not on this scale
+    pub fn points(self, size: &str) -> Option<u32> {
+        self.table()
+            .iter()
+            .find(|(value, _)| value.eq_ignore_ascii_case(size.trim()))
+            .map(|(_, points)| *points)
+    }
+}
+
+impl fmt::Display for SizeScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeScale::Fibonacci => write!(f, "fibonacci"),
+            SizeScale::Tshirt => write!(f, "tshirt"),
+        }
+    }
+}
+
+/// Summed points of a set of peas; scrapped peas do not count
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SizeTotals {
+    pub points: u32,
+    /// Points of completed peas
+    pub done: u32,
+    /// Peas without a size on the current scale
+    pub unestimated: usize,
+}
+
+impl SizeTotals {
+    pub fn of<'a>(peas: impl IntoIterator<Item = &'a Pea>, scale: SizeScale) -> Self {
+        let mut totals = Self::default();
+        for pea in peas {
+            if pea.status == PeaStatus::Scrapped {
+                continue;
+            }
+            match pea.size.as_deref().and_then(|s| scale.points(s)) {
+                Some(points) => {
+                    totals.points += points;
+                    if pea.status == PeaStatus::Completed {
+                        totals.done += points;
+                    }
+                }
+                None => totals.unestimated += 1,
+            }
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    #[test]
+    fn test_totals_skip_scrapped_and_count_unsized() {
+        let pea = |size: Option<&str>, status| {
+            let mut pea = Pea::new("p".into(), "p".into(), PeaType::Task).with_status(status);
+            pea.size = size.map(str::to_string);
+            pea
+        };
+        let peas = [
+            pea(Some("5"), PeaStatus::Completed),
+            pea(Some("3"), PeaStatus::Todo),
+            pea(Some("8"), PeaStatus::Scrapped),
+            pea(None, PeaStatus::Todo),
+            pea(Some("M"), PeaStatus::Todo),
+        ];
+        assert_eq!(
+            SizeTotals::of(&peas, SizeScale::Fibonacci),
+            SizeTotals {
+                points: 8,
+                done: 5,
+                unestimated: 2
+            }
+        );
+        assert_eq!(SizeTotals::of(&peas, SizeScale::Tshirt).points, 3);
+    }
+}
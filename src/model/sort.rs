@@ -0,0 +1,160 @@
+use super::Pea;
+use crate::error::{PeasError, Result};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// A field peas can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+    Id,
+    /// Workflow order: draft, todo, in-progress, completed, scrapped
+    Status,
+    /// Milestone, epic, story, feature, bug, chore, research, task
+    Type,
+    /// Urgency: critical, high, normal, low, deferred
+    Priority,
+    /// Case-insensitive
+    Title,
+    Created,
+    Updated,
+}
+
+impl SortField {
+    const ALL: [(&'static str, SortField); 7] = [
+        ("id", SortField::Id),
+        ("status", SortField::Status),
+        ("type", SortField::Type),
+        ("priority", SortField::Priority),
+        ("title", SortField::Title),
+        ("created", SortField::Created),
+        ("updated", SortField::Updated),
+    ];
+
+    fn compare(self, a: &Pea, b: &Pea) -> Ordering {
+        match self {
+            SortField::Id => a.id.cmp(&b.id),
+            SortField::Status => (a.status as u8).cmp(&(b.status as u8)),
+            SortField::Type => (a.pea_type as u8).cmp(&(b.pea_type as u8)),
+            SortField::Priority => (a.priority as u8).cmp(&(b.priority as u8)),
+            SortField::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+            SortField::Created => a.created.cmp(&b.created),
+            SortField::Updated => a.updated.cmp(&b.updated),
+        }
+    }
+}
+
+/// One key of a sort order such as `priority,-updated`
+///
+/// Keys sort ascending, or descending with a leading `-`. Ascending priority
+/// puts the most urgent first, ascending status follows the workflow, and
+/// ascending dates put the oldest first.
+///
+/// ```
+/// use peas::model::{SortField, SortKey};
+///
+/// let key: SortKey = "-updated".parse().unwrap();
+/// assert_eq!(key, SortKey { field: SortField::Updated, descending: true });
+/// assert_eq!(key.to_string(), "-updated");
+/// assert!("owner".parse::<SortKey>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: SortField,
+    pub descending: bool,
+}
+
+impl SortKey {
+    /// Parse a comma-separated list of keys
+    pub fn parse_list(s: &str) -> Result<Vec<SortKey>> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl FromStr for SortKey {
+    type Err = PeasError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (name, descending) = match s.strip_prefix('-') {
+            Some(name) => (name, true),
+            None => (s.strip_prefix('+').unwrap_or(s), false),
+        };
+        let field = SortField::ALL
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, field)| *field)
+            .ok_or_else(|| {
+                let names: Vec<&str> = SortField::ALL.iter().map(|(n, _)| *n).collect();
+                PeasError::parse(format!(
+                    "Invalid sort key: {} (expected one of {}, optionally prefixed with '-')",
+                    s,
+                    names.join(", ")
+                ))
+            })?;
+        Ok(SortKey { field, descending })
+    }
+}
+
+impl fmt::Display for SortKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = SortField::ALL
+            .iter()
+            .find(|(_, field)| *field == self.field)
+            .map_or("", |(n, _)| *n);
+        write!(f, "{}{}", if self.descending { "-" } else { "" }, name)
+    }
+}
+
+/// Sort peas by several keys, each breaking ties of the ones before
+///
+/// The sort is stable: peas equal on every key keep their order.
+pub fn sort_peas(peas: &mut [Pea], keys: &[SortKey]) {
+    peas.sort_by(|a, b| {
+        keys.iter().fold(Ordering::Equal, |ordering, key| {
+            ordering.then_with(|| {
+                let ordering = key.field.compare(a, b);
+                if key.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+        })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaType};
+
+    #[test]
+    fn test_sort_by_several_keys() {
+        let start = chrono::Utc::now();
+        let pea = |id: &str, priority: PeaPriority, minutes: i64| {
+            let mut pea =
+                Pea::new(id.to_string(), id.to_string(), PeaType::Task).with_priority(priority);
+            pea.updated = start + chrono::Duration::minutes(minutes);
+            pea
+        };
+        let mut peas = vec![
+            pea("a", PeaPriority::Low, 1),
+            pea("b", PeaPriority::Critical, 1),
+            pea("c", PeaPriority::Low, 2),
+            pea("d", PeaPriority::Critical, 1),
+        ];
+        sort_peas(
+            &mut peas,
+            &SortKey::parse_list("priority,-updated").unwrap(),
+        );
+        let ids: Vec<&str> = peas.iter().map(|p| p.id.as_str()).collect();
+        // b and d tie on both keys and keep their order
+        assert_eq!(ids, ["b", "d", "c", "a"]);
+        assert!(SortKey::parse_list("priority,size").is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use std::collections::HashSet;
+
+/// Words that carry no meaning when comparing titles
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "at", "for", "in", "of", "on", "or", "the", "to", "with",
+];
+
+/// Lowercased title words without stopwords
+pub fn title_words(title: &str) -> HashSet<String> {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(w))
+        .map(String::from)
+        .collect()
+}
+
+/// Sørensen–Dice coefficient of two sets, 0.0 when either is empty
+pub fn dice(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_words_and_dice() {
+        let a = title_words("Fix the login-bug");
+        assert_eq!(a, title_words("fix LOGIN bug"));
+        assert_eq!(dice(&a, &title_words("Fix page")), 0.4);
+        assert_eq!(dice(&a, &title_words("The")), 0.0);
+        assert_eq!(dice(&HashSet::new(), &HashSet::new()), 0.0);
+    }
+}
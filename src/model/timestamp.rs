@@ -0,0 +1,68 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::Deserializer;
+use serde::de::{self, MapAccess, Visitor};
+use std::fmt;
+
+/// Parse a timestamp as written by hand in frontmatter
+///
+/// RFC 3339 with any UTC offset is converted to UTC. A space may stand in for
+/// the `T`, and times or dates without an offset are taken as UTC.
+///
+/// ```
+/// use peas::model::parse_timestamp;
+///
+/// let at = parse_timestamp("2024-03-01T10:00:00+02:00").unwrap();
+/// assert_eq!(at.to_rfc3339(), "2024-03-01T08:00:00+00:00");
+/// assert_eq!(parse_timestamp("2024-03-01 08:00").unwrap(), at);
+/// assert!(parse_timestamp("yesterday").is_none());
+/// ```
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    let value = value.replacen(' ', "T", 1);
+    if let Ok(at) = DateTime::parse_from_rfc3339(&value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|at| at.and_utc())
+}
+
+/// Deserialize `created` and `updated` leniently (see [`parse_timestamp`]),
+/// including TOML's native, unquoted datetimes
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DateTime<Utc>, D::Error> {
+    struct TimestampVisitor;
+
+    impl<'de> Visitor<'de> for TimestampVisitor {
+        type Value = DateTime<Utc>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an RFC 3339 date and time")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            parse_timestamp(value)
+                .ok_or_else(|| E::custom(format!("invalid timestamp '{}'", value)))
+        }
+
+        // TOML datetimes arrive as a map with a single private key
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let Some((_, value)) = map.next_entry::<String, String>()? else {
+                return Err(de::Error::custom("empty timestamp"));
+            };
+            self.visit_str(&value)
+        }
+    }
+
+    deserializer.deserialize_any(TimestampVisitor)
+}
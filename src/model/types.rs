@@ -64,7 +64,7 @@ impl FromStr for PeaType {
             "chore" => Ok(PeaType::Chore),
             "research" | "spike" => Ok(PeaType::Research),
             "task" => Ok(PeaType::Task),
-            _ => Err(PeasError::Parse(format!("Invalid pea type: {}", s))),
+            _ => Err(PeasError::parse(format!("Invalid pea type: {}", s))),
         }
     }
 }
@@ -119,7 +119,7 @@ impl FromStr for PeaStatus {
             "in-progress" | "inprogress" | "in_progress" => Ok(PeaStatus::InProgress),
             "completed" | "done" => Ok(PeaStatus::Completed),
             "scrapped" | "cancelled" | "canceled" => Ok(PeaStatus::Scrapped),
-            _ => Err(PeasError::Parse(format!("Invalid pea status: {}", s))),
+            _ => Err(PeasError::parse(format!("Invalid pea status: {}", s))),
         }
     }
 }
@@ -173,7 +173,65 @@ impl FromStr for PeaPriority {
             "normal" | "p2" => Ok(PeaPriority::Normal),
             "low" | "p3" => Ok(PeaPriority::Low),
             "deferred" | "p4" => Ok(PeaPriority::Deferred),
-            _ => Err(PeasError::Parse(format!("Invalid priority: {}", s))),
+            _ => Err(PeasError::parse(format!("Invalid priority: {}", s))),
+        }
+    }
+}
+
+/// What a [`PeaLink`](super::PeaLink) points at.
+///
+/// ```
+/// use peas::model::LinkKind;
+///
+/// assert_eq!("pr".parse::<LinkKind>().unwrap(), LinkKind::Pr);
+/// assert_eq!("mr".parse::<LinkKind>().unwrap(), LinkKind::Pr);
+///
+/// // Inferred from well-known forge and tracker URLs, docs otherwise
+/// assert_eq!(LinkKind::infer("https://github.com/o/r/pull/7"), LinkKind::Pr);
+/// assert_eq!(LinkKind::infer("https://gitlab.com/o/r/-/issues/3"), LinkKind::Issue);
+/// assert_eq!(LinkKind::infer("https://example.com/design"), LinkKind::Doc);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Pr,
+    Issue,
+    #[default]
+    Doc,
+}
+
+impl LinkKind {
+    /// Guess the kind from the URL shape
+    pub fn infer(url: &str) -> Self {
+        if url.contains("/pull/") || url.contains("/merge_requests/") {
+            LinkKind::Pr
+        } else if url.contains("/issues/") || url.contains("/browse/") {
+            LinkKind::Issue
+        } else {
+            LinkKind::Doc
+        }
+    }
+}
+
+impl fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkKind::Pr => write!(f, "pr"),
+            LinkKind::Issue => write!(f, "issue"),
+            LinkKind::Doc => write!(f, "doc"),
+        }
+    }
+}
+
+impl FromStr for LinkKind {
+    type Err = PeasError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "pr" | "mr" | "pull" => Ok(LinkKind::Pr),
+            "issue" => Ok(LinkKind::Issue),
+            "doc" | "docs" => Ok(LinkKind::Doc),
+            _ => Err(PeasError::parse(format!("Invalid link kind: {}", s))),
         }
     }
 }
@@ -0,0 +1,155 @@
+use super::Pea;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+
+/// Heading of the body section `peas note` appends to
+pub const WORK_LOG_HEADING: &str = "## Work log";
+
+/// Timestamped notes kept in a "Work log" section of a pea's body.
+///
+/// Each note is a list item starting with its UTC time and, when known, the
+/// actor who wrote it. Notes written by hand in the same format count too.
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use peas::model::{Pea, PeaType};
+///
+/// let mut pea = Pea::new("peas-abc12".into(), "Login".into(), PeaType::Task)
+///     .with_body("Build the login form.".into());
+/// let at = Utc.with_ymd_and_hms(2026, 10, 16, 9, 30, 0).unwrap();
+/// pea.add_note("Form renders, validation next", Some("ann"), at)?;
+///
+/// assert!(pea.body.ends_with("## Work log\n\n- 2026-10-16 09:30 (ann) Form renders, validation next\n"));
+/// assert_eq!(pea.work_log(), ["2026-10-16 09:30 (ann) Form renders, validation next"]);
+/// # Ok::<(), peas::error::PeasError>(())
+/// ```
+impl Pea {
+    /// Append a note to the work log, starting the section if needed
+    ///
+    /// Continuation lines of a multi-line note are indented so the note stays
+    /// one list item. Fails if the body was not loaded.
+    pub fn add_note(&mut self, text: &str, actor: Option<&str>, at: DateTime<Utc>) -> Result<()> {
+        let mut item = format!("- {}", at.format("%Y-%m-%d %H:%M"));
+        if let Some(actor) = actor {
+            item.push_str(&format!(" ({})", actor));
+        }
+        for (i, line) in text.trim().lines().enumerate() {
+            match (i, line.trim_end()) {
+                (0, line) => item.push_str(&format!(" {}", line)),
+                (_, "") => item.push('\n'),
+                (_, line) => item.push_str(&format!("\n  {}", line)),
+            }
+        }
+        item.push('\n');
+
+        let body = self.body.to_mut()?;
+        match section(body) {
+            Some((_, end)) => {
+                let (before, after) = body.split_at(end);
+                let before = before.trim_end();
+                // Leave a blank line after the heading, none between notes
+                let separator = if before.ends_with(WORK_LOG_HEADING) {
+                    "\n\n"
+                } else {
+                    "\n"
+                };
+                let after = after.trim_start_matches('\n');
+                let gap = if after.is_empty() { "" } else { "\n" };
+                *body = format!("{}{}{}{}{}", before, separator, item, gap, after);
+            }
+            None => {
+                let before = body.trim_end();
+                let gap = if before.is_empty() { "" } else { "\n\n" };
+                *body = format!("{}{}{}\n\n{}", before, gap, WORK_LOG_HEADING, item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Notes in the work log, oldest first, without the list marker
+    pub fn work_log(&self) -> Vec<String> {
+        let body = &self.body;
+        let Some((start, end)) = section(body) else {
+            return Vec::new();
+        };
+        let mut entries: Vec<String> = Vec::new();
+        for line in body[start..end].lines() {
+            if let Some(item) = line.strip_prefix("- ") {
+                entries.push(item.trim_end().to_string());
+            } else if let Some(entry) = entries.last_mut()
+                && line.starts_with("  ")
+            {
+                entry.push('\n');
+                entry.push_str(line.trim());
+            }
+        }
+        entries
+    }
+}
+
+/// Byte range of the work log section's content, from after its heading to
+/// the next heading of the same or a higher level
+fn section(body: &str) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    let mut start = None;
+    for line in body.split_inclusive('\n') {
+        let heading = line.trim_end();
+        match start {
+            None if heading == WORK_LOG_HEADING => start = Some(offset + line.len()),
+            Some(start) if heading.starts_with("# ") || heading.starts_with("## ") => {
+                return Some((start, offset));
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    start.map(|start| (start.min(body.len()), body.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 16, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_add_starts_and_extends_section() {
+        let mut pea = Pea::new("peas-abc12".into(), "Login".into(), PeaType::Task);
+        pea.add_note("First", None, at(9)).unwrap();
+        assert_eq!(pea.body, "## Work log\n\n- 2026-10-16 09:00 First\n");
+
+        pea.add_note("Second\nwith details\n\nand more", Some("agent-x"), at(10))
+            .unwrap();
+        assert_eq!(
+            pea.body,
+            "## Work log\n\n- 2026-10-16 09:00 First\n\
+             - 2026-10-16 10:00 (agent-x) Second\n  with details\n\n  and more\n"
+        );
+        assert_eq!(
+            pea.work_log(),
+            [
+                "2026-10-16 09:00 First",
+                "2026-10-16 10:00 (agent-x) Second\nwith details\nand more"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_keeps_later_sections() {
+        let mut pea = Pea::new("peas-abc12".into(), "Login".into(), PeaType::Task).with_body(
+            "Intro\n\n## Work log\n\n- 2026-10-15 08:00 Old\n\n## Links\n\nSee docs".into(),
+        );
+        pea.add_note("New", None, at(9)).unwrap();
+        assert_eq!(
+            pea.body,
+            "Intro\n\n## Work log\n\n- 2026-10-15 08:00 Old\n- 2026-10-16 09:00 New\n\n## Links\n\nSee docs"
+        );
+        assert_eq!(pea.work_log().len(), 2);
+        pea.body = "Intro\n\n### Work log\n- not a note".into();
+        assert!(pea.work_log().is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::error::{PeasError, Result};
+use std::path::{Path, PathBuf};
+use wasmtime::{Caller, Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// Directory under `.peas/` holding WASM plugins
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// Fuel per call, roughly the number of WASM instructions a plugin may execute
+const FUEL_PER_CALL: u64 = 100_000_000;
+
+/// Linear memory a plugin instance may grow to
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// A compiled plugin module
+pub struct Plugin {
+    /// File name, e.g. `require-size.wasm`
+    pub name: String,
+    module: Module,
+}
+
+impl Plugin {
+    /// Export handling `event` (a hook name like `pre-update`): `on_pre_update`
+    /// if the plugin has one, else `on_event`; `None` if it has neither
+    pub fn handler(&self, event: &str) -> Option<String> {
+        let specific = format!("on_{}", event.replace('-', "_"));
+        [specific, "on_event".to_string()]
+            .into_iter()
+            .find(|name| self.module.get_export(name).is_some())
+    }
+}
+
+/// WASM modules in `.peas/plugins/` that see the same events as hooks
+///
+/// A plugin exports `memory`, `alloc(len: i32) -> i32` and
+/// `on_event(ptr: i32, len: i32) -> i64`, or only handlers for the events it
+/// cares about, such as `on_pre_update`. Peas copies the event JSON into
+/// memory from `alloc` and calls the handler, which returns `0` for "no
+/// opinion" or a pointer and length packed as `(ptr << 32) | len` to a JSON
+/// reply: `{"error": "..."}` rejects the operation, `{"pea": ..., "body": ...}`
+/// changes the pea. A plugin may import `peas.log(ptr, len)` to write to the
+/// peas log.
+///
+/// Every call runs in a fresh instance with limited fuel and memory, and a
+/// plugin has no access to files, the network or the environment.
+pub struct Plugins {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+}
+
+impl Plugins {
+    /// Compile every `.wasm` (or text `.wat`) file in `.peas/plugins/`, in name order
+    ///
+    /// `None` when there are no plugins.
+    pub fn load(data_path: &Path) -> Result<Option<Self>> {
+        let mut paths: Vec<PathBuf> = match std::fs::read_dir(data_path.join(PLUGINS_DIR)) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext == "wasm" || ext == "wat")
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        if paths.is_empty() {
+            return Ok(None);
+        }
+        paths.sort();
+
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| PeasError::Plugin(e.to_string()))?;
+
+        let plugins = paths
+            .into_iter()
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                tracing::debug!(plugin = %name, "Loading plugin");
+                let module = Module::from_file(&engine, &path)
+                    .map_err(|e| PeasError::Plugin(format!("{}: {:#}", name, e)))?;
+                Ok(Plugin { name, module })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Some(Self { engine, plugins }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Plugin> {
+        self.plugins.iter()
+    }
+
+    /// Call a plugin's `handler` (see [`Plugin::handler`]) with the event JSON,
+    /// returning its reply if it gave one
+    pub fn call(&self, plugin: &Plugin, handler: &str, input: &str) -> Result<Option<String>> {
+        self.call_inner(plugin, handler, input)
+            .map_err(|e| PeasError::Plugin(format!("{}: {:#}", plugin.name, e)))
+    }
+
+    fn call_inner(
+        &self,
+        plugin: &Plugin,
+        handler: &str,
+        input: &str,
+    ) -> wasmtime::Result<Option<String>> {
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL_PER_CALL)?;
+
+        let mut linker: Linker<StoreLimits> = Linker::new(&self.engine);
+        let name = plugin.name.clone();
+        linker.func_wrap(
+            "peas",
+            "log",
+            move |mut caller: Caller<'_, StoreLimits>, ptr: i32, len: i32| {
+                if let Some(memory) = caller
+                    .get_export("memory")
+                    .and_then(|export| export.into_memory())
+                    && let Some(bytes) = memory
+                        .data(&caller)
+                        .get(ptr as usize..(ptr as usize).saturating_add(len as usize))
+                {
+                    tracing::info!(plugin = %name, "{}", String::from_utf8_lossy(bytes));
+                }
+            },
+        )?;
+
+        let instance = linker.instantiate(&mut store, &plugin.module)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("plugin does not export `memory`"))?;
+        let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+        let on_event = instance.get_typed_func::<(i32, i32), i64>(&mut store, handler)?;
+
+        let len = i32::try_from(input.len())?;
+        let ptr = alloc.call(&mut store, len)?;
+        memory.write(&mut store, ptr as usize, input.as_bytes())?;
+
+        let packed = on_event.call(&mut store, (ptr, len))? as u64;
+        if packed == 0 {
+            return Ok(None);
+        }
+        let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+        let reply = memory
+            .data(&store)
+            .get(ptr..ptr.saturating_add(len))
+            .ok_or_else(|| wasmtime::Error::msg("reply is outside the plugin's memory"))?;
+        Ok(Some(String::from_utf8(reply.to_vec())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Rejects deletes by checking the `d` in `{"hook":"pre-delete"`
+    const NO_DELETE: &str = r#"(module
+      (memory (export "memory") 1)
+      (global $next (mut i32) (i32.const 1024))
+      (data (i32.const 0) "{\"error\":\"deleting is not allowed\"}")
+      (func (export "alloc") (param $len i32) (result i32)
+        (local $ptr i32)
+        (local.set $ptr (global.get $next))
+        (global.set $next (i32.add (global.get $next) (local.get $len)))
+        (local.get $ptr))
+      (func (export "on_event") (param $ptr i32) (param $len i32) (result i64)
+        (if (result i64) (i32.eq (i32.load8_u offset=13 (local.get $ptr)) (i32.const 100))
+          (then (i64.const 35))
+          (else (i64.const 0)))))"#;
+
+    /// Spins only on `post-update`
+    const SPIN: &str = r#"(module
+      (memory (export "memory") 1)
+      (func (export "alloc") (param i32) (result i32) (i32.const 0))
+      (func (export "on_post_update") (param i32 i32) (result i64)
+        (loop $forever (br $forever))
+        (i64.const 0)))"#;
+
+    #[test]
+    fn test_plugin_replies_and_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(Plugins::load(temp_dir.path()).unwrap().is_none());
+
+        let dir = temp_dir.path().join(PLUGINS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a-no-delete.wat"), NO_DELETE).unwrap();
+        std::fs::write(dir.join("b-spin.wat"), SPIN).unwrap();
+        std::fs::write(dir.join("README.md"), "not a plugin").unwrap();
+
+        let plugins = Plugins::load(temp_dir.path()).unwrap().unwrap();
+        let loaded: Vec<_> = plugins.iter().collect();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].name, "a-no-delete.wat");
+        assert_eq!(loaded[0].handler("pre-create").as_deref(), Some("on_event"));
+        assert_eq!(
+            loaded[1].handler("post-update").as_deref(),
+            Some("on_post_update")
+        );
+        assert_eq!(loaded[1].handler("pre-update"), None);
+
+        let reply = plugins
+            .call(loaded[0], "on_event", r#"{"hook":"pre-delete","pea":{}}"#)
+            .unwrap();
+        assert_eq!(
+            reply.as_deref(),
+            Some(r#"{"error":"deleting is not allowed"}"#)
+        );
+        let reply = plugins
+            .call(loaded[0], "on_event", r#"{"hook":"pre-create","pea":{}}"#)
+            .unwrap();
+        assert_eq!(reply, None);
+
+        // Runaway plugins run out of fuel instead of hanging peas
+        let err = plugins.call(loaded[1], "on_post_update", "{}").unwrap_err();
+        assert!(err.to_string().starts_with("Plugin error: b-spin.wat:"));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::error::Result;
+use crate::local::LocalState;
+use crate::model::Pea;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const MARKERS_FILE: &str = "read.json";
+
+/// How a pea compares to what the user last looked at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadState {
+    Read,
+    /// Created since the user last looked
+    New,
+    /// Updated since the user last looked at it
+    Changed,
+}
+
+impl ReadState {
+    pub fn is_unread(self) -> bool {
+        self != ReadState::Read
+    }
+}
+
+impl std::fmt::Display for ReadState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadState::Read => write!(f, "read"),
+            ReadState::New => write!(f, "new"),
+            ReadState::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Markers {
+    /// Everything updated before this counts as read
+    since: Option<DateTime<Utc>>,
+    /// Last seen `updated` timestamp per pea
+    #[serde(default)]
+    seen: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// Per-user read markers, kept in `.peas/local/read.json`
+pub struct ReadMarkers {
+    local: LocalState,
+    markers: Markers,
+}
+
+impl ReadMarkers {
+    /// Load the markers
+    ///
+    /// The first load starts tracking from now, so opening an existing
+    /// tracker does not flag every ticket as new.
+    pub fn load(data_path: &Path) -> Self {
+        let local = LocalState::new(data_path);
+        let markers = match local.read(MARKERS_FILE) {
+            Some(content) => serde_json::from_str(&content).unwrap_or_default(),
+            None => {
+                let markers = Markers {
+                    since: Some(Utc::now()),
+                    seen: BTreeMap::new(),
+                };
+                if let Ok(content) = serde_json::to_string_pretty(&markers) {
+                    let _ = local.write(MARKERS_FILE, &content);
+                }
+                markers
+            }
+        };
+        Self { local, markers }
+    }
+
+    pub fn state(&self, pea: &Pea) -> ReadState {
+        if let Some(seen) = self.markers.seen.get(&pea.id) {
+            return if pea.updated > *seen {
+                ReadState::Changed
+            } else {
+                ReadState::Read
+            };
+        }
+        match self.markers.since {
+            Some(since) if pea.created > since => ReadState::New,
+            Some(since) if pea.updated > since => ReadState::Changed,
+            _ => ReadState::Read,
+        }
+    }
+
+    pub fn is_unread(&self, pea: &Pea) -> bool {
+        self.state(pea).is_unread()
+    }
+
+    /// Mark the current version of a pea as read
+    pub fn mark_read(&mut self, pea: &Pea) {
+        self.markers.seen.insert(pea.id.clone(), pea.updated);
+    }
+
+    /// Mark everything up to now as read
+    pub fn mark_all_read(&mut self) {
+        self.markers.since = Some(Utc::now());
+        self.markers.seen.clear();
+    }
+
+    pub fn save(&self) -> Result<()> {
+        self.local
+            .write(MARKERS_FILE, &serde_json::to_string_pretty(&self.markers)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_new_changed_and_read() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut markers = ReadMarkers::load(temp_dir.path());
+
+        let mut old = Pea::new("peas-old01".to_string(), "Old".to_string(), PeaType::Task);
+        old.created = Utc::now() - Duration::days(2);
+        old.updated = old.created;
+        assert_eq!(markers.state(&old), ReadState::Read);
+
+        let mut fresh = Pea::new("peas-new01".to_string(), "New".to_string(), PeaType::Task);
+        fresh.created = Utc::now() + Duration::seconds(5);
+        fresh.updated = fresh.created;
+        assert_eq!(markers.state(&fresh), ReadState::New);
+
+        old.updated = Utc::now() + Duration::seconds(5);
+        assert_eq!(markers.state(&old), ReadState::Changed);
+
+        markers.mark_read(&old);
+        assert_eq!(markers.state(&old), ReadState::Read);
+        old.updated += Duration::seconds(1);
+        assert_eq!(markers.state(&old), ReadState::Changed);
+
+        // Markers survive a reload
+        markers.mark_read(&fresh);
+        markers.save().unwrap();
+        let reloaded = ReadMarkers::load(temp_dir.path());
+        assert_eq!(reloaded.state(&fresh), ReadState::Read);
+        assert_eq!(reloaded.state(&old), ReadState::Changed);
+    }
+}
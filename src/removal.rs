@@ -0,0 +1,359 @@
+//! Deleting and archiving peas without leaving broken links behind
+//!
+//! A [`RemovalPlan`] works out what happens to the children of a removed
+//! pea, as chosen by a [`ChildPolicy`], and drops the removed peas from the
+//! `blocking` lists of the peas that stay. The CLI, the TUI and GraphQL all
+//! remove peas through it.
+
+use crate::error::{PeasError, Result};
+use crate::model::{Pea, References, descendants};
+use crate::storage::PeaRepository;
+use crate::undo::UndoOperation;
+use std::collections::BTreeMap;
+
+/// What happens to the children of a removed pea
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ChildPolicy {
+    /// Refuse to remove a pea that has children, so none is left pointing
+    /// at a missing parent
+    #[default]
+    Refuse,
+    /// Remove all descendants along with it
+    Cascade,
+    /// Move the children under another pea
+    Reparent(String),
+    /// Clear the parent of the children
+    Orphan,
+}
+
+/// Whether peas are deleted or moved to the archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalKind {
+    Delete,
+    Archive,
+}
+
+impl RemovalKind {
+    fn verb(self) -> &'static str {
+        match self {
+            RemovalKind::Delete => "Delete",
+            RemovalKind::Archive => "Archive",
+        }
+    }
+}
+
+/// The changes removing a pea makes
+#[derive(Debug, Clone)]
+pub struct RemovalPlan {
+    pub kind: RemovalKind,
+    /// IDs of the removed peas: the pea itself, then descendants when cascading
+    pub removed: Vec<String>,
+    /// Remaining peas as they will be written, with children moved and
+    /// removed peas dropped from `blocking`
+    pub updated: Vec<Pea>,
+    /// Children given another parent, or none, among `updated`
+    pub moved_children: Vec<String>,
+    /// Remaining peas whose body mentions a deleted pea; bodies are left for
+    /// a person to edit, and mentions of archived peas are kept as history
+    pub mentioned_by: Vec<String>,
+}
+
+impl RemovalPlan {
+    /// Plan the removal of the pea with the full ID `id` out of `peas`
+    ///
+    /// The pea itself need not be in `peas`, so a file that no longer parses
+    /// can still be removed.
+    ///
+    /// ```
+    /// use peas::model::{Pea, PeaType};
+    /// use peas::removal::{ChildPolicy, RemovalKind, RemovalPlan};
+    ///
+    /// let peas = vec![
+    ///     Pea::new("peas-epic1".into(), "Auth".into(), PeaType::Epic),
+    ///     Pea::new("peas-a1".into(), "Login".into(), PeaType::Task)
+    ///         .with_parent(Some("peas-epic1".into())),
+    /// ];
+    ///
+    /// let plan =
+    ///     RemovalPlan::new(RemovalKind::Delete, "peas-epic1", &ChildPolicy::Orphan, &peas).unwrap();
+    /// assert_eq!(plan.removed, ["peas-epic1"]);
+    /// assert_eq!(plan.updated[0].parent, None);
+    /// ```
+    pub fn new(kind: RemovalKind, id: &str, policy: &ChildPolicy, peas: &[Pea]) -> Result<Self> {
+        let below = descendants(peas, id);
+        let children: Vec<&Pea> = peas
+            .iter()
+            .filter(|p| p.parent.as_deref() == Some(id) && p.id != id)
+            .collect();
+
+        let mut removed = vec![id.to_string()];
+        if *policy == ChildPolicy::Cascade {
+            removed.extend(below.iter().map(|p| p.id.clone()));
+        }
+
+        // Keyed by ID so a child that is moved and unblocked is written once
+        let mut updated: BTreeMap<String, Pea> = BTreeMap::new();
+        match policy {
+            ChildPolicy::Refuse if !children.is_empty() => {
+                let ids: Vec<&str> = children.iter().map(|p| p.id.as_str()).collect();
+                return Err(PeasError::validation(
+                    "children",
+                    format!(
+                        "{} has children ({}); remove them too, move them under another pea or clear their parent",
+                        id,
+                        ids.join(", ")
+                    ),
+                ));
+            }
+            ChildPolicy::Refuse | ChildPolicy::Cascade => {}
+            ChildPolicy::Reparent(target) => {
+                if target == id || below.iter().any(|p| &p.id == target) {
+                    return Err(PeasError::validation(
+                        "reparent_to",
+                        format!("Cannot move the children of {} below itself", id),
+                    ));
+                }
+                if !peas.iter().any(|p| &p.id == target) {
+                    return Err(PeasError::NotFound(target.clone()));
+                }
+                for child in &children {
+                    let mut child = (*child).clone();
+                    child.parent = Some(target.clone());
+                    updated.insert(child.id.clone(), child);
+                }
+            }
+            ChildPolicy::Orphan => {
+                for child in &children {
+                    let mut child = (*child).clone();
+                    child.parent = None;
+                    updated.insert(child.id.clone(), child);
+                }
+            }
+        }
+
+        for other in peas.iter().filter(|p| !removed.contains(&p.id)) {
+            if other.blocking.iter().any(|b| removed.contains(b)) {
+                let entry = updated
+                    .entry(other.id.clone())
+                    .or_insert_with(|| other.clone());
+                entry.blocking.retain(|b| !removed.contains(b));
+            }
+        }
+
+        let mut mentioned_by = Vec::new();
+        if kind == RemovalKind::Delete {
+            let references = References::build(peas);
+            for id in &removed {
+                for other in references.referenced_by(id) {
+                    if !removed.contains(other) && !mentioned_by.contains(other) {
+                        mentioned_by.push(other.clone());
+                    }
+                }
+            }
+        }
+
+        let moved_children = match policy {
+            ChildPolicy::Reparent(_) | ChildPolicy::Orphan => {
+                children.iter().map(|p| p.id.clone()).collect()
+            }
+            ChildPolicy::Refuse | ChildPolicy::Cascade => Vec::new(),
+        };
+        Ok(Self {
+            kind,
+            removed,
+            updated: updated.into_values().collect(),
+            moved_children,
+            mentioned_by,
+        })
+    }
+
+    /// Undo description, e.g. "Delete peas-abc12 and 2 more"
+    pub fn description(&self) -> String {
+        match self.removed.len() {
+            1 => format!("{} {}", self.kind.verb(), self.removed[0]),
+            n => format!(
+                "{} {} and {} more",
+                self.kind.verb(),
+                self.removed[0],
+                n - 1
+            ),
+        }
+    }
+
+    /// Write the plan, pushing how to undo each step onto `operations`
+    ///
+    /// Remaining peas are updated before any pea is removed, so a moved child
+    /// never points at a missing parent. On an error, `operations` holds the
+    /// steps that were already taken.
+    pub fn apply(&self, repo: &PeaRepository, operations: &mut Vec<UndoOperation>) -> Result<()> {
+        for pea in &self.updated {
+            let path = repo.find_file_by_id(&pea.id)?;
+            let operation = UndoOperation::update_from_file(&pea.id, &path)?;
+            repo.update(&mut pea.clone())?;
+            operations.push(operation);
+        }
+        for id in &self.removed {
+            let path = repo.find_file_by_id(id)?;
+            match self.kind {
+                RemovalKind::Delete => {
+                    let operation = UndoOperation::delete_from_file(id, &path)?;
+                    repo.delete(id)?;
+                    operations.push(operation);
+                }
+                RemovalKind::Archive => {
+                    let archive_path = repo.archive(id)?;
+                    operations.push(UndoOperation::Archive {
+                        id: id.clone(),
+                        original_path: path,
+                        archive_path,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// One undo step for the operations [`Self::apply`] pushed
+    pub fn undo_step(&self, mut operations: Vec<UndoOperation>) -> Option<UndoOperation> {
+        match operations.len() {
+            0 => None,
+            1 => operations.pop(),
+            _ => Some(UndoOperation::Batch {
+                description: self.description(),
+                operations,
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+
+    fn peas() -> Vec<Pea> {
+        let pea = |id: &str, parent: Option<&str>| {
+            Pea::new(id.to_string(), id.to_string(), PeaType::Task)
+                .with_parent(parent.map(String::from))
+        };
+        vec![
+            pea("peas-root1", None),
+            pea("peas-epic1", Some("peas-root1")),
+            pea("peas-kid01", Some("peas-epic1")),
+            pea("peas-kid02", Some("peas-epic1")).with_blocking(vec!["peas-epic1".into()]),
+            pea("peas-gkid1", Some("peas-kid01")),
+            pea("peas-other", None)
+                .with_blocking(vec!["peas-kid01".into(), "peas-x".into()])
+                .with_body("Follows up on peas-epic1.".into()),
+        ]
+    }
+
+    fn ids(peas: &[Pea]) -> Vec<&str> {
+        peas.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_refuse_rejects_parents_and_prunes_blocking() {
+        for kind in [RemovalKind::Delete, RemovalKind::Archive] {
+            let err =
+                RemovalPlan::new(kind, "peas-epic1", &ChildPolicy::Refuse, &peas()).unwrap_err();
+            assert!(matches!(
+                err,
+                PeasError::ValidationFailed {
+                    field: "children",
+                    ..
+                }
+            ));
+        }
+
+        let plan = RemovalPlan::new(
+            RemovalKind::Delete,
+            "peas-kid02",
+            &ChildPolicy::Refuse,
+            &peas(),
+        )
+        .unwrap();
+        assert_eq!(plan.removed, ["peas-kid02"]);
+        assert!(plan.updated.is_empty());
+        let plan = RemovalPlan::new(
+            RemovalKind::Delete,
+            "peas-kid01",
+            &ChildPolicy::Orphan,
+            &peas(),
+        )
+        .unwrap();
+        assert_eq!(ids(&plan.updated), ["peas-gkid1", "peas-other"]);
+        assert_eq!(plan.updated[1].blocking, ["peas-x"]);
+    }
+
+    #[test]
+    fn test_cascade_removes_descendants() {
+        let plan = RemovalPlan::new(
+            RemovalKind::Archive,
+            "peas-epic1",
+            &ChildPolicy::Cascade,
+            &peas(),
+        )
+        .unwrap();
+        let mut removed = plan.removed.clone();
+        removed.sort();
+        assert_eq!(
+            removed,
+            ["peas-epic1", "peas-gkid1", "peas-kid01", "peas-kid02"]
+        );
+        assert_eq!(ids(&plan.updated), ["peas-other"]);
+        assert_eq!(plan.updated[0].blocking, ["peas-x"]);
+        assert_eq!(plan.description(), "Archive peas-epic1 and 3 more");
+        assert!(plan.mentioned_by.is_empty());
+    }
+
+    #[test]
+    fn test_reparent_and_orphan_move_direct_children() {
+        let plan = RemovalPlan::new(
+            RemovalKind::Delete,
+            "peas-epic1",
+            &ChildPolicy::Reparent("peas-root1".into()),
+            &peas(),
+        )
+        .unwrap();
+        assert_eq!(ids(&plan.updated), ["peas-kid01", "peas-kid02"]);
+        assert!(
+            plan.updated
+                .iter()
+                .all(|p| p.parent.as_deref() == Some("peas-root1"))
+        );
+
+        let plan = RemovalPlan::new(
+            RemovalKind::Delete,
+            "peas-epic1",
+            &ChildPolicy::Orphan,
+            &peas(),
+        )
+        .unwrap();
+        assert!(plan.updated.iter().all(|p| p.parent.is_none()));
+        assert_eq!(plan.moved_children, ["peas-kid01", "peas-kid02"]);
+        assert_eq!(plan.mentioned_by, ["peas-other"]);
+    }
+
+    #[test]
+    fn test_reparent_rejects_targets_below_the_pea() {
+        for target in ["peas-epic1", "peas-gkid1"] {
+            let err = RemovalPlan::new(
+                RemovalKind::Delete,
+                "peas-epic1",
+                &ChildPolicy::Reparent(target.into()),
+                &peas(),
+            )
+            .unwrap_err();
+            assert!(matches!(err, PeasError::ValidationFailed { .. }));
+        }
+        let err = RemovalPlan::new(
+            RemovalKind::Delete,
+            "peas-epic1",
+            &ChildPolicy::Reparent("peas-nope1".into()),
+            &peas(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, PeasError::NotFound(_)));
+    }
+}
@@ -0,0 +1,291 @@
+use crate::config::{RuleEvent, RuleSettings};
+use crate::error::{PeasError, Result};
+use crate::model::Pea;
+use rhai::{AST, Dynamic, Engine, EvalAltResult, Scope};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+/// Operations one rule may run per pea, so a runaway loop fails instead of hanging
+const MAX_OPERATIONS: u64 = 100_000;
+
+/// Fields that are left out of a pea's JSON when empty, but scripts expect to exist
+const LIST_FIELDS: [&str; 5] = ["tags", "blocking", "external_refs", "links", "assets"];
+const OPTIONAL_FIELDS: [&str; 7] = [
+    "size",
+    "component",
+    "assignee",
+    "parent",
+    "target_date",
+    "defer_until",
+    "released_in",
+];
+
+/// A compiled `[rules.<name>]` section
+pub struct Rule {
+    pub name: String,
+    pub on: Vec<RuleEvent>,
+    when: Option<AST>,
+    then: AST,
+}
+
+/// Automation rules from `[rules.<name>]`, written in [Rhai](https://rhai.rs)
+///
+/// A rule sees the pea being written as the map `pea` (with `body`) and runs
+/// its `then` script when its `when` condition holds. Scripts change fields in
+/// place (`pea.priority = "critical"`, `pea.tags.push("triage")`) and reject
+/// the write with `throw "reason"`. The ID and timestamps cannot be changed.
+///
+/// ```
+/// use peas::config::{RuleEvent, RuleSettings};
+/// use peas::model::{Pea, PeaPriority, PeaType};
+/// use peas::rules::Rules;
+///
+/// let settings = [(
+///     "security-bugs".to_string(),
+///     RuleSettings {
+///         on: vec![RuleEvent::Create],
+///         when: Some(r#"pea.type == "bug" && "security" in pea.tags"#.to_string()),
+///         then: r#"pea.priority = "critical""#.to_string(),
+///     },
+/// )]
+/// .into();
+/// let rules = Rules::new(&settings);
+///
+/// let bug = Pea::new("peas-bug01".into(), "XSS".into(), PeaType::Bug)
+///     .with_tags(vec!["security".into()]);
+/// let changed = rules.apply(RuleEvent::Create, &bug).unwrap().unwrap();
+/// assert_eq!(changed.priority, PeaPriority::Critical);
+/// ```
+pub struct Rules {
+    settings: BTreeMap<String, RuleSettings>,
+    engine: Engine,
+    /// Compiled on first use
+    compiled: OnceCell<Vec<Rule>>,
+}
+
+impl Rules {
+    pub fn new(settings: &BTreeMap<String, RuleSettings>) -> Self {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        // Keep `print` and `debug` out of command output
+        engine.on_print(|text| tracing::info!(target: "peas::rules", "{}", text));
+        engine.on_debug(|text, _, _| tracing::debug!(target: "peas::rules", "{}", text));
+        Self {
+            settings: settings.clone(),
+            engine,
+            compiled: OnceCell::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+    }
+
+    /// Compile every rule, reporting the first syntax error
+    pub fn check(&self) -> Result<()> {
+        self.compiled().map(|_| ())
+    }
+
+    /// The compiled rules, in name order
+    pub fn compiled(&self) -> Result<&[Rule]> {
+        if let Some(rules) = self.compiled.get() {
+            return Ok(rules);
+        }
+        let compile = |name: &str, field: &str, script: &str| {
+            self.engine.compile(script).map_err(|e| {
+                PeasError::Config(format!(
+                    "Invalid {} script in rule '{}': {}",
+                    field, name, e
+                ))
+            })
+        };
+        let rules = self
+            .settings
+            .iter()
+            .map(|(name, settings)| {
+                Ok(Rule {
+                    name: name.clone(),
+                    on: settings.on.clone(),
+                    when: settings
+                        .when
+                        .as_deref()
+                        .map(|when| compile(name, "when", when))
+                        .transpose()?,
+                    then: compile(name, "then", &settings.then)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(self.compiled.get_or_init(|| rules))
+    }
+
+    /// Run every rule for `event` in turn, returning the pea if any rule changed it
+    pub fn apply(&self, event: RuleEvent, pea: &Pea) -> Result<Option<Pea>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let mut changed: Option<Pea> = None;
+        for rule in self.compiled()?.iter().filter(|r| r.on.contains(&event)) {
+            let current = changed.as_ref().unwrap_or(pea);
+            if let Some(result) = self.run(rule, current)? {
+                tracing::debug!(rule = %rule.name, id = %pea.id, "Rule applied");
+                changed = Some(result);
+            }
+        }
+        Ok(changed.filter(|changed| changed != pea))
+    }
+
+    /// Run one rule regardless of its events; `None` when its condition does not hold
+    pub fn run(&self, rule: &Rule, pea: &Pea) -> Result<Option<Pea>> {
+        let failed = |e: Box<EvalAltResult>| PeasError::Rule {
+            rule: rule.name.clone(),
+            message: match *e {
+                // `throw "reason"` rejects with just the reason
+                EvalAltResult::ErrorRuntime(value, _) => value.to_string(),
+                other => other.to_string(),
+            },
+        };
+
+        let mut scope = Scope::new();
+        scope.push("pea", to_script(pea)?);
+        if let Some(when) = &rule.when {
+            let matches = self
+                .engine
+                .eval_ast_with_scope::<bool>(&mut scope, when)
+                .map_err(failed)?;
+            if !matches {
+                return Ok(None);
+            }
+        }
+        self.engine
+            .run_ast_with_scope(&mut scope, &rule.then)
+            .map_err(failed)?;
+
+        let value = scope.get_value::<Dynamic>("pea").unwrap_or_default();
+        from_script(&value, pea)
+            .map(Some)
+            .map_err(|message| PeasError::Rule {
+                rule: rule.name.clone(),
+                message,
+            })
+    }
+}
+
+/// The pea as a script map, with every field present
+fn to_script(pea: &Pea) -> Result<Dynamic> {
+    let mut value = serde_json::to_value(pea)?;
+    if let Some(map) = value.as_object_mut() {
+        for field in LIST_FIELDS {
+            map.entry(field).or_insert_with(|| serde_json::json!([]));
+        }
+        for field in OPTIONAL_FIELDS {
+            map.entry(field).or_insert(serde_json::Value::Null);
+        }
+        map.insert("body".to_string(), pea.body.to_string().into());
+    }
+    rhai::serde::to_dynamic(&value).map_err(|e| PeasError::Storage(e.to_string()))
+}
+
+/// Read the script map back, keeping the ID and timestamps of `original`
+fn from_script(value: &Dynamic, original: &Pea) -> std::result::Result<Pea, String> {
+    let mut value: serde_json::Value =
+        rhai::serde::from_dynamic(value).map_err(|e| format!("`pea` is no longer a pea: {}", e))?;
+    let body = value
+        .as_object_mut()
+        .and_then(|map| map.remove("body"))
+        .and_then(|body| body.as_str().map(str::to_string));
+    let mut pea: Pea =
+        serde_json::from_value(value).map_err(|e| format!("invalid pea after rule: {}", e))?;
+    if pea.id != original.id {
+        return Err("must not change the pea ID".to_string());
+    }
+    pea.body = body.map_or_else(|| original.body.clone(), Into::into);
+    pea.created = original.created;
+    pea.updated = original.updated;
+    Ok(pea)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaType};
+
+    fn rules(entries: &[(&str, Option<&str>, &str)]) -> Rules {
+        let settings = entries
+            .iter()
+            .map(|(name, when, then)| {
+                (
+                    name.to_string(),
+                    RuleSettings {
+                        on: vec![RuleEvent::Create],
+                        when: when.map(str::to_string),
+                        then: then.to_string(),
+                    },
+                )
+            })
+            .collect();
+        Rules::new(&settings)
+    }
+
+    #[test]
+    fn test_rules_chain_and_reject() {
+        let rules = rules(&[
+            (
+                "a-tag-bugs",
+                Some(r#"pea.type == "bug""#),
+                r#"pea.tags.push("triage")"#,
+            ),
+            (
+                "b-triage-body",
+                Some(r#""triage" in pea.tags"#),
+                r#"pea.body += "\n\nNeeds triage."; pea.priority = "high""#,
+            ),
+            (
+                "c-no-wontfix",
+                Some(r#"pea.title.contains("wontfix")"#),
+                r#"throw "not a bug""#,
+            ),
+        ]);
+        let bug = Pea::new("peas-bug01".to_string(), "Crash".to_string(), PeaType::Bug)
+            .with_body("Steps".to_string());
+
+        let changed = rules.apply(RuleEvent::Create, &bug).unwrap().unwrap();
+        assert_eq!(changed.tags, vec!["triage"]);
+        assert_eq!(changed.priority, PeaPriority::High);
+        assert_eq!(changed.body, "Steps\n\nNeeds triage.");
+        assert_eq!(changed.created, bug.created);
+
+        // Rules only run on their events
+        assert_eq!(rules.apply(RuleEvent::Update, &bug).unwrap(), None);
+
+        let task = Pea::new("peas-task1".to_string(), "Task".to_string(), PeaType::Task);
+        assert_eq!(rules.apply(RuleEvent::Create, &task).unwrap(), None);
+
+        let wontfix = Pea::new(
+            "peas-wf001".to_string(),
+            "wontfix".to_string(),
+            PeaType::Task,
+        );
+        let err = rules.apply(RuleEvent::Create, &wontfix).unwrap_err();
+        assert_eq!(err.to_string(), "Rule c-no-wontfix: not a bug");
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let err = rules(&[("broken", None, "pea.priority = ")])
+            .check()
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid then script in rule 'broken'")
+        );
+
+        let task = Pea::new("peas-task1".to_string(), "Task".to_string(), PeaType::Task);
+        let rules = rules(&[
+            ("bad-priority", None, r#"pea.priority = "whenever""#),
+            ("loop", None, "loop {}"),
+        ]);
+        let compiled = rules.compiled().unwrap();
+        assert!(rules.run(&compiled[0], &task).is_err());
+        assert!(rules.run(&compiled[1], &task).is_err());
+    }
+}
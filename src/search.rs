@@ -1,5 +1,13 @@
+use crate::aliases::Aliases;
 use crate::model::{Memory, Pea};
 use regex::Regex;
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+/// Words compared by fuzzy search
+static WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\w+").expect("word pattern should be valid"));
 
 /// Search query with optional field-specific and regex support
 #[derive(Debug, Clone)]
@@ -80,6 +88,74 @@ impl SearchQuery {
         Ok(SearchQuery::Simple(query.to_string()))
     }
 
+    /// Parse a query made of whitespace-separated terms that must all match.
+    ///
+    /// Each term is parsed like [`SearchQuery::parse`], so `tag:ui status:todo`
+    /// narrows by tag and status at the same time. In regex mode, unscoped
+    /// terms and scoped patterns are treated as regular expressions.
+    ///
+    /// ```
+    /// use peas::search::SearchQuery;
+    ///
+    /// let terms = SearchQuery::parse_terms("tag:ui status:todo", false).unwrap();
+    /// assert_eq!(terms.len(), 2);
+    ///
+    /// assert!(SearchQuery::parse_terms("fix|bug", true).is_ok());
+    /// assert!(SearchQuery::parse_terms("[bad", true).is_err());
+    /// ```
+    pub fn parse_terms(query: &str, regex: bool) -> Result<Vec<Self>, String> {
+        let terms = query
+            .split_whitespace()
+            .map(|term| {
+                if !regex {
+                    return Self::parse(term);
+                }
+                let new_regex =
+                    |p: &str| Regex::new(p).map_err(|e| format!("Invalid regex: {}", e));
+                if let Some((field_str, pattern)) = term.split_once(':')
+                    && let Ok(field) = field_str.parse::<SearchField>()
+                {
+                    return Ok(SearchQuery::Field {
+                        field,
+                        pattern: Box::new(SearchQuery::Regex(new_regex(pattern)?)),
+                    });
+                }
+                let pattern = term.strip_prefix("regex:").unwrap_or(term);
+                Ok(SearchQuery::Regex(new_regex(pattern)?))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if terms.is_empty() {
+            return Err("Empty query".to_string());
+        }
+        Ok(terms)
+    }
+
+    /// Regex pattern for highlighting this query's matches in free text.
+    ///
+    /// Only queries that can match titles, bodies or IDs produce a pattern;
+    /// scoped queries like `status:todo` have nothing to highlight.
+    pub fn highlight_pattern(&self) -> Option<String> {
+        match self {
+            SearchQuery::Simple(p) => Some(format!("(?i:{})", regex::escape(p))),
+            SearchQuery::Regex(r) => Some(format!("(?:{})", r.as_str())),
+            SearchQuery::Field { field, pattern } => match field {
+                SearchField::Title | SearchField::Body | SearchField::Id => {
+                    pattern.highlight_pattern()
+                }
+                _ => None,
+            },
+        }
+    }
+
+    /// Whether matching needs the body, which summaries do not load
+    pub fn needs_body(&self) -> bool {
+        match self {
+            SearchQuery::Simple(_) | SearchQuery::Regex(_) => true,
+            SearchQuery::Field { field, .. } => *field == SearchField::Body,
+        }
+    }
+
     /// Match against a Pea
     pub fn matches_pea(&self, pea: &Pea) -> bool {
         match self {
@@ -157,6 +233,19 @@ impl SearchQuery {
         }
     }
 
+    /// Match an ID the way `matches_pea` matches `pea.id`, e.g. a former ID of a pea
+    pub fn matches_id(&self, id: &str) -> bool {
+        match self {
+            SearchQuery::Simple(p) => id.to_lowercase().contains(&p.to_lowercase()),
+            SearchQuery::Regex(r) => r.is_match(id),
+            SearchQuery::Field {
+                field: SearchField::Id,
+                pattern,
+            } => pattern.matches_id(id),
+            SearchQuery::Field { .. } => false,
+        }
+    }
+
     /// Match against a Memory
     pub fn matches_memory(&self, memory: &Memory) -> bool {
         match self {
@@ -190,6 +279,420 @@ impl SearchQuery {
     }
 }
 
+/// A `peas search` query whose results are ranked by where their terms match
+///
+/// Whitespace separates terms, which must all match. Double quotes keep a
+/// phrase together (`"login page"`, `title:"login page"`), and a leading `-`
+/// excludes peas matching the term. Each term uses the syntax of
+/// [`SearchQuery::parse`].
+///
+/// ```
+/// use peas::search::RankedSearch;
+///
+/// assert!(RankedSearch::parse(r#"login "error page" -tag:wontfix"#).is_ok());
+/// assert!(RankedSearch::parse(r#""unterminated"#).is_err());
+/// assert!(RankedSearch::parse("  ").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct RankedSearch {
+    include: Vec<SearchQuery>,
+    exclude: Vec<SearchQuery>,
+    highlight: Option<Regex>,
+    fuzzy: bool,
+}
+
+/// A pea found by a [`RankedSearch`]
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub pea: Pea,
+    /// Higher for matches in more important fields
+    pub score: u32,
+    /// Context around the first match in the body
+    pub snippet: Option<Snippet>,
+}
+
+/// An excerpt of a body with the byte ranges of the matches in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub text: String,
+    pub matches: Vec<Range<usize>>,
+}
+
+/// Fields a term is scored on; a title match outweighs matches in all others
+const FIELD_WEIGHTS: [(SearchField, u32); 4] = [
+    (SearchField::Title, 8),
+    (SearchField::Tag, 4),
+    (SearchField::Id, 2),
+    (SearchField::Body, 1),
+];
+
+/// Similarity from which a word counts as a fuzzy match of a term
+pub const FUZZY_THRESHOLD: f32 = 0.5;
+
+/// Characters of context kept before the first match in a snippet
+const SNIPPET_BEFORE: usize = 30;
+/// Characters a snippet spans in total, at most
+const SNIPPET_LEN: usize = 120;
+
+impl RankedSearch {
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for (negated, quoted, term) in split_terms(query)? {
+            let parsed = if quoted {
+                SearchQuery::Simple(term)
+            } else {
+                SearchQuery::parse(&term)?
+            };
+            if negated {
+                exclude.push(parsed);
+            } else {
+                include.push(parsed);
+            }
+        }
+        if include.is_empty() && exclude.is_empty() {
+            return Err("Empty query".to_string());
+        }
+
+        let patterns: Vec<String> = include
+            .iter()
+            .filter_map(SearchQuery::highlight_pattern)
+            .collect();
+        let highlight = (!patterns.is_empty())
+            .then(|| Regex::new(&patterns.join("|")).ok())
+            .flatten();
+        Ok(Self {
+            include,
+            exclude,
+            highlight,
+            fuzzy: false,
+        })
+    }
+
+    /// Match single words fuzzily, tolerating typos (see [`fuzzy_similarity`])
+    ///
+    /// Phrases, regexes, exclusions and filters like `status:todo` still match
+    /// exactly.
+    pub fn with_fuzzy(mut self, fuzzy: bool) -> Self {
+        self.fuzzy = fuzzy;
+        self
+    }
+
+    /// Whether any term matches on the body, see [`SearchQuery::needs_body`]
+    pub fn needs_body(&self) -> bool {
+        self.include
+            .iter()
+            .chain(&self.exclude)
+            .any(SearchQuery::needs_body)
+    }
+
+    /// The score of a pea, or None if it does not match
+    ///
+    /// `aliases` are former IDs of the pea, matched like its ID.
+    pub fn score(&self, pea: &Pea, aliases: &[&str]) -> Option<u32> {
+        let matches = |query: &SearchQuery| {
+            query.matches_pea(pea) || aliases.iter().any(|id| query.matches_id(id))
+        };
+        if self.exclude.iter().any(matches) {
+            return None;
+        }
+        self.include.iter().try_fold(0, |total, query| {
+            let weight = match self.fuzzy.then(|| fuzzy_term(query)).flatten() {
+                Some((term, fields)) => fuzzy_term_weight(term, fields, pea, aliases),
+                None => term_weight(query, pea, aliases),
+            };
+            (weight > 0).then_some(total + weight)
+        })
+    }
+
+    /// Matching peas, best first; equal scores keep the order of `peas`
+    pub fn rank(&self, peas: Vec<Pea>, aliases: &Aliases) -> Vec<SearchHit> {
+        let mut hits: Vec<SearchHit> = peas
+            .into_iter()
+            .filter_map(|pea| {
+                let former: Vec<&str> = aliases.of(&pea.id).collect();
+                let score = self.score(&pea, &former)?;
+                let snippet = self.snippet(&pea.body);
+                Some(SearchHit {
+                    pea,
+                    score,
+                    snippet,
+                })
+            })
+            .collect();
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.score));
+        hits
+    }
+
+    /// Context around the first match in `text`, on a single line
+    pub fn snippet(&self, text: &str) -> Option<Snippet> {
+        let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut found: Vec<Range<usize>> = self
+            .highlight
+            .iter()
+            .flat_map(|highlight| highlight.find_iter(&flat))
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect();
+        if self.fuzzy {
+            for (term, _) in self.include.iter().filter_map(fuzzy_term) {
+                found.extend(fuzzy_matches(term, &flat));
+            }
+            found.sort_by_key(|range| (range.start, std::cmp::Reverse(range.end)));
+            found.dedup_by(|later, earlier| later.start < earlier.end);
+        }
+        let first = found.first()?.clone();
+
+        let start = floor_char_boundary(&flat, first.start.saturating_sub(SNIPPET_BEFORE));
+        let end = floor_char_boundary(&flat, (start + SNIPPET_LEN).max(first.end));
+        let (lead, trail) = (
+            if start > 0 { "…" } else { "" },
+            if end < flat.len() { "…" } else { "" },
+        );
+        let excerpt = &flat[start..end];
+        let shift = lead.len() as isize - start as isize;
+        let matches = found
+            .into_iter()
+            .filter(|range| range.start >= start && range.end <= end)
+            .map(|range| {
+                (range.start as isize + shift) as usize..(range.end as isize + shift) as usize
+            })
+            .collect();
+        Some(Snippet {
+            text: format!("{}{}{}", lead, excerpt, trail),
+            matches,
+        })
+    }
+}
+
+impl Snippet {
+    /// The text with every match wrapped in `open` and `close`
+    pub fn marked(&self, open: &str, close: &str) -> String {
+        let mut marked = String::new();
+        let mut last = 0;
+        for range in &self.matches {
+            marked.push_str(&self.text[last..range.start]);
+            marked.push_str(open);
+            marked.push_str(&self.text[range.clone()]);
+            marked.push_str(close);
+            last = range.end;
+        }
+        marked.push_str(&self.text[last..]);
+        marked
+    }
+}
+
+/// Split a query into (excluded, quoted, text) terms
+fn split_terms(query: &str) -> Result<Vec<(bool, bool, String)>, String> {
+    let mut terms = Vec::new();
+    let mut chars = query.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Ok(terms);
+        };
+        let mut negated = false;
+        if first == '-' {
+            chars.next();
+            negated = chars.peek().is_some_and(|c| !c.is_whitespace());
+        }
+        let quoted = chars.peek() == Some(&'"');
+        let mut term = String::new();
+        let mut in_quotes = false;
+        while let Some(c) = chars.next_if(|c| in_quotes || !c.is_whitespace()) {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else {
+                term.push(c);
+            }
+        }
+        if in_quotes {
+            return Err("Unterminated quote".to_string());
+        }
+        if !negated && first == '-' {
+            term.insert(0, '-');
+        }
+        if !term.is_empty() {
+            terms.push((negated, quoted, term));
+        }
+    }
+}
+
+/// Weight of a term for a pea: the fields it matches, or 1 for other filters
+fn term_weight(query: &SearchQuery, pea: &Pea, aliases: &[&str]) -> u32 {
+    let field_matches = |field: SearchField, pattern: &SearchQuery| match field {
+        SearchField::Id => {
+            pattern.matches_id(&pea.id) || aliases.iter().any(|id| pattern.matches_id(id))
+        }
+        _ => SearchQuery::Field {
+            field,
+            pattern: Box::new(pattern.clone()),
+        }
+        .matches_pea(pea),
+    };
+    match query {
+        SearchQuery::Field { field, pattern } => {
+            match FIELD_WEIGHTS.iter().find(|(f, _)| f == field) {
+                Some((_, weight)) => {
+                    if field_matches(*field, pattern) {
+                        *weight
+                    } else {
+                        0
+                    }
+                }
+                None => u32::from(query.matches_pea(pea)),
+            }
+        }
+        _ => FIELD_WEIGHTS
+            .iter()
+            .filter(|(field, _)| field_matches(*field, query))
+            .map(|(_, weight)| weight)
+            .sum(),
+    }
+}
+
+/// How closely `term` matches `text`, from 0 to 1, ignoring case
+///
+/// Text containing the term scores 1. Otherwise the term is compared with each
+/// word of the text by the trigrams they share, so a typo such as
+/// `authentcation` still finds "authentication".
+///
+/// ```
+/// use peas::search::fuzzy_similarity;
+///
+/// assert_eq!(fuzzy_similarity("auth", "OAuth login"), 1.0);
+/// assert!(fuzzy_similarity("authentcation", "Fix authentication") > 0.5);
+/// assert!(fuzzy_similarity("parser", "Fix authentication") < 0.5);
+/// ```
+pub fn fuzzy_similarity(term: &str, text: &str) -> f32 {
+    let term = term.to_lowercase();
+    if text.to_lowercase().contains(&term) {
+        return 1.0;
+    }
+    let trigrams = trigrams(&term);
+    WORD.find_iter(text)
+        .map(|word| word_similarity(&term, &trigrams, word.as_str()))
+        .fold(0.0, f32::max)
+}
+
+/// Words of the text matching `term` fuzzily, by byte range
+fn fuzzy_matches(term: &str, text: &str) -> Vec<Range<usize>> {
+    let term = term.to_lowercase();
+    let trigrams = trigrams(&term);
+    WORD.find_iter(text)
+        .filter(|word| word_similarity(&term, &trigrams, word.as_str()) >= FUZZY_THRESHOLD)
+        .map(|word| word.range())
+        .collect()
+}
+
+/// Dice coefficient of the trigrams of a lowercase term and a word
+fn word_similarity(term: &str, term_trigrams: &HashSet<String>, word: &str) -> f32 {
+    let word = word.to_lowercase();
+    if word.contains(term) {
+        return 1.0;
+    }
+    // Words of a very different length cannot come close
+    let (a, b) = (term.chars().count(), word.chars().count());
+    if a.abs_diff(b) > a.max(b) / 2 + 1 {
+        return 0.0;
+    }
+    let word_trigrams = trigrams(&word);
+    let shared = term_trigrams.intersection(&word_trigrams).count();
+    let dice = 2.0 * shared as f32 / (term_trigrams.len() + word_trigrams.len()) as f32;
+    // A typo leaves short words few trigrams in common, so edits count there
+    if a.max(b) <= SHORT_WORD {
+        let edits = edit_distance(term, &word) as f32;
+        return dice.max(1.0 - edits / a.max(b) as f32);
+    }
+    dice
+}
+
+/// Words up to this many characters are also compared by edit distance
+const SHORT_WORD: usize = 5;
+
+/// Insertions, deletions, substitutions and swaps of adjacent characters
+/// turning `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Trigrams of a word padded with spaces, so short words have some
+fn trigrams(word: &str) -> HashSet<String> {
+    let padded: Vec<char> = format!("  {} ", word).chars().collect();
+    padded.windows(3).map(|w| w.iter().collect()).collect()
+}
+
+/// A single word to match fuzzily, with the fields it is scored on
+fn fuzzy_term(query: &SearchQuery) -> Option<(&str, &[(SearchField, u32)])> {
+    let is_word = |p: &str| !p.contains(char::is_whitespace);
+    match query {
+        SearchQuery::Simple(p) if is_word(p) => Some((p, &FIELD_WEIGHTS[..])),
+        SearchQuery::Field { field, pattern } => match pattern.as_ref() {
+            SearchQuery::Simple(p) if is_word(p) => {
+                let weight = FIELD_WEIGHTS.iter().find(|(f, _)| f == field)?;
+                Some((p, std::slice::from_ref(weight)))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Weight of a fuzzy term: field weights scaled by how well it matches
+fn fuzzy_term_weight(
+    term: &str,
+    fields: &[(SearchField, u32)],
+    pea: &Pea,
+    aliases: &[&str],
+) -> u32 {
+    fields
+        .iter()
+        .map(|(field, weight)| {
+            let texts: Vec<&str> = match field {
+                SearchField::Title => vec![&pea.title],
+                SearchField::Body => vec![&pea.body],
+                SearchField::Tag => pea.tags.iter().map(String::as_str).collect(),
+                SearchField::Id => std::iter::once(pea.id.as_str())
+                    .chain(aliases.iter().copied())
+                    .collect(),
+                _ => Vec::new(),
+            };
+            let similarity = texts
+                .into_iter()
+                .map(|text| fuzzy_similarity(term, text))
+                .fold(0.0, f32::max);
+            if similarity >= FUZZY_THRESHOLD {
+                (*weight as f32 * similarity * 10.0).round() as u32
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
 impl std::str::FromStr for SearchField {
     type Err = String;
 
@@ -218,8 +721,7 @@ mod tests {
             "Fix critical bug in parser".to_string(),
             PeaType::Bug,
         );
-        pea.body =
-            "The parser crashes on malformed input.\nNeed to add error handling.".to_string();
+        pea.body = "The parser crashes on malformed input.\nNeed to add error handling.".into();
         pea.tags = vec!["bug".to_string(), "parser".to_string()];
         pea.status = PeaStatus::InProgress;
         pea.priority = PeaPriority::Critical;
@@ -443,6 +945,60 @@ mod tests {
         assert!(query.matches_memory(&memory));
     }
 
+    #[test]
+    fn test_parse_terms_scoped() {
+        let pea = create_test_pea();
+
+        let terms = SearchQuery::parse_terms("tag:parser status:in-progress", false).unwrap();
+        assert!(terms.iter().all(|q| q.matches_pea(&pea)));
+
+        let terms = SearchQuery::parse_terms("tag:parser type:feature", false).unwrap();
+        assert!(!terms.iter().all(|q| q.matches_pea(&pea)));
+
+        assert!(SearchQuery::parse_terms("   ", false).is_err());
+    }
+
+    #[test]
+    fn test_parse_terms_regex_mode() {
+        let pea = create_test_pea();
+
+        let terms = SearchQuery::parse_terms("crash(es|ed)", true).unwrap();
+        assert!(terms.iter().all(|q| q.matches_pea(&pea)));
+
+        let terms = SearchQuery::parse_terms("title:^Fix", true).unwrap();
+        assert!(terms.iter().all(|q| q.matches_pea(&pea)));
+
+        let terms = SearchQuery::parse_terms("title:^parser", true).unwrap();
+        assert!(!terms.iter().all(|q| q.matches_pea(&pea)));
+    }
+
+    #[test]
+    fn test_highlight_pattern() {
+        let query = SearchQuery::parse("a.b").unwrap();
+        let re = Regex::new(&query.highlight_pattern().unwrap()).unwrap();
+        assert!(re.is_match("see A.B here"));
+        assert!(!re.is_match("axb"));
+
+        let query = SearchQuery::parse("body:regex:cra.h").unwrap();
+        assert!(query.highlight_pattern().is_some());
+
+        let query = SearchQuery::parse("status:todo").unwrap();
+        assert!(query.highlight_pattern().is_none());
+    }
+
+    #[test]
+    fn test_needs_body() {
+        assert!(SearchQuery::parse("login").unwrap().needs_body());
+        assert!(SearchQuery::parse("body:crash").unwrap().needs_body());
+        assert!(!SearchQuery::parse("status:todo").unwrap().needs_body());
+        assert!(
+            !RankedSearch::parse("title:login tag:ui")
+                .unwrap()
+                .needs_body()
+        );
+        assert!(RankedSearch::parse("tag:ui -crash").unwrap().needs_body());
+    }
+
     #[test]
     fn test_colon_in_simple_query_with_unknown_field() {
         // "http://example.com" has a colon but "http" isn't a field name
@@ -454,4 +1010,107 @@ mod tests {
             _ => panic!("Expected simple query"),
         }
     }
+
+    #[test]
+    fn test_ranked_search_orders_by_field() {
+        let pea = |id: &str, title: &str, tags: &[&str], body: &str| {
+            let mut pea = Pea::new(id.to_string(), title.to_string(), PeaType::Task)
+                .with_body(body.to_string());
+            pea.tags = tags.iter().map(|t| t.to_string()).collect();
+            pea
+        };
+        let peas = vec![
+            pea("peas-aaaaa", "Refactor", &[], "The login form is slow"),
+            pea("peas-bbbbb", "Cleanup", &["login"], ""),
+            pea("peas-ccccc", "Login page", &[], ""),
+            pea("peas-ddddd", "Unrelated", &[], ""),
+        ];
+        let query = RankedSearch::parse("login").unwrap();
+        let hits = query.rank(peas.clone(), &Aliases::default());
+        let ids: Vec<&str> = hits.iter().map(|h| h.pea.id.as_str()).collect();
+        assert_eq!(ids, ["peas-ccccc", "peas-bbbbb", "peas-aaaaa"]);
+
+        let query = RankedSearch::parse(r#""login form" -tag:login"#).unwrap();
+        let hits = query.rank(peas.clone(), &Aliases::default());
+        assert_eq!(hits.len(), 1);
+        let snippet = hits[0].snippet.as_ref().unwrap();
+        assert_eq!(snippet.marked("[", "]"), "The [login form] is slow");
+
+        // Quoted text is a phrase, not a field or an exclusion
+        assert!(query.score(&peas[2], &[]).is_none());
+        let quoted = RankedSearch::parse(r#""-tag:login""#).unwrap();
+        assert!(quoted.score(&peas[1], &[]).is_none());
+    }
+
+    #[test]
+    fn test_snippet_keeps_context_around_the_match() {
+        let body = format!(
+            "{}\n\nThe crash happens in the parser.\n{}",
+            "x ".repeat(50),
+            "y ".repeat(80)
+        );
+        let query = RankedSearch::parse("crash").unwrap();
+        let snippet = query.snippet(&body).unwrap();
+        assert!(snippet.text.starts_with('…') && snippet.text.ends_with('…'));
+        assert!(snippet.text.chars().count() <= SNIPPET_LEN + 2);
+        assert_eq!(&snippet.text[snippet.matches[0].clone()], "crash");
+        assert!(!snippet.text.contains('\n'));
+
+        assert!(
+            RankedSearch::parse("status:todo")
+                .unwrap()
+                .snippet(&body)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_search_tolerates_typos() {
+        let mut pea = Pea::new(
+            "peas-aaaaa".to_string(),
+            "Fix authentication".to_string(),
+            PeaType::Bug,
+        )
+        .with_body("Tokens expire during the authentication handshake.".to_string());
+        pea.tags = vec!["security".to_string()];
+
+        let query = RankedSearch::parse("authentcation").unwrap();
+        assert!(query.score(&pea, &[]).is_none());
+        let query = query.with_fuzzy(true);
+        let fuzzy = query.score(&pea, &[]).unwrap();
+        let exact = RankedSearch::parse("authentication")
+            .unwrap()
+            .with_fuzzy(true)
+            .score(&pea, &[])
+            .unwrap();
+        assert!(exact > fuzzy);
+        assert_eq!(
+            query.snippet(&pea.body).unwrap().marked("[", "]"),
+            "Tokens expire during the [authentication] handshake."
+        );
+
+        // Short words with a typo share few trigrams but are still found
+        let task = Pea::new(
+            "peas-bbbbb".to_string(),
+            "Task one".to_string(),
+            PeaType::Task,
+        );
+        assert!(fuzzy_similarity("tsak", "Task one") >= FUZZY_THRESHOLD);
+        let short = RankedSearch::parse("tak one").unwrap().with_fuzzy(true);
+        assert!(short.score(&task, &[]).is_some());
+        let unrelated = RankedSearch::parse("bug").unwrap().with_fuzzy(true);
+        assert!(unrelated.score(&task, &[]).is_none());
+
+        // Scoped words are fuzzy too, filters and phrases stay exact
+        let scoped = RankedSearch::parse("tag:securty").unwrap().with_fuzzy(true);
+        assert!(scoped.score(&pea, &[]).is_some());
+        let status = RankedSearch::parse("status:tdo").unwrap().with_fuzzy(true);
+        assert!(status.score(&pea, &[]).is_none());
+        let phrase = RankedSearch::parse(r#""fix authentcation""#)
+            .unwrap()
+            .with_fuzzy(true);
+        assert!(phrase.score(&pea, &[]).is_none());
+        let unrelated = RankedSearch::parse("parser").unwrap().with_fuzzy(true);
+        assert!(unrelated.score(&pea, &[]).is_none());
+    }
 }
@@ -0,0 +1,498 @@
+//! Semantic search over embedding vectors.
+//!
+//! Every pea's title, tags and body are turned into a vector by an
+//! [`Embedder`], and queries are ranked by cosine similarity to them. Vectors
+//! are kept in `.peas/.index/vectors/`, one file per pea, and only computed
+//! again when the pea's text or the model changes.
+//!
+//! The `local` provider needs no model download or network: it hashes words
+//! and character trigrams into a fixed-size vector, which finds peas sharing
+//! vocabulary and word stems. The `api` provider calls an OpenAI-compatible
+//! `/embeddings` endpoint, such as a local Ollama or a hosted model, for
+//! similarity in meaning rather than wording.
+
+use crate::config::{EmbeddingProvider, SemanticSettings};
+use crate::error::{PeasError, Result};
+use crate::http::http_client;
+use crate::local::ignore_dir;
+use crate::model::Pea;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Directory under `.peas/` for derived indexes, kept out of git
+pub const INDEX_DIR: &str = ".index";
+
+/// Directory under [`INDEX_DIR`] with one vector file per pea
+pub const VECTORS_DIR: &str = "vectors";
+
+/// Results of a semantic search or `similar` unless a limit is given
+pub const DEFAULT_LIMIT: usize = 10;
+
+/// Texts sent to an embeddings API per request
+const BATCH_SIZE: usize = 64;
+
+/// Words too common to say anything about a pea
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "has", "in", "is", "it", "of",
+    "on", "or", "that", "the", "this", "to", "was", "with",
+];
+
+static WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\w+").expect("word pattern should be valid"));
+
+/// Turns texts into vectors
+pub trait Embedder {
+    /// Identifies the model, so vectors made by another one are recomputed
+    fn model(&self) -> String;
+
+    /// One vector per text, in order
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>>;
+}
+
+/// The embedder configured in `[semantic]`
+pub fn embedder(settings: &SemanticSettings) -> Result<Box<dyn Embedder>> {
+    match settings.provider {
+        EmbeddingProvider::Local => Ok(Box::new(LocalEmbedder::new(settings.dimensions))),
+        EmbeddingProvider::Api => {
+            let (Some(url), Some(model)) = (&settings.url, &settings.model) else {
+                return Err(PeasError::Config(
+                    "semantic.provider 'api' needs semantic.url and semantic.model".to_string(),
+                ));
+            };
+            let api_key = match &settings.api_key_env {
+                Some(var) => Some(std::env::var(var).map_err(|_| {
+                    PeasError::Config(format!("semantic.api_key_env: {} is not set", var))
+                })?),
+                None => None,
+            };
+            Ok(Box::new(ApiEmbedder::new(url, model, api_key)?))
+        }
+    }
+}
+
+/// Feature hashing of words and character trigrams, computed in-process
+///
+/// ```
+/// use peas::semantic::{Embedder, LocalEmbedder, cosine};
+///
+/// let embedder = LocalEmbedder::new(256);
+/// let v = embedder
+///     .embed(&[
+///         "login fails after password reset".to_string(),
+///         "cannot log in once the password was reset".to_string(),
+///         "dark mode colors".to_string(),
+///     ])
+///     .unwrap();
+/// assert!(cosine(&v[0], &v[1]) > cosine(&v[0], &v[2]));
+/// ```
+pub struct LocalEmbedder {
+    dimensions: usize,
+}
+
+impl LocalEmbedder {
+    pub fn new(dimensions: usize) -> Self {
+        Self { dimensions }
+    }
+
+    fn embed_one(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0; self.dimensions];
+        let mut add = |feature: &str, weight: f32| {
+            let hash = fnv1a(feature.as_bytes());
+            let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+            vector[(hash % self.dimensions as u64) as usize] += sign * weight;
+        };
+        for word in WORD.find_iter(&text.to_lowercase()) {
+            let word = word.as_str();
+            if STOPWORDS.contains(&word) {
+                continue;
+            }
+            add(word, 1.0);
+            // Trigrams of the padded word relate different forms of it
+            let padded: Vec<char> = format!(" {} ", word).chars().collect();
+            for trigram in padded.windows(3) {
+                add(&trigram.iter().collect::<String>(), 0.5);
+            }
+        }
+        normalize(&mut vector);
+        vector
+    }
+}
+
+impl Embedder for LocalEmbedder {
+    fn model(&self) -> String {
+        format!("local-{}", self.dimensions)
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|text| self.embed_one(text)).collect())
+    }
+}
+
+/// 64-bit FNV-1a, stable across builds unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// An OpenAI-compatible `/embeddings` endpoint
+pub struct ApiEmbedder {
+    client: reqwest::blocking::Client,
+    url: String,
+    model: String,
+    api_key: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl ApiEmbedder {
+    pub fn new(url: &str, model: &str, api_key: Option<String>) -> Result<Self> {
+        let client = http_client(std::time::Duration::from_secs(60))
+            .map_err(|e| PeasError::Embedding(e.to_string()))?;
+        Ok(Self {
+            client,
+            url: url.to_string(),
+            model: model.to_string(),
+            api_key,
+        })
+    }
+
+    fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self.client.post(&self.url).json(&serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        }));
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        let response = request
+            .send()
+            .map_err(|e| PeasError::Embedding(format!("{}: {}", self.url, e)))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(PeasError::Embedding(format!(
+                "{} returned {}",
+                self.url, status
+            )));
+        }
+        let mut response: EmbeddingResponse = response
+            .json()
+            .map_err(|e| PeasError::Embedding(format!("{}: {}", self.url, e)))?;
+        if response.data.len() != texts.len() {
+            return Err(PeasError::Embedding(format!(
+                "{} returned {} embeddings for {} texts",
+                self.url,
+                response.data.len(),
+                texts.len()
+            )));
+        }
+        response.data.sort_by_key(|d| d.index);
+        Ok(response
+            .data
+            .into_iter()
+            .map(|d| {
+                let mut vector = d.embedding;
+                normalize(&mut vector);
+                vector
+            })
+            .collect())
+    }
+}
+
+impl Embedder for ApiEmbedder {
+    fn model(&self) -> String {
+        format!("api-{}", self.model)
+    }
+
+    fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(BATCH_SIZE) {
+            vectors.extend(self.embed_batch(batch)?);
+        }
+        Ok(vectors)
+    }
+}
+
+/// Scale `vector` to unit length, leaving a zero vector as it is
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+/// Cosine similarity of two vectors, 0 when either is zero or their lengths differ
+pub fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norms =
+        a.iter().map(|x| x * x).sum::<f32>().sqrt() * b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norms == 0.0 { 0.0 } else { dot / norms }
+}
+
+/// The text of a pea that is embedded
+fn pea_text(pea: &Pea) -> String {
+    format!("{}\n{}\n{}", pea.title, pea.tags.join(" "), pea.body)
+}
+
+/// A pea's vector as stored in `.peas/.index/vectors/<id>.json`
+#[derive(Serialize, Deserialize)]
+struct StoredVector {
+    model: String,
+    /// SHA-256 of the embedded text
+    digest: String,
+    vector: Vec<f32>,
+}
+
+/// A pea with its similarity to a query or another pea, from -1 to 1
+#[derive(Debug, Clone)]
+pub struct SemanticHit {
+    pub pea: Pea,
+    pub similarity: f32,
+}
+
+/// Pea vectors under `.peas/.index/`, kept up to date with the peas
+pub struct SemanticIndex {
+    dir: PathBuf,
+    embedder: Box<dyn Embedder>,
+}
+
+impl SemanticIndex {
+    /// The index of the project at `data_path`, with the embedder from `settings`
+    pub fn new(data_path: &Path, settings: &SemanticSettings) -> Result<Self> {
+        Ok(Self::with_embedder(data_path, embedder(settings)?))
+    }
+
+    pub fn with_embedder(data_path: &Path, embedder: Box<dyn Embedder>) -> Self {
+        Self {
+            dir: data_path.join(INDEX_DIR),
+            embedder,
+        }
+    }
+
+    fn vector_path(&self, id: &str) -> PathBuf {
+        self.dir.join(VECTORS_DIR).join(format!("{}.json", id))
+    }
+
+    /// The vectors of `peas` in order, embedding only new or changed ones
+    ///
+    /// `peas` are all peas of the project with their bodies loaded; stored
+    /// vectors of any others are removed.
+    pub fn vectors(&self, peas: &[Pea]) -> Result<Vec<Vec<f32>>> {
+        let model = self.embedder.model();
+        let mut vectors = Vec::with_capacity(peas.len());
+        let mut missing = Vec::new();
+        for (i, pea) in peas.iter().enumerate() {
+            let digest = format!("{:x}", Sha256::digest(pea_text(pea).as_bytes()));
+            let stored = std::fs::read_to_string(self.vector_path(&pea.id))
+                .ok()
+                .and_then(|content| serde_json::from_str::<StoredVector>(&content).ok())
+                .filter(|stored| stored.model == model && stored.digest == digest);
+            match stored {
+                Some(stored) => vectors.push(stored.vector),
+                None => {
+                    vectors.push(Vec::new());
+                    missing.push((i, digest));
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            tracing::debug!(count = missing.len(), model = %model, "Embedding peas");
+            let texts: Vec<String> = missing.iter().map(|(i, _)| pea_text(&peas[*i])).collect();
+            let embedded = self.embedder.embed(&texts)?;
+            ignore_dir(&self.dir)?;
+            std::fs::create_dir_all(self.dir.join(VECTORS_DIR))?;
+            for ((i, digest), vector) in missing.into_iter().zip(embedded) {
+                let stored = StoredVector {
+                    model: model.clone(),
+                    digest,
+                    vector,
+                };
+                std::fs::write(
+                    self.vector_path(&peas[i].id),
+                    serde_json::to_string(&stored)?,
+                )?;
+                vectors[i] = stored.vector;
+            }
+        }
+
+        self.prune(peas)?;
+        Ok(vectors)
+    }
+
+    /// Remove the vectors of peas that no longer exist
+    fn prune(&self, peas: &[Pea]) -> Result<()> {
+        let dir = self.dir.join(VECTORS_DIR);
+        if !dir.exists() {
+            return Ok(());
+        }
+        let ids: HashSet<&str> = peas.iter().map(|p| p.id.as_str()).collect();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let stale = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .is_some_and(|id| !ids.contains(id));
+            if stale {
+                std::fs::remove_file(path)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The `limit` peas closest in meaning to `query`, most similar first
+    pub fn search(&self, query: &str, peas: Vec<Pea>, limit: usize) -> Result<Vec<SemanticHit>> {
+        let vectors = self.vectors(&peas)?;
+        let query = self
+            .embedder
+            .embed(&[query.to_string()])?
+            .pop()
+            .unwrap_or_default();
+        Ok(rank(peas, &vectors, &query, None, limit))
+    }
+
+    /// The `limit` peas closest in meaning to the pea `id`, which must be among `peas`
+    pub fn similar(&self, id: &str, peas: Vec<Pea>, limit: usize) -> Result<Vec<SemanticHit>> {
+        let vectors = self.vectors(&peas)?;
+        let target = peas
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| PeasError::NotFound(id.to_string()))?;
+        let query = vectors[target].clone();
+        Ok(rank(peas, &vectors, &query, Some(id), limit))
+    }
+}
+
+/// Peas by similarity of their vectors to `query`, leaving out `skip` and unrelated peas
+fn rank(
+    peas: Vec<Pea>,
+    vectors: &[Vec<f32>],
+    query: &[f32],
+    skip: Option<&str>,
+    limit: usize,
+) -> Vec<SemanticHit> {
+    let mut hits: Vec<SemanticHit> = peas
+        .into_iter()
+        .zip(vectors)
+        .filter(|(pea, _)| Some(pea.id.as_str()) != skip)
+        .map(|(pea, vector)| SemanticHit {
+            similarity: cosine(query, vector),
+            pea,
+        })
+        .filter(|hit| hit.similarity > 0.0)
+        .collect();
+    hits.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    hits.truncate(limit);
+    hits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PeaType;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tempfile::TempDir;
+
+    /// The local embedder, counting how many texts it embedded
+    struct Counting {
+        inner: LocalEmbedder,
+        count: Rc<Cell<usize>>,
+    }
+
+    impl Embedder for Counting {
+        fn model(&self) -> String {
+            self.inner.model()
+        }
+
+        fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+            self.count.set(self.count.get() + texts.len());
+            self.inner.embed(texts)
+        }
+    }
+
+    fn pea(id: &str, title: &str, body: &str) -> Pea {
+        Pea::new(id.to_string(), title.to_string(), PeaType::Task).with_body(body.to_string())
+    }
+
+    fn peas() -> Vec<Pea> {
+        vec![
+            pea(
+                "peas-auth1",
+                "Login broken after password reset",
+                "Users cannot authenticate once they reset their password.",
+            ),
+            pea(
+                "peas-dark1",
+                "Dark mode colors",
+                "Choose a palette for the dark theme.",
+            ),
+            pea(
+                "peas-auth2",
+                "Session expires during authentication",
+                "The auth flow drops the session token.",
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_search_ranks_by_similarity() {
+        let dir = TempDir::new().unwrap();
+        let index = SemanticIndex::with_embedder(dir.path(), Box::new(LocalEmbedder::new(512)));
+
+        let hits = index.search("auth flow broken", peas(), 10).unwrap();
+        let ids: Vec<&str> = hits.iter().map(|h| h.pea.id.as_str()).collect();
+        assert_eq!(ids[0], "peas-auth2");
+        assert!(ids.contains(&"peas-auth1"));
+        assert!(hits.windows(2).all(|w| w[0].similarity >= w[1].similarity));
+
+        let similar = index.similar("peas-auth1", peas(), 1).unwrap();
+        assert_eq!(similar.len(), 1);
+        assert_eq!(similar[0].pea.id, "peas-auth2");
+        assert!(index.similar("peas-nope1", peas(), 1).is_err());
+    }
+
+    #[test]
+    fn test_vectors_are_reused_until_a_pea_changes() {
+        let dir = TempDir::new().unwrap();
+        let count = Rc::new(Cell::new(0));
+        let index = SemanticIndex::with_embedder(
+            dir.path(),
+            Box::new(Counting {
+                inner: LocalEmbedder::new(64),
+                count: count.clone(),
+            }),
+        );
+
+        index.vectors(&peas()).unwrap();
+        assert_eq!(count.get(), 3);
+        assert!(dir.path().join(".index/vectors/peas-auth1.json").exists());
+        assert!(dir.path().join(".index/.gitignore").exists());
+
+        index.vectors(&peas()).unwrap();
+        assert_eq!(count.get(), 3);
+
+        let mut changed = peas();
+        changed[1].title = "Light mode colors".to_string();
+        changed.pop();
+        index.vectors(&changed).unwrap();
+        assert_eq!(count.get(), 4);
+        assert!(!dir.path().join(".index/vectors/peas-auth2.json").exists());
+    }
+}
@@ -0,0 +1,306 @@
+use crate::config::PeasConfig;
+use crate::error::{PeasError, Result};
+use crate::git;
+use crate::history;
+use crate::model::Pea;
+use crate::storage::render_markdown;
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Directory under `.peas/` holding snapshots
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// The active peas at one point in time, stored as `.peas/snapshots/<label>.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub label: String,
+    pub created: DateTime<Utc>,
+    /// Git commit the project was at, if it is in a git repository
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// SHA-256 over the manifest, equal for snapshots of the same state
+    pub hash: String,
+    /// SHA-256 of each pea's markdown file, by ID
+    pub manifest: BTreeMap<String, String>,
+    pub peas: Vec<SnapshotPea>,
+}
+
+/// A pea with its body, which pea JSON otherwise leaves out
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotPea {
+    #[serde(flatten)]
+    pub pea: Pea,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub body: String,
+}
+
+impl Snapshot {
+    /// Record `peas` under `label`
+    pub fn new(label: &str, peas: &[Pea], commit: Option<String>) -> Result<Self> {
+        let mut manifest = BTreeMap::new();
+        for pea in peas {
+            let digest = Sha256::digest(render_markdown(pea)?.as_bytes());
+            manifest.insert(pea.id.clone(), format!("{:x}", digest));
+        }
+        let mut hasher = Sha256::new();
+        for (id, digest) in &manifest {
+            hasher.update(format!("{} {}\n", id, digest));
+        }
+        Ok(Self {
+            label: label.to_string(),
+            created: Utc::now(),
+            commit,
+            hash: format!("{:x}", hasher.finalize()),
+            manifest,
+            peas: peas
+                .iter()
+                .map(|pea| SnapshotPea {
+                    pea: pea.clone(),
+                    body: pea.body.to_string(),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn origin(&self) -> Origin {
+        Origin::Snapshot {
+            label: self.label.clone(),
+            created: self.created,
+        }
+    }
+
+    /// The recorded peas, bodies included
+    pub fn into_peas(self) -> Vec<Pea> {
+        self.peas
+            .into_iter()
+            .map(|SnapshotPea { pea, body }| pea.with_body(body))
+            .collect()
+    }
+}
+
+/// Snapshots in `.peas/snapshots/`, meant to be committed with the peas
+pub struct Snapshots {
+    dir: PathBuf,
+}
+
+impl Snapshots {
+    pub fn new(data_path: &Path) -> Self {
+        Self {
+            dir: data_path.join(SNAPSHOTS_DIR),
+        }
+    }
+
+    /// Store a snapshot; labels are unique
+    pub fn save(&self, snapshot: &Snapshot) -> Result<PathBuf> {
+        validate_label(&snapshot.label)?;
+        let path = self.path(&snapshot.label);
+        if path.exists() {
+            return Err(PeasError::validation(
+                "label",
+                format!("Snapshot '{}' already exists", snapshot.label),
+            ));
+        }
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(&path, serde_json::to_string_pretty(snapshot)?)?;
+        Ok(path)
+    }
+
+    pub fn get(&self, label: &str) -> Result<Option<Snapshot>> {
+        if validate_label(label).is_err() {
+            return Ok(None);
+        }
+        match std::fs::read_to_string(self.path(label)) {
+            Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Every snapshot, oldest first
+    pub fn list(&self) -> Result<Vec<Snapshot>> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Ok(Vec::new());
+        };
+        let mut snapshots = Vec::new();
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let content = std::fs::read_to_string(&path)?;
+                snapshots.push(serde_json::from_str::<Snapshot>(&content)?);
+            }
+        }
+        snapshots.sort_by_key(|s| s.created);
+        Ok(snapshots)
+    }
+
+    fn path(&self, label: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", label))
+    }
+}
+
+/// Default label for a snapshot taken now
+pub fn default_label() -> String {
+    Utc::now().format("%Y-%m-%d-%H%M%S").to_string()
+}
+
+fn validate_label(label: &str) -> Result<()> {
+    let valid = !label.is_empty()
+        && !label.starts_with('.')
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(PeasError::validation(
+            "label",
+            format!(
+                "Invalid snapshot label '{}', use letters, digits, '-', '_' and '.'",
+                label
+            ),
+        ))
+    }
+}
+
+/// Where a historical state was read from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum Origin {
+    Snapshot {
+        label: String,
+        created: DateTime<Utc>,
+    },
+    Commit {
+        hash: String,
+        date: DateTime<Utc>,
+    },
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::Snapshot { label, created } => write!(
+                f,
+                "snapshot '{}' ({})",
+                label,
+                created.format("%Y-%m-%d %H:%M")
+            ),
+            Origin::Commit { hash, date } => write!(
+                f,
+                "commit {} ({})",
+                &hash[..hash.len().min(7)],
+                date.format("%Y-%m-%d %H:%M")
+            ),
+        }
+    }
+}
+
+/// The active peas as they were at a snapshot label or a point in time
+///
+/// `as_of` is a snapshot label, a date (`YYYY-MM-DD`, meaning the end of that
+/// day in UTC) or an RFC 3339 timestamp. For a time, the newest of the last
+/// snapshot and the last commit touching `.peas/` at or before it is used, so
+/// snapshots fill in history that was never committed.
+pub fn peas_as_of(config: &PeasConfig, root: &Path, as_of: &str) -> Result<(Vec<Pea>, Origin)> {
+    let data_path = config.data_path(root);
+    let snapshots = Snapshots::new(&data_path);
+    if let Some(snapshot) = snapshots.get(as_of)? {
+        let origin = snapshot.origin();
+        return Ok((snapshot.into_peas(), origin));
+    }
+
+    let time = parse_time(as_of)?;
+    let snapshot = snapshots.list()?.into_iter().rfind(|s| s.created <= time);
+    // Outside a git repository there is only the snapshots to go by
+    let commit = git::last_commit_before(root, time, &data_path)
+        .ok()
+        .flatten();
+
+    match (snapshot, commit) {
+        (Some(snapshot), commit)
+            if commit
+                .as_ref()
+                .is_none_or(|(_, date)| snapshot.created >= *date) =>
+        {
+            let origin = snapshot.origin();
+            Ok((snapshot.into_peas(), origin))
+        }
+        (_, Some((hash, date))) => {
+            let mut peas: Vec<Pea> = history::peas_at(root, &data_path, &hash)?
+                .into_iter()
+                .filter_map(|(pea, archived)| (!archived).then_some(pea))
+                .collect();
+            peas.sort_by_key(|p| p.created);
+            Ok((peas, Origin::Commit { hash, date }))
+        }
+        _ => Err(PeasError::validation(
+            "as_of",
+            format!("No snapshot or commit of .peas/ at or before {}", as_of),
+        )),
+    }
+}
+
+/// A date (end of that day, UTC) or an RFC 3339 timestamp
+fn parse_time(value: &str) -> Result<DateTime<Utc>> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let end_of_day = NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN);
+        return Ok(date.and_time(end_of_day).and_utc());
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| {
+            PeasError::validation(
+                "as_of",
+                format!(
+                    "'{}' is not a snapshot label, a date (YYYY-MM-DD) or an RFC 3339 timestamp",
+                    value
+                ),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaStatus, PeaType};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_snapshot_roundtrip_and_as_of() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let snapshots = Snapshots::new(&config.data_path(temp_dir.path()));
+
+        let pea = Pea::new("peas-snap1".into(), "Snapshotted".into(), PeaType::Task)
+            .with_body("Details".into());
+        let first = Snapshot::new("sprint-1", std::slice::from_ref(&pea), None).unwrap();
+        snapshots.save(&first).unwrap();
+        assert!(snapshots.save(&first).is_err());
+        assert!(
+            snapshots
+                .save(&Snapshot::new("../escape", &[], None).unwrap())
+                .is_err()
+        );
+
+        // Same state, same hash
+        let again = Snapshot::new("again", std::slice::from_ref(&pea), None).unwrap();
+        assert_eq!(again.hash, first.hash);
+        let started = pea.clone().with_status(PeaStatus::InProgress);
+        let later = Snapshot::new("sprint-2", &[started], None).unwrap();
+        assert_ne!(later.hash, first.hash);
+        snapshots.save(&later).unwrap();
+
+        let (peas, origin) = peas_as_of(&config, temp_dir.path(), "sprint-1").unwrap();
+        assert_eq!(peas, vec![pea]);
+        assert!(matches!(origin, Origin::Snapshot { label, .. } if label == "sprint-1"));
+
+        // By time, the newest snapshot at or before it
+        let (peas, _) = peas_as_of(&config, temp_dir.path(), "2999-01-01").unwrap();
+        assert_eq!(peas[0].status, PeaStatus::InProgress);
+        assert!(peas_as_of(&config, temp_dir.path(), "2000-01-01").is_err());
+        assert!(peas_as_of(&config, temp_dir.path(), "last tuesday").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+use super::markdown::parse_markdown;
+use crate::error::{PeasError, Result};
+use crate::local::LocalState;
+use crate::model::{Body, Pea};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::time::SystemTime;
+
+/// Summary index of the active peas, in `.peas/local/`
+pub const INDEX_FILE: &str = "index.json";
+
+/// A ticket file as last seen, with its frontmatter
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    modified: SystemTime,
+    len: u64,
+    pea: Pea,
+}
+
+/// Frontmatter of every ticket file, by file name
+///
+/// A file is parsed again only when its modification time or size changed
+/// since it was indexed, so listing a large tracker reads one file instead of
+/// every ticket. Edits made outside peas are picked up the same way.
+#[derive(Default, Serialize, Deserialize)]
+pub(super) struct SummaryIndex {
+    files: BTreeMap<String, Entry>,
+}
+
+impl SummaryIndex {
+    /// The saved index; a missing or outdated one starts empty and is rebuilt
+    pub(super) fn load(local: &LocalState) -> Self {
+        local
+            .read(INDEX_FILE)
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn save(&self, local: &LocalState) -> Result<()> {
+        local.write(INDEX_FILE, &serde_json::to_string(self)?)
+    }
+
+    /// Catch up with the ticket files in `dir`, returning whether anything changed
+    pub(super) fn refresh(&mut self, dir: &Path, prefix: &str) -> Result<bool> {
+        let mut changed = false;
+        let mut seen = HashSet::new();
+        if dir.exists() {
+            for entry in std::fs::read_dir(dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !path.is_file() || !name.ends_with(".md") || !name.starts_with(prefix) {
+                    continue;
+                }
+                let metadata = entry.metadata()?;
+                let (modified, len) = (metadata.modified()?, metadata.len());
+                seen.insert(name.clone());
+                if self
+                    .files
+                    .get(&name)
+                    .is_some_and(|e| e.modified == modified && e.len == len)
+                {
+                    continue;
+                }
+
+                let parsed = std::fs::read_to_string(&path)
+                    .map_err(PeasError::from)
+                    .and_then(|content| parse_markdown(&content));
+                match parsed {
+                    Ok(mut pea) => {
+                        pea.body = Body::NotLoaded;
+                        self.files.insert(name, Entry { modified, len, pea });
+                        changed = true;
+                    }
+                    Err(e) => {
+                        tracing::warn!(path = %path.display(), error = %e, "Failed to parse pea file");
+                        changed |= self.files.remove(&name).is_some();
+                    }
+                }
+            }
+        }
+
+        let before = self.files.len();
+        self.files.retain(|name, _| seen.contains(name));
+        Ok(changed || self.files.len() != before)
+    }
+
+    /// The indexed peas with their bodies not loaded, in creation order like `list`
+    pub(super) fn peas(&self) -> Vec<Pea> {
+        let mut peas: Vec<Pea> = self
+            .files
+            .values()
+            .map(|e| Pea {
+                body: Body::NotLoaded,
+                ..e.pea.clone()
+            })
+            .collect();
+        peas.sort_by_key(|p| p.created);
+        peas
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::error::{PeasError, Result};
 use crate::model::{Memory, Pea};
+use serde::de::DeserializeOwned;
 
 const YAML_DELIMITER: &str = "---";
 const TOML_DELIMITER: &str = "+++";
@@ -41,7 +42,7 @@ pub fn detect_format(content: &str) -> Option<FrontmatterFormat> {
 /// Parses markdown content with auto-detected frontmatter format.
 pub fn parse_markdown(content: &str) -> Result<Pea> {
     let format = detect_format(content).ok_or_else(|| {
-        PeasError::Parse("Missing frontmatter delimiter (--- for YAML or +++ for TOML)".to_string())
+        PeasError::parse("Missing frontmatter delimiter (--- for YAML or +++ for TOML)".to_string())
     })?;
 
     parse_markdown_with_format(content, format)
@@ -53,7 +54,7 @@ pub fn parse_markdown_with_format(content: &str, format: FrontmatterFormat) -> R
     let delimiter = format.delimiter();
 
     if !content.starts_with(delimiter) {
-        return Err(PeasError::Parse(format!(
+        return Err(PeasError::parse(format!(
             "Expected {} frontmatter delimiter",
             match format {
                 FrontmatterFormat::Yaml => "YAML (---)",
@@ -62,32 +63,95 @@ pub fn parse_markdown_with_format(content: &str, format: FrontmatterFormat) -> R
         )));
     }
 
-    let after_first = &content[delimiter.len()..];
-    let end_index = after_first
-        .find(delimiter)
-        .ok_or_else(|| PeasError::Parse("Missing closing frontmatter delimiter".to_string()))?;
-
-    let frontmatter_content = after_first[..end_index].trim();
-    let body_start = delimiter.len() + end_index + delimiter.len();
-    let body = content[body_start..].trim().to_string();
-
-    let mut pea: Pea = match format {
-        FrontmatterFormat::Yaml => serde_yaml::from_str(frontmatter_content)?,
-        FrontmatterFormat::Toml => toml::from_str(frontmatter_content)
-            .map_err(|e| PeasError::Parse(format!("TOML parse error: {}", e)))?,
-    };
-    pea.body = body;
+    let (mut pea, body): (Pea, String) = split_frontmatter(content, format)?;
+    pea.body = body.into();
 
     Ok(pea)
 }
 
+/// Splits trimmed content into its deserialized frontmatter and the body.
+///
+/// The closing delimiter must stand on a line of its own, so delimiters inside
+/// titles or tags are left alone. If the first such line does not end valid
+/// frontmatter (e.g. it sits inside a multi-line string), later ones are tried
+/// before the first error is reported.
+fn split_frontmatter<T: DeserializeOwned>(
+    content: &str,
+    format: FrontmatterFormat,
+) -> Result<(T, String)> {
+    let delimiter = format.delimiter();
+    let after_first = &content[delimiter.len()..];
+
+    let mut first_error = None;
+    let mut offset = 0;
+    for line in after_first.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        if line.trim_end() != delimiter {
+            continue;
+        }
+
+        let raw = &after_first[..line_start];
+        let frontmatter_content = raw.trim();
+        // 1-based line of the first frontmatter line, for error locations
+        let first_line = content[..delimiter.len() + raw.len() - raw.trim_start().len()]
+            .matches('\n')
+            .count()
+            + 1;
+        let parsed = match format {
+            FrontmatterFormat::Yaml => {
+                serde_yaml::from_str(frontmatter_content).map_err(|e: serde_yaml::Error| {
+                    let message = e.to_string();
+                    // The location is reported separately, relative to the whole file
+                    let message = message
+                        .rsplit_once(" at line ")
+                        .map_or(message.as_str(), |(m, _)| m);
+                    PeasError::ParseError {
+                        message: format!("invalid YAML frontmatter: {}", message),
+                        path: None,
+                        line: e.location().map(|l| first_line + l.line() - 1),
+                    }
+                })
+            }
+            FrontmatterFormat::Toml => {
+                toml::from_str(frontmatter_content).map_err(|e: toml::de::Error| {
+                    PeasError::ParseError {
+                        message: format!("invalid TOML frontmatter: {}", e.message().trim()),
+                        path: None,
+                        line: e.span().map(|span| {
+                            first_line + frontmatter_content[..span.start].matches('\n').count()
+                        }),
+                    }
+                })
+            }
+        };
+        match parsed {
+            Ok(value) => return Ok((value, after_first[offset..].trim().to_string())),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    Err(first_error
+        .unwrap_or_else(|| PeasError::parse("Missing closing frontmatter delimiter".to_string())))
+}
+
 /// Renders a pea to markdown with TOML frontmatter (default).
 pub fn render_markdown(pea: &Pea) -> Result<String> {
     render_markdown_with_format(pea, FrontmatterFormat::Toml)
 }
 
 /// Renders a pea to markdown with the specified frontmatter format.
+///
+/// Fails for a pea whose body was not loaded, which would otherwise be lost.
 pub fn render_markdown_with_format(pea: &Pea, format: FrontmatterFormat) -> Result<String> {
+    if !pea.body.is_loaded() {
+        return Err(PeasError::Storage(format!(
+            "The body of {} was not loaded",
+            pea.id
+        )));
+    }
     let delimiter = format.delimiter();
 
     let frontmatter = match format {
@@ -96,7 +160,7 @@ pub fn render_markdown_with_format(pea: &Pea, format: FrontmatterFormat) -> Resu
             yaml.trim().to_string()
         }
         FrontmatterFormat::Toml => toml::to_string_pretty(pea)
-            .map_err(|e| PeasError::Parse(format!("TOML serialize error: {}", e)))?,
+            .map_err(|e| PeasError::parse(format!("TOML serialize error: {}", e)))?,
     };
 
     let mut output = String::new();
@@ -121,7 +185,7 @@ pub fn render_markdown_with_format(pea: &Pea, format: FrontmatterFormat) -> Resu
 /// Parses markdown content for a Memory with auto-detected frontmatter format.
 pub fn parse_markdown_memory(content: &str) -> Result<Memory> {
     let format = detect_format(content).ok_or_else(|| {
-        PeasError::Parse("Missing frontmatter delimiter (--- for YAML or +++ for TOML)".to_string())
+        PeasError::parse("Missing frontmatter delimiter (--- for YAML or +++ for TOML)".to_string())
     })?;
 
     parse_markdown_memory_with_format(content, format)
@@ -136,7 +200,7 @@ pub fn parse_markdown_memory_with_format(
     let delimiter = format.delimiter();
 
     if !content.starts_with(delimiter) {
-        return Err(PeasError::Parse(format!(
+        return Err(PeasError::parse(format!(
             "Expected {} frontmatter delimiter",
             match format {
                 FrontmatterFormat::Yaml => "YAML (---)",
@@ -145,20 +209,7 @@ pub fn parse_markdown_memory_with_format(
         )));
     }
 
-    let after_first = &content[delimiter.len()..];
-    let end_index = after_first
-        .find(delimiter)
-        .ok_or_else(|| PeasError::Parse("Missing closing frontmatter delimiter".to_string()))?;
-
-    let frontmatter_content = after_first[..end_index].trim();
-    let body_start = delimiter.len() + end_index + delimiter.len();
-    let body = content[body_start..].trim().to_string();
-
-    let mut memory: Memory = match format {
-        FrontmatterFormat::Yaml => serde_yaml::from_str(frontmatter_content)?,
-        FrontmatterFormat::Toml => toml::from_str(frontmatter_content)
-            .map_err(|e| PeasError::Parse(format!("TOML parse error: {}", e)))?,
-    };
+    let (mut memory, body): (Memory, String) = split_frontmatter(content, format)?;
     memory.content = body;
 
     Ok(memory)
@@ -174,7 +225,7 @@ pub fn render_markdown_memory(memory: &Memory, format: FrontmatterFormat) -> Res
             yaml.trim().to_string()
         }
         FrontmatterFormat::Toml => toml::to_string_pretty(memory)
-            .map_err(|e| PeasError::Parse(format!("TOML serialize error: {}", e)))?,
+            .map_err(|e| PeasError::parse(format!("TOML serialize error: {}", e)))?,
     };
 
     let mut output = String::new();
@@ -265,6 +316,26 @@ This is a TOML frontmatter body.
         assert_eq!(pea.body, "This is a TOML frontmatter body.");
     }
 
+    #[test]
+    fn test_parse_timestamps_with_offsets() {
+        let content = r#"+++
+id = "peas-time1"
+title = "Timestamps"
+type = "task"
+created = 2026-01-01T10:00:00+02:00
+updated = "2026-01-01 09:30:00-01:00"
++++
+"#;
+
+        let pea = parse_markdown(content).unwrap();
+        assert_eq!(pea.created.to_rfc3339(), "2026-01-01T08:00:00+00:00");
+        assert_eq!(pea.updated.to_rfc3339(), "2026-01-01T10:30:00+00:00");
+
+        let rendered = render_markdown_with_format(&pea, FrontmatterFormat::Toml).unwrap();
+        assert!(rendered.contains("created = \"2026-01-01T08:00:00Z\""));
+        assert_eq!(parse_markdown(&rendered).unwrap(), pea);
+    }
+
     #[test]
     fn test_render_yaml_markdown() {
         let pea = Pea::new(
@@ -438,9 +509,229 @@ updated = "2024-01-01T00:00:00Z"
         assert_eq!(parsed.content, "");
     }
 
+    #[test]
+    fn test_delimiters_inside_values_do_not_end_frontmatter() {
+        for (title, format) in [
+            ("C+++ interop", FrontmatterFormat::Toml),
+            ("---", FrontmatterFormat::Yaml),
+            ("multi\n+++\nline", FrontmatterFormat::Toml),
+        ] {
+            let pea = Pea::new("peas-abc".to_string(), title.to_string(), PeaType::Task)
+                .with_body("after".to_string());
+            let rendered = render_markdown_with_format(&pea, format).unwrap();
+            let parsed = parse_markdown(&rendered).unwrap();
+            assert_eq!(parsed.title, title);
+            assert_eq!(parsed.body, "after");
+        }
+    }
+
+    #[test]
+    fn test_parse_error_reports_file_line() {
+        let toml = "+++\nid = \"peas-abc\"\ntitle = \"T\"\ntype = \"task\"\nstatus = bogus\n+++\n";
+        match parse_markdown(toml).unwrap_err() {
+            PeasError::ParseError { line, message, .. } => {
+                assert_eq!(line, Some(5));
+                assert!(message.contains("TOML"));
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let yaml = "---\nid: peas-abc\ntitle: T\ntype: [\n---\n";
+        assert!(matches!(
+            parse_markdown(yaml).unwrap_err(),
+            PeasError::ParseError { line: Some(_), .. }
+        ));
+    }
+
     #[test]
     fn test_frontmatter_format_delimiter() {
         assert_eq!(FrontmatterFormat::Toml.delimiter(), "+++");
         assert_eq!(FrontmatterFormat::Yaml.delimiter(), "---");
     }
+
+    mod roundtrip {
+        use super::*;
+        use crate::model::{Claim, LinkKind, PeaLink, PeaPriority};
+        use chrono::{DateTime, TimeZone, Utc};
+        use proptest::prelude::*;
+
+        /// Text that likes to contain frontmatter delimiters and YAML/TOML syntax
+        fn tricky_text() -> impl Strategy<Value = String> {
+            prop::collection::vec(
+                prop_oneof![
+                    4 => "\\PC{1,8}",
+                    1 => Just("---".to_string()),
+                    1 => Just("+++".to_string()),
+                    1 => Just("\\n".to_string()),
+                    1 => prop::sample::select(vec!["\"", "'", "#", ": ", "\\", "null", "\"\"\""])
+                        .prop_map(str::to_string),
+                ],
+                1..8,
+            )
+            .prop_map(|parts| parts.concat())
+        }
+
+        /// Multi-line body, including lines that are exactly a delimiter
+        fn body() -> impl Strategy<Value = String> {
+            prop::collection::vec(
+                prop_oneof![
+                    3 => "\\PC{0,30}",
+                    1 => Just("---".to_string()),
+                    1 => Just("+++".to_string()),
+                    1 => Just(String::new()),
+                ],
+                0..8,
+            )
+            // The parser trims surrounding whitespace off the body
+            .prop_map(|lines| lines.join("\n").trim().to_string())
+        }
+
+        fn timestamp() -> impl Strategy<Value = DateTime<Utc>> {
+            (0i64..4_102_444_800, 0u32..1_000_000_000)
+                .prop_map(|(secs, nanos)| Utc.timestamp_opt(secs, nanos).unwrap())
+        }
+
+        fn link() -> impl Strategy<Value = PeaLink> {
+            (
+                "https://[a-z]{1,8}\\.com/[a-z0-9/]{0,12}",
+                prop::option::of(tricky_text()),
+                prop::sample::select(vec![LinkKind::Pr, LinkKind::Issue, LinkKind::Doc]),
+            )
+                .prop_map(|(url, label, kind)| PeaLink { url, label, kind })
+        }
+
+        fn pea() -> impl Strategy<Value = Pea> {
+            let id = "[a-z]{1,6}-[a-z0-9]{1,8}";
+            (
+                (
+                    id,
+                    tricky_text(),
+                    body(),
+                    prop::option::of("[a-z][a-z0-9-]{0,10}"),
+                    prop::option::of(tricky_text()),
+                ),
+                (
+                    prop::sample::select(vec![
+                        PeaType::Milestone,
+                        PeaType::Epic,
+                        PeaType::Story,
+                        PeaType::Feature,
+                        PeaType::Bug,
+                        PeaType::Chore,
+                        PeaType::Research,
+                        PeaType::Task,
+                    ]),
+                    prop::sample::select(vec![
+                        PeaStatus::Draft,
+                        PeaStatus::Todo,
+                        PeaStatus::InProgress,
+                        PeaStatus::Completed,
+                        PeaStatus::Scrapped,
+                    ]),
+                    prop::sample::select(vec![
+                        PeaPriority::Critical,
+                        PeaPriority::High,
+                        PeaPriority::Normal,
+                        PeaPriority::Low,
+                        PeaPriority::Deferred,
+                    ]),
+                ),
+                (
+                    prop::collection::vec(tricky_text(), 0..4),
+                    prop::option::of(id),
+                    prop::collection::vec(id, 0..3),
+                    prop::collection::vec(tricky_text(), 0..3),
+                    prop::collection::vec("\\PC{1,20}", 0..3),
+                    prop::collection::vec(link(), 0..3),
+                ),
+                (
+                    timestamp(),
+                    timestamp(),
+                    prop::option::of(timestamp().prop_map(|t| t.date_naive())),
+                    prop::option::of("v?[0-9]{1,2}\\.[0-9]{1,2}\\.[0-9]{1,2}"),
+                    prop::option::of("[0-9]{1,2}|X{0,2}[SL]|M"),
+                    prop::option::of("[a-z][a-z0-9-]{0,10}"),
+                    prop::option::of("[a-z][a-z0-9-]{0,10}"),
+                    prop::option::of(
+                        ("[a-z][a-z0-9-]{0,10}", timestamp())
+                            .prop_map(|(by, until)| Claim { by, until }),
+                    ),
+                    0u32..4,
+                    prop::option::of(timestamp().prop_map(|t| t.date_naive())),
+                ),
+            )
+                .prop_map(
+                    |(
+                        (id, title, body, component, assignee),
+                        (pea_type, status, priority),
+                        (tags, parent, blocking, external_refs, assets, links),
+                        (
+                            created,
+                            updated,
+                            target_date,
+                            released_in,
+                            size,
+                            created_by,
+                            updated_by,
+                            claim,
+                            reopened_count,
+                            defer_until,
+                        ),
+                    )| Pea {
+                        id,
+                        slug: crate::model::title_slug(&title),
+                        title,
+                        pea_type,
+                        status,
+                        priority,
+                        size,
+                        tags,
+                        component,
+                        assignee,
+                        parent,
+                        blocking,
+                        external_refs,
+                        links,
+                        assets,
+                        target_date,
+                        defer_until,
+                        released_in,
+                        claim,
+                        reopened_count,
+                        created,
+                        updated,
+                        created_by,
+                        updated_by,
+                        body: body.into(),
+                    },
+                )
+        }
+
+        proptest! {
+            #[test]
+            fn toml_round_trip(pea in pea()) {
+                let rendered = render_markdown_with_format(&pea, FrontmatterFormat::Toml).unwrap();
+                prop_assert_eq!(parse_markdown(&rendered).unwrap(), pea);
+            }
+
+            #[test]
+            fn yaml_round_trip(pea in pea()) {
+                let rendered = render_markdown_with_format(&pea, FrontmatterFormat::Yaml).unwrap();
+                prop_assert_eq!(parse_markdown(&rendered).unwrap(), pea);
+            }
+
+            #[test]
+            fn memory_round_trip(
+                key in tricky_text(),
+                tags in prop::collection::vec(tricky_text(), 0..4),
+                content in body(),
+                format in prop::sample::select(vec![FrontmatterFormat::Toml, FrontmatterFormat::Yaml]),
+            ) {
+                let mut memory = Memory::new(key).with_content(content);
+                memory.tags = tags;
+                let rendered = render_markdown_memory(&memory, format).unwrap();
+                prop_assert_eq!(parse_markdown_memory(&rendered).unwrap(), memory);
+            }
+        }
+    }
 }
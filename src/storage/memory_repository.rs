@@ -3,7 +3,7 @@ use crate::{
     error::{PeasError, Result},
     model::Memory,
     storage::markdown::{FrontmatterFormat, parse_markdown_memory, render_markdown_memory},
-    validation,
+    validation::Validator,
 };
 use std::{
     cmp::Reverse,
@@ -19,6 +19,7 @@ pub const MAX_MEMORY_COUNT: usize = 500;
 pub struct MemoryRepository {
     memory_path: PathBuf,
     frontmatter_format: FrontmatterFormat,
+    validator: Validator,
 }
 
 impl MemoryRepository {
@@ -27,24 +28,26 @@ impl MemoryRepository {
         Self {
             memory_path,
             frontmatter_format: config.peas.frontmatter_format(),
+            validator: Validator::new(&config.validation),
         }
     }
 
     fn validate_key(&self, key: &str) -> Result<()> {
         if key.is_empty() {
-            return Err(PeasError::Validation("Key cannot be empty".to_string()));
+            return Err(PeasError::validation("key", "Key cannot be empty"));
         }
 
         // Validate that key is safe for use as a filename
         if key.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
-            return Err(PeasError::Validation(
+            return Err(PeasError::validation(
+                "key",
                 "Key contains invalid characters for filenames".to_string(),
             ));
         }
 
         // Prevent directory traversal
         if key.contains("..") {
-            return Err(PeasError::Validation("Key cannot contain '..'".to_string()));
+            return Err(PeasError::validation("key", "Key cannot contain '..'"));
         }
 
         Ok(())
@@ -59,18 +62,22 @@ impl MemoryRepository {
         self.memory_path.join(filename)
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(key = %memory.key))]
     pub fn create(&self, memory: &Memory) -> Result<PathBuf> {
         // Validate input
         self.validate_key(&memory.key)?;
-        validation::validate_body(&memory.content)?;
+        self.validator.body(&memory.content)?;
         if memory.content.len() > MAX_MEMORY_CONTENT_SIZE {
-            return Err(PeasError::Validation(format!(
-                "Memory content exceeds maximum size of {} bytes",
-                MAX_MEMORY_CONTENT_SIZE
-            )));
+            return Err(PeasError::validation(
+                "content",
+                format!(
+                    "Memory content exceeds maximum size of {} bytes",
+                    MAX_MEMORY_CONTENT_SIZE
+                ),
+            ));
         }
         for tag in &memory.tags {
-            validation::validate_tag(tag)?;
+            self.validator.tag(tag)?;
         }
 
         std::fs::create_dir_all(&self.memory_path)?;
@@ -85,10 +92,13 @@ impl MemoryRepository {
                 })
                 .count();
             if count >= MAX_MEMORY_COUNT {
-                return Err(PeasError::Validation(format!(
-                    "Maximum memory count ({}) reached — delete old memories first",
-                    MAX_MEMORY_COUNT
-                )));
+                return Err(PeasError::validation(
+                    "key",
+                    format!(
+                        "Maximum memory count ({}) reached — delete old memories first",
+                        MAX_MEMORY_COUNT
+                    ),
+                ));
             }
         }
 
@@ -119,18 +129,22 @@ impl MemoryRepository {
         parse_markdown_memory(&content)
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(key = %memory.key))]
     pub fn update(&self, memory: &Memory) -> Result<PathBuf> {
         // Validate input
         self.validate_key(&memory.key)?;
-        validation::validate_body(&memory.content)?;
+        self.validator.body(&memory.content)?;
         if memory.content.len() > MAX_MEMORY_CONTENT_SIZE {
-            return Err(PeasError::Validation(format!(
-                "Memory content exceeds maximum size of {} bytes",
-                MAX_MEMORY_CONTENT_SIZE
-            )));
+            return Err(PeasError::validation(
+                "content",
+                format!(
+                    "Memory content exceeds maximum size of {} bytes",
+                    MAX_MEMORY_CONTENT_SIZE
+                ),
+            ));
         }
         for tag in &memory.tags {
-            validation::validate_tag(tag)?;
+            self.validator.tag(tag)?;
         }
 
         let file_path = self.get_file_path(&memory.key);
@@ -145,6 +159,7 @@ impl MemoryRepository {
         Ok(file_path)
     }
 
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn delete(&self, key: &str) -> Result<()> {
         self.validate_key(key)?;
         let file_path = self.get_file_path(key);
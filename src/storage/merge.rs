@@ -0,0 +1,255 @@
+//! Field-aware three-way merging of peas.
+//!
+//! Used by `peas merge-driver`: instead of merging pea files line by line,
+//! each frontmatter field is merged on its own, so two branches that change
+//! different fields of the same pea never conflict.
+
+use crate::model::Pea;
+
+/// A field both sides changed to different values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldConflict {
+    pub field: &'static str,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// Result of [`merge_peas`]; conflicting fields keep our value
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeaMerge {
+    pub pea: Pea,
+    pub conflicts: Vec<FieldConflict>,
+}
+
+/// Merge the changes `ours` and `theirs` made to `base`.
+///
+/// Scalar fields take whichever side changed them. List fields (tags,
+/// blocking, refs, links, assets) keep additions from both sides and drop entries
+/// either side removed. `created` takes the earlier and `updated` the later
+/// timestamp, each with its `_by` attribution. A body changed on both sides is reported as a `body` conflict
+/// so the caller can merge it line by line.
+pub fn merge_peas(base: &Pea, ours: &Pea, theirs: &Pea) -> PeaMerge {
+    let mut conflicts = Vec::new();
+    let mut scalar = |field: &'static str, base: String, ours: String, theirs: String| -> bool {
+        // true if theirs should win
+        if ours == theirs || theirs == base {
+            false
+        } else if ours == base {
+            true
+        } else {
+            conflicts.push(FieldConflict {
+                field,
+                ours,
+                theirs,
+            });
+            false
+        }
+    };
+
+    let mut pea = ours.clone();
+    if scalar(
+        "title",
+        base.title.clone(),
+        ours.title.clone(),
+        theirs.title.clone(),
+    ) {
+        pea.title = theirs.title.clone();
+        pea.slug = theirs.slug.clone();
+    }
+    if scalar(
+        "type",
+        base.pea_type.to_string(),
+        ours.pea_type.to_string(),
+        theirs.pea_type.to_string(),
+    ) {
+        pea.pea_type = theirs.pea_type;
+    }
+    if scalar(
+        "status",
+        base.status.to_string(),
+        ours.status.to_string(),
+        theirs.status.to_string(),
+    ) {
+        pea.status = theirs.status;
+    }
+    if scalar(
+        "priority",
+        base.priority.to_string(),
+        ours.priority.to_string(),
+        theirs.priority.to_string(),
+    ) {
+        pea.priority = theirs.priority;
+    }
+    if scalar(
+        "size",
+        display(&base.size),
+        display(&ours.size),
+        display(&theirs.size),
+    ) {
+        pea.size = theirs.size.clone();
+    }
+    if scalar(
+        "component",
+        display(&base.component),
+        display(&ours.component),
+        display(&theirs.component),
+    ) {
+        pea.component = theirs.component.clone();
+    }
+    if scalar(
+        "assignee",
+        display(&base.assignee),
+        display(&ours.assignee),
+        display(&theirs.assignee),
+    ) {
+        pea.assignee = theirs.assignee.clone();
+    }
+    if scalar(
+        "parent",
+        display(&base.parent),
+        display(&ours.parent),
+        display(&theirs.parent),
+    ) {
+        pea.parent = theirs.parent.clone();
+    }
+    if scalar(
+        "target_date",
+        display(&base.target_date),
+        display(&ours.target_date),
+        display(&theirs.target_date),
+    ) {
+        pea.target_date = theirs.target_date;
+    }
+    if scalar(
+        "defer_until",
+        display(&base.defer_until),
+        display(&ours.defer_until),
+        display(&theirs.defer_until),
+    ) {
+        pea.defer_until = theirs.defer_until;
+    }
+    if scalar(
+        "released_in",
+        display(&base.released_in),
+        display(&ours.released_in),
+        display(&theirs.released_in),
+    ) {
+        pea.released_in = theirs.released_in.clone();
+    }
+    if scalar(
+        "claim",
+        display(&base.claim),
+        display(&ours.claim),
+        display(&theirs.claim),
+    ) {
+        pea.claim = theirs.claim.clone();
+    }
+    if scalar(
+        "body",
+        base.body.to_string(),
+        ours.body.to_string(),
+        theirs.body.to_string(),
+    ) {
+        pea.body = theirs.body.clone();
+    }
+
+    pea.tags = merge_list(&base.tags, &ours.tags, &theirs.tags);
+    pea.blocking = merge_list(&base.blocking, &ours.blocking, &theirs.blocking);
+    pea.external_refs = merge_list(
+        &base.external_refs,
+        &ours.external_refs,
+        &theirs.external_refs,
+    );
+    pea.links = merge_list(&base.links, &ours.links, &theirs.links);
+    pea.assets = merge_list(&base.assets, &ours.assets, &theirs.assets);
+    // Attribution follows the timestamp it belongs to
+    if theirs.created < ours.created {
+        pea.created_by = theirs.created_by.clone();
+    }
+    if theirs.updated > ours.updated {
+        pea.updated_by = theirs.updated_by.clone();
+    }
+    pea.created = ours.created.min(theirs.created);
+    pea.updated = ours.updated.max(theirs.updated);
+
+    PeaMerge { pea, conflicts }
+}
+
+/// Our entries that `theirs` did not remove, followed by the ones `theirs` added
+fn merge_list<T: PartialEq + Clone>(base: &[T], ours: &[T], theirs: &[T]) -> Vec<T> {
+    let mut merged: Vec<T> = ours
+        .iter()
+        .filter(|v| theirs.contains(v) || !base.contains(v))
+        .cloned()
+        .collect();
+    for value in theirs {
+        if !base.contains(value) && !merged.contains(value) {
+            merged.push(value.clone());
+        }
+    }
+    merged
+}
+
+fn display<T: ToString>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_else(|| "none".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{PeaPriority, PeaStatus, PeaType};
+
+    fn base() -> Pea {
+        Pea::new("peas-abc12".into(), "Login".into(), PeaType::Bug)
+            .with_tags(vec!["auth".into(), "ui".into()])
+            .with_body("Steps".into())
+    }
+
+    #[test]
+    fn test_different_fields_merge_cleanly() {
+        let base = base();
+        let ours = base
+            .clone()
+            .with_status(PeaStatus::InProgress)
+            .with_tags(vec!["auth".into(), "urgent".into()]);
+        let mut theirs = base
+            .clone()
+            .with_priority(PeaPriority::High)
+            .with_tags(vec!["ui".into(), "auth".into(), "mobile".into()]);
+        theirs.touch();
+        theirs.updated_by = Some("agent-x".into());
+
+        let merged = merge_peas(&base, &ours, &theirs);
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(merged.pea.status, PeaStatus::InProgress);
+        assert_eq!(merged.pea.priority, PeaPriority::High);
+        assert_eq!(merged.pea.tags, vec!["auth", "urgent", "mobile"]);
+        assert_eq!(merged.pea.updated, theirs.updated);
+        assert_eq!(merged.pea.updated_by.as_deref(), Some("agent-x"));
+    }
+
+    #[test]
+    fn test_same_field_changed_twice_conflicts() {
+        let base = base();
+        let ours = base.clone().with_status(PeaStatus::Completed);
+        let theirs = base
+            .clone()
+            .with_status(PeaStatus::Scrapped)
+            .with_body("Other steps".into());
+
+        let merged = merge_peas(&base, &ours, &theirs);
+        assert_eq!(
+            merged.conflicts,
+            vec![FieldConflict {
+                field: "status",
+                ours: "completed".into(),
+                theirs: "scrapped".into(),
+            }]
+        );
+        assert_eq!(merged.pea.status, PeaStatus::Completed);
+        assert_eq!(merged.pea.body, "Other steps");
+    }
+}
@@ -22,18 +22,27 @@
 //! ## Components
 //!
 //! - [`PeaRepository`]: CRUD operations for peas
+//! - [`ListQuery`]: Filters, sort order and page shared by every pea listing
+//! - [`INDEX_FILE`]: Summary index behind `PeaRepository::list_summaries`
 //! - [`MemoryRepository`]: CRUD operations for memories
 //! - [`parse_markdown`]: Parse a pea from markdown content
 //! - [`render_markdown`]: Render a pea to markdown content
+//! - [`merge_peas`]: Field-aware three-way merge used by the git merge driver
 
+mod index;
 mod markdown;
 mod memory_repository;
+mod merge;
+mod query;
 mod repository;
 
+pub use index::INDEX_FILE;
 pub use markdown::{
     FrontmatterFormat, detect_format, parse_markdown, parse_markdown_memory,
     parse_markdown_with_format, render_markdown, render_markdown_memory,
     render_markdown_with_format,
 };
 pub use memory_repository::{MAX_MEMORY_CONTENT_SIZE, MAX_MEMORY_COUNT, MemoryRepository};
-pub use repository::PeaRepository;
+pub use merge::{FieldConflict, PeaMerge, merge_peas};
+pub use query::{ListPage, ListQuery};
+pub use repository::{FileStats, PeaRepository};
@@ -0,0 +1,223 @@
+use crate::model::{Pea, PeaPriority, PeaStatus, PeaType, SortKey, sort_peas};
+use crate::search::{RankedSearch, SearchQuery};
+use chrono::{DateTime, Utc};
+
+/// Filters, sort order and page of a pea listing
+///
+/// Shared by `peas list`, the GraphQL `peas` query and the TUI so they all
+/// agree on what a filter means. Unset fields don't filter anything.
+///
+/// ```
+/// use peas::model::{Pea, PeaStatus, PeaType, SortKey};
+/// use peas::storage::ListQuery;
+///
+/// let peas = vec![
+///     Pea::new("peas-a1".into(), "Login form".into(), PeaType::Bug),
+///     Pea::new("peas-b2".into(), "Dark mode".into(), PeaType::Task)
+///         .with_status(PeaStatus::InProgress),
+///     Pea::new("peas-c3".into(), "Audit log".into(), PeaType::Bug),
+/// ];
+/// let query = ListQuery {
+///     pea_type: Some(PeaType::Bug),
+///     sort: SortKey::parse_list("title").unwrap(),
+///     limit: Some(1),
+///     ..Default::default()
+/// };
+///
+/// let page = query.apply(peas, None);
+/// assert_eq!(page.total, 2);
+/// assert_eq!(page.peas[0].id, "peas-c3");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ListQuery {
+    pub pea_type: Option<PeaType>,
+    pub status: Option<PeaStatus>,
+    pub priority: Option<PeaPriority>,
+    pub parent: Option<String>,
+    pub tag: Option<String>,
+    pub component: Option<String>,
+    pub assignee: Option<String>,
+    pub created_by: Option<String>,
+    pub updated_by: Option<String>,
+    /// Keep only open (or only closed) peas
+    pub is_open: Option<bool>,
+    /// Hide peas with an active claim by anyone but the actor
+    pub unclaimed: bool,
+    /// Hide peas whose `defer_until` date is still ahead
+    pub hide_deferred: bool,
+    /// Search terms a pea has to match all of
+    pub terms: Vec<SearchQuery>,
+    /// A ranked search a pea has to match; the order still comes from `sort`
+    pub search: Option<RankedSearch>,
+    /// Keys to sort by; without any, peas keep their order
+    pub sort: Vec<SortKey>,
+    /// Reverse the order after sorting
+    pub reverse: bool,
+    /// Peas to skip from the start of the sorted list
+    pub offset: usize,
+    /// Most peas to return
+    pub limit: Option<usize>,
+}
+
+/// One page of a [`ListQuery`]
+#[derive(Debug, Clone)]
+pub struct ListPage {
+    pub peas: Vec<Pea>,
+    /// Matching peas before `offset` and `limit`
+    pub total: usize,
+}
+
+impl ListQuery {
+    /// Whether `pea` passes every filter
+    ///
+    /// `actor` decides which claims `unclaimed` leaves in.
+    pub fn matches(&self, pea: &Pea, actor: Option<&str>, now: DateTime<Utc>) -> bool {
+        self.pea_type.is_none_or(|t| pea.pea_type == t)
+            && self.status.is_none_or(|s| pea.status == s)
+            && self.priority.is_none_or(|p| pea.priority == p)
+            && self
+                .parent
+                .as_ref()
+                .is_none_or(|parent| pea.parent.as_ref() == Some(parent))
+            && self.tag.as_ref().is_none_or(|tag| pea.tags.contains(tag))
+            && self
+                .component
+                .as_ref()
+                .is_none_or(|component| pea.component.as_ref() == Some(component))
+            && self
+                .assignee
+                .as_ref()
+                .is_none_or(|assignee| pea.assignee.as_ref() == Some(assignee))
+            && self
+                .created_by
+                .as_ref()
+                .is_none_or(|actor| pea.created_by.as_ref() == Some(actor))
+            && self
+                .updated_by
+                .as_ref()
+                .is_none_or(|actor| pea.updated_by.as_ref() == Some(actor))
+            && self.is_open.is_none_or(|open| pea.is_open() == open)
+            && !(self.unclaimed && pea.is_claimed_by_other(actor, now))
+            && !(self.hide_deferred && pea.is_deferred(now.date_naive()))
+            && self.terms.iter().all(|term| term.matches_pea(pea))
+            && self
+                .search
+                .as_ref()
+                .is_none_or(|search| search.score(pea, &[]).is_some())
+    }
+
+    /// Filter and sort `peas`, then cut out the requested page
+    pub fn apply(&self, mut peas: Vec<Pea>, actor: Option<&str>) -> ListPage {
+        let now = Utc::now();
+        peas.retain(|pea| self.matches(pea, actor, now));
+        sort_peas(&mut peas, &self.sort);
+        if self.reverse {
+            peas.reverse();
+        }
+
+        let total = peas.len();
+        let peas = peas
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+        ListPage { peas, total }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Claim;
+    use chrono::Duration;
+
+    fn peas() -> Vec<Pea> {
+        vec![
+            Pea::new("peas-a1".into(), "Login form".into(), PeaType::Bug)
+                .with_tags(vec!["auth".into()]),
+            Pea::new("peas-b2".into(), "Dark mode".into(), PeaType::Task)
+                .with_status(PeaStatus::Completed),
+            Pea::new("peas-c3".into(), "Login rate limit".into(), PeaType::Task)
+                .with_tags(vec!["auth".into()]),
+            Pea::new("peas-d4".into(), "Audit trail".into(), PeaType::Task),
+        ]
+    }
+
+    fn ids(page: &ListPage) -> Vec<&str> {
+        page.peas.iter().map(|p| p.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_query_keeps_everything_in_order() {
+        let page = ListQuery::default().apply(peas(), None);
+        assert_eq!(ids(&page), ["peas-a1", "peas-b2", "peas-c3", "peas-d4"]);
+        assert_eq!(page.total, 4);
+    }
+
+    #[test]
+    fn test_filters_combine() {
+        let query = ListQuery {
+            tag: Some("auth".into()),
+            pea_type: Some(PeaType::Task),
+            ..Default::default()
+        };
+        assert_eq!(ids(&query.apply(peas(), None)), ["peas-c3"]);
+
+        let query = ListQuery {
+            is_open: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(ids(&query.apply(peas(), None)), ["peas-b2"]);
+    }
+
+    #[test]
+    fn test_search_terms_and_ranked_search() {
+        let query = ListQuery {
+            terms: SearchQuery::parse_terms("login tag:auth", false).unwrap(),
+            ..Default::default()
+        };
+        assert_eq!(ids(&query.apply(peas(), None)), ["peas-a1", "peas-c3"]);
+
+        let query = ListQuery {
+            search: Some(RankedSearch::parse("logn").unwrap().with_fuzzy(true)),
+            ..Default::default()
+        };
+        assert_eq!(ids(&query.apply(peas(), None)), ["peas-a1", "peas-c3"]);
+    }
+
+    #[test]
+    fn test_sort_reverse_and_page() {
+        let query = ListQuery {
+            sort: SortKey::parse_list("title").unwrap(),
+            reverse: true,
+            offset: 1,
+            limit: Some(2),
+            ..Default::default()
+        };
+        let page = query.apply(peas(), None);
+        assert_eq!(ids(&page), ["peas-a1", "peas-b2"]);
+        assert_eq!(page.total, 4);
+    }
+
+    #[test]
+    fn test_unclaimed_and_deferred() {
+        let now = Utc::now();
+        let mut claimed = Pea::new("peas-e5".into(), "Claimed".into(), PeaType::Task);
+        claimed.claim = Some(Claim {
+            by: "alice".into(),
+            until: now + Duration::hours(1),
+        });
+        let mut deferred = Pea::new("peas-f6".into(), "Later".into(), PeaType::Task);
+        deferred.defer_until = Some(now.date_naive() + Duration::days(3));
+
+        let query = ListQuery {
+            unclaimed: true,
+            hide_deferred: true,
+            ..Default::default()
+        };
+        assert!(!query.matches(&claimed, Some("bob"), now));
+        assert!(query.matches(&claimed, Some("alice"), now));
+        assert!(!query.matches(&deferred, None, now));
+        assert!(ListQuery::default().matches(&deferred, None, now));
+    }
+}
@@ -1,15 +1,24 @@
+use super::index::SummaryIndex;
 use super::markdown::{
     FrontmatterFormat, detect_format, parse_markdown, render_markdown_with_format,
 };
+use super::query::{ListPage, ListQuery};
 use crate::{
-    config::{IdMode, PeasConfig},
-    error::{PeasError, Result},
-    model::{Pea, PeaType},
-    validation,
+    aliases::Aliases,
+    autotag::AutoTags,
+    config::{
+        ComponentSettings, IdMode, NamingScheme, PeasConfig, RuleEvent, TagPolicy, UndoSettings,
+    },
+    error::{Constraint, PeasError, Result},
+    hooks::{HookEvent, Hooks},
+    local::LocalState,
+    model::{Pea, PeaType, title_slug},
+    rules::Rules,
+    undo::UndoManager,
+    validation::{self, Validator},
 };
-use slug::slugify;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
@@ -67,6 +76,17 @@ impl PeaCache {
     }
 }
 
+/// File-level statistics of the ticket data directory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileStats {
+    /// Ticket files found (parseable or not)
+    pub files: usize,
+    /// Total size of those files in bytes
+    pub bytes: u64,
+    /// Files that could not be read or parsed
+    pub parse_errors: usize,
+}
+
 pub struct PeaRepository {
     data_path: PathBuf,
     archive_path: PathBuf,
@@ -74,6 +94,20 @@ pub struct PeaRepository {
     id_length: usize,
     id_mode: IdMode,
     frontmatter_format: FrontmatterFormat,
+    naming_scheme: NamingScheme,
+    /// Refuse updates to closed peas other than reopening them
+    lock_closed: bool,
+    /// Configured tags when the tag policy is `strict`
+    allowed_tags: Option<HashSet<String>>,
+    components: BTreeMap<String, ComponentSettings>,
+    /// Limits on titles, bodies and tags from `[validation]`
+    validator: Validator,
+    autotags: AutoTags,
+    rules: Rules,
+    hooks: Hooks,
+    undo: UndoSettings,
+    /// Credited with the peas this repository creates and updates
+    actor: Option<String>,
     cache: RefCell<PeaCache>,
 }
 
@@ -86,10 +120,112 @@ impl PeaRepository {
             id_length: config.peas.id_length,
             id_mode: config.peas.id_mode,
             frontmatter_format: config.peas.frontmatter_format(),
+            naming_scheme: config.peas.naming_scheme,
+            lock_closed: config.peas.lock_closed,
+            allowed_tags: (config.peas.tag_policy == TagPolicy::Strict)
+                .then(|| config.tags.keys().cloned().collect()),
+            components: config.components.clone(),
+            validator: Validator::new(&config.validation),
+            autotags: AutoTags::new(&config.autotag),
+            rules: Rules::new(&config.rules),
+            hooks: Hooks::new(&config.data_path(project_root)),
+            undo: config.undo,
+            actor: config.actor.clone(),
             cache: RefCell::new(PeaCache::new()),
         }
     }
 
+    /// Credit changes to `actor` instead of the configured one
+    pub fn with_actor(mut self, actor: Option<String>) -> Self {
+        self.actor = actor;
+        self
+    }
+
+    /// The person or agent changes are credited to
+    pub fn actor(&self) -> Option<&str> {
+        self.actor.as_deref()
+    }
+
+    /// Checks for titles, bodies and tags as configured in `[validation]`
+    pub fn validator(&self) -> &Validator {
+        &self.validator
+    }
+
+    /// The project's undo history, pruned as configured in `[undo]`
+    pub fn undo_manager(&self) -> UndoManager {
+        UndoManager::new(&self.data_path).with_settings(self.undo)
+    }
+
+    /// Reject tags outside the allow-list under the `strict` tag policy,
+    /// except those in `existing`
+    fn check_allowed_tags(&self, tags: &[String], existing: &[String]) -> Result<()> {
+        let Some(allowed) = &self.allowed_tags else {
+            return Ok(());
+        };
+        if let Some(tag) = tags
+            .iter()
+            .find(|t| !allowed.contains(*t) && !existing.contains(t))
+        {
+            let mut names: Vec<&str> = allowed.iter().map(String::as_str).collect();
+            names.sort_unstable();
+            return Err(PeasError::validation(
+                "tags",
+                format!(
+                    "Tag '{}' is not configured (allowed: {})",
+                    tag,
+                    names.join(", ")
+                ),
+            )
+            .with_constraint(
+                Constraint::OneOf(names.iter().map(|n| n.to_string()).collect()),
+                tag,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reject a component without a `[components.<name>]` section, unless the
+    /// pea already had it
+    fn check_component(&self, component: Option<&str>, existing: Option<&str>) -> Result<()> {
+        let Some(component) = component.filter(|c| Some(*c) != existing) else {
+            return Ok(());
+        };
+        if self.components.contains_key(component) {
+            return Ok(());
+        }
+        let known: Vec<&str> = self.components.keys().map(String::as_str).collect();
+        Err(PeasError::validation(
+            "component",
+            if known.is_empty() {
+                format!(
+                    "Unknown component '{}': declare it in a [components.{}] config section",
+                    component, component
+                )
+            } else {
+                format!(
+                    "Unknown component '{}' (expected one of: {})",
+                    component,
+                    known.join(", ")
+                )
+            },
+        )
+        .with_constraint(
+            Constraint::OneOf(known.iter().map(|k| k.to_string()).collect()),
+            component,
+        ))
+    }
+
+    /// Number of active peas per tag
+    pub fn tag_counts(&self) -> Result<BTreeMap<String, usize>> {
+        let mut counts = BTreeMap::new();
+        for pea in self.list()? {
+            for tag in pea.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        Ok(counts)
+    }
+
     /// Invalidate the cache (call after external file changes)
     pub fn invalidate_cache(&self) {
         self.cache.borrow_mut().invalidate();
@@ -137,34 +273,28 @@ impl PeaRepository {
     }
 
     pub fn generate_filename(&self, id: &str, title: &str) -> String {
-        let slug = slugify(title);
-        let slug = if slug.len() > 50 {
-            slug[..50].to_string()
-        } else {
-            slug
-        };
-        format!("{}--{}.md", id, slug)
+        match (self.naming_scheme, title_slug(title)) {
+            (NamingScheme::IdSlug, Some(slug)) => format!("{}--{}.md", id, slug),
+            _ => format!("{}.md", id),
+        }
     }
 
+    #[tracing::instrument(level = "debug", skip_all, fields(id = %pea.id))]
     pub fn create(&self, pea: &Pea) -> Result<PathBuf> {
-        tracing::info!(id = %pea.id, title = %pea.title, "Creating pea");
-
-        // Validate input
-        validation::validate_id(&pea.id)?;
-        validation::validate_title(&pea.title)?;
-        validation::validate_body(&pea.body)?;
-        for tag in &pea.tags {
-            validation::validate_tag(tag)?;
-        }
-
-        // Validate relationships
-        validation::validate_no_self_parent(&pea.id, &pea.parent)?;
-        validation::validate_no_self_blocking(&pea.id, &pea.blocking)?;
-        validation::validate_parent_exists(&pea.parent, |id| self.exists(id))?;
-        validation::validate_blocking_exist(&pea.blocking, |id| self.exists(id))?;
-        validation::validate_no_circular_parent(&pea.id, &pea.parent, |id| {
-            self.get(id).ok().and_then(|p| p.parent)
-        })?;
+        tracing::info!(id = %pea.id, title = %pea.title, actor = self.actor.as_deref(), "Creating pea");
+
+        // Auto-tagging comes first so an added component can auto-assign
+        let tagged = self.autotags.apply(pea)?;
+        let credited = self.credit_creation(tagged.as_ref().unwrap_or(pea));
+        let pea = &credited;
+        // Rules and then a pre-create hook may reject the pea or hand back a changed one
+        let ruled = self.rules.apply(RuleEvent::Create, pea)?;
+        let pea = ruled.as_ref().unwrap_or(pea);
+        let hooked = self.hooks.run_pre(HookEvent::PreCreate, pea)?;
+        let mut written = hooked.unwrap_or_else(|| pea.clone());
+        written.slug = title_slug(&written.title);
+        let pea = &written;
+        self.check_create(pea)?;
 
         std::fs::create_dir_all(&self.data_path)?;
 
@@ -185,10 +315,68 @@ impl PeaRepository {
 
         // Update cache with new pea
         self.cache.borrow_mut().update_pea(pea);
+        self.hooks.run_post(HookEvent::PostCreate, pea);
 
         Ok(file_path)
     }
 
+    /// Check a new pea as [`create`](Self::create) does, without writing it
+    ///
+    /// Returns the pea as rules would change it. Hooks are not run, since
+    /// they may have side effects, so a dry run cannot tell if one would object.
+    pub fn preview_create(&self, pea: &Pea) -> Result<Pea> {
+        let tagged = self.autotags.apply(pea)?;
+        let pea = self.credit_creation(tagged.as_ref().unwrap_or(pea));
+        let mut pea = self.rules.apply(RuleEvent::Create, &pea)?.unwrap_or(pea);
+        pea.slug = title_slug(&pea.title);
+        self.check_create(&pea)?;
+        Ok(pea)
+    }
+
+    /// `pea` as a new pea of the configured actor, if there is one
+    ///
+    /// Without an actor the pea keeps the attribution it came with, as from an import.
+    /// An unassigned pea goes to the first owner of a component set to `auto_assign`.
+    fn credit_creation(&self, pea: &Pea) -> Pea {
+        let mut pea = pea.clone();
+        if let Some(actor) = &self.actor {
+            pea.created_by = Some(actor.clone());
+            pea.updated_by = Some(actor.clone());
+        }
+        if pea.assignee.is_none()
+            && let Some(component) = pea.component.as_ref().and_then(|c| self.components.get(c))
+            && component.auto_assign
+        {
+            pea.assignee = component.owners.first().cloned();
+        }
+        pea
+    }
+
+    fn check_create(&self, pea: &Pea) -> Result<()> {
+        // Validate input
+        validation::validate_id(&pea.id)?;
+        self.validator.pea(pea)?;
+        self.check_allowed_tags(&pea.tags, &[])?;
+        self.check_component(pea.component.as_deref(), None)?;
+        self.check_relationships(pea)?;
+
+        if self.has_file_for_id(&pea.id)? {
+            return Err(PeasError::DuplicateId(pea.id.clone()));
+        }
+        Ok(())
+    }
+
+    fn check_relationships(&self, pea: &Pea) -> Result<()> {
+        validation::validate_no_self_parent(&pea.id, &pea.parent)?;
+        validation::validate_no_self_blocking(&pea.id, &pea.blocking)?;
+        validation::validate_parent_exists(&pea.parent, |id| self.exists(id))?;
+        validation::validate_blocking_exist(&pea.blocking, |id| self.exists(id))?;
+        validation::validate_no_circular_parent(&pea.id, &pea.parent, |id| {
+            self.get(id).ok().and_then(|p| p.parent)
+        })
+    }
+
+    #[tracing::instrument(level = "trace", skip(self))]
     pub fn get(&self, id: &str) -> Result<Pea> {
         // Check cache first for O(1) lookup
         let cache = self.cache.borrow();
@@ -198,9 +386,10 @@ impl PeaRepository {
         drop(cache); // Release borrow before disk read
 
         // Cache miss - load from disk
+        tracing::trace!("Cache miss, reading from disk");
         let file_path = self.find_file_by_id(id)?;
         let content = std::fs::read_to_string(&file_path)?;
-        let pea = parse_markdown(&content)?;
+        let pea = parse_markdown(&content).map_err(|e| e.with_path(&file_path))?;
 
         // Update cache with loaded pea
         self.cache.borrow_mut().update_pea(&pea);
@@ -222,37 +411,60 @@ impl PeaRepository {
     }
 
     pub fn update(&self, pea: &mut Pea) -> Result<PathBuf> {
-        tracing::info!(id = %pea.id, title = %pea.title, "Updating pea");
+        self.write_update(pea, false)
+    }
 
-        // Validate input
-        validation::validate_title(&pea.title)?;
-        validation::validate_body(&pea.body)?;
-        for tag in &pea.tags {
-            validation::validate_tag(tag)?;
-        }
+    /// Reopen a closed pea, which the `lock_closed` setting otherwise keeps read-only
+    ///
+    /// `pea` must have an open status; other changes are written along with it.
+    /// A note in the work log records the reopening and the status it had.
+    pub fn reopen(&self, pea: &mut Pea) -> Result<PathBuf> {
+        self.load_body(pea)?;
+        self.mark_reopened(pea)?;
+        self.write_update(pea, true)
+    }
 
-        // Validate relationships
-        validation::validate_no_self_parent(&pea.id, &pea.parent)?;
-        validation::validate_no_self_blocking(&pea.id, &pea.blocking)?;
-        validation::validate_parent_exists(&pea.parent, |id| self.exists(id))?;
-        validation::validate_blocking_exist(&pea.blocking, |id| self.exists(id))?;
-        validation::validate_no_circular_parent(&pea.id, &pea.parent, |id| {
-            self.get(id).ok().and_then(|p| p.parent)
-        })?;
+    /// Check a reopen and log it in the work log of `pea`
+    fn mark_reopened(&self, pea: &mut Pea) -> Result<()> {
+        let current = self.get(&pea.id)?;
+        if !current.is_closed() {
+            return Err(PeasError::validation(
+                "status",
+                format!("{} is {}, not closed", pea.id, current.status),
+            ));
+        }
+        if !pea.is_open() {
+            return Err(PeasError::validation(
+                "status",
+                format!("Reopening needs an open status, not {}", pea.status),
+            ));
+        }
+        pea.reopened_count = current.reopened_count + 1;
+        pea.add_note(
+            &format!("Reopened (was {})", current.status),
+            self.actor.as_deref(),
+            chrono::Utc::now(),
+        )
+    }
 
-        let old_path = self.find_file_by_id(&pea.id)?;
+    #[tracing::instrument(level = "debug", skip_all, fields(id = %pea.id))]
+    fn write_update(&self, pea: &mut Pea, reopening: bool) -> Result<PathBuf> {
+        tracing::info!(id = %pea.id, title = %pea.title, actor = self.actor.as_deref(), "Updating pea");
 
-        // Concurrent edit detection: check if file was modified since we loaded it
-        // This prevents one TUI instance from clobbering another's changes
-        // IMPORTANT: This check must happen BEFORE we call touch(), so we still have
-        // the original timestamp that was loaded from disk
-        let current_pea = self.get(&pea.id)?;
-        if current_pea.updated != pea.updated {
-            return Err(PeasError::Storage(format!(
-                "Concurrent modification detected for pea '{}'. The file was modified by another process.\nYour version was updated at: {}\nCurrent version was updated at: {}\nPlease reload and try again.",
-                pea.id, pea.updated, current_pea.updated
-            )));
+        // A pea from the summary index keeps the body stored on disk
+        self.load_body(pea)?;
+        // Nobody is credited with a change made without an actor
+        pea.updated_by = self.actor.clone();
+        if let Some(ruled) = self.rules.apply(RuleEvent::Update, pea)? {
+            *pea = ruled;
+        }
+        if let Some(hooked) = self.hooks.run_pre(HookEvent::PreUpdate, pea)? {
+            *pea = hooked;
         }
+        pea.slug = title_slug(&pea.title);
+        self.check_update(pea, reopening)?;
+
+        let old_path = self.find_file_by_id(&pea.id)?;
 
         // Now that we've verified no concurrent edits, update the timestamp
         pea.touch();
@@ -275,24 +487,138 @@ impl PeaRepository {
 
         // Update cache with modified pea
         self.cache.borrow_mut().update_pea(pea);
+        self.hooks.run_post(HookEvent::PostUpdate, pea);
 
         Ok(new_path)
     }
 
+    /// Check a changed pea as [`update`](Self::update) does, without writing it
+    ///
+    /// Returns the pea as rules would change it; hooks are not run, as for
+    /// [`preview_create`](Self::preview_create).
+    pub fn preview_update(&self, pea: &Pea) -> Result<Pea> {
+        self.preview(pea.clone(), false)
+    }
+
+    /// Like [`preview_update`](Self::preview_update), for [`reopen`](Self::reopen)
+    pub fn preview_reopen(&self, pea: &Pea) -> Result<Pea> {
+        let mut pea = pea.clone();
+        self.load_body(&mut pea)?;
+        self.mark_reopened(&mut pea)?;
+        self.preview(pea, true)
+    }
+
+    fn preview(&self, mut pea: Pea, reopening: bool) -> Result<Pea> {
+        self.load_body(&mut pea)?;
+        pea.updated_by = self.actor.clone();
+        let mut pea = self.rules.apply(RuleEvent::Update, &pea)?.unwrap_or(pea);
+        pea.slug = title_slug(&pea.title);
+        self.check_update(&pea, reopening)?;
+        Ok(pea)
+    }
+
+    fn check_update(&self, pea: &Pea, reopening: bool) -> Result<()> {
+        // Validate input
+        self.validator.pea(pea)?;
+        self.check_relationships(pea)?;
+
+        // Concurrent edit detection: check if file was modified since we loaded it
+        // This prevents one TUI instance from clobbering another's changes
+        // IMPORTANT: This check must happen BEFORE we call touch(), so we still have
+        // the original timestamp that was loaded from disk
+        let current_pea = self.get(&pea.id)?;
+        if current_pea.updated != pea.updated {
+            tracing::warn!(
+                ours = %pea.updated,
+                theirs = %current_pea.updated,
+                "Rejected stale update"
+            );
+            return Err(PeasError::Conflict {
+                id: pea.id.clone(),
+                ours: pea.updated,
+                theirs: current_pea.updated,
+            });
+        }
+        if self.lock_closed && current_pea.is_closed() && !reopening {
+            return Err(PeasError::validation(
+                "status",
+                format!(
+                    "{} is {} and read-only (lock_closed); reopen it first, e.g. `peas update {} --reopen`",
+                    pea.id, current_pea.status, pea.id
+                ),
+            ));
+        }
+        // Tags and components the pea already had stay valid when the config changes
+        self.check_allowed_tags(&pea.tags, &current_pea.tags)?;
+        self.check_component(pea.component.as_deref(), current_pea.component.as_deref())
+    }
+
+    /// Write a pea exactly as given, creating it or replacing the stored version.
+    ///
+    /// Unlike [`update`](Self::update), timestamps are kept, relationships are
+    /// not checked and no hooks run, since `peas sync` can pull a child
+    /// before its parent.
+    #[tracing::instrument(level = "debug", skip_all, fields(id = %pea.id))]
+    pub fn put(&self, pea: &Pea) -> Result<PathBuf> {
+        tracing::info!(id = %pea.id, title = %pea.title, "Writing synced pea");
+
+        validation::validate_id(&pea.id)?;
+        self.validator.pea(pea)?;
+
+        std::fs::create_dir_all(&self.data_path)?;
+        let old_path = self.exact_file_for_id(&pea.id)?;
+        let new_path = self
+            .data_path
+            .join(self.generate_filename(&pea.id, &pea.title));
+
+        let format = match &old_path {
+            Some(path) => {
+                detect_format(&std::fs::read_to_string(path)?).unwrap_or(self.frontmatter_format)
+            }
+            None => self.frontmatter_format,
+        };
+        let content = render_markdown_with_format(pea, format)?;
+        self.atomic_write(&new_path, &content)?;
+
+        if let Some(old_path) = old_path
+            && old_path != new_path
+        {
+            std::fs::remove_file(&old_path)?;
+        }
+
+        self.cache.borrow_mut().update_pea(pea);
+        Ok(new_path)
+    }
+
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn delete(&self, id: &str) -> Result<()> {
-        tracing::info!(id = %id, "Deleting pea");
+        tracing::info!(id = %id, actor = self.actor.as_deref(), "Deleting pea");
+
+        // Only parse the pea when a hook needs it, so a broken file can still be deleted
+        let hooked =
+            if self.hooks.has(HookEvent::PreDelete) || self.hooks.has(HookEvent::PostDelete) {
+                let pea = self.get(id)?;
+                self.hooks.run_pre(HookEvent::PreDelete, &pea)?;
+                Some(pea)
+            } else {
+                None
+            };
 
         let file_path = self.find_file_by_id(id)?;
         std::fs::remove_file(&file_path)?;
 
         // Remove from cache
         self.cache.borrow_mut().remove_pea(id);
+        if let Some(pea) = hooked {
+            self.hooks.run_post(HookEvent::PostDelete, &pea);
+        }
 
         Ok(())
     }
 
+    #[tracing::instrument(level = "debug", skip(self))]
     pub fn archive(&self, id: &str) -> Result<PathBuf> {
-        tracing::info!(id = %id, "Archiving pea");
+        tracing::info!(id = %id, actor = self.actor.as_deref(), "Archiving pea");
 
         std::fs::create_dir_all(&self.archive_path)?;
 
@@ -312,6 +638,49 @@ impl PeaRepository {
         Ok(new_path)
     }
 
+    /// An archived pea by its ID
+    pub fn get_archived(&self, id: &str) -> Result<Pea> {
+        let file_path = self.archived_file_for_id(id)?;
+        let content = std::fs::read_to_string(&file_path)?;
+        parse_markdown(&content).map_err(|e| e.with_path(&file_path))
+    }
+
+    /// Move an archived pea back among the active ones
+    ///
+    /// Returns the path it had in the archive and the path it has now.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub fn unarchive(&self, id: &str) -> Result<(PathBuf, PathBuf)> {
+        tracing::info!(id = %id, actor = self.actor.as_deref(), "Unarchiving pea");
+
+        let old_path = self.archived_file_for_id(id)?;
+        let pea = self.get_archived(id)?;
+        if self.has_file_for_id(&pea.id)? {
+            return Err(PeasError::DuplicateId(pea.id));
+        }
+        let filename = old_path
+            .file_name()
+            .ok_or_else(|| PeasError::Storage("Path has no filename".to_string()))?;
+        std::fs::create_dir_all(&self.data_path)?;
+        let new_path = self.data_path.join(filename);
+
+        std::fs::rename(&old_path, &new_path)?;
+        self.cache.borrow_mut().invalidate();
+
+        Ok((old_path, new_path))
+    }
+
+    /// File of an archived pea, with or without the ID prefix
+    fn archived_file_for_id(&self, id: &str) -> Result<PathBuf> {
+        let search_id = if id.starts_with(&self.prefix) {
+            id.to_string()
+        } else {
+            format!("{}{}", self.prefix, id)
+        };
+        exact_file_in(&self.archive_path, &search_id)?
+            .ok_or_else(|| PeasError::NotFound(format!("{} (in the archive)", id)))
+    }
+
+    #[tracing::instrument(level = "trace", skip(self))]
     pub fn list(&self) -> Result<Vec<Pea>> {
         // Check cache first
         let cache = self.cache.borrow();
@@ -329,6 +698,42 @@ impl PeaRepository {
         Ok(peas)
     }
 
+    /// Active peas matching `query`, sorted and paged
+    ///
+    /// Claims are judged from the point of view of [`Self::actor`].
+    pub fn query(&self, query: &ListQuery) -> Result<ListPage> {
+        Ok(query.apply(self.list()?, self.actor()))
+    }
+
+    /// Active peas without their bodies, from the summary index in `.peas/local/`
+    ///
+    /// Only ticket files changed since the index was last written are parsed,
+    /// which makes this much cheaper than [`Self::list`] on large trackers.
+    /// Bodies are [`crate::model::Body::NotLoaded`]; see [`Self::load_body`].
+    pub fn list_summaries(&self) -> Result<Vec<Pea>> {
+        if let Some(peas) = self.cache.borrow().get_list() {
+            return Ok(peas.clone());
+        }
+        let local = LocalState::new(&self.data_path);
+        let mut index = SummaryIndex::load(&local);
+        if index.refresh(&self.data_path, &self.prefix)?
+            && let Err(e) = index.save(&local)
+        {
+            tracing::warn!(error = %e, "Failed to save summary index");
+        }
+        Ok(index.peas())
+    }
+
+    /// Read the body of a pea from a summary, if it was not loaded yet
+    ///
+    /// Updates do this on their own, so a summary can be changed and written back.
+    pub fn load_body(&self, pea: &mut Pea) -> Result<()> {
+        if !pea.body.is_loaded() {
+            pea.body = self.get(&pea.id)?.body;
+        }
+        Ok(())
+    }
+
     pub fn list_archived(&self) -> Result<Vec<Pea>> {
         if !self.archive_path.exists() {
             return Ok(Vec::new());
@@ -374,9 +779,55 @@ impl PeaRepository {
         }
 
         peas.sort_by_key(|a| a.created);
+        tracing::debug!(path = %path.display(), count = peas.len(), "Loaded peas from disk");
         Ok(peas)
     }
 
+    /// Scan the active data directory for file counts, size and parse errors
+    ///
+    /// Always reads from disk; unlike `list()`, unparseable files are counted
+    /// instead of skipped.
+    pub fn file_stats(&self) -> Result<FileStats> {
+        let mut stats = FileStats::default();
+        if !self.data_path.exists() {
+            return Ok(stats);
+        }
+
+        for entry in std::fs::read_dir(&self.data_path)? {
+            let path = entry?.path();
+            let is_pea_file = path.is_file()
+                && path.extension().is_some_and(|e| e == "md")
+                && path
+                    .file_name()
+                    .is_some_and(|f| f.to_string_lossy().starts_with(&self.prefix));
+            if !is_pea_file {
+                continue;
+            }
+
+            stats.files += 1;
+            match std::fs::read_to_string(&path) {
+                Ok(content) => {
+                    stats.bytes += content.len() as u64;
+                    if parse_markdown(&content).is_err() {
+                        stats.parse_errors += 1;
+                    }
+                }
+                Err(_) => stats.parse_errors += 1,
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Whether a file for exactly `id` exists (unlike the prefix match in [`Self::find_file_by_id`])
+    fn has_file_for_id(&self, id: &str) -> Result<bool> {
+        Ok(self.exact_file_for_id(id)?.is_some())
+    }
+
+    /// File of the pea with exactly this ID, unlike the prefix match in `find_file_by_id`
+    fn exact_file_for_id(&self, id: &str) -> Result<Option<PathBuf>> {
+        exact_file_in(&self.data_path, id)
+    }
+
     pub fn find_file_by_id(&self, id: &str) -> Result<PathBuf> {
         let search_id = if id.starts_with(&self.prefix) {
             id.to_string()
@@ -384,6 +835,19 @@ impl PeaRepository {
             format!("{}{}", self.prefix, id)
         };
 
+        // An exact ID wins over a longer ID that merely starts with it
+        if let Some(path) = self.exact_file_for_id(&search_id)? {
+            return Ok(path);
+        }
+
+        // Then a former ID of a renamed pea, which may have had another prefix
+        let aliases = self.aliases()?;
+        if let Some(current) = aliases.resolve(id).or_else(|| aliases.resolve(&search_id))
+            && let Some(path) = self.exact_file_for_id(current)?
+        {
+            return Ok(path);
+        }
+
         if self.data_path.exists() {
             for entry in std::fs::read_dir(&self.data_path)? {
                 let entry = entry?;
@@ -401,7 +865,35 @@ impl PeaRepository {
             }
         }
 
-        Err(PeasError::NotFound(id.to_string()))
+        // Last, a title slug; peas written before slugs were stored match by title
+        let matches: Vec<Pea> = self
+            .list()?
+            .into_iter()
+            .filter(|p| p.slug.clone().or_else(|| title_slug(&p.title)).as_deref() == Some(id))
+            .collect();
+        match matches.as_slice() {
+            [pea] => self
+                .exact_file_for_id(&pea.id)?
+                .ok_or_else(|| PeasError::NotFound(id.to_string())),
+            [] => Err(PeasError::NotFound(id.to_string())),
+            _ => Err(PeasError::validation(
+                "id",
+                format!(
+                    "Slug '{}' matches several peas, use an ID: {}",
+                    id,
+                    matches
+                        .iter()
+                        .map(|p| p.id.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            )),
+        }
+    }
+
+    /// Former IDs of renamed peas (see `peas mv`)
+    pub fn aliases(&self) -> Result<Aliases> {
+        Aliases::load(&self.data_path)
     }
 
     pub fn find_by_type(&self, pea_type: PeaType) -> Result<Vec<Pea>> {
@@ -454,10 +946,28 @@ impl PeaRepository {
     }
 }
 
+/// File in `dir` of the pea with exactly this ID
+fn exact_file_in(dir: &Path, id: &str) -> Result<Option<PathBuf>> {
+    if !dir.exists() {
+        return Ok(None);
+    }
+    let slugged = format!("{}--", id);
+    let bare = format!("{}.md", id);
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with(&slugged) || name == bare {
+            return Ok(Some(entry.path()));
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{PeaStatus, PeaType};
+    use crate::model::{Body, PeaStatus, PeaType};
     use tempfile::TempDir;
 
     fn setup_test_repo() -> (PeaRepository, TempDir) {
@@ -471,13 +981,64 @@ mod tests {
                 default_status: "todo".to_string(),
                 default_type: "task".to_string(),
                 frontmatter: "toml".to_string(),
+                tag_policy: Default::default(),
+                size_scale: Default::default(),
+                naming_scheme: Default::default(),
+                lock_closed: false,
+                cascade: Default::default(),
             },
             tui: crate::config::TuiSettings::default(),
+            log: Default::default(),
+            server: Default::default(),
+            lint: Default::default(),
+            open: Default::default(),
+            undo: Default::default(),
+            bulk: Default::default(),
+            display: Default::default(),
+            semantic: Default::default(),
+            validation: Default::default(),
+            tags: Default::default(),
+            components: Default::default(),
+            autotag: Default::default(),
+            rules: Default::default(),
+            actor: None,
         };
         let repo = PeaRepository::new(&config, temp_dir.path());
         (repo, temp_dir)
     }
 
+    #[test]
+    fn test_strict_tag_policy() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config: PeasConfig =
+            toml::from_str("[peas]\ntag_policy = \"strict\"\n\n[tags.ui]\n[tags.legacy]\n")
+                .unwrap();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+
+        let pea = Pea::new(
+            "peas-tag01".to_string(),
+            "Tagged".to_string(),
+            PeaType::Task,
+        )
+        .with_tags(vec!["ui".to_string(), "legacy".to_string()]);
+        repo.create(&pea).unwrap();
+        let stray = Pea::new("peas-tag02".to_string(), "Stray".to_string(), PeaType::Task)
+            .with_tags(vec!["uii".to_string()]);
+        assert!(matches!(
+            repo.create(&stray),
+            Err(PeasError::ValidationFailed { field: "tags", .. })
+        ));
+
+        // Dropping a tag from the allow-list does not block other edits
+        config.tags.remove("legacy");
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        let mut pea = repo.get("peas-tag01").unwrap();
+        pea.status = PeaStatus::InProgress;
+        repo.update(&mut pea).unwrap();
+        pea.tags.push("other".to_string());
+        assert!(repo.update(&mut pea).is_err());
+    }
+
     #[test]
     fn test_concurrent_edit_detection_rejects_stale_update() {
         let (repo, _temp_dir) = setup_test_repo();
@@ -488,7 +1049,7 @@ mod tests {
             "Original Title".to_string(),
             PeaType::Task,
         );
-        pea.body = "Original body".to_string();
+        pea.body = "Original body".into();
         repo.create(&pea).unwrap();
 
         // Load the pea (simulating first TUI instance)
@@ -526,7 +1087,7 @@ mod tests {
             "Original Title".to_string(),
             PeaType::Task,
         );
-        pea.body = "Original body".to_string();
+        pea.body = "Original body".into();
         repo.create(&pea).unwrap();
 
         // Load the pea (simulating first TUI instance)
@@ -565,7 +1126,7 @@ mod tests {
             "Original Title".to_string(),
             PeaType::Task,
         );
-        pea.body = "Original body".to_string();
+        pea.body = "Original body".into();
         repo.create(&pea).unwrap();
 
         // Load and modify
@@ -643,7 +1204,7 @@ mod tests {
         // Create some peas
         for i in 0..3 {
             let mut pea = Pea::new(format!("test-{}", i), format!("Pea {}", i), PeaType::Task);
-            pea.body = format!("Body {}", i);
+            pea.body = format!("Body {}", i).into();
             repo.create(&pea).unwrap();
         }
 
@@ -672,7 +1233,7 @@ mod tests {
             "Cached Pea".to_string(),
             PeaType::Task,
         );
-        pea.body = "Test body".to_string();
+        pea.body = "Test body".into();
         repo.create(&pea).unwrap();
 
         // First get() populates cache
@@ -718,7 +1279,7 @@ mod tests {
             "Original Title".to_string(),
             PeaType::Task,
         );
-        pea.body = "Original body".to_string();
+        pea.body = "Original body".into();
         repo.create(&pea).unwrap();
 
         // Load to populate cache
@@ -860,8 +1421,27 @@ mod tests {
                 default_status: "todo".to_string(),
                 default_type: "task".to_string(),
                 frontmatter: "toml".to_string(),
+                tag_policy: Default::default(),
+                size_scale: Default::default(),
+                naming_scheme: Default::default(),
+                lock_closed: false,
+                cascade: Default::default(),
             },
             tui: crate::config::TuiSettings::default(),
+            log: Default::default(),
+            server: Default::default(),
+            lint: Default::default(),
+            open: Default::default(),
+            undo: Default::default(),
+            bulk: Default::default(),
+            display: Default::default(),
+            semantic: Default::default(),
+            validation: Default::default(),
+            tags: Default::default(),
+            components: Default::default(),
+            autotag: Default::default(),
+            rules: Default::default(),
+            actor: None,
         };
         let repo = PeaRepository::new(&config, temp_dir.path());
         (repo, temp_dir)
@@ -892,8 +1472,27 @@ mod tests {
                 default_status: "todo".to_string(),
                 default_type: "task".to_string(),
                 frontmatter: "toml".to_string(),
+                tag_policy: Default::default(),
+                size_scale: Default::default(),
+                naming_scheme: Default::default(),
+                lock_closed: false,
+                cascade: Default::default(),
             },
             tui: crate::config::TuiSettings::default(),
+            log: Default::default(),
+            server: Default::default(),
+            lint: Default::default(),
+            open: Default::default(),
+            undo: Default::default(),
+            bulk: Default::default(),
+            display: Default::default(),
+            semantic: Default::default(),
+            validation: Default::default(),
+            tags: Default::default(),
+            components: Default::default(),
+            autotag: Default::default(),
+            rules: Default::default(),
+            actor: None,
         };
 
         // First repo generates some IDs
@@ -925,12 +1524,281 @@ mod tests {
                 default_status: "todo".to_string(),
                 default_type: "task".to_string(),
                 frontmatter: "toml".to_string(),
+                tag_policy: Default::default(),
+                size_scale: Default::default(),
+                naming_scheme: Default::default(),
+                lock_closed: false,
+                cascade: Default::default(),
             },
             tui: crate::config::TuiSettings::default(),
+            log: Default::default(),
+            server: Default::default(),
+            lint: Default::default(),
+            open: Default::default(),
+            undo: Default::default(),
+            bulk: Default::default(),
+            display: Default::default(),
+            semantic: Default::default(),
+            validation: Default::default(),
+            tags: Default::default(),
+            components: Default::default(),
+            autotag: Default::default(),
+            rules: Default::default(),
+            actor: None,
         };
         let repo = PeaRepository::new(&config, temp_dir.path());
 
         let id = repo.generate_id().unwrap();
         assert_eq!(id, "t-001");
     }
+
+    #[test]
+    fn test_file_stats_counts_parse_errors() {
+        let (repo, _temp_dir) = setup_test_repo();
+        let pea = Pea::new("test-abc12".to_string(), "Valid".to_string(), PeaType::Task);
+        repo.create(&pea).unwrap();
+        std::fs::write(
+            repo.data_path.join("test-zzz99--broken.md"),
+            "no frontmatter",
+        )
+        .unwrap();
+        std::fs::write(repo.data_path.join("README.md"), "not a ticket").unwrap();
+
+        let stats = repo.file_stats().unwrap();
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.parse_errors, 1);
+        assert!(stats.bytes > 0);
+        assert_eq!(repo.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_create_rejects_duplicate_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+
+        let pea = Pea::new("peas-abcd".to_string(), "First".to_string(), PeaType::Task);
+        repo.create(&pea).unwrap();
+
+        let clash = Pea::new("peas-abcd".to_string(), "Second".to_string(), PeaType::Bug);
+        assert!(matches!(
+            repo.create(&clash),
+            Err(PeasError::DuplicateId(id)) if id == "peas-abcd"
+        ));
+
+        // IDs that merely share a prefix are distinct
+        let prefix = Pea::new("peas-abc".to_string(), "Third".to_string(), PeaType::Task);
+        repo.create(&prefix).unwrap();
+    }
+
+    #[test]
+    fn test_put_keeps_timestamps_and_replaces_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+
+        repo.create(&Pea::new(
+            "peas-abcd".into(),
+            "Neighbour".into(),
+            PeaType::Task,
+        ))
+        .unwrap();
+        let mut pea = Pea::new("peas-abc".into(), "Synced".into(), PeaType::Bug);
+        pea.updated = "2020-01-01T00:00:00Z".parse().unwrap();
+        repo.put(&pea).unwrap();
+
+        pea.title = "Synced again".into();
+        let path = repo.put(&pea).unwrap();
+        assert!(path.ends_with("peas-abc--synced-again.md"));
+
+        let fresh = PeaRepository::new(&config, temp_dir.path());
+        assert_eq!(fresh.get("peas-abc").unwrap(), pea);
+        assert_eq!(fresh.get("peas-abcd").unwrap().title, "Neighbour");
+        assert_eq!(fresh.list().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_list_summaries_follow_file_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let fresh = || PeaRepository::new(&config, temp_dir.path());
+        let repo = fresh();
+        for (id, title) in [("peas-aaaaa", "First"), ("peas-bbbbb", "Second")] {
+            let pea = Pea::new(id.into(), title.into(), PeaType::Task).with_body("Body".into());
+            repo.create(&pea).unwrap();
+        }
+
+        let summaries = fresh().list_summaries().unwrap();
+        let mut listed = fresh().list().unwrap();
+        listed.iter_mut().for_each(|p| p.body = Body::NotLoaded);
+        assert_eq!(summaries, listed);
+        assert!(
+            temp_dir
+                .path()
+                .join(".peas/local")
+                .join(crate::storage::INDEX_FILE)
+                .exists()
+        );
+
+        // Edits and deletions made outside peas are picked up
+        let path = repo.find_file_by_id("peas-aaaaa").unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace("First", "First, edited by hand")).unwrap();
+        std::fs::remove_file(repo.find_file_by_id("peas-bbbbb").unwrap()).unwrap();
+        let summaries = fresh().list_summaries().unwrap();
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].title, "First, edited by hand");
+        assert!(!summaries[0].body.is_loaded());
+    }
+
+    #[test]
+    fn test_summary_bodies_load_on_demand() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        let pea = Pea::new("peas-aaaaa".into(), "First".into(), PeaType::Task)
+            .with_body("Steps to reproduce".into());
+        repo.create(&pea).unwrap();
+
+        let mut summary = PeaRepository::new(&config, temp_dir.path())
+            .list_summaries()
+            .unwrap()
+            .remove(0);
+        assert!(!summary.body.is_loaded());
+
+        let mut loaded = summary.clone();
+        repo.load_body(&mut loaded).unwrap();
+        assert_eq!(loaded.body, "Steps to reproduce");
+        assert!(summary.body.to_mut().is_err());
+
+        // Writing it back reads the body first instead of wiping it on disk
+        summary.title = "Renamed".into();
+        repo.update(&mut summary).unwrap();
+        assert_eq!(summary.body, "Steps to reproduce");
+        let stored = repo.get("peas-aaaaa").unwrap();
+        assert_eq!(stored.title, "Renamed");
+        assert_eq!(stored.body, "Steps to reproduce");
+    }
+
+    #[test]
+    fn test_actor_is_credited_with_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig {
+            actor: Some("agent-x".into()),
+            ..Default::default()
+        };
+        let repo = PeaRepository::new(&config, temp_dir.path());
+
+        repo.create(&Pea::new(
+            "peas-abc12".into(),
+            "Login".into(),
+            PeaType::Task,
+        ))
+        .unwrap();
+        let mut pea = repo.get("peas-abc12").unwrap();
+        assert_eq!(pea.created_by.as_deref(), Some("agent-x"));
+        assert_eq!(pea.updated_by.as_deref(), Some("agent-x"));
+
+        let human = PeaRepository::new(&config, temp_dir.path()).with_actor(Some("ann".into()));
+        pea.title = "Login form".into();
+        human.update(&mut pea).unwrap();
+        let pea = human.get("peas-abc12").unwrap();
+        assert_eq!(pea.created_by.as_deref(), Some("agent-x"));
+        assert_eq!(pea.updated_by.as_deref(), Some("ann"));
+
+        // A change without an actor is not credited to the previous one
+        let anonymous = PeaRepository::new(&PeasConfig::default(), temp_dir.path());
+        let mut pea = anonymous.get("peas-abc12").unwrap();
+        anonymous.update(&mut pea).unwrap();
+        assert_eq!(anonymous.get("peas-abc12").unwrap().updated_by, None);
+    }
+
+    #[test]
+    fn test_components_are_checked_and_auto_assign() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config: PeasConfig = toml::from_str(
+            "[components.auth]\nowners = [\"ann\", \"bob\"]\nauto_assign = true\n\n[components.ui]\nowners = [\"cid\"]\n",
+        )
+        .unwrap();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+
+        let login = Pea::new("peas-cmp01".into(), "Login".into(), PeaType::Task)
+            .with_component(Some("auth".into()));
+        repo.create(&login).unwrap();
+        assert_eq!(
+            repo.get("peas-cmp01").unwrap().assignee.as_deref(),
+            Some("ann")
+        );
+
+        // Without auto_assign, or with an assignee given, nobody is picked
+        let button = Pea::new("peas-cmp02".into(), "Button".into(), PeaType::Task)
+            .with_component(Some("ui".into()));
+        repo.create(&button).unwrap();
+        assert_eq!(repo.get("peas-cmp02").unwrap().assignee, None);
+        let logout = Pea::new("peas-cmp03".into(), "Logout".into(), PeaType::Task)
+            .with_component(Some("auth".into()))
+            .with_assignee(Some("dee".into()));
+        repo.create(&logout).unwrap();
+        assert_eq!(
+            repo.get("peas-cmp03").unwrap().assignee.as_deref(),
+            Some("dee")
+        );
+
+        let stray = Pea::new("peas-cmp04".into(), "Stray".into(), PeaType::Task)
+            .with_component(Some("billing".into()));
+        assert!(matches!(
+            repo.create(&stray),
+            Err(PeasError::ValidationFailed {
+                field: "component",
+                ..
+            })
+        ));
+
+        // Dropping a component from the config does not block other edits
+        config.components.remove("auth");
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        let mut pea = repo.get("peas-cmp01").unwrap();
+        pea.status = PeaStatus::InProgress;
+        repo.update(&mut pea).unwrap();
+        pea.component = Some("billing".into());
+        assert!(repo.update(&mut pea).is_err());
+    }
+
+    #[test]
+    fn test_slug_lookup_and_naming_scheme() {
+        let (repo, temp_dir) = setup_test_repo();
+        let mut pea = Pea::new("test-slug1".into(), "Fix login bug".into(), PeaType::Bug);
+        pea.slug = None;
+        let path = repo.create(&pea).unwrap();
+        assert!(path.ends_with("test-slug1--fix-login-bug.md"));
+
+        // The slug is stored and follows the title
+        let mut pea = repo.get("fix-login-bug").unwrap();
+        assert_eq!(pea.id, "test-slug1");
+        pea.title = "Fix login redirect".into();
+        repo.update(&mut pea).unwrap();
+        assert_eq!(pea.slug.as_deref(), Some("fix-login-redirect"));
+        assert!(repo.get("fix-login-bug").is_err());
+
+        let twin = Pea::new(
+            "test-slug2".into(),
+            "Fix login redirect".into(),
+            PeaType::Bug,
+        );
+        repo.create(&twin).unwrap();
+        assert!(matches!(
+            repo.find_file_by_id("fix-login-redirect"),
+            Err(PeasError::ValidationFailed { field: "id", .. })
+        ));
+
+        // Without slugs in file names, files are renamed on their next write
+        let mut config = PeasConfig::default();
+        config.peas.prefix = "test-".into();
+        config.peas.naming_scheme = NamingScheme::Id;
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        let mut pea = repo.get("test-slug1").unwrap();
+        let path = repo.update(&mut pea).unwrap();
+        assert!(path.ends_with("test-slug1.md"));
+        assert_eq!(repo.get("test-slug1").unwrap().slug, pea.slug);
+    }
 }
@@ -0,0 +1,473 @@
+//! Offline-first sync with another peas instance over its GraphQL API.
+//!
+//! Each side keeps working on its own files. `peas sync --remote <url>`
+//! pulls the peas the remote changed since the last sync, pushes the ones
+//! changed locally, and resolves peas changed on both sides by keeping the
+//! version with the later `updated` timestamp. Every run is recorded in a
+//! journal under the gitignored `.peas/local/`, which also remembers when
+//! each remote was last synced.
+
+use crate::error::{PeasError, Result};
+use crate::http::http_client;
+use crate::local::LocalState;
+use crate::model::Pea;
+use crate::storage::{
+    FrontmatterFormat, PeaRepository, parse_markdown, render_markdown_with_format,
+};
+use crate::undo::UndoOperation;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Journal file inside `.peas/local/`
+pub const JOURNAL_FILE: &str = "sync-journal.json";
+
+/// Runs kept in the journal; older ones are dropped
+const MAX_JOURNAL_ENTRIES: usize = 100;
+
+/// Which version of a pea to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The incoming version is newer (or there is no local one)
+    TakeIncoming,
+    /// The local version is newer
+    KeepLocal,
+    /// Both carry the same timestamp
+    Same,
+}
+
+/// Later `updated` timestamp wins
+pub fn resolve(local: Option<&Pea>, incoming: &Pea) -> Resolution {
+    match local {
+        None => Resolution::TakeIncoming,
+        Some(local) if incoming.updated > local.updated => Resolution::TakeIncoming,
+        Some(local) if incoming.updated < local.updated => Resolution::KeepLocal,
+        Some(_) => Resolution::Same,
+    }
+}
+
+/// A pea as exchanged between instances: its full markdown file
+pub fn encode(pea: &Pea) -> Result<String> {
+    render_markdown_with_format(pea, FrontmatterFormat::Toml)
+}
+
+/// Counterpart of [`encode`]
+pub fn decode(content: &str) -> Result<Pea> {
+    parse_markdown(content)
+}
+
+/// Store peas pushed by another instance unless the stored version is as new
+/// or newer; returns the IDs that were written, or would be with `dry_run`
+///
+/// Peas go through the same checks, rules and hooks as any create or update,
+/// so an accepted change is stamped with the time it was stored. A pea is
+/// written after the pushed peas it points at, so a child can arrive along with
+/// its parent, and all of them are checked before the first is written. How to
+/// undo each write is pushed onto `operations`, which on an error holds the
+/// writes that were already made.
+pub fn accept(
+    repo: &PeaRepository,
+    peas: Vec<Pea>,
+    dry_run: bool,
+    operations: &mut Vec<UndoOperation>,
+) -> Result<Vec<String>> {
+    let mut offered = Vec::new();
+    for pea in push_order(peas) {
+        let local = match repo.get(&pea.id) {
+            Ok(local) => Some(local),
+            Err(PeasError::NotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+        if resolve(local.as_ref(), &pea) == Resolution::TakeIncoming {
+            offered.push((pea, local));
+        }
+    }
+
+    // New peas of the batch are not stored yet while the others are checked
+    let mut pending = HashSet::new();
+    for (pea, local) in &offered {
+        let mut checked = pea.clone();
+        if checked
+            .parent
+            .as_ref()
+            .is_some_and(|id| pending.contains(id))
+        {
+            checked.parent = None;
+        }
+        checked.blocking.retain(|id| !pending.contains(id));
+        match local {
+            Some(local) => {
+                checked.updated = local.updated;
+                repo.preview_update(&checked)?;
+            }
+            None => {
+                repo.preview_create(&checked)?;
+                pending.insert(pea.id.clone());
+            }
+        }
+    }
+
+    let accepted = offered.iter().map(|(pea, _)| pea.id.clone()).collect();
+    if dry_run {
+        return Ok(accepted);
+    }
+    for (mut pea, local) in offered {
+        match local {
+            Some(local) => {
+                let path = repo.find_file_by_id(&pea.id)?;
+                let operation = UndoOperation::update_from_file(&pea.id, &path)?;
+                // The change is based on the stored version, not a concurrent edit
+                pea.updated = local.updated;
+                repo.update(&mut pea)?;
+                operations.push(operation);
+            }
+            None => {
+                let file_path = repo.create(&pea)?;
+                operations.push(UndoOperation::Create {
+                    id: pea.id.clone(),
+                    file_path,
+                });
+            }
+        }
+    }
+    Ok(accepted)
+}
+
+/// `peas` reordered so that each comes after the peas of the batch it points at
+///
+/// Peas pointing at each other in a cycle keep their order at the end.
+fn push_order(mut peas: Vec<Pea>) -> Vec<Pea> {
+    let mut ordered = Vec::with_capacity(peas.len());
+    while !peas.is_empty() {
+        let waiting_ids: HashSet<String> = peas.iter().map(|p| p.id.clone()).collect();
+        let (ready, waiting): (Vec<Pea>, Vec<Pea>) = peas.into_iter().partition(|p| {
+            !p.parent
+                .iter()
+                .chain(&p.blocking)
+                .any(|id| *id != p.id && waiting_ids.contains(id))
+        });
+        if ready.is_empty() {
+            ordered.extend(waiting);
+            break;
+        }
+        ordered.extend(ready);
+        peas = waiting;
+    }
+    ordered
+}
+
+/// One `peas sync` run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncReport {
+    pub remote: String,
+    pub at: DateTime<Utc>,
+    /// Peas written locally from the remote
+    pub pulled: Vec<String>,
+    /// Peas the remote accepted from us
+    pub pushed: Vec<String>,
+    /// Peas changed on both sides; the later version was kept
+    pub conflicts: Vec<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
+}
+
+/// Sync journal stored in `.peas/local/sync-journal.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SyncJournal {
+    /// Start time of the last successful sync per remote URL
+    #[serde(default)]
+    pub remotes: BTreeMap<String, DateTime<Utc>>,
+    /// Past runs, oldest first
+    #[serde(default)]
+    pub runs: Vec<SyncReport>,
+}
+
+impl SyncJournal {
+    pub fn path(data_path: &Path) -> PathBuf {
+        LocalState::new(data_path).path(JOURNAL_FILE)
+    }
+
+    /// Load the journal, or an empty one before the first sync
+    ///
+    /// A journal left in `.peas/` by older versions is moved to `.peas/local/` first.
+    pub fn load(data_path: &Path) -> Result<Self> {
+        let local = LocalState::new(data_path);
+        let legacy = data_path.join(JOURNAL_FILE);
+        if legacy.exists() && local.read(JOURNAL_FILE).is_none() {
+            local.write(JOURNAL_FILE, &std::fs::read_to_string(&legacy)?)?;
+            std::fs::remove_file(&legacy)?;
+        }
+        let path = local.path(JOURNAL_FILE);
+        let Some(content) = local.read(JOURNAL_FILE) else {
+            return Ok(Self::default());
+        };
+        serde_json::from_str(&content).map_err(|e| PeasError::parse(e.to_string()).with_path(&path))
+    }
+
+    pub fn save(&self, data_path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| PeasError::Storage(format!("Failed to write sync journal: {}", e)))?;
+        LocalState::new(data_path).write(JOURNAL_FILE, &content)
+    }
+
+    /// Record a finished run and move the remote's sync point to its start
+    pub fn record(&mut self, report: SyncReport) {
+        self.remotes.insert(report.remote.clone(), report.at);
+        self.runs.push(report);
+        let excess = self.runs.len().saturating_sub(MAX_JOURNAL_ENTRIES);
+        self.runs.drain(..excess);
+    }
+}
+
+/// The other side of a sync
+pub trait Remote {
+    /// Peas changed after `since`, or all peas when None
+    fn changes(&self, since: Option<DateTime<Utc>>) -> Result<Vec<Pea>>;
+
+    /// Offer peas to the remote; returns the IDs it accepted
+    fn push(&self, peas: &[Pea]) -> Result<Vec<String>>;
+}
+
+/// A `peas serve` instance reached over HTTP
+pub struct HttpRemote {
+    url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpRemote {
+    pub fn new(url: &str) -> Result<Self> {
+        let client = http_client(std::time::Duration::from_secs(30))
+            .map_err(|e| PeasError::Sync(e.to_string()))?;
+        Ok(Self {
+            url: url.to_string(),
+            client,
+        })
+    }
+
+    fn graphql(&self, query: &str, variables: serde_json::Value) -> Result<serde_json::Value> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({ "query": query, "variables": variables }))
+            .send()
+            .map_err(|e| PeasError::Sync(format!("{}: {}", self.url, e)))?;
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .map_err(|e| PeasError::Sync(format!("{} returned {}: {}", self.url, status, e)))?;
+        if let Some(error) = body["errors"].get(0) {
+            return Err(PeasError::Sync(format!(
+                "{}: {}",
+                self.url,
+                error["message"].as_str().unwrap_or("unknown error")
+            )));
+        }
+        Ok(body["data"].clone())
+    }
+}
+
+impl Remote for HttpRemote {
+    fn changes(&self, since: Option<DateTime<Utc>>) -> Result<Vec<Pea>> {
+        let data = self.graphql(
+            "query($since: String) { syncChanges(since: $since) { content } }",
+            serde_json::json!({ "since": since.map(|t| t.to_rfc3339()) }),
+        )?;
+        data["syncChanges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|p| decode(p["content"].as_str().unwrap_or_default()))
+            .collect()
+    }
+
+    fn push(&self, peas: &[Pea]) -> Result<Vec<String>> {
+        let contents = peas.iter().map(encode).collect::<Result<Vec<_>>>()?;
+        let data = self.graphql(
+            "mutation($peas: [String!]!) { syncPush(peas: $peas) }",
+            serde_json::json!({ "peas": contents }),
+        )?;
+        Ok(data["syncPush"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|id| id.as_str().map(str::to_string))
+            .collect())
+    }
+}
+
+/// Pull remote changes, push local ones and record the run in the journal
+pub fn sync(
+    repo: &PeaRepository,
+    data_path: &Path,
+    remote_url: &str,
+    remote: &dyn Remote,
+    dry_run: bool,
+) -> Result<SyncReport> {
+    let mut journal = SyncJournal::load(data_path)?;
+    let since = journal.remotes.get(remote_url).copied();
+    let changed_since = |pea: &Pea| since.is_none_or(|t| pea.updated > t);
+
+    let mut report = SyncReport {
+        remote: remote_url.to_string(),
+        at: Utc::now(),
+        dry_run,
+        ..Default::default()
+    };
+
+    let mut pulled = HashSet::new();
+    for incoming in remote.changes(since)? {
+        let local = repo.get(&incoming.id).ok();
+        let local_changed = local.as_ref().is_some_and(changed_since);
+        match resolve(local.as_ref(), &incoming) {
+            Resolution::TakeIncoming => {
+                if local_changed {
+                    report.conflicts.push(incoming.id.clone());
+                }
+                if !dry_run {
+                    repo.put(&incoming)?;
+                }
+                pulled.insert(incoming.id.clone());
+                report.pulled.push(incoming.id);
+            }
+            Resolution::KeepLocal if local_changed => report.conflicts.push(incoming.id),
+            Resolution::KeepLocal | Resolution::Same => {}
+        }
+    }
+
+    let mut outgoing: Vec<Pea> = repo
+        .list()?
+        .into_iter()
+        .filter(|p| changed_since(p) && !pulled.contains(&p.id))
+        .collect();
+    outgoing.sort_by(|a, b| a.id.cmp(&b.id));
+    if dry_run {
+        report.pushed = outgoing.iter().map(|p| p.id.clone()).collect();
+    } else if !outgoing.is_empty() {
+        let accepted: HashSet<String> = remote.push(&outgoing)?.into_iter().collect();
+        for pea in outgoing {
+            if accepted.contains(&pea.id) {
+                report.pushed.push(pea.id);
+            } else if !report.conflicts.contains(&pea.id) {
+                // The remote has a newer version we did not see yet
+                report.conflicts.push(pea.id);
+            }
+        }
+    }
+
+    if !dry_run {
+        journal.record(report.clone());
+        journal.save(data_path)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PeasConfig;
+    use crate::model::PeaType;
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    /// In-memory remote that accepts pushes with a newer timestamp
+    #[derive(Default)]
+    struct FakeRemote {
+        peas: RefCell<BTreeMap<String, Pea>>,
+    }
+
+    impl Remote for FakeRemote {
+        fn changes(&self, since: Option<DateTime<Utc>>) -> Result<Vec<Pea>> {
+            Ok(self
+                .peas
+                .borrow()
+                .values()
+                .filter(|p| since.is_none_or(|t| p.updated > t))
+                .cloned()
+                .collect())
+        }
+
+        fn push(&self, peas: &[Pea]) -> Result<Vec<String>> {
+            let mut stored = self.peas.borrow_mut();
+            let mut accepted = Vec::new();
+            for pea in peas {
+                if resolve(stored.get(&pea.id), pea) == Resolution::TakeIncoming {
+                    stored.insert(pea.id.clone(), pea.clone());
+                    accepted.push(pea.id.clone());
+                }
+            }
+            Ok(accepted)
+        }
+    }
+
+    fn pea(id: &str, title: &str, updated: &str) -> Pea {
+        let mut pea = Pea::new(id.into(), title.into(), PeaType::Task);
+        pea.updated = updated.parse().unwrap();
+        pea
+    }
+
+    #[test]
+    fn test_resolve_prefers_later_update() {
+        let old = pea("peas-a", "Old", "2026-01-01T00:00:00Z");
+        let new = pea("peas-a", "New", "2026-02-01T00:00:00Z");
+        assert_eq!(resolve(None, &old), Resolution::TakeIncoming);
+        assert_eq!(resolve(Some(&old), &new), Resolution::TakeIncoming);
+        assert_eq!(resolve(Some(&new), &old), Resolution::KeepLocal);
+        assert_eq!(resolve(Some(&new), &new), Resolution::Same);
+    }
+
+    #[test]
+    fn test_sync_pulls_pushes_and_resolves_conflicts() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let data_path = config.data_path(temp_dir.path());
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        let remote = FakeRemote::default();
+
+        repo.put(&pea("peas-local", "Local only", "2026-01-01T00:00:00Z"))
+            .unwrap();
+        repo.put(&pea("peas-both", "Edited here", "2026-01-03T00:00:00Z"))
+            .unwrap();
+        for p in [
+            pea("peas-remote", "Remote only", "2026-01-01T00:00:00Z"),
+            pea("peas-both", "Edited there", "2026-01-02T00:00:00Z"),
+        ] {
+            remote.peas.borrow_mut().insert(p.id.clone(), p);
+        }
+
+        let preview = sync(&repo, &data_path, "http://remote", &remote, true).unwrap();
+        assert_eq!(preview.pulled, vec!["peas-remote"]);
+        assert!(!SyncJournal::path(&data_path).exists());
+
+        let report = sync(&repo, &data_path, "http://remote", &remote, false).unwrap();
+        assert_eq!(report.pulled, vec!["peas-remote"]);
+        assert_eq!(report.pushed, vec!["peas-both", "peas-local"]);
+        assert_eq!(report.conflicts, vec!["peas-both"]);
+        assert_eq!(repo.get("peas-remote").unwrap().title, "Remote only");
+        assert_eq!(remote.peas.borrow()["peas-both"].title, "Edited here");
+
+        // Nothing changed since the recorded sync point
+        let again = sync(&repo, &data_path, "http://remote", &remote, false).unwrap();
+        assert!(again.pulled.is_empty() && again.pushed.is_empty());
+        let journal = SyncJournal::load(&data_path).unwrap();
+        assert_eq!(journal.runs.len(), 2);
+        assert_eq!(journal.remotes["http://remote"], again.at);
+    }
+
+    #[test]
+    fn test_journal_moves_out_of_tracked_data_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut journal = SyncJournal::default();
+        journal.record(SyncReport {
+            remote: "http://remote".into(),
+            ..Default::default()
+        });
+        let legacy = temp_dir.path().join(JOURNAL_FILE);
+        std::fs::write(&legacy, serde_json::to_string(&journal).unwrap()).unwrap();
+
+        let loaded = SyncJournal::load(temp_dir.path()).unwrap();
+        assert_eq!(loaded.runs.len(), 1);
+        assert!(!legacy.exists());
+        assert!(SyncJournal::path(temp_dir.path()).exists());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::config::{DisplaySettings, DisplayTimezone};
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
+use std::io::{IsTerminal, Write};
+use std::process::Child;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set while stdout goes through the pager
+static PAGING: AtomicBool = AtomicBool::new(false);
+
+/// When to color output (`--color`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal, unless `NO_COLOR` is set
+    #[default]
+    Auto,
+    /// Always color, also when piped
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// Apply the choice to command output on stdout
+    pub fn apply(self) {
+        match self {
+            ColorChoice::Auto => colored::control::unset_override(),
+            ColorChoice::Always => colored::control::set_override(true),
+            ColorChoice::Never => colored::control::set_override(false),
+        }
+    }
+
+    /// Whether log lines on stderr are colored
+    pub fn for_stderr(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Width of the terminal output is shown on, or `None` when stdout is not a
+/// terminal (directly or through the pager)
+pub fn output_width() -> Option<usize> {
+    if !std::io::stdout().is_terminal() && !PAGING.load(Ordering::Relaxed) {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+}
+
+/// How timestamps are printed: in UTC or local time, or relative to now
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeFormat {
+    pub timezone: DisplayTimezone,
+    pub relative: bool,
+}
+
+impl TimeFormat {
+    /// The `[display]` settings, made relative by `--relative`
+    pub fn new(settings: &DisplaySettings, relative: bool) -> Self {
+        Self {
+            timezone: settings.timezone,
+            relative: relative || settings.relative,
+        }
+    }
+
+    /// Date and time to the minute, e.g. `2024-03-01 14:05`
+    pub fn datetime(self, at: DateTime<Utc>) -> String {
+        self.format(at, "%Y-%m-%d %H:%M")
+    }
+
+    /// Date only, e.g. `2024-03-01`
+    pub fn date(self, at: DateTime<Utc>) -> String {
+        self.format(at, "%Y-%m-%d")
+    }
+
+    fn format(self, at: DateTime<Utc>, format: &str) -> String {
+        if self.relative {
+            return relative_time(at, Utc::now());
+        }
+        match self.timezone {
+            DisplayTimezone::Utc => at.format(format).to_string(),
+            DisplayTimezone::Local => at.with_timezone(&Local).format(format).to_string(),
+        }
+    }
+}
+
+/// How long before `now` a time was, in its largest whole unit
+///
+/// ```
+/// use chrono::{Duration, Utc};
+/// use peas::terminal::relative_time;
+///
+/// let now = Utc::now();
+/// assert_eq!(relative_time(now - Duration::seconds(20), now), "just now");
+/// assert_eq!(relative_time(now - Duration::hours(1), now), "1 hour ago");
+/// assert_eq!(relative_time(now - Duration::days(3), now), "3 days ago");
+/// assert_eq!(relative_time(now + Duration::days(14), now), "in 2 weeks");
+/// ```
+pub fn relative_time(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = (now - at).num_seconds();
+    let amount = seconds.unsigned_abs();
+    if amount < 60 {
+        return "just now".to_string();
+    }
+    let (count, unit) = [
+        (365 * 86_400, "year"),
+        (30 * 86_400, "month"),
+        (7 * 86_400, "week"),
+        (86_400, "day"),
+        (3_600, "hour"),
+        (60, "minute"),
+    ]
+    .into_iter()
+    .find(|(size, _)| amount >= *size)
+    .map(|(size, unit)| (amount / size, unit))
+    .unwrap_or((amount / 60, "minute"));
+    let plural = if count == 1 { "" } else { "s" };
+    if seconds >= 0 {
+        format!("{} {}{} ago", count, unit, plural)
+    } else {
+        format!("in {} {}{}", count, unit, plural)
+    }
+}
+
+/// Pager command line: `PEAS_PAGER`, then `PAGER`, then `less`
+///
+/// An empty value or `cat` turns paging off.
+pub fn pager_command() -> Option<String> {
+    let pager = std::env::var("PEAS_PAGER")
+        .or_else(|_| std::env::var("PAGER"))
+        .unwrap_or_else(|_| "less".to_string());
+    let pager = pager.trim();
+    (!pager.is_empty() && pager != "cat").then(|| pager.to_string())
+}
+
+/// Pipes stdout through a pager until dropped
+///
+/// `less` runs with `LESS=FRX` unless `LESS` is set, so output that fits on
+/// one screen is printed as is and colors come through.
+pub struct Pager {
+    child: Child,
+    /// The terminal, restored on drop
+    #[cfg(unix)]
+    terminal: std::os::fd::OwnedFd,
+}
+
+impl Pager {
+    /// Start paging if stdout is a terminal and a pager is configured
+    #[cfg(unix)]
+    pub fn start() -> Option<Self> {
+        use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+        use std::process::{Command, Stdio};
+
+        if !std::io::stdout().is_terminal() {
+            return None;
+        }
+        let command = pager_command()?;
+
+        // Decide on colors while stdout is still the terminal
+        colored::control::set_override(colored::control::SHOULD_COLORIZE.should_colorize());
+
+        let mut pager = Command::new("sh");
+        pager.arg("-c").arg(&command).stdin(Stdio::piped());
+        if std::env::var_os("LESS").is_none() {
+            pager.env("LESS", "FRX");
+        }
+        let mut child = match pager.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::debug!(pager = %command, error = %e, "Could not start pager");
+                return None;
+            }
+        };
+        let input = child.stdin.take()?;
+
+        // SAFETY: plain descriptor calls on stdout and the pipe we own; the
+        // duplicate is owned by the returned guard
+        let terminal = unsafe {
+            let saved = libc::dup(libc::STDOUT_FILENO);
+            if saved < 0 {
+                return None;
+            }
+            let terminal = OwnedFd::from_raw_fd(saved);
+            if libc::dup2(input.as_raw_fd(), libc::STDOUT_FILENO) < 0 {
+                return None;
+            }
+            // Quitting the pager early ends the command quietly, like `git log`
+            libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+            terminal
+        };
+        PAGING.store(true, Ordering::Relaxed);
+        Some(Self { child, terminal })
+    }
+
+    /// Paging needs Unix descriptors; elsewhere output is printed directly
+    #[cfg(not(unix))]
+    pub fn start() -> Option<Self> {
+        None
+    }
+}
+
+impl Drop for Pager {
+    fn drop(&mut self) {
+        let _ = std::io::stdout().flush();
+        // Give stdout back to the terminal, which closes the pager's input
+        #[cfg(unix)]
+        {
+            use std::os::fd::AsRawFd;
+            // SAFETY: the saved descriptor stays open until the guard is gone
+            unsafe {
+                libc::dup2(self.terminal.as_raw_fd(), libc::STDOUT_FILENO);
+            }
+        }
+        PAGING.store(false, Ordering::Relaxed);
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,173 @@
+//! Fixtures for tests that drive peas through its library API.
+//!
+//! [`TestProject`] is a throwaway project in a temp directory, [`fixed_pea`]
+//! builds peas with reproducible timestamps, and [`assert_golden`] compares
+//! rendered output against files checked into the repository. Helpers panic
+//! on setup failures, as test code would.
+//!
+//! ```
+//! use peas::model::PeaType;
+//! use peas::testing::TestProject;
+//!
+//! let project = TestProject::new();
+//! project.run(&["create", "From the CLI", "-t", "bug"]).unwrap();
+//!
+//! let peas = project.client().list().unwrap();
+//! assert_eq!(peas[0].pea_type, PeaType::Bug);
+//! ```
+
+use crate::{
+    api::Client,
+    cli::{Cli, handlers::CommandContext},
+    config::PeasConfig,
+    model::{Pea, PeaType},
+};
+use chrono::{DateTime, TimeZone, Utc};
+use clap::Parser;
+use std::path::Path;
+use tempfile::TempDir;
+
+/// Set to `1` to rewrite golden files from the actual output instead of comparing
+pub const UPDATE_GOLDEN_ENV: &str = "PEAS_UPDATE_GOLDEN";
+
+/// Timestamp given to peas built by [`fixed_pea`]
+pub fn fixed_time() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 1, 15, 10, 30, 0).unwrap()
+}
+
+/// A pea whose `created`/`updated` are [`fixed_time`], so its markdown is stable
+pub fn fixed_pea(id: &str, title: &str, pea_type: PeaType) -> Pea {
+    let mut pea = Pea::new(id.to_string(), title.to_string(), pea_type);
+    pea.created = fixed_time();
+    pea.updated = fixed_time();
+    pea
+}
+
+/// An initialized peas project in a temporary directory, removed on drop
+pub struct TestProject {
+    dir: TempDir,
+    config: PeasConfig,
+}
+
+impl Default for TestProject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestProject {
+    /// Project with the default config (`peas-` prefix, TOML frontmatter)
+    pub fn new() -> Self {
+        Self::with_config(PeasConfig::default())
+    }
+
+    pub fn with_config(config: PeasConfig) -> Self {
+        let dir = TempDir::new().expect("create temp dir");
+        Client::init(dir.path(), config.clone()).expect("initialize test project");
+        Self { dir, config }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// A fresh client, so reads always come from disk rather than a cache
+    pub fn client(&self) -> Client {
+        Client::with_config(self.config.clone(), self.root().to_path_buf())
+    }
+
+    /// Run `peas <args>` against this project through the CLI handlers
+    ///
+    /// Commands that do not run in a project (`init`, `migrate`, `doctor`)
+    /// depend on the process working directory and are rejected.
+    pub fn run(&self, args: &[&str]) -> anyhow::Result<()> {
+        let cli = Cli::try_parse_from(std::iter::once("peas").chain(args.iter().copied()))?;
+        let command = cli
+            .command
+            .ok_or_else(|| anyhow::anyhow!("No subcommand given"))?;
+        if !command.needs_project() {
+            anyhow::bail!("TestProject::run only supports commands that run in a project");
+        }
+        let ctx = CommandContext::new(self.config.clone(), self.root().to_path_buf());
+        crate::cli::handlers::dispatch(ctx, command)
+    }
+
+    /// Create a pea with a fresh ID and return it as stored
+    pub fn create(&self, title: &str, pea_type: PeaType) -> Pea {
+        let client = self.client();
+        let pea = client.new_pea(title, pea_type).expect("build pea");
+        self.insert(pea)
+    }
+
+    /// Store a prepared pea (e.g. from [`fixed_pea`]) and return it
+    pub fn insert(&self, pea: Pea) -> Pea {
+        self.client().create(&pea).expect("create pea");
+        pea
+    }
+
+    /// Raw markdown of the stored file for `id`
+    pub fn read_pea_file(&self, id: &str) -> String {
+        let path = self
+            .client()
+            .repository()
+            .find_file_by_id(id)
+            .expect("pea file exists");
+        std::fs::read_to_string(path).expect("read pea file")
+    }
+}
+
+/// Assert that `actual` matches the golden file at `path`
+///
+/// With `PEAS_UPDATE_GOLDEN=1` the file is (re)written instead. Line endings
+/// are normalized so checkouts with CRLF conversion still compare equal.
+pub fn assert_golden(path: impl AsRef<Path>, actual: &str) {
+    let path = path.as_ref();
+    if std::env::var(UPDATE_GOLDEN_ENV).is_ok_and(|v| v == "1") {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create golden dir");
+        }
+        std::fs::write(path, actual).expect("write golden file");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(path).unwrap_or_else(|_| {
+        panic!(
+            "Golden file {} is missing; rerun with {}=1 to create it",
+            path.display(),
+            UPDATE_GOLDEN_ENV
+        )
+    });
+    let expected = expected.replace("\r\n", "\n");
+    let actual = actual.replace("\r\n", "\n");
+    if expected != actual {
+        panic!(
+            "Output differs from golden file {} (rerun with {}=1 to accept)\n\
+             --- expected\n{}\n--- actual\n{}",
+            path.display(),
+            UPDATE_GOLDEN_ENV,
+            expected,
+            actual
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_golden_missing_file_panics_with_hint() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("missing.md");
+        let result = std::panic::catch_unwind(|| assert_golden(&path, "x"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_golden_ignores_line_endings() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("crlf.md");
+        std::fs::write(&path, "a\r\nb\r\n").unwrap();
+        assert_golden(&path, "a\nb\n");
+    }
+}
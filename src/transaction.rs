@@ -0,0 +1,353 @@
+use crate::error::{PeasError, Result};
+use crate::local::LocalState;
+use crate::model::Pea;
+use crate::storage::{
+    FrontmatterFormat, PeaRepository, detect_format, render_markdown_with_format,
+};
+use crate::undo::UndoOperation;
+use std::path::{Path, PathBuf};
+
+/// Journal of the running transaction, in `.peas/local/`
+pub const JOURNAL_FILE: &str = "transaction.json";
+
+/// Writes to several peas that take effect together or not at all
+///
+/// Each write is added to a journal before it happens. `commit` turns the
+/// journal into a single undo step; `rollback`, or dropping the transaction
+/// without committing, reverts the writes made so far. A journal left behind
+/// by a process that died mid-transaction is rolled back with [`recover`].
+///
+/// ```no_run
+/// use peas::config::PeasConfig;
+/// use peas::model::{Pea, PeaType};
+/// use peas::storage::PeaRepository;
+/// use peas::transaction::Transaction;
+/// use std::path::Path;
+///
+/// let config = PeasConfig::default();
+/// let root = Path::new(".");
+/// let repo = PeaRepository::new(&config, root);
+/// let mut tx = Transaction::begin(&repo, &config.data_path(root))?;
+/// tx.create(&Pea::new("peas-abc12".into(), "Parent".into(), PeaType::Epic))?;
+/// let child = Pea::new("peas-def34".into(), "Child".into(), PeaType::Task)
+///     .with_parent(Some("peas-abc12".into()));
+/// tx.create(&child)?;
+/// tx.commit("Create epic with task")?;
+/// # Ok::<(), peas::error::PeasError>(())
+/// ```
+pub struct Transaction<'a> {
+    repo: &'a PeaRepository,
+    data_path: PathBuf,
+    local: LocalState,
+    operations: Vec<UndoOperation>,
+    finished: bool,
+}
+
+impl<'a> Transaction<'a> {
+    /// Start a transaction, failing while another one is running or was interrupted
+    pub fn begin(repo: &'a PeaRepository, data_path: &Path) -> Result<Self> {
+        let local = LocalState::new(data_path);
+        if local.read(JOURNAL_FILE).is_some() {
+            return Err(PeasError::Storage(
+                "Another transaction is running or was interrupted; run `peas doctor --fix` to roll back an interrupted one".to_string(),
+            ));
+        }
+        let tx = Self {
+            repo,
+            data_path: data_path.to_path_buf(),
+            local,
+            operations: Vec::new(),
+            finished: false,
+        };
+        tx.save_journal()?;
+        Ok(tx)
+    }
+
+    /// Create a pea as part of the transaction
+    pub fn create(&mut self, pea: &Pea) -> Result<PathBuf> {
+        let filename = self.repo.generate_filename(&pea.id, &pea.title);
+        self.operations.push(UndoOperation::Create {
+            id: pea.id.clone(),
+            file_path: self.data_path.join(filename),
+        });
+        self.save_journal()?;
+        match self.repo.create(pea) {
+            Ok(path) => {
+                // A rule or pre-create hook may have changed the title and so the file name
+                if let Some(UndoOperation::Create { file_path, .. }) = self.operations.last_mut()
+                    && *file_path != path
+                {
+                    *file_path = path.clone();
+                    self.save_journal()?;
+                }
+                Ok(path)
+            }
+            Err(e) => {
+                // Nothing was written, and the file may belong to another pea
+                self.operations.pop();
+                self.save_journal()?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Update a pea as part of the transaction
+    pub fn update(&mut self, pea: &mut Pea) -> Result<PathBuf> {
+        let path = self.repo.find_file_by_id(&pea.id)?;
+        self.operations
+            .push(UndoOperation::update_from_file(&pea.id, &path)?);
+        self.save_journal()?;
+        self.repo.update(pea)
+    }
+
+    /// Write a pea as is to the file at `from`, renamed when its ID or title changed
+    ///
+    /// Unlike `update`, this skips hooks, rules and validation and keeps the
+    /// timestamps, for mechanical rewrites such as `peas migrate prefix`. It
+    /// works in the archive too.
+    pub fn rewrite(&mut self, old_id: &str, from: &Path, pea: &Pea) -> Result<PathBuf> {
+        let previous_content = std::fs::read_to_string(from)?;
+        let format = detect_format(&previous_content).unwrap_or(FrontmatterFormat::Toml);
+        let content = render_markdown_with_format(pea, format)?;
+        let to = from.with_file_name(self.repo.generate_filename(&pea.id, &pea.title));
+
+        if to == from {
+            self.operations.push(UndoOperation::Update {
+                id: pea.id.clone(),
+                file_path: to.clone(),
+                previous_content,
+            });
+        } else {
+            self.operations.push(UndoOperation::Delete {
+                id: old_id.to_string(),
+                file_path: from.to_path_buf(),
+                previous_content,
+            });
+            self.operations.push(UndoOperation::Create {
+                id: pea.id.clone(),
+                file_path: to.clone(),
+            });
+        }
+        self.save_journal()?;
+        std::fs::write(&to, content)?;
+        if to != from {
+            std::fs::remove_file(from)?;
+        }
+        self.repo.invalidate_cache();
+        Ok(to)
+    }
+
+    /// Write a file that is not a pea, such as the config, as part of the transaction
+    pub fn write_file(&mut self, path: &Path, content: &str) -> Result<()> {
+        let previous_content = match std::fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        self.operations.push(UndoOperation::File {
+            file_path: path.to_path_buf(),
+            previous_content,
+        });
+        self.save_journal()?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Move a file or directory, such as the assets of a pea, as part of the transaction
+    pub fn move_path(&mut self, from: &Path, to: &Path) -> Result<()> {
+        self.operations.push(UndoOperation::Move {
+            from: from.to_path_buf(),
+            to: to.to_path_buf(),
+        });
+        self.save_journal()?;
+        std::fs::rename(from, to)?;
+        Ok(())
+    }
+
+    /// Number of writes made so far
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Keep the writes, recorded as one undo step
+    pub fn commit(mut self, description: &str) -> Result<()> {
+        self.finished = true;
+        crate::undo::record_batch(
+            &self.repo.undo_manager(),
+            description,
+            std::mem::take(&mut self.operations),
+        )?;
+        self.local.remove(JOURNAL_FILE)
+    }
+
+    /// Revert the writes made so far, latest first
+    pub fn rollback(mut self) -> Result<()> {
+        self.finished = true;
+        self.revert()
+    }
+
+    fn revert(&mut self) -> Result<()> {
+        let operations = std::mem::take(&mut self.operations);
+        let result = operations
+            .into_iter()
+            .rev()
+            .try_for_each(UndoOperation::revert);
+        self.repo.invalidate_cache();
+        result?;
+        self.local.remove(JOURNAL_FILE)
+    }
+
+    fn save_journal(&self) -> Result<()> {
+        self.local.write(
+            JOURNAL_FILE,
+            &serde_json::to_string_pretty(&self.operations)?,
+        )
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        if !self.finished
+            && let Err(e) = self.revert()
+        {
+            tracing::error!(error = %e, "Failed to roll back transaction");
+        }
+    }
+}
+
+/// Roll back a transaction interrupted before it finished
+///
+/// Returns the number of writes reverted, or `None` if there was none.
+pub fn recover(data_path: &Path) -> Result<Option<usize>> {
+    let local = LocalState::new(data_path);
+    let Some(journal) = local.read(JOURNAL_FILE) else {
+        return Ok(None);
+    };
+    let operations: Vec<UndoOperation> = serde_json::from_str(&journal)?;
+    let count = operations.len();
+    for operation in operations.into_iter().rev() {
+        operation.revert()?;
+    }
+    local.remove(JOURNAL_FILE)?;
+    Ok(Some(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PeasConfig;
+    use crate::model::PeaType;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, PeasConfig, PeaRepository) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = PeasConfig::default();
+        let repo = PeaRepository::new(&config, temp_dir.path());
+        (temp_dir, config, repo)
+    }
+
+    #[test]
+    fn test_commit_keeps_writes_as_one_undo_step() {
+        let (temp_dir, config, repo) = setup();
+        let data_path = config.data_path(temp_dir.path());
+
+        let mut tx = Transaction::begin(&repo, &data_path).unwrap();
+        tx.create(&Pea::new("peas-abc12".into(), "One".into(), PeaType::Task))
+            .unwrap();
+        let mut pea = repo.get("peas-abc12").unwrap();
+        pea.title = "Renamed".into();
+        tx.update(&mut pea).unwrap();
+        assert!(
+            Transaction::begin(&repo, &data_path).is_err(),
+            "only one transaction at a time"
+        );
+        assert_eq!(tx.len(), 2);
+        tx.commit("Both").unwrap();
+
+        assert_eq!(repo.get("peas-abc12").unwrap().title, "Renamed");
+        assert_eq!(recover(&data_path).unwrap(), None);
+        let undo = repo.undo_manager();
+        assert_eq!(undo.undo().unwrap(), "Undone: Both");
+        repo.invalidate_cache();
+        assert!(!repo.exists("peas-abc12"));
+    }
+
+    #[test]
+    fn test_rollback_and_drop_revert_writes() {
+        let (temp_dir, config, repo) = setup();
+        let data_path = config.data_path(temp_dir.path());
+        repo.create(&Pea::new("peas-abc12".into(), "Kept".into(), PeaType::Task))
+            .unwrap();
+
+        let mut tx = Transaction::begin(&repo, &data_path).unwrap();
+        let mut pea = repo.get("peas-abc12").unwrap();
+        pea.title = "Changed".into();
+        tx.update(&mut pea).unwrap();
+        tx.create(&Pea::new("peas-new01".into(), "New".into(), PeaType::Task))
+            .unwrap();
+        // A duplicate fails without touching the existing file
+        assert!(
+            tx.create(&Pea::new("peas-abc12".into(), "Kept".into(), PeaType::Task))
+                .is_err()
+        );
+        tx.rollback().unwrap();
+        assert_eq!(repo.get("peas-abc12").unwrap().title, "Kept");
+        assert!(!repo.exists("peas-new01"));
+
+        // Dropped on an early return
+        {
+            let mut tx = Transaction::begin(&repo, &data_path).unwrap();
+            tx.create(&Pea::new("peas-new02".into(), "New".into(), PeaType::Task))
+                .unwrap();
+        }
+        assert!(!repo.exists("peas-new02"));
+        assert!(Transaction::begin(&repo, &data_path).is_ok());
+    }
+
+    #[test]
+    fn test_rewrite_moves_to_new_id_and_rolls_back() {
+        let (temp_dir, config, repo) = setup();
+        let data_path = config.data_path(temp_dir.path());
+        let from = repo
+            .create(&Pea::new(
+                "peas-abc12".into(),
+                "Moved".into(),
+                PeaType::Task,
+            ))
+            .unwrap();
+        let before = repo.get("peas-abc12").unwrap();
+
+        let mut tx = Transaction::begin(&repo, &data_path).unwrap();
+        let mut pea = before.clone();
+        pea.id = "peas-xyz12".into();
+        let to = tx.rewrite("peas-abc12", &from, &pea).unwrap();
+        assert!(to.ends_with("peas-xyz12--moved.md"));
+        assert!(!from.exists());
+        assert_eq!(repo.get("peas-xyz12").unwrap().updated, before.updated);
+
+        tx.rollback().unwrap();
+        assert!(!to.exists());
+        assert_eq!(repo.get("peas-abc12").unwrap(), before);
+    }
+
+    #[test]
+    fn test_recover_interrupted_transaction() {
+        let (temp_dir, config, repo) = setup();
+        let data_path = config.data_path(temp_dir.path());
+
+        let mut tx = Transaction::begin(&repo, &data_path).unwrap();
+        tx.create(&Pea::new("peas-abc12".into(), "One".into(), PeaType::Task))
+            .unwrap();
+        // The process dies before committing
+        std::mem::forget(tx);
+
+        assert_eq!(recover(&data_path).unwrap(), Some(1));
+        repo.invalidate_cache();
+        assert!(!repo.exists("peas-abc12"));
+        assert_eq!(recover(&data_path).unwrap(), None);
+    }
+}
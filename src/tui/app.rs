@@ -17,35 +17,55 @@
 //! The following invariants must be maintained:
 //! - `selected_index` must be < `tree_nodes.len()` in Normal mode
 //! - `modal_selection` must be < options count in Modal modes
-//! - `body_textarea` must be Some() when `input_mode == EditBody`
+//! - `body_textarea` must be Some() when `input_mode` is EditBody or BodyDiff
+//! - `edit_conflict` must be Some() when `input_mode` is EditConflict
 //! - `filtered_peas` must be a subset of `all_peas`
 //!
 //! # Concurrency
 //!
 //! The TUI implements concurrent edit detection to prevent lost updates when
-//! multiple instances are running or when CLI commands modify files.
-
-use super::{body_editor, handlers, modal_operations, relations, tree_builder, ui, url_utils};
+//! multiple instances are running or when CLI commands modify files. When the
+//! file watcher reports a change to a ticket that is open in the body editor
+//! or a modal, the edit is paused on an `EditConflict` prompt offering to
+//! reload, overwrite or merge instead of silently clobbering either side.
+
+use super::{
+    body_editor, handlers,
+    keymap::{Action, KeyResolution, Keymap},
+    modal_operations, relations, tree_builder, ui, url_utils,
+};
 use crate::{
-    config::PeasConfig,
-    error::Result,
+    config::{PeasConfig, TagPolicy},
+    error::{PeasError, Result},
+    focus::Focus,
     global_config::GlobalPeasConfig,
-    model::{Memory, Pea, PeaPriority, PeaStatus, PeaType},
-    storage::{MemoryRepository, PeaRepository},
-    undo::UndoManager,
+    local::LocalState,
+    model::{Body, IncludeTarget, Memory, Pea, PeaPriority, PeaStatus, PeaType, expand_includes},
+    read_markers::{ReadMarkers, ReadState},
+    removal::{ChildPolicy, RemovalKind, RemovalPlan},
+    search::{FUZZY_THRESHOLD, RankedSearch, SearchQuery, fuzzy_similarity},
+    storage::{ListQuery, MemoryRepository, PeaRepository},
+    terminal::TimeFormat,
+    undo::UndoOperation,
     updater::{UpdateCheckOutcome, spawn_update_check},
 };
+pub use modal_operations::BulkChange;
 
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+        KeyModifiers,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode};
 use rat_text::text_area::TextAreaState;
-use ratatui::{Terminal, backend::CrosstermBackend, widgets::ListState};
+use ratatui::{Terminal, backend::CrosstermBackend, style::Color, widgets::ListState};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashSet,
+    collections::{BTreeSet, HashMap, HashSet, VecDeque},
     io,
     path::{Path, PathBuf},
     sync::mpsc,
@@ -89,16 +109,196 @@ pub enum InputMode {
     BlockingModal,
     /// Detail view mode (deprecated, use Normal with detail_pane instead)
     DetailView,
-    /// Create new ticket modal (3-field form)
+    /// Create new ticket modal (multi-field form)
     CreateModal,
     /// Create new memory modal (3-field form)
     MemoryCreateModal,
     /// Multi-line body editing with textarea
     EditBody,
+    /// Review the diff of an edited body before saving
+    BodyDiff,
     /// Tag editing modal (comma-separated input)
     TagsModal,
     /// URL selection modal (choose URL from ticket body)
     UrlModal,
+    /// Bulk operations palette for multi-selected tickets
+    BulkModal,
+    /// Recent status-bar messages
+    MessageLog,
+    /// The ticket being edited changed on disk; choose how to resolve it
+    EditConflict,
+}
+
+/// Severity of a status-bar message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    /// Transient feedback, cleared on the next keypress
+    Info,
+    /// Stays visible until replaced by another message
+    Warning,
+    /// Stays visible and is highlighted until replaced by another message
+    Error,
+}
+
+/// A status-bar message, also kept in the message log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusMessage {
+    pub level: MessageLevel,
+    pub text: String,
+    pub time: chrono::DateTime<chrono::Local>,
+}
+
+/// Maximum number of messages kept in the message log
+pub const MAX_MESSAGE_LOG: usize = 50;
+
+/// Upper bound for vim-style count prefixes
+const MAX_COUNT: usize = 9999;
+
+/// Actions offered by the bulk operations palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkAction {
+    Status,
+    Priority,
+    Type,
+    AddTags,
+    RemoveTags,
+    Parent,
+    Archive,
+    Delete,
+}
+
+impl BulkAction {
+    /// All actions in palette order
+    pub fn all() -> &'static [BulkAction] {
+        &[
+            BulkAction::Status,
+            BulkAction::Priority,
+            BulkAction::Type,
+            BulkAction::AddTags,
+            BulkAction::RemoveTags,
+            BulkAction::Parent,
+            BulkAction::Archive,
+            BulkAction::Delete,
+        ]
+    }
+
+    /// Label shown in the palette
+    pub fn label(&self) -> &'static str {
+        match self {
+            BulkAction::Status => "Set status",
+            BulkAction::Priority => "Set priority",
+            BulkAction::Type => "Set type",
+            BulkAction::AddTags => "Add tags",
+            BulkAction::RemoveTags => "Remove tags",
+            BulkAction::Parent => "Set parent",
+            BulkAction::Archive => "Archive",
+            BulkAction::Delete => "Delete",
+        }
+    }
+}
+
+/// Ways to resolve a ticket that changed on disk while it was being edited
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    /// Discard the in-progress edit and use the version on disk
+    Reload,
+    /// Keep the in-progress edit; saving replaces the version on disk
+    Overwrite,
+    /// Combine the in-progress edit with the version on disk
+    Merge,
+}
+
+impl ConflictChoice {
+    /// Shortcut key in the conflict prompt
+    pub fn key(&self) -> char {
+        match self {
+            ConflictChoice::Reload => 'r',
+            ConflictChoice::Overwrite => 'o',
+            ConflictChoice::Merge => 'm',
+        }
+    }
+
+    /// Label shown in the conflict prompt
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConflictChoice::Reload => "Reload from disk (discard my edit)",
+            ConflictChoice::Overwrite => "Overwrite (my edit replaces the file)",
+            ConflictChoice::Merge => "Merge (combine both versions)",
+        }
+    }
+}
+
+/// A ticket that changed on disk while the body editor or a modal had it open
+#[derive(Debug, Clone)]
+pub struct EditConflict {
+    /// Ticket as it was when editing started
+    pub base: Pea,
+    /// Ticket as it is on disk now (None if it was deleted or archived)
+    pub theirs: Option<Pea>,
+    /// Mode that was interrupted by the conflict
+    pub resume_mode: InputMode,
+    /// Highlighted choice in the prompt
+    pub selection: usize,
+}
+
+impl EditConflict {
+    fn editing_body(&self) -> bool {
+        matches!(self.resume_mode, InputMode::EditBody | InputMode::BodyDiff)
+    }
+
+    /// Resolutions that make sense for the interrupted edit
+    ///
+    /// Modals only change a single field on top of the latest file, so
+    /// overwriting and merging are the same thing there.
+    pub fn choices(&self) -> Vec<ConflictChoice> {
+        match (self.editing_body(), self.theirs.is_some()) {
+            (true, true) => vec![
+                ConflictChoice::Reload,
+                ConflictChoice::Overwrite,
+                ConflictChoice::Merge,
+            ],
+            (true, false) => vec![ConflictChoice::Reload, ConflictChoice::Overwrite],
+            (false, true) => vec![ConflictChoice::Reload, ConflictChoice::Merge],
+            (false, false) => vec![ConflictChoice::Reload],
+        }
+    }
+
+    /// Names of the fields that differ between the edit base and the disk version
+    pub fn changed_fields(&self) -> Vec<&'static str> {
+        let Some(theirs) = &self.theirs else {
+            return Vec::new();
+        };
+        let base = &self.base;
+        [
+            ("title", base.title != theirs.title),
+            ("type", base.pea_type != theirs.pea_type),
+            ("status", base.status != theirs.status),
+            ("priority", base.priority != theirs.priority),
+            ("tags", base.tags != theirs.tags),
+            ("parent", base.parent != theirs.parent),
+            ("blocking", base.blocking != theirs.blocking),
+            ("body", base.body != theirs.body),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+    }
+}
+
+/// Search filter restored on the next start, kept in `.peas/local/`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SavedFilter {
+    query: String,
+    regex: bool,
+    #[serde(default)]
+    fuzzy: bool,
+}
+
+const SAVED_FILTER_FILE: &str = "tui-filter.json";
+
+/// Local state entry holding the unsaved body of a ticket
+fn draft_name(id: &str) -> String {
+    format!("drafts/{}.md", id)
 }
 
 /// Detail pane selection in Normal mode
@@ -128,7 +328,8 @@ pub enum DetailPane {
 /// The following must always hold:
 /// - `selected_index < tree_nodes.len()` when `input_mode == Normal` and `view_mode == Tickets`
 /// - `modal_selection < options.len()` in any modal mode
-/// - `body_textarea.is_some()` if and only if `input_mode == EditBody`
+/// - `body_textarea.is_some()` if and only if `input_mode` is `EditBody` or `BodyDiff`,
+///   or `EditConflict` interrupted one of them
 /// - `filtered_peas` is a subset of `all_peas`
 /// - `filtered_memories` is a subset of `all_memories`
 pub struct App {
@@ -153,6 +354,14 @@ pub struct App {
     pub tree_nodes: Vec<TreeNode>,
     /// Virtual page table for navigation
     pub page_table: Vec<PageInfo>,
+    /// Whether archived tickets are shown (dimmed) alongside active ones
+    pub show_archived: bool,
+    /// IDs of archived tickets currently loaded into `all_peas`
+    pub archived_ids: HashSet<String>,
+    /// ID of the ticket set with `peas focus`, highlighted in the tree
+    pub focus: Option<String>,
+    /// Which tickets are new or changed since the user last opened them
+    pub read_markers: ReadMarkers,
 
     // ========== Memory Data ==========
     /// All memories (unfiltered)
@@ -185,6 +394,8 @@ pub struct App {
     pub relations_selection: usize,
     /// Relationship items: (type, id, title, pea_type)
     pub relations_items: Vec<(String, String, String, PeaType)>,
+    /// IDs of tickets visited before jumping to a relation (for Backspace)
+    pub detail_history: Vec<String>,
 
     // ========== Assets Pane State ==========
     /// Selected item in assets list
@@ -205,18 +416,42 @@ pub struct App {
     // ========== Filter State ==========
     /// Search query text (supports regex and field-specific search)
     pub search_query: String,
+    /// Treat search terms as regular expressions
+    pub search_regex: bool,
+    /// Match search words fuzzily, tolerating typos
+    pub search_fuzzy: bool,
 
     // ========== UI State ==========
     /// Whether help overlay is shown
     pub show_help: bool,
-    /// Status message to display
-    pub message: Option<String>,
+    /// Show a preview of the selected ticket next to the tree
+    pub split_view: bool,
+    /// Normal-mode keybindings (from `[tui.keys]`)
+    pub keymap: Keymap,
+    /// Per-tag colors (from `[tags.<name>]`)
+    pub tag_colors: HashMap<String, Color>,
+    /// Tags with a `[tags.<name>]` section
+    pub configured_tags: BTreeSet<String>,
+    /// How tags outside `configured_tags` are treated (from `peas.tag_policy`)
+    pub tag_policy: TagPolicy,
+    /// How timestamps are shown (from `[display]`, or `--relative`)
+    pub time_format: TimeFormat,
+    /// Count typed before a motion (vim-style `5j`)
+    pub pending_count: Option<usize>,
+    /// Keys typed so far of a multi-key binding (vim-style `gg`, `dd`)
+    pub pending_keys: Vec<KeyEvent>,
+    /// Status message currently shown in the footer
+    pub message: Option<StatusMessage>,
+    /// Recent messages, newest last (bounded by `MAX_MESSAGE_LOG`)
+    pub message_log: VecDeque<StatusMessage>,
     /// App start time (for animations)
     pub start_time: Instant,
 
     // ========== Modal State ==========
     /// Current selection in modal dialogs
     pub modal_selection: usize,
+    /// Whether the confirmation modal deletes or archives the ticket
+    pub removal_kind: RemovalKind,
     /// Candidates for parent selection modal
     pub parent_candidates: Vec<Pea>,
     /// Candidates for blocking selection modal
@@ -231,9 +466,27 @@ pub struct App {
     pub create_title: String,
     /// Type selection for create modal
     pub create_type: PeaType,
+    /// Priority selection for create modal
+    pub create_priority: PeaPriority,
+    /// Tags input for create modal (comma-separated)
+    pub create_tags: String,
+    /// Search text for the parent picker in the create modal
+    pub create_parent_query: String,
+    /// Selected parent ID for create modal
+    pub create_parent: Option<String>,
+    /// Body input for create modal
+    pub create_body: String,
     /// Tag input for tags modal (comma-separated)
     pub tags_input: String,
 
+    // ========== Bulk Operations State ==========
+    /// Chosen bulk action (None while picking from the palette)
+    pub bulk_action: Option<BulkAction>,
+    /// Tag input for bulk add/remove tags (comma-separated)
+    pub bulk_input: String,
+    /// Change awaiting confirmation (Some = confirmation step)
+    pub bulk_pending: Option<BulkChange>,
+
     // ========== Memory Create Modal State ==========
     /// Key input for memory create modal
     pub memory_create_key: String,
@@ -247,6 +500,20 @@ pub struct App {
     // ========== Body Editor State ==========
     /// TextArea for multi-line body editing (Some when input_mode == EditBody)
     pub body_textarea: Option<TextAreaState>,
+    /// Wrap long lines at word boundaries in the body editor
+    pub body_wrap: bool,
+    /// Show line numbers in the body editor
+    pub body_line_numbers: bool,
+    /// Diff of the pending body edit, shown before saving
+    pub body_diff: Vec<body_editor::DiffLine>,
+    /// Scroll offset of the body diff view
+    pub body_diff_scroll: u16,
+
+    // ========== Dirty-State Protection ==========
+    /// Ticket as it was when the body editor or a modal opened it
+    pub edit_base: Option<Pea>,
+    /// Pending conflict (Some when input_mode == EditConflict)
+    pub edit_conflict: Option<EditConflict>,
 
     // ========== Update Checker State ==========
     /// Background thread handle for the update check (None once resolved)
@@ -259,12 +526,16 @@ impl App {
     pub fn new(config: &PeasConfig, project_root: &Path) -> Result<Self> {
         // Initialize TUI config with settings
         super::theme::init_tui_config(config.tui.use_type_emojis);
+        let keymap = Keymap::from_settings(&config.tui.keys).map_err(PeasError::Config)?;
 
         let repo = PeaRepository::new(config, project_root);
         let memory_repo = MemoryRepository::new(config, project_root);
         let data_path = config.data_path(project_root);
-        let all_peas = repo.list()?;
+        // Bodies stay on disk until shown or searched, see `load_selected_body`
+        let all_peas = repo.list_summaries()?;
         let filtered_peas = all_peas.clone();
+        let focus = Focus::new(&data_path).get();
+        let read_markers = ReadMarkers::load(&data_path);
         let all_memories = memory_repo.list(None).unwrap_or_default();
         let filtered_memories = all_memories.clone();
 
@@ -288,6 +559,10 @@ impl App {
             filtered_memories,
             tree_nodes: Vec::new(),
             page_table: Vec::new(),
+            show_archived: false,
+            archived_ids: HashSet::new(),
+            focus,
+            read_markers,
             selected_index: 0,
             page_height: 20, // Default, updated when drawing
             list_state,
@@ -296,6 +571,7 @@ impl App {
             relations_scroll: 0,
             relations_selection: 0,
             relations_items: Vec::new(),
+            detail_history: Vec::new(),
             assets_selection: 0,
             assets_items: Vec::new(),
             metadata_selection: 0,
@@ -303,17 +579,47 @@ impl App {
             input_mode: InputMode::Normal,
             previous_mode: InputMode::Normal,
             search_query: String::new(),
+            search_regex: false,
+            search_fuzzy: false,
             show_help: false,
+            split_view: false,
+            keymap,
+            tag_colors: config
+                .tags
+                .iter()
+                .filter_map(|(name, settings)| Some((name.clone(), settings.parsed_color()?)))
+                .collect(),
+            configured_tags: config.tags.keys().cloned().collect(),
+            tag_policy: config.peas.tag_policy,
+            time_format: TimeFormat::new(&config.display, false),
+            pending_count: None,
+            pending_keys: Vec::new(),
             message: None,
+            message_log: VecDeque::new(),
             modal_selection: 0,
+            removal_kind: RemovalKind::Delete,
             parent_candidates: Vec::new(),
             blocking_candidates: Vec::new(),
             blocking_selected: Vec::new(),
             create_title: String::new(),
             create_type: PeaType::Task,
+            create_priority: PeaPriority::Normal,
+            create_tags: String::new(),
+            create_parent_query: String::new(),
+            create_parent: None,
+            create_body: String::new(),
             tags_input: String::new(),
+            bulk_action: None,
+            bulk_input: String::new(),
+            bulk_pending: None,
             multi_selected: HashSet::new(),
             body_textarea: None,
+            body_wrap: true,
+            body_line_numbers: true,
+            body_diff: Vec::new(),
+            body_diff_scroll: 0,
+            edit_base: None,
+            edit_conflict: None,
             start_time: Instant::now(),
             url_candidates: Vec::new(),
             memory_create_key: String::new(),
@@ -324,21 +630,179 @@ impl App {
             available_update: None,
         };
         app.build_tree();
+        app.restore_filter();
+        app.load_selected_body();
         // Note: page_table will be built when page_height is set during first draw
         Ok(app)
     }
 
+    /// Show a message in the footer and record it in the message log
+    pub fn push_message(&mut self, level: MessageLevel, text: impl Into<String>) {
+        let message = StatusMessage {
+            level,
+            text: text.into(),
+            time: chrono::Local::now(),
+        };
+        if self.message_log.len() >= MAX_MESSAGE_LOG {
+            self.message_log.pop_front();
+        }
+        self.message_log.push_back(message.clone());
+        self.message = Some(message);
+    }
+
+    /// Show an informational message
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.push_message(MessageLevel::Info, text);
+    }
+
+    /// Show a warning that persists until replaced
+    pub fn warn(&mut self, text: impl Into<String>) {
+        self.push_message(MessageLevel::Warning, text);
+    }
+
+    /// Show an error that persists until replaced
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.push_message(MessageLevel::Error, text);
+    }
+
+    /// Show an error message if the result of an action failed
+    ///
+    /// Peas errors that the user can act on get a hint appended.
+    pub fn report_error<T, E: std::fmt::Display + 'static>(
+        &mut self,
+        context: &str,
+        result: std::result::Result<T, E>,
+    ) {
+        if let Err(e) = result {
+            let hint = (&e as &dyn std::any::Any)
+                .downcast_ref::<PeasError>()
+                .and_then(PeasError::hint);
+            match hint {
+                Some(hint) => self.error(format!("{}: {} ({})", context, e, hint)),
+                None => self.error(format!("{}: {}", context, e)),
+            }
+        }
+    }
+
+    /// Clear the footer message if it is informational; warnings and errors stay
+    pub fn clear_transient_message(&mut self) {
+        if self
+            .message
+            .as_ref()
+            .is_some_and(|m| m.level == MessageLevel::Info)
+        {
+            self.message = None;
+        }
+    }
+
+    /// Open the recent messages log
+    pub fn open_message_log(&mut self) {
+        self.modal_selection = 0;
+        self.previous_mode = self.input_mode;
+        self.input_mode = InputMode::MessageLog;
+    }
+
     pub fn refresh(&mut self) -> Result<()> {
-        self.all_peas = self.repo.list()?;
+        // Files may have changed behind the repository's back (undo, watcher, CLI)
+        self.repo.invalidate_cache();
+        self.all_peas = self.repo.list_summaries()?;
+        self.archived_ids.clear();
+        if self.show_archived {
+            let archived = self.repo.list_archived()?;
+            self.archived_ids = archived.iter().map(|p| p.id.clone()).collect();
+            self.all_peas.extend(archived);
+        }
         self.all_memories = self.memory_repo.list(None).unwrap_or_default();
+        self.focus = Focus::new(&self.data_path).get();
+        self.read_markers = ReadMarkers::load(&self.data_path);
+        tracing::debug!(
+            peas = self.all_peas.len(),
+            memories = self.all_memories.len(),
+            "Refreshed TUI data"
+        );
         self.apply_filter();
         self.build_tree();
         if self.page_height > 0 {
             self.build_page_table();
         }
+        self.load_selected_body();
         Ok(())
     }
 
+    /// Read the bodies the detail view needs from disk
+    ///
+    /// That is the body of the selected ticket, of the tickets it includes and
+    /// of the ticket being edited. Called on every event loop iteration.
+    pub fn load_selected_body(&mut self) {
+        let mut ids: HashSet<String> = self
+            .selected_pea()
+            .into_iter()
+            .chain(&self.edit_base)
+            .map(|pea| pea.id.clone())
+            .collect();
+        self.load_bodies_of(&ids);
+
+        let Some(pea) = self.selected_pea() else {
+            return;
+        };
+        let included = std::cell::RefCell::new(HashSet::new());
+        expand_includes(&pea.body, |target| {
+            let IncludeTarget::Pea(id) = target else {
+                return None;
+            };
+            let pea = self
+                .all_peas
+                .iter()
+                .find(|p| p.id == id || p.slug.as_deref() == Some(id))?;
+            included.borrow_mut().insert(pea.id.clone());
+            self.repo.get(&pea.id).ok().map(|p| p.body.into_string())
+        });
+        ids = included.into_inner();
+        self.load_bodies_of(&ids);
+    }
+
+    /// Read the bodies of all tickets, for searches and references across them
+    pub fn load_all_bodies(&mut self) {
+        if self.all_peas.iter().all(|pea| pea.body.is_loaded()) {
+            return;
+        }
+        match self.repo.list() {
+            Ok(peas) => self.set_bodies(peas.into_iter().map(|p| (p.id, p.body)).collect()),
+            Err(e) => tracing::warn!(error = %e, "Failed to load bodies"),
+        }
+    }
+
+    fn load_bodies_of(&mut self, ids: &HashSet<String>) {
+        let mut bodies = HashMap::new();
+        for pea in &self.all_peas {
+            if ids.contains(&pea.id) && !pea.body.is_loaded() {
+                match self.repo.get(&pea.id) {
+                    Ok(full) => {
+                        bodies.insert(pea.id.clone(), full.body);
+                    }
+                    Err(e) => tracing::warn!(id = %pea.id, error = %e, "Failed to load body"),
+                }
+            }
+        }
+        if !bodies.is_empty() {
+            self.set_bodies(bodies);
+        }
+    }
+
+    /// Hand loaded bodies to every copy of their tickets
+    fn set_bodies(&mut self, bodies: HashMap<String, Body>) {
+        let peas = self
+            .all_peas
+            .iter_mut()
+            .chain(&mut self.filtered_peas)
+            .chain(self.tree_nodes.iter_mut().map(|node| &mut node.pea));
+        for pea in peas.filter(|pea| !pea.body.is_loaded()) {
+            if let Some(body) = bodies.get(&pea.id) {
+                pea.body = body.clone();
+            }
+        }
+    }
+
     pub fn switch_view(&mut self) {
         self.view_mode = match self.view_mode {
             ViewMode::Tickets => ViewMode::Memory,
@@ -427,31 +891,105 @@ impl App {
         }
     }
 
+    /// Parse the search box into terms that must all match
+    ///
+    /// Falls back to a plain substring search while the query is not valid
+    /// (e.g. a half-typed regex), so the list never goes blank mid-typing.
+    fn search_terms(&self) -> Vec<SearchQuery> {
+        if self.search_query.trim().is_empty() {
+            return Vec::new();
+        }
+        SearchQuery::parse_terms(&self.search_query, self.search_regex)
+            .unwrap_or_else(|_| vec![SearchQuery::Simple(self.search_query.clone())])
+    }
+
+    /// Combined regex for highlighting search matches in titles, IDs and bodies
+    pub fn search_highlight(&self) -> Option<Regex> {
+        let patterns: Vec<String> = self
+            .search_terms()
+            .iter()
+            .filter_map(SearchQuery::highlight_pattern)
+            .collect();
+        if patterns.is_empty() {
+            return None;
+        }
+        Regex::new(&patterns.join("|")).ok()
+    }
+
+    /// Re-apply the search filter saved by the last session
+    fn restore_filter(&mut self) {
+        let saved = LocalState::new(&self.data_path)
+            .read(SAVED_FILTER_FILE)
+            .and_then(|content| serde_json::from_str::<SavedFilter>(&content).ok());
+        if let Some(saved) = saved.filter(|s| !s.query.is_empty()) {
+            self.search_query = saved.query;
+            self.search_regex = saved.regex;
+            self.search_fuzzy = saved.fuzzy;
+            self.apply_filter();
+        }
+    }
+
+    /// Remember the current search filter for the next session
+    pub fn save_filter(&self) -> Result<()> {
+        let local = LocalState::new(&self.data_path);
+        if self.search_query.is_empty() {
+            return local.remove(SAVED_FILTER_FILE);
+        }
+        let saved = SavedFilter {
+            query: self.search_query.clone(),
+            regex: self.search_regex,
+            fuzzy: self.search_fuzzy,
+        };
+        local.write(SAVED_FILTER_FILE, &serde_json::to_string_pretty(&saved)?)
+    }
+
+    /// Toggle regex mode for the search box and re-apply the filter
+    pub fn toggle_search_regex(&mut self) {
+        self.search_regex = !self.search_regex;
+        self.search_fuzzy &= !self.search_regex;
+        self.apply_filter();
+    }
+
+    /// Toggle fuzzy matching for the search box and re-apply the filter
+    pub fn toggle_search_fuzzy(&mut self) {
+        self.search_fuzzy = !self.search_fuzzy;
+        self.search_regex &= !self.search_fuzzy;
+        self.apply_filter();
+    }
+
     /// Returns the start index of the current page
     pub fn apply_filter(&mut self) {
+        // Search filter (supports scoped terms like `tag:ui status:todo` and regex)
+        let terms = self.search_terms();
+        let search = self
+            .search_fuzzy
+            .then(|| RankedSearch::parse(&self.search_query).ok())
+            .flatten()
+            .map(|query| query.with_fuzzy(true));
+        let needs_body = match &search {
+            Some(search) => search.needs_body(),
+            None => terms.iter().any(SearchQuery::needs_body),
+        };
+        if needs_body {
+            self.load_all_bodies();
+        }
+        let query = match search {
+            Some(search) => ListQuery {
+                search: Some(search),
+                ..Default::default()
+            },
+            None => ListQuery {
+                terms: terms.clone(),
+                ..Default::default()
+            },
+        };
+
         // Filter tickets
+        let now = chrono::Utc::now();
         self.filtered_peas = self
             .all_peas
             .iter()
-            .filter(|p| {
-                // Search filter (supports field-specific and regex)
-                if self.search_query.is_empty() {
-                    true
-                } else {
-                    // Parse search query and apply
-                    match crate::search::SearchQuery::parse(&self.search_query) {
-                        Ok(query) => query.matches_pea(p),
-                        Err(_) => {
-                            // If parse fails, fall back to simple substring search
-                            let query = self.search_query.to_lowercase();
-                            p.title.to_lowercase().contains(&query)
-                                || p.id.to_lowercase().contains(&query)
-                                || p.body.to_lowercase().contains(&query)
-                                || p.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
-                        }
-                    }
-                }
-            })
+            .filter(|p| query.matches(p, None, now))
             .cloned()
             .collect();
 
@@ -459,24 +997,7 @@ impl App {
         self.filtered_memories = self
             .all_memories
             .iter()
-            .filter(|m| {
-                // Search filter (supports field-specific and regex)
-                if self.search_query.is_empty() {
-                    true
-                } else {
-                    // Parse search query and apply
-                    match crate::search::SearchQuery::parse(&self.search_query) {
-                        Ok(query) => query.matches_memory(m),
-                        Err(_) => {
-                            // If parse fails, fall back to simple substring search
-                            let query = self.search_query.to_lowercase();
-                            m.key.to_lowercase().contains(&query)
-                                || m.content.to_lowercase().contains(&query)
-                                || m.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
-                        }
-                    }
-                }
-            })
+            .filter(|m| terms.iter().all(|q| q.matches_memory(m)))
             .cloned()
             .collect();
 
@@ -623,6 +1144,67 @@ impl App {
         }
     }
 
+    /// Jump to the item at `index`, clamped to the list
+    pub fn go_to(&mut self, index: usize) {
+        let count = self.display_count();
+        if count > 0 {
+            self.selected_index = index.min(count - 1);
+            self.list_state.select(Some(self.index_in_page()));
+            self.detail_scroll = 0;
+        }
+    }
+
+    /// Feed a normal-mode key press through count prefix and keymap
+    ///
+    /// Digits build up a count (`12j`), keys that start a longer binding
+    /// are held until it completes (`gg`). Returns the action to run with
+    /// its count, or None while input is pending or unbound.
+    pub fn resolve_key(&mut self, key: KeyEvent) -> Option<(Action, Option<usize>)> {
+        if let KeyCode::Char(c) = key.code
+            && let Some(digit) = c.to_digit(10)
+            && (digit > 0 || self.pending_count.is_some())
+            && !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+            && self.pending_keys.is_empty()
+            && self.keymap.action_for(&key).is_none()
+        {
+            let count = self.pending_count.unwrap_or(0);
+            self.pending_count = Some((count * 10 + digit as usize).min(MAX_COUNT));
+            return None;
+        }
+
+        let mut resolution = self.keymap.resolve(&self.pending_keys, &key);
+        if resolution == KeyResolution::Unbound && !self.pending_keys.is_empty() {
+            // An abandoned sequence: drop it and treat the key on its own
+            self.pending_keys.clear();
+            resolution = self.keymap.resolve(&[], &key);
+        }
+
+        match resolution {
+            KeyResolution::Pending => {
+                self.pending_keys.push(key);
+                None
+            }
+            KeyResolution::Action(action) => {
+                self.pending_keys.clear();
+                Some((action, self.pending_count.take()))
+            }
+            KeyResolution::Unbound => {
+                self.clear_pending_keys();
+                None
+            }
+        }
+    }
+
+    /// Drop a pending count or key sequence, returning whether there was one
+    pub fn clear_pending_keys(&mut self) -> bool {
+        let had_pending = self.pending_count.is_some() || !self.pending_keys.is_empty();
+        self.pending_count = None;
+        self.pending_keys.clear();
+        had_pending
+    }
+
     /// Jump to last item
     pub fn last(&mut self) {
         let count = self.display_count();
@@ -657,6 +1239,8 @@ impl App {
         self.relations_selection = 0;
         self.relations_scroll = 0;
 
+        // Backlinks come from the bodies of all other tickets
+        self.load_all_bodies();
         if let Some(pea) = self.selected_pea() {
             self.relations_items = relations::build_relations(pea, &self.all_peas);
         } else {
@@ -687,20 +1271,118 @@ impl App {
 
     /// Jump to the selected relationship ticket
     pub fn jump_to_relation(&mut self) -> bool {
-        if let Some((_, id, _, _)) = self.relations_items.get(self.relations_selection) {
-            let target_id = id.clone();
-            // Find the ticket in tree_nodes
-            if let Some(idx) = self.tree_nodes.iter().position(|n| n.pea.id == target_id) {
-                self.selected_index = idx;
-                self.list_state.select(Some(self.index_in_page()));
-                self.detail_scroll = 0;
-                self.build_relations(); // Rebuild for new ticket
+        let Some((_, target_id, _, _)) = self.relations_items.get(self.relations_selection) else {
+            return false;
+        };
+        let target_id = target_id.clone();
+        let current_id = self.selected_pea().map(|p| p.id.clone());
+        if self.select_detail_ticket(&target_id) {
+            if let Some(id) = current_id {
+                self.detail_history.push(id);
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Go back to the ticket shown before the last relation jump
+    pub fn detail_back(&mut self) -> bool {
+        while let Some(id) = self.detail_history.pop() {
+            // Skip tickets that disappeared since we visited them
+            if self.select_detail_ticket(&id) {
                 return true;
             }
         }
         false
     }
 
+    /// Select a ticket by ID in the tree and rebuild its detail panes
+    fn select_detail_ticket(&mut self, id: &str) -> bool {
+        if let Some(idx) = self.tree_nodes.iter().position(|n| n.pea.id == id) {
+            self.selected_index = idx;
+            self.list_state.select(Some(self.index_in_page()));
+            self.detail_scroll = 0;
+            self.build_relations(); // Rebuild for new ticket
+            self.mark_selected_read();
+            return true;
+        }
+        false
+    }
+
+    /// Open the detail view of a ticket by ID, for deep links
+    ///
+    /// Clears a search filter hiding the ticket and shows archived tickets
+    /// if it is archived. Returns false if there is no such ticket.
+    pub fn open_ticket(&mut self, id: &str) -> Result<bool> {
+        self.view_mode = ViewMode::Tickets;
+        if !self.tree_nodes.iter().any(|n| n.pea.id == id) && !self.search_query.is_empty() {
+            self.search_query.clear();
+            self.apply_filter();
+        }
+        if !self.tree_nodes.iter().any(|n| n.pea.id == id) && !self.show_archived {
+            self.show_archived = true;
+            self.refresh()?;
+        }
+        if !self.select_detail_ticket(id) {
+            return Ok(false);
+        }
+        self.detail_pane = DetailPane::Body;
+        self.input_mode = InputMode::DetailView;
+        Ok(true)
+    }
+
+    /// Apply the deep link given on the command line
+    ///
+    /// The view and filter come first, so a selected ticket hidden by the
+    /// filter is still shown.
+    pub fn start_at(&mut self, options: &TuiOptions) -> Result<()> {
+        match options.view {
+            Some(StartView::Tickets) => self.split_view = false,
+            Some(StartView::Split) => self.split_view = true,
+            Some(StartView::Memory) if self.view_mode != ViewMode::Memory => self.switch_view(),
+            Some(StartView::Memory) => {}
+            None => {}
+        }
+        if let Some(filter) = &options.filter {
+            self.search_query = filter.clone();
+            self.search_regex = false;
+            self.search_fuzzy = false;
+            self.apply_filter();
+        }
+        if let Some(id) = &options.select
+            && !self.open_ticket(id)?
+        {
+            self.warn(format!("Ticket {} not found", id));
+        }
+        Ok(())
+    }
+
+    /// Leave the detail view and forget the navigation history
+    pub fn close_detail_view(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.detail_pane = DetailPane::Body;
+        self.detail_history.clear();
+    }
+
+    /// Ancestor chain of the selected ticket, from the root down to the ticket itself
+    pub fn detail_breadcrumb(&self) -> Vec<&Pea> {
+        let mut chain = Vec::new();
+        let mut current = self.selected_pea();
+        while let Some(pea) = current {
+            // Guard against parent cycles in hand-edited files
+            if chain.iter().any(|p: &&Pea| p.id == pea.id) {
+                break;
+            }
+            chain.push(pea);
+            current = pea
+                .parent
+                .as_ref()
+                .and_then(|parent| self.all_peas.iter().find(|p| &p.id == parent));
+        }
+        chain.reverse();
+        chain
+    }
+
     /// Navigate down in assets pane
     pub fn assets_next(&mut self) {
         if !self.assets_items.is_empty() {
@@ -824,11 +1506,10 @@ impl App {
                 &target_ids,
                 &self.all_peas,
                 &self.repo,
-                &self.data_path,
                 new_status,
             )?;
             if !message.is_empty() {
-                self.message = Some(message);
+                self.info(message);
             }
             self.clear_multi_select();
             self.refresh()?;
@@ -867,11 +1548,10 @@ impl App {
                 &target_ids,
                 &self.all_peas,
                 &self.repo,
-                &self.data_path,
                 new_priority,
             )?;
             if !message.is_empty() {
-                self.message = Some(message);
+                self.info(message);
             }
             self.clear_multi_select();
             self.refresh()?;
@@ -913,11 +1593,10 @@ impl App {
                 &target_ids,
                 &self.all_peas,
                 &self.repo,
-                &self.data_path,
                 new_type,
             )?;
             if !message.is_empty() {
-                self.message = Some(message);
+                self.info(message);
             }
             self.clear_multi_select();
             self.refresh()?;
@@ -946,24 +1625,75 @@ impl App {
                 .map(|s| s.trim().to_string())
                 .filter(|s| !s.is_empty())
                 .collect();
+            if let Some(e) = new_tags
+                .iter()
+                .find_map(|tag| self.repo.validator().tag(tag).err())
+            {
+                self.warn(e.to_string());
+                return Ok(());
+            }
 
-            modal_operations::apply_tags_change(
-                &pea.id,
-                &self.all_peas,
-                &self.repo,
-                &self.data_path,
-                new_tags,
-            )?;
+            let new_to_project = self.new_tags(&new_tags);
+
+            modal_operations::apply_tags_change(&pea.id, &self.all_peas, &self.repo, new_tags)?;
 
-            self.message = Some("Tags updated".to_string());
+            if new_to_project.is_empty() {
+                self.info("Tags updated");
+            } else {
+                self.warn(format!(
+                    "Tags updated; new tag(s): {} (check for typos)",
+                    new_to_project.join(", ")
+                ));
+            }
             self.refresh()?;
         }
         self.input_mode = self.previous_mode;
         Ok(())
     }
 
+    /// Tags offered for completion: configured ones, plus those in use unless
+    /// the tag policy is `strict`
+    pub fn known_tags(&self) -> Vec<&str> {
+        let mut known: BTreeSet<&str> = self.configured_tags.iter().map(String::as_str).collect();
+        if self.tag_policy != TagPolicy::Strict {
+            known.extend(
+                self.all_peas
+                    .iter()
+                    .flat_map(|p| p.tags.iter().map(String::as_str)),
+            );
+        }
+        known.into_iter().collect()
+    }
+
+    /// Tags from `tags` that the `warn` policy reports as new to the project
+    fn new_tags(&self, tags: &[String]) -> Vec<String> {
+        if self.tag_policy != TagPolicy::Warn {
+            return Vec::new();
+        }
+        let known = self.known_tags();
+        tags.iter()
+            .filter(|t| !known.contains(&t.as_str()))
+            .cloned()
+            .collect()
+    }
+
+    /// Completions for the tag being typed in the tags modal
+    pub fn tags_modal_completions(&self) -> Vec<&str> {
+        modal_operations::tag_completions(&self.tags_input, &self.known_tags())
+    }
+
+    /// Complete the tag being typed in the tags modal (Tab)
+    pub fn complete_tags_modal(&mut self) {
+        if let Some(completed) =
+            modal_operations::complete_tags_input(&self.tags_input, &self.known_tags())
+        {
+            self.tags_input = completed;
+        }
+    }
+
     /// Open delete confirmation dialog
     pub fn open_delete_confirm(&mut self) {
+        self.removal_kind = RemovalKind::Delete;
         match self.view_mode {
             ViewMode::Tickets => {
                 if self.selected_pea().is_some() {
@@ -982,22 +1712,13 @@ impl App {
     pub fn delete_selected(&mut self) -> Result<()> {
         match self.view_mode {
             ViewMode::Tickets => {
-                if let Some(pea) = self.selected_pea().cloned() {
-                    // Record undo before delete
-                    let undo_manager = UndoManager::new(&self.data_path);
-                    if let Ok(path) = self.repo.find_file_by_id(&pea.id) {
-                        let _ = crate::undo::record_delete(&undo_manager, &pea.id, &path);
-                    }
-
-                    self.repo.delete(&pea.id)?;
-                    self.message = Some(format!("Deleted {}", pea.id));
-                    self.refresh()?;
-                }
+                self.removal_kind = RemovalKind::Delete;
+                self.remove_selected(ChildPolicy::Refuse)?;
             }
             ViewMode::Memory => {
                 if let Some(memory) = self.filtered_memories.get(self.selected_index).cloned() {
                     self.memory_repo.delete(&memory.key)?;
-                    self.message = Some(format!("Deleted memory '{}'", memory.key));
+                    self.info(format!("Deleted memory '{}'", memory.key));
                     self.refresh()?;
 
                     // Adjust selection if needed
@@ -1013,6 +1734,273 @@ impl App {
         Ok(())
     }
 
+    /// Children of the selected ticket, for the removal choices in the confirmation modal
+    pub fn selected_children(&self) -> Vec<&Pea> {
+        let Some(pea) = self
+            .selected_pea()
+            .filter(|_| self.view_mode == ViewMode::Tickets)
+        else {
+            return Vec::new();
+        };
+        self.all_peas
+            .iter()
+            .filter(|p| p.parent.as_deref() == Some(pea.id.as_str()) && !self.is_archived(&p.id))
+            .collect()
+    }
+
+    /// Delete or archive the selected ticket as `removal_kind` says, handling
+    /// its children by `policy`
+    pub fn remove_selected(&mut self, policy: ChildPolicy) -> Result<()> {
+        let Some(pea) = self.selected_pea().cloned() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(());
+        };
+        let plan = RemovalPlan::new(self.removal_kind, &pea.id, &policy, &self.repo.list()?)?;
+
+        // One undo step, including what was changed before a failure
+        let mut operations = Vec::new();
+        let result = plan.apply(&self.repo, &mut operations);
+        if let Some(step) = plan.undo_step(operations) {
+            let _ = self.repo.undo_manager().record(step);
+        }
+        self.input_mode = InputMode::Normal;
+        result?;
+
+        let verb = match self.removal_kind {
+            RemovalKind::Delete => "Deleted",
+            RemovalKind::Archive => "Archived",
+        };
+        self.info(match plan.removed.len() {
+            1 => format!("{} {}", verb, pea.id),
+            n => format!("{} {} and {} descendants", verb, pea.id, n - 1),
+        });
+        self.clear_multi_select();
+        self.refresh()
+    }
+
+    /// Check if a ticket is archived
+    pub fn is_archived(&self, id: &str) -> bool {
+        self.archived_ids.contains(id)
+    }
+
+    /// Check if a ticket is the current focus
+    pub fn is_focused(&self, id: &str) -> bool {
+        self.focus.as_deref() == Some(id)
+    }
+
+    /// Whether a ticket is new or changed since it was last opened
+    pub fn read_state(&self, pea: &Pea) -> ReadState {
+        self.read_markers.state(pea)
+    }
+
+    /// Mark the selected ticket as read
+    pub fn mark_selected_read(&mut self) {
+        let Some(pea) = self.selected_pea().cloned() else {
+            return;
+        };
+        if self.read_markers.is_unread(&pea) {
+            self.read_markers.mark_read(&pea);
+            let result = self.read_markers.save();
+            self.report_error("Failed to save read markers", result);
+        }
+    }
+
+    /// Toggle showing archived tickets in the tree
+    pub fn toggle_show_archived(&mut self) -> Result<()> {
+        self.show_archived = !self.show_archived;
+        self.info(if self.show_archived {
+            "Showing archived tickets"
+        } else {
+            "Hiding archived tickets"
+        });
+        self.refresh()
+    }
+
+    /// Archive the selected ticket(s), recording undo for each
+    pub fn archive_selected(&mut self) -> Result<()> {
+        if self.view_mode != ViewMode::Tickets {
+            return Ok(());
+        }
+
+        let target_ids: Vec<String> = self
+            .target_ids()
+            .into_iter()
+            .filter(|id| !self.is_archived(id))
+            .collect();
+        if target_ids.is_empty() {
+            self.warn("Already archived");
+            return Ok(());
+        }
+        // A single ticket goes through the removal plan; with children, the
+        // user picks what happens to them first
+        if self.multi_selected.is_empty() {
+            self.removal_kind = RemovalKind::Archive;
+            if self.selected_children().is_empty() {
+                return self.remove_selected(ChildPolicy::Refuse);
+            }
+            self.input_mode = InputMode::DeleteConfirm;
+            return Ok(());
+        }
+
+        let mut operations = Vec::new();
+        let mut archive = || -> Result<()> {
+            for id in &target_ids {
+                let original_path = self.repo.find_file_by_id(id)?;
+                let archive_path = self.repo.archive(id)?;
+                operations.push(UndoOperation::Archive {
+                    id: id.clone(),
+                    original_path,
+                    archive_path,
+                });
+            }
+            Ok(())
+        };
+        let result = archive();
+        // One undo step, including the tickets archived before a failure
+        let archived = operations.len();
+        let undo_manager = self.repo.undo_manager();
+        let _ = if archived == 1 {
+            undo_manager.record(operations.remove(0))
+        } else {
+            let description = format!("Archive {} tickets", archived);
+            crate::undo::record_batch(&undo_manager, &description, operations)
+        };
+        result?;
+
+        self.info(if archived == 1 {
+            format!("Archived {}", target_ids[0])
+        } else {
+            format!("Archived {} tickets", archived)
+        });
+        self.clear_multi_select();
+        self.refresh()?;
+        Ok(())
+    }
+
+    /// Open the bulk operations palette for the targeted tickets
+    pub fn open_bulk_modal(&mut self) {
+        if self.view_mode != ViewMode::Tickets || self.target_ids().is_empty() {
+            return;
+        }
+        self.bulk_action = None;
+        self.bulk_input.clear();
+        self.bulk_pending = None;
+        self.modal_selection = 0;
+        self.previous_mode = self.input_mode;
+        self.input_mode = InputMode::BulkModal;
+    }
+
+    /// Pick the highlighted action in the bulk palette
+    pub fn select_bulk_action(&mut self) {
+        let Some(&action) = BulkAction::all().get(self.modal_selection) else {
+            return;
+        };
+        self.bulk_action = Some(action);
+        self.modal_selection = 0;
+        match action {
+            BulkAction::Parent => {
+                let targets: HashSet<String> = self.target_ids().into_iter().collect();
+                self.parent_candidates = self
+                    .all_peas
+                    .iter()
+                    .filter(|p| !targets.contains(&p.id) && Self::is_container_type(p.pea_type))
+                    .cloned()
+                    .collect();
+            }
+            // Archive and delete have no value to pick, go straight to confirmation
+            BulkAction::Archive => self.bulk_pending = Some(BulkChange::Archive),
+            BulkAction::Delete => self.bulk_pending = Some(BulkChange::Delete),
+            _ => {}
+        }
+    }
+
+    /// Number of selectable values for the chosen bulk action
+    pub fn bulk_value_count(&self) -> usize {
+        match self.bulk_action {
+            Some(BulkAction::Status) => Self::status_options().len(),
+            Some(BulkAction::Priority) => Self::priority_options().len(),
+            Some(BulkAction::Type) => Self::type_options().len(),
+            Some(BulkAction::Parent) => self.parent_candidates.len() + 1, // +1 for "(none)"
+            _ => 0,
+        }
+    }
+
+    /// Turn the chosen bulk action and value into a pending change awaiting confirmation
+    pub fn choose_bulk_value(&mut self) {
+        let idx = self.modal_selection;
+        let tags: Vec<String> = self
+            .bulk_input
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let change = match self.bulk_action {
+            Some(BulkAction::Status) => Self::status_options()
+                .get(idx)
+                .map(|s| BulkChange::Status(*s)),
+            Some(BulkAction::Priority) => Self::priority_options()
+                .get(idx)
+                .map(|p| BulkChange::Priority(*p)),
+            Some(BulkAction::Type) => Self::type_options().get(idx).map(|t| BulkChange::Type(*t)),
+            Some(BulkAction::AddTags) if !tags.is_empty() => Some(BulkChange::AddTags(tags)),
+            Some(BulkAction::RemoveTags) if !tags.is_empty() => Some(BulkChange::RemoveTags(tags)),
+            Some(BulkAction::AddTags) | Some(BulkAction::RemoveTags) => None,
+            Some(BulkAction::Parent) => Some(BulkChange::Parent(if idx == 0 {
+                None
+            } else {
+                self.parent_candidates.get(idx - 1).map(|p| p.id.clone())
+            })),
+            Some(BulkAction::Archive) => Some(BulkChange::Archive),
+            Some(BulkAction::Delete) => Some(BulkChange::Delete),
+            None => None,
+        };
+
+        match change {
+            Some(change) => self.bulk_pending = Some(change),
+            None => self.warn("No tags entered"),
+        }
+    }
+
+    /// Apply the confirmed bulk change to all targeted tickets
+    pub fn apply_bulk_pending(&mut self) -> Result<()> {
+        if let Some(change) = self.bulk_pending.take() {
+            let target_ids = self.target_ids();
+            let result = modal_operations::apply_bulk_change(
+                &target_ids,
+                &self.all_peas,
+                &self.repo,
+                &change,
+            );
+            match result {
+                Ok(msg) => self.info(msg),
+                Err(e) => self.error(format!("Bulk operation failed: {}", e)),
+            }
+            self.clear_multi_select();
+            self.refresh()?;
+        }
+        self.bulk_action = None;
+        self.input_mode = InputMode::Normal;
+        Ok(())
+    }
+
+    /// Step back in the bulk palette (confirmation -> value -> action -> closed)
+    pub fn bulk_back(&mut self) {
+        if self.bulk_pending.take().is_some()
+            && !matches!(
+                self.bulk_action,
+                Some(BulkAction::Archive) | Some(BulkAction::Delete)
+            )
+        {
+            return;
+        }
+        if self.bulk_action.take().is_some() {
+            self.modal_selection = 0;
+            return;
+        }
+        self.input_mode = self.previous_mode;
+    }
+
     /// Open the parent selection modal
     /// Shows only tickets that can be valid parents (milestones, epics, stories, features)
     pub fn open_parent_modal(&mut self) {
@@ -1089,11 +2077,10 @@ impl App {
                 &pea.id,
                 &self.all_peas,
                 &self.repo,
-                &self.data_path,
                 new_parent,
             )?;
             if !message.is_empty() {
-                self.message = Some(message);
+                self.info(message);
             }
             self.refresh()?;
         }
@@ -1170,11 +2157,10 @@ impl App {
                 &pea.id,
                 &self.all_peas,
                 &self.repo,
-                &self.data_path,
                 new_blocking,
             )?;
             if !message.is_empty() {
-                self.message = Some(message);
+                self.info(message);
             }
             self.refresh()?;
         }
@@ -1182,32 +2168,109 @@ impl App {
         Ok(())
     }
 
+    /// Number of fields in the create ticket modal
+    pub const CREATE_FIELD_COUNT: usize = 6;
+
     /// Open the create ticket modal
     pub fn open_create_modal(&mut self) {
         self.create_title.clear();
         self.create_type = PeaType::Task;
-        self.modal_selection = 0; // 0 = title field, 1 = type field
+        self.create_priority = PeaPriority::Normal;
+        self.create_tags.clear();
+        self.create_parent_query.clear();
+        self.create_body.clear();
+        // If current selection is a container type, preselect it as parent
+        self.create_parent = self.selected_pea().and_then(|p| {
+            if Self::is_container_type(p.pea_type) {
+                Some(p.id.clone())
+            } else {
+                None
+            }
+        });
+        // 0 = title, 1 = type, 2 = priority, 3 = tags, 4 = parent, 5 = body
+        self.modal_selection = 0;
         self.input_mode = InputMode::CreateModal;
     }
 
+    /// Whether a pea type can act as a parent
+    fn is_container_type(pea_type: PeaType) -> bool {
+        matches!(
+            pea_type,
+            PeaType::Milestone | PeaType::Epic | PeaType::Story | PeaType::Feature
+        )
+    }
+
+    /// Parent candidates for the create modal, narrowed by the parent search text
+    ///
+    /// Every word must match the ID or title fuzzily; the closest matches come first.
+    pub fn create_parent_matches(&self) -> Vec<&Pea> {
+        let words: Vec<&str> = self.create_parent_query.split_whitespace().collect();
+        let mut matches: Vec<(f32, &Pea)> = self
+            .all_peas
+            .iter()
+            .filter(|p| Self::is_container_type(p.pea_type))
+            .filter_map(|p| {
+                words
+                    .iter()
+                    .try_fold(0.0, |total, word| {
+                        let similarity =
+                            fuzzy_similarity(word, &p.id).max(fuzzy_similarity(word, &p.title));
+                        (similarity >= FUZZY_THRESHOLD).then_some(total + similarity)
+                    })
+                    .map(|score| (score, p))
+            })
+            .collect();
+        matches.sort_by(|a, b| b.0.total_cmp(&a.0));
+        matches.into_iter().map(|(_, p)| p).collect()
+    }
+
+    /// Cycle the create modal parent through the matching candidates.
+    /// Cycling past either end selects "(none)".
+    pub fn cycle_create_parent(&mut self, forward: bool) {
+        let matches: Vec<String> = self
+            .create_parent_matches()
+            .iter()
+            .map(|p| p.id.clone())
+            .collect();
+        if matches.is_empty() {
+            self.create_parent = None;
+            return;
+        }
+
+        let current = self
+            .create_parent
+            .as_ref()
+            .and_then(|id| matches.iter().position(|m| m == id));
+        self.create_parent = match (current, forward) {
+            (None, true) => matches.first().cloned(),
+            (None, false) => matches.last().cloned(),
+            (Some(i), true) => matches.get(i + 1).cloned(),
+            (Some(0), false) => None,
+            (Some(i), false) => matches.get(i - 1).cloned(),
+        };
+    }
+
+    /// Update the parent search text and select the best match
+    pub fn update_create_parent_query(&mut self) {
+        if self.create_parent_query.is_empty() {
+            return;
+        }
+        self.create_parent = self.create_parent_matches().first().map(|p| p.id.clone());
+    }
+
     /// Create a new ticket from the modal inputs
-    pub fn create_from_modal(&mut self) -> Result<()> {
+    pub fn create_from_modal(&mut self) -> Result<Option<String>> {
         if self.create_title.trim().is_empty() {
-            self.message = Some("Title cannot be empty".to_string());
-            return Ok(());
+            self.warn("Title cannot be empty");
+            return Ok(None);
         }
 
-        // If current selection is a container type, use it as parent
-        let parent = self.selected_pea().and_then(|p| {
-            if matches!(
-                p.pea_type,
-                PeaType::Milestone | PeaType::Epic | PeaType::Story | PeaType::Feature
-            ) {
-                Some(p.id.clone())
-            } else {
-                None
-            }
-        });
+        let tags: Vec<String> = self
+            .create_tags
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
 
         let id = self.repo.generate_id()?;
         let pea = crate::model::Pea::new(
@@ -1215,17 +2278,47 @@ impl App {
             self.create_title.trim().to_string(),
             self.create_type,
         )
-        .with_parent(parent);
+        .with_priority(self.create_priority)
+        .with_tags(tags)
+        .with_parent(self.create_parent.clone())
+        .with_body(self.create_body.trim().to_string());
+
+        // Keep the modal open so the input can be corrected
+        if let Err(e) = self.repo.validator().pea(&pea) {
+            self.warn(e.to_string());
+            return Ok(None);
+        }
 
-        let path = self.repo.create(&pea)?;
+        let path = match self.repo.create(&pea) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error(format!("Create failed: {}", e));
+                return Ok(None);
+            }
+        };
 
         // Record undo after create
-        let undo_manager = UndoManager::new(&self.data_path);
+        let undo_manager = self.repo.undo_manager();
         let _ = crate::undo::record_create(&undo_manager, &id, &path);
 
-        self.message = Some(format!("Created {}", id));
+        self.info(format!("Created {}", id));
         self.refresh()?;
         self.input_mode = InputMode::Normal;
+        Ok(Some(id))
+    }
+
+    /// Create a new ticket from the modal inputs and open it in the body editor
+    pub fn create_from_modal_and_edit(&mut self) -> Result<()> {
+        if let Some(id) = self.create_from_modal()?
+            && let Some(idx) = self.tree_nodes.iter().position(|n| n.pea.id == id)
+        {
+            self.selected_index = idx;
+            self.list_state.select(Some(self.index_in_page()));
+            self.detail_scroll = 0;
+            self.build_relations();
+            self.input_mode = InputMode::DetailView;
+            self.start_body_edit();
+        }
         Ok(())
     }
 
@@ -1244,19 +2337,19 @@ impl App {
 
         // Validate key
         if key.is_empty() {
-            self.message = Some("Key cannot be empty".to_string());
+            self.warn("Key cannot be empty");
             return Ok(());
         }
 
         // Validate key for filename safety (no path separators, no special chars)
         if key.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']) {
-            self.message = Some("Key contains invalid characters".to_string());
+            self.warn("Key contains invalid characters");
             return Ok(());
         }
 
         // Check if memory already exists
         if self.memory_repo.get(key).is_ok() {
-            self.message = Some(format!("Memory '{}' already exists", key));
+            self.warn(format!("Memory '{}' already exists", key));
             return Ok(());
         }
 
@@ -1275,7 +2368,7 @@ impl App {
 
         self.memory_repo.create(&memory)?;
 
-        self.message = Some(format!("Created memory '{}'", key));
+        self.info(format!("Created memory '{}'", key));
         self.refresh()?;
         self.input_mode = InputMode::Normal;
         Ok(())
@@ -1283,14 +2376,14 @@ impl App {
 
     /// Undo the last operation
     pub fn undo(&mut self) -> Result<()> {
-        let undo_manager = UndoManager::new(&self.data_path);
+        let undo_manager = self.repo.undo_manager();
         match undo_manager.undo() {
             Ok(msg) => {
-                self.message = Some(format!("Undo: {}", msg));
+                self.info(format!("Undo: {}", msg));
                 self.refresh()?;
             }
             Err(e) => {
-                self.message = Some(format!("Nothing to undo: {}", e));
+                self.warn(format!("Nothing to undo: {}", e));
             }
         }
         Ok(())
@@ -1298,20 +2391,26 @@ impl App {
 
     /// Get the number of operations that can be undone
     pub fn undo_count(&self) -> usize {
-        let undo_manager = UndoManager::new(&self.data_path);
+        let undo_manager = self.repo.undo_manager();
         undo_manager.undo_count()
     }
 
-    /// Open URL modal showing all URLs found in ticket body
+    /// Open URL modal showing the ticket's links followed by URLs found in its body
     pub fn open_url_modal(&mut self) {
         if let Some(pea) = self.selected_pea() {
-            self.url_candidates = url_utils::extract_urls(&pea.body);
+            let mut urls: Vec<String> = pea.links.iter().map(|l| l.url.clone()).collect();
+            for url in url_utils::extract_urls(&pea.body) {
+                if !urls.contains(&url) {
+                    urls.push(url);
+                }
+            }
+            self.url_candidates = urls;
             if !self.url_candidates.is_empty() {
                 self.modal_selection = 0;
                 self.previous_mode = self.input_mode;
                 self.input_mode = InputMode::UrlModal;
             } else {
-                self.message = Some("No URLs found in ticket body".to_string());
+                self.warn("No links or URLs in ticket");
             }
         }
     }
@@ -1321,10 +2420,10 @@ impl App {
         if let Some(url) = self.url_candidates.get(self.modal_selection) {
             match open::that(url) {
                 Ok(_) => {
-                    self.message = Some(format!("Opening: {}", url));
+                    self.info(format!("Opening: {}", url));
                 }
                 Err(e) => {
-                    self.message = Some(format!("Failed to open URL: {}", e));
+                    self.error(format!("Failed to open URL: {}", e));
                 }
             }
         }
@@ -1333,23 +2432,113 @@ impl App {
     }
 
     /// Start editing body inline with TextArea
+    ///
+    /// Picks up a draft left behind by an earlier cancelled edit.
     pub fn start_body_edit(&mut self) {
-        if let Some(pea) = self.selected_pea() {
-            self.body_textarea = Some(body_editor::create_textarea(&pea.body));
+        if let Some(pea) = self.selected_pea().cloned() {
+            let draft = LocalState::new(&self.data_path).read(&draft_name(&pea.id));
+            let mut textarea = body_editor::create_textarea(draft.as_deref().unwrap_or(&pea.body));
+            body_editor::refresh_highlighting(&mut textarea);
+            self.edit_base = Some(pea);
+            self.body_textarea = Some(textarea);
             self.input_mode = InputMode::EditBody;
             self.detail_pane = DetailPane::Body; // Force Body pane focus
+            if draft.is_some() {
+                self.info("Restored unsaved draft");
+            }
+        }
+    }
+
+    /// Keep the unsaved body as a draft and leave the editor
+    pub fn stash_body_edit(&mut self) -> Result<()> {
+        if let (Some(textarea), Some(pea)) = (&self.body_textarea, self.body_edit_target()) {
+            let local = LocalState::new(&self.data_path);
+            let draft = draft_name(&pea.id);
+            let body = textarea.value();
+            if body == pea.body {
+                local.remove(&draft)?;
+            } else {
+                local.write(&draft, &body)?;
+                self.info("Draft kept, reopen the editor to continue");
+            }
+        }
+        self.cancel_body_edit();
+        Ok(())
+    }
+
+    /// Show the diff of the edited body for confirmation before saving
+    ///
+    /// Returns false (and leaves the editor) when there is nothing to save.
+    pub fn review_body_edit(&mut self) -> bool {
+        let (Some(textarea), Some(pea)) = (&self.body_textarea, self.body_edit_target()) else {
+            return false;
+        };
+        let new_body = textarea.value();
+        if new_body == pea.body {
+            let _ = LocalState::new(&self.data_path).remove(&draft_name(&pea.id));
+            self.cancel_body_edit();
+            self.info("No changes");
+            return false;
+        }
+
+        let mut diff = body_editor::unified_diff(&pea.body, &new_body, 3);
+        if diff.is_empty() {
+            // Only line endings or a trailing newline differ
+            diff.push(body_editor::DiffLine::Hunk(
+                "(whitespace-only change at line ends)".to_string(),
+            ));
+        }
+        self.body_diff = diff;
+        self.body_diff_scroll = 0;
+        self.input_mode = InputMode::BodyDiff;
+        true
+    }
+
+    /// Return from the diff review to the editor without saving
+    pub fn resume_body_edit(&mut self) {
+        self.body_diff.clear();
+        self.input_mode = InputMode::EditBody;
+    }
+
+    /// The ticket the body editor writes to
+    ///
+    /// Looked up by id so a refresh that reorders the tree cannot redirect
+    /// the save to another ticket. Falls back to the edit base when the
+    /// ticket was deleted on disk and the user chose to overwrite.
+    fn body_edit_target(&self) -> Option<&Pea> {
+        match &self.edit_base {
+            Some(base) => self
+                .all_peas
+                .iter()
+                .find(|p| p.id == base.id)
+                .or(Some(base)),
+            None => self.selected_pea(),
         }
     }
 
     /// Save body edit and update the pea
     pub fn save_body_edit(&mut self) -> Result<()> {
-        if let (Some(textarea), Some(pea)) = (&self.body_textarea, self.selected_pea().cloned()) {
-            body_editor::save_body(textarea, &pea, &self.repo, &self.data_path)?;
+        if let (Some(textarea), Some(pea)) = (&self.body_textarea, self.body_edit_target().cloned())
+        {
+            let draft = draft_name(&pea.id);
+            if self.repo.find_file_by_id(&pea.id).is_ok() {
+                body_editor::save_body(textarea, &pea, &self.repo)?;
+            } else {
+                // Deleted on disk while editing; recreate it with the edited body
+                let mut recreated = pea;
+                recreated.body = textarea.value().into();
+                self.repo.create(&recreated)?;
+            }
 
             // Cleanup
+            LocalState::new(&self.data_path).remove(&draft)?;
             self.body_textarea = None;
+            self.body_diff.clear();
+            self.edit_base = None;
             self.input_mode = InputMode::DetailView;
             self.refresh()?;
+            // Our own edit is not news to us
+            self.mark_selected_read();
         }
         Ok(())
     }
@@ -1357,18 +2546,182 @@ impl App {
     /// Cancel body edit without saving
     pub fn cancel_body_edit(&mut self) {
         self.body_textarea = None;
+        self.body_diff.clear();
+        self.edit_base = None;
         self.input_mode = InputMode::DetailView;
     }
+
+    /// Whether `mode` edits the selected ticket and is protected against
+    /// changes made on disk in the meantime
+    fn is_protected_mode(mode: InputMode) -> bool {
+        matches!(
+            mode,
+            InputMode::EditBody
+                | InputMode::BodyDiff
+                | InputMode::StatusModal
+                | InputMode::PriorityModal
+                | InputMode::TypeModal
+                | InputMode::ParentModal
+                | InputMode::BlockingModal
+                | InputMode::TagsModal
+        )
+    }
+
+    /// Remember the ticket an edit started from, or forget it once editing ends
+    ///
+    /// Called on every event loop iteration so all modal entry points are covered.
+    pub fn sync_edit_base(&mut self) {
+        if Self::is_protected_mode(self.input_mode) && self.view_mode == ViewMode::Tickets {
+            if self.edit_base.is_none() {
+                self.edit_base = self.selected_pea().cloned();
+            }
+        } else if self.input_mode != InputMode::EditConflict {
+            self.edit_base = None;
+        }
+    }
+
+    /// Detect whether the ticket being edited changed on disk
+    ///
+    /// Called after a file watcher refresh. Opens the conflict prompt (or
+    /// updates an open one) and returns true if the version on disk no
+    /// longer matches the one the edit started from.
+    pub fn check_edit_conflict(&mut self) -> bool {
+        let Some(base) = &self.edit_base else {
+            return false;
+        };
+        let mut theirs = self.all_peas.iter().find(|p| p.id == base.id).cloned();
+        if theirs.as_ref().is_some_and(|p| p.updated == base.updated) {
+            return false;
+        }
+        if let Some(pea) = &mut theirs
+            && let Err(e) = self.repo.load_body(pea)
+        {
+            tracing::warn!(id = %pea.id, error = %e, "Failed to load body");
+        }
+
+        if let Some(conflict) = &mut self.edit_conflict {
+            conflict.theirs = theirs;
+            conflict.selection = 0;
+            return true;
+        }
+        if !Self::is_protected_mode(self.input_mode) {
+            return false;
+        }
+
+        let theirs_missing = theirs.is_none();
+        let message = match theirs {
+            Some(_) => format!("{} changed on disk while you were editing", base.id),
+            None => format!("{} was removed on disk while you were editing", base.id),
+        };
+        self.edit_conflict = Some(EditConflict {
+            base: base.clone(),
+            theirs,
+            resume_mode: self.input_mode,
+            selection: 0,
+        });
+        self.input_mode = InputMode::EditConflict;
+        tracing::info!(id = %base.id, deleted = theirs_missing, "Edit conflict detected");
+        self.warn(message);
+        true
+    }
+
+    /// Resolve the pending edit conflict and return to the interrupted edit
+    pub fn resolve_edit_conflict(&mut self, choice: ConflictChoice) {
+        let Some(conflict) = self.edit_conflict.take() else {
+            return;
+        };
+        tracing::info!(id = %conflict.base.id, ?choice, "Resolving edit conflict");
+        self.input_mode = conflict.resume_mode;
+
+        if !conflict.editing_body() {
+            // Modals apply their single change on top of the latest file
+            if choice == ConflictChoice::Reload {
+                self.input_mode = self.previous_mode;
+                self.edit_base = None;
+                self.info("Reloaded from disk");
+            } else {
+                self.edit_base = conflict.theirs;
+                self.info("Your change will be applied on top of the file on disk");
+            }
+            return;
+        }
+
+        self.body_diff.clear();
+        self.input_mode = InputMode::EditBody;
+        match (choice, conflict.theirs) {
+            (ConflictChoice::Reload, Some(theirs)) => {
+                if let Some(textarea) = &mut self.body_textarea {
+                    textarea.set_text(&*theirs.body);
+                    body_editor::refresh_highlighting(textarea);
+                }
+                self.edit_base = Some(theirs);
+                self.info("Reloaded from disk");
+            }
+            (ConflictChoice::Reload, None) => {
+                self.cancel_body_edit();
+                self.warn("Ticket no longer exists on disk; edit discarded");
+            }
+            (ConflictChoice::Merge, Some(theirs)) => {
+                let Some(textarea) = &mut self.body_textarea else {
+                    return;
+                };
+                let merged =
+                    body_editor::merge_bodies(&conflict.base.body, &textarea.value(), &theirs.body);
+                textarea.set_text(&merged.text);
+                body_editor::refresh_highlighting(textarea);
+                self.edit_base = Some(theirs);
+                if merged.conflicts > 0 {
+                    self.warn(format!(
+                        "{} conflicting region(s) marked in the body; resolve them before saving",
+                        merged.conflicts
+                    ));
+                } else {
+                    self.info("Merged changes from disk");
+                }
+            }
+            (ConflictChoice::Overwrite, theirs) | (ConflictChoice::Merge, theirs @ None) => {
+                // A deleted ticket keeps the old base, so saving recreates it
+                if let Some(theirs) = theirs {
+                    self.edit_base = Some(theirs);
+                }
+                self.info("Keeping your edit; saving will overwrite the file on disk");
+            }
+        }
+    }
 }
 
-pub fn run_tui(config: PeasConfig, project_root: PathBuf) -> Result<()> {
+/// Where the TUI starts
+#[derive(Debug, Clone, Default)]
+pub struct TuiOptions {
+    /// Ticket to open in the detail view
+    pub select: Option<String>,
+    /// Search query replacing the saved filter
+    pub filter: Option<String>,
+    /// View to start in
+    pub view: Option<StartView>,
+}
+
+/// Views the TUI can be started in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartView {
+    /// Ticket tree
+    Tickets,
+    /// Ticket tree with a preview of the selected ticket
+    Split,
+    /// Memory list
+    Memory,
+}
+
+pub fn run_tui(config: PeasConfig, project_root: PathBuf, options: TuiOptions) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
+    let _span = tracing::info_span!("tui", root = %project_root.display()).entered();
     let mut app = App::new(&config, &project_root)?;
+    app.start_at(&options)?;
 
     // Set up file watcher for .peas directory
     let (fs_tx, fs_rx) = mpsc::channel();
@@ -1423,17 +2776,34 @@ fn run_app(
             }
         }
 
+        app.load_selected_body();
+        app.sync_edit_base();
         terminal.draw(|f| ui::draw(f, app))?;
 
         // Check for file system events (non-blocking) — drain all pending events
         {
             let mut had_events = false;
-            while let Ok(Ok(_events)) = fs_rx.try_recv() {
-                had_events = true;
+            while let Ok(Ok(events)) = fs_rx.try_recv() {
+                // Our own log writes under .peas/logs/ are not ticket changes
+                had_events |= events
+                    .iter()
+                    .any(|event| !crate::logging::is_log_path(&event.path));
             }
             if had_events {
-                let _ = app.refresh();
-                app.message = Some("Files changed - refreshed".to_string());
+                match app.refresh() {
+                    Ok(()) if app.check_edit_conflict() => {}
+                    Ok(()) => {
+                        // Don't hide a pending warning or error behind routine feedback
+                        if app
+                            .message
+                            .as_ref()
+                            .is_none_or(|m| m.level == MessageLevel::Info)
+                        {
+                            app.info("Files changed - refreshed");
+                        }
+                    }
+                    Err(e) => app.error(format!("Refresh failed: {}", e)),
+                }
                 continue;
             }
         }
@@ -1455,6 +2825,10 @@ fn run_app(
                     continue;
                 }
 
+                // Informational messages only last until the next key press
+                app.clear_transient_message();
+
+                let mode_before = app.input_mode;
                 let should_quit = match app.input_mode {
                     InputMode::Normal => {
                         handlers::normal_mode::handle_normal_mode(app, key, terminal)?
@@ -1486,17 +2860,22 @@ fn run_app(
                         handlers::modal_memory_create::handle_memory_create_modal(app, key)?
                     }
                     InputMode::EditBody => handlers::edit_body::handle_edit_body(app, key)?,
+                    InputMode::BodyDiff => handlers::body_diff::handle_body_diff(app, key)?,
                     InputMode::TagsModal => handlers::modal_tags::handle_tags_modal(app, key)?,
                     InputMode::UrlModal => handlers::modal_url::handle_url_modal(app, key)?,
+                    InputMode::BulkModal => handlers::modal_bulk::handle_bulk_modal(app, key)?,
+                    InputMode::MessageLog => handlers::message_log::handle_message_log(app, key)?,
+                    InputMode::EditConflict => {
+                        handlers::edit_conflict::handle_edit_conflict(app, key)?
+                    }
                 };
 
-                if should_quit {
-                    return Ok(());
+                if app.input_mode != mode_before {
+                    tracing::debug!(from = ?mode_before, to = ?app.input_mode, "Input mode changed");
                 }
 
-                // Clear message after any key press
-                if app.message.is_some() && key.code != KeyCode::Enter {
-                    app.message = None;
+                if should_quit {
+                    return Ok(());
                 }
             }
             _ => {}
@@ -1,7 +1,30 @@
-use crate::{error::Result, model::Pea, storage::PeaRepository, undo::UndoManager};
+use crate::{error::Result, model::Pea, storage::PeaRepository};
 use rat_text::text_area::TextAreaState;
 use rat_text::undo_buffer::UndoVec;
-use std::path::Path;
+use regex::Regex;
+use std::{ops::Range, sync::LazyLock};
+
+/// Style indices for markdown highlighting, in the order the editor widget
+/// receives its text styles
+pub const STYLE_HEADING: usize = 0;
+pub const STYLE_EMPHASIS: usize = 1;
+pub const STYLE_CODE: usize = 2;
+pub const STYLE_LINK: usize = 3;
+pub const STYLE_LIST_MARKER: usize = 4;
+pub const STYLE_QUOTE: usize = 5;
+
+/// Inline markdown: `code`, **strong**/__strong__, *em*/_em_, [links](url)
+static INLINE_MARKDOWN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(`[^`\n]+`)|(\*\*[^*\n]+\*\*|__[^_\n]+__|\*[^*\s][^*\n]*\*|_[^_\s][^_\n]*_)|(\[[^\]\n]*\]\([^)\n]*\))",
+    )
+    .expect("valid inline markdown regex")
+});
+
+/// List item marker at the start of a line: `- `, `* `, `+ `, `1. `, `1) `
+static LIST_MARKER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:[-*+]|\d+[.)])(?:\s|$)").expect("valid list marker regex")
+});
 
 /// Initialize a TextAreaState for editing a pea's body
 pub fn create_textarea(body: &str) -> TextAreaState {
@@ -19,26 +42,353 @@ pub fn create_textarea(body: &str) -> TextAreaState {
 }
 
 /// Save edited body content to a pea
-pub fn save_body(
-    textarea: &TextAreaState,
-    pea: &Pea,
-    repo: &PeaRepository,
-    data_path: &Path,
-) -> Result<()> {
+pub fn save_body(textarea: &TextAreaState, pea: &Pea, repo: &PeaRepository) -> Result<()> {
     // Get edited content
     let new_body = textarea.value();
 
     // Record undo before update
-    let undo_manager = UndoManager::new(data_path);
+    let undo_manager = repo.undo_manager();
     if let Ok(path) = repo.find_file_by_id(&pea.id) {
         let _ = crate::undo::record_update(&undo_manager, &pea.id, &path);
     }
 
     // Update pea
     let mut updated = pea.clone();
-    updated.body = new_body;
+    updated.body = new_body.into();
     // NOTE: No touch() call - update() handles it internally now
     repo.update(&mut updated)?;
 
     Ok(())
 }
+
+/// Compute markdown highlighting ranges (byte ranges and style indices)
+///
+/// This is a lightweight line-based highlighter, good enough to make
+/// headings, code, emphasis, links, list markers and quotes stand out
+/// while typing; it does not try to be a full CommonMark parser.
+pub fn markdown_styles(text: &str) -> Vec<(Range<usize>, usize)> {
+    let mut styles = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+
+    for raw_line in text.split_inclusive('\n') {
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let line_range = offset..offset + line.len();
+        offset += raw_line.len();
+        let trimmed = line.trim_start();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            styles.push((line_range, STYLE_CODE));
+            continue;
+        }
+        if in_fence {
+            styles.push((line_range, STYLE_CODE));
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+            let rest = &trimmed[hashes..];
+            if hashes <= 6 && (rest.is_empty() || rest.starts_with(' ')) {
+                styles.push((line_range, STYLE_HEADING));
+                continue;
+            }
+        }
+        if trimmed.starts_with('>') {
+            styles.push((line_range, STYLE_QUOTE));
+            continue;
+        }
+
+        if let Some(m) = LIST_MARKER.find(line) {
+            styles.push((
+                line_range.start..line_range.start + m.end(),
+                STYLE_LIST_MARKER,
+            ));
+        }
+        for caps in INLINE_MARKDOWN.captures_iter(line) {
+            let (m, style) = if let Some(m) = caps.get(1) {
+                (m, STYLE_CODE)
+            } else if let Some(m) = caps.get(2) {
+                (m, STYLE_EMPHASIS)
+            } else if let Some(m) = caps.get(3) {
+                (m, STYLE_LINK)
+            } else {
+                continue;
+            };
+            styles.push((
+                line_range.start + m.start()..line_range.start + m.end(),
+                style,
+            ));
+        }
+    }
+
+    styles
+}
+
+/// Recompute markdown highlighting for the textarea's current text
+pub fn refresh_highlighting(textarea: &mut TextAreaState) {
+    let styles = markdown_styles(&textarea.text());
+    textarea.set_styles(styles);
+}
+
+/// A line of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Hunk header, e.g. `@@ -3,4 +3,5 @@`
+    Hunk(String),
+    Context(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Longest common subsequence table of two line lists, filled from the end
+fn lcs_table(old: &[&str], new: &[&str]) -> Vec<Vec<u32>> {
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    lcs
+}
+
+/// For every line of `old`, the index of the line it is kept as in `new`
+fn matching_lines(old: &[&str], new: &[&str]) -> Vec<Option<usize>> {
+    let lcs = lcs_table(old, new);
+    let mut matches = vec![None; old.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            matches[i] = Some(j);
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+/// Markers wrapped around conflicting regions of a merged body
+pub const CONFLICT_START: &str = "<<<<<<< editor";
+pub const CONFLICT_SEPARATOR: &str = "=======";
+pub const CONFLICT_END: &str = ">>>>>>> disk";
+
+/// Outcome of a three-way body merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    pub text: String,
+    /// Number of regions both sides changed differently (marked in `text`)
+    pub conflicts: usize,
+}
+
+/// Three-way line merge of the editor's body and the on-disk body
+///
+/// `base` is the body the edit started from. Regions changed on only one
+/// side take that side's version; regions changed differently on both sides
+/// are kept with git-style conflict markers for the user to resolve.
+pub fn merge_bodies(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let ours_lines: Vec<&str> = ours.lines().collect();
+    let theirs_lines: Vec<&str> = theirs.lines().collect();
+    let ours_match = matching_lines(&base_lines, &ours_lines);
+    let theirs_match = matching_lines(&base_lines, &theirs_lines);
+
+    let mut merged: Vec<&str> = Vec::new();
+    let mut conflicts = 0;
+    let (mut i, mut a, mut b) = (0, 0, 0);
+    loop {
+        // Next base line both sides kept; everything before it is a changed chunk
+        let stable =
+            (i..base_lines.len()).find_map(|j| Some((j, ours_match[j]?, theirs_match[j]?)));
+        let (j, x, y) = stable.unwrap_or((base_lines.len(), ours_lines.len(), theirs_lines.len()));
+        let base_chunk = &base_lines[i..j];
+        let ours_chunk = &ours_lines[a..x];
+        let theirs_chunk = &theirs_lines[b..y];
+
+        if ours_chunk == base_chunk {
+            merged.extend(theirs_chunk);
+        } else if theirs_chunk == base_chunk || ours_chunk == theirs_chunk {
+            merged.extend(ours_chunk);
+        } else {
+            conflicts += 1;
+            merged.push(CONFLICT_START);
+            merged.extend(ours_chunk);
+            merged.push(CONFLICT_SEPARATOR);
+            merged.extend(theirs_chunk);
+            merged.push(CONFLICT_END);
+        }
+
+        let Some((j, x, y)) = stable else {
+            break;
+        };
+        merged.push(base_lines[j]);
+        (i, a, b) = (j + 1, x + 1, y + 1);
+    }
+
+    let mut text = merged.join("\n");
+    if !text.is_empty() && ours.ends_with('\n') {
+        text.push('\n');
+    }
+    MergeResult { text, conflicts }
+}
+
+/// Unified line diff of `old` vs `new` with `context` lines around each change
+pub fn unified_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+
+    let (n, m) = (old_lines.len(), new_lines.len());
+    let lcs = lcs_table(&old_lines, &new_lines);
+
+    // Walk the table into a flat edit script: (line, old index, new index)
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_lines[i] == new_lines[j] {
+            ops.push((DiffLine::Context(old_lines[i].to_string()), i, j));
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // Prefer removals first so a changed line reads as `-old` then `+new`
+            ops.push((DiffLine::Removed(old_lines[i].to_string()), i, j));
+            i += 1;
+        } else {
+            ops.push((DiffLine::Added(new_lines[j].to_string()), i, j));
+            j += 1;
+        }
+    }
+
+    // Group changes with surrounding context into hunks
+    let changed: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (line, _, _))| !matches!(line, DiffLine::Context(_)))
+        .map(|(idx, _)| idx)
+        .collect();
+    let mut hunks: Vec<Range<usize>> = Vec::new();
+    for idx in changed {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.end => last.end = end,
+            _ => hunks.push(start..end),
+        }
+    }
+
+    let mut result = Vec::new();
+    for hunk in hunks {
+        let slice = &ops[hunk];
+        let old_len = slice
+            .iter()
+            .filter(|(l, _, _)| !matches!(l, DiffLine::Added(_)))
+            .count();
+        let new_len = slice
+            .iter()
+            .filter(|(l, _, _)| !matches!(l, DiffLine::Removed(_)))
+            .count();
+        let (_, old_start, new_start) = slice[0];
+        result.push(DiffLine::Hunk(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + 1,
+            old_len,
+            new_start + 1,
+            new_len
+        )));
+        result.extend(slice.iter().map(|(line, _, _)| line.clone()));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn styled<'a>(text: &'a str, styles: &[(Range<usize>, usize)], style: usize) -> Vec<&'a str> {
+        styles
+            .iter()
+            .filter(|(_, s)| *s == style)
+            .map(|(r, _)| &text[r.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn test_markdown_styles() {
+        let text = "# Title\n- item with `code` and **bold**\n> quote\nsee [docs](http://x)\n```\nlet x = 1;\n```\n";
+        let styles = markdown_styles(text);
+
+        assert_eq!(styled(text, &styles, STYLE_HEADING), vec!["# Title"]);
+        assert_eq!(styled(text, &styles, STYLE_LIST_MARKER), vec!["- "]);
+        assert_eq!(
+            styled(text, &styles, STYLE_CODE),
+            vec!["`code`", "```", "let x = 1;", "```"]
+        );
+        assert_eq!(styled(text, &styles, STYLE_EMPHASIS), vec!["**bold**"]);
+        assert_eq!(styled(text, &styles, STYLE_QUOTE), vec!["> quote"]);
+        assert_eq!(styled(text, &styles, STYLE_LINK), vec!["[docs](http://x)"]);
+    }
+
+    #[test]
+    fn test_hashtag_is_not_heading() {
+        let styles = markdown_styles("#hashtag");
+        assert!(styles.is_empty());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        let old = "a\nb\nc\nd\ne\nf\ng";
+        let new = "a\nb\nC\nd\ne\nf\ng\nh";
+        let diff = unified_diff(old, new, 1);
+        assert_eq!(
+            diff,
+            vec![
+                DiffLine::Hunk("@@ -2,3 +2,3 @@".to_string()),
+                DiffLine::Context("b".to_string()),
+                DiffLine::Removed("c".to_string()),
+                DiffLine::Added("C".to_string()),
+                DiffLine::Context("d".to_string()),
+                DiffLine::Hunk("@@ -7,1 +7,2 @@".to_string()),
+                DiffLine::Context("g".to_string()),
+                DiffLine::Added("h".to_string()),
+            ]
+        );
+        assert!(unified_diff(old, old, 3).is_empty());
+    }
+
+    #[test]
+    fn test_merge_bodies_combines_independent_changes() {
+        let base = "a\nb\nc\nd\ne";
+        let ours = "a\nB\nc\nd\ne";
+        let theirs = "a\nb\nc\nd\nE\nf";
+        let merged = merge_bodies(base, ours, theirs);
+        assert_eq!(merged.text, "a\nB\nc\nd\nE\nf");
+        assert_eq!(merged.conflicts, 0);
+    }
+
+    #[test]
+    fn test_merge_bodies_marks_conflicts() {
+        let merged = merge_bodies("a\nb\nc", "a\nmine\nc", "a\ntheirs\nc");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            format!("a\n{CONFLICT_START}\nmine\n{CONFLICT_SEPARATOR}\ntheirs\n{CONFLICT_END}\nc")
+        );
+
+        // Identical changes on both sides are not a conflict
+        let same = merge_bodies("a\nb", "a\nx", "a\nx");
+        assert_eq!(
+            same,
+            MergeResult {
+                text: "a\nx".to_string(),
+                conflicts: 0
+            }
+        );
+    }
+}
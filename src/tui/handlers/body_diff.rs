@@ -0,0 +1,27 @@
+use crate::tui::app::App;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+/// Handle BodyDiff mode key events
+/// Returns Ok(true) if the application should quit, Ok(false) otherwise
+pub fn handle_body_diff(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    match key.code {
+        KeyCode::Char('y') | KeyCode::Enter => match app.save_body_edit() {
+            Ok(()) => app.info("Saved successfully"),
+            Err(e) => app.error(format!("Save failed: {}", e)),
+        },
+        KeyCode::Char('n') | KeyCode::Esc => {
+            app.resume_body_edit();
+        }
+        KeyCode::Down | KeyCode::Char('j') => {
+            let max = app.body_diff.len().saturating_sub(1) as u16;
+            app.body_diff_scroll = (app.body_diff_scroll + 1).min(max);
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.body_diff_scroll = app.body_diff_scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+
+    Ok(false)
+}
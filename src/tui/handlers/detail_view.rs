@@ -1,4 +1,4 @@
-use crate::tui::app::{App, DetailPane, InputMode};
+use crate::tui::app::{App, DetailPane};
 use arboard::Clipboard;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent},
@@ -17,8 +17,10 @@ pub fn handle_detail_view(
 ) -> io::Result<bool> {
     match key.code {
         KeyCode::Esc | KeyCode::Char('q') => {
-            app.input_mode = InputMode::Normal;
-            app.detail_pane = DetailPane::Body;
+            app.close_detail_view();
+        }
+        KeyCode::Backspace => {
+            app.detail_back();
         }
         KeyCode::Tab => {
             app.toggle_detail_pane();
@@ -36,10 +38,10 @@ pub fn handle_detail_view(
             } else if app.detail_pane == DetailPane::Relations && !app.relations_items.is_empty() {
                 app.jump_to_relation();
             } else if app.detail_pane == DetailPane::Assets && !app.assets_items.is_empty() {
-                let _ = app.open_selected_asset();
+                let result = app.open_selected_asset();
+                app.report_error("Failed to open asset", result);
             } else {
-                app.input_mode = InputMode::Normal;
-                app.detail_pane = DetailPane::Body;
+                app.close_detail_view();
             }
         }
         KeyCode::Down | KeyCode::Char('j') => match app.detail_pane {
@@ -107,7 +109,8 @@ pub fn handle_detail_view(
                 enable_raw_mode()?;
                 execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
                 terminal.clear()?;
-                let _ = app.refresh();
+                let result = app.refresh();
+                app.report_error("Refresh failed", result);
                 app.build_relations(); // Rebuild relations after edit
             }
         }
@@ -133,12 +136,12 @@ pub fn handle_detail_view(
                 let id = pea.id.clone();
                 if let Ok(mut ctx) = Clipboard::new() {
                     if ctx.set_text(id.clone()).is_ok() {
-                        app.message = Some(format!("Copied: {}", id));
+                        app.info(format!("Copied: {}", id));
                     } else {
-                        app.message = Some("Failed to copy to clipboard".to_string());
+                        app.error("Failed to copy to clipboard");
                     }
                 } else {
-                    app.message = Some("Clipboard not available".to_string());
+                    app.warn("Clipboard not available");
                 }
             }
         }
@@ -1,30 +1,51 @@
-use crate::tui::app::App;
-use crossterm::event::{Event, KeyCode, KeyEvent};
+use crate::tui::{app::App, body_editor};
+use crate::validation::sanitize_pasted;
+use arboard::Clipboard;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use rat_text::event::TextOutcome;
 use std::io;
 
 /// Handle EditBody mode key events
 /// Returns Ok(true) if the application should quit, Ok(false) otherwise
 pub fn handle_edit_body(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
     match key.code {
         KeyCode::Esc => {
-            app.cancel_body_edit();
+            let result = app.stash_body_edit();
+            app.report_error("Failed to keep draft", result);
         }
-        KeyCode::Char('s')
-            if key
-                .modifiers
-                .contains(crossterm::event::KeyModifiers::CONTROL) =>
-        {
-            if let Err(e) = app.save_body_edit() {
-                app.message = Some(format!("Save failed: {}", e));
-            } else {
-                app.message = Some("Saved successfully".to_string());
+        KeyCode::Char('s') if ctrl => {
+            // Review the diff first; saving happens on confirmation
+            app.review_body_edit();
+        }
+        KeyCode::Char('w') if ctrl => {
+            app.body_wrap = !app.body_wrap;
+        }
+        KeyCode::Char('l') if ctrl => {
+            app.body_line_numbers = !app.body_line_numbers;
+        }
+        KeyCode::Char('v') if ctrl => {
+            // Paste from the system clipboard rather than the editor's own
+            match Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+                Ok(text) => {
+                    let text = sanitize_pasted(&text);
+                    if let Some(ref mut textarea) = app.body_textarea
+                        && textarea.insert_str(&text)
+                    {
+                        body_editor::refresh_highlighting(textarea);
+                    }
+                }
+                Err(_) => app.warn("Clipboard not available"),
             }
         }
         _ => {
             // Pass all other events to textarea using the proper handle_events function
             if let Some(ref mut textarea) = app.body_textarea {
                 let event = Event::Key(key);
-                let _ = rat_text::text_area::handle_events(textarea, true, &event);
+                let outcome = rat_text::text_area::handle_events(textarea, true, &event);
+                if outcome == TextOutcome::TextChanged {
+                    body_editor::refresh_highlighting(textarea);
+                }
             }
         }
     }
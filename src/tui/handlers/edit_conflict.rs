@@ -0,0 +1,34 @@
+use crate::tui::app::App;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+/// Handle EditConflict mode key events
+/// Returns Ok(true) if the application should quit, Ok(false) otherwise
+pub fn handle_edit_conflict(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    let Some(conflict) = &mut app.edit_conflict else {
+        return Ok(false);
+    };
+    let choices = conflict.choices();
+
+    // No Esc here: leaving the prompt requires an explicit decision
+    match key.code {
+        KeyCode::Down | KeyCode::Char('j') => {
+            conflict.selection = (conflict.selection + 1) % choices.len();
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            conflict.selection = (conflict.selection + choices.len() - 1) % choices.len();
+        }
+        KeyCode::Enter => {
+            let choice = choices[conflict.selection.min(choices.len() - 1)];
+            app.resolve_edit_conflict(choice);
+        }
+        KeyCode::Char(c) => {
+            if let Some(&choice) = choices.iter().find(|choice| choice.key() == c) {
+                app.resolve_edit_conflict(choice);
+            }
+        }
+        _ => {}
+    }
+
+    Ok(false)
+}
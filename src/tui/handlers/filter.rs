@@ -1,5 +1,5 @@
 use crate::tui::app::{App, InputMode};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::io;
 
 /// Handle Filter mode key events
@@ -8,6 +8,14 @@ pub fn handle_filter_mode(app: &mut App, key: KeyEvent) -> io::Result<bool> {
     match key.code {
         KeyCode::Enter | KeyCode::Esc => {
             app.input_mode = InputMode::Normal;
+            let result = app.save_filter();
+            app.report_error("Failed to save filter", result);
+        }
+        KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_search_regex();
+        }
+        KeyCode::Char('f') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_search_fuzzy();
         }
         KeyCode::Char(c) => {
             app.search_query.push(c);
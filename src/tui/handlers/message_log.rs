@@ -0,0 +1,29 @@
+use crate::tui::app::App;
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+/// Handle MessageLog mode key events
+/// Returns Ok(true) if the application should quit, Ok(false) otherwise
+pub fn handle_message_log(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    let count = app.message_log.len();
+    match key.code {
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('m') => {
+            app.input_mode = app.previous_mode;
+        }
+        KeyCode::Char('c') => {
+            // Clear the log and dismiss any persistent message
+            app.message_log.clear();
+            app.message = None;
+            app.modal_selection = 0;
+        }
+        KeyCode::Down | KeyCode::Char('j') if count > 0 => {
+            app.modal_selection = (app.modal_selection + 1).min(count - 1);
+        }
+        KeyCode::Up | KeyCode::Char('k') => {
+            app.modal_selection = app.modal_selection.saturating_sub(1);
+        }
+        _ => {}
+    }
+
+    Ok(false)
+}
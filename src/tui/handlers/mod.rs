@@ -1,7 +1,11 @@
+pub mod body_diff;
 pub mod detail_view;
 pub mod edit_body;
+pub mod edit_conflict;
 pub mod filter;
+pub mod message_log;
 pub mod modal_blocking;
+pub mod modal_bulk;
 pub mod modal_create;
 pub mod modal_delete;
 pub mod modal_enum;
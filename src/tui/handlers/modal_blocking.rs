@@ -10,7 +10,8 @@ pub fn handle_blocking_modal(app: &mut App, key: KeyEvent) -> io::Result<bool> {
             app.input_mode = app.previous_mode;
         }
         KeyCode::Enter => {
-            let _ = app.apply_modal_blocking();
+            let result = app.apply_modal_blocking();
+            app.report_error("Failed to set blocking", result);
         }
         KeyCode::Char(' ') => {
             app.toggle_blocking_selection();
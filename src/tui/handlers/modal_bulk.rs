@@ -0,0 +1,73 @@
+use crate::tui::app::{App, BulkAction};
+use crossterm::event::{KeyCode, KeyEvent};
+use std::io;
+
+/// Handle BulkModal mode key events
+/// Returns Ok(true) if the application should quit, Ok(false) otherwise
+pub fn handle_bulk_modal(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    // Confirmation step
+    if app.bulk_pending.is_some() {
+        match key.code {
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let result = app.apply_bulk_pending();
+                app.report_error("Bulk operation failed", result);
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => app.bulk_back(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    match app.bulk_action {
+        // Action palette
+        None => {
+            let count = BulkAction::all().len();
+            match key.code {
+                KeyCode::Esc => app.bulk_back(),
+                KeyCode::Enter => app.select_bulk_action(),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    app.modal_selection = (app.modal_selection + 1) % count;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    app.modal_selection = if app.modal_selection == 0 {
+                        count - 1
+                    } else {
+                        app.modal_selection - 1
+                    };
+                }
+                _ => {}
+            }
+        }
+        // Tag input
+        Some(BulkAction::AddTags) | Some(BulkAction::RemoveTags) => match key.code {
+            KeyCode::Esc => app.bulk_back(),
+            KeyCode::Enter => app.choose_bulk_value(),
+            KeyCode::Backspace => {
+                app.bulk_input.pop();
+            }
+            KeyCode::Char(c) => app.bulk_input.push(c),
+            _ => {}
+        },
+        // Value list
+        Some(_) => {
+            let count = app.bulk_value_count().max(1);
+            match key.code {
+                KeyCode::Esc => app.bulk_back(),
+                KeyCode::Enter => app.choose_bulk_value(),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    app.modal_selection = (app.modal_selection + 1) % count;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    app.modal_selection = if app.modal_selection == 0 {
+                        count - 1
+                    } else {
+                        app.modal_selection - 1
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(false)
+}
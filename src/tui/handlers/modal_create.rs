@@ -1,56 +1,110 @@
 use crate::tui::app::App;
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use std::io;
 
+// Field indices in the create modal
+const FIELD_TITLE: usize = 0;
+const FIELD_TYPE: usize = 1;
+const FIELD_PRIORITY: usize = 2;
+const FIELD_TAGS: usize = 3;
+const FIELD_PARENT: usize = 4;
+const FIELD_BODY: usize = 5;
+
 /// Handle CreateModal mode key events
 /// Returns Ok(true) if the application should quit, Ok(false) otherwise
 pub fn handle_create_modal(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    // Ctrl+E: create and jump straight into the body editor of the new ticket
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('e') {
+        let result = app.create_from_modal_and_edit();
+        app.report_error("Create failed", result);
+        return Ok(false);
+    }
+
+    // Ctrl+S: create from any field, as Enter starts a new line in the body
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('s') {
+        let result = app.create_from_modal();
+        app.report_error("Create failed", result);
+        return Ok(false);
+    }
+
     match key.code {
         KeyCode::Esc => {
             app.input_mode = crate::tui::app::InputMode::Normal;
         }
+        KeyCode::Enter if app.modal_selection == FIELD_BODY => app.create_body.push('\n'),
         KeyCode::Enter => {
-            let _ = app.create_from_modal();
+            let result = app.create_from_modal();
+            app.report_error("Create failed", result);
         }
-        KeyCode::Tab => {
-            // Toggle between title (0) and type (1) fields
-            app.modal_selection = (app.modal_selection + 1) % 2;
+        KeyCode::Tab | KeyCode::Down => {
+            app.modal_selection = (app.modal_selection + 1) % App::CREATE_FIELD_COUNT;
         }
-        KeyCode::BackTab => {
-            app.modal_selection = if app.modal_selection == 0 { 1 } else { 0 };
-        }
-        KeyCode::Char(c) => {
-            if app.modal_selection == 0 {
-                // Title field - add character
-                app.create_title.push(c);
+        KeyCode::BackTab | KeyCode::Up => {
+            app.modal_selection = if app.modal_selection == 0 {
+                App::CREATE_FIELD_COUNT - 1
             } else {
-                // Type field - cycle through types with space
-                // (handled below)
+                app.modal_selection - 1
+            };
+        }
+        KeyCode::Left | KeyCode::Right => {
+            let forward = key.code == KeyCode::Right;
+            match app.modal_selection {
+                FIELD_TYPE => {
+                    app.create_type = cycle(App::type_options(), app.create_type, forward);
+                }
+                FIELD_PRIORITY => {
+                    app.create_priority =
+                        cycle(App::priority_options(), app.create_priority, forward);
+                }
+                FIELD_PARENT => app.cycle_create_parent(forward),
+                _ => {}
             }
         }
-        KeyCode::Backspace => {
-            if app.modal_selection == 0 {
+        KeyCode::Char(c) => match app.modal_selection {
+            FIELD_TITLE => app.create_title.push(c),
+            FIELD_TAGS => app.create_tags.push(c),
+            FIELD_PARENT => {
+                app.create_parent_query.push(c);
+                app.update_create_parent_query();
+            }
+            FIELD_BODY => app.create_body.push(c),
+            _ => {}
+        },
+        KeyCode::Backspace => match app.modal_selection {
+            FIELD_TITLE => {
                 app.create_title.pop();
             }
-        }
-        KeyCode::Left | KeyCode::Right if app.modal_selection == 1 => {
-            // Cycle type
-            let types = App::type_options();
-            let current_idx = types
-                .iter()
-                .position(|t| *t == app.create_type)
-                .unwrap_or(0);
-            let new_idx = if key.code == KeyCode::Right {
-                (current_idx + 1) % types.len()
-            } else if current_idx == 0 {
-                types.len() - 1
-            } else {
-                current_idx - 1
-            };
-            app.create_type = types[new_idx];
-        }
+            FIELD_TAGS => {
+                app.create_tags.pop();
+            }
+            FIELD_PARENT => {
+                // Backspace on an empty search clears the parent
+                if app.create_parent_query.pop().is_none() {
+                    app.create_parent = None;
+                } else {
+                    app.update_create_parent_query();
+                }
+            }
+            FIELD_BODY => {
+                app.create_body.pop();
+            }
+            _ => {}
+        },
         _ => {}
     }
 
     Ok(false)
 }
+
+/// Step to the next/previous option, wrapping around at either end
+fn cycle<T: Copy + PartialEq>(options: &[T], current: T, forward: bool) -> T {
+    let current_idx = options.iter().position(|o| *o == current).unwrap_or(0);
+    let new_idx = if forward {
+        (current_idx + 1) % options.len()
+    } else if current_idx == 0 {
+        options.len() - 1
+    } else {
+        current_idx - 1
+    };
+    options[new_idx]
+}
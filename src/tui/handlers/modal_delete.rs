@@ -1,16 +1,47 @@
-use crate::tui::app::{App, InputMode};
+use crate::removal::{ChildPolicy, RemovalKind};
+use crate::tui::app::{App, InputMode, ViewMode};
 use crossterm::event::{KeyCode, KeyEvent};
 use std::io;
 
 /// Handle DeleteConfirm mode key events
 /// Returns Ok(true) if the application should quit, Ok(false) otherwise
 pub fn handle_delete_confirm(app: &mut App, key: KeyEvent) -> io::Result<bool> {
+    let failed = match app.removal_kind {
+        RemovalKind::Delete => "Delete failed",
+        RemovalKind::Archive => "Archive failed",
+    };
     match key.code {
         KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
             app.input_mode = InputMode::Normal;
         }
-        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
-            let _ = app.delete_selected();
+        // A ticket with children is never removed without a choice for them
+        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y')
+            if app.view_mode != ViewMode::Tickets || app.selected_children().is_empty() =>
+        {
+            let result = if app.view_mode == ViewMode::Tickets {
+                app.remove_selected(ChildPolicy::Refuse)
+            } else {
+                app.delete_selected()
+            };
+            app.report_error(failed, result);
+        }
+        // Choices for the children of a ticket
+        KeyCode::Char('c') if !app.selected_children().is_empty() => {
+            let result = app.remove_selected(ChildPolicy::Cascade);
+            app.report_error(failed, result);
+        }
+        KeyCode::Char('o') if !app.selected_children().is_empty() => {
+            let result = app.remove_selected(ChildPolicy::Orphan);
+            app.report_error(failed, result);
+        }
+        KeyCode::Char('p') if !app.selected_children().is_empty() => {
+            match app.selected_pea().and_then(|p| p.parent.clone()) {
+                Some(parent) => {
+                    let result = app.remove_selected(ChildPolicy::Reparent(parent));
+                    app.report_error(failed, result);
+                }
+                None => app.warn("No parent to move the children to; use c or o"),
+            }
         }
         _ => {}
     }
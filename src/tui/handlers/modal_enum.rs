@@ -20,7 +20,8 @@ where
             app.input_mode = app.previous_mode;
         }
         KeyCode::Enter => {
-            let _ = apply_fn(app);
+            let result = apply_fn(app);
+            app.report_error("Update failed", result);
         }
         KeyCode::Down | KeyCode::Char('j') => {
             app.modal_selection = (app.modal_selection + 1) % options_count;
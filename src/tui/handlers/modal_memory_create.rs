@@ -10,7 +10,8 @@ pub fn handle_memory_create_modal(app: &mut App, key: KeyEvent) -> io::Result<bo
             app.input_mode = InputMode::Normal;
         }
         KeyCode::Enter => {
-            let _ = app.create_memory_from_modal();
+            let result = app.create_memory_from_modal();
+            app.report_error("Create failed", result);
         }
         KeyCode::Tab => {
             // Cycle between key (0), tags (1), and content (2) fields
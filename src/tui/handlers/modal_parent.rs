@@ -10,7 +10,8 @@ pub fn handle_parent_modal(app: &mut App, key: KeyEvent) -> io::Result<bool> {
             app.input_mode = app.previous_mode;
         }
         KeyCode::Enter => {
-            let _ = app.apply_modal_parent();
+            let result = app.apply_modal_parent();
+            app.report_error("Failed to set parent", result);
         }
         KeyCode::Down | KeyCode::Char('j') => {
             let count = app.parent_candidates.len() + 1; // +1 for "(none)"
@@ -11,9 +11,12 @@ pub fn handle_tags_modal(app: &mut App, key: KeyEvent) -> io::Result<bool> {
         }
         KeyCode::Enter => {
             if let Err(e) = app.apply_tags_modal() {
-                app.message = Some(format!("Failed to update tags: {}", e));
+                app.error(format!("Failed to update tags: {}", e));
             }
         }
+        KeyCode::Tab => {
+            app.complete_tags_modal();
+        }
         KeyCode::Char(c) => {
             app.tags_input.push(c);
         }
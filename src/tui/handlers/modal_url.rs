@@ -10,7 +10,8 @@ pub fn handle_url_modal(app: &mut App, key: KeyEvent) -> io::Result<bool> {
             app.input_mode = app.previous_mode;
         }
         KeyCode::Enter => {
-            let _ = app.open_selected_url();
+            let result = app.open_selected_url();
+            app.report_error("Failed to open URL", result);
         }
         KeyCode::Down | KeyCode::Char('j') => {
             let count = app.url_candidates.len();
@@ -1,4 +1,7 @@
-use crate::tui::app::{App, InputMode, ViewMode};
+use crate::tui::{
+    app::{App, InputMode, ViewMode},
+    keymap::Action,
+};
 use arboard::Clipboard;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture, KeyCode, KeyEvent},
@@ -15,32 +18,47 @@ pub fn handle_normal_mode(
     key: KeyEvent,
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
 ) -> io::Result<bool> {
-    match key.code {
-        KeyCode::Char('q') => return Ok(true),
-        KeyCode::Char('?') => app.show_help = !app.show_help,
-        KeyCode::Tab => {
+    // Esc is fixed: it always backs out of pending keys, help, selection and search
+    if key.code == KeyCode::Esc {
+        if app.clear_pending_keys() {
+            // Cancelled a count or key sequence
+        } else if app.show_help {
+            app.show_help = false;
+        } else if !app.multi_selected.is_empty() {
+            app.clear_multi_select();
+        } else if !app.search_query.is_empty() {
+            app.search_query.clear();
+            app.apply_filter();
+        }
+        return Ok(false);
+    }
+
+    let Some((action, count)) = app.resolve_key(key) else {
+        return Ok(false);
+    };
+    let repeat = count.unwrap_or(1);
+
+    match action {
+        Action::Quit => return Ok(true),
+        Action::Help => app.show_help = !app.show_help,
+        Action::SwitchView => {
             app.switch_view();
         }
-        KeyCode::Esc => {
-            if app.show_help {
-                app.show_help = false;
-            } else if !app.multi_selected.is_empty() {
-                app.clear_multi_select();
-            } else if !app.search_query.is_empty() {
-                app.search_query.clear();
-                app.apply_filter();
-            }
+        Action::SplitView => {
+            app.split_view = !app.split_view;
         }
-        KeyCode::Down | KeyCode::Char('j') => app.next(),
-        KeyCode::Up | KeyCode::Char('k') => app.previous(),
-        KeyCode::Right | KeyCode::PageDown | KeyCode::Char('J') => app.next_page(),
-        KeyCode::Left | KeyCode::PageUp | KeyCode::Char('K') => app.previous_page(),
-        KeyCode::Home | KeyCode::Char('g') => app.first(),
-        KeyCode::End | KeyCode::Char('G') => app.last(),
-        KeyCode::Char('/') => {
+        Action::MoveDown => (0..repeat).for_each(|_| app.next()),
+        Action::MoveUp => (0..repeat).for_each(|_| app.previous()),
+        Action::NextPage => (0..repeat).for_each(|_| app.next_page()),
+        Action::PrevPage => (0..repeat).for_each(|_| app.previous_page()),
+        // With a count, `5gg` / `5G` jump to the 5th item like vim's line numbers
+        Action::First | Action::Last if count.is_some() => app.go_to(repeat - 1),
+        Action::First => app.first(),
+        Action::Last => app.last(),
+        Action::Search => {
             app.input_mode = InputMode::Filter;
         }
-        KeyCode::Enter => {
+        Action::Open => {
             match app.view_mode {
                 ViewMode::Tickets => {
                     // Open full-screen detail view for tickets
@@ -48,6 +66,7 @@ pub fn handle_normal_mode(
                         app.detail_scroll = 0;
                         app.build_relations();
                         app.input_mode = InputMode::DetailView;
+                        app.mark_selected_read();
                     }
                 }
                 ViewMode::Memory => {
@@ -59,25 +78,31 @@ pub fn handle_normal_mode(
                 }
             }
         }
-        KeyCode::Char(' ') => {
+        Action::Select => {
             app.toggle_multi_select();
         }
-        KeyCode::Char('s') => {
+        Action::Status => {
             app.open_status_modal();
         }
-        KeyCode::Char('P') => {
+        Action::Priority => {
             app.open_priority_modal();
         }
-        KeyCode::Char('t') => {
+        Action::Type => {
             app.open_type_modal();
         }
-        KeyCode::Char('p') => {
+        Action::Parent => {
             app.open_parent_modal();
         }
-        KeyCode::Char('b') => {
+        Action::Blocking => {
             app.open_blocking_modal();
         }
-        KeyCode::Char('c') => match app.view_mode {
+        Action::Bulk => {
+            app.open_bulk_modal();
+        }
+        Action::Messages => {
+            app.open_message_log();
+        }
+        Action::Create => match app.view_mode {
             ViewMode::Tickets => {
                 app.open_create_modal();
             }
@@ -85,28 +110,36 @@ pub fn handle_normal_mode(
                 app.open_memory_create_modal();
             }
         },
-        KeyCode::Char('d') => {
+        Action::Delete => {
             app.open_delete_confirm();
         }
-        KeyCode::Char('r') => {
-            let _ = app.refresh();
-            app.message = Some("Refreshed".to_string());
+        Action::Archive => {
+            let result = app.archive_selected();
+            app.report_error("Archive failed", result);
         }
-        KeyCode::Char('y') => {
+        Action::ToggleArchived if app.view_mode == ViewMode::Tickets => {
+            let result = app.toggle_show_archived();
+            app.report_error("Refresh failed", result);
+        }
+        Action::Refresh => match app.refresh() {
+            Ok(()) => app.info("Refreshed"),
+            Err(e) => app.error(format!("Refresh failed: {}", e)),
+        },
+        Action::CopyId => {
             if let Some(pea) = app.selected_pea() {
                 let id = pea.id.clone();
                 if let Ok(mut ctx) = Clipboard::new() {
                     if ctx.set_text(id.clone()).is_ok() {
-                        app.message = Some(format!("Copied: {}", id));
+                        app.info(format!("Copied: {}", id));
                     } else {
-                        app.message = Some("Failed to copy to clipboard".to_string());
+                        app.error("Failed to copy to clipboard");
                     }
                 } else {
-                    app.message = Some("Clipboard not available".to_string());
+                    app.warn("Clipboard not available");
                 }
             }
         }
-        KeyCode::Char('e') => {
+        Action::Edit => {
             if let Some(file_path) = app.selected_pea_file_path() {
                 // Leave alternate screen temporarily
                 disable_raw_mode()?;
@@ -135,19 +168,20 @@ pub fn handle_normal_mode(
                 let _ = app.refresh();
                 match status {
                     Ok(s) if s.success() => {
-                        app.message = Some("Editor closed".to_string());
+                        app.info("Editor closed");
                     }
                     Ok(_) => {
-                        app.message = Some("Editor exited with error".to_string());
+                        app.warn("Editor exited with error");
                     }
                     Err(e) => {
-                        app.message = Some(format!("Failed to open editor: {}", e));
+                        app.error(format!("Failed to open editor: {}", e));
                     }
                 }
             }
         }
-        KeyCode::Char('u') => {
-            let _ = app.undo();
+        Action::Undo => {
+            let result = app.undo();
+            app.report_error("Undo failed", result);
         }
         _ => {}
     }
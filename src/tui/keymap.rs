@@ -0,0 +1,634 @@
+//! Configurable keybindings for the TUI normal mode.
+//!
+//! Bindings start from a preset (`default`, `vim` or `emacs`) and can be
+//! overridden per action in `[tui.keys]`. The help popup and footer hints are
+//! generated from the resulting keymap, so custom bindings stay documented.
+//!
+//! A binding can be a sequence of keys written with spaces (`"g g"`), which
+//! the vim preset uses for `gg` and `dd`.
+
+use crate::config::{KeyPreset, KeySettings};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+
+/// Group an action is listed under in the help popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionGroup {
+    Navigation,
+    Actions,
+    General,
+}
+
+impl ActionGroup {
+    pub fn all() -> [ActionGroup; 3] {
+        [
+            ActionGroup::Navigation,
+            ActionGroup::Actions,
+            ActionGroup::General,
+        ]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ActionGroup::Navigation => "Navigation",
+            ActionGroup::Actions => "Actions",
+            ActionGroup::General => "General",
+        }
+    }
+}
+
+/// A normal-mode action that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveDown,
+    MoveUp,
+    NextPage,
+    PrevPage,
+    First,
+    Last,
+    Open,
+    Search,
+    SwitchView,
+    SplitView,
+    Create,
+    Status,
+    Type,
+    Priority,
+    Parent,
+    Blocking,
+    Select,
+    Bulk,
+    Archive,
+    ToggleArchived,
+    Delete,
+    Edit,
+    CopyId,
+    Refresh,
+    Undo,
+    Messages,
+    Help,
+    Quit,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::MoveDown,
+            Action::MoveUp,
+            Action::NextPage,
+            Action::PrevPage,
+            Action::First,
+            Action::Last,
+            Action::Open,
+            Action::Search,
+            Action::SwitchView,
+            Action::SplitView,
+            Action::Create,
+            Action::Status,
+            Action::Type,
+            Action::Priority,
+            Action::Parent,
+            Action::Blocking,
+            Action::Select,
+            Action::Bulk,
+            Action::Archive,
+            Action::ToggleArchived,
+            Action::Delete,
+            Action::Edit,
+            Action::CopyId,
+            Action::Refresh,
+            Action::Undo,
+            Action::Messages,
+            Action::Help,
+            Action::Quit,
+        ]
+    }
+
+    /// Name used in `[tui.keys]`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::MoveDown => "move_down",
+            Action::MoveUp => "move_up",
+            Action::NextPage => "next_page",
+            Action::PrevPage => "prev_page",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::Open => "open",
+            Action::Search => "search",
+            Action::SwitchView => "switch_view",
+            Action::SplitView => "split_view",
+            Action::Create => "create",
+            Action::Status => "status",
+            Action::Type => "type",
+            Action::Priority => "priority",
+            Action::Parent => "parent",
+            Action::Blocking => "blocking",
+            Action::Select => "select",
+            Action::Bulk => "bulk",
+            Action::Archive => "archive",
+            Action::ToggleArchived => "toggle_archived",
+            Action::Delete => "delete",
+            Action::Edit => "edit",
+            Action::CopyId => "copy_id",
+            Action::Refresh => "refresh",
+            Action::Undo => "undo",
+            Action::Messages => "messages",
+            Action::Help => "help",
+            Action::Quit => "quit",
+        }
+    }
+
+    /// Description shown in the help popup
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::MoveDown => "Move down",
+            Action::MoveUp => "Move up",
+            Action::NextPage => "Next page",
+            Action::PrevPage => "Previous page",
+            Action::First => "First item",
+            Action::Last => "Last item",
+            Action::Open => "Open detail view",
+            Action::Search => "Search (tag: status: type:, Ctrl+R regex, Ctrl+F fuzzy)",
+            Action::SwitchView => "Switch between Tickets/Memory",
+            Action::SplitView => "Toggle list + preview split view",
+            Action::Create => "Create new ticket",
+            Action::Status => "Change status",
+            Action::Type => "Change type",
+            Action::Priority => "Change priority",
+            Action::Parent => "Set parent",
+            Action::Blocking => "Set blocking tickets",
+            Action::Select => "Toggle multi-select",
+            Action::Bulk => "Bulk operations on selection",
+            Action::Archive => "Archive ticket(s)",
+            Action::ToggleArchived => "Show/hide archived tickets",
+            Action::Delete => "Delete ticket",
+            Action::Edit => "Edit in $EDITOR",
+            Action::CopyId => "Copy ticket ID",
+            Action::Refresh => "Refresh list",
+            Action::Undo => "Undo last operation (multi-level)",
+            Action::Messages => "Show recent messages",
+            Action::Help => "Toggle help",
+            Action::Quit => "Quit",
+        }
+    }
+
+    pub fn group(&self) -> ActionGroup {
+        match self {
+            Action::MoveDown
+            | Action::MoveUp
+            | Action::NextPage
+            | Action::PrevPage
+            | Action::First
+            | Action::Last
+            | Action::Open
+            | Action::Search
+            | Action::SwitchView
+            | Action::SplitView => ActionGroup::Navigation,
+            Action::Help | Action::Quit => ActionGroup::General,
+            _ => ActionGroup::Actions,
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::all().iter().copied().find(|a| a.name() == name)
+    }
+
+    /// Default keys for this action in the given preset
+    fn preset_keys(&self, preset: KeyPreset) -> &'static [&'static str] {
+        match (preset, self) {
+            (KeyPreset::Vim, Action::NextPage) => &["ctrl+f", "pagedown", "J"],
+            (KeyPreset::Vim, Action::PrevPage) => &["ctrl+b", "pageup", "K"],
+            (KeyPreset::Vim, Action::MoveDown) => &["j", "down"],
+            (KeyPreset::Vim, Action::MoveUp) => &["k", "up"],
+            (KeyPreset::Vim, Action::First) => &["g g", "home"],
+            (KeyPreset::Vim, Action::Delete) => &["d d"],
+            (KeyPreset::Vim, Action::Last) => &["G", "end"],
+            (KeyPreset::Emacs, Action::MoveDown) => &["ctrl+n", "down"],
+            (KeyPreset::Emacs, Action::MoveUp) => &["ctrl+p", "up"],
+            (KeyPreset::Emacs, Action::NextPage) => &["ctrl+v", "pagedown", "right"],
+            (KeyPreset::Emacs, Action::PrevPage) => &["alt+v", "pageup", "left"],
+            (KeyPreset::Emacs, Action::First) => &["alt+<", "home"],
+            (KeyPreset::Emacs, Action::Last) => &["alt+>", "end"],
+            (KeyPreset::Emacs, Action::Search) => &["ctrl+s", "/"],
+            (KeyPreset::Emacs, Action::Quit) => &["ctrl+x", "q"],
+            (_, Action::MoveDown) => &["down", "j"],
+            (_, Action::MoveUp) => &["up", "k"],
+            (_, Action::NextPage) => &["right", "pagedown", "J"],
+            (_, Action::PrevPage) => &["left", "pageup", "K"],
+            (_, Action::First) => &["home", "g"],
+            (_, Action::Last) => &["end", "G"],
+            (_, Action::Open) => &["enter"],
+            (_, Action::Search) => &["/"],
+            (_, Action::SwitchView) => &["tab"],
+            (_, Action::SplitView) => &["v"],
+            (_, Action::Create) => &["c"],
+            (_, Action::Status) => &["s"],
+            (_, Action::Type) => &["t"],
+            (_, Action::Priority) => &["P"],
+            (_, Action::Parent) => &["p"],
+            (_, Action::Blocking) => &["b"],
+            (_, Action::Select) => &["space"],
+            (_, Action::Bulk) => &["B"],
+            (_, Action::Archive) => &["a"],
+            (_, Action::ToggleArchived) => &["A"],
+            (_, Action::Delete) => &["d"],
+            (_, Action::Edit) => &["e"],
+            (_, Action::CopyId) => &["y"],
+            (_, Action::Refresh) => &["r"],
+            (_, Action::Undo) => &["u"],
+            (_, Action::Messages) => &["m"],
+            (_, Action::Help) => &["?"],
+            (_, Action::Quit) => &["q"],
+        }
+    }
+}
+
+/// A single key with modifiers, e.g. `ctrl+d` or `G`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Parse a key description like `j`, `G`, `ctrl+d`, `alt+v`, `pagedown` or `space`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // Split off modifier prefixes, but keep a lone "+" as a key
+        while let Some((prefix, tail)) = rest.split_once('+')
+            && !tail.is_empty()
+        {
+            match prefix.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                _ => return Err(format!("Unknown modifier '{}' in key '{}'", prefix, s)),
+            }
+            rest = tail;
+        }
+
+        let code = match rest.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "delete" | "del" => KeyCode::Delete,
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("Unknown key '{}'", s)),
+                }
+            }
+        };
+
+        Ok(Self { code, modifiers })
+    }
+
+    /// Whether a key event triggers this binding
+    ///
+    /// Shift is implied by the character itself (`G` vs `g`), so only
+    /// Ctrl and Alt are compared.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let relevant = KeyModifiers::CONTROL | KeyModifiers::ALT;
+        key.code == self.code && (key.modifiers & relevant) == self.modifiers
+    }
+}
+
+impl From<&KeyEvent> for KeyBinding {
+    fn from(key: &KeyEvent) -> Self {
+        Self {
+            code: key.code,
+            modifiers: key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.is_empty() => write!(f, "{}", c),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Backspace => write!(f, "Bksp"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Delete => write!(f, "Del"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// One or more keys pressed in order, e.g. `G` or `g g`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySequence(pub Vec<KeyBinding>);
+
+impl KeySequence {
+    /// Parse a whitespace-separated key sequence like `g g` or `ctrl+w j`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let keys = s
+            .split_whitespace()
+            .map(KeyBinding::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err("Empty key binding".to_string());
+        }
+        Ok(Self(keys))
+    }
+
+    /// Whether `pending` followed by `key` completes this sequence
+    fn completed_by(&self, pending: &[KeyEvent], key: &KeyEvent) -> bool {
+        self.0.len() == pending.len() + 1 && self.starts_with(pending, key)
+    }
+
+    /// Whether `pending` followed by `key` is a proper prefix of this sequence
+    fn continued_by(&self, pending: &[KeyEvent], key: &KeyEvent) -> bool {
+        self.0.len() > pending.len() + 1 && self.starts_with(pending, key)
+    }
+
+    fn starts_with(&self, pending: &[KeyEvent], key: &KeyEvent) -> bool {
+        pending
+            .iter()
+            .chain(std::iter::once(key))
+            .zip(&self.0)
+            .all(|(event, binding)| binding.matches(event))
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<String> = self.0.iter().map(|k| k.to_string()).collect();
+        // Plain letters read naturally run together (`gg`), everything else gets a comma
+        let separator = if keys.iter().all(|k| k.chars().count() == 1) {
+            ""
+        } else {
+            ","
+        };
+        write!(f, "{}", keys.join(separator))
+    }
+}
+
+/// Outcome of feeding a key press to the keymap
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyResolution {
+    /// The keys so far complete a binding
+    Action(Action),
+    /// The keys so far are the start of a longer binding
+    Pending,
+    /// No binding matches
+    Unbound,
+}
+
+/// Resolved keybindings for normal mode
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Action, Vec<KeySequence>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::from_preset(KeyPreset::Default)
+    }
+}
+
+impl Keymap {
+    /// Build the keymap for a preset without overrides
+    pub fn from_preset(preset: KeyPreset) -> Self {
+        let bindings = Action::all()
+            .iter()
+            .map(|action| {
+                let keys = action
+                    .preset_keys(preset)
+                    .iter()
+                    .map(|k| KeySequence::parse(k).expect("preset keys are valid"))
+                    .collect();
+                (*action, keys)
+            })
+            .collect();
+        Self { bindings }
+    }
+
+    /// Build the keymap from `[tui.keys]`
+    ///
+    /// An override replaces the preset's keys for that action and takes
+    /// those keys away from any other action, so a key never does two things.
+    pub fn from_settings(settings: &KeySettings) -> Result<Self, String> {
+        let mut keymap = Self::from_preset(settings.preset);
+        for (name, keys) in &settings.bindings {
+            let action = Action::from_name(name)
+                .ok_or_else(|| format!("Unknown TUI action '{}' in [tui.keys]", name))?;
+            let keys = keys
+                .keys()
+                .into_iter()
+                .map(KeySequence::parse)
+                .collect::<Result<Vec<_>, _>>()?;
+            keymap.bind(action, keys);
+        }
+        Ok(keymap)
+    }
+
+    /// Replace the keys bound to an action
+    pub fn bind(&mut self, action: Action, keys: Vec<KeySequence>) {
+        for (other, other_keys) in &mut self.bindings {
+            if *other != action {
+                other_keys.retain(|k| !keys.contains(k));
+            }
+        }
+        if let Some((_, bound)) = self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            *bound = keys;
+        }
+    }
+
+    /// Feed a key press, given the keys already pending in a sequence
+    ///
+    /// A complete binding wins over a longer one it is a prefix of.
+    pub fn resolve(&self, pending: &[KeyEvent], key: &KeyEvent) -> KeyResolution {
+        let sequences = || {
+            self.bindings
+                .iter()
+                .flat_map(|(a, keys)| keys.iter().map(move |k| (a, k)))
+        };
+        if let Some((action, _)) = sequences().find(|(_, seq)| seq.completed_by(pending, key)) {
+            return KeyResolution::Action(*action);
+        }
+        if sequences().any(|(_, seq)| seq.continued_by(pending, key)) {
+            return KeyResolution::Pending;
+        }
+        KeyResolution::Unbound
+    }
+
+    /// Look up the action for a single key press
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        match self.resolve(&[], key) {
+            KeyResolution::Action(action) => Some(action),
+            _ => None,
+        }
+    }
+
+    /// Keys bound to an action
+    pub fn keys(&self, action: Action) -> &[KeySequence] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// All bound keys for an action joined for display, e.g. `↓ j`
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|k| k.to_string()).collect();
+        if keys.is_empty() {
+            "unbound".to_string()
+        } else {
+            keys.join(" ")
+        }
+    }
+
+    /// Primary key for an action, used in compact hints
+    pub fn primary(&self, action: Action) -> Option<String> {
+        self.keys(action).first().map(|k| k.to_string())
+    }
+
+    /// Actions in the given help group, in display order
+    pub fn group(&self, group: ActionGroup) -> impl Iterator<Item = (Action, String)> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |(action, _)| action.group() == group)
+            .map(|(action, _)| (*action, self.label(*action)))
+    }
+
+    /// Footer hints for normal mode, e.g. ` ↓/↑:nav  /:search  q:quit `
+    pub fn footer_hints(&self, hints: &[(&[Action], &str)]) -> String {
+        let parts: Vec<String> = hints
+            .iter()
+            .filter_map(|(actions, label)| {
+                let keys: Vec<String> = actions.iter().filter_map(|a| self.primary(*a)).collect();
+                (!keys.is_empty()).then(|| format!("{}:{}", keys.join("/"), label))
+            })
+            .collect();
+        format!(" {} ", parts.join("  "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KeyList;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_key_bindings() {
+        let b = KeyBinding::parse("ctrl+d").unwrap();
+        assert_eq!(b.code, KeyCode::Char('d'));
+        assert_eq!(b.modifiers, KeyModifiers::CONTROL);
+
+        assert_eq!(KeyBinding::parse("G").unwrap().code, KeyCode::Char('G'));
+        assert_eq!(
+            KeyBinding::parse("pagedown").unwrap().code,
+            KeyCode::PageDown
+        );
+        assert_eq!(KeyBinding::parse("+").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(KeyBinding::parse("alt+<").unwrap().code, KeyCode::Char('<'));
+        assert!(KeyBinding::parse("hyper+x").is_err());
+        assert!(KeyBinding::parse("nope").is_err());
+    }
+
+    #[test]
+    fn test_shift_is_implied_by_char() {
+        let keymap = Keymap::default();
+        let shifted = key(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(keymap.action_for(&shifted), Some(Action::Last));
+        let ctrl_g = key(KeyCode::Char('g'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.action_for(&ctrl_g), None);
+    }
+
+    #[test]
+    fn test_presets() {
+        let vim = Keymap::from_preset(KeyPreset::Vim);
+        let ctrl_f = key(KeyCode::Char('f'), KeyModifiers::CONTROL);
+        assert_eq!(vim.action_for(&ctrl_f), Some(Action::NextPage));
+
+        let emacs = Keymap::from_preset(KeyPreset::Emacs);
+        let ctrl_n = key(KeyCode::Char('n'), KeyModifiers::CONTROL);
+        assert_eq!(emacs.action_for(&ctrl_n), Some(Action::MoveDown));
+        assert_eq!(emacs.label(Action::Search), "Ctrl+S /");
+    }
+
+    #[test]
+    fn test_overrides_steal_keys() {
+        let mut settings = KeySettings::default();
+        settings
+            .bindings
+            .insert("archive".to_string(), KeyList::One("d".to_string()));
+        settings.bindings.insert(
+            "quit".to_string(),
+            KeyList::Many(vec!["q".to_string(), "ctrl+c".to_string()]),
+        );
+        let keymap = Keymap::from_settings(&settings).unwrap();
+
+        let d = key(KeyCode::Char('d'), KeyModifiers::NONE);
+        assert_eq!(keymap.action_for(&d), Some(Action::Archive));
+        assert_eq!(keymap.label(Action::Delete), "unbound");
+        assert_eq!(keymap.label(Action::Quit), "q Ctrl+C");
+    }
+
+    #[test]
+    fn test_key_sequences() {
+        let vim = Keymap::from_preset(KeyPreset::Vim);
+        let g = key(KeyCode::Char('g'), KeyModifiers::NONE);
+        let d = key(KeyCode::Char('d'), KeyModifiers::NONE);
+
+        assert_eq!(vim.resolve(&[], &g), KeyResolution::Pending);
+        assert_eq!(vim.resolve(&[g], &g), KeyResolution::Action(Action::First));
+        assert_eq!(vim.resolve(&[d], &d), KeyResolution::Action(Action::Delete));
+        assert_eq!(vim.resolve(&[g], &d), KeyResolution::Unbound);
+        assert_eq!(vim.label(Action::First), "gg Home");
+
+        let parsed = KeySequence::parse("ctrl+w j").unwrap();
+        assert_eq!(parsed.to_string(), "Ctrl+W,j");
+        assert!(KeySequence::parse("  ").is_err());
+    }
+
+    #[test]
+    fn test_unknown_action_rejected() {
+        let mut settings = KeySettings::default();
+        settings
+            .bindings
+            .insert("launch_rockets".to_string(), KeyList::One("x".to_string()));
+        assert!(Keymap::from_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn test_footer_hints_follow_bindings() {
+        let keymap = Keymap::from_preset(KeyPreset::Emacs);
+        let hints = keymap.footer_hints(&[(&[Action::MoveDown, Action::MoveUp], "nav")]);
+        assert_eq!(hints, " Ctrl+N/Ctrl+P:nav ");
+    }
+}
@@ -6,29 +6,41 @@
 //!
 //! ```bash
 //! peas tui
+//! peas tui --select peas-abc12   # start in the detail view of a ticket
+//! peas tui --filter "status:in-progress" --view split
 //! ```
 //!
 //! ## Keybindings
 //!
 //! - `↑/↓`: Navigate up/down
 //! - `←/→`: Previous/next page
+//! - `5j`/`10k`: Count prefix repeats a motion (`12G` jumps to the 12th item)
 //! - `Tab`: Switch between Tickets/Memory views
-//! - `/`: Search
+//! - `/`: Search (`tag:ui`, `status:todo`, `type:bug`; `Ctrl+R` toggles regex)
 //! - `Enter`: Open detail view
+//! - `v`: Toggle split view with preview
+//! - `Backspace`: Back to previous ticket in detail view
 //! - `Space`: Multi-select toggle
+//! - `B`: Bulk operations on selection
 //! - `c`: Create new ticket
 //! - `s`: Change status
 //! - `t`: Change type
 //! - `P`: Change priority
 //! - `e`: Edit in $EDITOR
+//! - `a`: Archive ticket(s)
+//! - `A`: Show/hide archived tickets
 //! - `r`: Refresh
 //! - `u`: Undo last operation
+//! - `m`: Recent messages
 //! - `?`: Help
 //! - `q`: Quit
+//!
+//! Normal-mode keys can be rebound in `[tui.keys]` (see [`keymap`]).
 
 pub mod app;
 mod body_editor;
 mod handlers;
+pub mod keymap;
 mod modal_operations;
 mod relations;
 pub mod theme;
@@ -39,4 +51,5 @@ mod ui_utils;
 mod ui_views;
 mod url_utils;
 
-pub use app::run_tui;
+pub use app::{TuiOptions, run_tui};
+pub use ui_utils::markdown_ansi;
@@ -2,17 +2,106 @@ use crate::{
     error::Result,
     model::{Pea, PeaPriority, PeaStatus, PeaType},
     storage::PeaRepository,
-    undo::UndoManager,
+    undo::UndoOperation,
 };
-use std::path::Path;
+
+/// A change applied to all targeted tickets from the bulk operations palette
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BulkChange {
+    Status(PeaStatus),
+    Priority(PeaPriority),
+    Type(PeaType),
+    AddTags(Vec<String>),
+    RemoveTags(Vec<String>),
+    Parent(Option<String>),
+    Archive,
+    Delete,
+}
+
+impl BulkChange {
+    /// Short human-readable description of the change
+    pub fn describe(&self) -> String {
+        match self {
+            BulkChange::Status(s) => format!("status -> {}", s),
+            BulkChange::Priority(p) => format!("priority -> {}", p),
+            BulkChange::Type(t) => format!("type -> {}", t),
+            BulkChange::AddTags(tags) => format!("add tags {}", tags.join(", ")),
+            BulkChange::RemoveTags(tags) => format!("remove tags {}", tags.join(", ")),
+            BulkChange::Parent(Some(p)) => format!("parent -> {}", p),
+            BulkChange::Parent(None) => "parent -> (none)".to_string(),
+            BulkChange::Archive => "archive".to_string(),
+            BulkChange::Delete => "delete".to_string(),
+        }
+    }
+}
+
+/// Tags from `known` that complete the entry being typed, i.e. the text
+/// after the last comma in `input`
+///
+/// Matching is case-insensitive; tags already entered are left out.
+pub fn tag_completions<'a>(input: &str, known: &[&'a str]) -> Vec<&'a str> {
+    let (entered, current) = match input.rfind(',') {
+        Some(i) => (&input[..i], input[i + 1..].trim_start()),
+        None => ("", input),
+    };
+    let entered: Vec<&str> = entered.split(',').map(str::trim).collect();
+    let current = current.to_lowercase();
+    known
+        .iter()
+        .copied()
+        .filter(|t| t.to_lowercase().starts_with(&current) && !entered.contains(t))
+        .collect()
+}
+
+/// Complete the entry being typed in a comma-separated tags input
+///
+/// A single match is completed in full and followed by a separator; several
+/// matches extend the entry to their longest common prefix.
+pub fn complete_tags_input(input: &str, known: &[&str]) -> Option<String> {
+    let matches = tag_completions(input, known);
+    let completion = match matches.as_slice() {
+        [] => return None,
+        [only] => format!("{}, ", only),
+        [first, rest @ ..] => {
+            let mut prefix: Vec<char> = first.chars().collect();
+            for tag in rest {
+                let common = prefix
+                    .iter()
+                    .zip(tag.chars())
+                    .take_while(|(a, b)| **a == *b);
+                prefix.truncate(common.count());
+            }
+            prefix.into_iter().collect()
+        }
+    };
+    let (head, current) = match input.rfind(',') {
+        Some(i) => (format!("{}, ", &input[..i]), input[i + 1..].trim_start()),
+        None => (String::new(), input),
+    };
+    // Matches that differ only in case can share less than what was typed
+    if completion.chars().count() < current.chars().count() {
+        return None;
+    }
+    Some(head + completion.as_str())
+}
 
 /// Generic function to apply a property change to multiple tickets
+/// Write an edited ticket, reopening it when a closed ticket is moved back
+/// to an open status (allowed even when closed tickets are locked)
+fn save(repo: &PeaRepository, was_closed: bool, pea: &mut Pea) -> Result<()> {
+    if was_closed && pea.is_open() {
+        repo.reopen(pea)?;
+    } else {
+        repo.update(pea)?;
+    }
+    Ok(())
+}
+
 fn apply_property_change<T, F>(
     target_ids: &[String],
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
-    _property_name: &str,
+    property_name: &str,
     new_value: T,
     mut update_fn: F,
 ) -> Result<String>
@@ -21,22 +110,38 @@ where
     F: FnMut(&mut Pea, T),
 {
     let count = target_ids.len();
-    let undo_manager = UndoManager::new(data_path);
-
-    for (i, id) in target_ids.iter().enumerate() {
-        if let Some(pea) = all_peas.iter().find(|p| p.id == *id).cloned() {
-            // Record undo for the last item (will be what gets undone)
-            if i == count - 1
-                && let Ok(path) = repo.find_file_by_id(&pea.id)
-            {
-                let _ = crate::undo::record_update(&undo_manager, &pea.id, &path);
+    let mut operations = Vec::new();
+    let mut apply = || -> Result<()> {
+        for id in target_ids {
+            if let Some(pea) = all_peas.iter().find(|p| p.id == *id).cloned() {
+                let path = repo.find_file_by_id(&pea.id)?;
+                let operation = UndoOperation::update_from_file(&pea.id, &path)?;
+                let was_closed = pea.is_closed();
+                let mut updated = pea;
+                update_fn(&mut updated, new_value);
+                // NOTE: No touch() call - update() handles it internally now
+                save(repo, was_closed, &mut updated)?;
+                operations.push(operation);
             }
-            let mut updated = pea;
-            update_fn(&mut updated, new_value);
-            // NOTE: No touch() call - update() handles it internally now
-            repo.update(&mut updated)?;
         }
-    }
+        Ok(())
+    };
+    let result = apply();
+
+    // All tickets are undone together, including those applied before a failure
+    let undo_manager = repo.undo_manager();
+    let _ = if operations.len() == 1 {
+        undo_manager.record(operations.remove(0))
+    } else {
+        let description = format!(
+            "Set {} to {} on {} tickets",
+            property_name,
+            new_value,
+            operations.len()
+        );
+        crate::undo::record_batch(&undo_manager, &description, operations)
+    };
+    result?;
 
     let message = if count > 1 {
         format!("{} tickets -> {}", count, new_value)
@@ -54,14 +159,12 @@ pub fn apply_status_change(
     target_ids: &[String],
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
     new_status: PeaStatus,
 ) -> Result<String> {
     apply_property_change(
         target_ids,
         all_peas,
         repo,
-        data_path,
         "status",
         new_status,
         |pea, status| pea.status = status,
@@ -73,14 +176,12 @@ pub fn apply_priority_change(
     target_ids: &[String],
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
     new_priority: PeaPriority,
 ) -> Result<String> {
     apply_property_change(
         target_ids,
         all_peas,
         repo,
-        data_path,
         "priority",
         new_priority,
         |pea, priority| pea.priority = priority,
@@ -92,14 +193,12 @@ pub fn apply_type_change(
     target_ids: &[String],
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
     new_type: PeaType,
 ) -> Result<String> {
     apply_property_change(
         target_ids,
         all_peas,
         repo,
-        data_path,
         "type",
         new_type,
         |pea, pea_type| pea.pea_type = pea_type,
@@ -111,10 +210,9 @@ pub fn apply_parent_change(
     ticket_id: &str,
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
     new_parent: Option<String>,
 ) -> Result<String> {
-    let undo_manager = UndoManager::new(data_path);
+    let undo_manager = repo.undo_manager();
 
     if let Some(pea) = all_peas.iter().find(|p| p.id == ticket_id).cloned() {
         if let Ok(path) = repo.find_file_by_id(&pea.id) {
@@ -138,10 +236,9 @@ pub fn apply_blocking_change(
     ticket_id: &str,
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
     new_blocking: Vec<String>,
 ) -> Result<String> {
-    let undo_manager = UndoManager::new(data_path);
+    let undo_manager = repo.undo_manager();
 
     if let Some(pea) = all_peas.iter().find(|p| p.id == ticket_id).cloned() {
         if let Ok(path) = repo.find_file_by_id(&pea.id) {
@@ -165,10 +262,9 @@ pub fn apply_tags_change(
     ticket_id: &str,
     all_peas: &[Pea],
     repo: &PeaRepository,
-    data_path: &Path,
     new_tags: Vec<String>,
 ) -> Result<()> {
-    let undo_manager = UndoManager::new(data_path);
+    let undo_manager = repo.undo_manager();
 
     if let Some(pea) = all_peas.iter().find(|p| p.id == ticket_id).cloned() {
         if let Ok(path) = repo.find_file_by_id(&pea.id) {
@@ -183,3 +279,84 @@ pub fn apply_tags_change(
 
     Ok(())
 }
+
+/// Apply a bulk change to all target tickets, recorded as a single undo entry
+pub fn apply_bulk_change(
+    target_ids: &[String],
+    all_peas: &[Pea],
+    repo: &PeaRepository,
+    change: &BulkChange,
+) -> Result<String> {
+    let mut operations = Vec::new();
+    let result = apply_bulk_change_inner(target_ids, all_peas, repo, change, &mut operations);
+
+    // Record whatever was applied, even if a later ticket failed
+    let count = operations.len();
+    let description = format!("Bulk {} on {} tickets", change.describe(), count);
+    let undo_manager = repo.undo_manager();
+    let _ = crate::undo::record_batch(&undo_manager, &description, operations);
+
+    result?;
+    Ok(format!("{} tickets: {}", count, change.describe()))
+}
+
+fn apply_bulk_change_inner(
+    target_ids: &[String],
+    all_peas: &[Pea],
+    repo: &PeaRepository,
+    change: &BulkChange,
+    operations: &mut Vec<UndoOperation>,
+) -> Result<()> {
+    for id in target_ids {
+        let Some(pea) = all_peas.iter().find(|p| p.id == *id).cloned() else {
+            continue;
+        };
+        // A ticket can't become its own parent
+        if let BulkChange::Parent(Some(parent)) = change
+            && parent == id
+        {
+            continue;
+        }
+
+        let path = repo.find_file_by_id(id)?;
+        match change {
+            BulkChange::Archive => {
+                let archive_path = repo.archive(id)?;
+                operations.push(UndoOperation::Archive {
+                    id: id.clone(),
+                    original_path: path,
+                    archive_path,
+                });
+            }
+            BulkChange::Delete => {
+                let op = UndoOperation::delete_from_file(id, &path)?;
+                repo.delete(id)?;
+                operations.push(op);
+            }
+            _ => {
+                let op = UndoOperation::update_from_file(id, &path)?;
+                let was_closed = pea.is_closed();
+                let mut updated = pea;
+                match change {
+                    BulkChange::Status(s) => updated.status = *s,
+                    BulkChange::Priority(p) => updated.priority = *p,
+                    BulkChange::Type(t) => updated.pea_type = *t,
+                    BulkChange::AddTags(tags) => {
+                        for tag in tags {
+                            if !updated.tags.contains(tag) {
+                                updated.tags.push(tag.clone());
+                            }
+                        }
+                    }
+                    BulkChange::RemoveTags(tags) => updated.tags.retain(|t| !tags.contains(t)),
+                    BulkChange::Parent(parent) => updated.parent = parent.clone(),
+                    BulkChange::Archive | BulkChange::Delete => unreachable!(),
+                }
+                // NOTE: No touch() call - update() handles it internally now
+                save(repo, was_closed, &mut updated)?;
+                operations.push(op);
+            }
+        }
+    }
+    Ok(())
+}
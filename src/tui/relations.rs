@@ -1,4 +1,7 @@
-use crate::model::{Pea, PeaType};
+use crate::model::{Pea, PeaType, References, related};
+
+/// Most related peas listed after the explicit relationships
+const RELATED_LIMIT: usize = 5;
 
 /// A relationship item for display (relationship type, id, title, pea_type)
 pub type RelationItem = (String, String, String, PeaType);
@@ -59,5 +62,36 @@ pub fn build_relations(pea: &Pea, all_peas: &[Pea]) -> Vec<RelationItem> {
         ));
     }
 
+    // Add backlinks (peas mentioning this one in their body)
+    let references = References::build(all_peas);
+    for id in references.referenced_by(&pea.id) {
+        if let Some(source) = all_peas.iter().find(|p| p.id == *id) {
+            relations_items.push((
+                "ReferencedBy".to_string(),
+                source.id.clone(),
+                source.title.clone(),
+                source.pea_type,
+            ));
+        }
+    }
+
+    // Add related peas (shared tags, similar title, siblings) not listed yet
+    let listed: Vec<String> = relations_items
+        .iter()
+        .map(|(_, id, _, _)| id.clone())
+        .collect();
+    for found in related(pea, all_peas)
+        .into_iter()
+        .filter(|r| !listed.contains(&r.pea.id))
+        .take(RELATED_LIMIT)
+    {
+        relations_items.push((
+            "Related".to_string(),
+            found.pea.id.clone(),
+            found.pea.title.clone(),
+            found.pea.pea_type,
+        ));
+    }
+
     relations_items
 }
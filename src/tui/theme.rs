@@ -84,11 +84,17 @@ pub struct Theme {
     // Multi-select
     pub multi_select: Color,
 
+    // Read markers
+    pub unread_new: Color,
+    pub unread_changed: Color,
+
     // Tree lines
     pub tree_lines: Color,
 
     // Message
     pub message: Color,
+    pub message_warning: Color,
+    pub message_error: Color,
 
     // Modal UI elements
     pub modal_cursor: Color,
@@ -98,6 +104,20 @@ pub struct Theme {
     pub help_key: Color,
     pub help_border: Color,
 
+    // Body editor markdown highlighting
+    pub md_heading: Color,
+    pub md_emphasis: Color,
+    pub md_code: Color,
+    pub md_link: Color,
+    pub md_list_marker: Color,
+    pub md_quote: Color,
+    pub line_number: Color,
+
+    // Diff view
+    pub diff_added: Color,
+    pub diff_removed: Color,
+    pub diff_hunk: Color,
+
     // Type emojis
     pub emoji_milestone: &'static str,
     pub emoji_epic: &'static str,
@@ -202,11 +222,17 @@ impl Default for Theme {
             // Multi-select
             multi_select: Color::Rgb(102, 217, 239), // Blue
 
+            // Read markers
+            unread_new: Color::Rgb(166, 226, 46),     // Green
+            unread_changed: Color::Rgb(253, 151, 31), // Orange
+
             // Tree lines
             tree_lines: Color::Rgb(117, 113, 94), // Muted
 
             // Message
-            message: Color::Rgb(166, 226, 46), // Green
+            message: Color::Rgb(166, 226, 46),          // Green
+            message_warning: Color::Rgb(230, 219, 116), // Yellow
+            message_error: Color::Rgb(249, 38, 114),    // Pink/red
 
             // Modal UI elements
             modal_cursor: Color::Rgb(102, 217, 239), // Blue
@@ -216,6 +242,20 @@ impl Default for Theme {
             help_key: Color::Rgb(102, 217, 239),    // Blue
             help_border: Color::Rgb(230, 219, 116), // Yellow
 
+            // Body editor markdown highlighting
+            md_heading: Color::Rgb(249, 38, 114),     // Pink
+            md_emphasis: Color::Rgb(253, 151, 31),    // Orange
+            md_code: Color::Rgb(230, 219, 116),       // Yellow
+            md_link: Color::Rgb(102, 217, 239),       // Blue
+            md_list_marker: Color::Rgb(166, 226, 46), // Green
+            md_quote: Color::Rgb(117, 113, 94),       // Muted
+            line_number: Color::Rgb(117, 113, 94),    // Muted
+
+            // Diff view
+            diff_added: Color::Rgb(166, 226, 46),   // Green
+            diff_removed: Color::Rgb(249, 38, 114), // Pink/red
+            diff_hunk: Color::Rgb(102, 217, 239),   // Blue
+
             // Type emojis
             emoji_milestone: "🏁",
             emoji_epic: "🌟",
@@ -317,6 +357,8 @@ impl Theme {
             "Blocks" => self.relation_blocks,
             "BlockedBy" => self.relation_blocks, // Same color as Blocks
             "Child" => self.relation_child,
+            "ReferencedBy" => self.text_muted,
+            "Related" => self.text_highlight,
             _ => self.text,
         }
     }
@@ -328,6 +370,8 @@ impl Theme {
             "Blocks" => "→",
             "BlockedBy" => "←", // Opposite direction from Blocks
             "Child" => "↓",
+            "ReferencedBy" => "↩",
+            "Related" => "≈",
             _ => " ",
         }
     }
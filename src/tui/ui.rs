@@ -17,6 +17,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         app.input_mode,
         InputMode::DetailView
             | InputMode::EditBody
+            | InputMode::BodyDiff
             | InputMode::StatusModal
             | InputMode::PriorityModal
             | InputMode::TypeModal
@@ -25,6 +26,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             | InputMode::BlockingModal
             | InputMode::TagsModal
             | InputMode::UrlModal
+            | InputMode::EditConflict
     );
 
     // Draw the base view (either detail or list view)
@@ -58,6 +60,15 @@ pub fn draw(f: &mut Frame, app: &mut App) {
             .split(f.area());
 
         match app.view_mode {
+            super::app::ViewMode::Tickets if app.split_view => {
+                // Split view: tree on the left, live preview on the right
+                let panes = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(chunks[0]);
+                ui_views::draw_tree(f, app, panes[0]);
+                ui_views::draw_preview(f, app, panes[1]);
+            }
             super::app::ViewMode::Tickets => ui_views::draw_tree(f, app, chunks[0]),
             super::app::ViewMode::Memory => ui_views::draw_memory_list(f, app, chunks[0]),
         }
@@ -66,7 +77,7 @@ pub fn draw(f: &mut Frame, app: &mut App) {
 
     // Draw help popup if active (overlays everything)
     if app.show_help {
-        ui_views::draw_help_popup(f);
+        ui_views::draw_help_popup(f, app);
     }
 
     // Draw modal on top of current view (overlays base view)
@@ -78,9 +89,13 @@ pub fn draw(f: &mut Frame, app: &mut App) {
         InputMode::ParentModal => ui_modals::draw_parent_modal(f, app),
         InputMode::BlockingModal => ui_modals::draw_blocking_modal(f, app),
         InputMode::CreateModal => ui_modals::draw_create_modal(f, app),
+        InputMode::BodyDiff => ui_modals::draw_body_diff(f, app),
         InputMode::MemoryCreateModal => ui_modals::draw_memory_create_modal(f, app),
         InputMode::TagsModal => ui_modals::draw_tags_modal(f, app),
         InputMode::UrlModal => ui_modals::draw_url_modal(f, app),
+        InputMode::BulkModal => ui_modals::draw_bulk_modal(f, app),
+        InputMode::MessageLog => ui_modals::draw_message_log(f, app),
+        InputMode::EditConflict => ui_modals::draw_edit_conflict(f, app),
         _ => {}
     }
 }
@@ -1,12 +1,14 @@
-use super::app::App;
+use super::app::{App, BulkAction};
+use super::body_editor::DiffLine;
 use super::theme::{theme, tui_config};
 use super::ui_utils;
+use crate::removal::RemovalKind;
 use ratatui::{
     Frame,
     style::{Modifier, Style},
     symbols::border,
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
 pub fn draw_status_modal(f: &mut Frame, app: &App) {
@@ -97,8 +99,10 @@ pub fn draw_priority_modal(f: &mut Frame, app: &App) {
 }
 
 pub fn draw_delete_confirm(f: &mut Frame, app: &App) {
-    let area = ui_utils::centered_rect(50, 20, f.area());
     let t = theme();
+    let archiving = app.removal_kind == RemovalKind::Archive;
+    let children = app.selected_children().len();
+    let area = ui_utils::centered_rect(50, if children > 0 { 30 } else { 20 }, f.area());
 
     let (question, item_info) = match app.view_mode {
         super::app::ViewMode::Tickets => {
@@ -107,7 +111,12 @@ pub fn draw_delete_confirm(f: &mut Frame, app: &App) {
             } else {
                 "No ticket selected".to_string()
             };
-            ("Are you sure you want to delete this ticket?", pea_info)
+            let question = if archiving {
+                "Are you sure you want to archive this ticket?"
+            } else {
+                "Are you sure you want to delete this ticket?"
+            };
+            (question, pea_info)
         }
         super::app::ViewMode::Memory => {
             let memory_info = if let Some(memory) = app.filtered_memories.get(app.selected_index) {
@@ -119,7 +128,15 @@ pub fn draw_delete_confirm(f: &mut Frame, app: &App) {
         }
     };
 
-    let content = vec![
+    let key = |k: &'static str| {
+        Span::styled(
+            k,
+            Style::default()
+                .fg(t.checkbox_checked)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    let mut content = vec![
         Line::from(""),
         Line::from(Span::styled(
             question,
@@ -128,13 +145,37 @@ pub fn draw_delete_confirm(f: &mut Frame, app: &App) {
         Line::from(""),
         Line::from(Span::styled(item_info, Style::default().fg(t.id))),
         Line::from(""),
-        Line::from(vec![
+    ];
+    if children > 0 {
+        let has_parent = app.selected_pea().is_some_and(|p| p.parent.is_some());
+        content.push(Line::from(Span::styled(
+            format!("It has {} child ticket(s):", children),
+            Style::default().fg(t.text_muted),
+        )));
+        let verb = if archiving { "archive" } else { "delete" };
+        content.push(Line::from(vec![
+            key("c"),
+            Span::raw(format!(" = {} with children", verb)),
+        ]));
+        let mut choices = vec![key("o"), Span::raw(" = orphan children")];
+        if has_parent {
+            choices.push(Span::raw("    "));
+            choices.push(key("p"));
+            choices.push(Span::raw(" = move them up"));
+        }
+        content.push(Line::from(choices));
+        content.push(Line::from(vec![
             Span::styled(
-                "y",
+                "n",
                 Style::default()
-                    .fg(t.checkbox_checked)
+                    .fg(t.modal_border_delete)
                     .add_modifier(Modifier::BOLD),
             ),
+            Span::raw("/Esc = Cancel"),
+        ]));
+    } else {
+        content.push(Line::from(vec![
+            key("y"),
             Span::raw("/Enter = Yes    "),
             Span::styled(
                 "n",
@@ -143,13 +184,18 @@ pub fn draw_delete_confirm(f: &mut Frame, app: &App) {
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw("/Esc = No"),
-        ]),
-    ];
+        ]));
+    }
 
+    let title = if archiving {
+        " Archive Confirmation "
+    } else {
+        " Delete Confirmation "
+    };
     let paragraph = Paragraph::new(content)
         .block(
             Block::default()
-                .title(" Delete Confirmation ")
+                .title(title)
                 .borders(Borders::ALL)
                 .border_set(border::ROUNDED)
                 .border_style(Style::default().fg(t.modal_border_delete)),
@@ -164,6 +210,33 @@ pub fn draw_tags_modal(f: &mut Frame, app: &App) {
     let area = ui_utils::centered_rect(60, 20, f.area());
     let t = theme();
 
+    const MAX_SUGGESTIONS: usize = 8;
+    let completions = app.tags_modal_completions();
+    let suggestions = if completions.is_empty() {
+        Line::from(Span::styled(
+            "  Enter comma-separated tags (e.g., bug, ui, performance)",
+            Style::default().fg(t.text_muted),
+        ))
+    } else {
+        let mut spans = vec![Span::styled("  ", Style::default())];
+        spans.extend(ui_utils::tag_spans(
+            &completions
+                .iter()
+                .take(MAX_SUGGESTIONS)
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>(),
+            &app.tag_colors,
+            t.tags,
+        ));
+        if completions.len() > MAX_SUGGESTIONS {
+            spans.push(Span::styled(
+                format!(" (+{} more)", completions.len() - MAX_SUGGESTIONS),
+                Style::default().fg(t.text_muted),
+            ));
+        }
+        Line::from(spans)
+    };
+
     let content = vec![
         Line::from(""),
         Line::from(vec![
@@ -172,13 +245,10 @@ pub fn draw_tags_modal(f: &mut Frame, app: &App) {
             Span::styled("_", Style::default().fg(t.modal_cursor)),
         ]),
         Line::from(""),
-        Line::from(Span::styled(
-            "  Enter comma-separated tags (e.g., bug, ui, performance)",
-            Style::default().fg(t.text_muted),
-        )),
+        suggestions,
         Line::from(""),
         Line::from(Span::styled(
-            "  Press Enter to save, Esc to cancel",
+            "  Press Tab to complete, Enter to save, Esc to cancel",
             Style::default().fg(t.text_muted),
         )),
     ];
@@ -266,100 +336,148 @@ pub fn draw_url_modal(f: &mut Frame, app: &App) {
 }
 
 pub fn draw_create_modal(f: &mut Frame, app: &App) {
-    let area = ui_utils::centered_rect(50, 25, f.area());
+    let area = ui_utils::centered_rect(60, 50, f.area());
     let t = theme();
 
-    let title_active = app.modal_selection == 0;
-    let type_active = app.modal_selection == 1;
+    // Field label with selection marker and highlight
+    let label = |idx: usize, text: &'static str| -> Vec<Span<'static>> {
+        let active = app.modal_selection == idx;
+        let style = if active {
+            Style::default().fg(t.modal_cursor)
+        } else {
+            Style::default().fg(t.text)
+        };
+        vec![
+            Span::styled(
+                if active { "▶ " } else { "  " },
+                Style::default().fg(t.modal_cursor),
+            ),
+            Span::styled(text, style.add_modifier(Modifier::BOLD)),
+        ]
+    };
 
-    // Build display text for title field
-    let title_display = if app.create_title.is_empty() {
-        Span::styled("Enter title...", Style::default().fg(t.text_muted))
-    } else {
-        Span::raw(app.create_title.clone())
+    // Free-text field value with placeholder and cursor
+    let text_field = |idx: usize, value: &str, placeholder: &'static str| -> Vec<Span<'static>> {
+        let mut spans = vec![if value.is_empty() {
+            Span::styled(placeholder, Style::default().fg(t.text_muted))
+        } else {
+            Span::raw(value.to_string())
+        }];
+        if app.modal_selection == idx {
+            spans.push(Span::styled("_", Style::default().fg(t.modal_cursor)));
+        }
+        spans
     };
 
-    let title_style = if title_active {
-        Style::default().fg(t.modal_cursor)
+    let type_display = if tui_config().use_type_emojis {
+        format!(
+            "< {} {} >",
+            theme().type_emoji(&app.create_type),
+            app.create_type
+        )
     } else {
-        Style::default().fg(t.text)
+        format!("< {} >", app.create_type)
     };
 
-    let type_style = if type_active {
-        Style::default().fg(t.modal_cursor)
-    } else {
-        Style::default().fg(t.text)
+    let parent_display = match &app.create_parent {
+        Some(id) => {
+            let title = app
+                .all_peas
+                .iter()
+                .find(|p| &p.id == id)
+                .map(|p| p.title.as_str())
+                .unwrap_or("");
+            Span::styled(format!("< {} {} >", id, title), Style::default().fg(t.text))
+        }
+        None => Span::styled("< (none) >", Style::default().fg(t.text_muted)),
     };
 
-    let pea_type_color = ui_utils::type_color(&app.create_type);
+    let mut parent_line = label(4, "Parent:   ");
+    parent_line.push(parent_display);
+    if !app.create_parent_query.is_empty() || app.modal_selection == 4 {
+        parent_line.push(Span::styled(
+            format!("  /{}", app.create_parent_query),
+            Style::default().fg(t.text_muted),
+        ));
+        if app.modal_selection == 4 {
+            parent_line.push(Span::styled("_", Style::default().fg(t.modal_cursor)));
+        }
+    }
 
-    let content = vec![
+    let mut lines = vec![
         Line::from(""),
-        Line::from(vec![
-            Span::styled(
-                if title_active { "▶ " } else { "  " },
-                Style::default().fg(t.modal_cursor),
-            ),
-            Span::styled("Title: ", title_style.add_modifier(Modifier::BOLD)),
-            title_display,
-            if title_active {
-                Span::styled("_", Style::default().fg(t.modal_cursor))
-            } else {
-                Span::raw("")
-            },
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled(
-                if type_active { "▶ " } else { "  " },
-                Style::default().fg(t.modal_cursor),
-            ),
-            Span::styled("Type:  ", type_style.add_modifier(Modifier::BOLD)),
-            Span::styled(
-                if tui_config().use_type_emojis {
-                    format!(
-                        "< {} {} >",
-                        theme().type_emoji(&app.create_type),
-                        app.create_type
-                    )
-                } else {
-                    format!("< {} >", app.create_type)
-                },
-                Style::default().fg(pea_type_color),
-            ),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled(
-            "  (use ←/→ to change type)",
-            Style::default().fg(t.text_muted),
-        )),
+        Line::from(
+            [
+                label(0, "Title:    "),
+                text_field(0, &app.create_title, "Enter title..."),
+            ]
+            .concat(),
+        ),
+        Line::from(
+            [
+                label(1, "Type:     "),
+                vec![Span::styled(
+                    type_display,
+                    Style::default().fg(ui_utils::type_color(&app.create_type)),
+                )],
+            ]
+            .concat(),
+        ),
+        Line::from(
+            [
+                label(2, "Priority: "),
+                vec![Span::styled(
+                    format!("< {} >", app.create_priority),
+                    Style::default().fg(ui_utils::priority_color(&app.create_priority)),
+                )],
+            ]
+            .concat(),
+        ),
+        Line::from(
+            [
+                label(3, "Tags:     "),
+                text_field(3, &app.create_tags, "tag1, tag2, ..."),
+            ]
+            .concat(),
+        ),
+        Line::from(parent_line),
     ];
 
-    // Show parent info if current selection would become parent
-    let parent_info = app.selected_pea().and_then(|p| {
-        if matches!(
-            p.pea_type,
-            crate::model::PeaType::Milestone
-                | crate::model::PeaType::Epic
-                | crate::model::PeaType::Story
-                | crate::model::PeaType::Feature
-        ) {
-            Some(format!("  Parent: {} ({})", p.id, p.title))
+    // Later body lines are indented under the first, the cursor ends the last
+    let mut body: Vec<Vec<Span>> = app
+        .create_body
+        .split('\n')
+        .map(|text| vec![Span::raw(text.to_string())])
+        .collect();
+    if app.create_body.is_empty() {
+        body = vec![text_field(5, "", "Short description...")];
+    } else if app.modal_selection == 5
+        && let Some(last) = body.last_mut()
+    {
+        last.push(Span::styled("_", Style::default().fg(t.modal_cursor)));
+    }
+    for (i, spans) in body.into_iter().enumerate() {
+        let start = if i == 0 {
+            label(5, "Body:     ")
         } else {
-            None
-        }
-    });
+            vec![Span::raw(" ".repeat(12))]
+        };
+        lines.push(Line::from([start, spans].concat()));
+    }
 
-    let mut all_content = content;
-    if let Some(info) = parent_info {
-        all_content.push(Line::from(""));
-        all_content.push(Line::from(Span::styled(
-            info,
+    lines.extend([
+        Line::from(""),
+        Line::from(Span::styled(
+            "  ←/→ change value, type to search parents",
             Style::default().fg(t.text_muted),
-        )));
-    }
+        )),
+        Line::from(Span::styled(
+            "  Enter new body line, Ctrl+S create, Ctrl+E create and edit body",
+            Style::default().fg(t.text_muted),
+        )),
+    ]);
 
-    let paragraph = Paragraph::new(all_content).block(
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
         Block::default()
             .title(" Create Ticket ")
             .borders(Borders::ALL)
@@ -677,3 +795,294 @@ pub fn draw_type_modal(f: &mut Frame, app: &App) {
     f.render_widget(Clear, area);
     f.render_widget(list, area);
 }
+
+pub fn draw_bulk_modal(f: &mut Frame, app: &App) {
+    let area = ui_utils::centered_rect(50, 50, f.area());
+    let t = theme();
+    let target_count = app.target_ids().len();
+
+    let block = Block::default()
+        .title(format!(" Bulk: {} tickets ", target_count))
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(Style::default().fg(t.modal_border));
+
+    f.render_widget(Clear, area);
+
+    // Confirmation step
+    if let Some(ref change) = app.bulk_pending {
+        let border_color = if matches!(change, super::app::BulkChange::Delete) {
+            t.modal_border_delete
+        } else {
+            t.modal_border
+        };
+        let content = vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Apply '{}' to {} tickets?", change.describe(), target_count),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(
+                    "y",
+                    Style::default()
+                        .fg(t.checkbox_checked)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("/Enter = Yes    "),
+                Span::styled(
+                    "n",
+                    Style::default()
+                        .fg(t.modal_border_delete)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::raw("/Esc = No"),
+            ]),
+        ];
+        let paragraph = Paragraph::new(content)
+            .wrap(Wrap { trim: false })
+            .block(block.border_style(Style::default().fg(border_color)));
+        f.render_widget(paragraph, area);
+        return;
+    }
+
+    // Tag input step
+    if matches!(
+        app.bulk_action,
+        Some(BulkAction::AddTags) | Some(BulkAction::RemoveTags)
+    ) {
+        let input = if app.bulk_input.is_empty() {
+            Span::styled("tag1, tag2, ...", Style::default().fg(t.text_muted))
+        } else {
+            Span::raw(app.bulk_input.clone())
+        };
+        let content = vec![
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(
+                    "Tags: ",
+                    Style::default()
+                        .fg(t.modal_cursor)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                input,
+                Span::styled("_", Style::default().fg(t.modal_cursor)),
+            ]),
+        ];
+        f.render_widget(Paragraph::new(content).block(block), area);
+        return;
+    }
+
+    // List step: either the action palette or the values for the chosen action
+    let labels: Vec<String> = match app.bulk_action {
+        None => BulkAction::all()
+            .iter()
+            .map(|a| a.label().to_string())
+            .collect(),
+        Some(BulkAction::Status) => App::status_options()
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+        Some(BulkAction::Priority) => App::priority_options()
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+        Some(BulkAction::Type) => App::type_options()
+            .iter()
+            .map(|ty| ty.to_string())
+            .collect(),
+        Some(BulkAction::Parent) => std::iter::once("(none)".to_string())
+            .chain(
+                app.parent_candidates
+                    .iter()
+                    .map(|p| format!("{} {}", p.id, p.title)),
+            )
+            .collect(),
+        Some(_) => Vec::new(),
+    };
+
+    let items: Vec<ListItem> = labels
+        .into_iter()
+        .enumerate()
+        .map(|(idx, label)| {
+            let is_selected = idx == app.modal_selection;
+            let selection_indicator = if is_selected {
+                Span::styled(theme().row_marker, Style::default().fg(t.modal_cursor))
+            } else {
+                Span::raw(" ")
+            };
+            let style = if is_selected {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                selection_indicator,
+                Span::styled(label, style),
+            ]))
+        })
+        .collect();
+
+    f.render_widget(List::new(items).block(block), area);
+}
+
+pub fn draw_message_log(f: &mut Frame, app: &App) {
+    let area = ui_utils::centered_rect(70, 50, f.area());
+    let t = theme();
+
+    // Newest first
+    let items: Vec<ListItem> = if app.message_log.is_empty() {
+        vec![ListItem::new(Span::styled(
+            "No messages",
+            Style::default().fg(t.text_muted),
+        ))]
+    } else {
+        app.message_log
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(idx, msg)| {
+                let selection_indicator = if idx == app.modal_selection {
+                    Span::styled(theme().row_marker, Style::default().fg(t.modal_cursor))
+                } else {
+                    Span::raw(" ")
+                };
+                ListItem::new(Line::from(vec![
+                    selection_indicator,
+                    Span::styled(
+                        format!("{} ", msg.time.format("%H:%M:%S")),
+                        Style::default().fg(t.text_muted),
+                    ),
+                    Span::styled(msg.text.clone(), ui_utils::message_style(msg.level)),
+                ]))
+            })
+            .collect()
+    };
+
+    let mut state = ratatui::widgets::ListState::default();
+    state.select(Some(app.modal_selection));
+
+    let list = List::new(items).block(
+        Block::default()
+            .title(format!(" Messages ({}) ", app.message_log.len()))
+            .borders(Borders::ALL)
+            .border_set(border::ROUNDED)
+            .border_style(Style::default().fg(t.modal_border)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+pub fn draw_body_diff(f: &mut Frame, app: &App) {
+    let area = ui_utils::centered_rect(80, 70, f.area());
+    let t = theme();
+
+    let lines: Vec<Line> = app
+        .body_diff
+        .iter()
+        .map(|line| match line {
+            DiffLine::Hunk(header) => Line::from(Span::styled(
+                header.clone(),
+                Style::default().fg(t.diff_hunk),
+            )),
+            DiffLine::Context(text) => Line::from(Span::styled(
+                format!(" {}", text),
+                Style::default().fg(t.text_muted),
+            )),
+            DiffLine::Added(text) => Line::from(Span::styled(
+                format!("+{}", text),
+                Style::default().fg(t.diff_added),
+            )),
+            DiffLine::Removed(text) => Line::from(Span::styled(
+                format!("-{}", text),
+                Style::default().fg(t.diff_removed),
+            )),
+        })
+        .collect();
+
+    let diff = Paragraph::new(lines)
+        .scroll((app.body_diff_scroll, 0))
+        .block(
+            Block::default()
+                .title(" Save these changes? (y/n) ")
+                .borders(Borders::ALL)
+                .border_set(border::ROUNDED)
+                .border_style(Style::default().fg(t.modal_border)),
+        );
+
+    f.render_widget(Clear, area);
+    f.render_widget(diff, area);
+}
+
+pub fn draw_edit_conflict(f: &mut Frame, app: &App) {
+    let Some(conflict) = &app.edit_conflict else {
+        return;
+    };
+    let area = ui_utils::centered_rect(60, 40, f.area());
+    let t = theme();
+
+    let summary = match &conflict.theirs {
+        Some(_) => {
+            let fields = conflict.changed_fields();
+            if fields.is_empty() {
+                "The file was rewritten on disk.".to_string()
+            } else {
+                format!("Changed on disk: {}", fields.join(", "))
+            }
+        }
+        None => "The ticket no longer exists on disk.".to_string(),
+    };
+
+    let mut content = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{} - {}", conflict.base.id, conflict.base.title),
+            Style::default().fg(t.id),
+        )),
+        Line::from(Span::styled(
+            summary,
+            Style::default().add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    for (idx, choice) in conflict.choices().iter().enumerate() {
+        let is_selected = idx == conflict.selection;
+        let marker = if is_selected {
+            Span::styled(theme().row_marker, Style::default().fg(t.modal_cursor))
+        } else {
+            Span::raw(" ")
+        };
+        let style = if is_selected {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        content.push(Line::from(vec![
+            marker,
+            Span::raw(" "),
+            Span::styled(
+                choice.key().to_string(),
+                Style::default()
+                    .fg(t.text_highlight)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::raw("  "),
+            Span::styled(choice.label(), style),
+        ]));
+    }
+
+    let paragraph = Paragraph::new(content)
+        .block(
+            Block::default()
+                .title(" Edit Conflict ")
+                .borders(Borders::ALL)
+                .border_set(border::ROUNDED)
+                .border_style(Style::default().fg(t.modal_border_delete)),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
@@ -2,10 +2,13 @@ use crate::model::{Pea, PeaPriority, PeaStatus, PeaType};
 use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
-    text::{Span, Text},
+    text::{Line, Span, Text},
 };
 use ratatui_core;
+use regex::Regex;
+use std::collections::HashMap;
 
+use super::app::MessageLevel;
 use super::theme::theme;
 
 /// Convert ratatui_core::Color to ratatui::Color
@@ -98,41 +101,160 @@ pub fn estimate_wrapped_lines(text: &Text, width: usize) -> u16 {
     total_lines
 }
 
-/// Highlight search term in text by splitting into spans
-pub fn highlight_search<'a>(text: &str, query: &str, base_style: Style) -> Vec<Span<'a>> {
-    if query.is_empty() {
+/// Highlight search matches in text by splitting into spans
+pub fn highlight_search<'a>(
+    text: &str,
+    pattern: Option<&Regex>,
+    base_style: Style,
+) -> Vec<Span<'a>> {
+    let Some(pattern) = pattern else {
         return vec![Span::styled(text.to_string(), base_style)];
-    }
+    };
 
     let t = theme();
-    let lower_text = text.to_lowercase();
-    let lower_query = query.to_lowercase();
     let mut spans = Vec::new();
     let mut last_end = 0;
 
-    for (idx, _) in lower_text.match_indices(&lower_query) {
+    for m in pattern.find_iter(text).filter(|m| !m.is_empty()) {
         // Add text before match
-        if idx > last_end {
-            spans.push(Span::styled(text[last_end..idx].to_string(), base_style));
+        if m.start() > last_end {
+            spans.push(Span::styled(
+                text[last_end..m.start()].to_string(),
+                base_style,
+            ));
         }
         // Add highlighted match
         spans.push(Span::styled(
-            text[idx..idx + query.len()].to_string(),
+            m.as_str().to_string(),
             base_style
                 .fg(t.modal_border_create) // Blue highlight
                 .add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
         ));
-        last_end = idx + query.len();
+        last_end = m.end();
     }
 
     // Add remaining text
-    if last_end < text.len() {
+    if last_end < text.len() || spans.is_empty() {
         spans.push(Span::styled(text[last_end..].to_string(), base_style));
     }
 
     spans
 }
 
+/// Render markdown into lines, optionally highlighting search matches
+pub fn markdown_lines(markdown: &str, highlight: Option<&Regex>) -> Vec<Line<'static>> {
+    let md_text_core = tui_markdown::from_str(markdown);
+
+    // Convert from ratatui_core::Text to ratatui::Text by extracting lines
+    md_text_core
+        .lines
+        .into_iter()
+        .map(|line_core| {
+            let spans: Vec<Span> = line_core
+                .spans
+                .into_iter()
+                .map(|span_core| {
+                    Span::styled(
+                        span_core.content.into_owned(),
+                        convert_style(span_core.style),
+                    )
+                })
+                .collect();
+            let line = Line::from(spans).style(convert_style(line_core.style));
+            match highlight {
+                Some(pattern) => highlight_line(line, pattern),
+                None => line,
+            }
+        })
+        .collect()
+}
+
+/// Render markdown as ANSI-styled text for the terminal, styled like the
+/// detail view (`peas show --render`)
+///
+/// Styles go through `colored`, so they are dropped when output is not a
+/// terminal or `NO_COLOR` is set.
+pub fn markdown_ansi(markdown: &str) -> String {
+    markdown_lines(markdown, None)
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| ansi_span(&span.content, line.style.patch(span.style)))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn ansi_span(text: &str, style: Style) -> String {
+    use colored::Colorize;
+
+    let mut styled = text.normal();
+    if let Some(fg) = style.fg.and_then(ansi_color) {
+        styled = styled.color(fg);
+    }
+    if let Some(bg) = style.bg.and_then(ansi_color) {
+        styled = styled.on_color(bg);
+    }
+    let modifier = style.add_modifier - style.sub_modifier;
+    if modifier.contains(Modifier::BOLD) {
+        styled = styled.bold();
+    }
+    if modifier.contains(Modifier::DIM) {
+        styled = styled.dimmed();
+    }
+    if modifier.contains(Modifier::ITALIC) {
+        styled = styled.italic();
+    }
+    if modifier.contains(Modifier::UNDERLINED) {
+        styled = styled.underline();
+    }
+    if modifier.contains(Modifier::REVERSED) {
+        styled = styled.reversed();
+    }
+    if modifier.contains(Modifier::CROSSED_OUT) {
+        styled = styled.strikethrough();
+    }
+    styled.to_string()
+}
+
+fn ansi_color(color: Color) -> Option<colored::Color> {
+    use colored::Color as Ansi;
+
+    Some(match color {
+        Color::Reset => return None,
+        Color::Black => Ansi::Black,
+        Color::Red => Ansi::Red,
+        Color::Green => Ansi::Green,
+        Color::Yellow => Ansi::Yellow,
+        Color::Blue => Ansi::Blue,
+        Color::Magenta => Ansi::Magenta,
+        Color::Cyan => Ansi::Cyan,
+        Color::Gray => Ansi::White,
+        Color::DarkGray => Ansi::BrightBlack,
+        Color::LightRed => Ansi::BrightRed,
+        Color::LightGreen => Ansi::BrightGreen,
+        Color::LightYellow => Ansi::BrightYellow,
+        Color::LightBlue => Ansi::BrightBlue,
+        Color::LightMagenta => Ansi::BrightMagenta,
+        Color::LightCyan => Ansi::BrightCyan,
+        Color::White => Ansi::BrightWhite,
+        Color::Rgb(r, g, b) => Ansi::TrueColor { r, g, b },
+        Color::Indexed(i) => Ansi::AnsiColor(i),
+    })
+}
+
+/// Highlight search matches within each span of a rendered line
+pub fn highlight_line<'a>(line: Line<'a>, pattern: &Regex) -> Line<'a> {
+    let spans: Vec<Span<'a>> = line
+        .spans
+        .into_iter()
+        .flat_map(|span| highlight_search(&span.content, Some(pattern), span.style))
+        .collect();
+    Line::from(spans).style(line.style)
+}
+
 /// Returns priority indicator and color for a pea
 pub fn priority_indicator(pea: &Pea) -> Option<(String, Color)> {
     theme()
@@ -155,6 +277,38 @@ pub fn type_color(pea_type: &PeaType) -> Color {
     theme().type_color(pea_type)
 }
 
+/// Comma-separated tag spans, using the configured color per tag
+pub fn tag_spans(
+    tags: &[String],
+    colors: &HashMap<String, Color>,
+    default: Color,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::with_capacity(tags.len() * 2);
+    for (i, tag) in tags.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(", ", Style::default().fg(default)));
+        }
+        let color = colors.get(tag).copied().unwrap_or(default);
+        spans.push(Span::styled(tag.clone(), Style::default().fg(color)));
+    }
+    spans
+}
+
+/// Footer style for a status message of the given level
+pub fn message_style(level: MessageLevel) -> Style {
+    let t = theme();
+    match level {
+        MessageLevel::Info => Style::default().fg(t.message).add_modifier(Modifier::BOLD),
+        MessageLevel::Warning => Style::default()
+            .fg(t.message_warning)
+            .add_modifier(Modifier::BOLD),
+        MessageLevel::Error => Style::default()
+            .fg(Color::Black)
+            .bg(t.message_error)
+            .add_modifier(Modifier::BOLD),
+    }
+}
+
 /// Calculate a centered rectangle within a parent rectangle
 pub fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = ratatui::layout::Layout::default()
@@ -1,6 +1,10 @@
 use super::app::{App, DetailPane, InputMode};
+use super::keymap::{Action, ActionGroup, KeyBinding};
 use super::theme::{theme, tui_config};
 use super::ui_utils;
+use crate::model::{expand_includes, resolve_include};
+use crate::read_markers::ReadState;
+use crate::removal::RemovalKind;
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -50,6 +54,7 @@ pub fn draw_tree(f: &mut Frame, app: &mut App, area: Rect) {
 
     // Calculate index within page for highlighting
     let index_in_page = app.selected_index.saturating_sub(page_start);
+    let search_highlight = app.search_highlight();
 
     // Build parent context rows using indices from page table (Layer 2 → Layer 3)
     let mut parent_context_rows: Vec<Row> = Vec::new();
@@ -172,14 +177,52 @@ pub fn draw_tree(f: &mut Frame, app: &mut App, area: Rect) {
         };
 
         // Highlight search terms in title
-        let title_spans = ui_utils::highlight_search(&pea.title, &app.search_query, title_style);
+        let mut title_spans =
+            ui_utils::highlight_search(&pea.title, search_highlight.as_ref(), title_style);
+
+        // Dot for tickets new or changed since last opened
+        let read_color = match app.read_state(pea) {
+            ReadState::New => Some(theme().unread_new),
+            ReadState::Changed => Some(theme().unread_changed),
+            ReadState::Read => None,
+        };
+        if let Some(color) = read_color {
+            title_spans.insert(
+                0,
+                Span::styled(
+                    "● ",
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            );
+        }
+
+        // Mark the ticket set with `peas focus`
+        if app.is_focused(&pea.id) {
+            title_spans.insert(
+                0,
+                Span::styled(
+                    "◉ ",
+                    Style::default()
+                        .fg(theme().text_highlight)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            );
+        }
+
+        // Name who holds a claim on the ticket
+        if let Some(claim) = pea.active_claim(chrono::Utc::now()) {
+            title_spans.push(Span::styled(
+                format!(" (claimed by {})", claim.by),
+                Style::default().fg(theme().text_muted),
+            ));
+        }
 
         // Tree + ID combined in one cell (so tree connects to ID visually)
         // ID is bold and bright green when selected
         let id_style = theme().id_style(is_selected);
 
         // Highlight search terms in ID
-        let id_spans = ui_utils::highlight_search(&pea.id, &app.search_query, id_style);
+        let id_spans = ui_utils::highlight_search(&pea.id, search_highlight.as_ref(), id_style);
         let mut tree_id_spans = vec![Span::styled(
             prefix,
             Style::default().fg(theme().tree_lines),
@@ -210,7 +253,7 @@ pub fn draw_tree(f: &mut Frame, app: &mut App, area: Rect) {
             format!("{}", pea.pea_type)
         };
 
-        Row::new(vec![
+        let row = Row::new(vec![
             Cell::from(sel).style(sel_style),
             Cell::from(checkbox).style(checkbox_style),
             Cell::from(tree_and_id),
@@ -218,7 +261,14 @@ pub fn draw_tree(f: &mut Frame, app: &mut App, area: Rect) {
             Cell::from(format!("{} {}", status_icon, pea.status)).style(status_style),
             Cell::from(pri).style(Style::default().fg(pri_color)),
             Cell::from(Line::from(title_spans)),
-        ])
+        ]);
+
+        // Archived tickets are shown dimmed
+        if app.is_archived(&pea.id) {
+            row.style(Style::default().add_modifier(Modifier::DIM))
+        } else {
+            row
+        }
     }));
 
     // Title shows count, selection count, and current date/time (ISO 8601)
@@ -226,14 +276,20 @@ pub fn draw_tree(f: &mut Frame, app: &mut App, area: Rect) {
     let now = chrono::Local::now();
     let datetime_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
+    let archive_marker = if app.show_archived {
+        " [+archived]"
+    } else {
+        ""
+    };
     let title_left = if selection_count > 0 {
         format!(
-            "─🫛 peas ({}) [{} selected] ○",
+            "─🫛 peas ({}){} [{} selected] ○",
             app.tree_nodes.len(),
+            archive_marker,
             selection_count
         )
     } else {
-        format!("─🫛 peas ({}) ○", app.tree_nodes.len())
+        format!("─🫛 peas ({}){} ○", app.tree_nodes.len(), archive_marker)
     };
 
     let title_right = format!("○ {} ○─", datetime_str);
@@ -448,6 +504,81 @@ pub fn draw_memory_list(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_stateful_widget(table, inner_area, &mut table_state);
 }
 
+/// Draw a read-only preview of the selected ticket next to the tree (split view)
+pub fn draw_preview(f: &mut Frame, app: &App, area: Rect) {
+    let t = theme();
+    let block = Block::default()
+        .title(" Preview ")
+        .borders(Borders::ALL)
+        .border_set(border::ROUNDED)
+        .border_style(t.border_style(false));
+
+    let Some(pea) = app.selected_pea() else {
+        let empty = Paragraph::new("No ticket selected")
+            .style(Style::default().fg(t.text_muted))
+            .block(block);
+        f.render_widget(empty, area);
+        return;
+    };
+
+    let (status_icon, status_color) = ui_utils::status_indicator(&pea.status);
+    let mut meta = vec![
+        Span::styled(
+            format!("{} {}", status_icon, pea.status),
+            Style::default().fg(status_color),
+        ),
+        Span::raw("  "),
+        Span::styled(
+            pea.pea_type.to_string(),
+            Style::default().fg(ui_utils::type_color(&pea.pea_type)),
+        ),
+        Span::raw("  "),
+        Span::styled(
+            pea.priority.to_string(),
+            Style::default().fg(ui_utils::priority_color(&pea.priority)),
+        ),
+    ];
+    if !pea.tags.is_empty() {
+        meta.push(Span::raw("  "));
+        meta.extend(ui_utils::tag_spans(
+            &pea.tags,
+            &app.tag_colors,
+            t.text_muted,
+        ));
+    }
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(pea.id.clone(), t.id_style(true)),
+            Span::raw(" "),
+            Span::styled(
+                pea.title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(meta),
+    ];
+    if let Some(ref parent) = pea.parent {
+        lines.push(Line::from(vec![
+            Span::styled("parent: ", Style::default().fg(t.text_muted)),
+            Span::styled(parent.clone(), Style::default().fg(t.id)),
+        ]));
+    }
+    lines.push(Line::from(""));
+    let body = expand_includes(&pea.body, |target| {
+        resolve_include(&app.all_peas, &app.all_memories, target)
+    });
+    lines.extend(ui_utils::markdown_lines(
+        &body,
+        app.search_highlight().as_ref(),
+    ));
+
+    let preview = Paragraph::new(Text::from(lines))
+        .block(block)
+        .wrap(Wrap { trim: false });
+    f.render_widget(preview, area);
+}
+
 pub fn draw_memory_detail(f: &mut Frame, app: &mut App, area: Rect, detail_scroll: u16) {
     use ratatui::{
         layout::{Constraint, Layout},
@@ -570,19 +701,68 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
         let status_color = theme().status_color(&pea.status);
         let pea_priority_color = ui_utils::priority_color(&pea.priority);
 
+        // Breadcrumb line: ancestor path plus back-navigation hint
+        let breadcrumb = app.detail_breadcrumb();
+        let area = if breadcrumb.len() > 1 || !app.detail_history.is_empty() {
+            let t = theme();
+            let mut spans = Vec::new();
+            for (i, crumb) in breadcrumb.iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::styled(" › ", Style::default().fg(t.text_muted)));
+                }
+                let style = if crumb.id == pea.id {
+                    Style::default()
+                        .fg(t.type_color(&crumb.pea_type))
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(t.type_color(&crumb.pea_type))
+                };
+                spans.push(Span::styled(format!("{} {}", crumb.id, crumb.title), style));
+            }
+            if !app.detail_history.is_empty() {
+                spans.push(Span::styled(
+                    format!("  (Backspace: back, {} more)", app.detail_history.len()),
+                    Style::default().fg(t.text_muted),
+                ));
+            }
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            f.render_widget(Paragraph::new(Line::from(spans)), chunks[0]);
+            chunks[1]
+        } else {
+            area
+        };
+
         // Check if we have body content
-        let has_body = !pea.body.is_empty();
+        let editing_body = app.body_textarea.is_some();
+        let has_body = !pea.body.is_empty() || editing_body;
         let has_relations = !app.relations_items.is_empty();
         let has_assets = !app.assets_items.is_empty();
-        let body_content = pea.body.clone();
+        // Rendered with includes expanded; the editor works on the raw body
+        let body_content = expand_includes(&pea.body, |target| {
+            resolve_include(&app.all_peas, &app.all_memories, target)
+        });
+        let claim = pea.active_claim(chrono::Utc::now()).cloned();
+        // Optional metadata rows below the fixed ones
+        let extra_rows = [
+            claim.is_some(),
+            pea.component.is_some(),
+            pea.assignee.is_some(),
+        ]
+        .into_iter()
+        .filter(|shown| *shown)
+        .count() as u16;
 
         // Layout: Top section (metadata + relations + assets) | Bottom section (body)
         let vertical_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(if has_body {
                 vec![
-                    Constraint::Length(12), // Top section (metadata + relations + assets)
-                    Constraint::Min(5),     // Body
+                    // Top section (metadata + relations + assets), taller for optional rows
+                    Constraint::Length(12 + extra_rows),
+                    Constraint::Min(5), // Body
                 ]
             } else {
                 vec![Constraint::Min(0)]
@@ -651,13 +831,17 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
         };
 
         let tags_display = if pea.tags.is_empty() {
-            "(none)".to_string()
+            Line::from(Span::styled("(none)", Style::default().fg(theme().tags)))
         } else {
-            pea.tags.join(", ")
+            Line::from(ui_utils::tag_spans(
+                &pea.tags,
+                &app.tag_colors,
+                theme().tags,
+            ))
         };
 
         // Build table rows
-        let metadata_rows = vec![
+        let mut metadata_rows = vec![
             // Title row
             Row::new(vec![
                 Cell::from(""),
@@ -700,9 +884,27 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
             Row::new(vec![
                 Cell::from(Span::styled(row_marker(3), pulsing_style)),
                 Cell::from("Tags:"),
+                Cell::from(tags_display),
+            ]),
+            // Links (opened with the URL modal)
+            Row::new(vec![
+                Cell::from(""),
+                Cell::from("Links:"),
                 Cell::from(Span::styled(
-                    tags_display,
-                    Style::default().fg(theme().tags),
+                    if pea.links.is_empty() {
+                        "(none)".to_string()
+                    } else {
+                        pea.links
+                            .iter()
+                            .map(|l| format!("[{}] {}", l.kind, l.title()))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    },
+                    Style::default().fg(if pea.links.is_empty() {
+                        t.text_muted
+                    } else {
+                        t.text
+                    }),
                 )),
             ]),
             // Empty row
@@ -711,21 +913,66 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
             Row::new(vec![
                 Cell::from(""),
                 Cell::from("Created:"),
-                Cell::from(Span::styled(
-                    pea.created.format("%Y-%m-%d %H:%M").to_string(),
-                    Style::default().fg(theme().timestamp),
-                )),
+                Cell::from(Line::from(vec![
+                    Span::styled(
+                        app.time_format.datetime(pea.created),
+                        Style::default().fg(theme().timestamp),
+                    ),
+                    Span::styled(
+                        pea.created_by
+                            .as_ref()
+                            .map(|a| format!(" by {}", a))
+                            .unwrap_or_default(),
+                        Style::default().fg(t.text_muted),
+                    ),
+                ])),
             ]),
             // Updated
             Row::new(vec![
                 Cell::from(""),
                 Cell::from("Updated:"),
-                Cell::from(Span::styled(
-                    pea.updated.format("%Y-%m-%d %H:%M").to_string(),
-                    Style::default().fg(theme().timestamp),
-                )),
+                Cell::from(Line::from(vec![
+                    Span::styled(
+                        app.time_format.datetime(pea.updated),
+                        Style::default().fg(theme().timestamp),
+                    ),
+                    Span::styled(
+                        pea.updated_by
+                            .as_ref()
+                            .map(|a| format!(" by {}", a))
+                            .unwrap_or_default(),
+                        Style::default().fg(t.text_muted),
+                    ),
+                ])),
             ]),
         ];
+        if let Some(component) = &pea.component {
+            metadata_rows.push(Row::new(vec![
+                Cell::from(""),
+                Cell::from("Component:"),
+                Cell::from(Span::styled(
+                    component.clone(),
+                    Style::default().fg(t.text_highlight),
+                )),
+            ]));
+        }
+        if let Some(assignee) = &pea.assignee {
+            metadata_rows.push(Row::new(vec![
+                Cell::from(""),
+                Cell::from("Assignee:"),
+                Cell::from(assignee.clone()),
+            ]));
+        }
+        if let Some(claim) = &claim {
+            metadata_rows.push(Row::new(vec![
+                Cell::from(""),
+                Cell::from("Claimed:"),
+                Cell::from(Span::styled(
+                    claim.to_string(),
+                    Style::default().fg(t.text_highlight),
+                )),
+            ]));
+        }
 
         let widths = [
             Constraint::Length(2),  // Row marker column
@@ -759,11 +1006,20 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
             let inner = relations_block.inner(rel_area);
             f.render_widget(relations_block, rel_area);
 
-            // Build list items for relationships
+            // Keep the selection in view and only build items for the visible rows
+            let visible = inner.height.max(1);
+            let selection = app.relations_selection as u16;
+            if selection < app.relations_scroll {
+                app.relations_scroll = selection;
+            } else if selection >= app.relations_scroll + visible {
+                app.relations_scroll = selection + 1 - visible;
+            }
             let items: Vec<ListItem> = app
                 .relations_items
                 .iter()
                 .enumerate()
+                .skip(app.relations_scroll as usize)
+                .take(visible as usize)
                 .map(|(i, (rel_type, id, title, pea_type))| {
                     let is_selected = i == app.relations_selection;
                     let prefix = super::theme::Theme::relation_prefix(rel_type);
@@ -877,17 +1133,21 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
         if let Some(body_rect) = body_area {
             let body_focused = app.detail_pane == DetailPane::Body;
 
-            let title = if app.input_mode == InputMode::EditBody {
-                " Description [EDITING - Ctrl+S to save, Esc to cancel] "
+            let title = if editing_body {
+                format!(
+                    " Description [EDITING - Ctrl+S save, Ctrl+V paste, Ctrl+W wrap: {}, Ctrl+L line numbers: {}, Esc cancel] ",
+                    if app.body_wrap { "on" } else { "off" },
+                    if app.body_line_numbers { "on" } else { "off" },
+                )
             } else {
-                " Description "
+                " Description ".to_string()
             };
 
             let body_block = Block::default()
                 .title(title)
                 .borders(Borders::ALL)
                 .border_set(border::ROUNDED)
-                .border_style(if app.input_mode == InputMode::EditBody {
+                .border_style(if editing_body {
                     Style::default().fg(theme().text_highlight) // Yellow/bright to indicate edit mode
                 } else {
                     theme().border_style(body_focused)
@@ -897,48 +1157,79 @@ pub fn draw_detail_fullscreen(f: &mut Frame, app: &mut App, area: Rect, detail_s
             f.render_widget(body_block, body_rect);
 
             // Render textarea if in edit mode, otherwise render markdown
-            if app.input_mode == InputMode::EditBody {
+            if editing_body {
                 if let Some(textarea) = app.body_textarea.as_mut() {
                     use rat_text::HasScreenCursor;
-                    use rat_text::text_area::TextArea;
+                    use rat_text::line_number::{LineNumberState, LineNumbers};
+                    use rat_text::text_area::{TextArea, TextWrap};
                     use ratatui::widgets::StatefulWidget;
 
-                    // Configure TextArea with proper styling and focus
-                    let widget = TextArea::new()
-                        .style(Style::default().fg(theme().text).bg(Color::Reset))
-                        .select_style(Style::default().fg(Color::Black).bg(theme().text_highlight));
+                    let t = theme();
+                    let (numbers_area, text_area) = if app.body_line_numbers {
+                        let width = LineNumbers::width_for(textarea.offset().1, 0, (0, 1), 0);
+                        let chunks = Layout::default()
+                            .direction(Direction::Horizontal)
+                            .constraints([Constraint::Length(width), Constraint::Min(0)])
+                            .split(inner);
+                        (Some(chunks[0]), chunks[1])
+                    } else {
+                        (None, inner)
+                    };
 
-                    widget.render(inner, f.buffer_mut(), textarea);
+                    // Configure TextArea with proper styling and focus; text styles are
+                    // indexed by the body_editor::STYLE_* constants
+                    let widget = TextArea::new()
+                        .style(Style::default().fg(t.text).bg(Color::Reset))
+                        .select_style(Style::default().fg(Color::Black).bg(t.text_highlight))
+                        .text_wrap(if app.body_wrap {
+                            TextWrap::Word(8)
+                        } else {
+                            TextWrap::Shift
+                        })
+                        .text_style([
+                            Style::default()
+                                .fg(t.md_heading)
+                                .add_modifier(Modifier::BOLD),
+                            Style::default().fg(t.md_emphasis),
+                            Style::default().fg(t.md_code),
+                            Style::default()
+                                .fg(t.md_link)
+                                .add_modifier(Modifier::UNDERLINED),
+                            Style::default().fg(t.md_list_marker),
+                            Style::default()
+                                .fg(t.md_quote)
+                                .add_modifier(Modifier::ITALIC),
+                        ]);
+
+                    widget.render(text_area, f.buffer_mut(), textarea);
+
+                    // Line numbers follow the textarea's scroll and wrapping
+                    if let Some(numbers_area) = numbers_area {
+                        LineNumbers::new()
+                            .with_textarea(textarea)
+                            .margin((0, 1))
+                            .style(Style::default().fg(t.line_number))
+                            .cursor_style(Style::default().fg(t.text_highlight))
+                            .render(
+                                numbers_area,
+                                f.buffer_mut(),
+                                &mut LineNumberState::default(),
+                            );
+                    }
 
-                    // Set cursor position for rendering
-                    if let Some((cx, cy)) = textarea.screen_cursor() {
+                    // Set cursor position for rendering (not while reviewing the diff)
+                    if app.input_mode == InputMode::EditBody
+                        && let Some((cx, cy)) = textarea.screen_cursor()
+                    {
                         f.set_cursor_position((cx, cy));
                     }
                 }
                 // No scrolling in edit mode (textarea handles its own scrolling)
                 app.set_detail_max_scroll(0);
             } else {
-                // Render markdown using tui-markdown
-                let md_text_core = tui_markdown::from_str(&body_content);
-
-                // Convert from ratatui_core::Text to ratatui::Text by extracting lines
-                let lines: Vec<Line> = md_text_core
-                    .lines
-                    .into_iter()
-                    .map(|line_core| {
-                        let spans: Vec<Span> = line_core
-                            .spans
-                            .into_iter()
-                            .map(|span_core| {
-                                Span::styled(
-                                    span_core.content,
-                                    ui_utils::convert_style(span_core.style),
-                                )
-                            })
-                            .collect();
-                        Line::from(spans)
-                    })
-                    .collect();
+                // Render markdown using tui-markdown, highlighting search matches
+                let lines =
+                    ui_utils::markdown_lines(&body_content, app.search_highlight().as_ref());
                 let md_text = Text::from(lines);
 
                 // Calculate content height for scroll limiting
@@ -1005,7 +1296,10 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
             Style::default().bg(t.mode_type.0).fg(t.mode_type.1),
         ),
         InputMode::DeleteConfirm => Span::styled(
-            " DELETE ",
+            match app.removal_kind {
+                RemovalKind::Delete => " DELETE ",
+                RemovalKind::Archive => " ARCHIVE ",
+            },
             Style::default().bg(t.mode_delete.0).fg(t.mode_delete.1),
         ),
         InputMode::ParentModal => Span::styled(
@@ -1032,6 +1326,10 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
             " EDIT ",
             Style::default().bg(t.text_highlight).fg(Color::Black),
         ),
+        InputMode::BodyDiff => Span::styled(
+            " DIFF ",
+            Style::default().bg(t.text_highlight).fg(Color::Black),
+        ),
         InputMode::TagsModal => Span::styled(
             " TAGS ",
             Style::default().bg(t.mode_parent.0).fg(t.mode_parent.1),
@@ -1040,16 +1338,49 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
             " URL ",
             Style::default().bg(t.mode_parent.0).fg(t.mode_parent.1),
         ),
+        InputMode::BulkModal => Span::styled(
+            " BULK ",
+            Style::default().bg(t.mode_status.0).fg(t.mode_status.1),
+        ),
+        InputMode::MessageLog => Span::styled(
+            " MESSAGES ",
+            Style::default().bg(t.mode_detail.0).fg(t.mode_detail.1),
+        ),
+        InputMode::EditConflict => Span::styled(
+            " CONFLICT ",
+            Style::default().bg(t.modal_border_delete).fg(Color::Black),
+        ),
+    };
+
+    let normal_hints = match app.view_mode {
+        super::app::ViewMode::Tickets => app.keymap.footer_hints(&[
+            (&[Action::MoveDown, Action::MoveUp], "nav"),
+            (&[Action::PrevPage, Action::NextPage], "page"),
+            (&[Action::Select], "select"),
+            (&[Action::Search], "search"),
+            (&[Action::SwitchView], "memory"),
+            (&[Action::SplitView], "split"),
+            (&[Action::Create], "create"),
+            (&[Action::Status], "status"),
+            (&[Action::Edit], "edit"),
+            (&[Action::Archive], "archive"),
+            (&[Action::Help], "help"),
+            (&[Action::Quit], "quit"),
+        ]),
+        super::app::ViewMode::Memory => app.keymap.footer_hints(&[
+            (&[Action::MoveDown, Action::MoveUp], "nav"),
+            (&[Action::SwitchView], "tickets"),
+            (&[Action::Create], "new"),
+            (&[Action::Help], "help"),
+            (&[Action::Quit], "quit"),
+        ]),
     };
 
     let help_text = match app.input_mode {
-        InputMode::Normal => match app.view_mode {
-            super::app::ViewMode::Tickets => {
-                " ↑↓:nav  ←→:page  Space:select  /:search  Tab:memory  c:create  s:status  e:edit  ?:help  q:quit "
-            }
-            super::app::ViewMode::Memory => " ↑↓:nav  Tab:tickets  c:new  ?:help  q:quit ",
-        },
-        InputMode::Filter => " Type to search, Enter/Esc to confirm ",
+        InputMode::Normal => normal_hints.as_str(),
+        InputMode::Filter => {
+            " Type to search (tag:ui status:todo type:bug)  Ctrl+R:regex  Ctrl+F:fuzzy  Enter/Esc:confirm "
+        }
         InputMode::StatusModal
         | InputMode::PriorityModal
         | InputMode::TypeModal
@@ -1057,16 +1388,27 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
         InputMode::BlockingModal => " ↓/↑:nav  Space:toggle  Enter:apply  Esc:cancel ",
         InputMode::DetailView => match app.view_mode {
             super::app::ViewMode::Tickets => {
-                " ↓/↑:scroll  e:edit  o:open-url  s:status  P:priority  t:type  p:parent  b:blocking  y:copy-id  Esc/q:close "
+                " ↓/↑:scroll  e:edit  o:open-url  s:status  P:priority  t:type  p:parent  b:blocking  y:copy-id  Bksp:back  Esc/q:close "
             }
             super::app::ViewMode::Memory => " ↓/↑:scroll  Esc/q:close ",
         },
-        InputMode::CreateModal => " Tab:next field  ←→:change type  Enter:create  Esc:cancel ",
+        InputMode::CreateModal => {
+            " Tab:next field  ←→:change  Enter:create (body: new line)  Ctrl+S:create  Ctrl+E:create+edit  Esc:cancel "
+        }
         InputMode::MemoryCreateModal => " Tab:next field  Enter:create  Esc:cancel ",
+        InputMode::DeleteConfirm if !app.selected_children().is_empty() => {
+            " c:with children  o:orphan  p:move up  n/Esc:cancel "
+        }
         InputMode::DeleteConfirm => " y/Enter:confirm  n/Esc:cancel ",
-        InputMode::EditBody => " Ctrl+S:save  Esc:cancel ",
-        InputMode::TagsModal => " Type comma-separated tags  Enter:save  Esc:cancel ",
+        InputMode::EditBody => {
+            " Ctrl+S:save  Ctrl+V:paste  Ctrl+W:wrap  Ctrl+L:line numbers  Esc:cancel "
+        }
+        InputMode::BodyDiff => " y/Enter:save  n/Esc:keep editing  ↓/↑:scroll ",
+        InputMode::TagsModal => " Type comma-separated tags  Tab:complete  Enter:save  Esc:cancel ",
         InputMode::UrlModal => " ↓/↑:navigate  Enter:open  Esc:cancel ",
+        InputMode::BulkModal => " ↓/↑:nav  Enter:select  y:confirm  Esc:back ",
+        InputMode::MessageLog => " ↓/↑:scroll  c:clear  Esc/m:close ",
+        InputMode::EditConflict => " ↓/↑:nav  Enter:choose  r:reload  o:overwrite  m:merge ",
     };
 
     let mut footer_spans = vec![mode_indicator];
@@ -1080,6 +1422,26 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
         ));
         footer_spans.push(Span::styled("_", Style::default().fg(t.modal_cursor)));
         footer_spans.push(Span::raw(" "));
+        if app.search_regex {
+            footer_spans.push(Span::styled("[regex] ", Style::default().fg(t.text_muted)));
+        }
+        if app.search_fuzzy {
+            footer_spans.push(Span::styled("[fuzzy] ", Style::default().fg(t.text_muted)));
+        }
+    }
+
+    // Show a pending count or key sequence like vim's showcmd
+    if app.input_mode == InputMode::Normal
+        && (app.pending_count.is_some() || !app.pending_keys.is_empty())
+    {
+        let mut pending = app.pending_count.map(|c| c.to_string()).unwrap_or_default();
+        for key in &app.pending_keys {
+            pending.push_str(&KeyBinding::from(key).to_string());
+        }
+        footer_spans.push(Span::styled(
+            format!(" {} ", pending),
+            Style::default().fg(t.text_highlight),
+        ));
     }
 
     // Show undo count if available
@@ -1087,7 +1449,11 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
     if undo_count > 0 {
         footer_spans.push(Span::raw(" "));
         footer_spans.push(Span::styled(
-            format!("[u:undo×{}]", undo_count),
+            format!(
+                "[{}:undo×{}]",
+                app.keymap.primary(Action::Undo).unwrap_or_default(),
+                undo_count
+            ),
             Style::default().fg(t.text_muted),
         ));
     }
@@ -1103,10 +1469,7 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
 
     if let Some(ref msg) = app.message {
         footer_spans.push(Span::raw(" "));
-        footer_spans.push(Span::styled(
-            msg,
-            Style::default().fg(t.message).add_modifier(Modifier::BOLD),
-        ));
+        footer_spans.push(Span::styled(&msg.text, ui_utils::message_style(msg.level)));
     }
 
     footer_spans.push(Span::styled(help_text, Style::default().fg(t.text_muted)));
@@ -1116,109 +1479,51 @@ pub fn draw_footer(f: &mut Frame, app: &App, area: Rect) {
 }
 
 /// Get color for priority
-pub fn draw_help_popup(f: &mut Frame) {
-    let area = ui_utils::centered_rect(60, 70, f.area());
+pub fn draw_help_popup(f: &mut Frame, app: &App) {
+    let area = ui_utils::centered_rect(60, 80, f.area());
     let t = theme();
     let key_style = Style::default().fg(t.help_key);
 
-    let help_text = vec![
-        Line::from(Span::styled(
-            "Keyboard Shortcuts",
-            Style::default().add_modifier(Modifier::BOLD),
-        )),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Navigation",
-            Style::default().add_modifier(Modifier::UNDERLINED),
-        )),
-        Line::from(vec![
-            Span::styled("↑/↓     ", key_style),
-            Span::raw("Move up/down"),
-        ]),
-        Line::from(vec![
-            Span::styled("←/→     ", key_style),
-            Span::raw("Prev/next page"),
-        ]),
-        Line::from(vec![
-            Span::styled("g/G     ", key_style),
-            Span::raw("First/last item"),
-        ]),
-        Line::from(vec![
-            Span::styled("Enter   ", key_style),
-            Span::raw("Open detail view"),
-        ]),
-        Line::from(vec![
-            Span::styled("/       ", key_style),
-            Span::raw("Search"),
-        ]),
+    // Generated from the keymap so custom `[tui.keys]` bindings show up here
+    let groups: Vec<(ActionGroup, Vec<(Action, String)>)> = ActionGroup::all()
+        .into_iter()
+        .map(|group| (group, app.keymap.group(group).collect()))
+        .collect();
+    let key_width = groups
+        .iter()
+        .flat_map(|(_, actions)| actions.iter().map(|(_, keys)| keys.chars().count()))
+        .max()
+        .unwrap_or(0)
+        .max(7)
+        + 1;
+    let key_line = |keys: &str, description: &str| {
         Line::from(vec![
-            Span::styled("Tab     ", key_style),
-            Span::raw("Switch between Tickets/Memory"),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled(
-            "Actions",
+            Span::styled(format!("{:<key_width$}", keys), key_style),
+            Span::raw(description.to_string()),
+        ])
+    };
+
+    let mut help_text = vec![Line::from(Span::styled(
+        "Keyboard Shortcuts",
+        Style::default().add_modifier(Modifier::BOLD),
+    ))];
+    for (group, actions) in &groups {
+        help_text.push(Line::from(""));
+        help_text.push(Line::from(Span::styled(
+            group.label(),
             Style::default().add_modifier(Modifier::UNDERLINED),
-        )),
-        Line::from(vec![
-            Span::styled("c       ", key_style),
-            Span::raw("Create new ticket"),
-        ]),
-        Line::from(vec![
-            Span::styled("s       ", key_style),
-            Span::raw("Change status"),
-        ]),
-        Line::from(vec![
-            Span::styled("t       ", key_style),
-            Span::raw("Change type"),
-        ]),
-        Line::from(vec![
-            Span::styled("P       ", key_style),
-            Span::raw("Change priority"),
-        ]),
-        Line::from(vec![
-            Span::styled("p       ", key_style),
-            Span::raw("Set parent"),
-        ]),
-        Line::from(vec![
-            Span::styled("b       ", key_style),
-            Span::raw("Set blocking tickets"),
-        ]),
-        Line::from(vec![
-            Span::styled("Space   ", key_style),
-            Span::raw("Toggle selection (multi-select)"),
-        ]),
-        Line::from(vec![
-            Span::styled("e       ", key_style),
-            Span::raw("Edit in $EDITOR"),
-        ]),
-        Line::from(vec![
-            Span::styled("d       ", key_style),
-            Span::raw("Delete ticket"),
-        ]),
-        Line::from(vec![
-            Span::styled("y       ", key_style),
-            Span::raw("Copy ID to clipboard"),
-        ]),
-        Line::from(vec![
-            Span::styled("r       ", key_style),
-            Span::raw("Refresh list"),
-        ]),
-        Line::from(vec![
-            Span::styled("u       ", key_style),
-            Span::raw("Undo last operation (multi-level)"),
-        ]),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("?       ", key_style),
-            Span::raw("Toggle help"),
-        ]),
-        Line::from(vec![
-            Span::styled("Esc     ", key_style),
-            Span::raw("Close / Cancel"),
-        ]),
-        Line::from(vec![Span::styled("q       ", key_style), Span::raw("Quit")]),
-    ];
+        )));
+        for (action, keys) in actions {
+            help_text.push(key_line(keys, action.description()));
+        }
+        match group {
+            ActionGroup::Navigation => {
+                help_text.push(key_line("Bksp", "Back to previous ticket (detail view)"))
+            }
+            ActionGroup::General => help_text.push(key_line("Esc", "Close / Cancel")),
+            ActionGroup::Actions => {}
+        }
+    }
 
     let help = Paragraph::new(help_text)
         .block(
@@ -1,4 +1,6 @@
+use crate::config::UndoSettings;
 use crate::error::{PeasError, Result};
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
@@ -25,6 +27,25 @@ pub enum UndoOperation {
         original_path: PathBuf,
         archive_path: PathBuf,
     },
+    /// Brought a pea back from the archive - undo by archiving it again
+    Unarchive {
+        id: String,
+        archive_path: PathBuf,
+        restored_path: PathBuf,
+    },
+    /// Wrote a file that is not a pea, such as the config - undo by restoring
+    /// its previous content, or removing it if there was none
+    File {
+        file_path: PathBuf,
+        previous_content: Option<String>,
+    },
+    /// Moved a file or directory, such as the assets of a renamed pea - undo by moving it back
+    Move { from: PathBuf, to: PathBuf },
+    /// Several operations applied together - undone as one unit, in reverse order
+    Batch {
+        description: String,
+        operations: Vec<UndoOperation>,
+    },
 }
 
 impl UndoOperation {
@@ -34,6 +55,12 @@ impl UndoOperation {
             UndoOperation::Update { id, .. } => format!("Update {}", id),
             UndoOperation::Delete { id, .. } => format!("Delete {}", id),
             UndoOperation::Archive { id, .. } => format!("Archive {}", id),
+            UndoOperation::Unarchive { id, .. } => format!("Unarchive {}", id),
+            UndoOperation::File { file_path, .. } => format!("Write {}", file_path.display()),
+            UndoOperation::Move { from, to } => {
+                format!("Move {} to {}", from.display(), to.display())
+            }
+            UndoOperation::Batch { description, .. } => description.clone(),
         }
     }
 
@@ -43,137 +70,374 @@ impl UndoOperation {
             UndoOperation::Update { id, .. } => id,
             UndoOperation::Delete { id, .. } => id,
             UndoOperation::Archive { id, .. } => id,
+            UndoOperation::Unarchive { id, .. } => id,
+            // Not a pea, the file name stands in for the ID
+            UndoOperation::File {
+                file_path: path, ..
+            }
+            | UndoOperation::Move { from: path, .. } => path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default(),
+            UndoOperation::Batch { operations, .. } => {
+                operations.first().map(|op| op.id()).unwrap_or_default()
+            }
+        }
+    }
+
+    /// IDs of the peas the operation touches, without duplicates
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for op in self.flatten() {
+            let is_pea = !matches!(op, UndoOperation::File { .. } | UndoOperation::Move { .. });
+            if is_pea && !ids.iter().any(|id| id == op.id()) {
+                ids.push(op.id().to_string());
+            }
+        }
+        ids
+    }
+
+    /// The single operations, with batches expanded, in the order they were applied
+    pub fn flatten(&self) -> Vec<&UndoOperation> {
+        match self {
+            UndoOperation::Batch { operations, .. } => {
+                operations.iter().flat_map(|op| op.flatten()).collect()
+            }
+            op => vec![op],
+        }
+    }
+
+    /// Revert this operation on disk
+    pub fn revert(self) -> Result<()> {
+        match self {
+            UndoOperation::Create { file_path, .. } => {
+                // Undo create by deleting the file
+                if file_path.exists() {
+                    std::fs::remove_file(&file_path)?;
+                }
+            }
+            UndoOperation::Update {
+                id,
+                file_path,
+                previous_content,
+            } => {
+                // Undo update by restoring previous content
+                std::fs::write(&file_path, previous_content)?;
+                // A title change renamed the file; drop the renamed copy
+                if let Some(dir) = file_path.parent() {
+                    let prefix = format!("{}--", id);
+                    for entry in std::fs::read_dir(dir)? {
+                        let path = entry?.path();
+                        let renamed = path
+                            .file_name()
+                            .is_some_and(|name| name.to_string_lossy().starts_with(&prefix));
+                        if renamed && path != file_path {
+                            std::fs::remove_file(&path)?;
+                        }
+                    }
+                }
+            }
+            UndoOperation::Delete {
+                file_path,
+                previous_content,
+                ..
+            } => {
+                // Undo delete by recreating the file
+                if let Some(parent) = file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&file_path, previous_content)?;
+            }
+            UndoOperation::Archive {
+                original_path,
+                archive_path,
+                ..
+            } => {
+                // Undo archive by moving back
+                if archive_path.exists() {
+                    std::fs::rename(&archive_path, &original_path)?;
+                }
+            }
+            UndoOperation::Unarchive {
+                archive_path,
+                restored_path,
+                ..
+            } => {
+                if restored_path.exists() {
+                    std::fs::rename(&restored_path, &archive_path)?;
+                }
+            }
+            UndoOperation::File {
+                file_path,
+                previous_content,
+            } => match previous_content {
+                Some(content) => std::fs::write(&file_path, content)?,
+                None if file_path.exists() => std::fs::remove_file(&file_path)?,
+                None => {}
+            },
+            UndoOperation::Move { from, to } => {
+                if to.exists() {
+                    std::fs::rename(&to, &from)?;
+                }
+            }
+            UndoOperation::Batch { operations, .. } => {
+                // Undo in reverse order so later changes are reverted first
+                for op in operations.into_iter().rev() {
+                    op.revert()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Capture an update operation from the current file content (call before the update)
+    pub fn update_from_file(id: &str, file_path: &Path) -> Result<Self> {
+        let previous_content = std::fs::read_to_string(file_path)?;
+        Ok(UndoOperation::Update {
+            id: id.to_string(),
+            file_path: file_path.to_path_buf(),
+            previous_content,
+        })
+    }
+
+    /// Capture a delete operation from the current file content (call before the delete)
+    pub fn delete_from_file(id: &str, file_path: &Path) -> Result<Self> {
+        let previous_content = std::fs::read_to_string(file_path)?;
+        Ok(UndoOperation::Delete {
+            id: id.to_string(),
+            file_path: file_path.to_path_buf(),
+            previous_content,
+        })
+    }
+}
+
+/// Directory of the undo history, in the data directory
+pub const UNDO_DIR: &str = ".undo";
+
+/// A recorded operation with when it happened and what it touched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UndoEntry {
+    pub recorded: DateTime<Utc>,
+    pub description: String,
+    /// IDs of the peas the operation changed
+    pub ids: Vec<String>,
+    pub operation: UndoOperation,
+}
+
+impl UndoEntry {
+    pub fn new(operation: UndoOperation) -> Self {
+        Self {
+            recorded: Utc::now(),
+            description: operation.description(),
+            ids: operation.ids(),
+            operation,
         }
     }
 }
 
 /// Manages undo state for peas operations
+///
+/// Each undo step is a JSON file in `.peas/.undo/`, named by a sequence
+/// number so the newest sorts last. Steps beyond the configured count or age
+/// are pruned whenever a new one is recorded.
 pub struct UndoManager {
-    undo_file: PathBuf,
+    undo_dir: PathBuf,
+    settings: UndoSettings,
 }
 
 impl UndoManager {
     pub fn new(data_path: &Path) -> Self {
         Self {
-            undo_file: data_path.join(".undo"),
+            undo_dir: data_path.join(UNDO_DIR),
+            settings: UndoSettings::default(),
         }
     }
 
+    /// Use the retention of the `[undo]` config section
+    pub fn with_settings(mut self, settings: UndoSettings) -> Self {
+        self.settings = settings;
+        self
+    }
+
     /// Record an operation for potential undo
     /// Supports multiple undo levels by maintaining a stack
     pub fn record(&self, op: UndoOperation) -> Result<()> {
-        let mut stack = self.get_stack()?;
+        self.migrate_legacy_stack()?;
+        std::fs::create_dir_all(&self.undo_dir)?;
+        let next = self
+            .entry_files()?
+            .last()
+            .and_then(|(seq, _)| seq.checked_add(1))
+            .unwrap_or(1);
+        let content = serde_json::to_string_pretty(&UndoEntry::new(op))?;
+        std::fs::write(self.undo_dir.join(format!("{:010}.json", next)), content)?;
+        self.prune()?;
+        Ok(())
+    }
 
-        // Limit stack size to prevent unbounded growth (keep last 50 operations)
-        const MAX_UNDO_LEVELS: usize = 50;
-        if stack.len() >= MAX_UNDO_LEVELS {
-            stack.remove(0); // Remove oldest operation
+    /// Remove the steps beyond the configured count or age, oldest first
+    ///
+    /// Returns the number of steps removed.
+    pub fn prune(&self) -> Result<usize> {
+        let entries = self.read_entries()?;
+        let max_age = (self.settings.max_age_days > 0)
+            .then(|| Duration::days(i64::from(self.settings.max_age_days)));
+        let excess = entries.len().saturating_sub(self.settings.max_entries);
+        let mut removed = 0;
+        for (index, (path, entry)) in entries.iter().enumerate() {
+            let expired = max_age.is_some_and(|age| Utc::now() - entry.recorded > age);
+            if index < excess || expired {
+                std::fs::remove_file(path)?;
+                removed += 1;
+            }
         }
+        Ok(removed)
+    }
 
-        stack.push(op);
-        self.save_stack(&stack)?;
-        Ok(())
+    /// Undo steps, oldest first
+    pub fn entries(&self) -> Result<Vec<UndoEntry>> {
+        Ok(self
+            .read_entries()?
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .collect())
     }
 
-    /// Get the entire undo stack
-    fn get_stack(&self) -> Result<Vec<UndoOperation>> {
-        if !self.undo_file.exists() {
+    /// Entry files with their sequence number, oldest first
+    fn entry_files(&self) -> Result<Vec<(u64, PathBuf)>> {
+        if !self.undo_dir.is_dir() {
             return Ok(Vec::new());
         }
-        let content = std::fs::read_to_string(&self.undo_file)?;
-        let stack: Vec<UndoOperation> = serde_json::from_str(&content)?;
-        Ok(stack)
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.undo_dir)? {
+            let path = entry?.path();
+            let seq = path
+                .file_stem()
+                .filter(|_| path.extension().is_some_and(|ext| ext == "json"))
+                .and_then(|stem| stem.to_str()?.parse::<u64>().ok());
+            if let Some(seq) = seq {
+                files.push((seq, path));
+            }
+        }
+        files.sort();
+        Ok(files)
     }
 
-    /// Save the undo stack to disk
-    fn save_stack(&self, stack: &[UndoOperation]) -> Result<()> {
-        let content = serde_json::to_string_pretty(&stack)?;
-        std::fs::write(&self.undo_file, content)?;
+    fn read_entries(&self) -> Result<Vec<(PathBuf, UndoEntry)>> {
+        self.migrate_legacy_stack()?;
+        self.entry_files()?
+            .into_iter()
+            .map(|(_, path)| {
+                let entry = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+                Ok((path, entry))
+            })
+            .collect()
+    }
+
+    /// Move a stack from the single `.undo` file of earlier versions into entries
+    fn migrate_legacy_stack(&self) -> Result<()> {
+        if !self.undo_dir.is_file() {
+            return Ok(());
+        }
+        let content = std::fs::read_to_string(&self.undo_dir)?;
+        let stack: Vec<UndoOperation> = serde_json::from_str(&content)?;
+        // The file's age is the best guess for when its operations happened
+        let recorded = std::fs::metadata(&self.undo_dir)?
+            .modified()
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        std::fs::remove_file(&self.undo_dir)?;
+        std::fs::create_dir_all(&self.undo_dir)?;
+        for (index, op) in stack.into_iter().enumerate() {
+            let entry = UndoEntry {
+                recorded,
+                ..UndoEntry::new(op)
+            };
+            std::fs::write(
+                self.undo_dir.join(format!("{:010}.json", index + 1)),
+                serde_json::to_string_pretty(&entry)?,
+            )?;
+        }
         Ok(())
     }
 
+    /// Replace text in every recorded step, e.g. an ID changed by `peas mv`
+    ///
+    /// Returns whether any step changed.
+    pub fn replace_in_entries(&self, replacements: &[(&str, &str)]) -> Result<bool> {
+        let replaced = self.replaced_entries(replacements)?;
+        for (path, content) in &replaced {
+            std::fs::write(path, content)?;
+        }
+        Ok(!replaced.is_empty())
+    }
+
+    /// The steps [`replace_in_entries`](Self::replace_in_entries) would change,
+    /// as entry files and their new content, for writing them in a transaction
+    pub fn replaced_entries(
+        &self,
+        replacements: &[(&str, &str)],
+    ) -> Result<Vec<(PathBuf, String)>> {
+        let mut replaced = Vec::new();
+        for (_, path) in self.entry_files()? {
+            let content = std::fs::read_to_string(&path)?;
+            let new_content = replacements
+                .iter()
+                .fold(content.clone(), |text, (from, to)| text.replace(from, to));
+            if new_content != content {
+                replaced.push((path, new_content));
+            }
+        }
+        Ok(replaced)
+    }
+
+    /// Get the last recorded entry
+    pub fn last_entry(&self) -> Result<Option<UndoEntry>> {
+        Ok(self.read_entries()?.pop().map(|(_, entry)| entry))
+    }
+
     /// Get the last recorded operation
     pub fn last_operation(&self) -> Result<Option<UndoOperation>> {
-        let stack = self.get_stack()?;
-        Ok(stack.last().cloned())
+        Ok(self.last_entry()?.map(|entry| entry.operation))
     }
 
     /// Get the number of operations that can be undone
     pub fn undo_count(&self) -> usize {
-        self.get_stack().map(|s| s.len()).unwrap_or(0)
+        self.migrate_legacy_stack()
+            .and_then(|()| self.entry_files())
+            .map(|files| files.len())
+            .unwrap_or(0)
     }
 
     /// Get descriptions of all operations in the undo stack
     pub fn undo_stack_descriptions(&self) -> Vec<String> {
-        self.get_stack()
+        self.entries()
             .unwrap_or_default()
-            .iter()
-            .map(|op| op.description())
+            .into_iter()
+            .map(|entry| entry.description)
             .collect()
     }
 
     /// Clear the undo state
     pub fn clear(&self) -> Result<()> {
-        if self.undo_file.exists() {
-            std::fs::remove_file(&self.undo_file)?;
+        if self.undo_dir.is_dir() {
+            std::fs::remove_dir_all(&self.undo_dir)?;
+        } else if self.undo_dir.exists() {
+            std::fs::remove_file(&self.undo_dir)?;
         }
         Ok(())
     }
 
     /// Execute undo of the last operation
     pub fn undo(&self) -> Result<String> {
-        let mut stack = self.get_stack()?;
-
-        let op = stack
+        let (path, entry) = self
+            .read_entries()?
             .pop()
             .ok_or_else(|| PeasError::Storage("Nothing to undo".to_string()))?;
 
-        let description = op.description();
-
-        match op {
-            UndoOperation::Create { file_path, .. } => {
-                // Undo create by deleting the file
-                if file_path.exists() {
-                    std::fs::remove_file(&file_path)?;
-                }
-            }
-            UndoOperation::Update {
-                file_path,
-                previous_content,
-                ..
-            } => {
-                // Undo update by restoring previous content
-                std::fs::write(&file_path, previous_content)?;
-            }
-            UndoOperation::Delete {
-                file_path,
-                previous_content,
-                ..
-            } => {
-                // Undo delete by recreating the file
-                if let Some(parent) = file_path.parent() {
-                    std::fs::create_dir_all(parent)?;
-                }
-                std::fs::write(&file_path, previous_content)?;
-            }
-            UndoOperation::Archive {
-                original_path,
-                archive_path,
-                ..
-            } => {
-                // Undo archive by moving back
-                if archive_path.exists() {
-                    std::fs::rename(&archive_path, &original_path)?;
-                }
-            }
-        }
-
-        // Save the updated stack (with the operation removed)
-        if stack.is_empty() {
-            self.clear()?;
-        } else {
-            self.save_stack(&stack)?;
-        }
+        let description = entry.operation.description();
+        entry.operation.revert()?;
+        std::fs::remove_file(&path)?;
 
         Ok(format!("Undone: {}", description))
     }
@@ -189,21 +453,26 @@ pub fn record_create(undo_manager: &UndoManager, id: &str, file_path: &Path) ->
 
 /// Helper to record an update operation (call before the update)
 pub fn record_update(undo_manager: &UndoManager, id: &str, file_path: &Path) -> Result<()> {
-    let previous_content = std::fs::read_to_string(file_path)?;
-    undo_manager.record(UndoOperation::Update {
-        id: id.to_string(),
-        file_path: file_path.to_path_buf(),
-        previous_content,
-    })
+    undo_manager.record(UndoOperation::update_from_file(id, file_path)?)
 }
 
 /// Helper to record a delete operation (call before the delete)
 pub fn record_delete(undo_manager: &UndoManager, id: &str, file_path: &Path) -> Result<()> {
-    let previous_content = std::fs::read_to_string(file_path)?;
-    undo_manager.record(UndoOperation::Delete {
-        id: id.to_string(),
-        file_path: file_path.to_path_buf(),
-        previous_content,
+    undo_manager.record(UndoOperation::delete_from_file(id, file_path)?)
+}
+
+/// Helper to record several operations as a single undo entry
+pub fn record_batch(
+    undo_manager: &UndoManager,
+    description: &str,
+    operations: Vec<UndoOperation>,
+) -> Result<()> {
+    if operations.is_empty() {
+        return Ok(());
+    }
+    undo_manager.record(UndoOperation::Batch {
+        description: description.to_string(),
+        operations,
     })
 }
 
@@ -370,6 +639,30 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&file).unwrap(), "original content");
     }
 
+    #[test]
+    fn test_undo_update_removes_renamed_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let undo_manager = UndoManager::new(temp_dir.path());
+
+        let old = temp_dir.path().join("peas-abc12--old-title.md");
+        let renamed = temp_dir.path().join("peas-abc12--new-title.md");
+        let other = temp_dir.path().join("peas-abc123--other.md");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&other, "other").unwrap();
+        undo_manager
+            .record(UndoOperation::update_from_file("peas-abc12", &old).unwrap())
+            .unwrap();
+
+        // A title change writes the new file and removes the old one
+        std::fs::write(&renamed, "new").unwrap();
+        std::fs::remove_file(&old).unwrap();
+
+        undo_manager.undo().unwrap();
+        assert_eq!(std::fs::read_to_string(&old).unwrap(), "old");
+        assert!(!renamed.exists());
+        assert!(other.exists());
+    }
+
     #[test]
     fn test_empty_undo_stack() {
         let temp_dir = TempDir::new().unwrap();
@@ -381,6 +674,87 @@ mod tests {
         assert!(undo_manager.undo().is_err());
     }
 
+    #[test]
+    fn test_prune_by_count_and_age() {
+        let temp_dir = TempDir::new().unwrap();
+        let undo_manager = UndoManager::new(temp_dir.path()).with_settings(UndoSettings {
+            max_entries: 2,
+            max_age_days: 7,
+        });
+        let create = |id: &str| UndoOperation::Create {
+            id: id.to_string(),
+            file_path: temp_dir.path().join(id),
+        };
+
+        for id in ["id1", "id2", "id3"] {
+            undo_manager.record(create(id)).unwrap();
+        }
+        assert_eq!(
+            undo_manager.undo_stack_descriptions(),
+            ["Create id2", "Create id3"]
+        );
+
+        // Age the oldest step past the limit
+        let (_, path) = undo_manager.entry_files().unwrap().remove(0);
+        let mut entry: UndoEntry =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        entry.recorded = Utc::now() - Duration::days(8);
+        std::fs::write(&path, serde_json::to_string(&entry).unwrap()).unwrap();
+        assert_eq!(undo_manager.prune().unwrap(), 1);
+        assert_eq!(undo_manager.undo_stack_descriptions(), ["Create id3"]);
+
+        let entry = undo_manager.last_entry().unwrap().unwrap();
+        assert_eq!(entry.ids, ["id3"]);
+    }
+
+    #[test]
+    fn test_migrate_legacy_undo_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let stack = vec![
+            UndoOperation::Create {
+                id: "id1".to_string(),
+                file_path: temp_dir.path().join("id1"),
+            },
+            UndoOperation::Batch {
+                description: "Both".to_string(),
+                operations: vec![
+                    UndoOperation::Create {
+                        id: "id2".to_string(),
+                        file_path: temp_dir.path().join("id2"),
+                    },
+                    UndoOperation::Create {
+                        id: "id3".to_string(),
+                        file_path: temp_dir.path().join("id3"),
+                    },
+                ],
+            },
+        ];
+        std::fs::write(
+            temp_dir.path().join(UNDO_DIR),
+            serde_json::to_string(&stack).unwrap(),
+        )
+        .unwrap();
+
+        let undo_manager = UndoManager::new(temp_dir.path());
+        assert_eq!(undo_manager.undo_count(), 2);
+        assert!(temp_dir.path().join(UNDO_DIR).is_dir());
+        let entry = undo_manager.last_entry().unwrap().unwrap();
+        assert_eq!(entry.description, "Both");
+        assert_eq!(entry.ids, ["id2", "id3"]);
+
+        // New steps go after the migrated ones
+        undo_manager
+            .record(UndoOperation::Create {
+                id: "id4".to_string(),
+                file_path: temp_dir.path().join("id4"),
+            })
+            .unwrap();
+        assert_eq!(
+            undo_manager.undo_stack_descriptions(),
+            ["Create id1", "Both", "Create id4"]
+        );
+    }
+
     #[test]
     fn test_undo_delete_restores_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -503,4 +877,46 @@ mod tests {
         assert_eq!(op.id(), "peas-xyz");
         assert_eq!(op.description(), "Archive peas-xyz");
     }
+
+    #[test]
+    fn test_undo_batch_reverts_all_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let undo_manager = UndoManager::new(temp_dir.path());
+
+        let file1 = temp_dir.path().join("a.md");
+        let file2 = temp_dir.path().join("b.md");
+        std::fs::write(&file1, "a original").unwrap();
+        std::fs::write(&file2, "b original").unwrap();
+
+        let ops = vec![
+            UndoOperation::update_from_file("peas-a", &file1).unwrap(),
+            UndoOperation::delete_from_file("peas-b", &file2).unwrap(),
+        ];
+        record_batch(&undo_manager, "Bulk edit 2 tickets", ops).unwrap();
+
+        std::fs::write(&file1, "a changed").unwrap();
+        std::fs::remove_file(&file2).unwrap();
+
+        // One entry for the whole batch
+        assert_eq!(undo_manager.undo_count(), 1);
+        assert_eq!(
+            undo_manager.undo_stack_descriptions(),
+            vec!["Bulk edit 2 tickets"]
+        );
+
+        let result = undo_manager.undo().unwrap();
+        assert!(result.contains("Bulk edit 2 tickets"));
+        assert_eq!(std::fs::read_to_string(&file1).unwrap(), "a original");
+        assert_eq!(std::fs::read_to_string(&file2).unwrap(), "b original");
+        assert_eq!(undo_manager.undo_count(), 0);
+    }
+
+    #[test]
+    fn test_record_empty_batch_is_noop() {
+        let temp_dir = TempDir::new().unwrap();
+        let undo_manager = UndoManager::new(temp_dir.path());
+
+        record_batch(&undo_manager, "nothing", Vec::new()).unwrap();
+        assert_eq!(undo_manager.undo_count(), 0);
+    }
 }
@@ -1,6 +1,14 @@
 //! Input validation for pea data.
+//!
+//! Titles, bodies and tags go through a [`Validator`] built from the
+//! `[validation]` config section, so the CLI, the TUI and GraphQL accept the
+//! same input. The free functions check against the default limits.
 
-use crate::error::{PeasError, Result};
+use crate::config::ValidationSettings;
+use crate::error::{Constraint, PeasError, Result};
+use crate::model::{Pea, PeaType};
+use chrono::NaiveDate;
+use regex::Regex;
 
 /// Maximum allowed length for a pea title.
 pub const MAX_TITLE_LENGTH: usize = 200;
@@ -8,13 +16,172 @@ pub const MAX_TITLE_LENGTH: usize = 200;
 /// Maximum allowed length for a pea body.
 pub const MAX_BODY_LENGTH: usize = 50_000;
 
+/// Maximum allowed length for a tag.
+pub const MAX_TAG_LENGTH: usize = 50;
+
 /// Maximum allowed length for a pea ID.
 pub const MAX_ID_LENGTH: usize = 50;
 
 /// Characters forbidden in IDs to prevent path traversal.
 const FORBIDDEN_ID_CHARS: &[char] = &['/', '\\', '\0'];
 
-/// Validates a pea title.
+/// Bidirectional embeddings, overrides and isolates, which make text display
+/// in a different order than it is stored ("Trojan Source")
+const BIDI_CONTROLS: &[char] = &[
+    '\u{202A}', '\u{202B}', '\u{202C}', '\u{202D}', '\u{202E}', '\u{2066}', '\u{2067}', '\u{2068}',
+    '\u{2069}',
+];
+
+/// Checks titles, bodies and tags against the configured limits.
+///
+/// ```
+/// use peas::config::ValidationSettings;
+/// use peas::validation::Validator;
+///
+/// let validator = Validator::new(&ValidationSettings {
+///     max_title_length: 20,
+///     tag_pattern: Some("[a-z0-9-]+".into()),
+///     ..Default::default()
+/// });
+/// assert!(validator.title("Fix the login bug").is_ok());
+/// assert!(validator.title("Fix the login bug on Safari").is_err());
+/// assert!(validator.tag("needs-review").is_ok());
+/// assert!(validator.tag("Needs Review").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Validator {
+    max_title_length: usize,
+    max_body_length: usize,
+    max_tag_length: usize,
+    tag_pattern: Option<(String, Regex)>,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            max_title_length: MAX_TITLE_LENGTH,
+            max_body_length: MAX_BODY_LENGTH,
+            max_tag_length: MAX_TAG_LENGTH,
+            tag_pattern: None,
+        }
+    }
+}
+
+impl Validator {
+    /// Build from the `[validation]` section; a tag pattern that doesn't
+    /// compile is ignored, as config validation already reported it
+    pub fn new(settings: &ValidationSettings) -> Self {
+        Self {
+            max_title_length: settings.max_title_length,
+            max_body_length: settings.max_body_length,
+            max_tag_length: settings.max_tag_length,
+            tag_pattern: settings.tag_pattern.as_ref().and_then(|pattern| {
+                let regex = Regex::new(&format!("^(?:{})$", pattern)).ok()?;
+                Some((pattern.clone(), regex))
+            }),
+        }
+    }
+
+    /// Titles must be non-empty, single-line and within `max_title_length` characters.
+    pub fn title(&self, title: &str) -> Result<()> {
+        if title.is_empty() {
+            return Err(PeasError::validation("title", "Title cannot be empty")
+                .with_constraint(Constraint::Required, title));
+        }
+        if title.chars().count() > self.max_title_length {
+            return Err(PeasError::validation(
+                "title",
+                format!(
+                    "Title exceeds maximum length of {} characters",
+                    self.max_title_length
+                ),
+            )
+            .with_constraint(Constraint::MaxLength(self.max_title_length), title));
+        }
+        check_chars("title", "Title", title, false)
+    }
+
+    /// Bodies must be within `max_body_length` characters; line breaks and tabs are fine.
+    pub fn body(&self, body: &str) -> Result<()> {
+        if body.chars().count() > self.max_body_length {
+            return Err(PeasError::validation(
+                "body",
+                format!(
+                    "Body exceeds maximum length of {} characters",
+                    self.max_body_length
+                ),
+            )
+            .with_constraint(Constraint::MaxLength(self.max_body_length), body));
+        }
+        check_chars("body", "Body", body, true)
+    }
+
+    /// Tags must be non-empty, within `max_tag_length` characters, free of
+    /// commas, which separate tags in lists, and match `tag_pattern` if set.
+    pub fn tag(&self, tag: &str) -> Result<()> {
+        if tag.is_empty() {
+            return Err(PeasError::validation("tags", "Tag cannot be empty")
+                .with_constraint(Constraint::Required, tag));
+        }
+        if tag.chars().count() > self.max_tag_length {
+            return Err(PeasError::validation(
+                "tags",
+                format!(
+                    "Tag exceeds maximum length of {} characters",
+                    self.max_tag_length
+                ),
+            )
+            .with_constraint(Constraint::MaxLength(self.max_tag_length), tag));
+        }
+        check_chars("tags", "Tag", tag, false)?;
+        if tag.contains(',') {
+            return Err(PeasError::validation(
+                "tags",
+                format!("Tag '{}' cannot contain a comma", tag),
+            )
+            .with_constraint(Constraint::ForbiddenCharacter(','), tag));
+        }
+        if let Some((pattern, regex)) = &self.tag_pattern
+            && !regex.is_match(tag)
+        {
+            return Err(PeasError::validation(
+                "tags",
+                format!("Tag '{}' does not match the pattern '{}'", tag, pattern),
+            )
+            .with_constraint(Constraint::Pattern(pattern.clone()), tag));
+        }
+        Ok(())
+    }
+
+    /// Check the title, body and tags of a pea.
+    pub fn pea(&self, pea: &Pea) -> Result<()> {
+        self.title(&pea.title)?;
+        self.body(&pea.body)?;
+        pea.tags.iter().try_for_each(|tag| self.tag(tag))
+    }
+}
+
+/// Rejects control characters (except line breaks and tabs when
+/// `multiline`) and bidirectional overrides.
+fn check_chars(field: &'static str, label: &str, text: &str, multiline: bool) -> Result<()> {
+    for c in text.chars() {
+        let what = if BIDI_CONTROLS.contains(&c) {
+            "a bidirectional override"
+        } else if c.is_control() && !(multiline && matches!(c, '\n' | '\r' | '\t')) {
+            "a control character"
+        } else {
+            continue;
+        };
+        return Err(PeasError::validation(
+            field,
+            format!("{} contains {} (U+{:04X})", label, what, c as u32),
+        )
+        .with_constraint(Constraint::ForbiddenCharacter(c), text));
+    }
+    Ok(())
+}
+
+/// Validates a pea title against the default limits.
 ///
 /// Titles must be non-empty and at most [`MAX_TITLE_LENGTH`] characters.
 ///
@@ -26,27 +193,58 @@ const FORBIDDEN_ID_CHARS: &[char] = &['/', '\\', '\0'];
 /// assert!(validate_title(&"a".repeat(201)).is_err());
 /// ```
 pub fn validate_title(title: &str) -> Result<()> {
-    if title.is_empty() {
-        return Err(PeasError::Validation("Title cannot be empty".to_string()));
-    }
-    if title.len() > MAX_TITLE_LENGTH {
-        return Err(PeasError::Validation(format!(
-            "Title exceeds maximum length of {} characters",
-            MAX_TITLE_LENGTH
-        )));
-    }
-    Ok(())
+    Validator::default().title(title)
 }
 
-/// Validates a pea body.
+/// Validates a pea body against the default limits.
 pub fn validate_body(body: &str) -> Result<()> {
-    if body.len() > MAX_BODY_LENGTH {
-        return Err(PeasError::Validation(format!(
-            "Body exceeds maximum length of {} characters",
-            MAX_BODY_LENGTH
-        )));
+    Validator::default().body(body)
+}
+
+/// Cleans up pasted text for use in a body.
+///
+/// Line endings become `\n`, terminal escape sequences are removed and other
+/// control characters except tabs are dropped.
+///
+/// ```
+/// use peas::validation::sanitize_pasted;
+///
+/// assert_eq!(sanitize_pasted("\x1b[31mred\x1b[0m\r\nnext\x07"), "red\nnext");
+/// ```
+pub fn sanitize_pasted(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                out.push('\n');
+            }
+            '\n' | '\t' => out.push(c),
+            '\x1b' => match chars.next() {
+                // CSI, e.g. colors: parameters up to a final byte
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC, e.g. hyperlinks: up to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
     }
-    Ok(())
+    out
 }
 
 /// Validates a pea ID to prevent path traversal attacks.
@@ -64,28 +262,35 @@ pub fn validate_body(body: &str) -> Result<()> {
 /// ```
 pub fn validate_id(id: &str) -> Result<()> {
     if id.is_empty() {
-        return Err(PeasError::Validation("ID cannot be empty".to_string()));
+        return Err(PeasError::validation("id", "ID cannot be empty")
+            .with_constraint(Constraint::Required, id));
     }
     if id.len() > MAX_ID_LENGTH {
-        return Err(PeasError::Validation(format!(
-            "ID exceeds maximum length of {} characters",
-            MAX_ID_LENGTH
-        )));
+        return Err(PeasError::validation(
+            "id",
+            format!("ID exceeds maximum length of {} characters", MAX_ID_LENGTH),
+        )
+        .with_constraint(Constraint::MaxLength(MAX_ID_LENGTH), id));
     }
     if id.contains("..") {
-        return Err(PeasError::Validation(
+        return Err(PeasError::validation(
+            "id",
             "ID cannot contain '..' (path traversal)".to_string(),
         ));
     }
     for c in FORBIDDEN_ID_CHARS {
         if id.contains(*c) {
-            return Err(PeasError::Validation(format!("ID cannot contain '{}'", c)));
+            return Err(
+                PeasError::validation("id", format!("ID cannot contain '{}'", c))
+                    .with_constraint(Constraint::ForbiddenCharacter(*c), id),
+            );
         }
     }
     // Check for URL-encoded path traversal sequences
     let lower = id.to_lowercase();
     if lower.contains("%2f") || lower.contains("%5c") || lower.contains("%2e%2e") {
-        return Err(PeasError::Validation(
+        return Err(PeasError::validation(
+            "id",
             "ID cannot contain URL-encoded path separators or traversal sequences".to_string(),
         ));
     }
@@ -97,31 +302,31 @@ pub fn validate_id(id: &str) -> Result<()> {
 pub fn validate_path_within(path: &std::path::Path, sandbox: &std::path::Path) -> Result<()> {
     // Canonicalize sandbox (must exist)
     let sandbox_canonical = sandbox.canonicalize().map_err(|_| {
-        PeasError::Validation(format!(
-            "Sandbox directory does not exist: {}",
-            sandbox.display()
-        ))
+        PeasError::validation(
+            "path",
+            format!("Sandbox directory does not exist: {}", sandbox.display()),
+        )
     })?;
 
     // For paths that exist, canonicalize and check containment
     if path.exists() {
         let path_canonical = path.canonicalize().map_err(|_| {
-            PeasError::Validation(format!("Cannot resolve path: {}", path.display()))
+            PeasError::validation("path", format!("Cannot resolve path: {}", path.display()))
         })?;
         if !path_canonical.starts_with(&sandbox_canonical) {
-            return Err(PeasError::Validation(format!(
-                "Path '{}' escapes the project directory",
-                path.display()
-            )));
+            return Err(PeasError::validation(
+                "path",
+                format!("Path '{}' escapes the project directory", path.display()),
+            ));
         }
     }
 
     Ok(())
 }
 
-/// Validates a tag name.
+/// Validates a tag name against the default limits.
 ///
-/// Tags must be non-empty and at most 50 characters.
+/// Tags must be non-empty and at most [`MAX_TAG_LENGTH`] characters.
 ///
 /// ```
 /// use peas::validation::validate_tag;
@@ -131,12 +336,47 @@ pub fn validate_path_within(path: &std::path::Path, sandbox: &std::path::Path) -
 /// assert!(validate_tag(&"x".repeat(51)).is_err());
 /// ```
 pub fn validate_tag(tag: &str) -> Result<()> {
-    if tag.is_empty() {
-        return Err(PeasError::Validation("Tag cannot be empty".to_string()));
-    }
-    if tag.len() > 50 {
-        return Err(PeasError::Validation(
-            "Tag exceeds maximum length of 50 characters".to_string(),
+    Validator::default().tag(tag)
+}
+
+/// Parses a `YYYY-MM-DD` target date.
+///
+/// ```
+/// use peas::validation::parse_target_date;
+///
+/// assert!(parse_target_date("2024-03-01").is_ok());
+/// assert!(parse_target_date("March 1st").is_err());
+/// ```
+pub fn parse_target_date(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        PeasError::validation(
+            "target_date",
+            format!("Invalid target date '{}', expected YYYY-MM-DD", value),
+        )
+        .with_constraint(Constraint::Format("YYYY-MM-DD"), value)
+    })
+}
+
+/// Parses a `YYYY-MM-DD` defer-until date.
+pub fn parse_defer_until(value: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        PeasError::validation(
+            "defer_until",
+            format!("Invalid defer-until date '{}', expected YYYY-MM-DD", value),
+        )
+        .with_constraint(Constraint::Format("YYYY-MM-DD"), value)
+    })
+}
+
+/// Validates that only milestones carry a target date.
+pub fn validate_target_date(pea_type: PeaType, target_date: Option<NaiveDate>) -> Result<()> {
+    if target_date.is_some() && pea_type != PeaType::Milestone {
+        return Err(PeasError::validation(
+            "target_date",
+            format!(
+                "Target dates are only supported on milestones, not on type '{}'",
+                pea_type
+            ),
         ));
     }
     Ok(())
@@ -151,10 +391,11 @@ where
     if let Some(parent_id) = parent
         && !exists_fn(parent_id)
     {
-        return Err(PeasError::Validation(format!(
-            "Parent pea '{}' does not exist",
-            parent_id
-        )));
+        return Err(PeasError::validation(
+            "parent",
+            format!("Parent pea '{}' does not exist", parent_id),
+        )
+        .with_constraint(Constraint::Exists, parent_id));
     }
     Ok(())
 }
@@ -164,7 +405,8 @@ pub fn validate_no_self_parent(id: &str, parent: &Option<String>) -> Result<()>
     if let Some(parent_id) = parent
         && id == parent_id
     {
-        return Err(PeasError::Validation(
+        return Err(PeasError::validation(
+            "parent",
             "A pea cannot be its own parent".to_string(),
         ));
     }
@@ -174,7 +416,8 @@ pub fn validate_no_self_parent(id: &str, parent: &Option<String>) -> Result<()>
 /// Validates that blocking relationships don't contain the pea's own ID.
 pub fn validate_no_self_blocking(id: &str, blocking: &[String]) -> Result<()> {
     if blocking.contains(&id.to_string()) {
-        return Err(PeasError::Validation(
+        return Err(PeasError::validation(
+            "blocking",
             "A pea cannot block itself".to_string(),
         ));
     }
@@ -188,10 +431,11 @@ where
 {
     for blocked_id in blocking {
         if !exists_fn(blocked_id) {
-            return Err(PeasError::Validation(format!(
-                "Blocked pea '{}' does not exist",
-                blocked_id
-            )));
+            return Err(PeasError::validation(
+                "blocking",
+                format!("Blocked pea '{}' does not exist", blocked_id),
+            )
+            .with_constraint(Constraint::Exists, blocked_id));
         }
     }
     Ok(())
@@ -215,10 +459,13 @@ where
 
         loop {
             if current == id {
-                return Err(PeasError::Validation(format!(
-                    "Setting '{}' as parent would create a circular relationship",
-                    parent_id
-                )));
+                return Err(PeasError::validation(
+                    "parent",
+                    format!(
+                        "Setting '{}' as parent would create a circular relationship",
+                        parent_id
+                    ),
+                ));
             }
 
             visited.insert(current.clone());
@@ -227,10 +474,13 @@ where
                 Some(next_parent) => {
                     if visited.contains(&next_parent) {
                         // Cycle detected in existing data (shouldn't happen but be safe)
-                        return Err(PeasError::Validation(format!(
-                            "Circular parent relationship detected in existing data involving '{}'",
-                            current
-                        )));
+                        return Err(PeasError::validation(
+                            "parent",
+                            format!(
+                                "Circular parent relationship detected in existing data involving '{}'",
+                                current
+                            ),
+                        ));
                     }
                     current = next_parent;
                 }
@@ -333,6 +583,16 @@ mod tests {
         assert!(validate_title(&"a".repeat(MAX_TITLE_LENGTH + 1)).is_err());
     }
 
+    #[test]
+    fn test_sanitize_pasted() {
+        assert_eq!(sanitize_pasted("a\rb\r\nc\td"), "a\nb\nc\td");
+        assert_eq!(
+            sanitize_pasted("\x1b]8;;https://x.io\x1b\\link\x1b]8;;\x07 \x00done\x1bc"),
+            "link done"
+        );
+        assert_eq!(sanitize_pasted("ünïcødé ✓"), "ünïcødé ✓");
+    }
+
     #[test]
     fn test_validate_body_at_boundary() {
         assert!(validate_body(&"a".repeat(MAX_BODY_LENGTH)).is_ok());
@@ -398,4 +658,31 @@ mod tests {
             validate_no_circular_parent("peas-1", &Some("peas-1".to_string()), get_parent).is_err()
         );
     }
+
+    #[test]
+    fn test_control_and_bidi_characters() {
+        assert!(validate_title("Fix\u{7}bell").is_err());
+        assert!(validate_title("Line\nbreak").is_err());
+        assert!(validate_body("Line\r\nbreak\tand tab").is_ok());
+        let err = validate_body("access = \u{202E}nimda\u{2066}").unwrap_err();
+        assert!(err.to_string().contains("bidirectional override (U+202E)"));
+        assert!(validate_tag("a\u{2067}b").is_err());
+        assert!(validate_tag("a,b").is_err());
+    }
+
+    #[test]
+    fn test_configured_limits_count_characters() {
+        let validator = Validator::new(&ValidationSettings {
+            max_title_length: 3,
+            max_body_length: 4,
+            max_tag_length: 2,
+            tag_pattern: Some("[a-z]+|v[0-9]".into()),
+        });
+        assert!(validator.title("äöü").is_ok());
+        assert!(validator.title("äöüß").is_err());
+        assert!(validator.body("12345").is_err());
+        assert!(validator.tag("v2").is_ok());
+        assert!(validator.tag("ab").is_ok());
+        assert!(validator.tag("a1").is_err());
+    }
 }
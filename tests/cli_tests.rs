@@ -6,6 +6,67 @@ fn peas_cmd() -> Command {
     Command::new(assert_cmd::cargo::cargo_bin!("peas"))
 }
 
+/// `git` with a fixed identity, for tests that need a repository
+fn git_cmd(dir: &std::path::Path) -> std::process::Command {
+    let mut cmd = std::process::Command::new("git");
+    cmd.args(["-c", "user.name=peas", "-c", "user.email=peas@example.com"])
+        .current_dir(dir);
+    cmd
+}
+
+/// `peas serve` on a free port, stopped when dropped (even if the test fails)
+struct Server {
+    child: std::process::Child,
+    port: u16,
+}
+
+impl Server {
+    fn start(dir: &std::path::Path, args: &[&str]) -> Self {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let child = std::process::Command::new(assert_cmd::cargo::cargo_bin!("peas"))
+            .args(["serve", "--port", &port.to_string()])
+            .args(args)
+            .current_dir(dir)
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .unwrap();
+        let server = Self { child, port };
+        let started = std::time::Instant::now();
+        while std::net::TcpStream::connect(("127.0.0.1", port)).is_err() {
+            assert!(started.elapsed().as_secs() < 20, "server did not start");
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+        server
+    }
+
+    /// Plain HTTP GET returning the whole response, status line included
+    fn get(&self, path: &str) -> String {
+        use std::io::{Read, Write};
+        let mut stream = std::net::TcpStream::connect(("127.0.0.1", self.port)).unwrap();
+        write!(
+            stream,
+            "GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+            path
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
 // =============================================================================
 // Basic CLI
 // =============================================================================
@@ -43,6 +104,37 @@ fn test_not_initialized_error() {
         );
 }
 
+#[test]
+fn test_exit_codes_by_error_category() {
+    let temp_dir = TempDir::new().unwrap();
+
+    // Not initialized: configuration error
+    peas_cmd()
+        .arg("list")
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(7);
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["show", "peas-nope"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Pea not found"));
+
+    peas_cmd()
+        .args(["create", ""])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+}
+
 // =============================================================================
 // Initialization
 // =============================================================================
@@ -167,6 +259,145 @@ fn test_list_filter_by_type() {
         .stdout(predicate::str::contains("Task One").not());
 }
 
+#[test]
+fn test_list_table_columns_and_sort() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success()
+    };
+    peas(&["init"]);
+    peas(&["create", "Beta", "-p", "low", "--tag", "api"]);
+    peas(&["create", "Alpha", "-p", "critical"]);
+
+    let stdout = |args: &[&str]| {
+        let output = peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let table = stdout(&[
+        "list",
+        "--sort",
+        "title",
+        "--columns",
+        "title,priority,tags",
+    ]);
+    let lines: Vec<&str> = table.lines().map(str::trim_end).collect();
+    assert_eq!(
+        lines,
+        [
+            "TITLE  PRIORITY  TAGS",
+            "Alpha  critical",
+            "Beta   low       api"
+        ]
+    );
+
+    let reversed = stdout(&["list", "--sort", "priority", "--reverse", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&reversed).unwrap();
+    assert_eq!(json[0]["title"], "Beta");
+
+    // Later keys break ties; a leading '-' sorts descending
+    peas(&["create", "Gamma", "-p", "low"]);
+    let sorted = stdout(&["list", "--sort", "priority,-title", "--json"]);
+    let json: serde_json::Value = serde_json::from_str(&sorted).unwrap();
+    let titles: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Alpha", "Gamma", "Beta"]);
+    peas_cmd()
+        .args(["list", "--sort", "priority,size"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(2);
+    peas_cmd()
+        .args(["list", "--columns", "id,owner"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_list_group_by() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success()
+    };
+    peas(&["init"]);
+    peas(&["create", "Both", "--tag", "api", "--tag", "ui"]);
+    peas(&["create", "Api only", "--tag", "api", "-s", "in-progress"]);
+    peas(&["create", "Loose"]);
+
+    let output = peas_cmd()
+        .args(["list", "--group-by", "tag", "--columns", "title"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        text,
+        "TITLE\n\napi (2)\nBoth\nApi only\n\nui (1)\nBoth\n\nuntagged (1)\nLoose\n"
+    );
+
+    let output = peas_cmd()
+        .args(["list", "--group-by", "status", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["group_by"], "status");
+    assert_eq!(json["groups"][0]["key"], "todo");
+    assert_eq!(json["groups"][0]["count"], 2);
+    assert_eq!(json["groups"][1]["key"], "in-progress");
+    assert_eq!(json["groups"][1]["peas"][0]["title"], "Api only");
+}
+
+#[test]
+fn test_list_count_and_summary() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success()
+    };
+    peas(&["init"]);
+    peas(&["create", "One", "-t", "bug"]);
+    peas(&["create", "Two", "-s", "in-progress"]);
+    peas(&["create", "Three"]);
+
+    peas(&["list", "--count"]).stdout("3\n");
+    peas(&["list", "--count", "-s", "todo"]).stdout("2\n");
+    peas(&["list", "--summary"])
+        .stdout(predicate::str::contains("3 peas"))
+        .stdout(predicate::str::contains("Status: todo 2  in-progress 1"))
+        .stdout(predicate::str::contains("Type:   bug 1  task 2"));
+
+    let output = peas_cmd()
+        .args(["list", "--summary", "--json", "-t", "task"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["total"], 2);
+    assert_eq!(json["by_status"]["in-progress"], 1);
+    assert_eq!(json["by_type"]["task"], 2);
+    assert!(json["by_type"].get("bug").is_none());
+}
+
 #[test]
 fn test_show_pea() {
     let temp_dir = TempDir::new().unwrap();
@@ -227,12 +458,8 @@ fn test_search() {
         .stdout(predicate::str::contains("1 results"));
 }
 
-// =============================================================================
-// Update, Status Workflow
-// =============================================================================
-
 #[test]
-fn test_update_status() {
+fn test_find_duplicates() {
     let temp_dir = TempDir::new().unwrap();
 
     peas_cmd()
@@ -241,32 +468,114 @@ fn test_update_status() {
         .assert()
         .success();
 
+    for title in ["Fix the login bug", "fix login-bug", "Write release notes"] {
+        peas_cmd()
+            .args(["create", title])
+            .current_dir(temp_dir.path())
+            .assert()
+            .success();
+    }
+
     let output = peas_cmd()
-        .args(["create", "Update Test", "--json"])
+        .args(["find-duplicates", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let clusters: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(clusters.as_array().unwrap().len(), 1);
+    assert_eq!(clusters[0]["peas"].as_array().unwrap().len(), 2);
+
+    peas_cmd()
+        .args(["find-duplicates", "--threshold", "1.5"])
         .current_dir(temp_dir.path())
         .assert()
-        .success();
+        .failure();
+}
 
-    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
-    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
-    let id = json["id"].as_str().unwrap();
+#[test]
+fn test_tag_list_rename_delete() {
+    let temp_dir = TempDir::new().unwrap();
 
     peas_cmd()
-        .args(["update", id, "-s", "in-progress"])
+        .arg("init")
         .current_dir(temp_dir.path())
         .assert()
         .success();
 
+    for (title, tags) in [
+        ("First", &["frontend", "urgent"][..]),
+        ("Second", &["frontend", "ui"][..]),
+        ("Third", &["backend"][..]),
+    ] {
+        let mut args = vec!["create", title];
+        for tag in tags {
+            args.extend(["--tag", tag]);
+        }
+        peas_cmd()
+            .args(&args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success();
+    }
+
+    let list_tags = || -> serde_json::Value {
+        let output = peas_cmd()
+            .args(["tag", "list", "--json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let tags = list_tags();
+    assert_eq!(tags[0]["tag"], "frontend");
+    assert_eq!(tags[0]["count"], 2);
+
+    // Dry run changes nothing
     peas_cmd()
-        .args(["show", id, "--json"])
+        .args(["tag", "rename", "frontend", "ui", "--dry-run"])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("in-progress"));
+        .stdout(predicate::str::contains("Would rename"));
+    assert_eq!(list_tags()[0]["tag"], "frontend");
+
+    // Renaming onto an existing tag merges without duplicates
+    peas_cmd()
+        .args(["tag", "rename", "frontend", "ui"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let tags = list_tags();
+    assert_eq!(tags[0]["tag"], "ui");
+    assert_eq!(tags[0]["count"], 2);
+    assert!(
+        tags.as_array()
+            .unwrap()
+            .iter()
+            .all(|t| t["tag"] != "frontend")
+    );
+
+    let output = peas_cmd()
+        .args(["tag", "delete", "ui", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(result["updated"].as_array().unwrap().len(), 2);
+    assert_eq!(list_tags().as_array().unwrap().len(), 2);
+
+    peas_cmd()
+        .args(["tag", "rename", "backend", ""])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure();
 }
 
 #[test]
-fn test_start_and_done() {
+fn test_tag_policy() {
     let temp_dir = TempDir::new().unwrap();
 
     peas_cmd()
@@ -274,34 +583,52 @@ fn test_start_and_done() {
         .current_dir(temp_dir.path())
         .assert()
         .success();
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config.replace("tag_policy = \"any\"", "tag_policy = \"warn\"") + "\n[tags.ui]\n",
+    )
+    .unwrap();
 
-    let output = peas_cmd()
-        .args(["create", "Workflow Test", "--json"])
+    peas_cmd()
+        .args(["create", "Known", "--tag", "ui"])
         .current_dir(temp_dir.path())
         .assert()
-        .success();
-
-    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
-    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
-    let id = json["id"].as_str().unwrap();
+        .success()
+        .stderr(predicate::str::contains("new tag").not());
+    peas_cmd()
+        .args(["create", "Typo", "--tag", "uii"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("'uii' is a new tag"));
 
     peas_cmd()
-        .args(["start", id])
+        .args(["tag", "list", "--names"])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("in-progress"));
+        .stdout("ui\nuii\n");
 
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(&config_path, config.replace("\"warn\"", "\"strict\"")).unwrap();
     peas_cmd()
-        .args(["done", id])
+        .args(["create", "Rejected", "--tag", "uiii"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("allowed: ui"));
+    peas_cmd()
+        .args(["tag", "list", "--names"])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("completed"));
+        .stdout("ui\n");
 }
 
 #[test]
-fn test_archive() {
+fn test_milestone_target_date_list_and_close() {
     let temp_dir = TempDir::new().unwrap();
 
     peas_cmd()
@@ -310,44 +637,78 @@ fn test_archive() {
         .assert()
         .success();
 
-    let output = peas_cmd()
-        .args(["create", "Archive Test", "--json"])
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+
+    let milestone = create(&["v1.0", "-t", "milestone", "--target-date", "2020-01-31"]);
+    let task = create(&["Ship it", "--parent", &milestone]);
+    create(&["Docs", "--parent", &milestone, "-s", "completed"]);
+
+    // Only milestones take a target date
+    peas_cmd()
+        .args(["update", &task, "--target-date", "2020-02-01"])
         .current_dir(temp_dir.path())
         .assert()
-        .success();
+        .code(4);
 
-    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
-    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
-    let id = json["id"].as_str().unwrap();
+    let output = peas_cmd()
+        .args(["milestone", "list", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let list: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(list[0]["id"], milestone.as_str());
+    assert_eq!(list[0]["target_date"], "2020-01-31");
+    assert_eq!(list[0]["overdue"], true);
+    assert_eq!(list[0]["done"], 1);
+    assert_eq!(list[0]["total"], 2);
 
     peas_cmd()
-        .args(["archive", id])
+        .arg("roadmap")
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("Archived"));
+        .stdout(predicate::str::contains("**Target:** 2020-01-31"))
+        .stderr(predicate::str::contains("is overdue"));
 
     peas_cmd()
-        .arg("list")
+        .args(["milestone", "close", &milestone])
         .current_dir(temp_dir.path())
         .assert()
-        .success()
-        .stdout(predicate::str::contains("Archive Test").not());
+        .code(4)
+        .stderr(predicate::str::contains(task.as_str()));
 
     peas_cmd()
-        .args(["list", "--archived"])
+        .args(["done", &task])
         .current_dir(temp_dir.path())
         .assert()
-        .success()
-        .stdout(predicate::str::contains("Archive Test"));
-}
+        .success();
+    peas_cmd()
+        .args(["milestone", "close", &milestone])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
 
-// =============================================================================
-// GraphQL
-// =============================================================================
+    let output = peas_cmd()
+        .args(["milestone", "list", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let list: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(list.as_array().unwrap().is_empty());
+}
 
 #[test]
-fn test_graphql_query() {
+fn test_release_notes_since_date_and_tag() {
     let temp_dir = TempDir::new().unwrap();
 
     peas_cmd()
@@ -356,22 +717,91 @@ fn test_graphql_query() {
         .assert()
         .success();
 
+    let create = |args: &[&str]| {
+        peas_cmd()
+            .arg("create")
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success();
+    };
+    create(&["Add export", "-t", "feature", "-s", "completed"]);
+    create(&[
+        "Fix crash",
+        "-t",
+        "bug",
+        "-s",
+        "completed",
+        "--ref",
+        "https://github.com/o/r/issues/42",
+    ]);
+    create(&["Still open", "-t", "bug"]);
+
     peas_cmd()
-        .args(["create", "GraphQL Test"])
+        .args([
+            "release-notes",
+            "--since",
+            "2000-01-01",
+            "--heading",
+            "1.0.0",
+        ])
         .current_dir(temp_dir.path())
         .assert()
-        .success();
+        .success()
+        .stdout(predicate::str::contains("## 1.0.0"))
+        .stdout(predicate::str::contains("### Features\n\n- Add export"))
+        .stdout(predicate::str::contains("- Fix crash (peas-"))
+        .stdout(predicate::str::contains(
+            "[#42](https://github.com/o/r/issues/42)",
+        ))
+        .stdout(predicate::str::contains("Still open").not());
+
+    // Git refs resolve to their commit date
+    let git = |args: &[&str], date: &str| {
+        let status = git_cmd(temp_dir.path())
+            .args(args)
+            .env("GIT_COMMITTER_DATE", date)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+    git(&["init", "-q"], "");
+    git(
+        &["commit", "-q", "--allow-empty", "-m", "old"],
+        "2000-01-01T00:00:00Z",
+    );
+    git(&["tag", "v0.1"], "");
+    git(
+        &["commit", "-q", "--allow-empty", "-m", "new"],
+        "2099-01-01T00:00:00Z",
+    );
+    git(&["tag", "v9.9"], "");
 
     peas_cmd()
-        .args(["query", "{ stats { total } }"])
+        .args(["release-notes", "--since", "v0.1"])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("\"total\": 1"));
+        .stdout(predicate::str::contains("## Unreleased"))
+        .stdout(predicate::str::contains("Fix crash"));
+
+    peas_cmd()
+        .args(["release-notes", "--since", "v9.9", "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::diff("[]\n"));
+
+    peas_cmd()
+        .args(["release-notes", "--since", "no-such-tag"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Git error"));
 }
 
 #[test]
-fn test_graphql_mutate() {
+fn test_changelog_sync() {
     let temp_dir = TempDir::new().unwrap();
 
     peas_cmd()
@@ -380,98 +810,3372 @@ fn test_graphql_mutate() {
         .assert()
         .success();
 
+    let create = |title: &str| -> String {
+        let output = peas_cmd()
+            .args(["create", title, "-s", "completed", "--json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let shipped = create("Shipped");
+    let forgotten = create("Forgotten");
+
+    std::fs::write(
+        temp_dir.path().join("CHANGELOG.md"),
+        format!(
+            "# Changelog\n\n## [0.2.0] - 2026-10-01\n\n- Shipped ({})\n",
+            shipped
+        ),
+    )
+    .unwrap();
+
+    let output = peas_cmd()
+        .args(["changelog", "sync", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["annotated"][0]["id"], shipped.as_str());
+    assert_eq!(json["annotated"][0]["released_in"], "0.2.0");
+    assert_eq!(json["unreleased"], serde_json::json!([forgotten]));
+
     peas_cmd()
-        .args([
-            "mutate",
-            "createPea(input: { title: \"Mutation Test\", peaType: TASK }) { id title }",
-        ])
+        .args(["show", &shipped])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("Mutation Test"));
+        .stdout(predicate::str::contains("Released: 0.2.0"));
 
+    // Released peas are not drafted into the next release notes again
     peas_cmd()
-        .arg("list")
+        .args(["release-notes", "--since", "2000-01-01"])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("Mutation Test"));
-}
+        .stdout(predicate::str::contains("Forgotten"))
+        .stdout(predicate::str::contains("Shipped").not());
 
-// =============================================================================
-// LLM Context Commands
-// =============================================================================
+    peas_cmd()
+        .args(["changelog", "sync"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("up to date"))
+        .stdout(predicate::str::contains("1 completed pea(s) not mentioned"));
+}
 
 #[test]
-fn test_prime_command() {
+fn test_blame_timeline() {
     let temp_dir = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = git_cmd(temp_dir.path()).args(args).status().unwrap();
+        assert!(status.success());
+    };
 
     peas_cmd()
         .arg("init")
         .current_dir(temp_dir.path())
         .assert()
         .success();
+    let output = peas_cmd()
+        .args(["create", "Login crash", "-t", "bug", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
 
+    // Not a git repository yet
     peas_cmd()
-        .arg("prime")
+        .args(["blame", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Git error"));
+
+    git(&["init", "-q"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Track login crash"]);
+    peas_cmd()
+        .args(["start", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    git(&["commit", "-q", "-am", "Start on it"]);
+    peas_cmd()
+        .args(["update", &id, "-p", "high", "--add-tag", "auth"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["blame", &id, "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let entries: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entries.as_array().unwrap().len(), 3);
+    assert_eq!(entries[0]["author"], "peas");
+    assert_eq!(
+        entries[0]["changes"][0],
+        "created as bug (todo, normal priority)"
+    );
+    assert_eq!(entries[1]["subject"], "Start on it");
+    assert_eq!(entries[1]["changes"][0], "status: todo -> in-progress");
+    assert_eq!(entries[2]["commit"], serde_json::Value::Null);
+    assert_eq!(
+        entries[2]["changes"],
+        serde_json::json!(["priority: normal -> high", "tags: +auth"])
+    );
+
+    peas_cmd()
+        .args(["blame", &id])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("Peas - Issue Tracker"))
-        .stdout(predicate::str::contains("GraphQL Interface"));
+        .stdout(predicate::str::contains("Start on it"))
+        .stdout(predicate::str::contains("Uncommitted changes"));
 }
 
 #[test]
-fn test_context_command() {
+fn test_ci_summary_between_refs() {
     let temp_dir = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = git_cmd(temp_dir.path()).args(args).status().unwrap();
+        assert!(status.success());
+    };
+    let create = |title: &str| {
+        let output = peas_cmd()
+            .args(["create", title, "--json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
 
     peas_cmd()
         .arg("init")
         .current_dir(temp_dir.path())
         .assert()
         .success();
+    let fixed = create("Fix the login form");
+    let started = create("Write the migration guide");
+    git(&["init", "-q", "-b", "main"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Initial peas"]);
+
+    git(&["checkout", "-q", "-b", "feature"]);
+    let added = create("Add dark mode");
+    for args in [["done", &fixed], ["start", &started]] {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success();
+    }
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Work on the feature"]);
+
+    let output = peas_cmd()
+        .args(["ci-summary", "--base", "main", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["counts"],
+        serde_json::json!({ "created": 1, "completed": 1, "status": 1 })
+    );
+    assert_eq!(json["changes"][0]["id"], added.as_str());
 
     peas_cmd()
-        .args(["create", "Context Test"])
+        .args(["ci-summary", "--base", "main"])
         .current_dir(temp_dir.path())
         .assert()
-        .success();
+        .success()
+        .stdout(predicate::str::contains(
+            "1 created, 1 completed, 1 status changes",
+        ))
+        .stdout(predicate::str::contains(format!(
+            "- `{}` Fix the login form (todo → completed)",
+            fixed
+        )))
+        .stdout(predicate::str::contains(format!(
+            "- `{}` Write the migration guide (todo → in-progress)",
+            started
+        )));
 
+    // Nothing changed on the base branch itself
     peas_cmd()
-        .arg("context")
+        .args(["ci-summary", "--base", "main", "--head", "main"])
         .current_dir(temp_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("\"total\": 1"))
-        .stdout(predicate::str::contains("\"by_status\""));
+        .stdout(predicate::str::contains("No pea changes."));
 }
 
-// =============================================================================
-// Frontmatter Format
-// =============================================================================
-
 #[test]
-fn test_toml_frontmatter_default() {
+fn test_snapshots_and_list_as_of() {
     let temp_dir = TempDir::new().unwrap();
+    let list_as_of = |as_of: &str| -> serde_json::Value {
+        let output = peas_cmd()
+            .args(["list", "--as-of", as_of, "--json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
 
     peas_cmd()
         .arg("init")
         .current_dir(temp_dir.path())
         .assert()
         .success();
-
     let output = peas_cmd()
-        .args(["create", "TOML Test", "--json"])
+        .args(["create", "Ship the beta", "--json"])
         .current_dir(temp_dir.path())
-        .assert()
-        .success();
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
 
-    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
-    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
-    let id = json["id"].as_str().unwrap();
+    // Committed long ago, so git history answers for dates in between
+    let status = git_cmd(temp_dir.path())
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let status = git_cmd(temp_dir.path())
+        .args(["add", "."])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let status = git_cmd(temp_dir.path())
+        .args(["commit", "-q", "-m", "Plan the beta"])
+        .env("GIT_AUTHOR_DATE", "2020-01-01T12:00:00Z")
+        .env("GIT_COMMITTER_DATE", "2020-01-01T12:00:00Z")
+        .status()
+        .unwrap();
+    assert!(status.success());
 
-    let data_dir = temp_dir.path().join(".peas");
-    let entries: Vec<_> = std::fs::read_dir(&data_dir)
+    peas_cmd()
+        .args(["start", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["snapshot", "create", "sprint-1"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("snapshot sprint-1 of 1 pea(s)"));
+    peas_cmd()
+        .args(["snapshot", "create", "sprint-1"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+    peas_cmd()
+        .args(["done", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    assert_eq!(list_as_of("2020-06-01")[0]["status"], "todo");
+    assert_eq!(list_as_of("sprint-1")[0]["status"], "in-progress");
+    assert_eq!(list_as_of("2999-01-01")[0]["status"], "in-progress");
+    peas_cmd()
+        .args(["list", "--as-of", "2019-01-01"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+    peas_cmd()
+        .args(["list", "--as-of", "sprint-1"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("As of snapshot 'sprint-1'"));
+
+    let output = peas_cmd()
+        .args(["snapshot", "list", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let snapshots: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(snapshots[0]["label"], "sprint-1");
+    assert_eq!(snapshots[0]["peas"], 1);
+    assert_eq!(snapshots[0]["hash"].as_str().unwrap().len(), 64);
+}
+
+#[test]
+fn test_report_cfd_from_git_history() {
+    let temp_dir = TempDir::new().unwrap();
+    let commit = |message: &str, days_ago: i64| {
+        let date = (chrono::Utc::now() - chrono::Duration::days(days_ago)).to_rfc3339();
+        let status = git_cmd(temp_dir.path())
+            .args(["add", "."])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let status = git_cmd(temp_dir.path())
+            .args(["commit", "-q", "-m", message])
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_DATE", &date)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    };
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let status = git_cmd(temp_dir.path())
+        .args(["init", "-q"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let output = peas_cmd()
+        .args(["create", "Measure the flow", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+    commit("Plan", 10);
+    peas_cmd()
+        .args(["start", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    commit("Start", 3);
+    // Completed, but not committed yet
+    peas_cmd()
+        .args(["done", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["report", "cfd", "--weeks", "2", "--format", "json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let days: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let days = days.as_array().unwrap();
+    assert_eq!(days.len(), 14);
+    assert_eq!(days[0]["todo"], 0);
+    assert_eq!(days[5]["todo"], 1);
+    assert_eq!(days[11]["in-progress"], 1);
+    assert_eq!(days[13]["completed"], 1);
+    assert_eq!(days[13]["in-progress"], 0);
+
+    peas_cmd()
+        .args(["report", "cfd", "--weeks", "1", "--format", "csv"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "date,completed,scrapped,in-progress,todo,draft\n",
+        ));
+    peas_cmd()
+        .args(["report", "cfd"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("█ completed"));
+}
+
+#[test]
+fn test_stats_flow_metrics() {
+    let temp_dir = TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = git_cmd(temp_dir.path()).args(args).status().unwrap();
+        assert!(status.success());
+    };
+    let peas = |args: &[&str]| {
+        let output = peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        output.stdout
+    };
+    let create = |args: &[&str]| {
+        let args = [&["create"], args, &["--json"]].concat();
+        let json: serde_json::Value = serde_json::from_slice(&peas(&args)).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+
+    peas(&["init"]);
+    git(&["init", "-q"]);
+    let epic = create(&["Checkout revamp", "-t", "epic"]);
+    let started = create(&["Payment form", "--parent", &epic]);
+    let skipped = create(&["Receipt email", "--parent", &epic]);
+    create(&["Unrelated chore", "-t", "chore"]);
+    git(&["add", "."]);
+    git(&["commit", "-q", "-m", "Plan"]);
+    peas(&["start", &started]);
+    git(&["commit", "-q", "-am", "Start"]);
+    peas(&["done", &started]);
+    peas(&["done", &skipped]);
+
+    let json: serde_json::Value = serde_json::from_slice(&peas(&["stats", "--json"])).unwrap();
+    assert_eq!(json["total"], 4);
+    assert_eq!(json["by_status"]["completed"], 2);
+    assert_eq!(json["lead_time_days"]["count"], 2);
+    assert_eq!(json["cycle_time_days"]["count"], 1);
+    assert_eq!(json["epics"][0]["id"], epic.as_str());
+    assert_eq!(json["epics"][0]["lead_time_days"]["count"], 2);
+    let per_pea = json["peas"].as_array().unwrap();
+    let of = |id: &str| per_pea.iter().find(|p| p["id"] == id).unwrap();
+    assert!(of(&started)["cycle_time_days"].is_number());
+    assert!(of(&skipped)["cycle_time_days"].is_null());
+
+    let text = String::from_utf8(peas(&["stats"])).unwrap();
+    assert!(text.contains("Flow (2 completed)"), "{}", text);
+    assert!(text.contains("Checkout revamp (2 completed)"), "{}", text);
+}
+
+#[test]
+fn test_report_accuracy() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        let output = peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        output.stdout
+    };
+    let create = |args: &[&str]| {
+        let args = [&["create"], args, &["--json"]].concat();
+        let json: serde_json::Value = serde_json::from_slice(&peas(&args)).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+
+    // No git repository: completion times come from the files alone
+    peas(&["init"]);
+    let epic = create(&["Search", "-t", "epic"]);
+    let sized = create(&["Index titles", "--parent", &epic]);
+    let bug = create(&["Empty results", "-t", "bug"]);
+    let chore = create(&["Tidy up"]);
+    peas(&["size", &sized, "3"]);
+    peas(&["size", &bug, "1"]);
+    for id in [&sized, &bug, &chore] {
+        peas(&["done", id]);
+    }
+
+    let output = peas(&["report", "accuracy", "--format", "json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(json["scale"], "fibonacci");
+    assert_eq!(json["estimated"], 2);
+    assert_eq!(json["unestimated"], 1);
+    assert_eq!(json["by_size"][0]["group"], "1");
+    assert_eq!(json["by_size"][1]["group"], "3");
+    assert_eq!(json["by_type"][1]["group"], "task");
+    assert_eq!(json["by_type"][1]["points"], 3);
+    assert_eq!(json["by_epic"][0]["group"], epic.as_str());
+    assert_eq!(json["by_epic"][0]["peas"], 1);
+
+    let csv = String::from_utf8(peas(&["report", "accuracy", "--format", "csv"])).unwrap();
+    assert!(csv.starts_with("by,group,peas,points,"), "{}", csv);
+    assert!(csv.contains("\ntype,bug,1,1,"), "{}", csv);
+
+    let text = String::from_utf8(peas(&["report", "accuracy"])).unwrap();
+    assert!(text.contains("2 completed with a size"), "{}", text);
+    assert!(text.contains("By epic"), "{}", text);
+}
+
+#[test]
+fn test_open_targets() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["create", "Open me", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    // Editor by default
+    peas_cmd()
+        .args(["open", &id, "--print"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(&id).and(predicate::str::ends_with(".md\n")));
+
+    // The url target needs a template
+    peas_cmd()
+        .args(["open", &id, "--target", "url", "--print"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+    peas_cmd()
+        .args(["open", &id, "--target", "tui", "--print"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[open]\ntarget = \"url\"\nurl = \"http://localhost:4000/#/{id}\"\n");
+    std::fs::write(&config_path, config).unwrap();
+    peas_cmd()
+        .args(["open", &id, "--print"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(format!("http://localhost:4000/#/{}\n", id));
+
+    peas_cmd()
+        .args(["open", "peas-nope1", "--print"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure();
+
+    // Deep links are checked before the terminal is taken over
+    peas_cmd()
+        .args(["tui", "--filter", "regex:[bad"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn test_patch_sets_and_unsets_fields() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"][..], args].concat());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let epic = create(&["Epic", "-t", "epic"]);
+    let task = create(&["Task", "--parent", &epic]);
+
+    let output = peas(&[
+        "patch",
+        &task,
+        "--set",
+        "priority=high",
+        "--set",
+        "tags=ui,api",
+        "--unset",
+        "parent",
+        "--json",
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["pea"]["priority"], "high");
+    assert_eq!(json["pea"]["tags"], serde_json::json!(["ui", "api"]));
+    assert!(json["pea"]["parent"].is_null());
+    assert_eq!(json["changes"].as_array().unwrap().len(), 3);
+
+    // A dry run reports the change without writing it
+    let output = peas(&["patch", &task, "--set", "status=in-progress", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Would patch"));
+    let output = peas(&["show", &task, "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["status"], "todo");
+
+    // Unknown fields and malformed assignments are validation errors
+    assert_eq!(
+        peas(&["patch", &task, "--set", "owner=me"]).status.code(),
+        Some(4)
+    );
+    assert_eq!(
+        peas(&["patch", &task, "--set", "priority"]).status.code(),
+        Some(4)
+    );
+    assert_eq!(
+        peas(&["patch", &task, "--unset", "title"]).status.code(),
+        Some(4)
+    );
+}
+
+#[test]
+fn test_apply_json_records() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], stdin: &str| {
+        peas_cmd()
+            .args(args)
+            .write_stdin(stdin)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"], "").status.success());
+    let create = |title: &str| -> serde_json::Value {
+        serde_json::from_slice(&peas(&["create", title, "--json"], "").stdout).unwrap()
+    };
+    let login = create("Login form");
+    let login_id = login["id"].as_str().unwrap();
+    let mut signup = create("Sign up");
+    signup["priority"] = "high".into();
+
+    // NDJSON: a patch by ID, an edited full pea object and a new pea with a given ID
+    let input = format!(
+        "{}\n{}\n{}\n",
+        serde_json::json!({"id": login_id, "status": "in-progress"}),
+        signup,
+        serde_json::json!({"id": "peas-logout", "title": "Logout", "parent": login_id}),
+    );
+    let output = peas(&["apply", "--json"], &input);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let actions: Vec<&str> = json["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["action"].as_str().unwrap())
+        .collect();
+    assert_eq!(actions, vec!["update", "update", "create"]);
+    assert_eq!(json["applied"], true);
+
+    let show = |id: &str| -> serde_json::Value {
+        serde_json::from_slice(&peas(&["show", id, "--json"], "").stdout).unwrap()
+    };
+    assert_eq!(show(login_id)["status"], "in-progress");
+    assert_eq!(show(signup["id"].as_str().unwrap())["priority"], "high");
+    assert_eq!(show("peas-logout")["parent"], login_id);
+
+    // The same pea twice is rejected rather than applied in order
+    let input = format!("{}\n{}", login, login);
+    assert_eq!(peas(&["apply"], &input).status.code(), Some(4));
+
+    // One invalid record applies nothing
+    let input = format!(
+        "[{}, {}]",
+        serde_json::json!({"id": login_id, "priority": "high"}),
+        serde_json::json!({"title": "Orphan", "parent": "peas-gone1"}),
+    );
+    let output = peas(&["apply", "--json"], &input);
+    assert_eq!(output.status.code(), Some(4));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["results"][0]["error"].is_null());
+    assert!(
+        json["results"][1]["error"]
+            .as_str()
+            .unwrap()
+            .contains("peas-gone1")
+    );
+    assert_eq!(show(login_id)["priority"], "normal");
+
+    // A single undo entry reverts the whole apply
+    let output = peas(&["undo"], "");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Apply 3 records"));
+    assert!(peas(&["show", "peas-logout"], "").status.code() == Some(3));
+}
+
+#[test]
+fn test_dry_run_for_mutating_commands() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let create = |title: &str| {
+        let output = peas(&["create", title, "--json"]);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let first = create("First");
+    let second = create("Second");
+    let status = |id: &str| {
+        let output = peas(&["show", id, "--json"]);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["status"].as_str().unwrap().to_string()
+    };
+
+    let output = peas(&["start", &first, "--dry-run", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["after"]["status"], "in-progress");
+    assert_eq!(status(&first), "todo");
+
+    let output = peas(&["done", &first, "--dry-run"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Would update"));
+    assert_eq!(status(&first), "todo");
+
+    let output = peas(&["bulk", "tag", "ui", &first, &second, "--dry-run", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["would_update"].as_array().unwrap().len(), 2);
+    let output = peas(&["list", "--tag", "ui", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.as_array().unwrap().is_empty());
+
+    let output = peas(&["delete", &first, "--dry-run"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Would delete"));
+    let output = peas(&["archive", &second, "--dry-run", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["would_archive"], second.as_str());
+    let output = peas(&["list", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    // Dry runs fail where the real command would
+    assert_eq!(
+        peas(&["delete", "peas-gone1", "--dry-run"]).status.code(),
+        Some(3)
+    );
+}
+
+#[test]
+fn test_bulk_scrap_guard_rails() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], stdin: &str| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"], "").status.success());
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(&config_path, config + "\n[bulk]\nmax_destructive = 2\n").unwrap();
+    let ids: Vec<String> = ["One", "Two", "Three"]
+        .iter()
+        .map(|title| {
+            let output = peas(&["create", title, "--json"], "");
+            let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+            json["id"].as_str().unwrap().to_string()
+        })
+        .collect();
+    let scrapped = || {
+        let output = peas(&["list", "--status", "scrapped", "--json"], "");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json.as_array().unwrap().len()
+    };
+    let scrap = |extra: &[&str], stdin: &str| {
+        let mut args = vec!["bulk", "status", "scrapped"];
+        args.extend(ids.iter().map(String::as_str));
+        args.extend(extra);
+        peas(&args, stdin)
+    };
+
+    // Over the limit: refused, even when confirmed
+    let output = scrap(&["--yes"], "");
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+
+    // A summary and a prompt; anything but y aborts
+    let output = scrap(&["--force"], "n\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Three"));
+    assert!(stdout.contains("Aborted."));
+    assert_eq!(scrapped(), 0);
+
+    assert!(scrap(&["--force"], "y\n").status.success());
+    assert_eq!(scrapped(), 3);
+
+    // JSON output skips the prompt but not the limit; other statuses are not guarded
+    assert_eq!(scrap(&["--json"], "").status.code(), Some(4));
+    let mut args = vec!["bulk", "status", "todo"];
+    args.extend(ids.iter().map(String::as_str));
+    assert!(peas(&args, "").status.success());
+}
+
+#[test]
+fn test_lock_closed_and_reopen() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config.replacen("[peas]\n", "[peas]\nlock_closed = true\n", 1),
+    )
+    .unwrap();
+    let output = peas(&["create", "Shipped", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+    assert!(peas(&["done", &id]).status.success());
+
+    // Closed peas are read-only
+    let output = peas(&["update", &id, "--title", "Renamed"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--reopen"));
+
+    // Reopening goes back to todo and is recorded in the history
+    assert!(
+        peas(&["update", &id, "--reopen", "--title", "Renamed"])
+            .status
+            .success()
+    );
+    let output = peas(&["show", &id, "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["status"], "todo");
+    assert_eq!(json["title"], "Renamed");
+    let output = peas(&["show", &id]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Reopened (was completed)"));
+}
+
+#[test]
+fn test_reopen_counts_in_stats() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let output = peas(&["create", "Crash", "-t", "bug", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    // Only closed peas can be reopened
+    assert_eq!(peas(&["reopen", &id]).status.code(), Some(4));
+
+    for count in 1..=2 {
+        assert!(peas(&["done", &id]).status.success());
+        let output = peas(&["reopen", &id, "--json"]);
+        assert!(output.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["status"], "todo");
+        assert_eq!(json["reopened_count"], count);
+    }
+
+    let output = peas(&["stats", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["reopened_by_type"]["bug"], 1);
+    assert_eq!(json["reopened_by_type"]["task"], 0);
+}
+
+#[test]
+fn test_status_cascade_to_parents() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], stdin: &str| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    };
+    let create = |args: &[&str]| -> String {
+        let output = peas(&[&["create", "--json"], args].concat(), "");
+        assert!(output.status.success(), "{:?}", output);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let status = |id: &str| {
+        let output = peas(&["show", id, "--json"], "");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["status"].as_str().unwrap().to_string()
+    };
+    assert!(peas(&["init"], "").status.success());
+    let epic = create(&["Epic", "-t", "epic"]);
+    let feature = create(&["Feature", "-t", "feature", "--parent", &epic]);
+    let first = create(&["First", "--parent", &feature]);
+    let second = create(&["Second", "--parent", &feature]);
+
+    // Off by default
+    assert!(peas(&["start", &first], "").status.success());
+    assert_eq!(status(&feature), "todo");
+
+    // Starting a child starts every epic or feature above it
+    assert!(peas(&["start", &second, "--cascade"], "").status.success());
+    assert_eq!(status(&feature), "in-progress");
+    assert_eq!(status(&epic), "in-progress");
+
+    // Completing the last open child asks for each parent in turn
+    assert!(peas(&["done", &first, "--cascade"], "").status.success());
+    let output = peas(&["done", &second, "--cascade"], "y\nn\n");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("complete it too?"));
+    assert_eq!(status(&feature), "completed");
+    assert_eq!(status(&epic), "in-progress");
+
+    // One undo step reverts the child and its parents
+    assert!(peas(&["undo"], "").status.success());
+    assert_eq!(status(&second), "in-progress");
+    assert_eq!(status(&feature), "in-progress");
+}
+
+#[test]
+fn test_multi_pea_commands_undo_in_one_step() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], stdin: &str| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .write_stdin(stdin)
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"], "").status.success());
+    let count = || {
+        let output = peas(&["list", "--json"], "");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json.as_array().unwrap().len()
+    };
+    let undo = || String::from_utf8(peas(&["undo"], "").stdout).unwrap();
+
+    let output = peas(
+        &["bulk", "create", "--tag", "ui", "--json"],
+        "One\nTwo\nThree\n",
+    );
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<String> = json["created"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(count(), 3);
+
+    let status = |id: &str| {
+        let output = peas(&["show", id, "--json"], "");
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["status"].as_str().unwrap().to_string()
+    };
+    assert!(
+        peas(&["bulk", "start", &ids[0], &ids[1]], "")
+            .status
+            .success()
+    );
+    assert!(
+        peas(&["tag", "rename", "ui", "frontend"], "")
+            .status
+            .success()
+    );
+
+    let undone = undo();
+    assert!(undone.contains("Rename tag ui to frontend"), "{}", undone);
+    let output = peas(&["list", "--tag", "ui", "--json"], "");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 3);
+
+    let undone = undo();
+    assert!(undone.contains("Bulk update of 2 peas"), "{}", undone);
+    assert_eq!(status(&ids[0]), "todo");
+    assert_eq!(status(&ids[1]), "todo");
+
+    let undone = undo();
+    assert!(undone.contains("Bulk create of 3 peas"), "{}", undone);
+    assert_eq!(count(), 0);
+}
+
+#[test]
+fn test_undo_show_previews_last_step() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let output = peas(&["undo", "--show", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["entry"].is_null());
+
+    let output = peas(&["create", "Preview me", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+    assert!(peas(&["start", &id]).status.success());
+    assert!(temp_dir.path().join(".peas/.undo").is_dir());
+
+    let output = peas(&["undo", "--show", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["entry"]["description"], format!("Update {}", id));
+    assert_eq!(json["entry"]["ids"], serde_json::json!([id]));
+    assert!(json["entry"]["recorded"].is_string());
+    assert_eq!(json["entry"]["steps"][0]["action"], "restore");
+    assert_eq!(
+        json["entry"]["steps"][0]["changes"],
+        serde_json::json!(["status: in-progress -> todo"])
+    );
+    assert_eq!(json["remaining"], 1);
+
+    // Showing reverts nothing
+    let output = peas(&["undo", "--show"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Would undo:"), "{}", stdout);
+    assert!(stdout.contains("1 earlier undo step(s)"), "{}", stdout);
+    let output = peas(&["show", &id, "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["status"], "in-progress");
+}
+
+#[test]
+fn test_suggest_skips_blocked_and_explains_scores() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"][..], args].concat());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let blocked = create(&["Blocked but critical", "-p", "critical"]);
+    let blocker = create(&["Blocker", "-p", "low", "--blocks", &blocked]);
+    let other = create(&["Low task", "-p", "low"]);
+
+    let output = peas(&["suggest", "--count", "5", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = json["suggestions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["pea"]["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, [blocker.as_str(), other.as_str()]);
+    assert_eq!(json["blocked"], 1);
+    let first = &json["suggestions"][0];
+    assert_eq!(first["reason"], "Unblocks 1 ticket(s)");
+    assert_eq!(first["blocks_count"], 1);
+    assert_eq!(first["score"], 10);
+    assert_eq!(first["factors"][0]["points"], 10);
+
+    // Once the blocker is done, the critical ticket comes first
+    assert!(peas(&["done", &blocker]).status.success());
+    let output = peas(&["suggest", "-n", "1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Blocked but critical"), "{}", stdout);
+    assert!(
+        stdout.contains("score 40: critical priority +40"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_defer_until_hides_from_list_and_suggest() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"][..], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let later = create(&["Later", "-p", "critical", "--defer-until", "2999-01-01"]);
+    let now = create(&["Now", "-p", "low"]);
+    let listed = |args: &[&str]| -> Vec<String> {
+        let output = peas(&[&["list", "--json"][..], args].concat());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(listed(&[]), [now.as_str()]);
+    assert_eq!(listed(&["--include-deferred"]).len(), 2);
+
+    let output = peas(&["suggest", "--count", "5", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["suggestions"].as_array().unwrap().len(), 1);
+    assert_eq!(json["suggestions"][0]["pea"]["id"], now.as_str());
+
+    // A past date no longer hides the pea, and an empty value clears it
+    assert!(
+        peas(&["update", &later, "--defer-until", "2000-01-01"])
+            .status
+            .success()
+    );
+    assert_eq!(listed(&[]).len(), 2);
+    assert!(
+        peas(&["update", &later, "--defer-until", ""])
+            .status
+            .success()
+    );
+    let output = peas(&["show", &later, "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["defer_until"].is_null());
+    assert_eq!(
+        peas(&["update", &later, "--defer-until", "soon"])
+            .status
+            .code(),
+        Some(4)
+    );
+}
+
+#[test]
+fn test_relative_timestamps_and_offsets() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let output = peas(&["create", "Timestamps", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    let output = peas(&["list", "--columns", "id,created", "--relative"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("just now"));
+    let output = peas(&["show", &id, "--relative"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("just now"));
+
+    // A hand-written offset is read as the same instant in UTC
+    for entry in std::fs::read_dir(temp_dir.path().join(".peas")).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if name.starts_with(&id) {
+            let content = std::fs::read_to_string(&path).unwrap();
+            let content: Vec<String> = content
+                .lines()
+                .map(|line| {
+                    if line.starts_with("created = ") {
+                        "created = 2020-01-01T10:00:00+02:00".to_string()
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect();
+            std::fs::write(&path, content.join("\n") + "\n").unwrap();
+        }
+    }
+    let output = peas(&["show", &id, "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["created"], "2020-01-01T08:00:00Z");
+    let output = peas(&["show", &id]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2020-01-01 08:00"));
+}
+
+#[test]
+fn test_list_fast_reads_summary_index() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    for title in ["One", "Two", "Three"] {
+        assert!(peas(&["create", title, "-d", "Some body"]).status.success());
+    }
+    let list = |args: &[&str]| {
+        let output = peas(&[&["list", "--json"][..], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    assert_eq!(list(&["--fast"]), list(&[]));
+    assert!(temp_dir.path().join(".peas/local/index.json").exists());
+
+    let output = peas(&["update", "two", "--status", "in-progress"]);
+    assert!(output.status.success(), "{:?}", output);
+    let fast = list(&["--fast", "--status", "in-progress"]);
+    assert_eq!(fast.as_array().unwrap().len(), 1);
+    assert_eq!(fast, list(&["--status", "in-progress"]));
+    assert!(!peas(&["list", "--fast", "--archived"]).status.success());
+}
+
+#[test]
+fn test_mv_keeps_old_id_as_alias() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(
+        peas(&["init", "--prefix", "t-", "--id-length", "3"])
+            .status
+            .success()
+    );
+    let output = peas(&["create", "Renamed", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let old = json["id"].as_str().unwrap().to_string();
+
+    assert!(peas(&["mv", &old, "abc"]).status.success());
+    assert!(peas(&["mv", "abc", "xyz"]).status.success());
+    let aliases = std::fs::read_to_string(temp_dir.path().join(".peas/aliases.toml")).unwrap();
+    assert!(aliases.contains(&format!("{} = \"t-xyz\"", old)));
+
+    // Both former IDs still resolve to the pea under its current ID
+    for id in [old.as_str(), "t-abc", "abc"] {
+        let output = peas(&["show", id, "--json"]);
+        assert!(output.status.success(), "{}: {:?}", id, output);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert_eq!(json["id"], "t-xyz");
+    }
+    let output = peas(&["show", "t-xyz"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.lines().find(|l| l.starts_with("Aliases:")).unwrap();
+    assert!(line.contains(&old) && line.contains("t-abc"), "{}", line);
+
+    let output = peas(&["search", "t-abc", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["id"], "t-xyz");
+}
+
+#[test]
+fn test_mv_parent_with_children_and_blockers() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let show = |id: &str| {
+        let output = peas(&["show", id, "--json"]);
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    assert!(
+        peas(&["init", "--prefix", "t-", "--id-length", "3"])
+            .status
+            .success()
+    );
+    let epic = create(&["Auth", "-t", "epic"]);
+    let child = create(&["Login", "--parent", &epic]);
+    let blocker = create(&["Audit", "--blocks", &epic]);
+
+    let output = peas(&["mv", &epic, "abc"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(show(&child)["parent"], "t-abc");
+    assert_eq!(show(&blocker)["blocking"][0], "t-abc");
+    assert_eq!(show(&epic)["id"], "t-abc");
+
+    // The whole rename is one undo step
+    assert!(peas(&["undo"]).status.success());
+    assert_eq!(show(&child)["parent"], epic.as_str());
+    assert_eq!(show(&blocker)["blocking"][0], epic.as_str());
+    assert!(!peas(&["show", "t-abc"]).status.success());
+}
+
+#[test]
+fn test_migrate_prefix() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init", "--prefix", "beans-"]).status.success());
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"][..], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let epic = create(&["Epic", "-t", "epic"]);
+    let body = format!("Part of {}.", epic);
+    let task = create(&["Task", "--parent", &epic, "--body", &body]);
+    let new_id = |id: &str| id.replacen("beans-", "peas-", 1);
+
+    let output = peas(&[
+        "migrate",
+        "prefix",
+        "--from",
+        "beans-",
+        "--to",
+        "peas-",
+        "--dry-run",
+        "--json",
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["renamed"].as_array().unwrap().len(), 2);
+    assert_eq!(json["config_updated"], true);
+    assert!(peas(&["show", &epic]).status.success());
+
+    let output = peas(&["migrate", "prefix", "--from", "beans-", "--to", "peas-"]);
+    assert!(output.status.success(), "{:?}", output);
+    let config = std::fs::read_to_string(temp_dir.path().join(".peas/config.toml")).unwrap();
+    assert!(config.contains("prefix = \"peas-\""));
+
+    let output = peas(&["show", &new_id(&task), "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["parent"], new_id(&epic));
+    let output = peas(&["show", &new_id(&task)]);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains(&format!("Part of {}", new_id(&epic)))
+    );
+
+    // Old IDs resolve as aliases
+    let output = peas(&["show", &epic, "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["id"], new_id(&epic));
+
+    let output = peas(&["migrate", "prefix", "--from", "x-", "--to", "x-"]);
+    assert_eq!(output.status.code(), Some(4));
+}
+
+#[test]
+fn test_migrate_prefix_undo() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init", "--prefix", "beans-"]).status.success());
+    let output = peas(&["create", "Epic", "-t", "epic", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let epic = json["id"].as_str().unwrap().to_string();
+    assert!(
+        peas(&["create", "Task", "--parent", &epic])
+            .status
+            .success()
+    );
+
+    let output = peas(&["migrate", "prefix", "--from", "beans-", "--to", "peas-"]);
+    assert!(output.status.success(), "{:?}", output);
+    let output = peas(&["undo"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    // Files, config and aliases are back as they were
+    let config = std::fs::read_to_string(temp_dir.path().join(".peas/config.toml")).unwrap();
+    assert!(config.contains("prefix = \"beans-\""));
+    assert!(!temp_dir.path().join(".peas/aliases.toml").exists());
+    let output = peas(&["list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&epic), "{}", stdout);
+    assert!(stdout.contains("Task"), "{}", stdout);
+
+    // Older undo steps name the old IDs again
+    assert!(peas(&["undo"]).status.success());
+    let output = peas(&["list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&epic) && !stdout.contains("Task"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn test_search_ranks_and_excludes() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    for args in [
+        &["create", "Refactor forms", "-d", "The login form is slow"][..],
+        &["create", "Cleanup", "--tag", "login"][..],
+        &["create", "Login page"][..],
+    ] {
+        assert!(peas(args).status.success());
+    }
+
+    let search = |query: &str| {
+        let output = peas(&["search", query, "--json"]);
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    let titles = |results: &serde_json::Value| -> Vec<String> {
+        results
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["title"].as_str().unwrap().to_string())
+            .collect()
+    };
+    let results = search("login");
+    assert_eq!(
+        titles(&results),
+        ["Login page", "Cleanup", "Refactor forms"]
+    );
+    assert_eq!(results[2]["snippet"], "The **login** form is slow");
+
+    let results = search(r#""login form" -forms"#);
+    assert!(results.as_array().unwrap().is_empty());
+    let results = search(r#""login form""#);
+    assert_eq!(titles(&results), ["Refactor forms"]);
+    assert_eq!(results[0]["snippet"], "The **login form** is slow");
+
+    let output = peas(&["search", "login"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("The login form is slow"), "{}", stdout);
+    assert!(!peas(&["search", r#""unterminated"#]).status.success());
+
+    // Typos need --fuzzy
+    assert!(search("logn").as_array().unwrap().is_empty());
+    let output = peas(&["search", "--fuzzy", "logn", "--json"]);
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        titles(&results),
+        ["Login page", "Cleanup", "Refactor forms"]
+    );
+}
+
+#[test]
+fn test_related_scores_tags_titles_and_parents() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    assert!(peas(&["init"]).status.success());
+    let epic = create(&["Auth", "-t", "epic"]);
+    let login = create(&["Login form", "--parent", &epic, "--tag", "auth"]);
+    create(&["Login error messages", "--tag", "auth"]);
+    create(&["Password reset", "--parent", &epic]);
+    create(&["Dark mode"]);
+
+    let output = peas(&["related", &login, "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let related: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let titles: Vec<&str> = related
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["pea"]["title"].as_str().unwrap())
+        .collect();
+    assert_eq!(titles, ["Login error messages", "Password reset"]);
+    assert_eq!(related[0]["score"], 26);
+    assert_eq!(related[0]["factors"][0]["reason"], "shares tag auth");
+    assert_eq!(
+        related[1]["factors"][0]["reason"],
+        format!("same parent {}", epic)
+    );
+
+    let output = peas(&["related", &login, "-n", "1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Login error messages"));
+    assert!(!stdout.contains("Password reset"));
+}
+
+#[test]
+fn test_delete_and_archive_handle_children_and_blockers() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let show = |id: &str| {
+        let output = peas(&["show", id, "--json"]);
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let root = create(&["Platform", "-t", "milestone"]);
+    let epic = create(&["Auth", "-t", "epic", "--parent", &root]);
+    let login = create(&["Login form", "--parent", &epic]);
+    let reset = create(&["Password reset", "--parent", &epic]);
+    let blocker = create(&["Audit", "--blocks", &epic]);
+
+    // Only one way to handle the children at a time
+    assert!(
+        !peas(&["delete", &epic, "-f", "--orphan", "--cascade"])
+            .status
+            .success()
+    );
+    let output = peas(&["delete", &epic, "--reparent-to", &login, "-f"]);
+    assert_eq!(output.status.code(), Some(4));
+
+    // Nor are the children left pointing at a deleted or archived parent
+    let output = peas(&["delete", &epic, "-f"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&login));
+    assert_eq!(peas(&["archive", &epic, "-y"]).status.code(), Some(4));
+    let output = peas(&["archive", "--type", "epic", "-y"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr).contains(&login));
+    assert_eq!(show(&login)["parent"], epic.as_str());
+
+    let output = peas(&["delete", &epic, "--orphan", "--dry-run", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["updated"].as_array().unwrap().len(), 3);
+    assert!(show(&login)["parent"].as_str().is_some());
+
+    let output = peas(&["delete", &epic, "--reparent-to", &root, "-f", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(show(&login)["parent"], root.as_str());
+    assert_eq!(show(&reset)["parent"], root.as_str());
+    assert!(
+        show(&blocker)["blocking"]
+            .as_array()
+            .is_none_or(|b| b.is_empty())
+    );
+
+    // One undo step brings everything back
+    assert!(peas(&["undo"]).status.success());
+    assert_eq!(show(&login)["parent"], epic.as_str());
+    assert_eq!(show(&blocker)["blocking"][0], epic.as_str());
+
+    let output = peas(&["archive", &epic, "--cascade", "-y", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(!peas(&["show", &login]).status.success());
+    let output = peas(&["list", "--archived", "--json"]);
+    let archived: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(archived.as_array().unwrap().len(), 3);
+    assert!(
+        show(&blocker)["blocking"]
+            .as_array()
+            .is_none_or(|b| b.is_empty())
+    );
+}
+
+#[test]
+fn test_references_follow_renames_and_doctor_repairs_them() {
+    let temp_dir = TempDir::new().unwrap();
+    // Keep doctor's update check off the network
+    let config_home = temp_dir.path().join("config");
+    std::fs::create_dir_all(config_home.join("peas")).unwrap();
+    std::fs::write(
+        config_home.join("peas/config.toml"),
+        "[updates]\nenabled = false\n",
+    )
+    .unwrap();
+    let project = temp_dir.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .env("XDG_CONFIG_HOME", &config_home)
+            .current_dir(&project)
+            .output()
+            .unwrap()
+    };
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"], args].concat());
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()["id"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    };
+    let show = |id: &str| {
+        let output = peas(&["show", id, "--json"]);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    assert!(
+        peas(&["init", "--prefix", "t-", "--id-length", "3"])
+            .status
+            .success()
+    );
+    let api = create(&["API"]);
+    let docs = create(&["Docs", "--body", &format!("Describe {}.", api)]);
+
+    // mv rewrites mentions in bodies along with parent and blocking
+    assert!(peas(&["mv", &api, "api"]).status.success());
+    let output = peas(&["show", &docs]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Describe t-api"));
+
+    // Deleting a mentioned pea names the bodies left to edit
+    let gone = create(&["Gone"]);
+    let notes = create(&["Notes", "--body", &format!("After {}.", gone)]);
+    let output = peas(&["delete", &gone, "-f", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["mentioned_by"], serde_json::json!([notes]));
+
+    // References written behind peas' back: a stale rename and missing peas
+    let data = project.join(".peas");
+    let path = std::fs::read_dir(&data)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.to_string_lossy().contains(&docs))
+        .unwrap();
+    let content = std::fs::read_to_string(&path).unwrap().replacen(
+        "title = \"Docs\"",
+        &format!(
+            "title = \"Docs\"\nparent = \"{}\"\nblocking = [\"t-zzz\"]",
+            api
+        ),
+        1,
+    );
+    std::fs::write(&path, content).unwrap();
+    let child = create(&["Child"]);
+    assert!(
+        peas(&["update", &child, "--parent", &docs])
+            .status
+            .success()
+    );
+    // Removed behind peas' back, as peas delete refuses to strand the child
+    let aside = temp_dir.path().join("docs.md");
+    std::fs::rename(&path, &aside).unwrap();
+
+    let output = peas(&["doctor"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Broken references found"), "{}", stdout);
+    assert!(
+        stdout.contains(&format!("{} references missing parent {}", child, docs)),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains(&format!("{} mentions missing ticket {}", notes, gone)),
+        "{}",
+        stdout
+    );
+
+    std::fs::rename(&aside, &path).unwrap();
+    let output = peas(&["doctor", "--fix"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Fixed references in"), "{}", stdout);
+    assert_eq!(show(&docs)["parent"], "t-api");
+    assert!(
+        show(&docs)["blocking"]
+            .as_array()
+            .is_none_or(|b| b.is_empty())
+    );
+}
+
+#[test]
+fn test_configured_validation_limits() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config + "\n[validation]\nmax_title_length = 12\ntag_pattern = \"[a-z-]+\"\n",
+    )
+    .unwrap();
+
+    let output = peas(&["create", "A title that is too long"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("12 characters"));
+    // Characters, not bytes
+    assert!(peas(&["create", "Ünïcödé"]).status.success());
+
+    let output = peas(&["create", "Tagged", "--tag", "Needs Review"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    let output = peas(&["create", "Tagged", "--tag", "Needs Review", "--dry-run"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[a-z-]+"));
+    assert!(
+        peas(&["create", "Tagged", "--tag", "needs-review"])
+            .status
+            .success()
+    );
+
+    let output = peas(&["create", "Pay \u{202E}gnp.exe"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("U+202E"));
+    let output = peas(&["create", "Bell", "-d", "Ding\u{7}"]);
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(&config_path, config + "max_body_length = 0\n").unwrap();
+    assert!(!peas(&["list"]).status.success());
+}
+
+#[test]
+fn test_actor_attribution_and_filters() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], actor: Option<&str>| {
+        let mut cmd = peas_cmd();
+        cmd.args(args).current_dir(temp_dir.path());
+        match actor {
+            Some(actor) => cmd.env("PEAS_ACTOR", actor),
+            None => cmd.env_remove("PEAS_ACTOR"),
+        };
+        cmd.output().unwrap()
+    };
+    assert!(peas(&["init"], None).status.success());
+    let create = |args: &[&str], actor: Option<&str>| {
+        let output = peas(&[&["create", "--json"][..], args].concat(), actor);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let by_agent = create(&["Agent task", "--actor", "agent-x"], None);
+    let by_human = create(&["Human task"], Some("ann"));
+    let anonymous = create(&["Nobody's task"], None);
+
+    // The flag wins over the environment
+    let output = peas(&["start", &by_human, "--actor", "agent-y"], Some("ann"));
+    assert!(output.status.success(), "{:?}", output);
+
+    let listed = |args: &[&str]| -> Vec<String> {
+        let output = peas(&[&["list", "--json"][..], args].concat(), None);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json.as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+    assert_eq!(listed(&["--created-by", "agent-x"]), [by_agent.as_str()]);
+    assert_eq!(listed(&["--created-by", "ann"]), [by_human.as_str()]);
+    assert_eq!(listed(&["--updated-by", "agent-y"]), [by_human.as_str()]);
+    assert!(listed(&["--updated-by", "ann"]).is_empty());
+
+    let output = peas(&["show", &by_human], None);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(" by ann"), "{}", stdout);
+    assert!(stdout.contains(" by agent-y"), "{}", stdout);
+
+    let output = peas(&["show", &anonymous, "--json"], None);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("created_by").is_none());
+}
+
+#[test]
+fn test_claim_and_release() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], actor: Option<&str>| {
+        let mut cmd = peas_cmd();
+        cmd.args(args).current_dir(temp_dir.path());
+        match actor {
+            Some(actor) => cmd.env("PEAS_ACTOR", actor),
+            None => cmd.env_remove("PEAS_ACTOR"),
+        };
+        cmd.output().unwrap()
+    };
+    assert!(peas(&["init"], None).status.success());
+    let create = |title: &str| {
+        let output = peas(&["create", title, "--json"], None);
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let login = create("Login form");
+    let other = create("Other task");
+
+    // A claim needs an actor
+    let output = peas(&["claim", &login], None);
+    assert_eq!(output.status.code(), Some(4));
+
+    let output = peas(&["claim", &login, "--ttl", "1h", "--json"], Some("agent-x"));
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["claim"]["by"], "agent-x");
+
+    // Others are turned away, and suggest and list skip the pea for them
+    let output = peas(&["claim", &login], Some("agent-y"));
+    assert_eq!(output.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("claimed by agent-x"));
+    let output = peas(&["suggest", "-n", "5", "--json"], Some("agent-y"));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["claimed"], 1);
+    assert_eq!(json["suggestions"].as_array().unwrap().len(), 1);
+    assert_eq!(json["suggestions"][0]["pea"]["id"], other.as_str());
+    let output = peas(&["list", "--unclaimed", "--json"], Some("agent-y"));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let output = peas(&["list", "--unclaimed", "--json"], Some("agent-x"));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    let output = peas(&["list"], None);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(claimed by agent-x)"));
+
+    assert_eq!(
+        peas(&["release", &login], Some("agent-y")).status.code(),
+        Some(5)
+    );
+    let output = peas(&["release", &login], Some("agent-x"));
+    assert!(output.status.success(), "{:?}", output);
+    let output = peas(&["show", &login, "--json"], None);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("claim").is_none());
+
+    // Taking over a claim needs --force
+    assert!(peas(&["claim", &login], Some("agent-y")).status.success());
+    let output = peas(&["claim", &login, "--force"], Some("agent-x"));
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("took over the claim by agent-y"));
+}
+
+#[test]
+fn test_note_appends_to_work_log() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["create", "Login form", "-d", "Build it", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    peas_cmd()
+        .args(["note", &id, "Form renders"])
+        .env("PEAS_ACTOR", "agent-x")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["note", &id, "-"])
+        .write_stdin("Validation done\n")
+        .env_remove("PEAS_ACTOR")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["note", &id, "  "])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+
+    let output = peas_cmd()
+        .args(["note", &id, "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let notes = json["notes"].as_array().unwrap();
+    assert_eq!(notes.len(), 2);
+    assert!(
+        notes[0]
+            .as_str()
+            .unwrap()
+            .ends_with("(agent-x) Form renders")
+    );
+    assert!(notes[1].as_str().unwrap().ends_with(" Validation done"));
+
+    let output = peas_cmd()
+        .args(["show", &id])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Build it\n\n## Work log\n\n- "),
+        "{}",
+        stdout
+    );
+
+    // One undo step per note
+    peas_cmd()
+        .arg("undo")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["note", &id, "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["notes"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_components_filter_and_auto_assign() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        "\n[components.auth]\nowners = [\"ann\"]\nauto_assign = true\n\n[components.billing]\n",
+    );
+    std::fs::write(&config_path, config).unwrap();
+
+    let output = peas(&["create", "Login form", "--component", "auth", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["component"], "auth");
+    assert_eq!(json["assignee"], "ann");
+    let login = json["id"].as_str().unwrap().to_string();
+    assert!(peas(&["create", "Unsorted"]).status.success());
+
+    let output = peas(&["create", "Invoice", "--component", "invoices"]);
+    assert_eq!(output.status.code(), Some(4));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("auth, billing"));
+
+    let output = peas(&["list", "--component", "auth", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let output = peas(&["list", "--assignee", "ann", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["id"], login.as_str());
+
+    // Moving the pea keeps its assignee, an empty value clears it
+    let output = peas(&["update", &login, "--component", "billing", "--assignee", ""]);
+    assert!(output.status.success(), "{:?}", output);
+    let output = peas(&["show", &login, "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["component"], "billing");
+    assert!(json.get("assignee").is_none());
+
+    let output = peas(&["stats", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["by_component"],
+        serde_json::json!({"auth": 0, "billing": 1})
+    );
+}
+
+#[test]
+fn test_autotag_on_create_and_retag() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    let create = |title: &str| {
+        let output = peas(&["create", title, "--json"]);
+        assert!(output.status.success(), "{:?}", output);
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    assert_eq!(peas(&["retag", "--apply-rules"]).status.code(), Some(4));
+    let before = create("Fix login redirect");
+    let other = create("Update docs");
+
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        "\n[components.auth]\nowners = [\"ann\"]\nauto_assign = true\n\n[autotag.auth]\ntitle = \"login|password\"\ntags = [\"auth\"]\ncomponent = \"auth\"\n",
+    );
+    std::fs::write(&config_path, config).unwrap();
+
+    // New peas are tagged, and the component's owner is assigned
+    let json = create("Reset PASSWORD flow");
+    assert_eq!(json["tags"], serde_json::json!(["auth"]));
+    assert_eq!(json["component"], "auth");
+    assert_eq!(json["assignee"], "ann");
+
+    let output = peas(&["retag", "--apply-rules", "--dry-run", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["updated"].as_array().unwrap().len(), 1);
+    assert_eq!(json["updated"][0]["id"], before["id"]);
+    assert_eq!(json["updated"][0]["tags"], serde_json::json!(["auth"]));
+
+    let output = peas(&["retag", "--apply-rules"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Retagged 1 pea(s)"));
+    let output = peas(&["show", before["id"].as_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["component"], "auth");
+    let output = peas(&["show", other["id"].as_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("tags").is_none());
+
+    // Everything is tagged now, and one undo step reverts the backfill
+    let output = peas(&["retag", "--apply-rules"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No peas need"));
+    assert!(peas(&["undo"]).status.success());
+    let output = peas(&["show", before["id"].as_str().unwrap(), "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json.get("component").is_none());
+}
+
+#[test]
+fn test_slugs_are_accepted_as_ids() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let output = peas(&["create", "Login epic", "-t", "epic", "--json"]);
+    let epic: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(epic["slug"], "login-epic");
+
+    let output = peas(&["create", "Login form", "--parent", "login-epic", "--json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["parent"], epic["id"]);
+
+    assert!(peas(&["start", "login-form"]).status.success());
+    let output = peas(&["show", "login-form", "--json"]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["status"], "in-progress");
+    assert_eq!(peas(&["show", "no-such-slug"]).status.code(), Some(3));
+}
+
+#[test]
+fn test_triage_queue() {
+    let temp_dir = TempDir::new().unwrap();
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let draft = create(&["Login fails", "-s", "draft"]);
+    let epic = create(&["Auth", "-t", "epic"]);
+    let orphan = create(&["Loose end"]);
+    create(&["Filed", "--parent", &epic]);
+
+    let output = peas_cmd()
+        .args(["triage", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let queue: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = queue
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, [draft.as_str(), orphan.as_str()]);
+
+    // Unknown answers are asked again; the second pea is skipped
+    peas_cmd()
+        .arg("triage")
+        .write_stdin("bug\nurgent\nhigh\nauth\nauth, ui\ns\n")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Invalid priority: urgent"))
+        .stdout(predicate::str::contains("Triaged 1 pea(s), skipped 1"));
+
+    let output = peas_cmd()
+        .args(["show", &draft, "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let pea: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(pea["status"], "todo");
+    assert_eq!(pea["type"], "bug");
+    assert_eq!(pea["priority"], "high");
+    assert_eq!(pea["parent"], epic.as_str());
+    assert_eq!(pea["tags"], serde_json::json!(["auth", "ui"]));
+}
+
+#[test]
+fn test_review_week() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let old = create(&["Old idea"]);
+    let stuck = create(&["Stuck work", "-s", "in-progress"]);
+    let ship = create(&["Ship", "-t", "milestone", "--target-date", "2020-01-10"]);
+    create(&["Fresh idea"]);
+
+    // Backdate the first two, as if untouched for a while
+    for entry in std::fs::read_dir(temp_dir.path().join(".peas")).unwrap() {
+        let path = entry.unwrap().path();
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        if name.starts_with(&old) || name.starts_with(&stuck) {
+            let content = std::fs::read_to_string(&path).unwrap();
+            let content: Vec<String> = content
+                .lines()
+                .map(|line| {
+                    if line.starts_with("created = ") || line.starts_with("updated = ") {
+                        format!("{}\"2020-01-01T00:00:00Z\"", &line[..10])
+                    } else {
+                        line.to_string()
+                    }
+                })
+                .collect();
+            std::fs::write(&path, content.join("\n") + "\n").unwrap();
+        }
+    }
+
+    let output = peas_cmd()
+        .args(["review", "--week", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["stale"][0]["id"], stuck.as_str());
+    assert_eq!(json["unprioritized"][0]["id"], old.as_str());
+    assert_eq!(json["due"][0]["id"], ship.as_str());
+    assert_eq!(json["unprioritized"].as_array().unwrap().len(), 1);
+
+    // Defer the stale work, keep the old idea, bump the milestone
+    peas_cmd()
+        .arg("review")
+        .write_stdin("d\n\nb\n")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Reviewed 3 pea(s): 2 changed, 1 kept",
+        ));
+    let show = |id: &str| -> serde_json::Value {
+        let output = peas_cmd()
+            .args(["show", id, "--json"])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    assert_eq!(show(&stuck)["status"], "todo");
+    assert_eq!(show(&stuck)["priority"], "deferred");
+    assert_eq!(show(&ship)["priority"], "high");
+}
+
+#[test]
+fn test_git_setup_and_merge_driver() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+
+    peas_cmd().arg("init").current_dir(dir).assert().success();
+    assert!(
+        git_cmd(dir)
+            .args(["init", "-q"])
+            .status()
+            .unwrap()
+            .success()
+    );
+
+    for _ in 0..2 {
+        peas_cmd()
+            .args(["git", "setup"])
+            .current_dir(dir)
+            .assert()
+            .success();
+    }
+    let attributes = std::fs::read_to_string(dir.join(".gitattributes")).unwrap();
+    assert_eq!(attributes, ".peas/**/*.md merge=peas\n");
+    let driver = git_cmd(dir)
+        .args(["config", "merge.peas.driver"])
+        .output()
+        .unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&driver.stdout).trim(),
+        "peas merge-driver %O %A %B %P"
+    );
+
+    let pea = |status: &str, priority: &str, tags: &str, body: &str| {
+        format!(
+            "+++\nid = \"peas-abc12\"\ntitle = \"Login\"\ntype = \"bug\"\n\
+             status = \"{}\"\npriority = \"{}\"\ntags = [{}]\n\
+             created = \"2026-01-01T00:00:00Z\"\nupdated = \"2026-01-01T00:00:00Z\"\n+++\n\n{}\n",
+            status, priority, tags, body
+        )
+    };
+    let write = |name: &str, content: String| {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().into_owned()
+    };
+    let merge = |ours: &str, theirs: &str| {
+        let base = write(
+            "base.md",
+            pea("todo", "normal", "\"auth\"", "one\ntwo\nthree"),
+        );
+        let ours = write("ours.md", ours.to_string());
+        let theirs = write("theirs.md", theirs.to_string());
+        let assert = peas_cmd()
+            .args(["merge-driver", &base, &ours, &theirs, ".peas/peas-abc12.md"])
+            .current_dir(dir)
+            .assert();
+        (assert, std::fs::read_to_string(&ours).unwrap())
+    };
+
+    // Different fields and different body lines merge cleanly
+    let (assert, merged) = merge(
+        &pea(
+            "in-progress",
+            "normal",
+            "\"auth\", \"ui\"",
+            "ONE\ntwo\nthree",
+        ),
+        &pea("todo", "high", "\"auth\", \"db\"", "one\ntwo\nTHREE"),
+    );
+    assert.success();
+    assert!(merged.contains("status = \"in-progress\""), "{}", merged);
+    assert!(merged.contains("priority = \"high\""), "{}", merged);
+    assert!(merged.contains("\"ui\""), "{}", merged);
+    assert!(merged.contains("\"db\""), "{}", merged);
+    assert!(merged.contains("ONE\ntwo\nTHREE"), "{}", merged);
+
+    // The same field changed on both sides stays conflicted
+    let (assert, merged) = merge(
+        &pea("completed", "normal", "\"auth\"", "one\ntwo\nthree"),
+        &pea("scrapped", "normal", "\"auth\"", "one\ntwo\nthree"),
+    );
+    assert.failure().stderr(predicate::str::contains(
+        "Merge conflict in .peas/peas-abc12.md",
+    ));
+    assert!(merged.contains("status = \"completed\""), "{}", merged);
+    assert!(
+        merged
+            .contains("<<<<<<< ours\nstatus: completed\n=======\nstatus: scrapped\n>>>>>>> theirs"),
+        "{}",
+        merged
+    );
+
+    // A pea renamed on the other branch is followed through its alias
+    std::fs::write(
+        dir.join(".peas/aliases.toml"),
+        "peas-old01 = \"peas-new01\"\n",
+    )
+    .unwrap();
+    let (assert, merged) = merge(
+        &pea("todo", "normal", "\"auth\"", "one\ntwo\nthree"),
+        &pea(
+            "todo",
+            "normal",
+            "\"auth\"",
+            "one\ntwo\nthree after peas-old01",
+        ),
+    );
+    assert.success();
+    assert!(merged.contains("three after peas-new01"), "{}", merged);
+}
+
+// =============================================================================
+// Update, Status Workflow
+// =============================================================================
+
+#[test]
+fn test_update_status() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["create", "Update Test", "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let id = json["id"].as_str().unwrap();
+
+    peas_cmd()
+        .args(["update", id, "-s", "in-progress"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["show", id, "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in-progress"));
+}
+
+#[test]
+fn test_start_and_done() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["create", "Workflow Test", "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let id = json["id"].as_str().unwrap();
+
+    peas_cmd()
+        .args(["start", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("in-progress"));
+
+    peas_cmd()
+        .args(["done", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("completed"));
+}
+
+#[test]
+fn test_size_and_rollups() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let milestone = create(&["v1", "-t", "milestone"]);
+    let epic = create(&["Auth", "-t", "epic", "--parent", &milestone]);
+    let login = create(&["Login", "--parent", &epic]);
+    let logout = create(&["Logout", "--parent", &epic]);
+    create(&["Signup", "--parent", &epic]);
+
+    peas_cmd()
+        .args(["size", &login, "5"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("size 5"));
+    peas_cmd()
+        .args(["size", &logout, "3"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["size", &logout, "4"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("fibonacci"));
+    peas_cmd()
+        .args(["done", &login])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .arg("roadmap")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "**Size:** 5/8 points done, 1 unestimated",
+        ))
+        .stdout(predicate::str::contains("size 3"));
+    peas_cmd()
+        .args(["show", &epic])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Points:   5/8 points done"));
+
+    // Switching to t-shirt sizes
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config.replace("size_scale = \"fibonacci\"", "size_scale = \"tshirt\""),
+    )
+    .unwrap();
+    let output = peas_cmd()
+        .args(["size", &logout, "xl", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["size"], "XL");
+
+    peas_cmd()
+        .args(["size", &logout, "--clear"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["size", &logout])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("has no size (tshirt scale"));
+}
+
+#[test]
+fn test_focus_set_show_and_clear() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    create(&["Urgent bug", "-t", "bug", "-p", "critical"]);
+    let chore = create(&["Tidy up", "-t", "chore", "-p", "low"]);
+
+    peas_cmd()
+        .arg("focus")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No focus set"));
+    peas_cmd()
+        .args(["focus", &chore])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Focused on"));
+    peas_cmd()
+        .arg("focus")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Tidy up"));
+
+    // The focused chore wins over the critical bug
+    let output = peas_cmd()
+        .args(["suggest", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["suggestions"][0]["pea"]["id"], chore.as_str());
+    assert_eq!(json["suggestions"][0]["reason"], "Current focus");
+
+    peas_cmd()
+        .args(["focus", "--clear"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cleared"));
+    let output = peas_cmd()
+        .args(["focus", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["focus"].is_null());
+
+    peas_cmd()
+        .args(["focus", "peas-nope0"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_inbox_new_and_changed() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |title: &str| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json", title])
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let old = create("Existing work");
+
+    // The first look starts tracking, existing peas count as read
+    peas_cmd()
+        .arg("inbox")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing new"));
+    assert!(temp_dir.path().join(".peas/local/.gitignore").exists());
+
+    let fresh = create("Fresh idea");
+    peas_cmd()
+        .args(["start", &old])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["inbox", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["count"], 2);
+    let state_of = |id: &str| {
+        json["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["pea"]["id"] == id)
+            .map(|item| item["state"].as_str().unwrap().to_string())
+    };
+    assert_eq!(state_of(&fresh).as_deref(), Some("new"));
+    assert_eq!(state_of(&old).as_deref(), Some("changed"));
+
+    peas_cmd()
+        .arg("list")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("●"));
+
+    // Viewing a pea marks it read
+    peas_cmd()
+        .args(["show", &fresh])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .arg("inbox")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Existing work"))
+        .stdout(predicate::str::contains("Fresh idea").not());
+
+    peas_cmd()
+        .args(["inbox", "--mark-read"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Marked 1 pea(s) as read"));
+    peas_cmd()
+        .arg("inbox")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing new"));
+}
+
+#[test]
+fn test_create_body_from_clipboard_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["create", "Pasted", "--body-from-clipboard", "-d", "text"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_capture_and_inbox_drafts() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["capture", "Look into flaky sync"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Captured"));
+
+    // One draft per non-empty line from stdin
+    let output = peas_cmd()
+        .args(["capture", "--json", "-t", "bug"])
+        .write_stdin("Login times out\n\n  Typo on settings page  \n")
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let captured = json.as_array().unwrap();
+    assert_eq!(captured.len(), 2);
+    assert_eq!(captured[1]["title"], "Typo on settings page");
+    assert_eq!(captured[1]["status"], "draft");
+    assert_eq!(captured[1]["type"], "bug");
+
+    peas_cmd()
+        .arg("capture")
+        .write_stdin("\n")
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4);
+
+    let output = peas_cmd()
+        .args(["inbox", "--drafts", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["count"], 3);
+    assert_eq!(json["items"][0]["title"], "Look into flaky sync");
+    peas_cmd()
+        .args(["inbox", "--drafts"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 draft(s)"));
+
+    // The stdin capture is undone as one step
+    peas_cmd()
+        .arg("undo")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["inbox", "--drafts", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["count"], 1);
+}
+
+#[test]
+fn test_capture_from_file_and_email() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    std::fs::write(
+        temp_dir.path().join("note.md"),
+        "# Offline mode\n\nCache the last sync.\n",
+    )
+    .unwrap();
+
+    let output = peas_cmd()
+        .args(["capture", "--json", "--from-file", "note.md"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["title"], "Offline mode");
+    assert_eq!(json[0]["status"], "draft");
+    let id = json[0]["id"].as_str().unwrap();
+    peas_cmd()
+        .args(["show", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Cache the last sync."));
+
+    let output = peas_cmd()
+        .args(["capture", "--json", "--from-email", "-"])
+        .write_stdin("From: ops@example.com\r\nSubject: Disk almost full\r\n\r\nOn build-02.\r\n")
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["title"], "Disk almost full");
+
+    peas_cmd()
+        .args(["capture", "Idea", "--from-file", "note.md"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_graph_export() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let milestone = create(&["v1", "-t", "milestone"]);
+    let schema = create(&["Schema", "--parent", &milestone, "--tag", "db"]);
+    let api = create(&["API", "--parent", &milestone, "--blocked-by", &schema]);
+    let other = create(&["Unrelated", "-d", &format!("Came up while doing {}", api)]);
+
+    let output = peas_cmd()
+        .args(["graph", "--format", "dot"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let dot = String::from_utf8(output.stdout).unwrap();
+    assert!(dot.starts_with("digraph peas {"));
+    assert!(dot.contains(&format!("\"{}\" -> \"{}\";", milestone, schema)));
+    assert!(dot.contains(&format!(
+        "\"{}\" -> \"{}\" [label=\"blocks\", color=red];",
+        schema, api
+    )));
+    assert!(dot.contains(&format!(
+        "\"{}\" -> \"{}\" [label=\"refs\", style=dashed];",
+        other, api
+    )));
+
+    // Default mermaid output, limited to the milestone
+    let output = peas_cmd()
+        .args(["graph", "--milestone", &milestone])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let mermaid = String::from_utf8(output.stdout).unwrap();
+    assert!(mermaid.starts_with("graph LR"));
+    assert!(mermaid.contains("-->|blocks|"));
+    assert!(!mermaid.contains("Unrelated"));
+
+    peas_cmd()
+        .args(["graph", "--tag", "db"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Schema"))
+        .stdout(predicate::str::contains("API").not());
+    peas_cmd()
+        .args(["graph", "--milestone", &api])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("not a milestone"));
+}
+
+#[test]
+fn test_show_referenced_by() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |args: &[&str]| -> String {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let target = create(&["Rate limiter"]);
+    create(&["Load test", "-d", &format!("Verify {} under load", target)]);
+
+    peas_cmd()
+        .args(["show", &target])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Referenced by:"))
+        .stdout(predicate::str::contains("Load test"));
+}
+
+#[test]
+fn test_color_choice() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success()
+    };
+    peas(&["init"]);
+    peas(&["create", "Colorful"]);
+
+    // Output is piped, so only `always` colors it
+    peas(&["list", "--no-pager"]).stdout(predicate::str::contains("\x1b[").not());
+    peas(&["list", "--color", "always"]).stdout(predicate::str::contains("\x1b["));
+    peas_cmd()
+        .args(["list", "--color", "never"])
+        .env("CLICOLOR_FORCE", "1")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn test_show_render() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["create", "Styled", "--json", "-d"])
+        .arg("# Plan\n\nShip **soon** with `peas`")
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    peas_cmd()
+        .args(["show", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship **soon** with `peas`"));
+    peas_cmd()
+        .args(["show", &id, "--render"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Ship soon with peas"));
+}
+
+#[test]
+fn test_show_expands_includes() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .assert()
+            .success()
+    };
+    peas(&["init"]);
+    peas(&["create", "Shared criteria", "-d=- Works offline"]);
+    peas(&["memory", "save", "style", "Use plain words"]);
+    let output = peas_cmd()
+        .args(["create", "Login", "--json", "-d"])
+        .arg("Build it.\n\n{{include: shared-criteria}}\n{{include: memory:style}}")
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    peas(&["show", &id]).stdout(predicate::str::contains(
+        "Build it.\n\n- Works offline\nUse plain words",
+    ));
+    // The stored body keeps the directives
+    let path = temp_dir.path().join(format!(".peas/{}--login.md", id));
+    let stored = std::fs::read_to_string(path).unwrap();
+    assert!(stored.contains("{{include: shared-criteria}}"));
+}
+
+#[test]
+fn test_link() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["create", "Link Test", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    let pr = "https://github.com/o/r/pull/12";
+    peas_cmd()
+        .args(["link", &id, pr, "--label", "Fix PR"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[pr] Fix PR"));
+    peas_cmd()
+        .args(["link", &id, "https://example.com/spec", "--kind", "doc"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    // Relinking the same URL updates it instead of adding a duplicate
+    let output = peas_cmd()
+        .args(["link", &id, pr, "-l", "Main PR", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["links"],
+        serde_json::json!([
+            { "url": pr, "label": "Main PR", "kind": "pr" },
+            { "url": "https://example.com/spec", "kind": "doc" },
+        ])
+    );
+
+    peas_cmd()
+        .args(["show", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[pr] Main PR"))
+        .stdout(predicate::str::contains("[doc] https://example.com/spec"));
+
+    peas_cmd()
+        .args(["link", &id, pr, "--remove"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["link", &id, pr, "--remove"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has no link"));
+}
+
+#[test]
+fn test_pr_status_completes_merged_pea() {
+    use std::io::{BufRead, BufReader, Write};
+
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["create", "Ship it", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    // A GitLab stand-in answering every request with a merged merge request
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request_line = String::new();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let _ = tx.send(request_line);
+            let body = r#"{"state":"merged","draft":false}"#;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    let url = format!("http://127.0.0.1:{}/team/app/-/merge_requests/3", port);
+    peas_cmd()
+        .args(["link", &id, &url, "-l", "MR 3"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["pr-status", &id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("merged"))
+        .stdout(predicate::str::contains("MR 3"));
+    assert!(
+        rx.recv()
+            .unwrap()
+            .starts_with("GET /api/v4/projects/team%2Fapp/merge_requests/3 ")
+    );
+
+    let output = peas_cmd()
+        .args(["pr-status", "--complete", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["completed"], serde_json::json!([id]));
+    assert_eq!(json["pull_requests"][0]["state"], "merged");
+
+    peas_cmd()
+        .args(["show", &id, "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"completed\""));
+}
+
+#[test]
+fn test_archive() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["create", "Archive Test", "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let id = json["id"].as_str().unwrap();
+
+    peas_cmd()
+        .args(["archive", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archived"));
+
+    peas_cmd()
+        .arg("list")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archive Test").not());
+
+    peas_cmd()
+        .args(["list", "--archived"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Archive Test"));
+}
+
+// =============================================================================
+// GraphQL
+// =============================================================================
+
+#[test]
+fn test_graphql_query() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["create", "GraphQL Test"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["query", "{ stats { total } }"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\": 1"));
+}
+
+#[test]
+fn test_graphql_mutate() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args([
+            "mutate",
+            "createPea(input: { title: \"Mutation Test\", peaType: TASK }) { id title }",
+        ])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mutation Test"));
+
+    peas_cmd()
+        .arg("list")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Mutation Test"));
+}
+
+// =============================================================================
+// LLM Context Commands
+// =============================================================================
+
+#[test]
+fn test_prime_command() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .arg("prime")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Peas - Issue Tracker"))
+        .stdout(predicate::str::contains("GraphQL Interface"));
+}
+
+#[test]
+fn test_context_command() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .args(["create", "Context Test"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    peas_cmd()
+        .arg("context")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\": 1"))
+        .stdout(predicate::str::contains("\"by_status\""));
+}
+
+#[test]
+fn test_prime_and_context_fit_token_budget() {
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"]).status.success());
+    let create = |args: &[&str]| {
+        let output = peas(&[&["create", "--json"][..], args].concat());
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let body = "Lots of detail about this ticket. ".repeat(40);
+    for i in 0..10 {
+        create(&[&format!("Filler {}", i), "-p", "high", "-d", &body]);
+    }
+    let epic = create(&["Checkout", "-t", "epic"]);
+    let child = create(&["Payment form", "--parent", &epic, "-d", &body]);
+    let note = format!("Payment form ({}) validates on blur", child);
+    assert!(
+        peas(&["memory", "save", "payments", &note])
+            .status
+            .success()
+    );
+    assert!(
+        peas(&["memory", "save", "unrelated", "Something else"])
+            .status
+            .success()
+    );
+
+    let output = peas(&["context", "--budget", "400", "--focus", &epic]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(
+        output.stdout.len() <= 400 * 4,
+        "{} bytes",
+        output.stdout.len()
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let ids: Vec<&str> = json["open_peas"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids[..2], [epic.as_str(), child.as_str()]);
+    assert!(
+        json["open_peas"][1]["body"]
+            .as_str()
+            .unwrap()
+            .ends_with('…')
+    );
+    assert_eq!(json["memories"][0]["key"], "payments");
+    assert_eq!(json["total"], 12);
+    assert!(json["budget"]["omitted_peas"].as_u64().unwrap() > 0);
+
+    // The instructions count against the budget too
+    let output = peas(&["prime", "--budget", "1500"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.chars().count() <= 1500 * 4, "{}", stdout);
+    assert!(stdout.contains("most relevant first"), "{}", stdout);
+    assert!(stdout.contains("more (use `peas list` for full list)"));
+    assert!(
+        stdout.contains("**unrelated**: Something else"),
+        "{}",
+        stdout
+    );
+
+    let output = peas(&["context", "--budget", "0"]);
+    assert_eq!(output.status.code(), Some(4));
+    let output = peas(&["prime", "--focus", "peas-nope1"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+// =============================================================================
+// Frontmatter Format
+// =============================================================================
+
+#[test]
+fn test_toml_frontmatter_default() {
+    let temp_dir = TempDir::new().unwrap();
+
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let output = peas_cmd()
+        .args(["create", "TOML Test", "--json"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let id = json["id"].as_str().unwrap();
+
+    let data_dir = temp_dir.path().join(".peas");
+    let entries: Vec<_> = std::fs::read_dir(&data_dir)
         .unwrap()
         .filter_map(|e| e.ok())
         .filter(|e| {
@@ -644,3 +4348,580 @@ fn test_yaml_frontmatter_preserved_on_update() {
         "Expected YAML frontmatter to be preserved after update"
     );
 }
+
+// =============================================================================
+// Logging
+// =============================================================================
+
+#[test]
+fn test_verbose_json_logging() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    // -vv enables trace-level storage spans, --log-format json makes them parseable
+    let output = peas_cmd()
+        .args(["-vv", "--log-format", "json", "create", "Logged task"])
+        .env_remove("RUST_LOG")
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let lines: Vec<serde_json::Value> = stderr
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("every log line is JSON"))
+        .collect();
+    assert!(lines.iter().any(|l| l["level"] == "DEBUG"));
+    assert!(
+        lines
+            .iter()
+            .any(|l| l["fields"]["message"] == "Creating pea")
+    );
+}
+
+#[test]
+fn test_project_log_file() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let mut config = std::fs::read_to_string(&config_path).unwrap();
+    config.push_str("\n[log]\nfile = true\nmax_files = 3\n");
+    std::fs::write(&config_path, config).unwrap();
+
+    peas_cmd()
+        .args(["create", "Logged task"])
+        .env_remove("RUST_LOG")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let logs_dir = temp_dir.path().join(".peas/logs");
+    assert!(logs_dir.join(".gitignore").exists());
+    let log = std::fs::read_dir(&logs_dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| p.extension().is_some_and(|ext| ext == "log"))
+        .expect("rotating log file is created");
+    let content = std::fs::read_to_string(log).unwrap();
+    assert!(content.contains("Creating pea"));
+}
+
+#[test]
+fn test_sync_with_served_remote() {
+    let local = TempDir::new().unwrap();
+    let remote = TempDir::new().unwrap();
+    for dir in [&local, &remote] {
+        peas_cmd()
+            .arg("init")
+            .current_dir(dir.path())
+            .assert()
+            .success();
+    }
+    peas_cmd()
+        .args(["create", "Written locally"])
+        .current_dir(local.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["create", "Written remotely"])
+        .current_dir(remote.path())
+        .assert()
+        .success();
+
+    let server = Server::start(remote.path(), &[]);
+
+    let url = format!("http://127.0.0.1:{}/", server.port);
+    let output = peas_cmd()
+        .args(["sync", "--remote", &url, "--json"])
+        .current_dir(local.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["pulled"].as_array().unwrap().len(), 1);
+    assert_eq!(report["pushed"].as_array().unwrap().len(), 1);
+    assert!(local.path().join(".peas/local/sync-journal.json").exists());
+
+    for dir in [&local, &remote] {
+        peas_cmd()
+            .args(["list"])
+            .current_dir(dir.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Written locally"))
+            .stdout(predicate::str::contains("Written remotely"));
+    }
+
+    peas_cmd()
+        .args(["sync", "--remote", &url])
+        .current_dir(local.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Already in sync"));
+}
+
+#[test]
+fn test_serve_viewer() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let output = peas_cmd()
+        .args(["create", "Fix <login>", "-t", "bug", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap().to_string();
+
+    let plain = Server::start(temp_dir.path(), &[]);
+    assert!(plain.get("/view").starts_with("HTTP/1.1 404"));
+    drop(plain);
+
+    let server = Server::start(temp_dir.path(), &["--viewer"]);
+    let index = server.get("/view");
+    assert!(index.starts_with("HTTP/1.1 200"), "{}", index);
+    assert!(index.contains(&format!("<a href=\"/view/{}\">Fix &lt;login&gt;</a>", id)));
+    assert!(server.get("/view?status=completed").contains("No peas."));
+    assert!(server.get("/view?status=bogus").starts_with("HTTP/1.1 400"));
+
+    let detail = server.get(&format!("/view/{}", id));
+    assert!(detail.contains("<th>Type</th><td>bug</td>"), "{}", detail);
+    assert!(server.get("/view/peas-nope").starts_with("HTTP/1.1 404"));
+
+    // Read-only: nothing but GET is routed
+    use std::io::{Read, Write};
+    let mut stream = std::net::TcpStream::connect(("127.0.0.1", server.port)).unwrap();
+    write!(
+        stream,
+        "POST /view HTTP/1.1\r\nHost: localhost\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 405"), "{}", response);
+}
+
+#[test]
+fn test_serve_web_ui() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    let server = Server::start(temp_dir.path(), &[]);
+    let page = server.get("/ui");
+    assert!(page.starts_with("HTTP/1.1 200"), "{}", page);
+    assert!(page.contains("content-type: text/html"));
+    assert!(page.contains("<script src=\"/ui/app.js\">"));
+
+    let script = server.get("/ui/app.js");
+    assert!(
+        script.contains("content-type: text/javascript"),
+        "{}",
+        script
+    );
+    assert!(script.contains("setStatus"));
+    assert!(
+        server
+            .get("/ui/style.css")
+            .contains("content-type: text/css")
+    );
+    assert!(server.get("/ui/missing.js").starts_with("HTTP/1.1 404"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_edit_list_applies_buffer() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str]| {
+        let output = peas_cmd()
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        output.stdout
+    };
+    let create = |title: &str| {
+        let json: serde_json::Value =
+            serde_json::from_slice(&peas(&["create", title, "--json"])).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    peas(&["init"]);
+    let login = create("Login form");
+    let logout = create("Logout button");
+    let bug = create("Crash on start");
+    peas(&["update", &bug, "-t", "bug"]);
+
+    // An "editor" that starts the login pea and renames the logout one
+    let editor = temp_dir.path().join("editor.sh");
+    std::fs::write(
+        &editor,
+        format!(
+            "#!/bin/sh\nsed -i -e '/^{}/s/todo/in-progress/' -e 's/Logout button/Log out/' \"$1\"\n",
+            login
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&editor, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = peas_cmd()
+        .args(["edit-list", "-t", "task", "--json"])
+        .env("EDITOR", &editor)
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["updated"].as_array().unwrap().len(), 2);
+
+    let show = |id: &str| -> serde_json::Value {
+        serde_json::from_slice(&peas(&["show", id, "--json"])).unwrap()
+    };
+    assert_eq!(show(&login)["status"], "in-progress");
+    assert_eq!(show(&logout)["title"], "Log out");
+    assert_eq!(show(&bug)["status"], "todo");
+    assert!(!temp_dir.path().join(".peas/local/edit-list.txt").exists());
+
+    // One undo entry for the whole edit
+    let undone = String::from_utf8(peas(&["undo"])).unwrap();
+    assert!(undone.contains("Edit list on 2 peas"), "{}", undone);
+    assert_eq!(show(&login)["status"], "todo");
+    assert_eq!(show(&logout)["title"], "Logout button");
+
+    // A broken line applies nothing
+    std::fs::write(
+        &editor,
+        "#!/bin/sh\nsed -i -e 's/Login form/Sign in/' -e 's/Logout button/Log out/' -e 's/ normal / urgent /' \"$1\"\n",
+    )
+    .unwrap();
+    peas_cmd()
+        .args(["edit-list", "-t", "task"])
+        .env("EDITOR", &editor)
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("Invalid priority: urgent"));
+    assert_eq!(show(&login)["title"], "Login form");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_atomic_bulk_and_apply_roll_back() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let peas = |args: &[&str], stdin: &str| {
+        peas_cmd()
+            .args(args)
+            .write_stdin(stdin)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap()
+    };
+    assert!(peas(&["init"], "").status.success());
+    let create = |title: &str| {
+        let json: serde_json::Value =
+            serde_json::from_slice(&peas(&["create", title, "--json"], "").stdout).unwrap();
+        json["id"].as_str().unwrap().to_string()
+    };
+    let first = create("First");
+    let locked = create("Locked");
+    let last = create("Last");
+
+    // A hook that rejects any update to the locked pea, after the first one was written
+    let hook = temp_dir.path().join(".peas/hooks/pre-update");
+    std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook,
+        "#!/bin/sh\ninput=$(cat)\ncase \"$input\" in *'\"title\":\"Locked\"'*) echo locked >&2; exit 1;; esac\nprintf '%s' \"$input\"\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let status = |id: &str| -> String {
+        let json: serde_json::Value =
+            serde_json::from_slice(&peas(&["show", id, "--json"], "").stdout).unwrap();
+        json["status"].as_str().unwrap().to_string()
+    };
+
+    let output = peas(&["bulk", "done", &first, &locked, &last, "--atomic"], "");
+    assert_eq!(output.status.code(), Some(4), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("rolled back"));
+    assert_eq!(status(&first), "todo");
+    assert_eq!(status(&last), "todo");
+
+    let input = format!(
+        "{}\n{}\n",
+        serde_json::json!({"id": first, "status": "completed"}),
+        serde_json::json!({"id": locked, "status": "completed"}),
+    );
+    assert_eq!(peas(&["apply"], &input).status.code(), Some(4));
+    assert_eq!(status(&first), "todo");
+    assert!(
+        !temp_dir
+            .path()
+            .join(".peas/local/transaction.json")
+            .exists()
+    );
+
+    // Without the locked pea everything is written, as one undo step
+    let output = peas(&["bulk", "done", &first, &last, "--atomic"], "");
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(status(&first), "completed");
+    let undone = String::from_utf8(peas(&["undo"], "").stdout).unwrap();
+    assert!(undone.contains("Bulk update of 2 peas"), "{}", undone);
+    assert_eq!(status(&first), "todo");
+    assert_eq!(status(&last), "todo");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_change_and_veto() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let hooks = temp_dir.path().join(".peas/hooks");
+    std::fs::create_dir_all(&hooks).unwrap();
+    let install = |name: &str, script: &str| {
+        let path = hooks.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    // Tag every new pea for triage
+    install(
+        "pre-create",
+        r#"sed 's/"pea":{/"pea":{"tags":["triage"],/'"#,
+    );
+    install(
+        "pre-delete",
+        "echo 'deleting is not allowed, archive instead' >&2; exit 1",
+    );
+
+    let output = peas_cmd()
+        .args(["create", "Hooked", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["tags"], serde_json::json!(["triage"]));
+    let id = json["id"].as_str().unwrap();
+
+    peas_cmd()
+        .args(["delete", id, "--force"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "Hook pre-delete rejected the operation: deleting is not allowed, archive instead",
+        ));
+    peas_cmd()
+        .args(["show", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+}
+
+#[cfg(feature = "wasm")]
+#[test]
+fn test_wasm_plugin_rejects_delete() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let plugins = temp_dir.path().join(".peas/plugins");
+    std::fs::create_dir_all(&plugins).unwrap();
+    // Replies with an error when the event is `pre-delete`
+    std::fs::write(
+        plugins.join("no-delete.wat"),
+        r#"(module
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (data (i32.const 0) "{\"error\":\"archive instead\"}")
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "on_event") (param $ptr i32) (param $len i32) (result i64)
+            (if (result i64) (i32.eq (i32.load8_u offset=13 (local.get $ptr)) (i32.const 100))
+              (then (i64.const 27))
+              (else (i64.const 0)))))"#,
+    )
+    .unwrap();
+
+    let output = peas_cmd()
+        .args(["create", "Keep me", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let id = json["id"].as_str().unwrap();
+
+    peas_cmd()
+        .args(["delete", id, "--force"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "Hook pre-delete rejected the operation: no-delete.wat: archive instead",
+        ));
+    peas_cmd()
+        .args(["archive", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_rules_apply_and_dry_run() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    let create = |args: &[&str]| -> serde_json::Value {
+        let output = peas_cmd()
+            .args(["create", "--json"])
+            .args(args)
+            .current_dir(temp_dir.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+    let existing = create(&["Old XSS", "-t", "bug", "--tag", "security"]);
+    create(&["Unrelated", "-t", "task"]);
+
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config
+            + r#"
+[rules.security-bugs]
+when = 'pea.type == "bug" && "security" in pea.tags'
+then = 'pea.priority = "critical"'
+
+[rules.no-untitled]
+on = ["create", "update"]
+when = 'pea.title.starts_with("TODO")'
+then = 'throw "give it a real title"'
+"#,
+    )
+    .unwrap();
+
+    let output = peas_cmd()
+        .args(["rules", "test", "--rule", "security-bugs", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["matched"], 1);
+    assert_eq!(json[0]["results"][0]["id"], existing["id"]);
+    assert_eq!(
+        json[0]["results"][0]["changes"],
+        serde_json::json!([{ "field": "priority", "from": "normal", "to": "critical" }])
+    );
+    // Dry run only
+    let id = existing["id"].as_str().unwrap();
+    peas_cmd()
+        .args(["show", id])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("critical").not());
+
+    let bug = create(&["New XSS", "-t", "bug", "--tag", "security"]);
+    assert_eq!(bug["priority"], "critical");
+    peas_cmd()
+        .args(["create", "TODO later"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains(
+            "Rule no-untitled: give it a real title",
+        ));
+}
+
+#[test]
+fn test_lint_fails_on_threshold() {
+    let temp_dir = TempDir::new().unwrap();
+    peas_cmd()
+        .arg("init")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["create", "Fix", "-t", "bug"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+    peas_cmd()
+        .args(["create", "Dark mode for the editor", "-t", "feature"])
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    // Only warnings, which pass by default
+    peas_cmd()
+        .arg("lint")
+        .current_dir(temp_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("[title-length]"))
+        .stdout(predicate::str::contains("[missing-body]"))
+        .stdout(predicate::str::contains("0 error(s), 2 warning(s)"));
+
+    let output = peas_cmd()
+        .args(["lint", "--fail-on", "warning", "--json"])
+        .current_dir(temp_dir.path())
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(4));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["passed"], false);
+    assert_eq!(json["counts"]["warning"], 2);
+
+    // Raising a check to error fails the default run
+    let config_path = temp_dir.path().join(".peas/config.toml");
+    let config = std::fs::read_to_string(&config_path).unwrap();
+    std::fs::write(
+        &config_path,
+        config + "\n[lint.checks]\nmissing-body = \"error\"\ntitle-length = \"off\"\n",
+    )
+    .unwrap();
+    peas_cmd()
+        .arg("lint")
+        .current_dir(temp_dir.path())
+        .assert()
+        .code(4)
+        .stdout(predicate::str::contains("[title-length]").not())
+        .stderr(predicate::str::contains("1 finding(s) at error or above"));
+}
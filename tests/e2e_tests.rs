@@ -0,0 +1,127 @@
+//! End-to-end tests driven through the library API and CLI handlers.
+
+use peas::model::{PeaPriority, PeaStatus, PeaType};
+use peas::storage::{FrontmatterFormat, parse_markdown, render_markdown_with_format};
+use peas::testing::{TestProject, assert_golden, fixed_pea};
+
+fn golden(name: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name)
+}
+
+fn sample_pea() -> peas::model::Pea {
+    fixed_pea("peas-gold1", "Fix login bug", PeaType::Bug)
+        .with_status(PeaStatus::InProgress)
+        .with_priority(PeaPriority::High)
+        .with_tags(vec!["auth".to_string(), "urgent".to_string()])
+        .with_external_refs(vec!["GH-42".to_string()])
+        .with_body("The login page crashes on submit.\n\n- [ ] reproduce\n- [ ] fix".to_string())
+}
+
+// =============================================================================
+// Markdown output
+// =============================================================================
+
+#[test]
+fn test_stored_file_matches_golden_toml() {
+    let project = TestProject::new();
+    let pea = project.insert(sample_pea());
+
+    assert_golden(golden("pea_toml.md"), &project.read_pea_file(&pea.id));
+}
+
+#[test]
+fn test_rendered_yaml_matches_golden() {
+    let rendered = render_markdown_with_format(&sample_pea(), FrontmatterFormat::Yaml).unwrap();
+    assert_golden(golden("pea_yaml.md"), &rendered);
+}
+
+#[test]
+fn test_stored_file_round_trips() {
+    let project = TestProject::new();
+    let pea = project.insert(sample_pea());
+
+    let parsed = parse_markdown(&project.read_pea_file(&pea.id)).unwrap();
+    assert_eq!(parsed, pea);
+    assert_eq!(project.client().get(&pea.id).unwrap(), pea);
+}
+
+// =============================================================================
+// CLI commands
+// =============================================================================
+
+#[test]
+fn test_create_and_update_through_cli() {
+    let project = TestProject::new();
+    project
+        .run(&[
+            "create",
+            "Embedded",
+            "-t",
+            "feature",
+            "--tag",
+            "api",
+            "-d",
+            "Body with --- inside",
+        ])
+        .unwrap();
+
+    let pea = project.client().search("title:Embedded").unwrap().remove(0);
+    assert_eq!(pea.pea_type, PeaType::Feature);
+    assert_eq!(pea.tags, vec!["api"]);
+    assert_eq!(pea.body, "Body with --- inside");
+
+    project
+        .run(&[
+            "update",
+            &pea.id,
+            "--status",
+            "completed",
+            "--add-tag",
+            "done",
+        ])
+        .unwrap();
+    let updated = project.client().get(&pea.id).unwrap();
+    assert_eq!(updated.status, PeaStatus::Completed);
+    assert_eq!(updated.tags, vec!["api", "done"]);
+    assert_eq!(updated.body, pea.body);
+}
+
+#[test]
+fn test_blocked_by_relationship() {
+    let project = TestProject::new();
+    let blocker = project.create("Blocker", PeaType::Task);
+    project
+        .run(&["create", "Blocked", "--blocked-by", &blocker.id])
+        .unwrap();
+
+    let blocked = project.client().search("title:Blocked").unwrap().remove(0);
+    assert_eq!(
+        project.client().get(&blocker.id).unwrap().blocking,
+        vec![blocked.id]
+    );
+}
+
+#[test]
+fn test_archive_and_undo() {
+    let project = TestProject::new();
+    let pea = project.create("Short lived", PeaType::Chore);
+
+    project.run(&["archive", &pea.id]).unwrap();
+    let stats = project.client().stats().unwrap();
+    assert_eq!((stats.total, stats.archived), (0, 1));
+
+    project.run(&["undo"]).unwrap();
+    let stats = project.client().stats().unwrap();
+    assert_eq!((stats.total, stats.archived), (1, 0));
+    assert_eq!(project.client().get(&pea.id).unwrap(), pea);
+}
+
+#[test]
+fn test_errors_surface_from_handlers() {
+    let project = TestProject::new();
+    assert!(project.run(&["show", "peas-nope"]).is_err());
+    assert!(project.run(&["init"]).is_err());
+    assert!(project.run(&["create", ""]).is_err());
+}
@@ -196,6 +196,231 @@ async fn test_delete_pea() {
     assert!(data["pea"].is_null());
 }
 
+#[tokio::test]
+async fn test_delete_pea_that_no_longer_parses() {
+    let (temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Broken" }) { id } }"#)
+        .await;
+    let data = res.data.into_json().unwrap();
+    let id = data["createPea"]["id"].as_str().unwrap().to_string();
+    let path = std::fs::read_dir(temp_dir.path().join(".peas"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .find(|path| path.file_name().unwrap().to_string_lossy().starts_with(&id))
+        .unwrap();
+    std::fs::write(&path, "+++\nnot = [valid\n+++\n").unwrap();
+
+    let mutation = format!(r#"mutation {{ deletePea(id: "{}") }}"#, id);
+    let res = schema.execute(&mutation).await;
+    assert!(res.errors.is_empty(), "delete errors: {:?}", res.errors);
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn test_mutations_record_undo() {
+    let (temp_dir, schema) = setup_project();
+    let undo = peas::undo::UndoManager::new(&temp_dir.path().join(".peas"));
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Undoable" }) { id } }"#)
+        .await;
+    let id = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let mutation = format!(
+        r#"mutation {{ setStatus(id: "{}", status: IN_PROGRESS) {{ status }} }}"#,
+        id
+    );
+    assert!(schema.execute(&mutation).await.errors.is_empty());
+    // Dry runs write nothing to undo
+    let mutation = format!(r#"mutation {{ deletePea(id: "{}", dryRun: true) }}"#, id);
+    assert!(schema.execute(&mutation).await.errors.is_empty());
+    assert_eq!(
+        undo.undo_stack_descriptions(),
+        vec![format!("Create {}", id), format!("Update {}", id)]
+    );
+
+    undo.undo().unwrap();
+    let query = format!(r#"{{ pea(id: "{}") {{ status }} }}"#, id);
+    let res = schema.execute(&query).await;
+    assert_eq!(res.data.into_json().unwrap()["pea"]["status"], "TODO");
+
+    let mutation = format!(r#"mutation {{ deletePea(id: "{}") }}"#, id);
+    assert!(schema.execute(&mutation).await.errors.is_empty());
+    undo.undo().unwrap();
+    let res = schema.execute(&query).await;
+    assert_eq!(res.data.into_json().unwrap()["pea"]["status"], "TODO");
+}
+
+#[tokio::test]
+async fn test_dry_run_mutations_do_not_write() {
+    let (_temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Draft" }, dryRun: true) { id title } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let res = schema.execute("{ stats { total } }").await;
+    assert_eq!(res.data.into_json().unwrap()["stats"]["total"], 0);
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Real" }) { id } }"#)
+        .await;
+    let id = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // The would-be result comes back, the stored pea is unchanged
+    let mutation = format!(
+        r#"mutation {{ setStatus(id: "{}", status: COMPLETED, dryRun: true) {{ status }} }}"#,
+        id
+    );
+    let res = schema.execute(&mutation).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(
+        res.data.into_json().unwrap()["setStatus"]["status"],
+        "COMPLETED"
+    );
+    let mutation = format!(r#"mutation {{ deletePea(id: "{}", dryRun: true) }}"#, id);
+    assert!(schema.execute(&mutation).await.errors.is_empty());
+    let query = format!(r#"{{ pea(id: "{}") {{ status }} }}"#, id);
+    let res = schema.execute(&query).await;
+    assert_eq!(res.data.into_json().unwrap()["pea"]["status"], "TODO");
+
+    // Dry runs still fail like the real mutation would
+    let res = schema
+        .execute(
+            r#"mutation { createPea(input: { title: "Orphan", parent: "peas-gone1" }, dryRun: true) { id } }"#,
+        )
+        .await;
+    assert!(!res.errors.is_empty());
+    let res = schema
+        .execute(r#"mutation { archivePea(id: "peas-gone1", dryRun: true) }"#)
+        .await;
+    assert!(!res.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_set_size_and_point_rollups() {
+    let (_temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Epic", peaType: EPIC }) { id } }"#)
+        .await;
+    let epic = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let create = format!(
+        r#"mutation {{ createPea(input: {{ title: "Task", parent: "{}" }}) {{ id }} }}"#,
+        epic
+    );
+    let res = schema.execute(&create).await;
+    let task = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let res = schema
+        .execute(format!(
+            r#"mutation {{ setSize(id: "{}", size: "13") {{ size }} }}"#,
+            task
+        ))
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(res.data.into_json().unwrap()["setSize"]["size"], "13");
+
+    let res = schema
+        .execute(format!(
+            r#"mutation {{ setSize(id: "{}", size: "XL") {{ size }} }}"#,
+            task
+        ))
+        .await;
+    assert_eq!(res.errors.len(), 1);
+
+    let res = schema
+        .execute(
+            "{ stats { points { points done unestimated } sizeRollups { id totals { points } } } }",
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["stats"]["points"]["points"], 13);
+    assert_eq!(data["stats"]["points"]["unestimated"], 1);
+    assert_eq!(data["stats"]["sizeRollups"][0]["id"], epic.as_str());
+    assert_eq!(data["stats"]["sizeRollups"][0]["totals"]["points"], 13);
+}
+
+#[tokio::test]
+async fn test_add_and_remove_link() {
+    let (_temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Linked" }) { id } }"#)
+        .await;
+    let data = res.data.into_json().unwrap();
+    let id = data["createPea"]["id"].as_str().unwrap().to_string();
+
+    let mutation = format!(
+        r#"mutation {{ addLink(id: "{}", url: "https://gitlab.com/o/r/-/merge_requests/4", label: "MR") {{ links {{ url label kind }} }} }}"#,
+        id
+    );
+    let res = schema.execute(&mutation).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["addLink"]["links"][0]["kind"], "PR");
+    assert_eq!(data["addLink"]["links"][0]["label"], "MR");
+
+    let mutation = format!(
+        r#"mutation {{ removeLink(id: "{}", url: "https://gitlab.com/o/r/-/merge_requests/4") {{ links {{ url }} }} }}"#,
+        id
+    );
+    let res = schema.execute(&mutation).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["removeLink"]["links"], serde_json::json!([]));
+
+    // Removing it again fails, as with `peas link --remove`
+    let res = peas::graphql::execute(&schema, async_graphql::Request::new(mutation)).await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "NOT_FOUND");
+    assert!(
+        errors[0]["message"]
+            .as_str()
+            .unwrap()
+            .contains("has no link to")
+    );
+}
+
+#[tokio::test]
+async fn test_referenced_by() {
+    let (_temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Auth API" }) { id } }"#)
+        .await;
+    let data = res.data.into_json().unwrap();
+    let id = data["createPea"]["id"].as_str().unwrap().to_string();
+
+    let mutation = format!(
+        r#"mutation {{ createPea(input: {{ title: "Docs", body: "Document {} first" }}) {{ id }} }}"#,
+        id
+    );
+    let res = schema.execute(&mutation).await;
+    let data = res.data.into_json().unwrap();
+    let docs_id = data["createPea"]["id"].as_str().unwrap().to_string();
+
+    let query = format!(r#"{{ pea(id: "{}") {{ referencedBy }} }}"#, id);
+    let res = schema.execute(&query).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["pea"]["referencedBy"], serde_json::json!([docs_id]));
+}
+
 #[tokio::test]
 async fn test_archive_pea() {
     let (_temp_dir, schema) = setup_project();
@@ -237,3 +462,1050 @@ async fn test_children_query() {
     assert_eq!(data["children"].as_array().unwrap().len(), 1);
     assert_eq!(data["children"][0]["title"], "Child");
 }
+
+#[tokio::test]
+async fn test_tags_query() {
+    let (_temp_dir, schema) = setup_project();
+
+    schema
+        .execute(r#"mutation { createPea(input: { title: "A", tags: ["ui", "backend"] }) { id } }"#)
+        .await;
+    schema
+        .execute(r#"mutation { createPea(input: { title: "B", tags: ["UX"] }) { id } }"#)
+        .await;
+
+    let res = schema.execute(r#"{ tags { name count allowed } }"#).await;
+    assert!(res.errors.is_empty());
+    let json = res.data.into_json().unwrap();
+    assert_eq!(json["tags"].as_array().unwrap().len(), 3);
+    assert_eq!(json["tags"][0]["allowed"], true);
+
+    let res = schema.execute(r#"{ tags(prefix: "u") { name } }"#).await;
+    let json = res.data.into_json().unwrap();
+    let names: Vec<_> = json["tags"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["UX", "ui"]);
+}
+
+#[tokio::test]
+async fn test_errors_carry_code_extensions() {
+    let (_temp_dir, schema) = setup_project();
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(r#"mutation { createPea(input: { title: "" }) { id } }"#),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "VALIDATION_FAILED");
+    assert_eq!(errors[0]["extensions"]["field"], "title");
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(
+            r#"mutation { setStatus(id: "peas-nope", status: TODO) { id } }"#,
+        ),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "NOT_FOUND");
+    assert_eq!(errors[0]["extensions"]["id"], "peas-nope");
+}
+
+#[tokio::test]
+async fn test_sync_changes_and_push() {
+    let (_temp_dir, schema) = setup_project();
+
+    let mut pea = peas::model::Pea::new(
+        "peas-sync1".into(),
+        "From elsewhere".into(),
+        peas::model::PeaType::Bug,
+    );
+    pea.updated = "2026-01-01T00:00:00Z".parse().unwrap();
+    let push = |pea: &peas::model::Pea| {
+        async_graphql::Request::new("mutation($peas: [String!]!) { syncPush(peas: $peas) }")
+            .variables(async_graphql::Variables::from_json(serde_json::json!({
+                "peas": [peas::sync::encode(pea).unwrap()]
+            })))
+    };
+
+    let res = schema.execute(push(&pea)).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(
+        res.data.into_json().unwrap()["syncPush"],
+        serde_json::json!(["peas-sync1"])
+    );
+
+    // An older version is refused
+    let mut stale = pea.clone();
+    stale.title = "Stale".into();
+    stale.updated = "2025-01-01T00:00:00Z".parse().unwrap();
+    let res = schema.execute(push(&stale)).await;
+    assert_eq!(
+        res.data.into_json().unwrap()["syncPush"],
+        serde_json::json!([])
+    );
+
+    let res = schema
+        .execute(r#"{ syncChanges(since: "2025-06-01T00:00:00Z") { id updated content } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let json = res.data.into_json().unwrap();
+    assert_eq!(json["syncChanges"][0]["id"], "peas-sync1");
+    let content = json["syncChanges"][0]["content"].as_str().unwrap();
+    assert_eq!(peas::sync::decode(content).unwrap(), pea);
+
+    let res = schema
+        .execute(r#"{ syncChanges(since: "2026-06-01T00:00:00Z") { id } }"#)
+        .await;
+    assert_eq!(
+        res.data.into_json().unwrap()["syncChanges"],
+        serde_json::json!([])
+    );
+}
+
+#[tokio::test]
+async fn test_sync_push_checks_peas_and_orders_parents_first() {
+    let (temp_dir, schema) = setup_project();
+
+    let parent = peas::model::Pea::new(
+        "peas-syncp".into(),
+        "Parent".into(),
+        peas::model::PeaType::Epic,
+    );
+    let child = peas::model::Pea::new(
+        "peas-syncc".into(),
+        "Child".into(),
+        peas::model::PeaType::Task,
+    )
+    .with_parent(Some("peas-syncp".into()));
+    let push = |peas: &[&peas::model::Pea], dry_run: bool| {
+        let contents: Vec<String> = peas
+            .iter()
+            .map(|p| peas::sync::encode(p).unwrap())
+            .collect();
+        async_graphql::Request::new(
+            "mutation($peas: [String!]!, $dryRun: Boolean!) { syncPush(peas: $peas, dryRun: $dryRun) }",
+        )
+        .variables(async_graphql::Variables::from_json(
+            serde_json::json!({ "peas": contents, "dryRun": dry_run }),
+        ))
+    };
+    let repo = || peas::storage::PeaRepository::new(&PeasConfig::default(), temp_dir.path());
+
+    // The child comes first but is written after its parent
+    let res = schema.execute(push(&[&child, &parent], true)).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(
+        res.data.into_json().unwrap()["syncPush"],
+        serde_json::json!(["peas-syncp", "peas-syncc"])
+    );
+    assert!(repo().list().unwrap().is_empty());
+
+    let res = schema.execute(push(&[&child, &parent], false)).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(repo().list().unwrap().len(), 2);
+
+    // A pea pointing at an unknown parent is rejected like any other write
+    let orphan = peas::model::Pea::new(
+        "peas-synco".into(),
+        "Orphan".into(),
+        peas::model::PeaType::Task,
+    )
+    .with_parent(Some("peas-nope".into()));
+    let res = schema.execute(push(&[&orphan], false)).await;
+    assert!(!res.errors.is_empty());
+    assert!(!repo().exists("peas-synco"));
+}
+
+#[tokio::test]
+async fn test_peas_as_of_snapshot() {
+    let (temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Before" }) { id } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "create errors: {:?}", res.errors);
+
+    let config = PeasConfig::default();
+    let repo = peas::storage::PeaRepository::new(&config, temp_dir.path());
+    let snapshot = peas::snapshot::Snapshot::new("before", &repo.list().unwrap(), None).unwrap();
+    peas::snapshot::Snapshots::new(&config.data_path(temp_dir.path()))
+        .save(&snapshot)
+        .unwrap();
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "After" }) { id } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "create errors: {:?}", res.errors);
+
+    let res = schema
+        .execute(r#"{ peas(asOf: "before") { totalCount nodes { title } } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["peas"]["totalCount"], 1);
+    assert_eq!(data["peas"]["nodes"][0]["title"], "Before");
+
+    let res = schema
+        .execute(r#"{ peas(asOf: "someday") { totalCount } }"#)
+        .await;
+    assert!(!res.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_request_actor_is_credited_and_filterable() {
+    let (_temp_dir, schema) = setup_project();
+
+    let res = schema
+        .execute(
+            async_graphql::Request::new(
+                r#"mutation { createPea(input: { title: "Agent task" }) { id createdBy updatedBy } }"#,
+            )
+            .data(peas::graphql::Actor("agent-x".into())),
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["createPea"]["createdBy"], "agent-x");
+    assert_eq!(data["createPea"]["updatedBy"], "agent-x");
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Other task" }) { createdBy } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert!(res.data.into_json().unwrap()["createPea"]["createdBy"].is_null());
+
+    let res = schema
+        .execute(r#"{ peas(filter: { createdBy: "agent-x" }) { nodes { title } } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["peas"]["nodes"].as_array().unwrap().len(), 1);
+    assert_eq!(data["peas"]["nodes"][0]["title"], "Agent task");
+}
+
+#[tokio::test]
+async fn test_claim_and_release_pea() {
+    let (_temp_dir, schema) = setup_project();
+    let as_actor = |query: &str, actor: &str| {
+        async_graphql::Request::new(query).data(peas::graphql::Actor(actor.into()))
+    };
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Login form" }) { id } }"#)
+        .await;
+    let id = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let claim = format!(
+        r#"mutation {{ claimPea(id: "{}", ttl: "30m") {{ claim {{ by until }} }} }}"#,
+        id
+    );
+    let res = schema.execute(claim.as_str()).await;
+    assert!(
+        res.errors[0]
+            .message
+            .contains("Claims need to know who holds them")
+    );
+
+    let res = schema.execute(as_actor(&claim, "agent-x")).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(
+        res.data.into_json().unwrap()["claimPea"]["claim"]["by"],
+        "agent-x"
+    );
+
+    let res = peas::graphql::execute(&schema, as_actor(&claim, "agent-y")).await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "CLAIMED");
+    assert_eq!(errors[0]["extensions"]["claimedBy"], "agent-x");
+
+    let res = schema
+        .execute(as_actor(
+            r#"{ peas(filter: { unclaimed: true }) { totalCount } }"#,
+            "agent-y",
+        ))
+        .await;
+    assert_eq!(res.data.into_json().unwrap()["peas"]["totalCount"], 0);
+
+    let release = format!(
+        r#"mutation {{ releasePea(id: "{}") {{ claim {{ by }} }} }}"#,
+        id
+    );
+    let res = schema.execute(as_actor(&release, "agent-x")).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert!(res.data.into_json().unwrap()["releasePea"]["claim"].is_null());
+}
+
+#[tokio::test]
+async fn test_add_note_to_work_log() {
+    let (_temp_dir, schema) = setup_project();
+    let res = schema
+        .execute(
+            r#"mutation { createPea(input: { title: "Login form", body: "Build it" }) { id } }"#,
+        )
+        .await;
+    let id = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let query = format!(
+        r#"mutation {{ addNote(id: "{}", text: "Form renders") {{ body workLog }} }}"#,
+        id
+    );
+    let res = schema
+        .execute(async_graphql::Request::new(query).data(peas::graphql::Actor("agent-x".into())))
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert!(
+        data["addNote"]["body"]
+            .as_str()
+            .unwrap()
+            .starts_with("Build it\n\n## Work log\n")
+    );
+    let notes = data["addNote"]["workLog"].as_array().unwrap();
+    assert_eq!(notes.len(), 1);
+    assert!(
+        notes[0]
+            .as_str()
+            .unwrap()
+            .ends_with("(agent-x) Form renders")
+    );
+
+    let query = format!(
+        r#"mutation {{ addNote(id: "{}", text: " ") {{ id }} }}"#,
+        id
+    );
+    assert!(!schema.execute(query.as_str()).await.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_components_in_filters_and_stats() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = PeasConfig::default();
+    config.components.insert(
+        "auth".into(),
+        peas::config::ComponentSettings {
+            owners: vec!["ann".into()],
+            auto_assign: true,
+            ..Default::default()
+        },
+    );
+    config
+        .components
+        .insert("billing".into(), Default::default());
+    let schema = build_schema(config, temp_dir.path().to_path_buf());
+
+    let res = schema
+        .execute(
+            r#"mutation { createPea(input: { title: "Login", component: "auth" }) { id component assignee } }"#,
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["createPea"]["assignee"], "ann");
+    let id = data["createPea"]["id"].as_str().unwrap().to_string();
+    let res = schema
+        .execute(
+            r#"mutation { createPea(input: { title: "Invoice", component: "invoices" }) { id } }"#,
+        )
+        .await;
+    assert!(!res.errors.is_empty());
+
+    let res = schema
+        .execute(r#"{ peas(filter: { assignee: "ann" }) { nodes { id } } }"#)
+        .await;
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["peas"]["nodes"][0]["id"], id.as_str());
+
+    let query = format!(
+        r#"mutation {{ updatePea(input: {{ id: "{}", component: "billing", assignee: "" }}) {{ component assignee }} }}"#,
+        id
+    );
+    let res = schema.execute(query.as_str()).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["updatePea"]["component"], "billing");
+    assert!(data["updatePea"]["assignee"].is_null());
+
+    let res = schema
+        .execute("{ stats { byComponent { component count } } }")
+        .await;
+    let data = res.data.into_json().unwrap();
+    assert_eq!(
+        data["stats"]["byComponent"],
+        serde_json::json!([
+            {"component": "auth", "count": 0},
+            {"component": "billing", "count": 1}
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_peas_sort() {
+    let (_temp_dir, schema) = setup_project();
+    for (title, priority) in [("Low", "LOW"), ("Urgent", "CRITICAL"), ("Also low", "LOW")] {
+        let mutation = format!(
+            r#"mutation {{ createPea(input: {{ title: "{}", priority: {} }}) {{ id }} }}"#,
+            title, priority
+        );
+        schema.execute(mutation.as_str()).await;
+    }
+
+    let res = schema
+        .execute(r#"{ peas(sort: "priority,-title", limit: 2) { nodes { title } totalCount } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["peas"]["totalCount"], 3);
+    assert_eq!(
+        data["peas"]["nodes"],
+        serde_json::json!([{"title": "Urgent"}, {"title": "Low"}])
+    );
+
+    let res = schema
+        .execute(r#"{ peas(sort: "size") { totalCount } }"#)
+        .await;
+    assert!(!res.errors.is_empty());
+}
+
+#[tokio::test]
+async fn test_reopen_pea_with_lock_closed() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = PeasConfig::default();
+    config.peas.lock_closed = true;
+    let schema = build_schema(config, temp_dir.path().to_path_buf());
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Shipped", status: COMPLETED }) { id } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    let id = data["createPea"]["id"].as_str().unwrap().to_string();
+
+    let query = format!(
+        r#"mutation {{ updatePea(input: {{ id: "{}", title: "Renamed" }}) {{ id }} }}"#,
+        id
+    );
+    assert!(!schema.execute(query.as_str()).await.errors.is_empty());
+
+    let query = format!(
+        r#"mutation {{ reopenPea(id: "{}", status: IN_PROGRESS) {{ status reopenedCount body }} }}"#,
+        id
+    );
+    let res = schema.execute(query.as_str()).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["reopenPea"]["status"], "IN_PROGRESS");
+    assert_eq!(data["reopenPea"]["reopenedCount"], 1);
+    assert!(
+        data["reopenPea"]["body"]
+            .as_str()
+            .unwrap()
+            .contains("Reopened (was completed)")
+    );
+}
+
+#[tokio::test]
+async fn test_pea_resolves_former_id() {
+    let temp_dir = TempDir::new().unwrap();
+    let schema = build_schema(PeasConfig::default(), temp_dir.path().to_path_buf());
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Renamed" }) { id } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    let id = data["createPea"]["id"].as_str().unwrap().to_string();
+
+    let mut aliases = peas::aliases::Aliases::load(&temp_dir.path().join(".peas")).unwrap();
+    aliases.add("peas-old01", &id);
+    aliases.save().unwrap();
+
+    let res = schema
+        .execute(r#"{ pea(id: "peas-old01") { id aliases } search(query: "old01") { id } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["pea"]["id"], id.as_str());
+    assert_eq!(data["pea"]["aliases"], serde_json::json!(["peas-old01"]));
+    assert_eq!(data["search"][0]["id"], id.as_str());
+}
+
+#[tokio::test]
+async fn test_deferred_bodies_arrive_last() {
+    let (_temp_dir, schema) = setup_project();
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Slow", body: "Long body" }) { id } }"#)
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+
+    let query = "{ peas { nodes { title ... @defer { body } } } stats { ... @defer { total } } }";
+
+    // Without incremental delivery the directives are ignored
+    let res = schema.execute(query).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(data["peas"]["nodes"][0]["body"], "Long body");
+    assert_eq!(data["stats"]["total"], 1);
+
+    let mut request = async_graphql::Request::new(query);
+    let initial = peas::graphql::defer::initial(&mut request).unwrap();
+    request.set_parsed_query(initial);
+    let res = schema.execute(request).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let first = peas::graphql::defer::initial_payload(&res);
+    assert_eq!(first["hasNext"], true);
+    assert_eq!(first["data"]["peas"]["nodes"][0]["title"], "Slow");
+    assert!(first["data"]["peas"]["nodes"][0].get("body").is_none());
+
+    let last = peas::graphql::defer::subsequent_payload(&first, &schema.execute(query).await);
+    assert_eq!(
+        last,
+        serde_json::json!({
+            "hasNext": false,
+            "incremental": [
+                { "data": { "body": "Long body" }, "path": ["peas", "nodes", 0] },
+                { "data": { "total": 1 }, "path": ["stats"] },
+            ],
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_search_hits_are_ranked_with_snippets() {
+    let (_temp_dir, schema) = setup_project();
+    for input in [
+        r#"{ title: "Refactor", body: "The login form is slow" }"#,
+        r#"{ title: "Login page" }"#,
+    ] {
+        let res = schema
+            .execute(format!("mutation {{ createPea(input: {}) {{ id }} }}", input).as_str())
+            .await;
+        assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    }
+
+    let res = schema
+        .execute(
+            r#"{
+                searchHits(query: "login") { pea { title } score snippet }
+                search(query: "login -page") { title }
+            }"#,
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    let hits = &data["searchHits"];
+    assert_eq!(hits[0]["pea"]["title"], "Login page");
+    assert_eq!(hits[0]["snippet"], serde_json::Value::Null);
+    assert_eq!(hits[1]["pea"]["title"], "Refactor");
+    assert_eq!(hits[1]["snippet"], "The **login** form is slow");
+    assert!(hits[0]["score"].as_u64() > hits[1]["score"].as_u64());
+    assert_eq!(data["search"], serde_json::json!([{ "title": "Refactor" }]));
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(r#"{ search(query: "\"open") { id } }"#),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "VALIDATION_FAILED");
+}
+
+#[tokio::test]
+async fn test_similar_peas_by_embeddings() {
+    let (_temp_dir, schema) = setup_project();
+    let mut ids = Vec::new();
+    for input in [
+        r#"{ title: "Login broken after password reset", body: "Users cannot authenticate" }"#,
+        r#"{ title: "Dark mode colors" }"#,
+        r#"{ title: "Session lost during login", body: "The auth flow drops the token" }"#,
+    ] {
+        let res = schema
+            .execute(format!("mutation {{ createPea(input: {}) {{ id }} }}", input).as_str())
+            .await;
+        assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+        ids.push(res.data.into_json().unwrap()["createPea"]["id"].clone());
+    }
+
+    let query = format!(
+        r#"{{ similar(id: {}, limit: 1) {{ pea {{ title }} similarity }} }}"#,
+        ids[0]
+    );
+    let res = peas::graphql::execute(&schema, async_graphql::Request::new(query)).await;
+    if cfg!(feature = "semantic") {
+        assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+        let data = res.data.into_json().unwrap();
+        assert_eq!(
+            data["similar"][0]["pea"]["title"],
+            "Session lost during login"
+        );
+        assert!(data["similar"][0]["similarity"].as_f64().unwrap() > 0.0);
+    } else {
+        let errors = serde_json::to_value(&res.errors).unwrap();
+        assert_eq!(errors[0]["extensions"]["code"], "CONFIG_ERROR");
+    }
+}
+
+#[tokio::test]
+async fn test_pea_related_resolver() {
+    let (_temp_dir, schema) = setup_project();
+    let mut ids = Vec::new();
+    for input in [
+        r#"{ title: "Login form", tags: ["auth"] }"#,
+        r#"{ title: "Audit log", tags: ["auth"] }"#,
+        r#"{ title: "Dark mode" }"#,
+    ] {
+        let res = schema
+            .execute(format!("mutation {{ createPea(input: {}) {{ id }} }}", input).as_str())
+            .await;
+        assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+        ids.push(res.data.into_json().unwrap()["createPea"]["id"].clone());
+    }
+
+    let query = format!(
+        r#"{{ pea(id: {}) {{ related {{ pea {{ title }} score reasons }} }} }}"#,
+        ids[0]
+    );
+    let res = schema.execute(query.as_str()).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let data = res.data.into_json().unwrap();
+    assert_eq!(
+        data["pea"]["related"],
+        serde_json::json!([
+            { "pea": { "title": "Audit log" }, "score": 10, "reasons": ["shares tag auth"] }
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_stats_breakdowns_and_date_range() {
+    let (_temp_dir, schema) = setup_project();
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "v1", peaType: MILESTONE }) { id } }"#)
+        .await;
+    let milestone = res.data.into_json().unwrap()["createPea"]["id"].clone();
+    for input in [
+        format!(
+            r#"{{ title: "A", priority: HIGH, tags: ["ui", "auth"], assignee: "ana", parent: {} }}"#,
+            milestone
+        ),
+        r#"{ title: "B", priority: HIGH, tags: ["auth"], status: COMPLETED }"#.to_string(),
+    ] {
+        let res = schema
+            .execute(format!("mutation {{ createPea(input: {}) {{ id }} }}", input).as_str())
+            .await;
+        assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    }
+
+    let res = schema
+        .execute(
+            r#"{ stats(createdAfter: "2000-01-01") {
+                total
+                byPriority { high normal }
+                byTag { tag count }
+                byAssignee { assignee count }
+                byMilestone { title total byStatus { todo } }
+                openAge { minDays maxDays count }
+            } }"#,
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let stats = &res.data.into_json().unwrap()["stats"];
+    assert_eq!(stats["total"], 3);
+    assert_eq!(
+        stats["byPriority"],
+        serde_json::json!({ "high": 2, "normal": 1 })
+    );
+    assert_eq!(
+        stats["byTag"],
+        serde_json::json!([{ "tag": "auth", "count": 2 }, { "tag": "ui", "count": 1 }])
+    );
+    assert_eq!(
+        stats["byAssignee"],
+        serde_json::json!([
+            { "assignee": null, "count": 2 },
+            { "assignee": "ana", "count": 1 },
+        ])
+    );
+    assert_eq!(
+        stats["byMilestone"],
+        serde_json::json!([{ "title": "v1", "total": 1, "byStatus": { "todo": 1 } }])
+    );
+    assert_eq!(
+        stats["openAge"][0],
+        serde_json::json!({ "minDays": 0, "maxDays": 1, "count": 2 })
+    );
+    assert_eq!(stats["openAge"][5]["maxDays"], serde_json::Value::Null);
+
+    let res = schema
+        .execute(r#"{ stats(createdAfter: "2999-01-01T00:00:00Z") { total } }"#)
+        .await;
+    assert_eq!(res.data.into_json().unwrap()["stats"]["total"], 0);
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(r#"{ stats(createdBefore: "last week") { total } }"#),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "VALIDATION_FAILED");
+}
+
+#[tokio::test]
+async fn test_archived_peas_and_unarchive() {
+    let (temp_dir, schema) = setup_project();
+    let mut ids = Vec::new();
+    for title in ["Kept", "Shelved"] {
+        let res = schema
+            .execute(
+                format!(
+                    r#"mutation {{ createPea(input: {{ title: "{}" }}) {{ id }} }}"#,
+                    title
+                )
+                .as_str(),
+            )
+            .await;
+        ids.push(
+            res.data.into_json().unwrap()["createPea"]["id"]
+                .as_str()
+                .unwrap()
+                .to_string(),
+        );
+    }
+    let res = schema
+        .execute(format!(r#"mutation {{ archivePea(id: "{}") }}"#, ids[1]).as_str())
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+
+    let titles = |data: serde_json::Value| -> Vec<(String, bool)> {
+        data["peas"]["nodes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| {
+                (
+                    p["title"].as_str().unwrap().to_string(),
+                    p["archived"].as_bool().unwrap(),
+                )
+            })
+            .collect()
+    };
+    let res = schema
+        .execute("{ peas { nodes { title archived } } }")
+        .await;
+    assert_eq!(
+        titles(res.data.into_json().unwrap()),
+        [("Kept".to_string(), false)]
+    );
+    let res = schema
+        .execute("{ peas(archived: true) { nodes { title archived } } }")
+        .await;
+    assert_eq!(
+        titles(res.data.into_json().unwrap()),
+        [("Shelved".to_string(), true)]
+    );
+    let res = schema
+        .execute("{ peas(includeArchived: true) { totalCount nodes { title archived } } }")
+        .await;
+    assert_eq!(
+        titles(res.data.into_json().unwrap()),
+        [("Kept".to_string(), false), ("Shelved".to_string(), true)]
+    );
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(
+            r#"{ peas(archived: true, asOf: "2024-01-01") { totalCount } }"#,
+        ),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "VALIDATION_FAILED");
+
+    let res = schema
+        .execute(
+            format!(
+                r#"mutation {{ unarchivePea(id: "{}") {{ title archived }} }}"#,
+                ids[1]
+            )
+            .as_str(),
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(
+        res.data.into_json().unwrap()["unarchivePea"],
+        serde_json::json!({ "title": "Shelved", "archived": false })
+    );
+    let res = schema.execute("{ peas { totalCount } }").await;
+    assert_eq!(res.data.into_json().unwrap()["peas"]["totalCount"], 2);
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(format!(
+            r#"mutation {{ unarchivePea(id: "{}") {{ id }} }}"#,
+            ids[1]
+        )),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "NOT_FOUND");
+
+    let undo = peas::undo::UndoManager::new(&temp_dir.path().join(".peas"));
+    undo.undo().unwrap();
+    let res = schema
+        .execute("{ peas(archived: true) { totalCount } }")
+        .await;
+    assert_eq!(res.data.into_json().unwrap()["peas"]["totalCount"], 1);
+}
+
+#[tokio::test]
+async fn test_delete_with_dependents_needs_confirmation() {
+    let (_temp_dir, schema) = setup_project();
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Auth", peaType: EPIC }) { id } }"#)
+        .await;
+    let epic = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let res = schema
+        .execute(
+            format!(
+                r#"mutation {{ createPea(input: {{ title: "Login", parent: "{}" }}) {{ id }} }}"#,
+                epic
+            )
+            .as_str(),
+        )
+        .await;
+    let child = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let delete = |confirm: Option<&str>| {
+        let confirm = confirm
+            .map(|token| format!(r#", confirm: "{}""#, token))
+            .unwrap_or_default();
+        async_graphql::Request::new(format!(
+            r#"mutation {{ deletePea(id: "{}", orphan: true{}) }}"#,
+            epic, confirm
+        ))
+    };
+
+    let res = peas::graphql::execute(&schema, delete(None)).await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    let extensions = &errors[0]["extensions"];
+    assert_eq!(extensions["code"], "CONFIRMATION_REQUIRED");
+    assert_eq!(extensions["children"], serde_json::json!([child]));
+    let token = extensions["confirmationToken"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // A dry run asks for the same confirmation, so it can fetch the token
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(format!(
+            r#"mutation {{ deletePea(id: "{}", orphan: true, dryRun: true) }}"#,
+            epic
+        )),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["confirmationToken"], token.as_str());
+
+    let res = peas::graphql::execute(&schema, delete(Some("not-the-token"))).await;
+    assert!(!res.errors.is_empty());
+    let res = peas::graphql::execute(&schema, delete(Some(&token))).await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(res.data.into_json().unwrap()["deletePea"], true);
+
+    // Peas nothing depends on go in one step
+    let res = schema
+        .execute(format!(r#"mutation {{ deletePea(id: "{}") }}"#, child).as_str())
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+}
+
+#[tokio::test]
+async fn test_archive_and_delete_handle_children() {
+    let (_temp_dir, schema) = setup_project();
+    let create = |input: String| {
+        let schema = &schema;
+        async move {
+            let res = schema
+                .execute(
+                    format!(
+                        r#"mutation {{ createPea(input: {{ {} }}) {{ id }} }}"#,
+                        input
+                    )
+                    .as_str(),
+                )
+                .await;
+            assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+            res.data.into_json().unwrap()["createPea"]["id"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        }
+    };
+    let root = create(r#"title: "Platform", peaType: MILESTONE"#.to_string()).await;
+    let epic = create(format!(
+        r#"title: "Auth", peaType: EPIC, parent: "{}""#,
+        root
+    ))
+    .await;
+    let login = create(format!(r#"title: "Login", parent: "{}""#, epic)).await;
+    let reset = create(format!(r#"title: "Reset", parent: "{}""#, login)).await;
+    let parent_of = |id: String| {
+        let schema = &schema;
+        async move {
+            let res = schema
+                .execute(format!(r#"{{ pea(id: "{}") {{ parent }} }}"#, id).as_str())
+                .await;
+            res.data.into_json().unwrap()["pea"]["parent"].clone()
+        }
+    };
+
+    let res = peas::graphql::execute(
+        &schema,
+        async_graphql::Request::new(format!(
+            r#"mutation {{ archivePea(id: "{}", cascade: true, orphan: true) }}"#,
+            epic
+        )),
+    )
+    .await;
+    let errors = serde_json::to_value(&res.errors).unwrap();
+    assert_eq!(errors[0]["extensions"]["code"], "VALIDATION_FAILED");
+    assert_eq!(errors[0]["extensions"]["field"], "children");
+
+    // Without a choice, children are not left pointing at a removed parent
+    for mutation in ["archivePea", "deletePea"] {
+        let res = peas::graphql::execute(
+            &schema,
+            async_graphql::Request::new(format!(r#"mutation {{ {}(id: "{}") }}"#, mutation, epic)),
+        )
+        .await;
+        let errors = serde_json::to_value(&res.errors).unwrap();
+        assert_eq!(errors[0]["extensions"]["field"], "children");
+    }
+    assert_eq!(parent_of(login.clone()).await, epic.as_str());
+
+    let res = schema
+        .execute(
+            format!(
+                r#"mutation {{ archivePea(id: "{}", reparentTo: "{}") }}"#,
+                epic, root
+            )
+            .as_str(),
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    assert_eq!(parent_of(login.clone()).await, root.as_str());
+
+    let res = schema
+        .execute(
+            format!(
+                r#"mutation {{ archivePea(id: "{}", cascade: true) }}"#,
+                root
+            )
+            .as_str(),
+        )
+        .await;
+    assert!(res.errors.is_empty(), "errors: {:?}", res.errors);
+    let res = schema
+        .execute("{ peas(archived: true) { nodes { id } } }")
+        .await;
+    let archived: Vec<String> = res.data.into_json().unwrap()["peas"]["nodes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["id"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(archived.len(), 4);
+    assert!(archived.contains(&reset));
+}
+
+#[tokio::test]
+async fn test_validation_errors_name_the_constraint() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = PeasConfig::default();
+    config.validation.max_title_length = 10;
+    config.validation.tag_pattern = Some("[a-z]+".into());
+    let data_dir = temp_dir.path().join(".peas");
+    std::fs::create_dir_all(&data_dir).unwrap();
+    config.save(&data_dir.join("config.toml")).unwrap();
+    let schema = build_schema(config, temp_dir.path().to_path_buf());
+    let extensions = |query: String| {
+        let schema = &schema;
+        async move {
+            let res =
+                peas::graphql::execute(schema, async_graphql::Request::new(query.as_str())).await;
+            let errors = serde_json::to_value(&res.errors).unwrap();
+            assert_eq!(errors[0]["extensions"]["code"], "VALIDATION_FAILED");
+            errors[0]["extensions"].clone()
+        }
+    };
+
+    let ext = extensions(
+        r#"mutation { createPea(input: { title: "Far too long a title" }, dryRun: true) { id } }"#
+            .into(),
+    )
+    .await;
+    assert_eq!(ext["field"], "title");
+    assert_eq!(ext["constraint"], "maxLength");
+    assert_eq!(ext["limit"], 10);
+    assert_eq!(ext["value"], "Far too long a title");
+
+    let ext = extensions(
+        r#"mutation { createPea(input: { title: "Tagged", tags: ["UI"] }) { id } }"#.into(),
+    )
+    .await;
+    assert_eq!(ext["field"], "tags");
+    assert_eq!(ext["constraint"], "pattern");
+    assert_eq!(ext["pattern"], "[a-z]+");
+    assert_eq!(ext["value"], "UI");
+
+    let res = schema
+        .execute(r#"mutation { createPea(input: { title: "Login" }) { id } }"#)
+        .await;
+    let id = res.data.into_json().unwrap()["createPea"]["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let ext = extensions(format!(
+        r#"mutation {{ updatePea(input: {{ id: "{}", body: "Ding\u0007" }}) {{ id }} }}"#,
+        id
+    ))
+    .await;
+    assert_eq!(ext["field"], "body");
+    assert_eq!(ext["constraint"], "forbiddenCharacter");
+    assert_eq!(ext["character"], "U+0007");
+
+    let ext = extensions(format!(
+        r#"mutation {{ updatePea(input: {{ id: "{}", parent: "peas-nope1" }}) {{ id }} }}"#,
+        id
+    ))
+    .await;
+    assert_eq!(ext["field"], "parent");
+    assert_eq!(ext["constraint"], "exists");
+    assert_eq!(ext["value"], "peas-nope1");
+
+    let ext = extensions(format!(
+        r#"mutation {{ updatePea(input: {{ id: "{}", body: "{}" }}) {{ id }} }}"#,
+        id,
+        "\\u0007".repeat(200)
+    ))
+    .await;
+    assert_eq!(ext["value"].as_str().unwrap().chars().count(), 101);
+}
@@ -1,15 +1,24 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use peas::{
-    config::PeasConfig,
-    model::{Pea, PeaType},
+    config::{KeyPreset, PeasConfig},
+    model::{Pea, PeaPriority, PeaStatus, PeaType},
+    read_markers::ReadState,
+    removal::{ChildPolicy, RemovalKind},
     storage::PeaRepository,
-    tui::app::{App, DetailPane, InputMode, ViewMode},
+    tui::{
+        TuiOptions,
+        app::{
+            App, BulkAction, BulkChange, ConflictChoice, DetailPane, InputMode, MAX_MESSAGE_LOG,
+            MessageLevel, StartView, ViewMode,
+        },
+        keymap::{Action, Keymap},
+    },
 };
 use tempfile::TempDir;
 
-/// Helper to create a test app with a temporary repository
-fn create_test_app() -> (App, TempDir) {
-    let temp_dir = TempDir::new().unwrap();
-    let config = PeasConfig {
+/// Helper to create the config used by test apps
+fn test_config() -> PeasConfig {
+    PeasConfig {
         peas: peas::config::PeasSettings {
             path: None,
             prefix: "test-".to_string(),
@@ -18,9 +27,34 @@ fn create_test_app() -> (App, TempDir) {
             default_status: "todo".to_string(),
             default_type: "task".to_string(),
             frontmatter: "toml".to_string(),
+            tag_policy: Default::default(),
+            size_scale: Default::default(),
+            naming_scheme: Default::default(),
+            lock_closed: false,
+            cascade: Default::default(),
         },
         tui: peas::config::TuiSettings::default(),
-    };
+        log: Default::default(),
+        server: Default::default(),
+        lint: Default::default(),
+        open: Default::default(),
+        undo: Default::default(),
+        bulk: Default::default(),
+        display: Default::default(),
+        semantic: Default::default(),
+        validation: Default::default(),
+        tags: Default::default(),
+        components: Default::default(),
+        autotag: Default::default(),
+        rules: Default::default(),
+        actor: None,
+    }
+}
+
+/// Helper to create a test app with a temporary repository
+fn create_test_app() -> (App, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let config = test_config();
 
     let data_path = config.data_path(temp_dir.path());
     std::fs::create_dir_all(&data_path).unwrap();
@@ -32,7 +66,7 @@ fn create_test_app() -> (App, TempDir) {
 /// Helper to create and save a test pea
 fn create_test_pea(repo: &PeaRepository, id: &str, title: &str, pea_type: PeaType) -> Pea {
     let mut pea = Pea::new(id.to_string(), title.to_string(), pea_type);
-    pea.body = format!("Test body for {}", title);
+    pea.body = format!("Test body for {}", title).into();
     repo.create(&pea).unwrap();
     pea
 }
@@ -93,6 +127,35 @@ fn test_modal_open_close_delete() {
     assert_eq!(app.input_mode, InputMode::Normal);
 }
 
+#[test]
+fn test_archive_with_children_asks_what_to_do_with_them() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-epic1", "Epic", PeaType::Epic);
+    let mut child = Pea::new("test-kid01".into(), "Child".into(), PeaType::Task)
+        .with_parent(Some("test-epic1".into()));
+    app.repo.create(&child).unwrap();
+    app.refresh().unwrap();
+
+    app.selected_index = app
+        .tree_nodes
+        .iter()
+        .position(|n| n.pea.id == "test-epic1")
+        .unwrap();
+    app.archive_selected().unwrap();
+    assert_eq!(app.input_mode, InputMode::DeleteConfirm);
+    assert_eq!(app.removal_kind, RemovalKind::Archive);
+
+    // Children are never left pointing at an archived ticket
+    assert!(app.remove_selected(ChildPolicy::Refuse).is_err());
+    assert!(app.repo.get("test-epic1").is_ok());
+
+    app.remove_selected(ChildPolicy::Orphan).unwrap();
+    child = app.repo.get("test-kid01").unwrap();
+    assert_eq!(child.parent, None);
+    assert!(app.repo.get("test-epic1").is_err());
+    assert!(app.repo.get_archived("test-epic1").is_ok());
+}
+
 #[test]
 fn test_modal_selection_reset_on_open() {
     let (mut app, _temp_dir) = create_test_app();
@@ -237,6 +300,223 @@ fn test_detail_pane_switching() {
     assert_eq!(app.detail_pane, DetailPane::Body);
 }
 
+#[test]
+fn test_opening_ticket_marks_it_read() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    let pea = app.selected_pea().unwrap().clone();
+    assert_eq!(app.read_state(&pea), ReadState::New);
+
+    app.mark_selected_read();
+    assert_eq!(app.read_state(&pea), ReadState::Read);
+
+    // Stays read after reloading markers from disk
+    app.refresh().unwrap();
+    let pea = app.selected_pea().unwrap().clone();
+    assert_eq!(app.read_state(&pea), ReadState::Read);
+}
+
+#[test]
+fn test_relations_include_backlinks() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    let mut notes = Pea::new(
+        "test-abc02".to_string(),
+        "Notes".to_string(),
+        PeaType::Research,
+    );
+    notes.body = "Findings feed into test-abc01.".into();
+    app.repo.create(&notes).unwrap();
+    app.refresh().unwrap();
+
+    let idx = app
+        .tree_nodes
+        .iter()
+        .position(|n| n.pea.id == "test-abc01")
+        .unwrap();
+    app.selected_index = idx;
+    app.build_relations();
+    assert!(
+        app.relations_items
+            .iter()
+            .any(|(rel, id, _, _)| rel == "ReferencedBy" && id == "test-abc02")
+    );
+}
+
+#[test]
+fn test_relations_list_related_peas_once() {
+    let (mut app, _temp_dir) = create_test_app();
+    for (id, title, tags) in [
+        ("test-abc01", "Login form", vec!["auth"]),
+        ("test-abc02", "Login error messages", vec!["auth"]),
+        ("test-abc03", "Dark mode", vec![]),
+    ] {
+        let pea = Pea::new(id.to_string(), title.to_string(), PeaType::Task)
+            .with_tags(tags.into_iter().map(String::from).collect());
+        app.repo.create(&pea).unwrap();
+    }
+    let mut notes = Pea::new(
+        "test-abc04".to_string(),
+        "Login notes".to_string(),
+        PeaType::Research,
+    );
+    notes.body = "Findings feed into test-abc01.".into();
+    app.repo.create(&notes).unwrap();
+    app.refresh().unwrap();
+
+    app.selected_index = app
+        .tree_nodes
+        .iter()
+        .position(|n| n.pea.id == "test-abc01")
+        .unwrap();
+    app.build_relations();
+    let rels: Vec<(&str, &str)> = app
+        .relations_items
+        .iter()
+        .map(|(rel, id, _, _)| (rel.as_str(), id.as_str()))
+        .collect();
+    assert_eq!(
+        rels,
+        [("ReferencedBy", "test-abc04"), ("Related", "test-abc02")]
+    );
+}
+
+#[test]
+fn test_detail_relation_jump_and_back() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-mil01", "Release", PeaType::Milestone);
+    let mut epic = Pea::new("test-epc01".to_string(), "Epic".to_string(), PeaType::Epic);
+    epic.parent = Some("test-mil01".to_string());
+    app.repo.create(&epic).unwrap();
+    let mut task = Pea::new("test-tsk01".to_string(), "Task".to_string(), PeaType::Task);
+    task.parent = Some("test-epc01".to_string());
+    app.repo.create(&task).unwrap();
+    app.refresh().unwrap();
+
+    let idx = app
+        .tree_nodes
+        .iter()
+        .position(|n| n.pea.id == "test-tsk01")
+        .unwrap();
+    app.selected_index = idx;
+    app.build_relations();
+    app.input_mode = InputMode::DetailView;
+
+    let crumbs: Vec<_> = app
+        .detail_breadcrumb()
+        .iter()
+        .map(|p| p.id.clone())
+        .collect();
+    assert_eq!(crumbs, vec!["test-mil01", "test-epc01", "test-tsk01"]);
+
+    // Jump to the parent epic, then to its parent milestone
+    assert!(app.jump_to_relation());
+    assert_eq!(app.selected_pea().unwrap().id, "test-epc01");
+    app.relations_selection = 0;
+    assert!(app.jump_to_relation());
+    assert_eq!(app.selected_pea().unwrap().id, "test-mil01");
+    assert_eq!(app.detail_history.len(), 2);
+
+    // Walk back through the history
+    assert!(app.detail_back());
+    assert_eq!(app.selected_pea().unwrap().id, "test-epc01");
+    assert!(app.detail_back());
+    assert_eq!(app.selected_pea().unwrap().id, "test-tsk01");
+    assert!(!app.detail_back());
+
+    app.jump_to_relation();
+    app.close_detail_view();
+    assert!(app.detail_history.is_empty());
+    assert_eq!(app.input_mode, InputMode::Normal);
+}
+
+#[test]
+fn test_open_ticket_deep_link() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Login form", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Logout button", PeaType::Task);
+    create_test_pea(&app.repo, "test-old01", "Old work", PeaType::Task);
+    app.repo.archive("test-old01").unwrap();
+    app.search_query = "Login".to_string();
+    app.refresh().unwrap();
+
+    // Hidden by the search filter, which is cleared
+    assert!(app.open_ticket("test-abc02").unwrap());
+    assert_eq!(app.selected_pea().unwrap().id, "test-abc02");
+    assert_eq!(app.input_mode, InputMode::DetailView);
+    assert!(app.search_query.is_empty());
+
+    // Archived, so archived tickets are shown
+    assert!(app.open_ticket("test-old01").unwrap());
+    assert!(app.show_archived);
+    assert_eq!(app.selected_pea().unwrap().id, "test-old01");
+
+    assert!(!app.open_ticket("test-nope1").unwrap());
+}
+
+#[test]
+fn test_bodies_load_for_the_selection_and_body_searches() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Login form", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Logout button", PeaType::Task);
+    app.refresh().unwrap();
+
+    // Only the selected ticket's body is read
+    let selected = app.selected_pea().unwrap().id.clone();
+    assert_eq!(
+        *app.selected_pea().unwrap().body,
+        format!("Test body for {}", app.selected_pea().unwrap().title)
+    );
+    let other = app.all_peas.iter().find(|p| p.id != selected).unwrap();
+    assert!(!other.body.is_loaded());
+
+    // Matching on bodies reads them all
+    app.search_query = "body:button".to_string();
+    app.apply_filter();
+    assert_eq!(app.filtered_peas.len(), 1);
+    assert_eq!(app.filtered_peas[0].id, "test-abc02");
+    assert!(app.all_peas.iter().all(|p| p.body.is_loaded()));
+}
+
+#[test]
+fn test_start_at_filter_and_view() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Login form", PeaType::Task);
+    let mut started = Pea::new("test-abc02".into(), "Logout".into(), PeaType::Task);
+    started.status = PeaStatus::InProgress;
+    app.repo.create(&started).unwrap();
+    app.refresh().unwrap();
+
+    app.start_at(&TuiOptions {
+        filter: Some("status:in-progress".to_string()),
+        view: Some(StartView::Split),
+        ..Default::default()
+    })
+    .unwrap();
+    assert!(app.split_view);
+    assert_eq!(app.tree_nodes.len(), 1);
+    assert_eq!(app.selected_pea().unwrap().id, "test-abc02");
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    app.start_at(&TuiOptions {
+        view: Some(StartView::Memory),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(app.view_mode, ViewMode::Memory);
+
+    // Selecting a ticket goes back to the tickets, into its detail view
+    app.start_at(&TuiOptions {
+        select: Some("test-abc02".to_string()),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(app.view_mode, ViewMode::Tickets);
+    assert_eq!(app.input_mode, InputMode::DetailView);
+}
+
 #[test]
 fn test_detail_scroll_limits() {
     let (mut app, _temp_dir) = create_test_app();
@@ -329,6 +609,260 @@ fn test_filter_query_persistence() {
     assert_eq!(app.search_query, "test query");
 }
 
+#[test]
+fn test_filter_scoped_terms() {
+    let (mut app, _temp_dir) = create_test_app();
+    let mut ui_bug = Pea::new(
+        "test-abc01".to_string(),
+        "Button misaligned".to_string(),
+        PeaType::Bug,
+    );
+    ui_bug.tags = vec!["ui".to_string()];
+    app.repo.create(&ui_bug).unwrap();
+    let mut ui_task = Pea::new(
+        "test-abc02".to_string(),
+        "Polish theme".to_string(),
+        PeaType::Task,
+    );
+    ui_task.tags = vec!["ui".to_string()];
+    ui_task.status = PeaStatus::Completed;
+    app.repo.create(&ui_task).unwrap();
+    create_test_pea(&app.repo, "test-abc03", "Parser bug", PeaType::Bug);
+    app.refresh().unwrap();
+
+    app.search_query = "tag:ui status:todo".to_string();
+    app.apply_filter();
+    assert_eq!(app.filtered_peas.len(), 1);
+    assert_eq!(app.filtered_peas[0].id, "test-abc01");
+
+    app.search_query = "type:bug".to_string();
+    app.apply_filter();
+    assert_eq!(app.filtered_peas.len(), 2);
+}
+
+#[test]
+fn test_filter_regex_mode_and_highlight() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Login crashes", PeaType::Bug);
+    create_test_pea(&app.repo, "test-abc02", "Logout flow", PeaType::Task);
+    app.refresh().unwrap();
+
+    // Without regex mode the pattern is a literal substring
+    app.search_query = "Log(in|out)".to_string();
+    app.apply_filter();
+    assert!(app.filtered_peas.is_empty());
+
+    app.toggle_search_regex();
+    assert_eq!(app.filtered_peas.len(), 2);
+
+    let pattern = app.search_highlight().unwrap();
+    assert!(pattern.is_match("Test body for Login crashes"));
+
+    // A half-typed regex falls back to a substring search instead of failing
+    app.search_query = "crashes(".to_string();
+    app.apply_filter();
+    assert!(app.filtered_peas.is_empty());
+
+    // Scoped terms without free text have nothing to highlight
+    app.search_query = "status:todo".to_string();
+    assert!(app.search_highlight().is_none());
+}
+
+#[test]
+fn test_filter_fuzzy_mode_tolerates_typos() {
+    let (mut app, temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Login crashes", PeaType::Bug);
+    create_test_pea(&app.repo, "test-ep001", "Dashboard", PeaType::Epic);
+    app.refresh().unwrap();
+
+    app.search_query = "crashs".to_string();
+    app.apply_filter();
+    assert!(app.filtered_peas.is_empty());
+
+    app.toggle_search_regex();
+    app.toggle_search_fuzzy();
+    assert!(!app.search_regex);
+    assert_eq!(app.filtered_peas.len(), 1);
+    assert_eq!(app.filtered_peas[0].id, "test-abc01");
+
+    app.save_filter().unwrap();
+    let restored = App::new(&test_config(), temp_dir.path()).unwrap();
+    assert!(restored.search_fuzzy);
+    assert_eq!(restored.filtered_peas.len(), 1);
+
+    // The parent search of the create form is fuzzy as well
+    app.open_create_modal();
+    app.create_parent_query = "dashbord".to_string();
+    app.update_create_parent_query();
+    assert_eq!(app.create_parent.as_deref(), Some("test-ep001"));
+}
+
+#[test]
+fn test_filter_saved_for_next_session() {
+    let (mut app, temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Login crashes", PeaType::Bug);
+    create_test_pea(&app.repo, "test-abc02", "Polish theme", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.search_query = "type:bug".to_string();
+    app.apply_filter();
+    app.save_filter().unwrap();
+    assert!(temp_dir.path().join(".peas/local/.gitignore").exists());
+
+    let restored = App::new(&test_config(), temp_dir.path()).unwrap();
+    assert_eq!(restored.search_query, "type:bug");
+    assert_eq!(restored.filtered_peas.len(), 1);
+
+    // Clearing the query forgets the saved filter
+    app.search_query.clear();
+    app.save_filter().unwrap();
+    let restored = App::new(&test_config(), temp_dir.path()).unwrap();
+    assert_eq!(restored.search_query, "");
+}
+
+#[test]
+fn test_custom_keymap_from_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = PeasConfig::default();
+    config.tui.keys = toml::from_str("preset = \"vim\"\narchive = \"x\"").unwrap();
+    std::fs::create_dir_all(config.data_path(temp_dir.path())).unwrap();
+    let app = App::new(&config, temp_dir.path()).unwrap();
+
+    let x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    assert_eq!(app.keymap.action_for(&x), Some(Action::Archive));
+    let a = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+    assert_eq!(app.keymap.action_for(&a), None);
+    let ctrl_f = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL);
+    assert_eq!(app.keymap.action_for(&ctrl_f), Some(Action::NextPage));
+}
+
+fn press(app: &mut App, c: char) -> Option<(Action, Option<usize>)> {
+    app.resolve_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+}
+
+#[test]
+fn test_count_prefix() {
+    let (mut app, _temp_dir) = create_test_app();
+    for i in 0..20 {
+        create_test_pea(
+            &app.repo,
+            &format!("test-abc{:02}", i),
+            "Task",
+            PeaType::Task,
+        );
+    }
+    app.refresh().unwrap();
+
+    assert_eq!(press(&mut app, '1'), None);
+    assert_eq!(press(&mut app, '2'), None);
+    assert_eq!(app.pending_count, Some(12));
+    assert_eq!(press(&mut app, 'j'), Some((Action::MoveDown, Some(12))));
+    assert_eq!(app.pending_count, None);
+
+    // A leading zero is not a count
+    assert_eq!(press(&mut app, '0'), None);
+    assert_eq!(app.pending_count, None);
+
+    // `5G` jumps to the fifth item
+    app.go_to(4);
+    assert_eq!(app.selected_index, 4);
+    app.go_to(100);
+    assert_eq!(app.selected_index, 19);
+}
+
+#[test]
+fn test_vim_pending_sequences() {
+    let (mut app, _temp_dir) = create_test_app();
+    app.keymap = Keymap::from_preset(KeyPreset::Vim);
+
+    assert_eq!(press(&mut app, 'g'), None);
+    assert_eq!(app.pending_keys.len(), 1);
+    assert_eq!(press(&mut app, 'g'), Some((Action::First, None)));
+    assert!(app.pending_keys.is_empty());
+
+    assert_eq!(press(&mut app, '3'), None);
+    assert_eq!(press(&mut app, 'd'), None);
+    assert_eq!(press(&mut app, 'd'), Some((Action::Delete, Some(3))));
+
+    // An abandoned sequence falls back to the new key on its own
+    assert_eq!(press(&mut app, 'd'), None);
+    assert_eq!(press(&mut app, 'j'), Some((Action::MoveDown, None)));
+
+    assert_eq!(press(&mut app, '4'), None);
+    assert!(app.clear_pending_keys());
+    assert!(!app.clear_pending_keys());
+}
+
+#[test]
+fn test_split_view_key() {
+    let (mut app, _temp_dir) = create_test_app();
+    assert!(!app.split_view);
+    assert_eq!(press(&mut app, 'v'), Some((Action::SplitView, None)));
+    assert!(
+        app.keymap
+            .footer_hints(&[(&[Action::SplitView], "split")])
+            .contains("v:split")
+    );
+}
+
+#[test]
+fn test_invalid_keymap_rejected() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = PeasConfig::default();
+    config.tui.keys = toml::from_str("archive = \"hyper+x\"").unwrap();
+    std::fs::create_dir_all(config.data_path(temp_dir.path())).unwrap();
+    assert!(App::new(&config, temp_dir.path()).is_err());
+}
+
+#[test]
+fn test_tag_colors_from_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = PeasConfig {
+        tags: toml::from_str("[urgent]\ncolor = \"red\"\n[ui]\ndescription = \"Frontend work\"\n")
+            .unwrap(),
+        ..Default::default()
+    };
+    std::fs::create_dir_all(config.data_path(temp_dir.path())).unwrap();
+    let app = App::new(&config, temp_dir.path()).unwrap();
+    assert_eq!(
+        app.tag_colors.get("urgent"),
+        Some(&ratatui::style::Color::Red)
+    );
+    assert!(!app.tag_colors.contains_key("ui"));
+}
+
+#[test]
+fn test_tags_modal_completion() {
+    let (mut app, _temp_dir) = create_test_app();
+    let pea = Pea::new(
+        "test-abc01".to_string(),
+        "Tagged".to_string(),
+        PeaType::Task,
+    )
+    .with_tags(vec!["frontend".to_string(), "frontier".to_string()]);
+    app.repo.create(&pea).unwrap();
+    app.configured_tags.insert("backend".to_string());
+    app.refresh().unwrap();
+
+    app.tags_input = "back".to_string();
+    app.complete_tags_modal();
+    assert_eq!(app.tags_input, "backend, ");
+
+    // Several matches extend to the common prefix; entered tags are skipped
+    app.tags_input.push_str("FR");
+    assert_eq!(app.tags_modal_completions(), vec!["frontend", "frontier"]);
+    app.complete_tags_modal();
+    assert_eq!(app.tags_input, "backend, front");
+    app.tags_input = "frontend, front".to_string();
+    app.complete_tags_modal();
+    assert_eq!(app.tags_input, "frontend, frontier, ");
+
+    // Under the strict policy only configured tags are offered
+    app.tag_policy = peas::config::TagPolicy::Strict;
+    app.tags_input = "fr".to_string();
+    assert!(app.tags_modal_completions().is_empty());
+}
+
 // ============================================================================
 // Multi-Selection Tests
 // ============================================================================
@@ -423,6 +957,473 @@ fn test_create_modal_type_selection() {
     assert_eq!(app.create_type, PeaType::Chore);
 }
 
+#[test]
+fn test_create_modal_full_fields() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-epic1", "Auth Epic", PeaType::Epic);
+    app.refresh().unwrap();
+
+    app.open_create_modal();
+    app.create_title = "Login form".to_string();
+    app.create_type = PeaType::Feature;
+    app.create_priority = PeaPriority::High;
+    app.create_tags = "ui, auth,".to_string();
+    app.create_parent = Some("test-epic1".to_string());
+    app.create_body = "Build the login form\n\n- email\n- password".to_string();
+
+    let id = app
+        .create_from_modal()
+        .unwrap()
+        .expect("pea should be created");
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    let pea = app.repo.get(&id).unwrap();
+    assert_eq!(pea.title, "Login form");
+    assert_eq!(pea.pea_type, PeaType::Feature);
+    assert_eq!(pea.priority, PeaPriority::High);
+    assert_eq!(pea.tags, vec!["ui", "auth"]);
+    assert_eq!(pea.parent.as_deref(), Some("test-epic1"));
+    assert_eq!(pea.body, "Build the login form\n\n- email\n- password");
+}
+
+#[test]
+fn test_create_modal_empty_title_rejected() {
+    let (mut app, _temp_dir) = create_test_app();
+
+    app.open_create_modal();
+    assert!(app.create_from_modal().unwrap().is_none());
+    assert_eq!(app.input_mode, InputMode::CreateModal);
+    assert!(app.all_peas.is_empty());
+}
+
+#[test]
+fn test_create_modal_invalid_input_keeps_modal_open() {
+    let (mut app, _temp_dir) = create_test_app();
+
+    app.open_create_modal();
+    app.create_title = "Pay \u{202E}gnp.exe".to_string();
+    assert!(app.create_from_modal().unwrap().is_none());
+    assert_eq!(app.input_mode, InputMode::CreateModal);
+    assert!(app.message.as_ref().unwrap().text.contains("U+202E"));
+
+    app.create_title = "Login form".to_string();
+    app.create_tags = "x".repeat(51);
+    assert!(app.create_from_modal().unwrap().is_none());
+    assert_eq!(app.input_mode, InputMode::CreateModal);
+    assert!(app.all_peas.is_empty());
+}
+
+#[test]
+fn test_create_modal_parent_search() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-ms001", "Q1 Release", PeaType::Milestone);
+    create_test_pea(&app.repo, "test-ep001", "Dashboard", PeaType::Epic);
+    create_test_pea(&app.repo, "test-tk001", "Dashboard task", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.open_create_modal();
+    app.create_parent = None;
+
+    // Only container types are offered, narrowed by the search text
+    app.create_parent_query = "dash".to_string();
+    app.update_create_parent_query();
+    assert_eq!(app.create_parent.as_deref(), Some("test-ep001"));
+    assert_eq!(app.create_parent_matches().len(), 1);
+
+    // Cycling past the last match clears the parent
+    app.cycle_create_parent(true);
+    assert_eq!(app.create_parent, None);
+
+    app.create_parent_query.clear();
+    app.cycle_create_parent(true);
+    assert!(app.create_parent.is_some());
+}
+
+#[test]
+fn test_create_modal_and_edit_opens_body_editor() {
+    let (mut app, _temp_dir) = create_test_app();
+
+    app.open_create_modal();
+    app.create_title = "Write docs".to_string();
+    app.create_from_modal_and_edit().unwrap();
+
+    assert_eq!(app.input_mode, InputMode::EditBody);
+    assert!(app.body_textarea.is_some());
+    assert_eq!(app.selected_pea().unwrap().title, "Write docs");
+}
+
+#[test]
+fn test_body_edit_review_diff_then_save() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.start_body_edit();
+    app.body_textarea
+        .as_mut()
+        .unwrap()
+        .set_text("# Heading\n\nNew body");
+    assert!(app.review_body_edit());
+    assert_eq!(app.input_mode, InputMode::BodyDiff);
+    assert!(!app.body_diff.is_empty());
+
+    // Going back keeps the edited text
+    app.resume_body_edit();
+    assert_eq!(app.input_mode, InputMode::EditBody);
+    assert!(app.body_diff.is_empty());
+    assert!(app.review_body_edit());
+
+    app.save_body_edit().unwrap();
+    assert_eq!(app.input_mode, InputMode::DetailView);
+    let pea = app.repo.get("test-abc01").unwrap();
+    assert_eq!(pea.body, "# Heading\n\nNew body");
+}
+
+#[test]
+fn test_body_edit_conflict_merge() {
+    let (mut app, _temp_dir) = create_test_app();
+    let mut pea = Pea::new(
+        "test-abc01".to_string(),
+        "Task 1".to_string(),
+        PeaType::Task,
+    );
+    pea.body = "intro\nmiddle\noutro".into();
+    app.repo.create(&pea).unwrap();
+    app.refresh().unwrap();
+
+    app.start_body_edit();
+    app.body_textarea
+        .as_mut()
+        .unwrap()
+        .set_text("intro (edited)\nmiddle\noutro");
+
+    // Another process changes a different part of the body
+    let mut theirs = app.repo.get("test-abc01").unwrap();
+    theirs.body = "intro\nmiddle\noutro\nappendix".into();
+    app.repo.update(&mut theirs).unwrap();
+    app.refresh().unwrap();
+
+    assert!(app.check_edit_conflict());
+    assert_eq!(app.input_mode, InputMode::EditConflict);
+    let conflict = app.edit_conflict.as_ref().unwrap();
+    assert_eq!(
+        conflict.choices(),
+        vec![
+            ConflictChoice::Reload,
+            ConflictChoice::Overwrite,
+            ConflictChoice::Merge
+        ]
+    );
+    assert_eq!(conflict.changed_fields(), vec!["body"]);
+
+    app.resolve_edit_conflict(ConflictChoice::Merge);
+    assert_eq!(app.input_mode, InputMode::EditBody);
+    assert_eq!(
+        app.body_textarea.as_ref().unwrap().text(),
+        "intro (edited)\nmiddle\noutro\nappendix"
+    );
+
+    // The merged body saves cleanly on top of the newer file
+    assert!(app.review_body_edit());
+    app.save_body_edit().unwrap();
+    assert_eq!(
+        app.repo.get("test-abc01").unwrap().body,
+        "intro (edited)\nmiddle\noutro\nappendix"
+    );
+}
+
+#[test]
+fn test_body_edit_conflict_reload_and_overwrite() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.start_body_edit();
+    app.body_textarea.as_mut().unwrap().set_text("mine");
+    let mut theirs = app.repo.get("test-abc01").unwrap();
+    theirs.body = "theirs".into();
+    app.repo.update(&mut theirs).unwrap();
+    app.refresh().unwrap();
+
+    assert!(app.check_edit_conflict());
+    app.resolve_edit_conflict(ConflictChoice::Reload);
+    assert_eq!(app.body_textarea.as_ref().unwrap().text(), "theirs");
+    // Reloading makes the disk version the new base; no repeated prompt
+    assert!(!app.check_edit_conflict());
+
+    app.body_textarea.as_mut().unwrap().set_text("mine");
+    let mut theirs = app.repo.get("test-abc01").unwrap();
+    theirs.status = PeaStatus::InProgress;
+    app.repo.update(&mut theirs).unwrap();
+    app.refresh().unwrap();
+
+    assert!(app.check_edit_conflict());
+    app.resolve_edit_conflict(ConflictChoice::Overwrite);
+    app.save_body_edit().unwrap();
+    let saved = app.repo.get("test-abc01").unwrap();
+    assert_eq!(saved.body, "mine");
+    // Frontmatter changes made on disk survive the overwrite
+    assert_eq!(saved.status, PeaStatus::InProgress);
+}
+
+#[test]
+fn test_modal_conflict_reload_closes_modal() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.open_status_modal();
+    app.sync_edit_base();
+    assert!(app.edit_base.is_some());
+
+    let mut theirs = app.repo.get("test-abc01").unwrap();
+    theirs.status = PeaStatus::Completed;
+    app.repo.update(&mut theirs).unwrap();
+    app.refresh().unwrap();
+
+    assert!(app.check_edit_conflict());
+    assert_eq!(
+        app.edit_conflict.as_ref().unwrap().choices(),
+        vec![ConflictChoice::Reload, ConflictChoice::Merge]
+    );
+    app.resolve_edit_conflict(ConflictChoice::Reload);
+    assert_eq!(app.input_mode, InputMode::Normal);
+
+    app.sync_edit_base();
+    assert!(app.edit_base.is_none());
+}
+
+#[test]
+fn test_body_edit_review_without_changes_closes_editor() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.start_body_edit();
+    assert!(!app.review_body_edit());
+    assert!(app.body_textarea.is_none());
+    assert!(app.body_diff.is_empty());
+}
+
+#[test]
+fn test_body_edit_draft_kept_on_cancel() {
+    let (mut app, temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.start_body_edit();
+    app.body_textarea
+        .as_mut()
+        .unwrap()
+        .set_text("Half-written idea");
+    app.stash_body_edit().unwrap();
+    assert!(app.body_textarea.is_none());
+    assert_eq!(
+        app.repo.get("test-abc01").unwrap().body,
+        "Test body for Task 1"
+    );
+    let draft = temp_dir.path().join(".peas/local/drafts/test-abc01.md");
+    assert!(draft.exists());
+
+    // Reopening picks the draft up again, saving clears it
+    app.start_body_edit();
+    assert_eq!(
+        app.body_textarea.as_ref().unwrap().value(),
+        "Half-written idea"
+    );
+    assert!(app.review_body_edit());
+    app.save_body_edit().unwrap();
+    assert!(!draft.exists());
+    assert_eq!(
+        app.repo.get("test-abc01").unwrap().body,
+        "Half-written idea"
+    );
+}
+
+// ============================================================================
+// Archive Tests
+// ============================================================================
+
+#[test]
+fn test_archive_selected_and_undo() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Task 2", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.archive_selected().unwrap();
+    assert_eq!(app.all_peas.len(), 1);
+    assert_eq!(app.repo.list_archived().unwrap().len(), 1);
+
+    app.undo().unwrap();
+    assert_eq!(app.all_peas.len(), 2);
+    assert!(app.repo.list_archived().unwrap().is_empty());
+}
+
+#[test]
+fn test_archive_multi_selected() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Task 2", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc03", "Task 3", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.multi_selected.insert("test-abc01".to_string());
+    app.multi_selected.insert("test-abc03".to_string());
+    app.archive_selected().unwrap();
+
+    assert_eq!(app.all_peas.len(), 1);
+    assert_eq!(app.all_peas[0].id, "test-abc02");
+    assert!(app.multi_selected.is_empty());
+
+    // One undo brings both back
+    app.undo().unwrap();
+    assert_eq!(app.all_peas.len(), 3);
+}
+
+#[test]
+fn test_status_modal_multi_select_single_undo_entry() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Task 2", PeaType::Task);
+    app.refresh().unwrap();
+    app.multi_selected.insert("test-abc01".to_string());
+    app.multi_selected.insert("test-abc02".to_string());
+
+    app.open_status_modal();
+    app.modal_selection = App::status_options()
+        .iter()
+        .position(|s| *s == PeaStatus::InProgress)
+        .unwrap();
+    app.apply_modal_status().unwrap();
+    assert!(
+        app.all_peas
+            .iter()
+            .all(|p| p.status == PeaStatus::InProgress)
+    );
+
+    app.undo().unwrap();
+    assert!(app.all_peas.iter().all(|p| p.status == PeaStatus::Todo));
+}
+
+#[test]
+fn test_toggle_show_archived() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Task 2", PeaType::Task);
+    app.repo.archive("test-abc01").unwrap();
+    app.refresh().unwrap();
+    assert_eq!(app.tree_nodes.len(), 1);
+
+    app.toggle_show_archived().unwrap();
+    assert!(app.show_archived);
+    assert_eq!(app.tree_nodes.len(), 2);
+    assert!(app.is_archived("test-abc01"));
+    assert!(!app.is_archived("test-abc02"));
+
+    app.toggle_show_archived().unwrap();
+    assert_eq!(app.tree_nodes.len(), 1);
+    assert!(!app.is_archived("test-abc01"));
+}
+
+// ============================================================================
+// Bulk Operations Tests
+// ============================================================================
+
+fn select_bulk_action(app: &mut App, action: BulkAction) {
+    app.modal_selection = BulkAction::all().iter().position(|a| *a == action).unwrap();
+    app.select_bulk_action();
+}
+
+#[test]
+fn test_bulk_status_single_undo_entry() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Task 2", PeaType::Task);
+    app.refresh().unwrap();
+    app.multi_selected.insert("test-abc01".to_string());
+    app.multi_selected.insert("test-abc02".to_string());
+
+    app.open_bulk_modal();
+    assert_eq!(app.input_mode, InputMode::BulkModal);
+    select_bulk_action(&mut app, BulkAction::Status);
+    app.modal_selection = App::status_options()
+        .iter()
+        .position(|s| *s == PeaStatus::Completed)
+        .unwrap();
+    app.choose_bulk_value();
+    assert_eq!(
+        app.bulk_pending,
+        Some(BulkChange::Status(PeaStatus::Completed))
+    );
+
+    let undo_before = app.undo_count();
+    app.apply_bulk_pending().unwrap();
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert!(app.multi_selected.is_empty());
+    assert!(
+        app.all_peas
+            .iter()
+            .all(|p| p.status == PeaStatus::Completed)
+    );
+    assert_eq!(app.undo_count(), undo_before + 1);
+
+    // One undo reverts the whole batch
+    app.undo().unwrap();
+    assert!(app.all_peas.iter().all(|p| p.status == PeaStatus::Todo));
+}
+
+#[test]
+fn test_bulk_add_tags_and_delete() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc02", "Task 2", PeaType::Task);
+    create_test_pea(&app.repo, "test-abc03", "Task 3", PeaType::Task);
+    app.refresh().unwrap();
+    app.multi_selected.insert("test-abc01".to_string());
+    app.multi_selected.insert("test-abc02".to_string());
+
+    app.open_bulk_modal();
+    select_bulk_action(&mut app, BulkAction::AddTags);
+    app.bulk_input = "backend, urgent".to_string();
+    app.choose_bulk_value();
+    app.apply_bulk_pending().unwrap();
+    let tagged = app.all_peas.iter().filter(|p| p.tags.len() == 2).count();
+    assert_eq!(tagged, 2);
+
+    app.multi_selected.insert("test-abc01".to_string());
+    app.multi_selected.insert("test-abc03".to_string());
+    app.open_bulk_modal();
+    select_bulk_action(&mut app, BulkAction::Delete);
+    // Delete goes straight to confirmation
+    assert_eq!(app.bulk_pending, Some(BulkChange::Delete));
+    app.apply_bulk_pending().unwrap();
+    assert_eq!(app.all_peas.len(), 1);
+
+    app.undo().unwrap();
+    assert_eq!(app.all_peas.len(), 3);
+}
+
+#[test]
+fn test_bulk_back_navigation() {
+    let (mut app, _temp_dir) = create_test_app();
+    create_test_pea(&app.repo, "test-abc01", "Task 1", PeaType::Task);
+    app.refresh().unwrap();
+
+    app.open_bulk_modal();
+    select_bulk_action(&mut app, BulkAction::Archive);
+    assert!(app.bulk_pending.is_some());
+
+    // Archive has no value step, so back returns to the palette
+    app.bulk_back();
+    assert!(app.bulk_pending.is_none());
+    assert!(app.bulk_action.is_none());
+
+    app.bulk_back();
+    assert_eq!(app.input_mode, InputMode::Normal);
+    assert_eq!(app.all_peas.len(), 1);
+}
+
 // ============================================================================
 // Message Display Tests
 // ============================================================================
@@ -431,13 +1432,62 @@ fn test_create_modal_type_selection() {
 fn test_message_display() {
     let (mut app, _temp_dir) = create_test_app();
 
-    assert_eq!(app.message, None);
+    assert!(app.message.is_none());
+
+    app.info("Test message");
+    assert_eq!(app.message.as_ref().unwrap().text, "Test message");
+    assert_eq!(app.message.as_ref().unwrap().level, MessageLevel::Info);
 
-    app.message = Some("Test message".to_string());
-    assert_eq!(app.message, Some("Test message".to_string()));
+    // Informational messages are cleared on key press
+    app.clear_transient_message();
+    assert!(app.message.is_none());
+}
+
+#[test]
+fn test_warnings_and_errors_persist() {
+    let (mut app, _temp_dir) = create_test_app();
+
+    app.warn("Careful");
+    app.clear_transient_message();
+    assert_eq!(app.message.as_ref().unwrap().level, MessageLevel::Warning);
+
+    app.report_error("Save failed", Err::<(), _>("disk full"));
+    app.clear_transient_message();
+    let msg = app.message.as_ref().unwrap();
+    assert_eq!(msg.level, MessageLevel::Error);
+    assert_eq!(msg.text, "Save failed: disk full");
+
+    // Successful results don't produce messages
+    app.report_error("Save failed", Ok::<(), String>(()));
+    assert_eq!(app.message_log.len(), 2);
+}
+
+#[test]
+fn test_peas_errors_show_hints() {
+    let (mut app, _temp_dir) = create_test_app();
+
+    app.report_error(
+        "Update failed",
+        Err::<(), _>(peas::error::PeasError::NotFound("test-x".to_string())),
+    );
+    assert_eq!(
+        app.message.as_ref().unwrap().text,
+        "Update failed: Pea not found: test-x (it may have been deleted or archived; press r to refresh)"
+    );
+}
+
+#[test]
+fn test_message_log_is_bounded() {
+    let (mut app, _temp_dir) = create_test_app();
+
+    for i in 0..(MAX_MESSAGE_LOG + 5) {
+        app.info(format!("msg {}", i));
+    }
+    assert_eq!(app.message_log.len(), MAX_MESSAGE_LOG);
+    assert_eq!(app.message_log.front().unwrap().text, "msg 5");
 
-    app.message = None;
-    assert_eq!(app.message, None);
+    app.open_message_log();
+    assert_eq!(app.input_mode, InputMode::MessageLog);
 }
 
 // ============================================================================